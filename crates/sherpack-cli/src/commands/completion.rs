@@ -0,0 +1,47 @@
+//! Completion command - generate shell completion scripts
+//!
+//! Scripts are static, but arguments taking a repository name are completed
+//! from the repositories configured when the script is generated.
+//! Regenerate the script after `repo add`/`repo remove` to refresh them.
+
+use clap::Command;
+use clap::builder::PossibleValuesParser;
+use clap_complete::Shell;
+use sherpack_repo::RepositoryConfig;
+use std::io::Write;
+
+/// Write the completion script for `shell`
+pub fn run(shell: Shell, cmd: Command, out: &mut dyn Write) {
+    // Completion must work before any repository is configured
+    let repo_names: Vec<String> = RepositoryConfig::load()
+        .map(|config| {
+            config
+                .repositories
+                .into_iter()
+                .map(|repo| repo.name)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut cmd = with_repo_names(cmd, &repo_names);
+    let bin_name = cmd.get_name().to_string();
+    clap_complete::generate(shell, &mut cmd, bin_name, out);
+}
+
+/// Offer `names` as values for the arguments that take a repository name
+fn with_repo_names(cmd: Command, names: &[String]) -> Command {
+    if names.is_empty() {
+        return cmd;
+    }
+
+    let repo_name = |arg: clap::Arg| arg.value_parser(PossibleValuesParser::new(names));
+
+    cmd.mut_subcommand("repo", |repo| {
+        repo.mut_subcommand("update", |c| c.mut_arg("name", repo_name))
+            .mut_subcommand("remove", |c| c.mut_arg("name", repo_name))
+            .mut_subcommand("cache", |cache| {
+                cache.mut_subcommand("clear", |c| c.mut_arg("repo", repo_name))
+            })
+    })
+    .mut_subcommand("search", |c| c.mut_arg("repo", repo_name))
+}
@@ -6,17 +6,22 @@
 use console::style;
 use miette::{IntoDiagnostic, Result, WrapErr};
 use sherpack_convert::{
-    ConversionResult, ConvertOptions, WarningCategory, WarningSeverity, convert_with_options,
+    ConversionReport, ConversionResult, ConvertOptions, FunctionSupport, WarningCategory,
+    WarningSeverity, convert_with_options,
 };
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
+#[allow(clippy::too_many_arguments)]
 pub fn run(
     chart_path: &Path,
     output: Option<&Path>,
     force: bool,
     dry_run: bool,
     verbose: bool,
+    json: bool,
+    verify_render: bool,
+    report: Option<PathBuf>,
 ) -> Result<()> {
     // Determine output path
     let output_path = if let Some(out) = output {
@@ -33,27 +38,125 @@ pub fn run(
     };
 
     // Header
-    print_header(chart_path, &output_path);
+    if !json {
+        print_header(chart_path, &output_path);
+    }
 
     let options = ConvertOptions {
         force,
         dry_run,
         verbose,
+        verify_render,
+        report,
+        ..ConvertOptions::default()
     };
 
     let result = convert_with_options(chart_path, &output_path, options)
         .into_diagnostic()
         .wrap_err("Conversion failed")?;
 
+    if json {
+        print_json_report(&result, chart_path, &output_path, dry_run)?;
+        return Ok(());
+    }
+
     // Print results
     print_files(&result, &output_path, chart_path);
     print_warnings(&result, &output_path, chart_path, verbose);
+    if verbose {
+        print_coverage(&result);
+    }
+    print_render_check(&result);
     print_summary(&result);
     print_next_steps(&result, &output_path, dry_run);
 
     Ok(())
 }
 
+/// Print the [`ConversionReport`] that `--report` writes
+fn print_json_report(
+    result: &ConversionResult,
+    chart_path: &Path,
+    output_path: &Path,
+    dry_run: bool,
+) -> Result<()> {
+    let report = ConversionReport::new(result, chart_path, output_path, dry_run);
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&report).into_diagnostic()?
+    );
+    Ok(())
+}
+
+fn print_coverage(result: &ConversionResult) {
+    let coverage = &result.coverage;
+    if coverage.is_empty() {
+        return;
+    }
+
+    println!("  {}", style("Function Coverage").bold());
+    println!("  {}", style("─────────────────").dim());
+
+    for (name, tally) in &coverage.functions {
+        let icon = match tally.support {
+            FunctionSupport::Mapped => style("✓").green(),
+            FunctionSupport::Warning => style("⚠").yellow(),
+            FunctionSupport::Unsupported => style("✗").magenta(),
+        };
+        println!(
+            "  {} {:<24} {:>4}  {}",
+            icon,
+            name,
+            tally.count,
+            style(tally.support.label()).dim()
+        );
+    }
+
+    println!();
+    println!(
+        "  {} {} {} mapped, {} need review, {} unsupported",
+        style(format!("{:>3}", coverage.total())).bold(),
+        style("functions:").dim(),
+        style(coverage.count(FunctionSupport::Mapped)).green(),
+        style(coverage.count(FunctionSupport::Warning)).yellow(),
+        style(coverage.count(FunctionSupport::Unsupported)).magenta(),
+    );
+    println!();
+}
+
+fn print_render_check(result: &ConversionResult) {
+    let Some(check) = &result.render_check else {
+        return;
+    };
+
+    println!("  {}", style("Render Check").bold());
+    println!("  {}", style("────────────").dim());
+
+    if check.passed() {
+        println!(
+            "  {} {} template{} rendered with the converted values.yaml",
+            style("✓").green().bold(),
+            check.rendered.len(),
+            if check.rendered.len() == 1 { "" } else { "s" }
+        );
+    } else {
+        for failure in &check.errors {
+            println!(
+                "  {} {}",
+                style("✗").red().bold(),
+                style(&failure.template).bold()
+            );
+            println!("      {}", style(&failure.message).dim());
+        }
+    }
+
+    for warning in &check.warnings {
+        println!("  {} {}", style("⚠").yellow(), warning);
+    }
+
+    println!();
+}
+
 fn print_header(chart_path: &Path, output_path: &Path) {
     println!();
     println!(
@@ -5,8 +5,9 @@ use std::path::Path;
 use crate::error::{CliError, Result};
 use sherpack_core::{LoadedPack, ResolvePolicy, Values};
 use sherpack_repo::{
-    CredentialStore, DependencyResolver, LockFile, RepositoryConfig, create_backend,
-    filter_dependencies,
+    BackendOptions, CredentialStore, DependencyResolver, DependencySpec, GitCache, LockFile,
+    LockedDependency, RepositoryBackend, RepositoryConfig, TreeOptions, VerifyResult,
+    create_backend, filter_dependencies,
 };
 
 /// List dependencies
@@ -38,18 +39,28 @@ pub async fn list(pack_path: &Path) -> Result<()> {
             " [disabled]"
         } else if dep.resolve == ResolvePolicy::Never {
             " [resolve: never]"
-        } else if dep.condition.is_some() && !dep.should_resolve(&values) {
-            " [condition: false]"
+        } else if !dep.should_resolve(&values) {
+            if dep.condition_value(&values).is_some() {
+                " [condition: false]"
+            } else {
+                " [tags: false]"
+            }
         } else {
             ""
         };
 
         println!("  {} @ {}{}{}", dep.name, dep.version, alias_info, status);
-        println!("    repository: {}", dep.repository);
+        match &dep.git {
+            Some(git) => println!("    git: {} @ {}", git.repo, git.git_ref),
+            None => println!("    repository: {}", dep.repository),
+        }
 
         if let Some(condition) = &dep.condition {
             println!("    condition: {}", condition);
         }
+        if !dep.tags.is_empty() {
+            println!("    tags: {}", dep.tags.join(", "));
+        }
         if dep.resolve != ResolvePolicy::WhenEnabled {
             println!("    resolve: {:?}", dep.resolve);
         }
@@ -89,7 +100,13 @@ pub async fn list(pack_path: &Path) -> Result<()> {
 }
 
 /// Update dependencies and create lock file
-pub async fn update(pack_path: &Path) -> Result<()> {
+///
+/// Offline, versions are resolved from the cached repository indices.
+///
+/// With `only`, the other locked dependencies are pinned and only that
+/// entry of the lock file changes, moving to the highest version Pack.yaml
+/// allows.
+pub async fn update(pack_path: &Path, only: Option<&str>, offline: bool) -> Result<()> {
     let pack = LoadedPack::load(pack_path).map_err(|e| CliError::input(e.to_string()))?;
 
     if pack.pack.dependencies.is_empty() {
@@ -130,26 +147,40 @@ pub async fn update(pack_path: &Path) -> Result<()> {
 
     let config = RepositoryConfig::load().map_err(|e| CliError::internal(e.to_string()))?;
     let cred_store = CredentialStore::load().unwrap_or_default();
+    let options = BackendOptions {
+        http: config.http,
+        offline,
+    };
 
-    // Create resolver with fetch function
-    let resolver = DependencyResolver::new(|repo_url, name, version| {
-        // This is synchronous context, need to use futures
-        let rt = tokio::runtime::Handle::current();
-        rt.block_on(async {
-            // Try to find repo in config
-            let repo = if let Some(r) = config.repositories.iter().find(|r| r.url == repo_url) {
-                r.clone()
-            } else {
-                // Create temporary repo for URL
-                sherpack_repo::Repository::new("_temp", repo_url)?
-            };
+    let git_cache = GitCache::open()
+        .map_err(|e| CliError::internal(e.to_string()))?
+        .offline(offline);
 
-            let credentials = cred_store.get(&repo.name).and_then(|c| c.resolve().ok());
-            let mut backend = create_backend(repo, credentials).await?;
+    let lock_path = pack_path.join("Pack.lock.yaml");
+    let previous = match only {
+        Some(name) => Some(selective_base(&lock_path, name, &filter_result.to_resolve)?),
+        None => None,
+    };
 
+    // Create resolver with fetch and version listing functions
+    // This is synchronous context, need to use futures
+    let resolver = DependencyResolver::new(|repo_url, name, version| {
+        tokio::runtime::Handle::current().block_on(async {
+            let mut backend = open_backend(&config, &cred_store, repo_url, options).await?;
             backend.find_best_match(name, version).await
         })
-    });
+    })
+    .with_versions(|repo_url, name| {
+        tokio::runtime::Handle::current().block_on(async {
+            let mut backend = open_backend(&config, &cred_store, repo_url, options).await?;
+            backend.list_versions(name).await
+        })
+    })
+    .with_git_cache(git_cache);
+    let resolver = match (&previous, only) {
+        (Some(previous), Some(name)) => resolver.with_lock(previous, &[name]),
+        _ => resolver,
+    };
 
     // Resolve only the filtered dependencies
     let graph = resolver
@@ -166,7 +197,13 @@ pub async fn update(pack_path: &Path) -> Result<()> {
             .map(|a| format!(" (alias: {})", a))
             .unwrap_or_default();
 
-        println!("  {} @ {}{}", dep.name, dep.version, alias_info);
+        let git_info = dep
+            .git
+            .as_ref()
+            .map(|g| format!(" ({} at {:.12})", g.git_ref, g.commit))
+            .unwrap_or_default();
+
+        println!("  {} @ {}{}{}", dep.name, dep.version, alias_info, git_info);
     }
 
     // Show dependency tree
@@ -178,8 +215,23 @@ pub async fn update(pack_path: &Path) -> Result<()> {
     let pack_yaml_content =
         std::fs::read_to_string(pack_path.join("Pack.yaml")).map_err(CliError::io)?;
 
-    let lock = graph.to_lock_file(&pack_yaml_content);
-    let lock_path = pack_path.join("Pack.lock.yaml");
+    let mut lock = graph.to_lock_file(&pack_yaml_content);
+    if let (Some(mut previous), Some(name)) = (previous, only) {
+        let old_version = previous.get(name).map(|d| d.version.clone());
+        previous.apply_update(lock, name);
+        lock = previous;
+
+        let new_version = lock.get(name).map(|d| d.version.clone());
+        println!();
+        match (old_version, new_version) {
+            (Some(old), Some(new)) if old == new => println!("{} is up to date ({})", name, new),
+            (Some(old), Some(new)) => println!("Updated {} {} -> {}", name, old, new),
+            (_, Some(new)) => println!("Locked {} at {}", name, new),
+            _ => {}
+        }
+    } else if let Ok(previous) = LockFile::load(&lock_path) {
+        lock.carry_sources(&previous);
+    }
     lock.save(&lock_path)
         .map_err(|e| CliError::internal(e.to_string()))?;
 
@@ -192,8 +244,69 @@ pub async fn update(pack_path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Load the lock file a single-dependency update starts from
+///
+/// Every other dependency must still be locked as Pack.yaml declares it:
+/// when Pack.yaml changed for them too, only a full update is correct.
+fn selective_base(lock_path: &Path, name: &str, specs: &[DependencySpec]) -> Result<LockFile> {
+    if !lock_path.exists() {
+        return Err(CliError::input(
+            "Pack.lock.yaml not found. Run 'sherpack dependency update' first",
+        ));
+    }
+    let lock = LockFile::load(lock_path).map_err(|e| CliError::internal(e.to_string()))?;
+
+    if !specs.iter().any(|d| d.effective_name() == name) {
+        return Err(CliError::input(format!(
+            "'{}' is not a dependency to resolve in Pack.yaml (unknown, disabled or skipped)",
+            name
+        )));
+    }
+
+    let stale: Vec<&str> = specs
+        .iter()
+        .filter(|d| d.effective_name() != name)
+        .filter(|d| {
+            lock.get(d.effective_name()).is_none_or(|locked| {
+                locked.constraint != d.version
+                    || (d.git.is_none() && locked.repository != d.repository)
+            })
+        })
+        .map(|d| d.effective_name())
+        .collect();
+    if !stale.is_empty() {
+        return Err(CliError::input(format!(
+            "Pack.yaml changed for {} since the lock was written. Run 'sherpack dependency update' to update everything",
+            stale.join(", ")
+        )));
+    }
+
+    Ok(lock)
+}
+
+/// Open the backend for a repository URL, configured or not
+async fn open_backend(
+    config: &RepositoryConfig,
+    cred_store: &CredentialStore,
+    repo_url: &str,
+    options: BackendOptions,
+) -> sherpack_repo::Result<Box<dyn RepositoryBackend>> {
+    // Try to find repo in config
+    let repo = if let Some(r) = config.repositories.iter().find(|r| r.url == repo_url) {
+        r.clone()
+    } else {
+        // Create temporary repo for URL
+        sherpack_repo::Repository::new("_temp", repo_url)?
+    };
+
+    let credentials = cred_store.resolve_for(&repo.name, &repo.url);
+    create_backend(repo, credentials, options).await
+}
+
 /// Build (download) dependencies
-pub async fn build(pack_path: &Path, verify: bool) -> Result<()> {
+///
+/// Offline, archives come from the archive cache only.
+pub async fn build(pack_path: &Path, verify: bool, offline: bool) -> Result<()> {
     // Validate pack exists (we only need to check if it loads)
     let _pack = LoadedPack::load(pack_path).map_err(|e| CliError::input(e.to_string()))?;
 
@@ -223,6 +336,10 @@ pub async fn build(pack_path: &Path, verify: bool) -> Result<()> {
 
     let config = RepositoryConfig::load().map_err(|e| CliError::internal(e.to_string()))?;
     let cred_store = CredentialStore::load().unwrap_or_default();
+    let options = BackendOptions {
+        http: config.http,
+        offline,
+    };
 
     // Create charts directory
     let charts_dir = pack_path.join("charts");
@@ -232,49 +349,33 @@ pub async fn build(pack_path: &Path, verify: bool) -> Result<()> {
 
     for locked in &lock.dependencies {
         print!("  {} @ {}... ", locked.name, locked.version);
+        let dest = charts_dir.join(locked.effective_name());
 
-        // Find repository
-        let repo = if let Some(r) = config
-            .repositories
-            .iter()
-            .find(|r| r.url == locked.repository)
-        {
-            r.clone()
-        } else {
-            sherpack_repo::Repository::new("_temp", &locked.repository)
-                .map_err(|e| CliError::internal(e.to_string()))?
-        };
-
-        let credentials = cred_store.get(&repo.name).and_then(|c| c.resolve().ok());
-
-        let backend = create_backend(repo, credentials)
-            .await
-            .map_err(|e| CliError::internal(e.to_string()))?;
+        // Git dependencies are checked out at the locked commit
+        if let Some(git) = &locked.git {
+            let checkout = GitCache::open()
+                .and_then(|cache| {
+                    cache
+                        .offline(offline)
+                        .checkout_commit(&git.source(), &git.commit)
+                })
+                .map_err(|e| CliError::internal(e.to_string()))?;
+
+            if verify {
+                report_verify(lock.verify_checkout(locked.effective_name(), &checkout))?;
+            }
 
-        // Download
-        let data = backend
-            .download(&locked.name, &locked.version.to_string())
-            .await
-            .map_err(|e| CliError::internal(e.to_string()))?;
+            checkout
+                .copy_pack_to(&dest)
+                .map_err(|e| CliError::internal(e.to_string()))?;
 
-        // Verify if requested
-        if verify {
-            match lock.verify(locked.effective_name(), &data) {
-                Ok(sherpack_repo::VerifyResult::Match) => {
-                    print!("verified... ");
-                }
-                Ok(sherpack_repo::VerifyResult::DigestChanged { .. }) => {
-                    print!("(digest changed)... ");
-                }
-                Err(e) => {
-                    println!("FAILED");
-                    return Err(CliError::internal(format!("Integrity check failed: {}", e)));
-                }
-            }
+            println!("OK");
+            continue;
         }
 
+        let data = download_locked(&lock, locked, &config, &cred_store, options, verify).await?;
+
         // Extract to charts/
-        let dest = charts_dir.join(locked.effective_name());
         extract_archive(&data, &dest)?;
 
         println!("OK");
@@ -289,8 +390,80 @@ pub async fn build(pack_path: &Path, verify: bool) -> Result<()> {
     Ok(())
 }
 
+/// Download a locked archive, trying its sources in order
+///
+/// An archive from an alternative source is always checked against the
+/// pinned digest, and one that doesn't match counts as a failed source. The
+/// primary repository is only checked with `verify`, as before.
+async fn download_locked(
+    lock: &LockFile,
+    locked: &LockedDependency,
+    config: &RepositoryConfig,
+    cred_store: &CredentialStore,
+    options: BackendOptions,
+    verify: bool,
+) -> Result<Vec<u8>> {
+    let version = locked.version.to_string();
+    let mut failures = Vec::new();
+
+    for source in locked.all_sources() {
+        let is_primary = source == locked.repository;
+        let download = match open_backend(config, cred_store, source, options).await {
+            Ok(backend) => backend.download(&locked.name, &version).await,
+            Err(e) => Err(e),
+        };
+        let data = match download {
+            Ok(data) => data,
+            Err(e) => {
+                failures.push(format!("{}: {}", source, e));
+                continue;
+            }
+        };
+
+        if is_primary && !verify {
+            print!("from {}... ", source);
+            cache_source_archive(source, locked, &version, &data, options);
+            return Ok(data);
+        }
+        match lock.verify_source(locked.effective_name(), source, &data) {
+            Ok(result) => {
+                report_verify(Ok(result))?;
+                cache_source_archive(source, locked, &version, &data, options);
+                return Ok(data);
+            }
+            Err(e) if is_primary => {
+                report_verify(Err(e))?;
+            }
+            Err(e) => failures.push(format!("{}: {}", source, e)),
+        }
+    }
+
+    println!("FAILED");
+    if let [failure] = failures.as_slice() {
+        return Err(CliError::internal(failure.clone()));
+    }
+    Err(CliError::internal(format!(
+        "No source could provide {} {}:\n  {}",
+        locked.name,
+        version,
+        failures.join("\n  ")
+    )))
+}
+
+fn cache_source_archive(
+    source: &str,
+    locked: &LockedDependency,
+    version: &str,
+    data: &[u8],
+    options: BackendOptions,
+) {
+    if !options.offline {
+        super::pull::cache_archive(source, &locked.name, version, data);
+    }
+}
+
 /// Show dependency tree
-pub async fn tree(pack_path: &Path) -> Result<()> {
+pub async fn tree(pack_path: &Path, depth: Option<usize>, duplicates: bool) -> Result<()> {
     let pack = LoadedPack::load(pack_path).map_err(|e| CliError::input(e.to_string()))?;
 
     let lock_path = pack_path.join("Pack.lock.yaml");
@@ -298,8 +471,13 @@ pub async fn tree(pack_path: &Path) -> Result<()> {
         // Just show from Pack.yaml without resolution
         println!("{}@{}", pack.pack.metadata.name, pack.pack.metadata.version);
 
-        for (i, dep) in pack.pack.dependencies.iter().enumerate() {
-            let is_last = i == pack.pack.dependencies.len() - 1;
+        let deps = if depth == Some(0) {
+            &[][..]
+        } else {
+            &pack.pack.dependencies[..]
+        };
+        for (i, dep) in deps.iter().enumerate() {
+            let is_last = i == deps.len() - 1;
             let prefix = if is_last { "└── " } else { "├── " };
             println!("{}{} @ {}", prefix, dep.name, dep.version);
         }
@@ -323,11 +501,36 @@ pub async fn tree(pack_path: &Path) -> Result<()> {
         .map_err(|e| CliError::internal(e.to_string()))?;
 
     println!("{}@{}", pack.pack.metadata.name, pack.pack.metadata.version);
-    println!("{}", graph.render_tree());
+    let rendered = graph.render_tree_with(&TreeOptions { depth, duplicates });
+    if !rendered.is_empty() {
+        println!("{}", rendered);
+    }
 
     Ok(())
 }
 
+/// Print the outcome of an integrity check, failing on a mismatch
+fn report_verify(result: sherpack_repo::Result<VerifyResult>) -> Result<()> {
+    match result {
+        Ok(VerifyResult::Match { source }) => {
+            print!("verified from {}... ", source);
+            Ok(())
+        }
+        Ok(VerifyResult::DigestChanged { source, .. }) => {
+            print!("(digest changed on {})... ", source);
+            Ok(())
+        }
+        Ok(VerifyResult::Mirror { source }) => {
+            print!("verified from mirror {}... ", source);
+            Ok(())
+        }
+        Err(e) => {
+            println!("FAILED");
+            Err(CliError::internal(format!("Integrity check failed: {}", e)))
+        }
+    }
+}
+
 fn extract_archive(data: &[u8], dest: &std::path::PathBuf) -> Result<()> {
     use flate2::read::GzDecoder;
     use tar::Archive;
@@ -0,0 +1,189 @@
+//! Diff command - compare revisions or detect drift against the cluster
+
+use console::style;
+use miette::IntoDiagnostic;
+use sherpack_core::{LoadedPack, ReleaseInfo, TemplateContext, Values, parse_set_values};
+use sherpack_engine::Engine;
+use sherpack_kube::{
+    DiffEngine, DiffResult,
+    storage::{FileDriver, StorageConfig, StorageDriver},
+};
+use std::path::{Path, PathBuf};
+
+use crate::error::{CliError, Result};
+use crate::util::KubeTarget;
+
+/// Run the diff command
+///
+/// Modes, in order of precedence:
+/// - `--three-way <PACK>`: desired pack vs last release vs live cluster
+/// - `--live`: latest release vs live cluster
+/// - otherwise: a stored revision (default: the previous one) vs the latest
+///
+/// Returns [`CliError::DriftDetected`] when the cluster differs from the
+/// release, so the command can be used in monitoring jobs.
+#[allow(clippy::too_many_arguments)]
+pub async fn run(
+    name: &str,
+    namespace: &str,
+    revision: Option<u32>,
+    live: bool,
+    three_way: Option<&Path>,
+    values_files: &[PathBuf],
+    set_values: &[String],
+    reveal_secrets: bool,
+    output_json: bool,
+    kube: &KubeTarget,
+) -> Result<()> {
+    // Create storage driver
+    let storage_path = dirs::data_dir()
+        .unwrap_or_else(|| std::path::PathBuf::from("."))
+        .join("sherpack")
+        .join("releases");
+
+    let storage = FileDriver::new(storage_path, StorageConfig::default()).into_diagnostic()?;
+
+    // Create client
+    let client = kube.client(storage).await?;
+    let engine = DiffEngine::new().reveal_secrets(reveal_secrets);
+
+    let latest = client
+        .storage()
+        .get_latest(namespace, name)
+        .await
+        .into_diagnostic()?;
+
+    if let Some(pack_path) = three_way {
+        let desired = render_desired(pack_path, values_files, set_values, &latest)?;
+        let result = engine
+            .three_way_diff(&desired, &latest, client.kube_client())
+            .await
+            .into_diagnostic()?;
+
+        let drift_count = result.changes.iter().filter(|c| c.is_drift).count();
+
+        if output_json {
+            let json = serde_json::to_string_pretty(&result).into_diagnostic()?;
+            println!("{}", json);
+        } else {
+            let display = DiffResult {
+                old_version: latest.version,
+                new_version: latest.version + 1,
+                changes: result.changes,
+                has_drift: result.has_drift,
+            };
+            print!("{}", engine.format_colored(&display));
+        }
+
+        return drift_outcome(drift_count);
+    }
+
+    if live {
+        let result = engine
+            .detect_drift(&latest, client.kube_client())
+            .await
+            .into_diagnostic()?;
+
+        let drift_count = result.drift_changes().len();
+
+        if output_json {
+            let json = serde_json::to_string_pretty(&result).into_diagnostic()?;
+            println!("{}", json);
+        } else if result.has_changes() {
+            print!("{}", engine.format_colored(&result));
+        } else {
+            println!(
+                "{} Release {} (revision {}) matches cluster state",
+                style("✓").green().bold(),
+                style(name).cyan(),
+                style(latest.version).yellow()
+            );
+        }
+
+        return drift_outcome(drift_count);
+    }
+
+    // Revision comparison
+    let old_version = match revision {
+        Some(r) => r,
+        None if latest.version > 1 => latest.version - 1,
+        None => {
+            return Err(CliError::input(format!(
+                "release '{}' has a single revision; use --live to compare with the cluster",
+                name
+            )));
+        }
+    };
+
+    let old = client
+        .storage()
+        .get(namespace, name, old_version)
+        .await
+        .into_diagnostic()?;
+    let result = engine.diff_releases(&old, &latest);
+
+    if output_json {
+        let json = serde_json::to_string_pretty(&result).into_diagnostic()?;
+        println!("{}", json);
+    } else {
+        print!("{}", engine.format_colored(&result));
+    }
+
+    Ok(())
+}
+
+/// Render the desired manifest for a three-way diff
+fn render_desired(
+    pack_path: &Path,
+    values_files: &[PathBuf],
+    set_values: &[String],
+    latest: &sherpack_kube::StoredRelease,
+) -> Result<String> {
+    let pack = LoadedPack::load(pack_path).into_diagnostic()?;
+
+    // Load and merge values
+    let mut values = Values::from_file(&pack.values_path).into_diagnostic()?;
+
+    // Apply schema defaults if available
+    if let Some(schema) = pack.load_schema().into_diagnostic()? {
+        let defaults = Values::from(schema.extract_defaults());
+        values = Values::with_schema_defaults(defaults, values);
+    }
+
+    // Merge additional values files
+    for vf in values_files {
+        let overlay = Values::from_file(vf).into_diagnostic()?;
+        values.merge(&overlay);
+    }
+
+    // Apply --set values
+    if !set_values.is_empty() {
+        let set_values_map = parse_set_values(set_values).into_diagnostic()?;
+        values.merge_set(&set_values_map);
+    }
+
+    let release_info =
+        ReleaseInfo::for_upgrade(&latest.name, &latest.namespace, latest.version + 1);
+    let context = TemplateContext::new(values, release_info, &pack.pack.metadata);
+
+    let engine = Engine::builder().strict(pack.pack.engine.strict).build();
+    let render_result = engine
+        .render_pack(&pack, &context)
+        .map_err(|e| CliError::template(e.to_string()))?;
+
+    Ok(render_result
+        .manifests
+        .values()
+        .cloned()
+        .collect::<Vec<_>>()
+        .join("\n---\n"))
+}
+
+/// Map a drift count to the command outcome
+fn drift_outcome(drift_count: usize) -> Result<()> {
+    if drift_count > 0 {
+        Err(CliError::drift_detected(drift_count))
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,146 @@
+//! Get command - print what was stored for a release
+//!
+//! Equivalent to `helm get`. Reads the stored release directly from the
+//! storage driver, so no cluster access is needed.
+
+use clap::ValueEnum;
+use miette::IntoDiagnostic;
+use sherpack_kube::{
+    StoredRelease,
+    hooks::parse_hooks_from_manifest,
+    storage::{FileDriver, StorageConfig, StorageDriver},
+};
+
+use crate::display::display_values_provenance;
+use crate::error::Result;
+
+/// Output format for structured `get` output
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Yaml,
+    Json,
+}
+
+/// Print the release values
+///
+/// Only user-supplied values by default; `all` prints the full computed
+/// values, including pack and schema defaults. With `provenance`, each
+/// value path is listed with its source instead.
+pub async fn values(
+    name: &str,
+    namespace: &str,
+    revision: Option<u32>,
+    all: bool,
+    provenance: bool,
+    output: OutputFormat,
+) -> Result<()> {
+    let release = load_release(name, namespace, revision).await?;
+
+    if provenance {
+        let mut origins = release.values_provenance.origins(&release.values);
+        if !all {
+            origins.retain(|origin| origin.source.as_ref().is_some_and(|s| !s.is_default()));
+        }
+
+        if output == OutputFormat::Json {
+            let json = serde_json::to_string_pretty(&origins).into_diagnostic()?;
+            println!("{}", json);
+        } else {
+            display_values_provenance(&origins);
+        }
+        return Ok(());
+    }
+
+    let values = if all {
+        release.values
+    } else {
+        release
+            .values_provenance
+            .user_supplied(&release.values)
+            .into_diagnostic()?
+    };
+
+    print_structured(values.inner(), output)
+}
+
+/// Print the rendered manifest
+pub async fn manifest(name: &str, namespace: &str, revision: Option<u32>) -> Result<()> {
+    let release = load_release(name, namespace, revision).await?;
+    println!("{}", release.manifest.trim());
+    Ok(())
+}
+
+/// Print the release hooks
+pub async fn hooks(
+    name: &str,
+    namespace: &str,
+    revision: Option<u32>,
+    output: OutputFormat,
+) -> Result<()> {
+    let release = load_release(name, namespace, revision).await?;
+
+    // Hooks may not have been parsed into older releases
+    let hooks = if release.hooks.is_empty() {
+        parse_hooks_from_manifest(&release.manifest)
+    } else {
+        release.hooks
+    };
+
+    match output {
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(&hooks).into_diagnostic()?;
+            println!("{}", json);
+        }
+        OutputFormat::Yaml => {
+            for hook in &hooks {
+                println!("---");
+                println!("# Source: {}", hook.name);
+                println!("{}", hook.resource.trim());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Print the release notes
+pub async fn notes(name: &str, namespace: &str, revision: Option<u32>) -> Result<()> {
+    let release = load_release(name, namespace, revision).await?;
+
+    match release.notes {
+        Some(notes) => println!("{}", notes.trim_end()),
+        None => eprintln!(
+            "Release {}/{} (revision {}) has no notes",
+            namespace, name, release.version
+        ),
+    }
+
+    Ok(())
+}
+
+/// Load a specific revision, or the latest one
+async fn load_release(name: &str, namespace: &str, revision: Option<u32>) -> Result<StoredRelease> {
+    let storage_path = dirs::data_dir()
+        .unwrap_or_else(|| std::path::PathBuf::from("."))
+        .join("sherpack")
+        .join("releases");
+
+    let storage = FileDriver::new(storage_path, StorageConfig::default()).into_diagnostic()?;
+
+    let release = match revision {
+        Some(revision) => storage.get(namespace, name, revision).await,
+        None => storage.get_latest(namespace, name).await,
+    };
+
+    Ok(release.into_diagnostic()?)
+}
+
+fn print_structured(value: &serde_json::Value, output: OutputFormat) -> Result<()> {
+    let text = match output {
+        OutputFormat::Json => serde_json::to_string_pretty(value).into_diagnostic()?,
+        OutputFormat::Yaml => serde_yaml::to_string(value).into_diagnostic()?,
+    };
+    println!("{}", text.trim_end());
+    Ok(())
+}
@@ -3,18 +3,24 @@
 use console::style;
 use miette::IntoDiagnostic;
 use sherpack_kube::{
-    KubeClient,
+    DiffEngine, ReleaseState, StoredRelease,
     storage::{FileDriver, StorageConfig},
 };
 
-use crate::error::Result;
+use crate::error::{CliError, Result};
+use crate::util::KubeTarget;
 
 /// Run the history command
+///
+/// With `diff` (`A:B`, or `A` for A against the latest revision), compares
+/// two stored revisions instead of listing them.
 pub async fn run(
     name: &str,
     namespace: &str,
     max_revisions: Option<usize>,
+    diff: Option<&str>,
     output_json: bool,
+    kube: &KubeTarget,
 ) -> Result<()> {
     // Create storage driver
     let storage_path = dirs::data_dir()
@@ -25,11 +31,25 @@ pub async fn run(
     let storage = FileDriver::new(storage_path, StorageConfig::default()).into_diagnostic()?;
 
     // Create client
-    let client = KubeClient::new(storage).await.into_diagnostic()?;
+    let client = kube.client(storage).await?;
 
     // Get history
     let mut history = client.history(namespace, name).await.into_diagnostic()?;
 
+    if let Some(spec) = diff {
+        let (old, new) = diff_revisions(name, &history, spec)?;
+        let engine = DiffEngine::new();
+        let result = engine.diff_releases(old, new);
+
+        if output_json {
+            let json = serde_json::to_string_pretty(&result).into_diagnostic()?;
+            println!("{}", json);
+        } else {
+            print!("{}", engine.format_colored(&result));
+        }
+        return Ok(());
+    }
+
     // Limit if requested
     if let Some(max) = max_revisions {
         history.truncate(max);
@@ -60,7 +80,7 @@ pub async fn run(
     for release in history {
         let status_style = match release.state.status_name() {
             "deployed" => style(release.state.status_name()).green(),
-            "failed" => style(release.state.status_name()).red(),
+            "failed" | "rolled-back" => style(release.state.status_name()).red(),
             "superseded" => style(release.state.status_name()).dim(),
             s if s.starts_with("pending") => style(release.state.status_name()).yellow(),
             _ => style(release.state.status_name()).dim(),
@@ -75,7 +95,105 @@ pub async fn run(
             pack_info,
             release.updated_at.format("%Y-%m-%d %H:%M:%S")
         );
+
+        // Explain why an atomic operation's revision was replaced
+        if let ReleaseState::AtomicRollback { .. } = release.state {
+            println!("{:<10} {}", "", style(&release.state).dim());
+        }
     }
 
     Ok(())
 }
+
+/// Resolve a `A:B` or `A` (against the latest) diff spec to stored revisions
+fn diff_revisions<'a>(
+    name: &str,
+    history: &'a [StoredRelease],
+    spec: &str,
+) -> Result<(&'a StoredRelease, &'a StoredRelease)> {
+    let parse = |revision: &str| {
+        revision.trim().parse::<u32>().map_err(|_| {
+            CliError::input(format!(
+                "invalid revision '{}' in --diff (expected A:B or A)",
+                revision
+            ))
+        })
+    };
+
+    let latest = history
+        .iter()
+        .map(|r| r.version)
+        .max()
+        .ok_or_else(|| CliError::input(format!("release '{}' has no history", name)))?;
+    let (old, new) = match spec.split_once(':') {
+        Some((a, b)) => (parse(a)?, parse(b)?),
+        None => (parse(spec)?, latest),
+    };
+
+    let find = |revision: u32| {
+        history
+            .iter()
+            .find(|r| r.version == revision)
+            .ok_or_else(|| {
+                let mut available: Vec<u32> = history.iter().map(|r| r.version).collect();
+                available.sort_unstable();
+                let available: Vec<String> = available.iter().map(u32::to_string).collect();
+                CliError::input(format!(
+                    "revision {} not found for release '{}'; available revisions: {}",
+                    revision,
+                    name,
+                    available.join(", ")
+                ))
+            })
+    };
+
+    Ok((find(old)?, find(new)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sherpack_core::Values;
+
+    fn history(versions: &[u32]) -> Vec<StoredRelease> {
+        let pack = serde_json::from_value(serde_json::json!({
+            "name": "web",
+            "version": "1.0.0"
+        }))
+        .unwrap();
+        let first = StoredRelease::for_install(
+            "web".to_string(),
+            "default".to_string(),
+            pack,
+            Values::new(),
+            String::new(),
+        );
+        versions
+            .iter()
+            .map(|&version| StoredRelease {
+                version,
+                ..first.clone()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_diff_revisions() {
+        let history = history(&[3, 2, 1]);
+
+        let (old, new) = diff_revisions("web", &history, "1:2").unwrap();
+        assert_eq!((old.version, new.version), (1, 2));
+
+        // A single revision is compared with the latest
+        let (old, new) = diff_revisions("web", &history, "1").unwrap();
+        assert_eq!((old.version, new.version), (1, 3));
+
+        let err = diff_revisions("web", &history, "1:7").unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("revision 7 not found for release 'web'; available revisions: 1, 2, 3"),
+            "{err}"
+        );
+        assert!(diff_revisions("web", &history, "x:2").is_err());
+    }
+}
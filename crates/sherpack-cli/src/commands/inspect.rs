@@ -59,7 +59,7 @@ pub fn run(archive_path: &Path, show_manifest: bool, show_checksums: bool) -> Re
                 .files
                 .iter()
                 .find(|f| f.path == entry.path)
-                .map(|f| format!("sha256:{}...", truncate_hash(&f.sha256, 12)))
+                .map(|f| format!("{}:{}...", f.algorithm, truncate_hash(&f.digest, 12)))
                 .unwrap_or_else(|| "N/A".to_string());
 
             println!(
@@ -2,14 +2,17 @@
 
 use console::style;
 use miette::IntoDiagnostic;
-use sherpack_core::{LoadedPack, Values, parse_set_values};
+use sherpack_core::LoadedPack;
+use sherpack_engine::{ExecPostRenderer, PostRenderer};
 use sherpack_kube::{
-    InstallOptions, KubeClient,
+    DryRunMode, HealthCheckConfig, InstallOptions, ProgressFormat, WaitCondition,
     storage::{FileDriver, StorageConfig},
 };
 use std::path::Path;
+use std::sync::Arc;
 
 use crate::error::Result;
+use crate::util::KubeTarget;
 
 /// Run the install command
 #[allow(clippy::too_many_arguments)]
@@ -18,62 +21,63 @@ pub async fn run(
     pack_path: &Path,
     values_files: &[std::path::PathBuf],
     set_values: &[String],
+    set_literal_values: &[String],
     namespace: &str,
     wait: bool,
     timeout: Option<u64>,
+    wait_for: &[WaitCondition],
     atomic: bool,
+    atomic_timeout: Option<u64>,
     create_namespace: bool,
-    dry_run: bool,
+    take_ownership: bool,
+    verify_apply: bool,
+    hook_logs: bool,
+    render_subchart_notes: bool,
+    skip_kube_version_check: bool,
+    dry_run: DryRunMode,
     show_diff: bool,
     skip_crds: bool,
+    post_renderer: Option<ExecPostRenderer>,
+    progress: Option<ProgressFormat>,
+    debug: bool,
+    kube: &KubeTarget,
 ) -> Result<()> {
+    let out = crate::util::human_output(progress);
+
     // Load the pack
     let pack = LoadedPack::load(pack_path).into_diagnostic()?;
-    println!(
+    out.write_line(&format!(
         "{} Installing pack {} version {}",
         style("→").blue().bold(),
         style(&pack.pack.metadata.name).cyan(),
         style(&pack.pack.metadata.version).yellow()
-    );
+    ))
+    .into_diagnostic()?;
 
     // Check for CRDs
     if pack.has_crds() {
         let crd_files = pack.crd_files().into_diagnostic()?;
         if skip_crds {
-            println!(
+            out.write_line(&format!(
                 "{} Skipping {} CRD file(s) (--skip-crds)",
                 style("⚠").yellow(),
                 crd_files.len()
-            );
+            ))
+            .into_diagnostic()?;
         } else {
-            println!(
+            out.write_line(&format!(
                 "{} Found {} CRD file(s) in crds/ directory",
                 style("→").blue(),
                 crd_files.len()
-            );
+            ))
+            .into_diagnostic()?;
         }
     }
 
-    // Load and merge values
-    let mut values = Values::from_file(&pack.values_path).into_diagnostic()?;
-
-    // Apply schema defaults if available
-    if let Some(schema) = pack.load_schema().into_diagnostic()? {
-        let defaults = Values(schema.extract_defaults());
-        values = Values::with_schema_defaults(defaults, values);
-    }
-
-    // Merge additional values files
-    for vf in values_files {
-        let overlay = Values::from_file(vf).into_diagnostic()?;
-        values.merge(&overlay);
-    }
-
-    // Apply --set values
-    if !set_values.is_empty() {
-        let set_values_map = parse_set_values(set_values).into_diagnostic()?;
-        values.merge(&set_values_map);
-    }
+    // Load and merge values, recording where each one came from
+    let values =
+        crate::util::load_tracked_values(&pack, values_files, set_values, set_literal_values)
+            .into_diagnostic()?;
 
     // Create storage driver (file-based for now, since we might not have a cluster)
     let storage_path = dirs::data_dir()
@@ -84,19 +88,40 @@ pub async fn run(
     let storage = FileDriver::new(storage_path, StorageConfig::default()).into_diagnostic()?;
 
     // Create client
-    let client = KubeClient::new(storage).await.into_diagnostic()?;
+    let client = kube.client(storage).await?;
+
+    if debug {
+        crate::util::print_cluster_preflight(&client).await;
+    }
 
     // Build install options
     let mut options = InstallOptions::new(name, namespace);
-    options.wait = wait;
+    options.wait = wait || !wait_for.is_empty();
+    if !wait_for.is_empty() {
+        options.health_check = Some(HealthCheckConfig {
+            wait_for: wait_for.to_vec(),
+            ..Default::default()
+        });
+    }
     options.atomic = atomic;
     options.create_namespace = create_namespace;
+    options.take_ownership = take_ownership;
+    options.verify_apply = verify_apply;
+    options.hook_logs = hook_logs;
+    options.render_subchart_notes = render_subchart_notes;
+    options.skip_kube_version_check = skip_kube_version_check;
     options.dry_run = dry_run;
     options.show_diff = show_diff;
+    options.post_renderer = post_renderer.map(|r| Arc::new(r) as Arc<dyn PostRenderer>);
+    options.progress = progress.map(ProgressFormat::sink);
+    options.values_provenance = values.provenance().clone();
 
     if let Some(t) = timeout {
         options.timeout = Some(chrono::Duration::seconds(t as i64));
     }
+    if let Some(t) = atomic_timeout {
+        options.atomic_timeout = Some(chrono::Duration::seconds(t as i64));
+    }
 
     // Execute install
     let release = client
@@ -104,27 +129,31 @@ pub async fn run(
         .await
         .into_diagnostic()?;
 
-    if dry_run {
-        println!(
-            "{} Dry run - would install {} in namespace {}",
+    if dry_run.is_enabled() {
+        out.write_line(&format!(
+            "{} Dry run ({}) - would install {} in namespace {}",
             style("✓").green().bold(),
+            dry_run,
             style(name).cyan(),
             style(namespace).yellow()
-        );
+        ))
+        .into_diagnostic()?;
     } else {
-        println!(
+        out.write_line(&format!(
             "{} Successfully installed {} (revision {}) in namespace {}",
             style("✓").green().bold(),
             style(&release.name).cyan(),
             style(release.version).yellow(),
             style(&release.namespace).yellow()
-        );
+        ))
+        .into_diagnostic()?;
     }
 
     // Show notes if present
     if let Some(notes) = &release.notes {
-        println!("\n{}", style("NOTES:").bold());
-        println!("{}", notes);
+        out.write_line(&format!("\n{}", style("NOTES:").bold()))
+            .into_diagnostic()?;
+        out.write_line(notes).into_diagnostic()?;
     }
 
     Ok(())
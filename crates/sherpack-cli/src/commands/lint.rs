@@ -1,37 +1,168 @@
 //! Lint command - validate a pack
+//!
+//! Every problem is recorded as a [`LintFinding`]. Text output prints them as
+//! they are found; `--format json` prints them all at the end instead.
 
+use clap::ValueEnum;
 use console::style;
 use indexmap::IndexMap;
-use sherpack_core::{LoadedPack, ReleaseInfo, SchemaValidator, TemplateContext, Values};
-use sherpack_engine::Engine;
+use miette::IntoDiagnostic;
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use sherpack_core::{
+    CoreError, LoadedPack, ReleaseInfo, Schema, SchemaValidator, SecretState, TemplateContext,
+    Values,
+};
+use sherpack_engine::{Engine, IssueSeverity, PackRenderer, function_calls, value_references};
 use sherpack_kube::{
     CrdLocation, DetectedCrd, LintSeverity, TemplatedCrdFile, detect_crds_in_manifests, lint_crds,
 };
+use std::fmt::Display;
 use std::path::Path;
 
 use crate::display::display_render_report;
 use crate::error::{CliError, Result};
 
-pub fn run(path: &Path, strict: bool, skip_schema: bool) -> Result<()> {
-    println!("{} Linting pack at {}", style("→").blue(), path.display());
+/// Output format for lint results
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum LintFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+/// A single problem found in the pack
+#[derive(Debug, Clone, Serialize)]
+pub struct LintFinding {
+    pub severity: LintSeverity,
+    /// Rule that produced the finding (e.g. `undefined_variable`)
+    pub rule: String,
+    /// File path relative to the pack root
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl LintFinding {
+    fn new(
+        severity: LintSeverity,
+        rule: impl Into<String>,
+        path: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            severity,
+            rule: rule.into(),
+            path: path.into(),
+            line: None,
+            message: message.into(),
+        }
+    }
+
+    fn error(rule: &str, path: &str, message: impl Into<String>) -> Self {
+        Self::new(LintSeverity::Error, rule, path, message)
+    }
+
+    fn warning(rule: &str, path: &str, message: impl Into<String>) -> Self {
+        Self::new(LintSeverity::Warning, rule, path, message)
+    }
+
+    fn at_line(mut self, line: Option<usize>) -> Self {
+        self.line = line;
+        self
+    }
+}
+
+/// JSON document printed by `--format json`
+#[derive(Debug, Serialize)]
+struct LintReport<'a> {
+    passed: bool,
+    findings: &'a [LintFinding],
+}
+
+/// Collects findings, printing progress unless the output is JSON
+struct Linter {
+    quiet: bool,
+    findings: Vec<LintFinding>,
+}
+
+impl Linter {
+    fn say(&self, line: impl Display) {
+        if !self.quiet {
+            println!("{}", line);
+        }
+    }
+
+    fn report(&mut self, finding: LintFinding) {
+        self.findings.push(finding);
+    }
+
+    fn count(&self, severity: LintSeverity) -> usize {
+        self.findings
+            .iter()
+            .filter(|f| f.severity == severity)
+            .count()
+    }
+}
+
+/// Run the lint command
+///
+/// Fails on error-level findings, and on warnings too in strict mode.
+/// `secret_state` is a persisted secret state (JSON) backing
+/// `generate_secret()` calls.
+pub fn run(
+    path: &Path,
+    strict: bool,
+    skip_schema: bool,
+    secret_state: Option<&Path>,
+    format: LintFormat,
+) -> Result<()> {
+    let secret_state: Option<SecretState> = match secret_state {
+        Some(file) => {
+            let content = std::fs::read_to_string(file).into_diagnostic()?;
+            Some(serde_json::from_str(&content).into_diagnostic()?)
+        }
+        None => None,
+    };
+
+    let mut lint = Linter {
+        quiet: format == LintFormat::Json,
+        findings: Vec::new(),
+    };
 
-    let mut errors = 0;
-    let mut warnings = 0;
+    lint.say(format_args!(
+        "{} Linting pack at {}",
+        style("→").blue(),
+        path.display()
+    ));
 
     // Check Pack.yaml exists and is valid
     let pack = match LoadedPack::load(path) {
         Ok(p) => {
-            println!(
-                "  {} Pack.yaml is valid ({} v{})",
-                style("✓").green(),
-                p.pack.metadata.name,
-                p.pack.metadata.version
-            );
+            match p.validate() {
+                Ok(()) => lint.say(format_args!(
+                    "  {} Pack.yaml is valid ({} v{})",
+                    style("✓").green(),
+                    p.pack.metadata.name,
+                    p.pack.metadata.version
+                )),
+                Err(errors) => {
+                    for e in errors {
+                        let path = match &e {
+                            CoreError::InvalidStructure { path, .. } => path.as_str(),
+                            _ => "Pack.yaml",
+                        };
+                        lint.say(format_args!("  {} {}: {}", style("✗").red(), path, e));
+                        lint.report(LintFinding::error("pack_validation", path, e.to_string()));
+                    }
+                }
+            }
             Some(p)
         }
         Err(e) => {
-            println!("  {} Pack.yaml: {}", style("✗").red(), e);
-            errors += 1;
+            lint.say(format_args!("  {} Pack.yaml: {}", style("✗").red(), e));
+            lint.report(LintFinding::error("pack_yaml", "Pack.yaml", e.to_string()));
             None
         }
     };
@@ -41,16 +172,30 @@ pub fn run(path: &Path, strict: bool, skip_schema: bool) -> Result<()> {
     if values_path.exists() {
         match Values::from_file(&values_path) {
             Ok(_) => {
-                println!("  {} values.yaml is valid", style("✓").green());
+                lint.say(format_args!(
+                    "  {} values.yaml is valid",
+                    style("✓").green()
+                ));
             }
             Err(e) => {
-                println!("  {} values.yaml: {}", style("✗").red(), e);
-                errors += 1;
+                lint.say(format_args!("  {} values.yaml: {}", style("✗").red(), e));
+                lint.report(LintFinding::error(
+                    "values_yaml",
+                    "values.yaml",
+                    e.to_string(),
+                ));
             }
         }
     } else {
-        println!("  {} values.yaml not found (optional)", style("⚠").yellow());
-        warnings += 1;
+        lint.say(format_args!(
+            "  {} values.yaml not found (optional)",
+            style("⚠").yellow()
+        ));
+        lint.report(LintFinding::warning(
+            "no_values",
+            "values.yaml",
+            "values.yaml not found",
+        ));
     }
 
     // Check templates directory
@@ -61,18 +206,32 @@ pub fn run(path: &Path, strict: bool, skip_schema: bool) -> Result<()> {
             .collect();
 
         if entries.is_empty() {
-            println!("  {} templates/ directory is empty", style("⚠").yellow());
-            warnings += 1;
+            lint.say(format_args!(
+                "  {} templates/ directory is empty",
+                style("⚠").yellow()
+            ));
+            lint.report(LintFinding::warning(
+                "empty_templates",
+                "templates",
+                "templates/ directory is empty",
+            ));
         } else {
-            println!(
+            lint.say(format_args!(
                 "  {} templates/ contains {} file(s)",
                 style("✓").green(),
                 entries.len()
-            );
+            ));
         }
     } else {
-        println!("  {} templates/ directory not found", style("✗").red());
-        errors += 1;
+        lint.say(format_args!(
+            "  {} templates/ directory not found",
+            style("✗").red()
+        ));
+        lint.report(LintFinding::error(
+            "no_templates",
+            "templates",
+            "templates/ directory not found",
+        ));
     }
 
     // Check and validate schema if present
@@ -81,35 +240,59 @@ pub fn run(path: &Path, strict: bool, skip_schema: bool) -> Result<()> {
         && !skip_schema
     {
         if let Some(schema_path) = &pack.schema_path {
+            let schema_file = schema_path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "schema".into());
             match pack.load_schema() {
                 Ok(Some(schema)) => {
-                    println!(
+                    lint.say(format_args!(
                         "  {} {} is valid",
                         style("✓").green(),
-                        schema_path
-                            .file_name()
-                            .map(|n| n.to_string_lossy())
-                            .unwrap_or_else(|| "schema".into())
-                    );
+                        schema_file
+                    ));
                     match SchemaValidator::new(schema) {
                         Ok(validator) => {
                             schema_validator = Some(validator);
                         }
                         Err(e) => {
-                            println!("  {} Schema compilation failed: {}", style("✗").red(), e);
-                            errors += 1;
+                            lint.say(format_args!(
+                                "  {} Schema compilation failed: {}",
+                                style("✗").red(),
+                                e
+                            ));
+                            lint.report(LintFinding::error(
+                                "schema",
+                                &schema_file,
+                                format!("Schema compilation failed: {}", e),
+                            ));
                         }
                     }
                 }
                 Ok(None) => {}
                 Err(e) => {
-                    println!("  {} Failed to load schema: {}", style("✗").red(), e);
-                    errors += 1;
+                    lint.say(format_args!(
+                        "  {} Failed to load schema: {}",
+                        style("✗").red(),
+                        e
+                    ));
+                    lint.report(LintFinding::error(
+                        "schema",
+                        &schema_file,
+                        format!("Failed to load schema: {}", e),
+                    ));
                 }
             }
         } else {
-            println!("  {} No schema file found (optional)", style("⚠").yellow());
-            warnings += 1;
+            lint.say(format_args!(
+                "  {} No schema file found (optional)",
+                style("⚠").yellow()
+            ));
+            lint.report(LintFinding::warning(
+                "no_schema",
+                "values.schema.yaml",
+                "No schema file found",
+            ));
         }
     }
 
@@ -129,102 +312,397 @@ pub fn run(path: &Path, strict: bool, skip_schema: bool) -> Result<()> {
 
         // Validate values against schema if present
         if let Some(ref validator) = schema_validator {
-            println!();
-            println!("{} Validating values against schema...", style("→").blue());
+            lint.say("");
+            lint.say(format_args!(
+                "{} Validating values against schema...",
+                style("→").blue()
+            ));
 
             let result = validator.validate(values.inner());
             if result.is_valid {
-                println!("  {} Values match schema", style("✓").green());
+                lint.say(format_args!("  {} Values match schema", style("✓").green()));
             } else {
-                println!("  {} Values do not match schema:", style("✗").red());
+                lint.say(format_args!(
+                    "  {} Values do not match schema:",
+                    style("✗").red()
+                ));
                 for err in &result.errors {
-                    println!("    - {}: {}", err.path, err.message);
+                    lint.say(format_args!("    - {}: {}", err.path, err.message));
+                    lint.report(LintFinding::error(
+                        "values_schema",
+                        "values.yaml",
+                        format!("{}: {}", err.path, err.message),
+                    ));
                 }
-                errors += result.errors.len();
             }
         }
 
-        println!();
-        println!("{} Testing template rendering...", style("→").blue());
+        lint.say("");
+        lint.say(format_args!(
+            "{} Testing template rendering...",
+            style("→").blue()
+        ));
 
         let release = ReleaseInfo::for_install("RELEASE-NAME", "NAMESPACE");
+        let default_values = values.inner().clone();
         let context = TemplateContext::new(values, release, &pack.pack.metadata);
 
         let engine = Engine::builder()
             .strict(strict || pack.pack.engine.strict)
             .build();
 
+        // Library dependencies lend their templates to the pack
+        let renderer = PackRenderer::new(engine);
+        let libraries: Vec<(String, LoadedPack)> = renderer
+            .discover_subcharts(pack, &default_values)
+            .subcharts
+            .into_iter()
+            .filter(|subchart| subchart.enabled && subchart.pack.pack.is_library())
+            .map(|subchart| (subchart.name, subchart.pack))
+            .collect();
+        let libraries: Vec<(&str, &LoadedPack)> = libraries
+            .iter()
+            .map(|(name, library)| (name.as_str(), library))
+            .collect();
+
         // Use the error-collecting render method
-        let result = engine.render_pack_collect_errors(pack, &context);
+        let result = renderer
+            .engine()
+            .render_pack_with_libraries(pack, &context, &libraries);
 
         if result.is_success() {
-            println!(
+            lint.say(format_args!(
                 "  {} Rendered {} template(s) successfully",
                 style("✓").green(),
                 result.manifests.len()
-            );
+            ));
 
             // Validate YAML output
             for (name, content) in &result.manifests {
                 match serde_yaml::from_str::<serde_yaml::Value>(content) {
                     Ok(_) => {
-                        println!("    {} {} produces valid YAML", style("✓").green(), name);
+                        lint.say(format_args!(
+                            "    {} {} produces valid YAML",
+                            style("✓").green(),
+                            name
+                        ));
                     }
                     Err(e) => {
-                        println!(
+                        lint.say(format_args!(
                             "    {} {} produces invalid YAML: {}",
                             style("✗").red(),
                             name,
                             e
+                        ));
+                        lint.report(
+                            LintFinding::error(
+                                "invalid_yaml",
+                                &template_path(name),
+                                format!("produces invalid YAML: {}", e),
+                            )
+                            .at_line(e.location().map(|l| l.line())),
                         );
-                        errors += 1;
                     }
                 }
             }
-        } else {
+
+            for issue in &result.report.issues {
+                lint.say(format_args!("  {} {}", style("⚠").yellow(), issue.message));
+            }
+        } else if !lint.quiet {
             // Display comprehensive error report
             display_render_report(&result.report);
-            errors += result.report.total_errors;
         }
 
+        for (name, template_errors) in &result.report.errors_by_template {
+            for error in template_errors {
+                lint.report(
+                    LintFinding::error(
+                        error.kind.to_code_string(),
+                        &template_path(name),
+                        error.message.clone(),
+                    )
+                    .at_line(error.line()),
+                );
+            }
+        }
+        for issue in &result.report.issues {
+            let severity = match issue.severity {
+                IssueSeverity::Warning => LintSeverity::Warning,
+                IssueSeverity::Error => LintSeverity::Error,
+            };
+            lint.report(LintFinding::new(
+                severity,
+                &issue.category,
+                "templates",
+                &issue.message,
+            ));
+        }
+
+        lint_values_coverage(
+            &mut lint,
+            pack,
+            &default_values,
+            schema_validator.as_ref().map(|v| v.schema()),
+        );
+        lint_determinism(&mut lint, pack, secret_state.as_ref(), strict);
+
         // CRD Linting (Phase 3)
         if result.is_success() {
-            let (crd_errors, crd_warnings) = lint_crds_in_pack(pack, &result.manifests);
-            errors += crd_errors;
-            warnings += crd_warnings;
+            lint_crds_in_pack(&mut lint, pack, &result.manifests);
         }
     }
 
-    // Summary
-    println!();
-    if errors > 0 {
-        println!(
-            "{} Linting failed with {} error(s) and {} warning(s)",
-            style("✗").red().bold(),
-            errors,
-            warnings
-        );
-        return Err(CliError::lint_failed(errors, warnings));
-    } else if warnings > 0 {
-        println!(
-            "{} Linting passed with {} warning(s)",
-            style("⚠").yellow().bold(),
-            warnings
-        );
+    let errors = lint.count(LintSeverity::Error);
+    let warnings = lint.count(LintSeverity::Warning);
+    let passed = errors == 0 && !(strict && warnings > 0);
+
+    if format == LintFormat::Json {
+        let report = LintReport {
+            passed,
+            findings: &lint.findings,
+        };
+        let json = serde_json::to_string_pretty(&report).into_diagnostic()?;
+        println!("{}", json);
     } else {
-        println!("{} Linting passed!", style("✓").green().bold());
+        // Summary
+        println!();
+        if !passed {
+            println!(
+                "{} Linting failed with {} error(s) and {} warning(s)",
+                style("✗").red().bold(),
+                errors,
+                warnings
+            );
+        } else if warnings > 0 {
+            println!(
+                "{} Linting passed with {} warning(s)",
+                style("⚠").yellow().bold(),
+                warnings
+            );
+        } else {
+            println!("{} Linting passed!", style("✓").green().bold());
+        }
     }
 
-    Ok(())
+    if passed {
+        Ok(())
+    } else {
+        Err(CliError::lint_failed(errors, warnings))
+    }
 }
 
-/// Lint CRDs in the pack
+/// Path of a rendered template relative to the pack root
+fn template_path(name: &str) -> String {
+    if name.starts_with('<') {
+        name.to_string()
+    } else {
+        format!("templates/{}", name)
+    }
+}
+
+/// Warn about `values.<path>` reads that neither values.yaml nor the schema define
+///
+/// Such reads render as empty strings instead of failing, so a renamed or
+/// removed value silently disappears from the output.
+fn lint_values_coverage(
+    lint: &mut Linter,
+    pack: &LoadedPack,
+    values: &JsonValue,
+    schema: Option<&Schema>,
+) {
+    const RULE: &str = "values-coverage";
+
+    lint.say("");
+    lint.say(format_args!(
+        "{} Checking referenced values...",
+        style("→").blue()
+    ));
+
+    let mut missing = 0;
+    for file in pack.template_files().unwrap_or_default() {
+        let Ok(source) = std::fs::read_to_string(&file) else {
+            continue;
+        };
+        let name = file
+            .strip_prefix(&pack.templates_dir)
+            .unwrap_or(&file)
+            .to_string_lossy()
+            .into_owned();
+
+        // One finding per path and template is enough to locate it
+        let mut seen = std::collections::HashSet::new();
+        for reference in value_references(&source) {
+            if values_cover(values, &reference.path)
+                || schema.is_some_and(|s| s.declares(&reference.path))
+                || is_suppressed(&source, reference.line, RULE)
+                || !seen.insert(reference.dotted())
+            {
+                continue;
+            }
+
+            lint.say(format_args!(
+                "  {} {}:{}: values.{} is not defined in values.yaml or the schema",
+                style("⚠").yellow(),
+                name,
+                reference.line,
+                reference.dotted()
+            ));
+            lint.report(
+                LintFinding::warning(
+                    RULE,
+                    &template_path(&name),
+                    format!(
+                        "values.{} is not defined in values.yaml or the schema",
+                        reference.dotted()
+                    ),
+                )
+                .at_line(Some(reference.line)),
+            );
+            missing += 1;
+        }
+    }
+
+    if missing == 0 {
+        lint.say(format_args!(
+            "  {} All referenced values are defined",
+            style("✓").green()
+        ));
+    }
+}
+
+/// Flag template functions that return a different value on every render
+///
+/// GitOps tools diff the rendered output against the cluster, so a manifest
+/// that changes on each render is never in sync. `generate_secret()` is only
+/// stable when a persisted secret state already holds the secret.
+fn lint_determinism(
+    lint: &mut Linter,
+    pack: &LoadedPack,
+    secret_state: Option<&SecretState>,
+    strict: bool,
+) {
+    const RULE: &str = "non-deterministic";
+    let severity = if strict {
+        LintSeverity::Error
+    } else {
+        LintSeverity::Warning
+    };
+
+    lint.say("");
+    lint.say(format_args!(
+        "{} Checking render determinism...",
+        style("→").blue()
+    ));
+
+    let mut found = 0;
+    for file in pack.template_files().unwrap_or_default() {
+        let Ok(source) = std::fs::read_to_string(&file) else {
+            continue;
+        };
+        let name = file
+            .strip_prefix(&pack.templates_dir)
+            .unwrap_or(&file)
+            .to_string_lossy()
+            .into_owned();
+
+        for call in function_calls(&source) {
+            let message = match call.name.as_str() {
+                "now" => "now() returns the render time, so every render produces a \
+                          different manifest; pass a timestamp through values instead"
+                    .to_string(),
+                "uuidv4" => "uuidv4() returns a new random UUID on every render; derive the \
+                             identifier from release or values data (e.g. `| sha256`) instead"
+                    .to_string(),
+                "generate_secret" => {
+                    let backed = secret_state.is_some_and(|state| {
+                        call.first_arg
+                            .as_deref()
+                            .is_some_and(|secret| state.get(secret).is_some())
+                    });
+                    if backed {
+                        continue;
+                    }
+                    let secret = call.first_arg.as_deref().unwrap_or("<dynamic>");
+                    format!(
+                        "generate_secret(\"{}\") has no persisted secret state, so each render \
+                         generates a new value; pass --secret-state or reference an existing Secret",
+                        secret
+                    )
+                }
+                _ => continue,
+            };
+            if is_suppressed(&source, call.line, RULE) {
+                continue;
+            }
+
+            lint.say(format_args!(
+                "  {} {}:{}: {}",
+                if strict {
+                    style("✗").red()
+                } else {
+                    style("⚠").yellow()
+                },
+                name,
+                call.line,
+                message
+            ));
+            lint.report(
+                LintFinding::new(severity, RULE, template_path(&name), message)
+                    .at_line(Some(call.line)),
+            );
+            found += 1;
+        }
+    }
+
+    if found == 0 {
+        lint.say(format_args!(
+            "  {} No non-deterministic functions used",
+            style("✓").green()
+        ));
+    }
+}
+
+/// Whether the default values define a path
 ///
-/// Returns (error_count, warning_count)
-fn lint_crds_in_pack(pack: &LoadedPack, manifests: &IndexMap<String, String>) -> (usize, usize) {
-    let mut errors = 0;
-    let mut warnings = 0;
+/// Below the top level, empty maps and non-map values accept any key: packs
+/// use `{}` and `null` placeholders for structures users fill in.
+fn values_cover(values: &JsonValue, path: &[String]) -> bool {
+    let mut current = values;
+    for (depth, key) in path.iter().enumerate() {
+        match current {
+            JsonValue::Object(map) => match map.get(key) {
+                Some(value) => current = value,
+                None => return depth > 0 && map.is_empty(),
+            },
+            _ => return depth > 0,
+        }
+    }
+    true
+}
+
+/// Whether a `sherpack-lint-ignore: <rule>` comment on the line, or the line
+/// above, silences a finding
+fn is_suppressed(source: &str, line: usize, rule: &str) -> bool {
+    const MARKER: &str = "sherpack-lint-ignore:";
+
+    let lines: Vec<&str> = source.lines().collect();
+    let candidates = [line.checked_sub(2), line.checked_sub(1)];
+
+    candidates
+        .into_iter()
+        .flatten()
+        .filter_map(|index| lines.get(index))
+        .filter_map(|text| text.split_once(MARKER).map(|(_, rules)| rules))
+        .any(|rules| {
+            rules
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .any(|r| r == rule)
+        })
+}
 
+/// Lint CRDs in the pack
+fn lint_crds_in_pack(lint: &mut Linter, pack: &LoadedPack, manifests: &IndexMap<String, String>) {
     // Collect CRDs from crds/ directory
     let crds_dir_crds: Vec<DetectedCrd> = match pack.load_crds() {
         Ok(crds) => crds
@@ -253,55 +731,53 @@ fn lint_crds_in_pack(pack: &LoadedPack, manifests: &IndexMap<String, String>) ->
 
     // Skip if no CRDs found
     if crds_dir_crds.is_empty() && templated_files.is_empty() && templates_crds.is_empty() {
-        return (0, 0);
+        return;
     }
 
-    println!();
-    println!("{} Checking CRD configuration...", style("→").blue());
+    lint.say("");
+    lint.say(format_args!(
+        "{} Checking CRD configuration...",
+        style("→").blue()
+    ));
 
     // Show CRDs found
     let total_crds = crds_dir_crds.len() + templates_crds.len();
     if total_crds > 0 {
-        println!("  {} Found {} CRD(s)", style("✓").green(), total_crds);
-
-        for crd in &crds_dir_crds {
-            println!(
+        lint.say(format_args!(
+            "  {} Found {} CRD(s)",
+            style("✓").green(),
+            total_crds
+        ));
+
+        for crd in crds_dir_crds.iter().chain(&templates_crds) {
+            lint.say(format_args!(
                 "    {} {} ({})",
                 style("•").dim(),
                 crd.name,
                 crd.location.description()
-            );
-        }
-
-        for crd in &templates_crds {
-            println!(
-                "    {} {} ({})",
-                style("•").dim(),
-                crd.name,
-                crd.location.description()
-            );
+            ));
         }
     }
 
     // Show templated CRD files
     if !templated_files.is_empty() {
-        println!(
+        lint.say(format_args!(
             "  {} Found {} templated CRD file(s) in crds/",
-            style("ℹ").blue(),
+            style("✗").red(),
             templated_files.len()
-        );
+        ));
     }
 
     // Run lint checks
     let lint_warnings = lint_crds(&crds_dir_crds, &templates_crds, &templated_files);
 
     if lint_warnings.is_empty() {
-        println!("  {} No CRD issues found", style("✓").green());
-        return (0, 0);
+        lint.say(format_args!("  {} No CRD issues found", style("✓").green()));
+        return;
     }
 
-    println!();
-    println!("{} CRD Recommendations:", style("→").blue());
+    lint.say("");
+    lint.say(format_args!("{} CRD Recommendations:", style("→").blue()));
 
     for warning in &lint_warnings {
         let icon = match warning.severity() {
@@ -310,23 +786,26 @@ fn lint_crds_in_pack(pack: &LoadedPack, manifests: &IndexMap<String, String>) ->
             LintSeverity::Info => style("ℹ").blue(),
         };
 
-        // Count by severity
-        match warning.severity() {
-            LintSeverity::Error => errors += 1,
-            LintSeverity::Warning => warnings += 1,
-            LintSeverity::Info => {} // Info doesn't count as error or warning
-        }
-
-        println!();
-        println!("  {} {}", icon, warning.path);
+        lint.say("");
+        lint.say(format_args!("  {} {}", icon, warning.path));
         if let Some(name) = &warning.crd_name {
-            println!("    CRD: {}", name);
+            lint.say(format_args!("    CRD: {}", name));
         }
-        println!("    {}", warning.message);
+        lint.say(format_args!("    {}", warning.message));
         if let Some(suggestion) = &warning.suggestion {
-            println!("    {} {}", style("Tip:").dim(), suggestion);
+            lint.say(format_args!("    {} {}", style("Tip:").dim(), suggestion));
         }
-    }
 
-    (errors, warnings)
+        // Info findings are reported but never fail the lint
+        let path = Path::new(&warning.path);
+        lint.report(LintFinding::new(
+            warning.severity(),
+            warning.rule(),
+            path.strip_prefix(&pack.root)
+                .unwrap_or(path)
+                .display()
+                .to_string(),
+            &warning.message,
+        ));
+    }
 }
@@ -2,15 +2,19 @@
 
 use console::style;
 use miette::IntoDiagnostic;
-use sherpack_kube::{
-    KubeClient,
-    storage::{FileDriver, StorageConfig},
-};
+use sherpack_kube::storage::{FileDriver, ReleaseFilter, StorageConfig};
 
 use crate::error::Result;
+use crate::util::KubeTarget;
 
 /// Run the list command
-pub async fn run(namespace: Option<&str>, all_namespaces: bool, output_json: bool) -> Result<()> {
+pub async fn run(
+    namespace: Option<&str>,
+    all_namespaces: bool,
+    filter: &ReleaseFilter,
+    output_json: bool,
+    kube: &KubeTarget,
+) -> Result<()> {
     // Create storage driver
     let storage_path = dirs::data_dir()
         .unwrap_or_else(|| std::path::PathBuf::from("."))
@@ -20,11 +24,11 @@ pub async fn run(namespace: Option<&str>, all_namespaces: bool, output_json: boo
     let storage = FileDriver::new(storage_path, StorageConfig::default()).into_diagnostic()?;
 
     // Create client
-    let client = KubeClient::new(storage).await.into_diagnostic()?;
+    let client = kube.client(storage).await?;
 
     // List releases
     let releases = client
-        .list(namespace, all_namespaces)
+        .list_filtered(namespace, all_namespaces, filter)
         .await
         .into_diagnostic()?;
 
@@ -60,7 +64,7 @@ pub async fn run(namespace: Option<&str>, all_namespaces: bool, output_json: boo
     for release in releases {
         let status_style = match release.state.status_name() {
             "deployed" => style(release.state.status_name()).green(),
-            "failed" => style(release.state.status_name()).red(),
+            "failed" | "rolled-back" => style(release.state.status_name()).red(),
             s if s.starts_with("pending") => style(release.state.status_name()).yellow(),
             _ => style(release.state.status_name()).dim(),
         };
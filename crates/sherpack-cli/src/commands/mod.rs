@@ -1,5 +1,6 @@
 //! CLI commands
 
+pub mod completion;
 pub mod convert;
 pub mod create;
 pub mod inspect;
@@ -14,11 +15,14 @@ pub mod validate;
 pub mod verify;
 
 // Phase 4 - Kubernetes deployment commands
+pub mod diff;
+pub mod get;
 pub mod history;
 pub mod install;
 pub mod list;
 pub mod recover;
 pub mod rollback;
+pub mod secret;
 pub mod status;
 pub mod test;
 pub mod uninstall;
@@ -1,14 +1,39 @@
 //! Package command - create distributable archives
 
+use clap::ValueEnum;
 use console::style;
 use miette::{IntoDiagnostic, Result};
-use sherpack_core::{LoadedPack, create_archive, default_archive_name};
+use sherpack_core::{HashAlgorithm, LoadedPack, create_archive_with_hash, default_archive_name};
 use std::path::Path;
 
 use super::signing::sign_archive;
 use crate::util::{format_size, truncate_hash};
 
-pub fn run(path: &Path, output: Option<&Path>, sign_key: Option<&Path>) -> Result<()> {
+/// Checksum algorithm for the MANIFEST file entries
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum HashArg {
+    #[default]
+    Sha256,
+    Sha512,
+    Blake3,
+}
+
+impl From<HashArg> for HashAlgorithm {
+    fn from(arg: HashArg) -> Self {
+        match arg {
+            HashArg::Sha256 => Self::Sha256,
+            HashArg::Sha512 => Self::Sha512,
+            HashArg::Blake3 => Self::Blake3,
+        }
+    }
+}
+
+pub fn run(
+    path: &Path,
+    output: Option<&Path>,
+    sign_key: Option<&Path>,
+    hash: HashArg,
+) -> Result<()> {
     // Load the pack
     let pack = LoadedPack::load(path).into_diagnostic()?;
 
@@ -27,7 +52,8 @@ pub fn run(path: &Path, output: Option<&Path>, sign_key: Option<&Path>) -> Resul
         pack.pack.metadata.version
     );
 
-    let created_path = create_archive(&pack, &output_path).into_diagnostic()?;
+    let created_path =
+        create_archive_with_hash(&pack, &output_path, hash.into()).into_diagnostic()?;
 
     // Get file size for display
     let metadata = std::fs::metadata(&created_path).into_diagnostic()?;
@@ -39,6 +65,7 @@ pub fn run(path: &Path, output: Option<&Path>, sign_key: Option<&Path>) -> Resul
         created_path.display()
     );
     println!("  {} {}", style("Size").dim(), size);
+    println!("  {} {}", style("Hash").dim(), HashAlgorithm::from(hash));
 
     // Sign if key provided
     if let Some(key_path) = sign_key {
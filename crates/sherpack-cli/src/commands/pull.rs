@@ -1,17 +1,40 @@
 //! Pull command - download a pack from a repository
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::error::{CliError, Result};
-use sherpack_repo::{CredentialStore, IndexCache, Repository, RepositoryConfig, create_backend};
+use sherpack_repo::{
+    ArchiveCache, BackendOptions, CredentialStore, IndexCache, OciRegistry, Repository,
+    RepositoryConfig, SignatureKey, create_backend,
+};
 
 /// Pull a pack from a repository
+///
+/// Downloaded archives are kept in the archive cache; offline, they are the
+/// only source.
+///
+/// With a `signature_key`, an OCI pack must carry a cosign signature made
+/// with that key, and its archive must then pass the same integrity check as
+/// `sherpack verify`.
 pub async fn run(
     pack_ref: &str,
     version: Option<&str>,
     output: Option<&PathBuf>,
     untar: bool,
+    signature_key: Option<&Path>,
+    offline: bool,
 ) -> Result<()> {
+    let signature_key = signature_key
+        .map(|path| {
+            if offline {
+                return Err(CliError::input(
+                    "Signature verification needs registry access and cannot run offline",
+                ));
+            }
+            SignatureKey::from_file(path).map_err(|e| CliError::input(e.to_string()))
+        })
+        .transpose()?;
+
     // Parse pack reference: [repo/]name[:version] or oci://registry/repo:tag
     let (repo_name, pack_name, pack_version) = parse_pack_ref(pack_ref, version)?;
 
@@ -50,9 +73,20 @@ pub async fn run(
         }
     };
 
-    let credentials = cred_store.get(&repo.name).and_then(|c| c.resolve().ok());
+    if signature_key.is_some() && !repo.is_oci() {
+        return Err(CliError::input(format!(
+            "Signature verification is only supported for OCI registries, '{}' is not one",
+            repo.name
+        )));
+    }
+
+    let credentials = cred_store.resolve_for(&repo.name, &repo.url);
 
-    let mut backend = create_backend(repo.clone(), credentials)
+    let options = BackendOptions {
+        http: config.http,
+        offline,
+    };
+    let mut backend = create_backend(repo.clone(), credentials.clone(), options)
         .await
         .map_err(|e| CliError::internal(e.to_string()))?;
 
@@ -75,10 +109,28 @@ pub async fn run(
     );
 
     // Download
-    let data = backend
-        .download(&pack_entry.name, &pack_entry.version)
-        .await
-        .map_err(|e| CliError::internal(e.to_string()))?;
+    let data = if let Some(key) = &signature_key {
+        let registry = OciRegistry::new(repo.clone(), credentials)
+            .map_err(|e| CliError::internal(e.to_string()))?;
+        let data = registry
+            .pull_verified(&pack_entry.name, &pack_entry.version, key)
+            .await
+            .map_err(|e| CliError::internal(e.to_string()))?;
+        println!("Signature verified");
+
+        verify_integrity(&data, &pack_entry.name, &pack_entry.version)?;
+        println!("Integrity verified");
+        data
+    } else {
+        backend
+            .download(&pack_entry.name, &pack_entry.version)
+            .await
+            .map_err(|e| CliError::internal(e.to_string()))?
+    };
+
+    if !offline {
+        cache_archive(&repo.url, &pack_entry.name, &pack_entry.version, &data);
+    }
 
     // Save to file
     let output_path = if let Some(output) = output {
@@ -94,6 +146,10 @@ pub async fn run(
         std::fs::create_dir_all(&output_path)?;
         extract_archive(&data, &output_path)?;
         println!("Extracted to {}/", output_path.display());
+        if let Some(chart) = helm_chart_dir(&output_path) {
+            println!("This is a Helm chart. To convert it:");
+            println!("  sherpack convert {}", chart.display());
+        }
     } else {
         // Save archive
         std::fs::write(&output_path, &data)?;
@@ -103,6 +159,57 @@ pub async fn run(
     Ok(())
 }
 
+/// Directory of a Helm chart (no Pack.yaml) extracted under `dir`
+///
+/// Chart archives usually hold a single top-level `<name>/` directory.
+fn helm_chart_dir(dir: &Path) -> Option<PathBuf> {
+    let is_chart = |d: &Path| d.join("Chart.yaml").is_file() && !d.join("Pack.yaml").is_file();
+    if is_chart(dir) {
+        return Some(dir.to_path_buf());
+    }
+    std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .find(|path| path.is_dir() && is_chart(path))
+}
+
+/// Keep a downloaded archive for offline use
+///
+/// A failure only costs offline availability, so it is logged, not returned.
+pub(crate) fn cache_archive(repo_url: &str, name: &str, version: &str, data: &[u8]) {
+    if let Err(e) = ArchiveCache::open().and_then(|cache| cache.put(repo_url, name, version, data))
+    {
+        tracing::debug!("Could not cache archive {}-{}: {}", name, version, e);
+    }
+}
+
+/// Check the archive's file checksums against its manifest
+fn verify_integrity(data: &[u8], name: &str, version: &str) -> Result<()> {
+    let path = std::env::temp_dir().join(format!(
+        "sherpack-pull-{}-{}-{}.tgz",
+        name,
+        version,
+        std::process::id()
+    ));
+    std::fs::write(&path, data)?;
+    let result = sherpack_core::verify_archive(&path);
+    let _ = std::fs::remove_file(&path);
+
+    let result =
+        result.map_err(|e| CliError::internal(format!("Integrity check failed: {}", e)))?;
+    if !result.valid {
+        let mut files: Vec<&str> = result.mismatched.iter().map(|m| m.path.as_str()).collect();
+        files.extend(result.missing.iter().map(String::as_str));
+        return Err(CliError::internal(format!(
+            "Integrity check failed for {}-{}: {}",
+            name,
+            version,
+            files.join(", ")
+        )));
+    }
+    Ok(())
+}
+
 /// Parse pack reference into (repo, name, version)
 fn parse_pack_ref(
     pack_ref: &str,
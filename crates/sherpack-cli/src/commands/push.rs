@@ -3,10 +3,13 @@
 use std::path::Path;
 
 use crate::error::{CliError, Result};
-use sherpack_repo::{CredentialStore, OciRegistry, Repository};
+use sherpack_repo::{CredentialStore, OciRegistry, PackMediaType, Repository};
 
 /// Push a pack archive to an OCI registry
-pub async fn run(archive: &Path, destination: &str) -> Result<()> {
+///
+/// With [`PackMediaType::Sherpack`], the pack is published under Sherpack's
+/// own media types instead of Helm's chart media types.
+pub async fn run(archive: &Path, destination: &str, media_type: PackMediaType) -> Result<()> {
     // Validate archive exists
     if !archive.exists() {
         return Err(CliError::input(format!(
@@ -24,7 +27,12 @@ pub async fn run(archive: &Path, destination: &str) -> Result<()> {
 
     let (base_url, name, tag) = parse_oci_destination(destination)?;
 
-    println!("Pushing {} to {}...", archive.display(), destination);
+    println!(
+        "Pushing {} to {} ({} media types)...",
+        archive.display(),
+        destination,
+        media_type
+    );
 
     // Read archive
     let data = std::fs::read(archive)?;
@@ -39,7 +47,7 @@ pub async fn run(archive: &Path, destination: &str) -> Result<()> {
         .next()
         .unwrap_or("");
 
-    let credentials = cred_store.get(registry).and_then(|c| c.resolve().ok());
+    let credentials = cred_store.resolve_for(registry, &base_url);
 
     // Create OCI client
     let repo = Repository::new("_push", &base_url).map_err(|e| CliError::input(e.to_string()))?;
@@ -48,7 +56,7 @@ pub async fn run(archive: &Path, destination: &str) -> Result<()> {
 
     // Push
     let manifest_url = oci
-        .push(&name, &tag, &data)
+        .push(&name, &tag, &data, media_type)
         .await
         .map_err(|e| CliError::internal(e.to_string()))?;
 
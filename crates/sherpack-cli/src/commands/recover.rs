@@ -1,16 +1,17 @@
-//! Recover command - recover a stuck release
+//! Recover command - resume a stuck or failed release
 
 use console::style;
 use miette::IntoDiagnostic;
-use sherpack_kube::{
-    KubeClient,
-    storage::{FileDriver, StorageConfig},
-};
+use sherpack_kube::storage::{FileDriver, StorageConfig};
 
 use crate::error::Result;
+use crate::util::KubeTarget;
 
 /// Run the recover command
-pub async fn run(name: &str, namespace: &str) -> Result<()> {
+///
+/// Re-applies the release and finishes its operation unless `state_only`,
+/// which only clears the stuck state.
+pub async fn run(name: &str, namespace: &str, state_only: bool, kube: &KubeTarget) -> Result<()> {
     println!(
         "{} Attempting to recover release {}",
         style("→").blue().bold(),
@@ -26,20 +27,58 @@ pub async fn run(name: &str, namespace: &str) -> Result<()> {
     let storage = FileDriver::new(storage_path, StorageConfig::default()).into_diagnostic()?;
 
     // Create client
-    let client = KubeClient::new(storage).await.into_diagnostic()?;
+    let client = kube.client(storage).await?;
 
-    // Attempt recovery
-    let release = client.recover(namespace, name).await.into_diagnostic()?;
+    if state_only {
+        let release = client.recover(namespace, name).await.into_diagnostic()?;
+
+        println!(
+            "{} Successfully recovered {} (now marked as {})",
+            style("✓").green().bold(),
+            style(&release.name).cyan(),
+            style(release.state.status_name()).yellow()
+        );
+
+        println!("\nYou can now retry the operation:");
+        println!("  sherpack upgrade {} <pack>", name);
+
+        return Ok(());
+    }
+
+    let report = client.resume(namespace, name).await.into_diagnostic()?;
+
+    if report.was_noop() {
+        println!(
+            "{} Release {} is already deployed, nothing to resume",
+            style("✓").green().bold(),
+            style(name).cyan()
+        );
+        return Ok(());
+    }
+
+    for resource in &report.reapplied {
+        println!("  {} {} (re-applied)", style("+").green(), resource);
+    }
+    for resource in &report.already_present {
+        println!("  {} {} (already present)", style("=").dim(), resource);
+    }
+    for hook in &report.hooks {
+        let symbol = if hook.success {
+            style("✓").green()
+        } else {
+            style("✗").red()
+        };
+        println!("  {} Hook {} [{}]", symbol, hook.name, hook.phase);
+    }
 
     println!(
-        "{} Successfully recovered {} (now marked as {})",
+        "{} Resumed {} (revision {}): {} re-applied, {} already present",
         style("✓").green().bold(),
-        style(&release.name).cyan(),
-        style(release.state.status_name()).yellow()
+        style(&report.release.name).cyan(),
+        style(report.release.version).yellow(),
+        report.reapplied.len(),
+        report.already_present.len()
     );
 
-    println!("\nYou can now retry the operation:");
-    println!("  sherpack upgrade {} <pack>", name);
-
     Ok(())
 }
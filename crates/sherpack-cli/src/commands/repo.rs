@@ -1,11 +1,12 @@
 //! Repository management commands
 
-use std::path::{Path, PathBuf};
+use std::path::Path;
 
 use crate::error::{CliError, Result};
 use sherpack_repo::{
-    CredentialStore, Credentials, IndexCache, IndexDependency, Maintainer, PackEntry, Repository,
-    RepositoryConfig, RepositoryIndex, RepositoryType, create_backend,
+    BackendOptions, CredentialStore, Credentials, HttpRepository, HttpSettings, IndexCache,
+    IndexRefresh, Login, PackEntry, Repository, RepositoryConfig, RepositoryIndex, RepositoryType,
+    ResolvedCredentials, create_backend, login_scope, registry_login,
 };
 
 /// Add a new repository
@@ -100,12 +101,14 @@ pub async fn list(show_auth: bool) -> Result<()> {
 
         let auth_info = if show_auth {
             if cred_store.has(&repo.name) {
-                " (authenticated)"
+                " (authenticated)".to_string()
+            } else if let Some((scope, _)) = cred_store.login_for(&repo.url) {
+                format!(" (logged in to {})", scope)
             } else {
-                " (public)"
+                " (public)".to_string()
             }
         } else {
-            ""
+            String::new()
         };
 
         println!(
@@ -118,10 +121,16 @@ pub async fn list(show_auth: bool) -> Result<()> {
 }
 
 /// Update repository index
-pub async fn update(name: Option<&str>) -> Result<()> {
+pub async fn update(name: Option<&str>, offline: bool) -> Result<()> {
+    if offline {
+        return Err(CliError::input(
+            "Cannot update repositories in offline mode (--offline / SHERPACK_OFFLINE)",
+        ));
+    }
+
     let config = RepositoryConfig::load().map_err(|e| CliError::internal(e.to_string()))?;
     let cred_store = CredentialStore::load().unwrap_or_default();
-    let mut cache = IndexCache::open().map_err(|e| CliError::internal(e.to_string()))?;
+    let mut cache = open_index_cache(&config)?;
 
     let repos_to_update: Vec<_> = if let Some(name) = name {
         let repo = config
@@ -141,32 +150,24 @@ pub async fn update(name: Option<&str>) -> Result<()> {
         print!("Updating {}... ", repo.name);
 
         // Get credentials if available
-        let credentials = cred_store.get(&repo.name).and_then(|c| c.resolve().ok());
-
-        // Create backend and fetch index
-        match create_backend(repo.clone(), credentials).await {
-            Ok(mut backend) => match backend.refresh().await {
-                Ok(()) => {
-                    // For HTTP repos, cache the index
-                    if repo.repo_type == RepositoryType::Http {
-                        if let Ok(packs) = backend.list().await {
-                            cache
-                                .upsert_repository(&repo.name, &repo.url, "http", None)
-                                .ok();
-                            cache.add_packs(&repo.name, &packs).ok();
-                            println!("done ({} packs)", packs.len());
-                        } else {
-                            println!("done (index cached)");
-                        }
-                    } else {
-                        println!("done");
-                    }
-                }
-                Err(e) => {
-                    println!("failed");
-                    eprintln!("  Error: {}", e);
-                }
-            },
+        let credentials = cred_store.resolve_for(&repo.name, &repo.url);
+
+        // HTTP repos are cached; other backends only check they respond
+        let result = if repo.repo_type == RepositoryType::Http {
+            update_http(repo, credentials, config.http, &mut cache).await
+        } else {
+            let options = BackendOptions {
+                http: config.http,
+                offline: false,
+            };
+            match create_backend(repo.clone(), credentials, options).await {
+                Ok(mut backend) => backend.refresh().await.map(|()| "done".to_string()),
+                Err(e) => Err(e),
+            }
+        };
+
+        match result {
+            Ok(summary) => println!("{}", summary),
             Err(e) => {
                 println!("failed");
                 eprintln!("  Error: {}", e);
@@ -177,6 +178,52 @@ pub async fn update(name: Option<&str>) -> Result<()> {
     Ok(())
 }
 
+/// Open the index cache with the TTL from the repository config
+pub(crate) fn open_index_cache(config: &RepositoryConfig) -> Result<IndexCache> {
+    IndexCache::open()
+        .map(|cache| cache.with_ttl(config.cache.ttl()))
+        .map_err(|e| CliError::internal(e.to_string()))
+}
+
+/// Refresh the cached index of an HTTP repository
+///
+/// The request is conditional on the validators stored with the cache, so
+/// an unchanged index is neither downloaded nor parsed again.
+pub(crate) async fn update_http(
+    repo: &Repository,
+    credentials: Option<ResolvedCredentials>,
+    settings: HttpSettings,
+    cache: &mut IndexCache,
+) -> sherpack_repo::Result<String> {
+    let validators = cache.validators(&repo.name, &repo.url).unwrap_or_default();
+    let mut http = HttpRepository::with_settings(repo.clone(), credentials, settings)?;
+
+    match http.refresh_index(&validators).await? {
+        IndexRefresh::NotModified => {
+            cache.record_not_modified(&repo.name).ok();
+            Ok("done (not modified)".to_string())
+        }
+        IndexRefresh::Updated(validators) => {
+            let packs: Vec<PackEntry> = http
+                .index()
+                .map(|index| {
+                    index
+                        .entries
+                        .keys()
+                        .filter_map(|name| index.get_latest(name).cloned())
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            cache
+                .upsert_repository(&repo.name, &repo.url, "http", Some(&validators))
+                .ok();
+            cache.add_packs(&repo.name, &packs).ok();
+            Ok(format!("done ({} packs)", packs.len()))
+        }
+    }
+}
+
 /// Remove a repository
 pub async fn remove(name: &str) -> Result<()> {
     let mut config = RepositoryConfig::load().map_err(|e| CliError::internal(e.to_string()))?;
@@ -205,11 +252,180 @@ pub async fn remove(name: &str) -> Result<()> {
     Ok(())
 }
 
-/// Generate a repository index.yaml from a directory of *.tgz packs
+/// Log in to a registry or repository URL scope
 ///
-/// Equivalent to `helm repo index`. Walks `dir` for `*.tgz` archives, extracts
-/// `Pack.yaml` from each, computes the SHA256 digest of the archive, and writes
-/// `<dir>/index.yaml`.
+/// Credentials are stored under the normalized scope and used for every
+/// repository below it. OCI registries (`oci://` or a bare host) are
+/// checked with their token exchange first.
+pub async fn login(
+    registry: &str,
+    username: Option<&str>,
+    password_stdin: bool,
+    token: Option<&str>,
+) -> Result<()> {
+    let scope = login_scope(registry);
+    if scope.is_empty() {
+        return Err(CliError::input("Registry must not be empty"));
+    }
+
+    let credentials = match token {
+        Some(token) => Credentials::bearer(token),
+        None => {
+            let username = match username {
+                Some(username) => username.to_string(),
+                None => prompt_line("Username: ")?,
+            };
+            let password = if password_stdin {
+                read_stdin_line()?
+            } else {
+                rpassword::prompt_password("Password: ")?
+            };
+            if username.is_empty() || password.is_empty() {
+                return Err(CliError::input("Username and password must not be empty"));
+            }
+            Credentials::basic(username, password)
+        }
+    };
+
+    let is_http = registry.starts_with("http://") || registry.starts_with("https://");
+    if !is_http {
+        let host = scope.split('/').next().unwrap_or(&scope);
+        let resolved = credentials
+            .resolve()
+            .map_err(|e| CliError::internal(e.to_string()))?;
+        registry_login(&format!("https://{}", host), &resolved)
+            .await
+            .map_err(|e| CliError::input(format!("Login to {} failed: {}", host, e)))?;
+    }
+
+    let mut cred_store = CredentialStore::load().map_err(|e| CliError::internal(e.to_string()))?;
+    cred_store.login(registry, Login { credentials });
+    cred_store
+        .save()
+        .map_err(|e| CliError::internal(e.to_string()))?;
+
+    println!("Login succeeded for {}", scope);
+    Ok(())
+}
+
+/// Remove the credentials stored by `repo login`
+pub async fn logout(registry: &str) -> Result<()> {
+    let mut cred_store = CredentialStore::load().map_err(|e| CliError::internal(e.to_string()))?;
+    let scope = login_scope(registry);
+
+    if cred_store.logout(registry).is_none() {
+        return Err(CliError::input(format!("Not logged in to {}", scope)));
+    }
+    cred_store
+        .save()
+        .map_err(|e| CliError::internal(e.to_string()))?;
+
+    println!("Removed login for {}", scope);
+    Ok(())
+}
+
+fn prompt_line(prompt: &str) -> Result<String> {
+    use std::io::Write;
+
+    eprint!("{}", prompt);
+    std::io::stderr().flush()?;
+    read_stdin_line()
+}
+
+fn read_stdin_line() -> Result<String> {
+    let mut line = String::new();
+    std::io::stdin().read_line(&mut line)?;
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// Show index cache statistics
+pub async fn cache_stats() -> Result<()> {
+    let config = RepositoryConfig::load().map_err(|e| CliError::internal(e.to_string()))?;
+    let cache = open_index_cache(&config)?;
+    let stats = cache
+        .stats()
+        .map_err(|e| CliError::internal(e.to_string()))?;
+
+    let ttl = match stats.ttl {
+        Some(ttl) => format!("{}h", ttl.as_secs() / 3600),
+        None => "never expires".to_string(),
+    };
+
+    println!("Repositories:  {}", stats.repository_count);
+    println!("Packs:         {}", stats.pack_count);
+    println!("Not modified:  {}", stats.not_modified_hits);
+    println!("TTL:           {}", ttl);
+    if let Some(oldest) = stats.oldest_update {
+        println!("Oldest update: {}", oldest.format("%Y-%m-%d %H:%M:%S UTC"));
+    }
+    if !stats.expired.is_empty() {
+        println!("Expired:       {}", stats.expired.join(", "));
+        println!();
+        println!(
+            "Run 'sherpack repo update' to refresh, or 'sherpack repo cache prune' to drop them."
+        );
+    }
+
+    Ok(())
+}
+
+/// Clear the index cache of one repository, or all of it
+pub async fn cache_clear(name: Option<&str>) -> Result<()> {
+    let config = RepositoryConfig::load().map_err(|e| CliError::internal(e.to_string()))?;
+    let mut cache = open_index_cache(&config)?;
+
+    match name {
+        Some(name) => {
+            let cached = cache
+                .get_repository_id(name)
+                .map_err(|e| CliError::internal(e.to_string()))?;
+            if cached.is_none() {
+                return Err(CliError::input(format!(
+                    "Repository '{}' is not in the cache",
+                    name
+                )));
+            }
+            cache
+                .remove_repository(name)
+                .map_err(|e| CliError::internal(e.to_string()))?;
+            println!("Cleared cached index of \"{}\"", name);
+        }
+        None => {
+            cache
+                .clear()
+                .map_err(|e| CliError::internal(e.to_string()))?;
+            println!("Cleared the index cache");
+        }
+    }
+
+    Ok(())
+}
+
+/// Drop the cached indices older than the TTL
+pub async fn cache_prune() -> Result<()> {
+    let config = RepositoryConfig::load().map_err(|e| CliError::internal(e.to_string()))?;
+    let mut cache = open_index_cache(&config)?;
+    let pruned = cache
+        .prune()
+        .map_err(|e| CliError::internal(e.to_string()))?;
+
+    if pruned.is_empty() {
+        println!("No expired indices");
+    } else {
+        println!(
+            "Pruned {} expired index(es): {}",
+            pruned.len(),
+            pruned.join(", ")
+        );
+    }
+
+    Ok(())
+}
+
+/// Generate a repository index.yaml from a directory of packs
+///
+/// Equivalent to `helm repo index`. Indexes the `*.tgz` / `*.tar.gz`
+/// archives in `dir` and writes `<dir>/index.yaml`.
 ///
 /// - `url`: prepended to each archive filename to form the entry URL.
 ///   If absent, only the archive filename is used (relative).
@@ -224,25 +440,17 @@ pub async fn index(dir: &Path, url: Option<&str>, merge: Option<&Path>) -> Resul
         )));
     }
 
-    // 1) Discover *.tgz files
-    let mut archives: Vec<PathBuf> = Vec::new();
-    for entry in std::fs::read_dir(dir).map_err(|e| CliError::internal(e.to_string()))? {
-        let entry = entry.map_err(|e| CliError::internal(e.to_string()))?;
-        let path = entry.path();
-        if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("tgz") {
-            archives.push(path);
-        }
-    }
-    archives.sort();
-
-    if archives.is_empty() {
+    let generated =
+        RepositoryIndex::generate_from_dir(dir, url).map_err(|e| CliError::input(e.to_string()))?;
+    let archives: usize = generated.entries.values().map(Vec::len).sum();
+    if archives == 0 {
         return Err(CliError::input(format!(
             "No *.tgz archives found in {}",
             dir.display()
         )));
     }
 
-    // 2) Optionally start from an existing index
+    // Optionally start from an existing index
     let mut index = match merge {
         Some(path) => {
             let yaml = std::fs::read_to_string(path).map_err(|e| {
@@ -257,33 +465,8 @@ pub async fn index(dir: &Path, url: Option<&str>, merge: Option<&Path>) -> Resul
         }
         None => RepositoryIndex::default(),
     };
+    let added = index.merge_new_versions(generated);
 
-    // 3) Build a set of existing (name, version) so merge doesn't duplicate
-    let mut existing: std::collections::HashSet<(String, String)> =
-        std::collections::HashSet::new();
-    for (name, versions) in &index.entries {
-        for v in versions {
-            existing.insert((name.clone(), v.version.clone()));
-        }
-    }
-
-    // 4) Build entries from each archive
-    let mut added = 0usize;
-    let mut skipped = 0usize;
-    for archive in &archives {
-        let entry = build_pack_entry(archive, url)?;
-        let key = (entry.name.clone(), entry.version.clone());
-        if existing.contains(&key) {
-            skipped += 1;
-            continue;
-        }
-        existing.insert(key);
-        index.add_entry(entry);
-        added += 1;
-    }
-
-    // 5) Refresh generated timestamp and write
-    index.generated = chrono::Utc::now();
     let yaml = serde_yaml::to_string(&index)
         .map_err(|e| CliError::internal(format!("Failed to serialize index: {}", e)))?;
     let out = dir.join("index.yaml");
@@ -294,79 +477,8 @@ pub async fn index(dir: &Path, url: Option<&str>, merge: Option<&Path>) -> Resul
         "Wrote {} ({} new, {} already in merge index, {} archives total)",
         out.display(),
         added,
-        skipped,
-        archives.len()
+        archives - added,
+        archives
     );
     Ok(())
 }
-
-/// Construct a PackEntry by reading Pack.yaml from inside the archive and
-/// hashing the archive bytes.
-fn build_pack_entry(archive: &Path, url_base: Option<&str>) -> Result<PackEntry> {
-    use sha2::Digest;
-
-    // Read Pack.yaml from inside the archive
-    let pack_yaml_bytes = sherpack_core::read_file_from_archive(archive, "Pack.yaml")
-        .map_err(|e| CliError::input(format!("{}: {}", archive.display(), e)))?;
-    let pack: sherpack_core::Pack = serde_yaml::from_slice(&pack_yaml_bytes).map_err(|e| {
-        CliError::input(format!("Invalid Pack.yaml in {}: {}", archive.display(), e))
-    })?;
-
-    // SHA256 of the archive bytes (Helm-compatible digest)
-    let archive_bytes = std::fs::read(archive)
-        .map_err(|e| CliError::internal(format!("Failed to read {}: {}", archive.display(), e)))?;
-    let mut hasher = sha2::Sha256::new();
-    hasher.update(&archive_bytes);
-    let digest = format!("{:x}", hasher.finalize());
-
-    // URL: <base>/<filename> if base is provided, else bare filename
-    let filename = archive
-        .file_name()
-        .and_then(|s| s.to_str())
-        .unwrap_or("")
-        .to_string();
-    let entry_url = match url_base {
-        Some(base) => format!("{}/{}", base.trim_end_matches('/'), filename),
-        None => filename,
-    };
-
-    let m = &pack.metadata;
-    Ok(PackEntry {
-        name: m.name.clone(),
-        version: m.version.to_string(),
-        app_version: m.app_version.clone(),
-        description: m.description.clone(),
-        home: m.home.clone(),
-        icon: m.icon.clone(),
-        sources: m.sources.clone(),
-        keywords: m.keywords.clone(),
-        maintainers: m
-            .maintainers
-            .iter()
-            .map(|mt| Maintainer {
-                name: mt.name.clone(),
-                email: mt.email.clone(),
-                url: mt.url.clone(),
-            })
-            .collect(),
-        urls: vec![entry_url],
-        digest: Some(digest),
-        created: Some(chrono::Utc::now()),
-        deprecated: false,
-        dependencies: pack
-            .dependencies
-            .iter()
-            .map(|d| IndexDependency {
-                name: d.name.clone(),
-                version: d.version.clone(),
-                repository: Some(d.repository.clone()),
-                condition: d.condition.clone(),
-                tags: d.tags.clone(),
-                alias: d.alias.clone(),
-            })
-            .collect(),
-        annotations: m.annotations.clone(),
-        api_version: Some(pack.api_version.clone()),
-        r#type: None,
-    })
-}
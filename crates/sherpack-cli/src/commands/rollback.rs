@@ -3,12 +3,13 @@
 use console::style;
 use miette::IntoDiagnostic;
 use sherpack_kube::{
-    KubeClient, RollbackOptions,
+    RollbackOptions,
     actions::ImmutableStrategy,
     storage::{FileDriver, StorageConfig},
 };
 
 use crate::error::Result;
+use crate::util::KubeTarget;
 
 /// Run the rollback command
 #[allow(clippy::too_many_arguments)]
@@ -23,7 +24,10 @@ pub async fn run(
     dry_run: bool,
     show_diff: bool,
     immutable_strategy: Option<&str>,
+    confirm_stateful_recreation: bool,
     max_history: Option<u32>,
+    debug: bool,
+    kube: &KubeTarget,
 ) -> Result<()> {
     let target = if revision == 0 {
         "previous".to_string()
@@ -47,7 +51,11 @@ pub async fn run(
     let storage = FileDriver::new(storage_path, StorageConfig::default()).into_diagnostic()?;
 
     // Create client
-    let client = KubeClient::new(storage).await.into_diagnostic()?;
+    let client = kube.client(storage).await?;
+
+    if debug {
+        crate::util::print_cluster_preflight(&client).await;
+    }
 
     // Build rollback options
     let mut options = RollbackOptions::new(name, namespace);
@@ -66,6 +74,7 @@ pub async fn run(
     if let Some(strategy) = immutable_strategy {
         options.immutable_strategy = strategy.parse().unwrap_or(ImmutableStrategy::Fail);
     }
+    options.confirm_stateful_recreation = confirm_stateful_recreation;
 
     // Execute rollback
     let release = client.rollback(&options).await.into_diagnostic()?;
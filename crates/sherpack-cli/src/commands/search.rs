@@ -1,19 +1,27 @@
 //! Search command
 
+use crate::commands::repo::{open_index_cache, update_http};
 use crate::error::{CliError, Result};
 use sherpack_repo::{
-    CredentialStore, IndexCache, RepositoryConfig, RepositoryType, create_backend,
+    BackendOptions, CredentialStore, IndexCache, RepositoryConfig, RepositoryType, create_backend,
 };
 
 /// Search for packs across repositories
+///
+/// Cached indices older than the TTL are refreshed first. Offline, only the
+/// local cache is searched, stale or not.
 pub async fn run(
     query: &str,
     repo_name: Option<&str>,
     versions: bool,
     json_output: bool,
+    offline: bool,
 ) -> Result<()> {
+    let config = RepositoryConfig::load().map_err(|e| CliError::internal(e.to_string()))?;
+
     // First try local cache (fast)
-    let cache = IndexCache::open().map_err(|e| CliError::internal(e.to_string()))?;
+    let mut cache = open_index_cache(&config)?;
+    refresh_expired(&config, &mut cache, repo_name, offline).await;
 
     let results = if let Some(repo_name) = repo_name {
         cache
@@ -26,9 +34,16 @@ pub async fn run(
     };
 
     if results.is_empty() {
+        if offline {
+            println!("No packs found matching '{}' in the local cache", query);
+            println!();
+            println!("Run 'sherpack repo update' while online to refresh indices.");
+            return Ok(());
+        }
+
         // Try online search if cache is empty
         println!("No results in local cache. Searching online...");
-        return search_online(query, repo_name).await;
+        return search_online(&config, query, repo_name).await;
     }
 
     if json_output {
@@ -90,8 +105,55 @@ pub async fn run(
     Ok(())
 }
 
-async fn search_online(query: &str, repo_name: Option<&str>) -> Result<()> {
-    let config = RepositoryConfig::load().map_err(|e| CliError::internal(e.to_string()))?;
+/// Refresh the expired cached indices of the searched HTTP repositories
+///
+/// Failures only warn: the stale index is still searched. Offline, nothing
+/// is refreshed and a note says the results may be out of date.
+async fn refresh_expired(
+    config: &RepositoryConfig,
+    cache: &mut IndexCache,
+    repo_name: Option<&str>,
+    offline: bool,
+) {
+    let expired: Vec<_> = cache
+        .expired_repositories()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|name| repo_name.is_none_or(|r| r == name))
+        .filter_map(|name| config.get(&name))
+        .filter(|repo| repo.repo_type == RepositoryType::Http)
+        .collect();
+
+    if expired.is_empty() {
+        return;
+    }
+
+    if offline {
+        let names: Vec<_> = expired.iter().map(|r| r.name.as_str()).collect();
+        eprintln!(
+            "Note: cached index of {} has expired, results may be stale",
+            names.join(", ")
+        );
+        return;
+    }
+
+    let cred_store = CredentialStore::load().unwrap_or_default();
+    for repo in expired {
+        let credentials = cred_store.resolve_for(&repo.name, &repo.url);
+        if let Err(e) = update_http(repo, credentials, config.http, cache).await {
+            eprintln!(
+                "Warning: failed to refresh expired index of {}: {}",
+                repo.name, e
+            );
+        }
+    }
+}
+
+async fn search_online(
+    config: &RepositoryConfig,
+    query: &str,
+    repo_name: Option<&str>,
+) -> Result<()> {
     let cred_store = CredentialStore::load().unwrap_or_default();
 
     let repos: Vec<_> = if let Some(name) = repo_name {
@@ -120,9 +182,13 @@ async fn search_online(query: &str, repo_name: Option<&str>) -> Result<()> {
             continue;
         }
 
-        let credentials = cred_store.get(&repo.name).and_then(|c| c.resolve().ok());
+        let credentials = cred_store.resolve_for(&repo.name, &repo.url);
 
-        match create_backend(repo.clone(), credentials).await {
+        let options = BackendOptions {
+            http: config.http,
+            offline: false,
+        };
+        match create_backend(repo.clone(), credentials, options).await {
             Ok(mut backend) => match backend.search(query).await {
                 Ok(results) => {
                     for pack in results {
@@ -0,0 +1,85 @@
+//! Secret command - manage the secrets generated for a release
+//!
+//! Works on the secret state stored with the release, so no cluster access
+//! is needed. New values reach the cluster on the next upgrade.
+
+use console::style;
+use miette::IntoDiagnostic;
+use sherpack_core::SecretGenerator;
+use sherpack_kube::{
+    KubeError,
+    storage::{FileDriver, StorageConfig, StorageDriver},
+};
+
+use crate::error::{CliError, Result};
+
+/// Rotate a generated secret of a release
+///
+/// The old value is kept as the secret's previous value, so templates can
+/// render both through `previous_secret()` while consumers switch over.
+pub async fn rotate(name: &str, namespace: &str, secret: &str) -> Result<()> {
+    let storage_path = dirs::data_dir()
+        .unwrap_or_else(|| std::path::PathBuf::from("."))
+        .join("sherpack")
+        .join("releases");
+
+    let storage = FileDriver::new(storage_path, StorageConfig::default()).into_diagnostic()?;
+
+    let mut release = storage
+        .get_latest(namespace, name)
+        .await
+        .into_diagnostic()?;
+
+    // Never race an install/upgrade that will persist its own secret state
+    if release.state.is_pending() {
+        return Err(KubeError::ReleaseLocked {
+            name: release.name.clone(),
+            holder: format!(
+                "{} of revision {} by {}",
+                release.state.status_name(),
+                release.version,
+                release.state.owner().unwrap_or("an unknown process")
+            ),
+        })
+        .into_diagnostic()?;
+    }
+
+    let mut generator = SecretGenerator::with_state(std::mem::take(&mut release.secrets));
+    if generator.rotate(secret).into_diagnostic()?.is_none() {
+        let mut known: Vec<_> = generator.state().names().collect();
+        known.sort_unstable();
+        let help = if known.is_empty() {
+            "this release has no generated secrets".to_string()
+        } else {
+            format!("generated secrets: {}", known.join(", "))
+        };
+        return Err(CliError::validation_with_help(
+            format!(
+                "release '{}' has no generated secret named '{}'",
+                name, secret
+            ),
+            help,
+        ));
+    }
+
+    release.secrets = generator.into_state();
+    release.secrets.mark_clean();
+    release.updated_at = chrono::Utc::now();
+    storage.update(&release).await.into_diagnostic()?;
+
+    println!(
+        "{} Rotated secret {} of release {} (revision {})",
+        style("✓").green().bold(),
+        style(secret).cyan(),
+        style(name).cyan(),
+        release.version
+    );
+    println!("\nRoll out the new value with:");
+    println!("  sherpack upgrade {} <pack> --reuse-values", name);
+    println!(
+        "The old value stays available as previous_secret(\"{}\") until the next rotation.",
+        secret
+    );
+
+    Ok(())
+}
@@ -3,21 +3,26 @@
 use console::style;
 use miette::IntoDiagnostic;
 use sherpack_kube::{
-    KubeClient,
+    ReleaseState,
     health::HealthCheckConfig,
     storage::{FileDriver, StorageConfig},
 };
 
+use crate::display::display_values_provenance;
 use crate::error::Result;
+use crate::util::KubeTarget;
 
 /// Run the status command
+#[allow(clippy::too_many_arguments)]
 pub async fn run(
     name: &str,
     namespace: &str,
     show_resources: bool,
     show_values: bool,
     show_manifest: bool,
+    show_provenance: bool,
     output_json: bool,
+    kube: &KubeTarget,
 ) -> Result<()> {
     // Create storage driver
     let storage_path = dirs::data_dir()
@@ -28,11 +33,18 @@ pub async fn run(
     let storage = FileDriver::new(storage_path, StorageConfig::default()).into_diagnostic()?;
 
     // Create client
-    let client = KubeClient::new(storage).await.into_diagnostic()?;
+    let client = kube.client(storage).await?;
 
     // Get release status
     let release = client.status(namespace, name).await.into_diagnostic()?;
 
+    if output_json && show_provenance {
+        let origins = release.values_provenance.origins(&release.values);
+        let json = serde_json::to_string_pretty(&origins).into_diagnostic()?;
+        println!("{}", json);
+        return Ok(());
+    }
+
     if output_json {
         let json = serde_json::to_string_pretty(&release).into_diagnostic()?;
         println!("{}", json);
@@ -47,11 +59,23 @@ pub async fn run(
 
     let status_style = match release.state.status_name() {
         "deployed" => style(release.state.to_string()).green(),
-        "failed" => style(release.state.to_string()).red(),
+        "failed" | "rolled-back" => style(release.state.to_string()).red(),
         s if s.starts_with("pending") => style(release.state.to_string()).yellow(),
         _ => style(release.state.to_string()).dim(),
     };
     println!("  Status:     {}", status_style);
+
+    // A revision deployed by an atomic rollback: show what failed
+    if let Ok(history) = client.history(namespace, name).await
+        && let Some(previous) = history.iter().find(|r| r.version < release.version)
+        && let ReleaseState::AtomicRollback { error, .. } = &previous.state
+    {
+        println!(
+            "  Rollback:   revision {} failed and was rolled back: {}",
+            previous.version,
+            style(error).red()
+        );
+    }
     println!(
         "  Created:    {}",
         release.created_at.format("%Y-%m-%d %H:%M:%S")
@@ -72,6 +96,27 @@ pub async fn run(
         println!("  App Version: {}", app_ver);
     }
 
+    // Hooks run for this revision
+    if !release.hook_runs.is_empty() {
+        println!("\n{}", style("HOOKS").bold().underlined());
+        for run in &release.hook_runs {
+            let status = if run.success {
+                style("PASS").green().bold()
+            } else if run.timed_out {
+                style("TIMEOUT").red().bold()
+            } else {
+                style("FAIL").red().bold()
+            };
+            println!(
+                "  [{}] {}  ({}, {}s)",
+                status,
+                run.name,
+                run.phase,
+                run.duration.num_seconds()
+            );
+        }
+    }
+
     // Show values if requested
     if show_values {
         println!("\n{}", style("VALUES").bold().underlined());
@@ -79,6 +124,12 @@ pub async fn run(
         println!("{}", yaml);
     }
 
+    // Show where each value came from if requested
+    if show_provenance {
+        println!("\n{}", style("VALUES PROVENANCE").bold().underlined());
+        display_values_provenance(&release.values_provenance.origins(&release.values));
+    }
+
     // Show manifest if requested
     if show_manifest {
         println!("\n{}", style("MANIFEST").bold().underlined());
@@ -100,12 +151,18 @@ pub async fn run(
                     } else {
                         style(icon).red()
                     };
+                    let condition = resource
+                        .condition
+                        .as_ref()
+                        .map(|c| format!(" ({})", c))
+                        .unwrap_or_default();
                     println!(
-                        "  {} {}/{} - {}",
+                        "  {} {}/{} - {}{}",
                         icon_style,
                         resource.kind,
                         resource.name,
-                        resource.readiness_display()
+                        resource.readiness_display(),
+                        condition
                     );
                 }
             }
@@ -1,9 +1,15 @@
 //! Template command - render pack templates locally
 
 use console::style;
+use indexmap::IndexMap;
 use miette::{IntoDiagnostic, Result, WrapErr};
-use sherpack_core::{LoadedPack, ReleaseInfo, SchemaValidator, TemplateContext, Values};
-use sherpack_engine::{Engine, PackRenderer};
+use sherpack_core::context::{Capabilities, KubeVersion};
+use sherpack_core::{
+    KubeVersionConstraint, LoadedPack, ReleaseInfo, SchemaValidator, TemplateContext, Values,
+};
+use sherpack_engine::{Engine, ExecPostRenderer, PackRenderer, UndefinedMode};
+use sherpack_kube::OpenApiValidator;
+use std::collections::BTreeSet;
 use std::fs;
 use std::path::Path;
 
@@ -17,15 +23,24 @@ pub fn run(
     set_values: &[String],
     namespace: &str,
     output_dir: Option<&Path>,
-    show_only: Option<&str>,
+    show_only: &[String],
     show_values: bool,
     skip_schema: bool,
+    strict: bool,
+    fail_fast: bool,
+    kube_version: Option<&str>,
+    skip_kube_version_check: bool,
+    api_versions: &[String],
+    post_renderer: Option<ExecPostRenderer>,
+    secret_seed: Option<&str>,
+    validate: bool,
     debug: bool,
 ) -> Result<()> {
     // Load pack
     let pack = LoadedPack::load(pack_path)
         .into_diagnostic()
         .wrap_err_with(|| format!("Failed to load pack from {}", pack_path.display()))?;
+    pack.pack.ensure_deployable().into_diagnostic()?;
 
     if debug {
         eprintln!(
@@ -118,7 +133,7 @@ pub fn run(
         let set_vals = sherpack_core::values::parse_set_values(set_values)
             .into_diagnostic()
             .wrap_err("Failed to parse --set values")?;
-        values.merge(&set_vals);
+        values.merge_set(&set_vals);
 
         if debug {
             eprintln!(
@@ -157,20 +172,49 @@ pub fn run(
         println!();
     }
 
+    // Cluster capabilities for offline rendering
+    let mut capabilities = Capabilities::default().with_api_versions(api_versions.iter().cloned());
+    if let Some(version) = kube_version {
+        let version = KubeVersion::parse(version).ok_or_else(|| {
+            miette::miette!(
+                "Invalid --kube-version '{}': expected a version like 1.29.0",
+                version
+            )
+        })?;
+        if !skip_kube_version_check {
+            check_kube_version(&pack, &version)?;
+        }
+        capabilities = capabilities.with_kube_version(version);
+    }
+
     // Create template context
     let release = ReleaseInfo::for_install(name, namespace);
     let context = TemplateContext::new(values, release, &pack.pack.metadata);
 
     // Create secret state for generate_secret() function support
-    // In template mode, secrets are generated fresh each time (no persistence)
-    let secret_state = sherpack_engine::SecretFunctionState::new();
+    // In template mode, secrets are generated fresh each time (no persistence),
+    // unless a seed makes them reproducible
+    let mut secret_state = sherpack_engine::SecretFunctionState::new();
+    if let Some(seed) = secret_seed {
+        if seed.is_empty() {
+            return Err(miette::miette!("--secret-seed must not be empty"));
+        }
+        eprintln!(
+            "{} Secrets are derived from --secret-seed: anyone with the seed can recompute them. Do not use in production.",
+            style("⚠").yellow()
+        );
+        secret_state = secret_state.with_seed(seed);
+    }
 
     // Create pack renderer (handles subcharts automatically)
-    let engine = Engine::builder()
+    let mut builder = Engine::builder()
         .strict(pack.pack.engine.strict)
-        .with_secret_state(secret_state)
-        .build();
-    let renderer = PackRenderer::new(engine);
+        .fail_fast(fail_fast);
+    if strict {
+        builder = builder.undefined_behavior(UndefinedMode::Strict);
+    }
+    let engine = builder.with_secret_state(secret_state).build();
+    let renderer = PackRenderer::new(engine).with_capabilities(capabilities);
 
     // Render templates with subchart support and error collection
     let render_result = renderer.render_collect_errors(&pack, &context);
@@ -190,7 +234,8 @@ pub fn run(
                 } else {
                     style("disabled").dim()
                 };
-                eprintln!("  - {} ({})", subchart.name, status);
+                let indent = "  ".repeat(subchart.depth);
+                eprintln!("{}- {} ({})", indent, subchart.qualified_name(), status);
             }
         }
         for warning in &discovery.warnings {
@@ -207,63 +252,57 @@ pub fn run(
         ));
     }
 
-    let result = sherpack_engine::RenderResult {
+    let source_paths = render_result.source_paths;
+    let mut result = sherpack_engine::RenderResult {
         manifests: render_result.manifests,
         notes: render_result.notes,
     };
 
-    // Output results
-    if let Some(output_path) = output_dir {
-        // Write to directory
-        fs::create_dir_all(output_path)
-            .into_diagnostic()
-            .wrap_err_with(|| {
-                format!(
-                    "Failed to create output directory: {}",
-                    output_path.display()
-                )
-            })?;
-
-        for (filename, content) in &result.manifests {
-            if let Some(filter) = show_only
-                && !filename.contains(filter)
-            {
-                continue;
-            }
+    if !show_only.is_empty() {
+        let selected = select_templates(result.manifests.keys(), show_only)?;
+        result
+            .manifests
+            .retain(|filename, _| selected.contains(filename));
+        result.manifests.sort_keys();
+    }
 
-            let file_path = output_path.join(filename);
+    // Post-render the selected templates as a single manifest
+    if let Some(post_renderer) = &post_renderer {
+        let manifest = result
+            .manifests
+            .values()
+            .map(|content| content.trim())
+            .collect::<Vec<_>>()
+            .join("\n---\n");
 
-            // Create parent directories if needed
-            if let Some(parent) = file_path.parent() {
-                fs::create_dir_all(parent).into_diagnostic()?;
-            }
+        let rendered = sherpack_kube::post_render_manifest(&manifest, post_renderer)
+            .into_diagnostic()
+            .wrap_err("Post-rendering failed")?;
 
-            fs::write(&file_path, content)
-                .into_diagnostic()
-                .wrap_err_with(|| format!("Failed to write {}", file_path.display()))?;
+        result.manifests = [("post-rendered.yaml".to_string(), rendered)]
+            .into_iter()
+            .collect();
+    }
 
-            println!("{} {}", style("wrote").green(), file_path.display());
-        }
+    if validate {
+        validate_against_cluster(&result.manifests)?;
+    }
 
-        // Write notes if present
-        if let Some(notes) = &result.notes {
-            let notes_path = output_path.join("NOTES.txt");
-            fs::write(&notes_path, notes).into_diagnostic()?;
-            println!("{} {}", style("wrote").green(), notes_path.display());
-        }
+    // Output results
+    if let Some(output_path) = output_dir {
+        write_output_dir(
+            output_path,
+            &pack.pack.metadata.name,
+            &result,
+            &source_paths,
+        )?;
     } else {
         // Output to stdout
         let mut first = true;
 
         for (filename, content) in &result.manifests {
-            if let Some(filter) = show_only
-                && !filename.contains(filter)
-            {
-                continue;
-            }
-
             if !first {
-                println!();
+                println!("---");
             }
             first = false;
 
@@ -283,3 +322,205 @@ pub fn run(
 
     Ok(())
 }
+
+/// Check `--kube-version` against the pack's `kubeVersion` constraint, as
+/// install and upgrade do against the live cluster
+fn check_kube_version(pack: &LoadedPack, version: &KubeVersion) -> Result<()> {
+    let Some(constraint) = &pack.pack.metadata.kube_version else {
+        return Ok(());
+    };
+    let allowed = KubeVersionConstraint::parse(constraint)
+        .into_diagnostic()?
+        .allows(version);
+
+    if allowed {
+        Ok(())
+    } else {
+        Err(miette::miette!(
+            help = "Use --skip-kube-version-check to render anyway",
+            "Pack '{}' requires Kubernetes {}, but --kube-version is {}",
+            pack.pack.metadata.name,
+            constraint,
+            version.version
+        ))
+    }
+}
+
+/// Check rendered manifests against the OpenAPI schemas of the current cluster
+///
+/// Without a reachable cluster this only warns: the pack's own schema has
+/// already been checked, so rendering still succeeds.
+fn validate_against_cluster(manifests: &IndexMap<String, String>) -> Result<()> {
+    let runtime = tokio::runtime::Runtime::new().into_diagnostic()?;
+
+    let violations = runtime.block_on(async {
+        let mut validator = match OpenApiValidator::connect().await {
+            Ok(validator) => validator,
+            Err(e) => {
+                eprintln!(
+                    "{} Cluster not reachable, validated against the pack schema only: {}",
+                    style("⚠").yellow(),
+                    e
+                );
+                return Ok(Vec::new());
+            }
+        };
+
+        let mut violations = Vec::new();
+        for (filename, content) in manifests {
+            violations.extend(validator.validate_manifest(filename, content).await?);
+        }
+        Ok::<_, sherpack_kube::KubeError>(violations)
+    });
+    let violations = violations
+        .into_diagnostic()
+        .wrap_err("Cluster schema validation failed")?;
+
+    if violations.is_empty() {
+        return Ok(());
+    }
+
+    for violation in &violations {
+        eprintln!("{} {}", style("✗").red().bold(), violation);
+    }
+    Err(miette::miette!(
+        "{} field(s) rejected by the cluster's OpenAPI schemas",
+        violations.len()
+    ))
+}
+
+/// Write each manifest to `<output>/<pack>/<source path>`, like `helm template --output-dir`
+///
+/// Parent templates land under `templates/`, subchart templates under
+/// `charts/<name>/templates/`. Manifests without a known source path (the
+/// post-rendered output) go directly under `<output>/<pack>/`.
+fn write_output_dir(
+    output_path: &Path,
+    pack_name: &str,
+    result: &sherpack_engine::RenderResult,
+    source_paths: &IndexMap<String, String>,
+) -> Result<()> {
+    let pack_dir = output_path.join(pack_name);
+    fs::create_dir_all(&pack_dir)
+        .into_diagnostic()
+        .wrap_err_with(|| {
+            format!(
+                "Failed to create output directory: {}",
+                output_path.display()
+            )
+        })?;
+
+    let mut written = 0;
+    let mut skipped = 0;
+
+    for (filename, content) in &result.manifests {
+        if is_blank_manifest(content) {
+            skipped += 1;
+            continue;
+        }
+
+        let source = source_paths
+            .get(filename)
+            .map(String::as_str)
+            .unwrap_or(filename);
+        let file_path = pack_dir.join(source);
+
+        // Create parent directories if needed
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent).into_diagnostic()?;
+        }
+
+        let document = format!(
+            "---\n# Source: {}/{}\n{}\n",
+            pack_name,
+            source,
+            content.trim()
+        );
+        fs::write(&file_path, document)
+            .into_diagnostic()
+            .wrap_err_with(|| format!("Failed to write {}", file_path.display()))?;
+
+        println!("{} {}", style("wrote").green(), file_path.display());
+        written += 1;
+    }
+
+    // Write notes if present
+    if let Some(notes) = &result.notes {
+        let notes_path = pack_dir.join("templates").join("NOTES.txt");
+        fs::create_dir_all(pack_dir.join("templates")).into_diagnostic()?;
+        fs::write(&notes_path, notes).into_diagnostic()?;
+        println!("{} {}", style("wrote").green(), notes_path.display());
+        written += 1;
+    }
+
+    print!(
+        "\n{} {} file(s) to {}",
+        style("Wrote").green().bold(),
+        written,
+        pack_dir.display()
+    );
+    if skipped > 0 {
+        print!(", skipped {} empty template(s)", skipped);
+    }
+    println!();
+
+    Ok(())
+}
+
+/// Whether a rendered manifest holds nothing but whitespace, comments and separators
+fn is_blank_manifest(content: &str) -> bool {
+    content
+        .lines()
+        .map(str::trim)
+        .all(|line| line.is_empty() || line.starts_with('#') || line == "---")
+}
+
+/// Resolve `--show-only` patterns to template names
+///
+/// A pattern with wildcards is a glob matched against the template name,
+/// with or without its `templates/` prefix; a plain pattern matches any
+/// template whose name contains it. Every pattern must match something.
+fn select_templates<'a>(
+    names: impl Iterator<Item = &'a String>,
+    patterns: &[String],
+) -> Result<BTreeSet<String>> {
+    let names: Vec<&String> = names.collect();
+    let mut selected = BTreeSet::new();
+
+    for pattern in patterns {
+        let glob = if pattern.contains(['*', '?', '[']) {
+            Some(
+                glob::Pattern::new(pattern)
+                    .into_diagnostic()
+                    .wrap_err_with(|| format!("Invalid --show-only pattern '{}'", pattern))?,
+            )
+        } else {
+            None
+        };
+
+        let matches = |candidate: &str| match &glob {
+            Some(glob) => glob.matches(candidate),
+            None => candidate.contains(pattern.as_str()),
+        };
+
+        let mut matched = false;
+        for name in &names {
+            if matches(name) || matches(&format!("templates/{}", name)) {
+                selected.insert((*name).clone());
+                matched = true;
+            }
+        }
+
+        if !matched {
+            let mut available: Vec<&str> = names.iter().map(|name| name.as_str()).collect();
+            available.sort_unstable();
+            return Err(miette::miette!(
+                "No template matches --show-only '{}'. Available templates: {}",
+                pattern,
+                available.join(", ")
+            ));
+        }
+    }
+
+    Ok(selected)
+}
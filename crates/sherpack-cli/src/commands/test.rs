@@ -3,27 +3,39 @@
 //! Equivalent to `helm test`. Loads the latest stored release, parses its
 //! manifests for hooks tagged with the `test` phase (annotation
 //! `sherpack.io/hook: test` or `helm.sh/hook: test`), and executes them
-//! against the cluster.
+//! against the cluster. Test Pods and Jobs are awaited until they complete,
+//! then cleaned up according to their cleanup policy unless `--keep` is set.
 
 use console::style;
 use miette::IntoDiagnostic;
 use sherpack_kube::{
-    KubeClient,
-    hooks::{HookExecutor, HookPhase, parse_hooks_from_manifest},
+    TestOptions,
+    hooks::{HookPhase, parse_hooks_from_manifest},
     storage::{FileDriver, StorageConfig},
 };
 
 use crate::error::{CliError, Result};
+use crate::util::KubeTarget;
 
 /// Run the test command
-pub async fn run(name: &str, namespace: &str) -> Result<()> {
+///
+/// Logs of failed tests are always printed; `logs` streams them for every
+/// test as it runs.
+pub async fn run(
+    name: &str,
+    namespace: &str,
+    timeout: Option<u64>,
+    logs: bool,
+    keep: bool,
+    kube: &KubeTarget,
+) -> Result<()> {
     let storage_path = dirs::data_dir()
         .unwrap_or_else(|| std::path::PathBuf::from("."))
         .join("sherpack")
         .join("releases");
 
     let storage = FileDriver::new(storage_path, StorageConfig::default()).into_diagnostic()?;
-    let client = KubeClient::new(storage).await.into_diagnostic()?;
+    let client = kube.client(storage).await?;
 
     // Load the latest stored release
     let release = client.status(namespace, name).await.into_diagnostic()?;
@@ -58,43 +70,54 @@ pub async fn run(name: &str, namespace: &str) -> Result<()> {
         release.version
     );
 
-    let mut executor = HookExecutor::with_namespace(namespace);
-    let result = executor
-        .execute_phase(
-            &hooks,
-            HookPhase::Test,
-            name,
-            release.version,
-            client.kube_client(),
-        )
-        .await;
+    // Logs are always collected so failures can show them
+    let mut options = TestOptions::new(name, namespace);
+    options.logs = true;
+    options.stream_logs = logs;
+    options.keep = keep;
+    if let Some(t) = timeout {
+        options.timeout = Some(chrono::Duration::seconds(t as i64));
+    }
+
+    let results = client.test(&options).await.into_diagnostic()?;
 
     // Always print results, success or failure
     println!();
     println!("{}", style("RESULTS").bold().underlined());
-    for r in executor.results_for_phase(HookPhase::Test) {
-        let status = if r.error.is_some() {
-            style("FAIL").red().bold()
-        } else {
+    for r in &results {
+        let status = if r.success {
             style("PASS").green().bold()
+        } else if r.timed_out {
+            style("TIMEOUT").red().bold()
+        } else {
+            style("FAIL").red().bold()
         };
         let duration_ms = r.duration().num_milliseconds();
         println!("  [{}] {}  ({}ms)", status, r.name, duration_ms);
         if let Some(err) = &r.error {
             println!("        {}", style(err).red().dim());
         }
-    }
-
-    match result {
-        Ok(()) => {
-            if executor.has_failures() {
-                Err(CliError::internal(
-                    "One or more test hooks reported failures",
-                ))
-            } else {
-                Ok(())
+        // Streamed logs were already shown
+        if !logs
+            && !r.success
+            && let Some(output) = &r.logs
+        {
+            for line in output.lines() {
+                println!("        {}", style(line).dim());
             }
         }
-        Err(e) => Err(CliError::internal(format!("Test execution aborted: {}", e))),
     }
+
+    // Tests after a failing one may not have run
+    let failed = results.iter().filter(|r| !r.success).count();
+    if failed > 0 {
+        return Err(CliError::tests_failed(failed, test_hooks.len()));
+    }
+
+    if keep {
+        println!();
+        println!("Test resources kept (--keep)");
+    }
+
+    Ok(())
 }
@@ -3,11 +3,15 @@
 use console::style;
 use miette::IntoDiagnostic;
 use sherpack_kube::{
-    KubeClient, UninstallOptions,
-    storage::{FileDriver, StorageConfig},
+    CrdDeletionImpact, CrdProtection, DeletionConfirmation, DeletionImpactSummary, KubeClient,
+    UninstallOptions,
+    crd::detect_crds_in_manifests,
+    storage::{FileDriver, StorageConfig, StorageDriver},
 };
 
+use crate::display;
 use crate::error::Result;
+use crate::util::KubeTarget;
 
 /// Run the uninstall command
 #[allow(clippy::too_many_arguments)]
@@ -21,9 +25,14 @@ pub async fn run(
     dry_run: bool,
     delete_crds: bool,
     confirm_crd_deletion: bool,
+    delete_orphans: bool,
+    no_delete_waves: bool,
+    no_backup: bool,
+    debug: bool,
+    kube: &KubeTarget,
 ) -> Result<()> {
-    // Validate CRD deletion flags
-    if delete_crds && !confirm_crd_deletion {
+    // Validate CRD deletion flags (a dry run only reports the impact)
+    if delete_crds && !confirm_crd_deletion && !dry_run {
         eprintln!(
             "{} Cannot delete CRDs without confirmation",
             style("✗").red().bold()
@@ -40,7 +49,7 @@ pub async fn run(
         style(namespace).yellow()
     );
 
-    if delete_crds && confirm_crd_deletion {
+    if delete_crds && confirm_crd_deletion && !dry_run {
         println!(
             "{} CRDs will be deleted (--delete-crds --confirm-crd-deletion)",
             style("⚠").yellow()
@@ -56,7 +65,63 @@ pub async fn run(
     let storage = FileDriver::new(storage_path, StorageConfig::default()).into_diagnostic()?;
 
     // Create client
-    let client = KubeClient::new(storage).await.into_diagnostic()?;
+    let client = kube.client(storage).await?;
+
+    if debug {
+        crate::util::print_cluster_preflight(&client).await;
+    }
+
+    // Report resources the manifest-based deletion would leave behind
+    let release = client
+        .storage()
+        .get_latest(namespace, name)
+        .await
+        .into_diagnostic()?;
+
+    // With --dry-run, report what deleting the CRDs would destroy and stop
+    if delete_crds && dry_run {
+        let summary = release_crd_impact(&client, &release.manifest).await?;
+        display::display_deletion_impact(&summary);
+        display::display_deletion_confirmation(&DeletionConfirmation::from_impact(&summary));
+        println!();
+        println!(
+            "{} Dry run - nothing was deleted from {}",
+            style("✓").green().bold(),
+            style(name).cyan()
+        );
+        return Ok(());
+    }
+
+    let orphans = match client.find_orphans(&release).await {
+        Ok(orphans) => orphans,
+        Err(e) => {
+            eprintln!(
+                "{} Could not check for orphaned resources: {}",
+                style("⚠").yellow(),
+                e
+            );
+            Vec::new()
+        }
+    };
+
+    if !orphans.is_empty() {
+        println!(
+            "{} Found {} orphaned resource(s) labeled for {} but not in its manifest:",
+            style("⚠").yellow(),
+            orphans.len(),
+            style(name).cyan()
+        );
+        for orphan in &orphans {
+            println!("  - {}/{}", orphan.kind, orphan.name);
+        }
+    }
+
+    // Back up CRDs before they are deleted
+    let restore_hint = if delete_crds && !dry_run && !no_backup {
+        backup_release_crds(&client, &release.manifest).await?
+    } else {
+        None
+    };
 
     // Build uninstall options
     let mut options = UninstallOptions::new(name, namespace);
@@ -64,6 +129,8 @@ pub async fn run(
     options.keep_history = keep_history;
     options.no_hooks = no_hooks;
     options.dry_run = dry_run;
+    options.delete_orphans = delete_orphans;
+    options.no_delete_waves = no_delete_waves;
 
     if let Some(t) = timeout {
         options.timeout = Some(chrono::Duration::seconds(t as i64));
@@ -89,7 +156,67 @@ pub async fn run(
         if keep_history {
             println!("  History preserved (use --purge to remove completely)");
         }
+
+        if let Some(hint) = restore_hint {
+            println!("  {}", hint.replace('\n', "\n  "));
+        }
+
+        if !orphans.is_empty() {
+            if delete_orphans {
+                println!("  Deleted {} orphaned resource(s)", orphans.len());
+            } else {
+                println!(
+                    "  {} orphaned resource(s) left in the cluster (use --delete-orphans to remove them)",
+                    orphans.len()
+                );
+            }
+        }
     }
 
     Ok(())
 }
+
+/// Count the CustomResources that deleting a release's CRDs would remove
+async fn release_crd_impact<S: StorageDriver>(
+    client: &KubeClient<S>,
+    manifest: &str,
+) -> Result<DeletionImpactSummary> {
+    let source = "manifest".to_string();
+    let manifest = manifest.to_string();
+    let crds = detect_crds_in_manifests([(&source, &manifest)]);
+
+    DeletionImpactSummary::analyze_impact(client.kube_client(), &crds)
+        .await
+        .into_diagnostic()
+        .map_err(Into::into)
+}
+
+/// Back up the CRDs of a release manifest, returning how to restore them
+async fn backup_release_crds<S: StorageDriver>(
+    client: &KubeClient<S>,
+    manifest: &str,
+) -> Result<Option<String>> {
+    let source = "manifest".to_string();
+    let manifest = manifest.to_string();
+    let crds: Vec<_> = detect_crds_in_manifests([(&source, &manifest)])
+        .into_iter()
+        .filter(|crd| crd.policy.allows_delete())
+        .collect();
+    let names: Vec<String> = crds.iter().map(|crd| crd.name.clone()).collect();
+    let backups = crate::util::backup_crds(client, &names, &console::Term::stdout()).await?;
+
+    let protection = CrdProtection::new(client.kube_client().clone());
+    let mut summary = DeletionImpactSummary::new();
+    for crd in &crds {
+        let mut impact = protection
+            .analyze_deletion_impact(&crd.name, crd.policy)
+            .await
+            .unwrap_or_else(|_| CrdDeletionImpact::empty(&crd.name, crd.policy));
+        if let Some(backup) = backups.iter().find(|backup| backup.crd == crd.name) {
+            impact = impact.with_backup(&backup.path);
+        }
+        summary.add(impact);
+    }
+
+    Ok(DeletionConfirmation::from_impact(&summary).restore_hint())
+}
@@ -2,15 +2,20 @@
 
 use console::style;
 use miette::IntoDiagnostic;
-use sherpack_core::{LoadedPack, Values, parse_set_values};
+use sherpack_core::LoadedPack;
+use sherpack_engine::{ExecPostRenderer, PostRenderer};
 use sherpack_kube::{
-    KubeClient, UpgradeOptions,
+    CrdManager, DryRunMode, HealthCheckConfig, KubeClient, ProgressFormat, UpgradeOptions,
+    WaitCondition,
     actions::ImmutableStrategy,
+    crd::{extract_crd_name, is_crd_manifest},
     storage::{FileDriver, StorageConfig},
 };
 use std::path::Path;
+use std::sync::Arc;
 
 use crate::error::Result;
+use crate::util::KubeTarget;
 
 /// Run the upgrade command
 #[allow(clippy::too_many_arguments)]
@@ -19,86 +24,92 @@ pub async fn run(
     pack_path: &Path,
     values_files: &[std::path::PathBuf],
     set_values: &[String],
+    set_literal_values: &[String],
     namespace: &str,
     wait: bool,
     timeout: Option<u64>,
+    wait_for: &[WaitCondition],
     atomic: bool,
+    atomic_timeout: Option<u64>,
     install: bool,
+    take_ownership: bool,
+    verify_apply: bool,
+    hook_logs: bool,
+    render_subchart_notes: bool,
+    skip_kube_version_check: bool,
     force: bool,
     reset_values: bool,
     reuse_values: bool,
     no_hooks: bool,
-    dry_run: bool,
+    dry_run: DryRunMode,
     show_diff: bool,
     immutable_strategy: Option<&str>,
+    confirm_stateful_recreation: bool,
     max_history: Option<u32>,
     skip_crd_update: bool,
     force_crd_update: bool,
     show_crd_diff: bool,
+    migrate_crd_storage: bool,
+    no_backup: bool,
+    post_renderer: Option<ExecPostRenderer>,
+    progress: Option<ProgressFormat>,
+    debug: bool,
+    kube: &KubeTarget,
 ) -> Result<()> {
+    let out = crate::util::human_output(progress);
+
     // Load the pack
     let pack = LoadedPack::load(pack_path).into_diagnostic()?;
-    println!(
+    out.write_line(&format!(
         "{} Upgrading release {} with pack {} version {}",
         style("→").blue().bold(),
         style(name).cyan(),
         style(&pack.pack.metadata.name).cyan(),
         style(&pack.pack.metadata.version).yellow()
-    );
+    ))
+    .into_diagnostic()?;
 
     // Check for CRDs
     if pack.has_crds() {
         let crd_files = pack.crd_files().into_diagnostic()?;
         if skip_crd_update {
-            println!(
+            out.write_line(&format!(
                 "{} Skipping CRD updates for {} file(s) (--skip-crd-update)",
                 style("⚠").yellow(),
                 crd_files.len()
-            );
+            ))
+            .into_diagnostic()?;
         } else if force_crd_update {
-            println!(
+            out.write_line(&format!(
                 "{} Will force-update {} CRD file(s) (--force-crd-update)",
                 style("⚠").yellow(),
                 crd_files.len()
-            );
+            ))
+            .into_diagnostic()?;
         } else {
-            println!(
+            out.write_line(&format!(
                 "{} Found {} CRD file(s) - safe updates only",
                 style("→").blue(),
                 crd_files.len()
-            );
+            ))
+            .into_diagnostic()?;
         }
         if show_crd_diff {
-            println!(
+            out.write_line(&format!(
                 "{} CRD diff will be shown before applying",
                 style("→").blue()
-            );
+            ))
+            .into_diagnostic()?;
         }
     }
 
     // Handle unused variables for now
     let _ = (skip_crd_update, force_crd_update, show_crd_diff);
 
-    // Load and merge values
-    let mut values = Values::from_file(&pack.values_path).into_diagnostic()?;
-
-    // Apply schema defaults if available
-    if let Some(schema) = pack.load_schema().into_diagnostic()? {
-        let defaults = Values(schema.extract_defaults());
-        values = Values::with_schema_defaults(defaults, values);
-    }
-
-    // Merge additional values files
-    for vf in values_files {
-        let overlay = Values::from_file(vf).into_diagnostic()?;
-        values.merge(&overlay);
-    }
-
-    // Apply --set values
-    if !set_values.is_empty() {
-        let set_values_map = parse_set_values(set_values).into_diagnostic()?;
-        values.merge(&set_values_map);
-    }
+    // Load and merge values, recording where each one came from
+    let values =
+        crate::util::load_tracked_values(&pack, values_files, set_values, set_literal_values)
+            .into_diagnostic()?;
 
     // Create storage driver
     let storage_path = dirs::data_dir()
@@ -109,28 +120,56 @@ pub async fn run(
     let storage = FileDriver::new(storage_path, StorageConfig::default()).into_diagnostic()?;
 
     // Create client
-    let client = KubeClient::new(storage).await.into_diagnostic()?;
+    let client = kube.client(storage).await?;
+
+    if debug {
+        crate::util::print_cluster_preflight(&client).await;
+    }
 
     // Build upgrade options
     let mut options = UpgradeOptions::new(name, namespace);
-    options.wait = wait;
+    options.wait = wait || !wait_for.is_empty();
+    if !wait_for.is_empty() {
+        options.health_check = Some(HealthCheckConfig {
+            wait_for: wait_for.to_vec(),
+            ..Default::default()
+        });
+    }
     options.atomic = atomic;
     options.install = install;
+    options.take_ownership = take_ownership;
+    options.verify_apply = verify_apply;
+    options.hook_logs = hook_logs;
+    options.render_subchart_notes = render_subchart_notes;
+    options.skip_kube_version_check = skip_kube_version_check;
     options.force = force;
     options.reset_values = reset_values;
     options.reuse_values = reuse_values;
     options.no_hooks = no_hooks;
     options.dry_run = dry_run;
     options.show_diff = show_diff;
+    options.post_renderer = post_renderer.map(|r| Arc::new(r) as Arc<dyn PostRenderer>);
+    options.progress = progress.map(ProgressFormat::sink);
+    options.values_provenance = values.provenance().clone();
     options.max_history = max_history;
 
     if let Some(t) = timeout {
         options.timeout = Some(chrono::Duration::seconds(t as i64));
     }
+    if let Some(t) = atomic_timeout {
+        options.atomic_timeout = Some(chrono::Duration::seconds(t as i64));
+    }
 
     if let Some(strategy) = immutable_strategy {
         options.immutable_strategy = strategy.parse().unwrap_or(ImmutableStrategy::Fail);
     }
+    options.confirm_stateful_recreation = confirm_stateful_recreation;
+
+    // Back up CRDs before a forced, possibly breaking, update
+    if force_crd_update && !skip_crd_update && !no_backup && !dry_run.is_enabled() {
+        let names = pack_crd_names(&pack)?;
+        crate::util::backup_crds(&client, &names, &out).await?;
+    }
 
     // Execute upgrade
     let release = client
@@ -138,29 +177,105 @@ pub async fn run(
         .await
         .into_diagnostic()?;
 
-    if dry_run {
-        println!(
-            "{} Dry run - would upgrade {} to revision {} in namespace {}",
+    if dry_run.is_enabled() {
+        out.write_line(&format!(
+            "{} Dry run ({}) - would upgrade {} to revision {} in namespace {}",
             style("✓").green().bold(),
+            dry_run,
             style(name).cyan(),
             style(release.version).yellow(),
             style(namespace).yellow()
-        );
+        ))
+        .into_diagnostic()?;
     } else {
-        println!(
+        out.write_line(&format!(
             "{} Successfully upgraded {} to revision {} in namespace {}",
             style("✓").green().bold(),
             style(&release.name).cyan(),
             style(release.version).yellow(),
             style(&release.namespace).yellow()
-        );
+        ))
+        .into_diagnostic()?;
+    }
+
+    if migrate_crd_storage && !dry_run.is_enabled() {
+        migrate_crd_storage_for(&client, &pack, &out).await?;
     }
 
     // Show notes if present
     if let Some(notes) = &release.notes {
-        println!("\n{}", style("NOTES:").bold());
-        println!("{}", notes);
+        out.write_line(&format!("\n{}", style("NOTES:").bold()))
+            .into_diagnostic()?;
+        out.write_line(notes).into_diagnostic()?;
+    }
+
+    Ok(())
+}
+
+/// Re-write the custom resources of the pack's CRDs that still have objects
+/// stored in an older version
+async fn migrate_crd_storage_for<S: sherpack_kube::storage::StorageDriver>(
+    client: &KubeClient<S>,
+    pack: &LoadedPack,
+    out: &console::Term,
+) -> Result<()> {
+    let manager = CrdManager::new(client.kube_client().clone());
+
+    for name in pack_crd_names(pack)? {
+        match manager.needs_storage_migration(&name).await {
+            Ok(true) => {}
+            Ok(false) => continue,
+            Err(e) => {
+                out.write_line(&format!(
+                    "{} Could not check CRD {}: {}",
+                    style("⚠").yellow(),
+                    name,
+                    e
+                ))
+                .into_diagnostic()?;
+                continue;
+            }
+        }
+
+        match manager.migrate_storage(&name).await {
+            Ok(migration) => {
+                let icon = if migration.is_success() {
+                    style("✓").green()
+                } else {
+                    style("⚠").yellow()
+                };
+                out.write_line(&format!("{} {}", icon, migration.message()))
+                    .into_diagnostic()?;
+                for (resource, reason) in &migration.failed {
+                    out.write_line(&format!("    {}: {}", resource, reason))
+                        .into_diagnostic()?;
+                }
+            }
+            Err(e) => out
+                .write_line(&format!(
+                    "{} Storage migration of CRD {} failed: {}",
+                    style("⚠").yellow(),
+                    name,
+                    e
+                ))
+                .into_diagnostic()?,
+        }
     }
 
     Ok(())
 }
+
+/// Names of the CRDs in the pack's `crds/` directory
+fn pack_crd_names(pack: &LoadedPack) -> Result<Vec<String>> {
+    let mut names = Vec::new();
+    for file in pack.crd_files().into_diagnostic()? {
+        let content = std::fs::read_to_string(&file).into_diagnostic()?;
+        names.extend(
+            content
+                .split("\n---")
+                .filter(|doc| is_crd_manifest(doc))
+                .filter_map(extract_crd_name),
+        );
+    }
+    Ok(names)
+}
@@ -139,7 +139,7 @@ pub fn run(
             .into_diagnostic()
             .wrap_err("Failed to parse --set values")
             .into_cli_result()?;
-        values.merge(&set_vals);
+        values.merge_set(&set_vals);
 
         if verbose && !json_output {
             println!(
@@ -2,8 +2,8 @@
 
 use console::style;
 use miette::{IntoDiagnostic, Result};
-use minisign::{PublicKeyBox, SignatureBox};
-use std::io::{Cursor, Read};
+use minisign::{PublicKey, PublicKeyBox};
+use sherpack_core::SignatureStatus;
 use std::path::Path;
 
 use super::keygen::default_key_dir;
@@ -24,12 +24,60 @@ pub fn run(archive_path: &Path, key_path: Option<&Path>, require_signature: bool
     );
     println!();
 
-    // Step 1: Verify manifest checksums
-    println!("{}:", style("Integrity check").bold());
+    // Step 1: Check the signature first - it covers the whole archive,
+    // MANIFEST included, so the checksums are only trusted once it holds
+    let sig_path = sherpack_core::signature_path(archive_path);
+    let result = if sig_path.exists() {
+        let pk = load_public_key(key_path)?;
+        sherpack_core::verify_archive_signed(archive_path, &pk).into_diagnostic()?
+    } else {
+        let mut result = sherpack_core::verify_archive(archive_path).into_diagnostic()?;
+        result.signature_status = Some(SignatureStatus::Missing);
+        result
+    };
+
+    println!("{}:", style("Signature check").bold());
 
-    let verification_result = sherpack_core::verify_archive(archive_path).into_diagnostic()?;
+    match &result.signature_status {
+        Some(SignatureStatus::Valid { trusted_comment }) => {
+            println!("  {} Signature valid", style("[OK]").green().bold());
 
-    if verification_result.valid {
+            // Show trusted comment if available
+            if let Some(trusted_comment) = trusted_comment {
+                println!("  {}: {}", style("Signed by").dim(), trusted_comment);
+            }
+        }
+        Some(SignatureStatus::Invalid { reason }) => {
+            println!(
+                "  {} Signature verification failed: {}",
+                style("[FAIL]").red().bold(),
+                reason
+            );
+            return Err(miette::miette!(
+                "Signature verification failed: the MANIFEST checksums cannot be trusted"
+            ));
+        }
+        Some(SignatureStatus::Missing) | None => {
+            if require_signature {
+                println!("  {} No signature found", style("[FAIL]").red().bold());
+                return Err(miette::miette!(
+                    "Signature required but not found: {}",
+                    sig_path.display()
+                ));
+            }
+            println!(
+                "  {} No signature file ({})",
+                style("[SKIP]").yellow().bold(),
+                sig_path.display()
+            );
+        }
+    }
+
+    // Step 2: Verify manifest checksums
+    println!();
+    println!("{}:", style("Integrity check").bold());
+
+    if result.valid {
         println!(
             "  {} All file checksums match",
             style("[OK]").green().bold()
@@ -40,58 +88,45 @@ pub fn run(archive_path: &Path, key_path: Option<&Path>, require_signature: bool
             style("[FAIL]").red().bold()
         );
 
-        for mismatch in &verification_result.mismatched {
+        for mismatch in &result.mismatched {
             println!(
-                "    {} {}: expected {}, got {}",
+                "    {} {}: {} expected {}, got {}",
                 style("-").red(),
                 mismatch.path,
+                mismatch.algorithm,
                 truncate_hash(&mismatch.expected, 16),
                 truncate_hash(&mismatch.actual, 16)
             );
         }
 
-        for missing in &verification_result.missing {
+        for missing in &result.missing {
             println!("    {} {}: missing from archive", style("-").red(), missing);
         }
 
         return Err(miette::miette!("Archive integrity check failed"));
     }
 
-    // Step 2: Check signature (if present or required)
-    let sig_path = format!("{}.minisig", archive_path.display());
-    let sig_exists = Path::new(&sig_path).exists();
-
     println!();
-    println!("{}:", style("Signature check").bold());
-
-    if !sig_exists {
-        if require_signature {
-            println!("  {} No signature found", style("[FAIL]").red().bold());
-            return Err(miette::miette!(
-                "Signature required but not found: {}",
-                sig_path
-            ));
-        } else {
-            println!(
-                "  {} No signature file ({})",
-                style("[SKIP]").yellow().bold(),
-                sig_path
-            );
-            println!();
-            println!(
-                "{}",
-                style("Archive integrity verified (no signature).").green()
-            );
-            return Ok(());
-        }
+    if result.is_trusted() {
+        println!("{}", style("Archive verified successfully.").green().bold());
+    } else {
+        println!(
+            "{}",
+            style("Archive integrity verified (no signature).").green()
+        );
     }
 
-    // Find public key
+    Ok(())
+}
+
+/// Load the public key from `--key`, or the default key location
+fn load_public_key(key_path: Option<&Path>) -> Result<PublicKey> {
     let key_path = key_path
         .map(|p| p.to_path_buf())
         .unwrap_or_else(|| default_key_dir().join("sherpack.pub"));
 
     if !key_path.exists() {
+        println!("{}:", style("Signature check").bold());
         println!(
             "  {} Public key not found at {}",
             style("[FAIL]").red().bold(),
@@ -103,47 +138,10 @@ pub fn run(archive_path: &Path, key_path: Option<&Path>, require_signature: bool
         ));
     }
 
-    // Read public key
     let pk_content = std::fs::read_to_string(&key_path).into_diagnostic()?;
     let pk_box = PublicKeyBox::from_string(&pk_content)
         .map_err(|e| miette::miette!("Failed to parse public key: {}", e))?;
-    let pk = pk_box
+    pk_box
         .into_public_key()
-        .map_err(|e| miette::miette!("Invalid public key: {}", e))?;
-
-    // Read signature
-    let sig_content = std::fs::read_to_string(&sig_path).into_diagnostic()?;
-    let sig_box = SignatureBox::from_string(&sig_content)
-        .map_err(|e| miette::miette!("Failed to parse signature: {}", e))?;
-
-    // Read archive data
-    let mut file = std::fs::File::open(archive_path).into_diagnostic()?;
-    let mut data = Vec::new();
-    file.read_to_end(&mut data).into_diagnostic()?;
-
-    // Verify signature using Cursor for Read + Seek
-    let mut cursor = Cursor::new(&data);
-    match minisign::verify(&pk, &sig_box, &mut cursor, true, false, false) {
-        Ok(()) => {
-            println!("  {} Signature valid", style("[OK]").green().bold());
-
-            // Show trusted comment if available
-            if let Ok(trusted_comment) = sig_box.trusted_comment() {
-                println!("  {}: {}", style("Signed by").dim(), trusted_comment);
-            }
-        }
-        Err(e) => {
-            println!(
-                "  {} Signature verification failed: {}",
-                style("[FAIL]").red().bold(),
-                e
-            );
-            return Err(miette::miette!("Signature verification failed"));
-        }
-    }
-
-    println!();
-    println!("{}", style("Archive verified successfully.").green().bold());
-
-    Ok(())
+        .map_err(|e| miette::miette!("Invalid public key: {}", e))
 }
@@ -4,13 +4,14 @@
 //! - Validation errors with grouped display
 //! - CRD change analysis with severity colors
 //! - Render reports with suggestions
+//! - Value provenance tables
 
 #![allow(dead_code)]
 
 use console::{Style, style};
 use sherpack_engine::RenderReport;
 use sherpack_kube::crd::{ChangeKind, ChangeSeverity, CrdAnalysis, CrdChange};
-use sherpack_kube::{CrdDeletionImpact, DeletionConfirmation, DeletionImpactSummary};
+use sherpack_kube::{CrdDeletionImpact, DeletionConfirmation, DeletionImpactSummary, ValueOrigin};
 use std::collections::BTreeMap;
 use std::io::{self, Write};
 
@@ -200,6 +201,48 @@ pub fn display_render_report(report: &RenderReport) {
     }
 }
 
+/// Maximum width of the VALUE column before values are truncated
+const PROVENANCE_VALUE_WIDTH: usize = 40;
+
+/// Display value paths with their values and sources as a table
+pub fn display_values_provenance(origins: &[ValueOrigin]) {
+    let rows: Vec<(&str, String, Option<String>)> = origins
+        .iter()
+        .map(|origin| {
+            let mut value = origin.value.to_string();
+            if value.chars().count() > PROVENANCE_VALUE_WIDTH {
+                value = value.chars().take(PROVENANCE_VALUE_WIDTH - 1).collect();
+                value.push('…');
+            }
+            let source = origin.source.as_ref().map(ToString::to_string);
+            (origin.path.as_str(), value, source)
+        })
+        .collect();
+
+    let path_width = rows.iter().map(|r| r.0.len()).max().unwrap_or(0).max(4);
+    let value_width = rows
+        .iter()
+        .map(|r| r.1.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(5);
+
+    println!(
+        "{:<path_width$}  {:<value_width$}  {}",
+        style("PATH").bold(),
+        style("VALUE").bold(),
+        style("SOURCE").bold()
+    );
+    for (path, value, source) in rows {
+        // Unrecorded paths come from pack or schema defaults
+        let source = match source {
+            Some(source) => style(source).cyan(),
+            None => style("default".to_string()).dim(),
+        };
+        println!("{:<path_width$}  {:<value_width$}  {}", path, value, source);
+    }
+}
+
 /// Format count with proper pluralization
 pub fn pluralize(count: usize, singular: &str, plural: &str) -> String {
     if count == 1 {
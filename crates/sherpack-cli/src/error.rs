@@ -46,6 +46,16 @@ pub enum CliError {
     #[diagnostic(code(sherpack::cli::lint))]
     LintFailed { errors: usize, warnings: usize },
 
+    /// Cluster drift detected by `sherpack diff`
+    #[error("Drift detected in {count} resource(s)")]
+    #[diagnostic(code(sherpack::cli::drift))]
+    DriftDetected { count: usize },
+
+    /// Release tests failed in `sherpack test`
+    #[error("{failed} of {total} test(s) failed")]
+    #[diagnostic(code(sherpack::cli::test))]
+    TestsFailed { failed: usize, total: usize },
+
     /// IO error (file not found, permissions, etc.)
     #[error("IO error: {message}")]
     #[diagnostic(code(sherpack::cli::io))]
@@ -70,6 +80,8 @@ impl CliError {
             CliError::Template { .. } => exit_codes::TEMPLATE_ERROR,
             CliError::Pack { .. } => exit_codes::PACK_ERROR,
             CliError::LintFailed { .. } => exit_codes::ERROR,
+            CliError::DriftDetected { .. } => exit_codes::DRIFT_DETECTED,
+            CliError::TestsFailed { .. } => exit_codes::ERROR,
             CliError::Io { .. } => exit_codes::IO_ERROR,
             CliError::Other { .. } => exit_codes::ERROR,
             CliError::Internal { .. } => exit_codes::ERROR,
@@ -120,6 +132,16 @@ impl CliError {
         Self::LintFailed { errors, warnings }
     }
 
+    /// Create a drift detected error
+    pub fn drift_detected(count: usize) -> Self {
+        Self::DriftDetected { count }
+    }
+
+    /// Create a test failure error
+    pub fn tests_failed(failed: usize, total: usize) -> Self {
+        Self::TestsFailed { failed, total }
+    }
+
     /// Create an input error (user provided invalid input)
     pub fn input(message: impl Into<String>) -> Self {
         Self::Validation {
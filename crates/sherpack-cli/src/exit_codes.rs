@@ -22,5 +22,8 @@ pub const PACK_ERROR: i32 = 4;
 /// IO error - file not found, permission denied, etc.
 pub const IO_ERROR: i32 = 5;
 
+/// Drift detected - cluster state differs from the release
+pub const DRIFT_DETECTED: i32 = 6;
+
 /// Usage error - invalid arguments or options (following sysexits.h convention)
 pub const USAGE_ERROR: i32 = 64;
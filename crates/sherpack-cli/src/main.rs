@@ -2,6 +2,9 @@
 
 use clap::{CommandFactory, Parser, Subcommand};
 use clap_complete::Shell;
+use sherpack_kube::storage::{ReleaseFilter, StatusFilter};
+use sherpack_kube::{DryRunMode, ProgressFormat, WaitCondition};
+use sherpack_repo::PackMediaType;
 use std::path::PathBuf;
 use std::process::ExitCode;
 
@@ -26,6 +29,31 @@ struct Cli {
     /// Enable debug output
     #[arg(long, global = true)]
     debug: bool,
+
+    /// Resolve packs from the local cache only, without network access
+    #[arg(
+        long,
+        global = true,
+        env = "SHERPACK_OFFLINE",
+        value_parser = clap::builder::BoolishValueParser::new()
+    )]
+    offline: bool,
+
+    /// Kubeconfig file to use (defaults to $KUBECONFIG or ~/.kube/config)
+    #[arg(long, global = true, value_name = "PATH")]
+    kubeconfig: Option<PathBuf>,
+
+    /// Kubeconfig context to use (defaults to the current context)
+    #[arg(long, global = true, value_name = "NAME")]
+    kube_context: Option<String>,
+
+    /// User to impersonate for Kubernetes requests
+    #[arg(long = "as", global = true, value_name = "USER")]
+    as_user: Option<String>,
+
+    /// Group to impersonate (repeatable, requires --as)
+    #[arg(long, global = true, value_name = "GROUP", requires = "as_user")]
+    as_group: Vec<String>,
 }
 
 #[derive(Subcommand)]
@@ -54,9 +82,9 @@ enum Commands {
         #[arg(long)]
         output_dir: Option<PathBuf>,
 
-        /// Show only specific template
+        /// Show only templates matching a name or glob (repeatable)
         #[arg(short = 's', long)]
-        show_only: Option<String>,
+        show_only: Vec<String>,
 
         /// Show rendered values
         #[arg(long)]
@@ -65,6 +93,46 @@ enum Commands {
         /// Skip schema validation before rendering
         #[arg(long)]
         skip_schema: bool,
+
+        /// Fail on undefined values instead of rendering them empty
+        #[arg(long)]
+        strict: bool,
+
+        /// Stop at the first failing template instead of reporting them all
+        #[arg(long)]
+        fail_fast: bool,
+
+        /// Kubernetes version templates see in `capabilities.kubeVersion`
+        #[arg(long, value_name = "VERSION")]
+        kube_version: Option<String>,
+
+        /// Render even if --kube-version doesn't satisfy the pack's kubeVersion
+        #[arg(long, requires = "kube_version")]
+        skip_kube_version_check: bool,
+
+        /// Extra API version available in `capabilities.apiVersions` (repeatable)
+        #[arg(long = "api-versions", value_name = "API_VERSION")]
+        api_versions: Vec<String>,
+
+        /// Pipe rendered manifests through this command (e.g. a kustomize wrapper)
+        #[arg(long, value_name = "PATH")]
+        post_renderer: Option<PathBuf>,
+
+        /// Argument passed to the post-renderer (repeatable)
+        #[arg(
+            long = "post-renderer-args",
+            value_name = "ARG",
+            requires = "post_renderer"
+        )]
+        post_renderer_args: Vec<String>,
+
+        /// Derive generate_secret() values from this seed (NOT for production)
+        #[arg(long, value_name = "SEED", env = "SHERPACK_SECRET_SEED")]
+        secret_seed: Option<String>,
+
+        /// Validate rendered manifests against the cluster's OpenAPI schemas
+        #[arg(long)]
+        validate: bool,
     },
 
     /// Create a new pack
@@ -83,13 +151,21 @@ enum Commands {
         #[arg(default_value = ".")]
         path: PathBuf,
 
-        /// Strict mode
+        /// Strict mode (undefined variables and warnings fail the lint)
         #[arg(long)]
         strict: bool,
 
         /// Skip schema validation even if schema exists
         #[arg(long)]
         skip_schema: bool,
+
+        /// Persisted secret state (JSON) backing generate_secret() calls
+        #[arg(long, value_name = "FILE")]
+        secret_state: Option<PathBuf>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t)]
+        format: commands::lint::LintFormat,
     },
 
     /// Show pack information
@@ -151,6 +227,10 @@ enum Commands {
         /// Sign with key after packaging
         #[arg(long)]
         sign: Option<PathBuf>,
+
+        /// Checksum algorithm for the MANIFEST file entries
+        #[arg(long, value_enum, default_value_t)]
+        hash: commands::package::HashArg,
     },
 
     /// Inspect a packaged archive
@@ -230,6 +310,18 @@ enum Commands {
         /// Show detailed output
         #[arg(short, long)]
         verbose: bool,
+
+        /// Print the JSON conversion report (the one --report writes) instead of the summary
+        #[arg(long)]
+        json: bool,
+
+        /// Skip rendering the converted pack to check that it works
+        #[arg(long)]
+        no_verify: bool,
+
+        /// Write the JSON conversion report (files, warnings, counts by severity, coverage) to this path
+        #[arg(long, value_name = "PATH")]
+        report: Option<PathBuf>,
     },
 
     // ========== Phase 4: Kubernetes Deployment ==========
@@ -249,9 +341,13 @@ enum Commands {
         #[arg(long = "set")]
         set: Vec<String>,
 
-        /// Target namespace
-        #[arg(short, long, default_value = "default")]
-        namespace: String,
+        /// Set a literal string value (key is not split on dots)
+        #[arg(long = "set-literal")]
+        set_literal: Vec<String>,
+
+        /// Target namespace (defaults to the kube context's namespace)
+        #[arg(short, long)]
+        namespace: Option<String>,
 
         /// Wait for resources to be ready
         #[arg(long)]
@@ -261,17 +357,51 @@ enum Commands {
         #[arg(long)]
         timeout: Option<u64>,
 
+        /// Also wait for a condition, e.g. 'Certificate/web-tls: condition=Ready' (repeatable, implies --wait)
+        #[arg(long, value_name = "CONDITION")]
+        wait_for: Vec<WaitCondition>,
+
         /// Rollback on failure
         #[arg(long)]
         atomic: bool,
 
+        /// Timeout in seconds for the --atomic wait (defaults to --timeout)
+        #[arg(long, requires = "atomic")]
+        atomic_timeout: Option<u64>,
+
         /// Create namespace if not exists
         #[arg(long)]
         create_namespace: bool,
 
-        /// Simulate without applying
+        /// Adopt existing resources not managed by any release instead of failing
         #[arg(long)]
-        dry_run: bool,
+        take_ownership: bool,
+
+        /// After applying, warn about fields the server changed (admission webhooks)
+        #[arg(long)]
+        verify_apply: bool,
+
+        /// Stream the container logs of hook Pods and Jobs as they run
+        #[arg(long)]
+        logs: bool,
+
+        /// Show the notes of enabled subcharts after the pack's own
+        #[arg(long)]
+        render_subchart_notes: bool,
+
+        /// Deploy even if the cluster doesn't satisfy the pack's kubeVersion
+        #[arg(long)]
+        skip_kube_version_check: bool,
+
+        /// Simulate without applying (`--dry-run=server` validates against the API server)
+        #[arg(
+            long,
+            value_name = "MODE",
+            num_args = 0..=1,
+            require_equals = true,
+            default_missing_value = "client"
+        )]
+        dry_run: Option<DryRunMode>,
 
         /// Show diff before applying
         #[arg(long)]
@@ -280,6 +410,23 @@ enum Commands {
         /// Skip CRD installation (assume CRDs are managed externally)
         #[arg(long)]
         skip_crds: bool,
+
+        /// Pipe rendered manifests through this command (e.g. a kustomize wrapper)
+        #[arg(long, value_name = "PATH")]
+        post_renderer: Option<PathBuf>,
+
+        /// Argument passed to the post-renderer (repeatable)
+        #[arg(
+            long = "post-renderer-args",
+            value_name = "ARG",
+            requires = "post_renderer"
+        )]
+        post_renderer_args: Vec<String>,
+
+        /// Report per-resource progress (`terminal` or `json` for NDJSON events
+        /// on stdout, the rest of the output going to stderr)
+        #[arg(long, value_name = "FORMAT")]
+        progress: Option<ProgressFormat>,
     },
 
     /// Upgrade an existing release
@@ -298,9 +445,13 @@ enum Commands {
         #[arg(long = "set")]
         set: Vec<String>,
 
-        /// Target namespace
-        #[arg(short, long, default_value = "default")]
-        namespace: String,
+        /// Set a literal string value (key is not split on dots)
+        #[arg(long = "set-literal")]
+        set_literal: Vec<String>,
+
+        /// Target namespace (defaults to the kube context's namespace)
+        #[arg(short, long)]
+        namespace: Option<String>,
 
         /// Wait for resources to be ready
         #[arg(long)]
@@ -310,14 +461,42 @@ enum Commands {
         #[arg(long)]
         timeout: Option<u64>,
 
+        /// Also wait for a condition, e.g. 'Certificate/web-tls: condition=Ready' (repeatable, implies --wait)
+        #[arg(long, value_name = "CONDITION")]
+        wait_for: Vec<WaitCondition>,
+
         /// Rollback on failure
         #[arg(long)]
         atomic: bool,
 
+        /// Timeout in seconds for the --atomic wait (defaults to --timeout)
+        #[arg(long, requires = "atomic")]
+        atomic_timeout: Option<u64>,
+
         /// Install if not exists
         #[arg(short, long)]
         install: bool,
 
+        /// With --install, adopt existing resources not managed by any release
+        #[arg(long, requires = "install")]
+        take_ownership: bool,
+
+        /// After applying, warn about fields the server changed (admission webhooks)
+        #[arg(long)]
+        verify_apply: bool,
+
+        /// Stream the container logs of hook Pods and Jobs as they run
+        #[arg(long)]
+        logs: bool,
+
+        /// Show the notes of enabled subcharts after the pack's own
+        #[arg(long)]
+        render_subchart_notes: bool,
+
+        /// Deploy even if the cluster doesn't satisfy the pack's kubeVersion
+        #[arg(long)]
+        skip_kube_version_check: bool,
+
         /// Force recreate resources
         #[arg(long)]
         force: bool,
@@ -334,9 +513,15 @@ enum Commands {
         #[arg(long)]
         no_hooks: bool,
 
-        /// Simulate without applying
-        #[arg(long)]
-        dry_run: bool,
+        /// Simulate without applying (`--dry-run=server` validates against the API server)
+        #[arg(
+            long,
+            value_name = "MODE",
+            num_args = 0..=1,
+            require_equals = true,
+            default_missing_value = "client"
+        )]
+        dry_run: Option<DryRunMode>,
 
         /// Show diff before applying
         #[arg(long)]
@@ -346,7 +531,11 @@ enum Commands {
         #[arg(long)]
         immutable_strategy: Option<String>,
 
-        /// Max history revisions to keep
+        /// Confirm that recreate may delete StatefulSets and volumes
+        #[arg(long)]
+        confirm_stateful_recreation: bool,
+
+        /// Max history revisions to keep (default 10, 0 for no limit)
         #[arg(long)]
         max_history: Option<u32>,
 
@@ -361,6 +550,31 @@ enum Commands {
         /// Show CRD diff before applying
         #[arg(long)]
         show_crd_diff: bool,
+
+        /// Re-write custom resources still stored in an old CRD storage version
+        #[arg(long)]
+        migrate_crd_storage: bool,
+
+        /// Don't back up CRDs and their resources before --force-crd-update
+        #[arg(long)]
+        no_backup: bool,
+
+        /// Pipe rendered manifests through this command (e.g. a kustomize wrapper)
+        #[arg(long, value_name = "PATH")]
+        post_renderer: Option<PathBuf>,
+
+        /// Argument passed to the post-renderer (repeatable)
+        #[arg(
+            long = "post-renderer-args",
+            value_name = "ARG",
+            requires = "post_renderer"
+        )]
+        post_renderer_args: Vec<String>,
+
+        /// Report per-resource progress (`terminal` or `json` for NDJSON events
+        /// on stdout, the rest of the output going to stderr)
+        #[arg(long, value_name = "FORMAT")]
+        progress: Option<ProgressFormat>,
     },
 
     /// Uninstall a release
@@ -368,9 +582,9 @@ enum Commands {
         /// Release name
         name: String,
 
-        /// Target namespace
-        #[arg(short, long, default_value = "default")]
-        namespace: String,
+        /// Target namespace (defaults to the kube context's namespace)
+        #[arg(short, long)]
+        namespace: Option<String>,
 
         /// Wait for deletion
         #[arg(long)]
@@ -399,6 +613,18 @@ enum Commands {
         /// Confirm CRD deletion (required with --delete-crds)
         #[arg(long)]
         confirm_crd_deletion: bool,
+
+        /// Also delete resources labeled for the release but missing from its manifest
+        #[arg(long)]
+        delete_orphans: bool,
+
+        /// Delete by resource kind only, ignoring sync/delete waves
+        #[arg(long)]
+        no_delete_waves: bool,
+
+        /// Don't back up CRDs and their resources before --delete-crds
+        #[arg(long)]
+        no_backup: bool,
     },
 
     /// Rollback to a previous revision
@@ -410,9 +636,9 @@ enum Commands {
         #[arg(default_value = "0")]
         revision: u32,
 
-        /// Target namespace
-        #[arg(short, long, default_value = "default")]
-        namespace: String,
+        /// Target namespace (defaults to the kube context's namespace)
+        #[arg(short, long)]
+        namespace: Option<String>,
 
         /// Wait for resources to be ready
         #[arg(long)]
@@ -442,7 +668,11 @@ enum Commands {
         #[arg(long)]
         immutable_strategy: Option<String>,
 
-        /// Max history revisions to keep
+        /// Confirm that recreate may delete StatefulSets and volumes
+        #[arg(long)]
+        confirm_stateful_recreation: bool,
+
+        /// Max history revisions to keep (default 10, 0 for no limit)
         #[arg(long)]
         max_history: Option<u32>,
     },
@@ -458,6 +688,18 @@ enum Commands {
         #[arg(short = 'A', long)]
         all_namespaces: bool,
 
+        /// Only releases in this status (deployed, failed, superseded, pending)
+        #[arg(long)]
+        status: Option<StatusFilter>,
+
+        /// Only releases with these labels (key=value, comma-separated or repeated)
+        #[arg(short = 'l', long, value_name = "SELECTOR")]
+        selector: Vec<String>,
+
+        /// Only releases of this pack
+        #[arg(long, value_name = "NAME")]
+        pack: Option<String>,
+
         /// Output as JSON
         #[arg(long)]
         json: bool,
@@ -468,14 +710,18 @@ enum Commands {
         /// Release name
         name: String,
 
-        /// Target namespace
-        #[arg(short, long, default_value = "default")]
-        namespace: String,
+        /// Target namespace (defaults to the kube context's namespace)
+        #[arg(short, long)]
+        namespace: Option<String>,
 
         /// Max revisions to show
         #[arg(long)]
         max: Option<usize>,
 
+        /// Compare two revisions (`A:B`, or `A` to compare with the latest)
+        #[arg(long, value_name = "A[:B]")]
+        diff: Option<String>,
+
         /// Output as JSON
         #[arg(long)]
         json: bool,
@@ -486,9 +732,9 @@ enum Commands {
         /// Release name
         name: String,
 
-        /// Target namespace
-        #[arg(short, long, default_value = "default")]
-        namespace: String,
+        /// Target namespace (defaults to the kube context's namespace)
+        #[arg(short, long)]
+        namespace: Option<String>,
 
         /// Show resource status
         #[arg(long)]
@@ -502,21 +748,75 @@ enum Commands {
         #[arg(long)]
         manifest: bool,
 
+        /// Show where each value came from
+        #[arg(long)]
+        provenance: bool,
+
         /// Output as JSON
         #[arg(long)]
         json: bool,
     },
 
-    /// Recover a stuck release
+    /// Print what was stored for a release
+    #[command(subcommand)]
+    Get(GetCommands),
+
+    /// Show differences between revisions, or drift against the cluster
+    Diff {
+        /// Release name
+        name: String,
+
+        /// Target namespace (defaults to the kube context's namespace)
+        #[arg(short, long)]
+        namespace: Option<String>,
+
+        /// Compare this stored revision with the latest one
+        #[arg(long, conflicts_with_all = ["live", "three_way"])]
+        revision: Option<u32>,
+
+        /// Compare the latest release with live cluster state
+        #[arg(long, conflicts_with = "three_way")]
+        live: bool,
+
+        /// Three-way diff of a pack against the latest release and the cluster
+        #[arg(long = "three-way", value_name = "PACK")]
+        three_way: Option<PathBuf>,
+
+        /// Values file(s) to merge (for --three-way)
+        #[arg(short = 'f', long = "values", requires = "three_way")]
+        values: Vec<PathBuf>,
+
+        /// Set values on command line (for --three-way)
+        #[arg(long = "set", requires = "three_way")]
+        set: Vec<String>,
+
+        /// Show Secret values in plaintext
+        #[arg(long)]
+        reveal_secrets: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Resume a stuck or failed release
     Recover {
         /// Release name
         name: String,
 
-        /// Target namespace
-        #[arg(short, long, default_value = "default")]
-        namespace: String,
+        /// Target namespace (defaults to the kube context's namespace)
+        #[arg(short, long)]
+        namespace: Option<String>,
+
+        /// Only mark the release as failed, without re-applying anything
+        #[arg(long)]
+        state_only: bool,
     },
 
+    /// Manage the secrets generated for a release
+    #[command(subcommand)]
+    Secret(SecretCommands),
+
     // ========== Phase 5: Repository Management ==========
     /// Manage pack repositories
     #[command(subcommand)]
@@ -556,6 +856,14 @@ enum Commands {
         /// Extract to directory instead of saving archive
         #[arg(long)]
         untar: bool,
+
+        /// Verify the cosign signature of an OCI pack (requires --key)
+        #[arg(long, requires = "key")]
+        verify_signature: bool,
+
+        /// Cosign public key (PEM) for --verify-signature
+        #[arg(short, long, requires = "verify_signature")]
+        key: Option<PathBuf>,
     },
 
     /// Push a pack to an OCI registry
@@ -565,6 +873,10 @@ enum Commands {
 
         /// OCI destination (oci://registry/repo:tag)
         destination: String,
+
+        /// Artifact media types to publish under (`helm` or `sherpack`)
+        #[arg(long, default_value = "helm")]
+        media_type: PackMediaType,
     },
 
     /// Run `test`-phase hooks against an installed release
@@ -576,9 +888,21 @@ enum Commands {
         /// Release name
         name: String,
 
-        /// Namespace
-        #[arg(short, long, default_value = "default")]
-        namespace: String,
+        /// Target namespace (defaults to the kube context's namespace)
+        #[arg(short, long)]
+        namespace: Option<String>,
+
+        /// Timeout in seconds for tests without a hook timeout annotation
+        #[arg(long)]
+        timeout: Option<u64>,
+
+        /// Stream the logs of every test as it runs, not only those of failed ones
+        #[arg(long)]
+        logs: bool,
+
+        /// Keep test resources instead of applying their cleanup policy
+        #[arg(long)]
+        keep: bool,
     },
 
     /// Manage pack dependencies
@@ -643,12 +967,41 @@ enum RepoCommands {
         name: String,
     },
 
+    /// Log in to a registry or repository URL
+    ///
+    /// Prompts for a username and password unless `--token` or
+    /// `--password-stdin` is given. The credentials apply to every
+    /// repository under the scope (`host[:port][/path]`). For OCI registries
+    /// the registry's token exchange is performed and the token cached.
+    Login {
+        /// Registry (oci://host[:port][/path], host[:port]) or repository URL
+        registry: String,
+
+        /// Username
+        #[arg(short, long)]
+        username: Option<String>,
+
+        /// Read the password from stdin
+        #[arg(long, conflicts_with = "token")]
+        password_stdin: bool,
+
+        /// Token for authentication (alternative to username/password)
+        #[arg(long)]
+        token: Option<String>,
+    },
+
+    /// Remove the credentials stored by `repo login`
+    Logout {
+        /// Registry or URL scope given to `repo login`
+        registry: String,
+    },
+
     /// Generate a repository index.yaml from a directory of *.tgz packs
     ///
     /// Equivalent to `helm repo index`. Walks the directory for archives,
     /// reads Pack.yaml from each, hashes the archive, and writes index.yaml.
     Index {
-        /// Directory containing the *.tgz pack archives
+        /// Directory containing the *.tgz / *.tar.gz pack archives
         dir: PathBuf,
 
         /// Base URL prepended to each archive filename in the index
@@ -659,6 +1012,119 @@ enum RepoCommands {
         #[arg(long)]
         merge: Option<PathBuf>,
     },
+
+    /// Manage the local index cache
+    #[command(subcommand)]
+    Cache(RepoCacheCommands),
+}
+
+/// Index cache subcommands
+#[derive(Subcommand)]
+enum RepoCacheCommands {
+    /// Show cache statistics and expired indices
+    Stats,
+
+    /// Clear the cached index of a repository (all if not specified)
+    Clear {
+        /// Repository name
+        repo: Option<String>,
+    },
+
+    /// Drop the cached indices older than the TTL
+    Prune,
+}
+
+/// Get subcommands
+#[derive(Subcommand)]
+enum GetCommands {
+    /// Print the release values (user-supplied only, unless --all)
+    Values {
+        /// Release name
+        name: String,
+
+        /// Target namespace (defaults to the kube context's namespace)
+        #[arg(short, long)]
+        namespace: Option<String>,
+
+        /// Revision to read (default: latest)
+        #[arg(long)]
+        revision: Option<u32>,
+
+        /// Print all computed values, including defaults
+        #[arg(short, long)]
+        all: bool,
+
+        /// List each value path with where it came from
+        #[arg(long)]
+        provenance: bool,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t)]
+        output: commands::get::OutputFormat,
+    },
+
+    /// Print the rendered manifest
+    Manifest {
+        /// Release name
+        name: String,
+
+        /// Target namespace (defaults to the kube context's namespace)
+        #[arg(short, long)]
+        namespace: Option<String>,
+
+        /// Revision to read (default: latest)
+        #[arg(long)]
+        revision: Option<u32>,
+    },
+
+    /// Print the release hooks
+    Hooks {
+        /// Release name
+        name: String,
+
+        /// Target namespace (defaults to the kube context's namespace)
+        #[arg(short, long)]
+        namespace: Option<String>,
+
+        /// Revision to read (default: latest)
+        #[arg(long)]
+        revision: Option<u32>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t)]
+        output: commands::get::OutputFormat,
+    },
+
+    /// Print the release notes
+    Notes {
+        /// Release name
+        name: String,
+
+        /// Target namespace (defaults to the kube context's namespace)
+        #[arg(short, long)]
+        namespace: Option<String>,
+
+        /// Revision to read (default: latest)
+        #[arg(long)]
+        revision: Option<u32>,
+    },
+}
+
+/// Secret subcommands
+#[derive(Subcommand)]
+enum SecretCommands {
+    /// Generate a new value for a secret, keeping the old one as its previous value
+    Rotate {
+        /// Release name
+        name: String,
+
+        /// Secret name, as passed to generate_secret()
+        secret: String,
+
+        /// Target namespace (defaults to the kube context's namespace)
+        #[arg(short, long)]
+        namespace: Option<String>,
+    },
 }
 
 /// Dependency subcommands
@@ -673,10 +1139,17 @@ enum DependencyCommands {
     },
 
     /// Resolve and lock dependencies
+    ///
+    /// With a dependency name, only that entry of Pack.lock.yaml is
+    /// updated and every other dependency stays at its locked version.
+    /// `sherpack dependency update redis` works from the pack directory.
     Update {
         /// Pack path
         #[arg(default_value = ".")]
         path: PathBuf,
+
+        /// Only update this dependency (name or alias)
+        name: Option<String>,
     },
 
     /// Download dependencies
@@ -695,6 +1168,14 @@ enum DependencyCommands {
         /// Pack path
         #[arg(default_value = ".")]
         path: PathBuf,
+
+        /// Maximum number of dependency levels to display
+        #[arg(long)]
+        depth: Option<usize>,
+
+        /// Highlight dependencies reached through more than one path
+        #[arg(long)]
+        duplicates: bool,
     },
 }
 
@@ -702,6 +1183,10 @@ fn main() -> ExitCode {
     // Setup miette for nice error display
     miette::set_panic_hook();
 
+    // kube and reqwest enable both rustls crypto providers, so neither is
+    // picked automatically
+    let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+
     let cli = Cli::parse();
 
     // Initialize tracing.
@@ -744,6 +1229,13 @@ fn main() -> ExitCode {
 }
 
 fn run_command(cli: Cli) -> error::Result<()> {
+    let kube = util::KubeTarget {
+        kubeconfig: cli.kubeconfig.clone(),
+        context: cli.kube_context.clone(),
+        impersonate_user: cli.as_user.clone(),
+        impersonate_groups: cli.as_group.clone(),
+    };
+
     match cli.command {
         Commands::Template {
             name,
@@ -755,6 +1247,15 @@ fn run_command(cli: Cli) -> error::Result<()> {
             show_only,
             show_values,
             skip_schema,
+            strict,
+            fail_fast,
+            kube_version,
+            skip_kube_version_check,
+            api_versions,
+            post_renderer,
+            post_renderer_args,
+            secret_seed,
+            validate,
         } => commands::template::run(
             &name,
             &pack,
@@ -762,9 +1263,17 @@ fn run_command(cli: Cli) -> error::Result<()> {
             &set,
             &namespace,
             output_dir.as_deref(),
-            show_only.as_deref(),
+            &show_only,
             show_values,
             skip_schema,
+            strict,
+            fail_fast,
+            kube_version.as_deref(),
+            skip_kube_version_check,
+            &api_versions,
+            util::post_renderer(post_renderer, post_renderer_args),
+            secret_seed.as_deref(),
+            validate,
             cli.debug,
         )
         .map_err(CliError::from),
@@ -777,7 +1286,9 @@ fn run_command(cli: Cli) -> error::Result<()> {
             path,
             strict,
             skip_schema,
-        } => commands::lint::run(&path, strict, skip_schema),
+            secret_state,
+            format,
+        } => commands::lint::run(&path, strict, skip_schema, secret_state.as_deref(), format),
 
         Commands::Show { path, all } => commands::show::run(&path, all).map_err(CliError::from),
 
@@ -801,10 +1312,13 @@ fn run_command(cli: Cli) -> error::Result<()> {
             strict,
         ),
 
-        Commands::Package { path, output, sign } => {
-            commands::package::run(&path, output.as_deref(), sign.as_deref())
-                .map_err(CliError::from)
-        }
+        Commands::Package {
+            path,
+            output,
+            sign,
+            hash,
+        } => commands::package::run(&path, output.as_deref(), sign.as_deref(), hash)
+            .map_err(CliError::from),
 
         Commands::Inspect {
             archive,
@@ -838,8 +1352,20 @@ fn run_command(cli: Cli) -> error::Result<()> {
             force,
             dry_run,
             verbose,
-        } => commands::convert::run(&chart, output.as_deref(), force, dry_run, verbose)
-            .map_err(CliError::from),
+            json,
+            no_verify,
+            report,
+        } => commands::convert::run(
+            &chart,
+            output.as_deref(),
+            force,
+            dry_run,
+            verbose,
+            json,
+            !no_verify,
+            report,
+        )
+        .map_err(CliError::from),
 
         // Phase 4: Kubernetes deployment commands (async)
         Commands::Install {
@@ -847,15 +1373,27 @@ fn run_command(cli: Cli) -> error::Result<()> {
             pack,
             values,
             set,
+            set_literal,
             namespace,
             wait,
             timeout,
+            wait_for,
             atomic,
+            atomic_timeout,
             create_namespace,
+            take_ownership,
+            verify_apply,
+            logs,
+            render_subchart_notes,
+            skip_kube_version_check,
             dry_run,
             diff,
             skip_crds,
+            post_renderer,
+            post_renderer_args,
+            progress,
         } => {
+            let namespace = kube.namespace(namespace);
             let rt =
                 tokio::runtime::Runtime::new().map_err(|e| CliError::internal(e.to_string()))?;
             rt.block_on(commands::install::run(
@@ -863,14 +1401,26 @@ fn run_command(cli: Cli) -> error::Result<()> {
                 &pack,
                 &values,
                 &set,
+                &set_literal,
                 &namespace,
                 wait,
                 timeout,
+                &wait_for,
                 atomic,
+                atomic_timeout,
                 create_namespace,
-                dry_run,
+                take_ownership,
+                verify_apply,
+                logs,
+                render_subchart_notes,
+                skip_kube_version_check,
+                dry_run.unwrap_or_default(),
                 diff,
                 skip_crds,
+                util::post_renderer(post_renderer, post_renderer_args),
+                progress,
+                cli.debug,
+                &kube,
             ))
         }
 
@@ -879,11 +1429,19 @@ fn run_command(cli: Cli) -> error::Result<()> {
             pack,
             values,
             set,
+            set_literal,
             namespace,
             wait,
             timeout,
+            wait_for,
             atomic,
+            atomic_timeout,
             install,
+            take_ownership,
+            verify_apply,
+            logs,
+            render_subchart_notes,
+            skip_kube_version_check,
             force,
             reset_values,
             reuse_values,
@@ -891,11 +1449,18 @@ fn run_command(cli: Cli) -> error::Result<()> {
             dry_run,
             diff,
             immutable_strategy,
+            confirm_stateful_recreation,
             max_history,
             skip_crd_update,
             force_crd_update,
             show_crd_diff,
+            migrate_crd_storage,
+            no_backup,
+            post_renderer,
+            post_renderer_args,
+            progress,
         } => {
+            let namespace = kube.namespace(namespace);
             let rt =
                 tokio::runtime::Runtime::new().map_err(|e| CliError::internal(e.to_string()))?;
             rt.block_on(commands::upgrade::run(
@@ -903,22 +1468,37 @@ fn run_command(cli: Cli) -> error::Result<()> {
                 &pack,
                 &values,
                 &set,
+                &set_literal,
                 &namespace,
                 wait,
                 timeout,
+                &wait_for,
                 atomic,
+                atomic_timeout,
                 install,
+                take_ownership,
+                verify_apply,
+                logs,
+                render_subchart_notes,
+                skip_kube_version_check,
                 force,
                 reset_values,
                 reuse_values,
                 no_hooks,
-                dry_run,
+                dry_run.unwrap_or_default(),
                 diff,
                 immutable_strategy.as_deref(),
+                confirm_stateful_recreation,
                 max_history,
                 skip_crd_update,
                 force_crd_update,
                 show_crd_diff,
+                migrate_crd_storage,
+                no_backup,
+                util::post_renderer(post_renderer, post_renderer_args),
+                progress,
+                cli.debug,
+                &kube,
             ))
         }
 
@@ -932,7 +1512,11 @@ fn run_command(cli: Cli) -> error::Result<()> {
             dry_run,
             delete_crds,
             confirm_crd_deletion,
+            delete_orphans,
+            no_delete_waves,
+            no_backup,
         } => {
+            let namespace = kube.namespace(namespace);
             let rt =
                 tokio::runtime::Runtime::new().map_err(|e| CliError::internal(e.to_string()))?;
             rt.block_on(commands::uninstall::run(
@@ -945,6 +1529,11 @@ fn run_command(cli: Cli) -> error::Result<()> {
                 dry_run,
                 delete_crds,
                 confirm_crd_deletion,
+                delete_orphans,
+                no_delete_waves,
+                no_backup,
+                cli.debug,
+                &kube,
             ))
         }
 
@@ -959,8 +1548,10 @@ fn run_command(cli: Cli) -> error::Result<()> {
             dry_run,
             diff,
             immutable_strategy,
+            confirm_stateful_recreation,
             max_history,
         } => {
+            let namespace = kube.namespace(namespace);
             let rt =
                 tokio::runtime::Runtime::new().map_err(|e| CliError::internal(e.to_string()))?;
             rt.block_on(commands::rollback::run(
@@ -974,21 +1565,40 @@ fn run_command(cli: Cli) -> error::Result<()> {
                 dry_run,
                 diff,
                 immutable_strategy.as_deref(),
+                confirm_stateful_recreation,
                 max_history,
+                cli.debug,
+                &kube,
             ))
         }
 
         Commands::List {
             namespace,
             all_namespaces,
+            status,
+            selector,
+            pack,
             json,
         } => {
+            let mut filter = ReleaseFilter {
+                status,
+                pack,
+                ..Default::default()
+            };
+            for term in &selector {
+                filter
+                    .selector
+                    .extend(ReleaseFilter::parse_selector(term).map_err(CliError::input)?);
+            }
+
             let rt =
                 tokio::runtime::Runtime::new().map_err(|e| CliError::internal(e.to_string()))?;
             rt.block_on(commands::list::run(
                 namespace.as_deref(),
                 all_namespaces,
+                &filter,
                 json,
+                &kube,
             ))
         }
 
@@ -996,11 +1606,20 @@ fn run_command(cli: Cli) -> error::Result<()> {
             name,
             namespace,
             max,
+            diff,
             json,
         } => {
+            let namespace = kube.namespace(namespace);
             let rt =
                 tokio::runtime::Runtime::new().map_err(|e| CliError::internal(e.to_string()))?;
-            rt.block_on(commands::history::run(&name, &namespace, max, json))
+            rt.block_on(commands::history::run(
+                &name,
+                &namespace,
+                max,
+                diff.as_deref(),
+                json,
+                &kube,
+            ))
         }
 
         Commands::Status {
@@ -1009,8 +1628,10 @@ fn run_command(cli: Cli) -> error::Result<()> {
             resources,
             show_values,
             manifest,
+            provenance,
             json,
         } => {
+            let namespace = kube.namespace(namespace);
             let rt =
                 tokio::runtime::Runtime::new().map_err(|e| CliError::internal(e.to_string()))?;
             rt.block_on(commands::status::run(
@@ -1019,20 +1640,131 @@ fn run_command(cli: Cli) -> error::Result<()> {
                 resources,
                 show_values,
                 manifest,
+                provenance,
                 json,
+                &kube,
             ))
         }
 
-        Commands::Recover { name, namespace } => {
+        Commands::Get(subcmd) => {
             let rt =
                 tokio::runtime::Runtime::new().map_err(|e| CliError::internal(e.to_string()))?;
-            rt.block_on(commands::recover::run(&name, &namespace))
+            match subcmd {
+                GetCommands::Values {
+                    name,
+                    namespace,
+                    revision,
+                    all,
+                    provenance,
+                    output,
+                } => rt.block_on(commands::get::values(
+                    &name,
+                    &kube.namespace(namespace),
+                    revision,
+                    all,
+                    provenance,
+                    output,
+                )),
+                GetCommands::Manifest {
+                    name,
+                    namespace,
+                    revision,
+                } => rt.block_on(commands::get::manifest(
+                    &name,
+                    &kube.namespace(namespace),
+                    revision,
+                )),
+                GetCommands::Hooks {
+                    name,
+                    namespace,
+                    revision,
+                    output,
+                } => rt.block_on(commands::get::hooks(
+                    &name,
+                    &kube.namespace(namespace),
+                    revision,
+                    output,
+                )),
+                GetCommands::Notes {
+                    name,
+                    namespace,
+                    revision,
+                } => rt.block_on(commands::get::notes(
+                    &name,
+                    &kube.namespace(namespace),
+                    revision,
+                )),
+            }
         }
 
-        Commands::Test { name, namespace } => {
+        Commands::Diff {
+            name,
+            namespace,
+            revision,
+            live,
+            three_way,
+            values,
+            set,
+            reveal_secrets,
+            json,
+        } => {
+            let namespace = kube.namespace(namespace);
+            let rt =
+                tokio::runtime::Runtime::new().map_err(|e| CliError::internal(e.to_string()))?;
+            rt.block_on(commands::diff::run(
+                &name,
+                &namespace,
+                revision,
+                live,
+                three_way.as_deref(),
+                &values,
+                &set,
+                reveal_secrets,
+                json,
+                &kube,
+            ))
+        }
+
+        Commands::Recover {
+            name,
+            namespace,
+            state_only,
+        } => {
+            let namespace = kube.namespace(namespace);
+            let rt =
+                tokio::runtime::Runtime::new().map_err(|e| CliError::internal(e.to_string()))?;
+            rt.block_on(commands::recover::run(&name, &namespace, state_only, &kube))
+        }
+
+        Commands::Secret(subcmd) => {
+            let rt =
+                tokio::runtime::Runtime::new().map_err(|e| CliError::internal(e.to_string()))?;
+            match subcmd {
+                SecretCommands::Rotate {
+                    name,
+                    secret,
+                    namespace,
+                } => rt.block_on(commands::secret::rotate(
+                    &name,
+                    &kube.namespace(namespace),
+                    &secret,
+                )),
+            }
+        }
+
+        Commands::Test {
+            name,
+            namespace,
+            timeout,
+            logs,
+            keep,
+        } => {
+            let namespace = kube.namespace(namespace);
             let rt =
                 tokio::runtime::Runtime::new().map_err(|e| CliError::internal(e.to_string()))?;
-            rt.block_on(commands::test::run(&name, &namespace))
+            rt.block_on(commands::test::run(
+                &name, &namespace, timeout, logs, keep, &kube,
+            ))
         }
 
         // Phase 5: Repository management commands
@@ -1055,14 +1787,33 @@ fn run_command(cli: Cli) -> error::Result<()> {
                 )),
                 RepoCommands::List { auth } => rt.block_on(commands::repo::list(auth)),
                 RepoCommands::Update { name } => {
-                    rt.block_on(commands::repo::update(name.as_deref()))
+                    rt.block_on(commands::repo::update(name.as_deref(), cli.offline))
                 }
                 RepoCommands::Remove { name } => rt.block_on(commands::repo::remove(&name)),
+                RepoCommands::Login {
+                    registry,
+                    username,
+                    password_stdin,
+                    token,
+                } => rt.block_on(commands::repo::login(
+                    &registry,
+                    username.as_deref(),
+                    password_stdin,
+                    token.as_deref(),
+                )),
+                RepoCommands::Logout { registry } => rt.block_on(commands::repo::logout(&registry)),
                 RepoCommands::Index { dir, url, merge } => rt.block_on(commands::repo::index(
                     &dir,
                     url.as_deref(),
                     merge.as_deref(),
                 )),
+                RepoCommands::Cache(cmd) => match cmd {
+                    RepoCacheCommands::Stats => rt.block_on(commands::repo::cache_stats()),
+                    RepoCacheCommands::Clear { repo } => {
+                        rt.block_on(commands::repo::cache_clear(repo.as_deref()))
+                    }
+                    RepoCacheCommands::Prune => rt.block_on(commands::repo::cache_prune()),
+                },
             }
         }
 
@@ -1079,6 +1830,7 @@ fn run_command(cli: Cli) -> error::Result<()> {
                 repo.as_deref(),
                 versions,
                 json,
+                cli.offline,
             ))
         }
 
@@ -1087,6 +1839,8 @@ fn run_command(cli: Cli) -> error::Result<()> {
             pack_version,
             output,
             untar,
+            verify_signature: _,
+            key,
         } => {
             let rt =
                 tokio::runtime::Runtime::new().map_err(|e| CliError::internal(e.to_string()))?;
@@ -1095,16 +1849,19 @@ fn run_command(cli: Cli) -> error::Result<()> {
                 pack_version.as_deref(),
                 output.as_ref(),
                 untar,
+                key.as_deref(),
+                cli.offline,
             ))
         }
 
         Commands::Push {
             archive,
             destination,
+            media_type,
         } => {
             let rt =
                 tokio::runtime::Runtime::new().map_err(|e| CliError::internal(e.to_string()))?;
-            rt.block_on(commands::push::run(&archive, &destination))
+            rt.block_on(commands::push::run(&archive, &destination, media_type))
         }
 
         Commands::Dependency(subcmd) => {
@@ -1112,18 +1869,30 @@ fn run_command(cli: Cli) -> error::Result<()> {
                 tokio::runtime::Runtime::new().map_err(|e| CliError::internal(e.to_string()))?;
             match subcmd {
                 DependencyCommands::List { path } => rt.block_on(commands::dep::list(&path)),
-                DependencyCommands::Update { path } => rt.block_on(commands::dep::update(&path)),
+                DependencyCommands::Update { path, name } => {
+                    // A lone argument that isn't a directory names a dependency
+                    let (path, name) = match name {
+                        None if !path.is_dir() => (
+                            PathBuf::from("."),
+                            Some(path.to_string_lossy().into_owned()),
+                        ),
+                        name => (path, name),
+                    };
+                    rt.block_on(commands::dep::update(&path, name.as_deref(), cli.offline))
+                }
                 DependencyCommands::Build { path, verify } => {
-                    rt.block_on(commands::dep::build(&path, verify))
+                    rt.block_on(commands::dep::build(&path, verify, cli.offline))
                 }
-                DependencyCommands::Tree { path } => rt.block_on(commands::dep::tree(&path)),
+                DependencyCommands::Tree {
+                    path,
+                    depth,
+                    duplicates,
+                } => rt.block_on(commands::dep::tree(&path, depth, duplicates)),
             }
         }
 
         Commands::Completion { shell } => {
-            let mut cmd = Cli::command();
-            let bin_name = cmd.get_name().to_string();
-            clap_complete::generate(shell, &mut cmd, bin_name, &mut std::io::stdout());
+            commands::completion::run(shell, Cli::command(), &mut std::io::stdout());
             Ok(())
         }
     }
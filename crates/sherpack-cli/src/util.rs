@@ -1,5 +1,16 @@
 //! Shared utility functions for CLI commands
 
+use console::{Term, style};
+use miette::IntoDiagnostic;
+use sherpack_core::{
+    CoreError, LoadedPack, ValueSource, Values, parse_set_literal_values, parse_set_values,
+};
+use sherpack_engine::ExecPostRenderer;
+use sherpack_kube::{
+    CrdBackup, CrdManager, KubeClient, ProgressFormat, kubeconfig, storage::StorageDriver,
+};
+use std::path::PathBuf;
+
 /// Format a byte size as a human-readable string
 #[must_use]
 pub fn format_size(bytes: u64) -> String {
@@ -28,6 +39,183 @@ pub fn truncate_hash(hash: &str, max_len: usize) -> &str {
     &hash[..end]
 }
 
+/// Build the post-renderer selected by `--post-renderer`
+pub fn post_renderer(command: Option<PathBuf>, args: Vec<String>) -> Option<ExecPostRenderer> {
+    command.map(|command| ExecPostRenderer::new(command).with_args(args))
+}
+
+/// Load the values of `pack` with `--values` files and `--set` flags on top
+///
+/// Every merge is tracked, so [`Values::provenance`] names the defaults,
+/// file or flag that set each path last.
+pub fn load_tracked_values(
+    pack: &LoadedPack,
+    values_files: &[PathBuf],
+    set_values: &[String],
+    set_literal_values: &[String],
+) -> Result<Values, CoreError> {
+    let mut values = Values::new();
+    if let Some(schema) = pack.load_schema()? {
+        let defaults = Values::from(schema.extract_defaults());
+        values.merge_tracked(&defaults, ValueSource::SchemaDefault);
+    }
+    values.merge_tracked(
+        &Values::from_file(&pack.values_path)?,
+        ValueSource::PackDefault,
+    );
+
+    for file in values_files {
+        let source = ValueSource::ValuesFile {
+            path: file.display().to_string(),
+            line: None,
+        };
+        values.merge_tracked(&Values::from_file(file)?, source);
+    }
+
+    // Flags are applied one at a time so each records its own source
+    let user = std::env::var("USER").ok();
+    let source = |flag: &String| ValueSource::CommandLine {
+        flag: flag.clone(),
+        timestamp: chrono::Utc::now(),
+        user: user.clone(),
+    };
+    for flag in set_values {
+        let set = parse_set_values(std::slice::from_ref(flag))?;
+        values.merge_set_tracked(&set, source(flag));
+    }
+    for flag in set_literal_values {
+        let literal = parse_set_literal_values(std::slice::from_ref(flag))?;
+        values.merge_tracked(&literal, source(flag));
+    }
+
+    Ok(values)
+}
+
+/// Cluster chosen with the global `--kubeconfig` and `--kube-context` flags
+#[derive(Debug, Clone, Default)]
+pub struct KubeTarget {
+    /// Kubeconfig file (defaults to `$KUBECONFIG` or `~/.kube/config`)
+    pub kubeconfig: Option<PathBuf>,
+    /// Context (defaults to the kubeconfig's current context)
+    pub context: Option<String>,
+    /// User to impersonate (`--as`)
+    pub impersonate_user: Option<String>,
+    /// Groups to impersonate (`--as-group`)
+    pub impersonate_groups: Vec<String>,
+}
+
+impl KubeTarget {
+    /// Connect to the chosen cluster
+    pub async fn client<S: StorageDriver>(
+        &self,
+        storage: S,
+    ) -> crate::error::Result<KubeClient<S>> {
+        let mut config =
+            kubeconfig::load_config(self.kubeconfig.as_deref(), self.context.as_deref())
+                .await
+                .into_diagnostic()?;
+        kubeconfig::impersonate(
+            &mut config,
+            self.impersonate_user.as_deref(),
+            &self.impersonate_groups,
+        )
+        .into_diagnostic()?;
+
+        KubeClient::from_config(storage, config)
+            .into_diagnostic()
+            .map_err(Into::into)
+    }
+
+    /// `namespace`, or else the namespace of the chosen context, or `default`
+    pub fn namespace(&self, namespace: Option<String>) -> String {
+        namespace
+            .or_else(|| {
+                kubeconfig::context_namespace(self.kubeconfig.as_deref(), self.context.as_deref())
+            })
+            .unwrap_or_else(|| "default".to_string())
+    }
+}
+
+/// Where the human-readable output of a command goes
+///
+/// `--progress json` keeps stdout for NDJSON events, so everything else
+/// goes to stderr.
+pub fn human_output(progress: Option<ProgressFormat>) -> Term {
+    match progress {
+        Some(ProgressFormat::Json) => Term::stderr(),
+        _ => Term::stdout(),
+    }
+}
+
+/// Print the target cluster and its version (for `--debug`)
+pub async fn print_cluster_preflight<S: StorageDriver>(client: &KubeClient<S>) {
+    match client.server_info().await {
+        Ok(info) if info.reachable => eprintln!(
+            "{} Target cluster: Kubernetes {}{} (default namespace: {})",
+            style("DEBUG").dim(),
+            info.kube_version.version,
+            info.platform
+                .map(|p| format!(" on {}", p))
+                .unwrap_or_default(),
+            client.kube_client().default_namespace()
+        ),
+        Ok(_) => eprintln!("{} Target cluster is unreachable", style("DEBUG").dim()),
+        Err(e) => eprintln!(
+            "{} Failed to query cluster version: {}",
+            style("DEBUG").dim(),
+            e
+        ),
+    }
+}
+
+/// Back up the given CRDs and their custom resources before a dangerous change
+///
+/// CRDs not present in the cluster are skipped. Fails if a backup can't be
+/// written, so the change doesn't go ahead without its safety net.
+pub async fn backup_crds<S: StorageDriver>(
+    client: &KubeClient<S>,
+    crd_names: &[String],
+    out: &Term,
+) -> crate::error::Result<Vec<CrdBackup>> {
+    let manager = CrdManager::new(client.kube_client().clone());
+    let dir = dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("sherpack")
+        .join("backups")
+        .join("crds");
+
+    let mut backups = Vec::new();
+    for name in crd_names {
+        let exists = manager
+            .get_crd(name)
+            .await
+            .map_err(|e| miette::miette!("Failed to read CRD {}: {}", name, e))?
+            .is_some();
+        if !exists {
+            continue;
+        }
+
+        let backup = manager.backup(name, &dir, true).await.map_err(|e| {
+            miette::miette!(
+                "Failed to back up CRD {}: {} (use --no-backup to skip)",
+                name,
+                e
+            )
+        })?;
+        out.write_line(&format!(
+            "{} Backed up CRD {} and {} resource(s) to {}",
+            style("→").blue(),
+            name,
+            backup.resources,
+            backup.path.display()
+        ))
+        .into_diagnostic()?;
+        backups.push(backup);
+    }
+
+    Ok(backups)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -130,6 +130,206 @@ mod lint_command {
         // Should not mention schema validation when skipped
         assert!(!stdout.contains("Validating values against schema"));
     }
+
+    #[test]
+    fn test_lint_json_output() {
+        let output = sherpack(&["lint", &fixture_pack("simple-pack"), "--format", "json"]);
+
+        assert!(output.status.success());
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(report["passed"], true);
+        let findings = report["findings"].as_array().unwrap();
+        assert!(
+            findings
+                .iter()
+                .any(|f| f["severity"] == "warning" && f["rule"] == "no_schema")
+        );
+
+        // Warnings fail the lint in strict mode
+        let output = sherpack(&[
+            "lint",
+            &fixture_pack("simple-pack"),
+            "--format",
+            "json",
+            "--strict",
+        ]);
+        assert!(!output.status.success());
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(report["passed"], false);
+    }
+
+    #[test]
+    fn test_lint_values_coverage() {
+        let pack = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            pack.path().join("Pack.yaml"),
+            "apiVersion: sherpack/v1\nkind: application\nmetadata:\n  name: coverage\n  version: 1.0.0\n",
+        )
+        .unwrap();
+        std::fs::write(
+            pack.path().join("values.yaml"),
+            "image:\n  repository: nginx\nports:\n  - 80\npodLabels: {}\n",
+        )
+        .unwrap();
+        std::fs::create_dir(pack.path().join("templates")).unwrap();
+        std::fs::write(
+            pack.path().join("templates/deployment.yaml"),
+            r#"image: {{ values.image.repository }}:{{ values.image.tag }}
+{% for port in values.ports %}port: {{ port }}{% endfor %}
+team: {{ values.podLabels.team }}
+# sherpack-lint-ignore: values-coverage
+legacy: {{ values.legacyName }}
+debug: {{ values.debug | default(false) }}
+"#,
+        )
+        .unwrap();
+
+        let output = sherpack(&["lint", pack.path().to_str().unwrap(), "--format", "json"]);
+
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let coverage: Vec<_> = report["findings"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|f| f["rule"] == "values-coverage")
+            .collect();
+        assert_eq!(coverage.len(), 1, "{:?}", coverage);
+        assert_eq!(coverage[0]["severity"], "warning");
+        assert_eq!(coverage[0]["path"], "templates/deployment.yaml");
+        assert_eq!(coverage[0]["line"], 1);
+        assert!(
+            coverage[0]["message"]
+                .as_str()
+                .unwrap()
+                .contains("values.image.tag")
+        );
+    }
+
+    #[test]
+    fn test_lint_flags_now_inside_date() {
+        let pack = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            pack.path().join("Pack.yaml"),
+            "apiVersion: sherpack/v1\nkind: application\nmetadata:\n  name: dated\n  version: 1.0.0\n",
+        )
+        .unwrap();
+        std::fs::write(pack.path().join("values.yaml"), "ttl: 60\n").unwrap();
+        std::fs::create_dir(pack.path().join("templates")).unwrap();
+        std::fs::write(
+            pack.path().join("templates/cm.yaml"),
+            "stamp: {{ now() | date(\"2006-01-02\") }}\nttl: {{ values.ttl | duration }}\n",
+        )
+        .unwrap();
+
+        let output = sherpack(&["lint", pack.path().to_str().unwrap(), "--format", "json"]);
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let flagged: Vec<_> = report["findings"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|f| f["rule"] == "non-deterministic")
+            .collect();
+
+        // Only now() is flagged; date() and duration() of fixed inputs are stable
+        assert_eq!(flagged.len(), 1, "{:?}", flagged);
+        assert_eq!(flagged[0]["line"], 1);
+    }
+
+    #[test]
+    fn test_lint_non_deterministic_functions() {
+        let pack = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            pack.path().join("Pack.yaml"),
+            "apiVersion: sherpack/v1\nkind: application\nmetadata:\n  name: gitops\n  version: 1.0.0\n",
+        )
+        .unwrap();
+        std::fs::write(pack.path().join("values.yaml"), "name: app\n").unwrap();
+        std::fs::create_dir(pack.path().join("templates")).unwrap();
+        std::fs::write(
+            pack.path().join("templates/secret.yaml"),
+            r#"deployedAt: "{{ now() }}"
+id: {{ uuidv4() }}
+password: {{ generate_secret("db-password", 16) }}
+token: {{ generate_secret("api-token", 32) }}
+"#,
+        )
+        .unwrap();
+        let state = pack.path().join("secrets.json");
+        std::fs::write(
+            &state,
+            r#"{"secrets": {"db-password": {"value": "s3cr3t", "charset": "alphanumeric", "length": 16, "created_at": "2024-01-01T00:00:00Z"}}}"#,
+        )
+        .unwrap();
+
+        let findings = |extra: &[&str]| {
+            let mut args = vec!["lint", pack.path().to_str().unwrap(), "--format", "json"];
+            args.extend_from_slice(extra);
+            let output = sherpack(&args);
+            let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+            report["findings"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .filter(|f| f["rule"] == "non-deterministic")
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+
+        let unbacked = findings(&[]);
+        assert_eq!(unbacked.len(), 4, "{:?}", unbacked);
+        assert_eq!(unbacked[0]["severity"], "warning");
+        assert_eq!(unbacked[0]["path"], "templates/secret.yaml");
+        assert_eq!(unbacked[0]["line"], 1);
+        assert!(unbacked[0]["message"].as_str().unwrap().contains("now()"));
+        assert!(
+            unbacked[1]["message"]
+                .as_str()
+                .unwrap()
+                .contains("uuidv4()")
+        );
+
+        let backed = findings(&["--strict", "--secret-state", state.to_str().unwrap()]);
+        assert_eq!(backed.len(), 3, "{:?}", backed);
+        assert_eq!(backed[2]["severity"], "error");
+        assert!(
+            backed[2]["message"]
+                .as_str()
+                .unwrap()
+                .contains("generate_secret(\"api-token\")")
+        );
+    }
+
+    #[test]
+    fn test_lint_json_template_error_location() {
+        let pack = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            pack.path().join("Pack.yaml"),
+            "apiVersion: sherpack/v1\nkind: application\nmetadata:\n  name: broken\n  version: 1.0.0\n",
+        )
+        .unwrap();
+        std::fs::write(pack.path().join("values.yaml"), "name: app\n").unwrap();
+        std::fs::create_dir(pack.path().join("templates")).unwrap();
+        std::fs::write(
+            pack.path().join("templates/configmap.yaml"),
+            "kind: ConfigMap\nname: {{ values.name | nosuchfilter }}\n",
+        )
+        .unwrap();
+
+        let output = sherpack(&["lint", pack.path().to_str().unwrap(), "--format", "json"]);
+
+        assert!(!output.status.success());
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(report["passed"], false);
+        let error = report["findings"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|f| f["severity"] == "error")
+            .expect("template error finding");
+        assert_eq!(error["rule"], "unknown_filter");
+        assert_eq!(error["path"], "templates/configmap.yaml");
+        assert_eq!(error["line"], 2);
+    }
 }
 
 mod template_command {
@@ -159,6 +359,25 @@ mod template_command {
         assert!(stdout.contains("customapp"));
     }
 
+    #[test]
+    fn test_template_set_deletes_default() {
+        let output = sherpack(&[
+            "template",
+            "myrelease",
+            &fixture_pack("demo-pack"),
+            "--set",
+            "labels.costCenter-",
+            "--set",
+            "labels.environment=null",
+        ]);
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("platform"));
+        assert!(!stdout.contains("CC-1234"));
+        assert!(!stdout.contains("staging"));
+    }
+
     #[test]
     fn test_template_show_only() {
         let output = sherpack(&[
@@ -176,6 +395,44 @@ mod template_command {
         assert!(!stdout.contains("# Source: configmap.yaml"));
     }
 
+    #[test]
+    fn test_template_show_only_multiple_and_glob() {
+        let output = sherpack(&[
+            "template",
+            "myrelease",
+            &fixture_pack("demo-pack"),
+            "-s",
+            "service.yaml",
+            "-s",
+            "templates/d*.yaml",
+        ]);
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let deployment = stdout.find("# Source: deployment.yaml").unwrap();
+        let service = stdout.find("# Source: service.yaml").unwrap();
+        // Path order, not argument order
+        assert!(deployment < service);
+        assert!(stdout[deployment..service].contains("\n---\n"));
+        assert!(!stdout.contains("# Source: configmap.yaml"));
+    }
+
+    #[test]
+    fn test_template_show_only_no_match() {
+        let output = sherpack(&[
+            "template",
+            "myrelease",
+            &fixture_pack("demo-pack"),
+            "-s",
+            "deploymnet.yaml",
+        ]);
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("No template matches --show-only 'deploymnet.yaml'"));
+        assert!(stderr.contains("deployment.yaml"));
+    }
+
     #[test]
     fn test_template_skip_schema() {
         // Test that --skip-schema allows invalid values
@@ -234,6 +491,250 @@ mod template_command {
         let stdout = String::from_utf8_lossy(&output.stdout);
         assert!(stdout.contains("# Computed Values"));
     }
+
+    #[test]
+    fn test_template_invalid_kube_version() {
+        let output = sherpack(&[
+            "template",
+            "myrelease",
+            &fixture_pack("demo-pack"),
+            "--kube-version",
+            "latest",
+        ]);
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("Invalid --kube-version"));
+    }
+
+    #[test]
+    fn test_template_kube_version_constraint() {
+        let pack = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            pack.path().join("Pack.yaml"),
+            "apiVersion: sherpack/v1\nmetadata:\n  name: modern\n  version: 1.0.0\n  kubeVersion: \">= 1.27.0-0\"\n",
+        )
+        .unwrap();
+        std::fs::create_dir(pack.path().join("templates")).unwrap();
+        std::fs::write(
+            pack.path().join("templates/cm.yaml"),
+            "kind: ConfigMap\nversion: {{ capabilities.kubeVersion.minor }}\n",
+        )
+        .unwrap();
+        let pack = pack.path().to_str().unwrap();
+
+        let output = sherpack(&["template", "myrelease", pack, "--kube-version", "1.29.4"]);
+        assert!(output.status.success());
+        assert!(String::from_utf8_lossy(&output.stdout).contains("version: 29"));
+
+        let output = sherpack(&["template", "myrelease", pack, "--kube-version", "v1.24.0"]);
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            stderr.contains("requires Kubernetes >= 1.27.0-0"),
+            "{}",
+            stderr
+        );
+
+        let output = sherpack(&[
+            "template",
+            "myrelease",
+            pack,
+            "--kube-version",
+            "1.24.0",
+            "--skip-kube-version-check",
+        ]);
+        assert!(output.status.success());
+    }
+
+    #[test]
+    fn test_template_library_pack() {
+        let pack = tempfile::TempDir::new().unwrap();
+        let write = |path: &str, content: &str| {
+            let path = pack.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        write(
+            "Pack.yaml",
+            "apiVersion: sherpack/v1\nkind: application\nmetadata:\n  name: web\n  version: 1.0.0\n\
+             dependencies:\n  - name: common\n    version: 1.0.0\n",
+        );
+        write(
+            "templates/app.yaml",
+            "{% import \"common/_labels.j2\" as common %}\nlabels: {{ common.labels() }}\n",
+        );
+        write(
+            "charts/common/Pack.yaml",
+            "apiVersion: sherpack/v1\nkind: library\nmetadata:\n  name: common\n  version: 1.0.0\n",
+        );
+        write(
+            "charts/common/templates/_labels.j2",
+            "{% macro labels() %}app={{ release.name }}{% endmacro %}\n",
+        );
+
+        // Used as a dependency, the library provides its macros
+        let output = sherpack(&["template", "myrelease", pack.path().to_str().unwrap()]);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(output.status.success(), "{}", stdout);
+        assert!(stdout.contains("labels: app=myrelease"), "{}", stdout);
+
+        // On its own, it is refused
+        let library = pack.path().join("charts/common");
+        let output = sherpack(&["template", "myrelease", library.to_str().unwrap()]);
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("'common' is a library pack"), "{}", stderr);
+
+        // And lint sees the library's macros
+        let output = sherpack(&["lint", pack.path().to_str().unwrap()]);
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stdout)
+        );
+    }
+
+    #[test]
+    fn test_template_secret_seed() {
+        let pack = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            pack.path().join("Pack.yaml"),
+            "apiVersion: sherpack/v1\nkind: application\nmetadata:\n  name: seeded\n  version: 1.0.0\n",
+        )
+        .unwrap();
+        std::fs::create_dir(pack.path().join("templates")).unwrap();
+        std::fs::write(
+            pack.path().join("templates/secret.yaml"),
+            "password: {{ generate_secret(\"db-password\", 16) }}\n",
+        )
+        .unwrap();
+        let pack = pack.path().to_str().unwrap();
+
+        let render = |extra: &[&str]| {
+            let mut args = vec!["template", "myrelease", pack];
+            args.extend_from_slice(extra);
+            let output = sherpack(&args);
+            assert!(output.status.success());
+            output
+        };
+
+        let seeded = render(&["--secret-seed", "dev"]);
+        assert!(String::from_utf8_lossy(&seeded.stderr).contains("Do not use in production"));
+        assert_eq!(render(&["--secret-seed", "dev"]).stdout, seeded.stdout);
+        assert_ne!(render(&["--secret-seed", "ci"]).stdout, seeded.stdout);
+        assert_ne!(render(&[]).stdout, seeded.stdout);
+    }
+
+    #[test]
+    fn test_template_api_versions() {
+        let pack = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            pack.path().join("Pack.yaml"),
+            "apiVersion: sherpack/v1\nkind: application\nmetadata:\n  name: caps\n  version: 1.0.0\n",
+        )
+        .unwrap();
+        std::fs::create_dir(pack.path().join("templates")).unwrap();
+        std::fs::write(
+            pack.path().join("templates/caps.yaml"),
+            concat!(
+                "monitor: {% if capabilities.apiVersions.has(\"monitoring.coreos.com/v1/ServiceMonitor\") %}yes{% else %}no{% endif %}\n",
+                "gateway: {% if capabilities.apiVersions.has(\"gateway.networking.k8s.io/v1\") %}yes{% else %}no{% endif %}\n",
+            ),
+        )
+        .unwrap();
+
+        let output = sherpack(&[
+            "template",
+            "myrelease",
+            pack.path().to_str().unwrap(),
+            "--api-versions",
+            "monitoring.coreos.com/v1/ServiceMonitor",
+        ]);
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("monitor: yes"), "{}", stdout);
+        assert!(stdout.contains("gateway: no"), "{}", stdout);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_template_post_renderer() {
+        let output = sherpack(&[
+            "template",
+            "myrelease",
+            &fixture_pack("demo-pack"),
+            "-s",
+            "deployment",
+            "--post-renderer",
+            "sed",
+            "--post-renderer-args",
+            "s/^kind: Deployment$/kind: Deployment\\nmetadata_patched: true/",
+        ]);
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("# Source: post-rendered.yaml"));
+        assert!(stdout.contains("metadata_patched: true"));
+    }
+
+    #[test]
+    fn test_template_validate_offline_falls_back() {
+        let temp = tempfile::TempDir::new().unwrap();
+
+        let output = Command::new(env!("CARGO_BIN_EXE_sherpack"))
+            .args([
+                "template",
+                "myrelease",
+                &fixture_pack("demo-pack"),
+                "--validate",
+            ])
+            .env("KUBECONFIG", temp.path().join("missing-kubeconfig"))
+            .env("HOME", temp.path())
+            .env_remove("KUBERNETES_SERVICE_HOST")
+            .output()
+            .expect("Failed to execute sherpack");
+
+        assert!(output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("validated against the pack schema only"));
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("apiVersion"));
+    }
+
+    #[test]
+    fn test_template_output_dir_layout() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let out = temp.path().join("out");
+
+        let output = sherpack(&[
+            "template",
+            "myrelease",
+            &fixture_pack("pack-with-subcharts"),
+            "--output-dir",
+            out.to_str().unwrap(),
+        ]);
+        assert!(output.status.success());
+
+        let pack_dir = out.join("app-with-deps");
+        let parent = pack_dir.join("templates/deployment.yaml");
+        let subchart = pack_dir.join("charts/redis/templates/deployment.yaml");
+        assert!(parent.is_file());
+        assert!(subchart.is_file());
+        // postgresql is disabled, so nothing is written for it
+        assert!(!pack_dir.join("charts/postgresql").exists());
+
+        let content = std::fs::read_to_string(&subchart).unwrap();
+        assert!(
+            content.starts_with(
+                "---\n# Source: app-with-deps/charts/redis/templates/deployment.yaml\n"
+            )
+        );
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("Wrote 2 file(s)"));
+    }
 }
 
 mod show_command {
@@ -281,6 +782,32 @@ mod package_command {
         assert!(stdout.contains("Created"));
     }
 
+    #[test]
+    fn test_package_with_blake3_hash() {
+        let temp = TempDir::new().unwrap();
+        let output_path = temp.path().join("test.tar.gz");
+
+        let output = sherpack(&[
+            "package",
+            &fixture_pack("demo-pack"),
+            "-o",
+            output_path.to_str().unwrap(),
+            "--hash",
+            "blake3",
+        ]);
+        assert!(output.status.success());
+
+        let output = sherpack(&["inspect", output_path.to_str().unwrap(), "--manifest"]);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("Pack.yaml blake3:"));
+        assert!(!stdout.contains("Pack.yaml sha256:"));
+
+        let output = sherpack(&["verify", output_path.to_str().unwrap()]);
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("All file checksums match"));
+    }
+
     #[test]
     fn test_package_default_output_name() {
         let temp = TempDir::new().unwrap();
@@ -698,6 +1225,79 @@ mod sign_and_verify_command {
         let stdout = String::from_utf8_lossy(&output.stdout);
         assert!(stdout.contains("[FAIL]") || stdout.contains("failed"));
     }
+
+    /// Package and sign the demo pack, returning (archive, public key)
+    fn signed_archive(temp: &TempDir) -> (std::path::PathBuf, std::path::PathBuf) {
+        let key_dir = temp.path().join("keys");
+        let archive_path = temp.path().join("test.tar.gz");
+
+        sherpack(&["keygen", "-o", key_dir.to_str().unwrap(), "--no-password"]);
+        sherpack(&[
+            "package",
+            &fixture_pack("demo-pack"),
+            "-o",
+            archive_path.to_str().unwrap(),
+        ]);
+        sherpack(&[
+            "sign",
+            archive_path.to_str().unwrap(),
+            "-k",
+            key_dir.join("sherpack.key").to_str().unwrap(),
+        ]);
+
+        (archive_path, key_dir.join("sherpack.pub"))
+    }
+
+    #[test]
+    fn test_verify_require_signature_with_valid_signature() {
+        let temp = TempDir::new().unwrap();
+        let (archive_path, public_key) = signed_archive(&temp);
+
+        let output = sherpack(&[
+            "verify",
+            archive_path.to_str().unwrap(),
+            "-k",
+            public_key.to_str().unwrap(),
+            "--require-signature",
+        ]);
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("Signature valid"));
+        assert!(stdout.contains("All file checksums match"));
+    }
+
+    #[test]
+    fn test_verify_rejects_tampered_manifest() {
+        let temp = TempDir::new().unwrap();
+        let (archive_path, public_key) = signed_archive(&temp);
+
+        // Repackage modified content over the signed archive: the new
+        // MANIFEST matches the files, but the signature no longer does
+        let extracted = temp.path().join("extracted");
+        sherpack_core::extract_archive(&archive_path, &extracted).unwrap();
+        std::fs::write(extracted.join("values.yaml"), "injected: true\n").unwrap();
+        let output = sherpack(&[
+            "package",
+            extracted.to_str().unwrap(),
+            "-o",
+            archive_path.to_str().unwrap(),
+        ]);
+        assert!(output.status.success());
+
+        let output = sherpack(&[
+            "verify",
+            archive_path.to_str().unwrap(),
+            "-k",
+            public_key.to_str().unwrap(),
+        ]);
+
+        assert!(!output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("Signature verification failed"));
+        // Checksums are never reported as trusted
+        assert!(!stdout.contains("All file checksums match"));
+    }
 }
 
 // ============================================================================
@@ -749,6 +1349,53 @@ mod repo_command {
         assert!(!output.status.success());
     }
 
+    #[test]
+    fn test_repo_login_logout() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let run = |args: &[&str]| {
+            Command::new(env!("CARGO_BIN_EXE_sherpack"))
+                .args(args)
+                .env("HOME", temp.path())
+                .env("XDG_CONFIG_HOME", temp.path().join("config"))
+                .output()
+                .expect("Failed to execute sherpack")
+        };
+
+        // HTTP repositories have no token exchange, so no network is needed
+        let output = run(&[
+            "repo",
+            "login",
+            "https://charts.example.com/private/",
+            "--token",
+            "secret",
+        ]);
+        assert!(output.status.success(), "{:?}", output);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("Login succeeded for charts.example.com/private"));
+
+        let stored = temp.path().join("config/sherpack/credentials.yaml");
+        let content = std::fs::read_to_string(&stored).unwrap();
+        assert!(content.contains("charts.example.com/private"));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&stored).unwrap().permissions().mode();
+            assert_eq!(
+                mode & 0o077,
+                0,
+                "credentials must not be group/world readable"
+            );
+        }
+
+        let output = run(&["repo", "logout", "charts.example.com/private"]);
+        assert!(output.status.success(), "{:?}", output);
+
+        let output = run(&["repo", "logout", "charts.example.com/private"]);
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("Not logged in to charts.example.com/private"));
+    }
+
     #[test]
     fn test_repo_update_nonexistent() {
         let output = sherpack(&["repo", "update", "nonexistent-repo-xyz"]);
@@ -929,6 +1576,42 @@ mod convert_command {
         );
     }
 
+    #[test]
+    fn test_convert_writes_report() {
+        let temp_dir = TempDir::new().unwrap();
+        let output_path = temp_dir.path().join("nginx-sherpack");
+        let report_path = temp_dir.path().join("report.json");
+
+        let output = sherpack(&[
+            "convert",
+            &format!("{}/helm-nginx", fixtures_path()),
+            "--output",
+            output_path.to_str().unwrap(),
+            "--report",
+            report_path.to_str().unwrap(),
+            "--json",
+        ]);
+        assert!(
+            output.status.success(),
+            "Convert failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let report: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&report_path).unwrap()).unwrap();
+        // --json prints the same report
+        let printed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(printed, report);
+        assert_eq!(report["dryRun"], false);
+        assert!(report["coverage"]["total"].is_u64());
+        let converted = report["convertedFiles"].as_array().unwrap();
+        assert!(converted.contains(&serde_json::json!("Pack.yaml")));
+        assert!(converted.contains(&serde_json::json!("templates/deployment.yaml")));
+        for severity in ["info", "warning", "unsupported", "error"] {
+            assert!(report["counts"][severity].is_u64(), "{}", severity);
+        }
+    }
+
     #[test]
     fn test_convert_e2e_render_after_convert() {
         // E2E test: convert Helm chart → lint converted pack → render templates
@@ -1102,6 +1785,101 @@ mod dependency_command {
     }
 }
 
+mod diff_command {
+    use super::*;
+
+    #[test]
+    fn test_diff_modes_conflict() {
+        let output = sherpack(&["diff", "myrelease", "--revision", "1", "--live"]);
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("cannot be used with"), "stderr: {}", stderr);
+    }
+
+    #[test]
+    fn test_diff_values_require_three_way() {
+        let output = sherpack(&["diff", "myrelease", "--set", "replicas=2"]);
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("--three-way"), "stderr: {}", stderr);
+    }
+}
+
+mod install_command {
+    use super::*;
+
+    /// Write a kubeconfig for a cluster nothing listens on
+    fn unreachable_kubeconfig(dir: &Path) -> PathBuf {
+        let path = dir.join("kubeconfig");
+        std::fs::write(
+            &path,
+            r#"apiVersion: v1
+kind: Config
+clusters:
+- name: test
+  cluster:
+    server: http://127.0.0.1:1
+contexts:
+- name: test
+  context:
+    cluster: test
+    user: test
+current-context: test
+users:
+- name: test
+  user: {}
+"#,
+        )
+        .unwrap();
+        path
+    }
+
+    #[test]
+    fn test_progress_json_keeps_stdout_ndjson() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let kubeconfig = unreachable_kubeconfig(temp.path());
+        let pack = fixture_pack("demo-pack");
+
+        for (args, banner) in [
+            (vec!["install", "web", &pack], "Installing pack demo-pack"),
+            (
+                vec!["upgrade", "web", &pack, "--install"],
+                "Upgrading release web",
+            ),
+        ] {
+            let output = Command::new(env!("CARGO_BIN_EXE_sherpack"))
+                .args(&args)
+                .args([
+                    "--dry-run",
+                    "--skip-kube-version-check",
+                    "--progress",
+                    "json",
+                ])
+                .env("KUBECONFIG", &kubeconfig)
+                .env("HOME", temp.path())
+                .env_remove("XDG_DATA_HOME")
+                .env_remove("KUBERNETES_SERVICE_HOST")
+                .output()
+                .expect("Failed to execute sherpack");
+
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            assert!(output.status.success(), "{}", stderr);
+            for line in String::from_utf8_lossy(&output.stdout).lines() {
+                assert!(
+                    serde_json::from_str::<serde_json::Value>(line).is_ok(),
+                    "not NDJSON: {}",
+                    line
+                );
+            }
+            // Human-readable output moves to stderr
+            assert!(stderr.contains(banner), "{}", stderr);
+            assert!(stderr.contains("Dry run (client)"), "{}", stderr);
+        }
+    }
+}
+
 mod files_api {
     use super::*;
     use std::fs;
@@ -1565,3 +2343,461 @@ mod repo_index_command {
         assert!(combined.contains("No *.tgz archives"));
     }
 }
+
+mod completion_command {
+    use super::*;
+
+    #[test]
+    fn test_completion_all_shells() {
+        for shell in ["bash", "zsh", "fish", "powershell", "elvish"] {
+            let output = sherpack(&["completion", shell]);
+
+            assert!(output.status.success(), "{} completion failed", shell);
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            assert!(stdout.contains("sherpack"), "{} script is empty", shell);
+        }
+    }
+
+    // `dirs::config_dir()` only follows XDG_CONFIG_HOME on Linux
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_completion_offers_configured_repos() {
+        let config_dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(config_dir.path().join("sherpack")).unwrap();
+        std::fs::write(
+            config_dir.path().join("sherpack/repositories.yaml"),
+            "repositories:\n  - name: bitnami-mirror\n    url: https://charts.example.com\n",
+        )
+        .unwrap();
+
+        let output = Command::new(env!("CARGO_BIN_EXE_sherpack"))
+            .args(["completion", "bash"])
+            .env("XDG_CONFIG_HOME", config_dir.path())
+            .output()
+            .expect("Failed to execute sherpack");
+
+        assert!(output.status.success());
+        assert!(String::from_utf8_lossy(&output.stdout).contains("bitnami-mirror"));
+    }
+}
+
+// `dirs::data_dir()` only follows XDG_DATA_HOME on Linux
+#[cfg(target_os = "linux")]
+mod get_command {
+    use super::*;
+    use sherpack_core::Values;
+    use sherpack_kube::storage::{FileDriver, StorageConfig, StorageDriver};
+    use sherpack_kube::{StoredRelease, ValueSource, ValuesProvenance};
+    use tempfile::TempDir;
+
+    /// Store a release under a temporary data dir and return that dir
+    fn store_release() -> TempDir {
+        let data_dir = TempDir::new().unwrap();
+
+        let values = Values::from_yaml("replicas: 3\nimage:\n  tag: \"1.25\"\n").unwrap();
+        let mut provenance = ValuesProvenance::default();
+        provenance.record(
+            "replicas",
+            ValueSource::ValuesFile {
+                path: "prod.yaml".to_string(),
+                line: None,
+            },
+        );
+
+        let pack = serde_yaml::from_str("name: web\nversion: 1.0.0\n").unwrap();
+        let mut release = StoredRelease::for_install(
+            "web".to_string(),
+            "default".to_string(),
+            pack,
+            values,
+            "apiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: web\n".to_string(),
+        );
+        release.values_provenance = provenance;
+        release.notes = Some("Visit http://web.local".to_string());
+
+        let storage = FileDriver::new(
+            data_dir.path().join("sherpack").join("releases"),
+            StorageConfig::default(),
+        )
+        .unwrap();
+        tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(storage.create(&release))
+            .unwrap();
+
+        data_dir
+    }
+
+    fn get(data_dir: &TempDir, args: &[&str]) -> std::process::Output {
+        Command::new(env!("CARGO_BIN_EXE_sherpack"))
+            .arg("get")
+            .args(args)
+            .env("XDG_DATA_HOME", data_dir.path())
+            .output()
+            .expect("Failed to execute sherpack")
+    }
+
+    #[test]
+    fn test_get_values_user_supplied_and_all() {
+        let data_dir = store_release();
+
+        let output = get(&data_dir, &["values", "web"]);
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert_eq!(stdout.trim(), "replicas: 3");
+
+        let output = get(&data_dir, &["values", "web", "--all", "-o", "json"]);
+        assert!(output.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(json["image"]["tag"], "1.25");
+        assert_eq!(json["replicas"], 3);
+    }
+
+    #[test]
+    fn test_get_manifest_and_notes() {
+        let data_dir = store_release();
+
+        let output = get(&data_dir, &["manifest", "web", "--revision", "1"]);
+        assert!(output.status.success());
+        assert!(String::from_utf8_lossy(&output.stdout).contains("kind: ConfigMap"));
+
+        let output = get(&data_dir, &["notes", "web"]);
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout).trim(),
+            "Visit http://web.local"
+        );
+    }
+
+    #[test]
+    fn test_get_values_provenance() {
+        let data_dir = store_release();
+
+        let output = get(&data_dir, &["values", "web", "--provenance", "--all"]);
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert!(lines[0].starts_with("PATH"));
+        // Sorted by path, defaults marked as such
+        assert!(lines[1].starts_with("image.tag") && lines[1].ends_with("default"));
+        assert!(lines[2].starts_with("replicas") && lines[2].ends_with("prod.yaml"));
+
+        let output = get(&data_dir, &["values", "web", "--provenance", "-o", "json"]);
+        assert!(output.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(json.as_array().unwrap().len(), 1);
+        assert_eq!(json[0]["path"], "replicas");
+        assert_eq!(json[0]["source"]["path"], "prod.yaml");
+    }
+
+    #[test]
+    fn test_get_missing_revision() {
+        let data_dir = store_release();
+
+        let output = get(&data_dir, &["manifest", "web", "--revision", "7"]);
+        assert!(!output.status.success());
+    }
+}
+
+// `dirs::data_dir()` only follows XDG_DATA_HOME on Linux
+#[cfg(target_os = "linux")]
+mod secret_command {
+    use super::*;
+    use sherpack_core::{SecretGenerator, Values};
+    use sherpack_kube::storage::{FileDriver, StorageConfig, StorageDriver};
+    use sherpack_kube::{ReleaseState, StoredRelease};
+    use tempfile::TempDir;
+
+    fn storage(data_dir: &TempDir) -> FileDriver {
+        FileDriver::new(
+            data_dir.path().join("sherpack").join("releases"),
+            StorageConfig::default(),
+        )
+        .unwrap()
+    }
+
+    /// Store a deployed release holding one generated secret
+    fn store_release(state: ReleaseState) -> (TempDir, String) {
+        let data_dir = TempDir::new().unwrap();
+
+        let mut generator = SecretGenerator::new();
+        let password = generator.get_or_generate("db-password", 16);
+
+        let pack = serde_yaml::from_str("name: web\nversion: 1.0.0\n").unwrap();
+        let mut release = StoredRelease::for_install(
+            "web".to_string(),
+            "default".to_string(),
+            pack,
+            Values::new(),
+            String::new(),
+        );
+        release.state = state;
+        release.secrets = generator.into_state();
+
+        tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(storage(&data_dir).create(&release))
+            .unwrap();
+
+        (data_dir, password)
+    }
+
+    fn secret(data_dir: &TempDir, args: &[&str]) -> std::process::Output {
+        Command::new(env!("CARGO_BIN_EXE_sherpack"))
+            .arg("secret")
+            .args(args)
+            .env("XDG_DATA_HOME", data_dir.path())
+            .output()
+            .expect("Failed to execute sherpack")
+    }
+
+    #[test]
+    fn test_secret_rotate_keeps_previous_value() {
+        let (data_dir, password) = store_release(ReleaseState::Deployed);
+
+        let output = secret(&data_dir, &["rotate", "web", "db-password"]);
+        assert!(
+            output.status.success(),
+            "stderr: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let release = tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(storage(&data_dir).get_latest("default", "web"))
+            .unwrap();
+        let entry = release.secrets.get("db-password").unwrap();
+        assert_ne!(entry.value(), password);
+        assert_eq!(entry.value().len(), 16);
+        assert_eq!(entry.previous_value(), Some(password.as_str()));
+        assert_eq!(release.version, 1);
+    }
+
+    #[test]
+    fn test_secret_rotate_unknown_secret() {
+        let (data_dir, _) = store_release(ReleaseState::Deployed);
+
+        let output = secret(&data_dir, &["rotate", "web", "api-key"]);
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("no generated secret named 'api-key'"));
+        assert!(stderr.contains("db-password"));
+    }
+
+    #[test]
+    fn test_secret_rotate_refuses_pending_release() {
+        let (data_dir, password) = store_release(ReleaseState::PendingUpgrade {
+            started_at: chrono::Utc::now(),
+            timeout: chrono::Duration::minutes(5),
+            previous_version: 1,
+            owner: None,
+        });
+
+        let output = secret(&data_dir, &["rotate", "web", "db-password"]);
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("locked"));
+
+        let release = tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(storage(&data_dir).get_latest("default", "web"))
+            .unwrap();
+        assert_eq!(
+            release.secrets.get_value("db-password"),
+            Some(password.as_str())
+        );
+    }
+}
+
+// `dirs` only follows XDG_CONFIG_HOME / XDG_CACHE_HOME on Linux
+#[cfg(target_os = "linux")]
+mod offline_mode {
+    use super::*;
+    use sherpack_repo::{ArchiveCache, IndexCache, PackEntry};
+
+    const REPO_URL: &str = "https://charts.invalid";
+
+    /// Config and cache directories with nginx cached and redis only indexed
+    fn cached_environment() -> tempfile::TempDir {
+        let home = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(home.path().join("config/sherpack")).unwrap();
+        std::fs::write(
+            home.path().join("config/sherpack/repositories.yaml"),
+            format!("repositories:\n  - name: stable\n    url: {}\n", REPO_URL),
+        )
+        .unwrap();
+
+        let mut index = IndexCache::open_at(&home.path().join("cache/sherpack/index.db")).unwrap();
+        index
+            .upsert_repository("stable", REPO_URL, "http", None)
+            .unwrap();
+        let entry = |name: &str| PackEntry {
+            name: name.to_string(),
+            version: "1.0.0".to_string(),
+            ..Default::default()
+        };
+        index
+            .add_packs("stable", &[entry("nginx"), entry("redis")])
+            .unwrap();
+
+        ArchiveCache::at(home.path().join("cache/sherpack/archives"))
+            .put(REPO_URL, "nginx", "1.0.0", b"cached archive")
+            .unwrap();
+
+        home
+    }
+
+    fn sherpack_in(home: &Path, args: &[&str], env: &[(&str, &str)]) -> std::process::Output {
+        Command::new(env!("CARGO_BIN_EXE_sherpack"))
+            .args(args)
+            .current_dir(home)
+            .env("XDG_CONFIG_HOME", home.join("config"))
+            .env("XDG_CACHE_HOME", home.join("cache"))
+            .envs(env.iter().copied())
+            .output()
+            .expect("Failed to execute sherpack")
+    }
+
+    #[test]
+    fn test_pull_cached_pack_offline() {
+        let home = cached_environment();
+
+        let output = sherpack_in(
+            home.path(),
+            &["pull", "stable/nginx", "--offline", "-o", "nginx.tgz"],
+            &[],
+        );
+
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert_eq!(
+            std::fs::read(home.path().join("nginx.tgz")).unwrap(),
+            b"cached archive"
+        );
+    }
+
+    #[test]
+    fn test_pull_uncached_pack_offline_fails() {
+        let home = cached_environment();
+
+        let output = sherpack_in(
+            home.path(),
+            &["pull", "stable/redis:1.0.0"],
+            &[("SHERPACK_OFFLINE", "true")],
+        );
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("Offline mode"), "{}", stderr);
+        assert!(stderr.contains("redis-1.0.0"), "{}", stderr);
+        assert!(!home.path().join("redis-1.0.0.tgz").exists());
+    }
+
+    #[test]
+    fn test_expired_index_offline_and_prune() {
+        let home = cached_environment();
+        std::fs::write(
+            home.path().join("config/sherpack/repositories.yaml"),
+            format!(
+                "repositories:\n  - name: stable\n    url: {}\ncache:\n  ttlHours: 1\n",
+                REPO_URL
+            ),
+        )
+        .unwrap();
+        rusqlite::Connection::open(home.path().join("cache/sherpack/index.db"))
+            .unwrap()
+            .execute("UPDATE repositories SET last_updated = 0", [])
+            .unwrap();
+
+        // Stale results are still served offline
+        let output = sherpack_in(home.path(), &["search", "nginx", "--offline"], &[]);
+        assert!(output.status.success());
+        assert!(String::from_utf8_lossy(&output.stdout).contains("stable/nginx"));
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("may be stale"), "{}", stderr);
+
+        let output = sherpack_in(home.path(), &["repo", "cache", "stats"], &[]);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("TTL:           1h"), "{}", stdout);
+        assert!(stdout.contains("Expired:       stable"), "{}", stdout);
+
+        let output = sherpack_in(home.path(), &["repo", "cache", "prune"], &[]);
+        assert!(output.status.success());
+        assert!(String::from_utf8_lossy(&output.stdout).contains("stable"));
+
+        let index = IndexCache::open_at(&home.path().join("cache/sherpack/index.db")).unwrap();
+        assert_eq!(index.stats().unwrap().repository_count, 0);
+    }
+
+    #[test]
+    fn test_repo_cache_clear_unknown_repository() {
+        let home = cached_environment();
+
+        let output = sherpack_in(home.path(), &["repo", "cache", "clear", "missing"], &[]);
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("not in the cache"));
+
+        let output = sherpack_in(home.path(), &["repo", "cache", "clear", "stable"], &[]);
+        assert!(output.status.success());
+    }
+}
+
+mod pull_signature {
+    use super::*;
+
+    /// A well-formed P-256 public key; it never has to verify anything here
+    const PUBLIC_KEY: &str = "-----BEGIN PUBLIC KEY-----
+MFkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDQgAEAQIDBAUGBwgJCgsMDQ4PEBESExQVFhcYGRobHB0eHyAhIiMkJSYnKCkqKywtLi8wMTIzNDU2Nzg5Ojs8PT4/QA==
+-----END PUBLIC KEY-----
+";
+
+    #[test]
+    fn test_verify_signature_requires_key() {
+        let output = sherpack(&[
+            "pull",
+            "oci://ghcr.invalid/charts/nginx:1.0.0",
+            "--verify-signature",
+        ]);
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("--key"), "stderr: {}", stderr);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_verify_signature_rejects_http_repository() {
+        let home = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(home.path().join("config/sherpack")).unwrap();
+        std::fs::write(
+            home.path().join("config/sherpack/repositories.yaml"),
+            "repositories:\n  - name: stable\n    url: https://charts.invalid\n",
+        )
+        .unwrap();
+        std::fs::write(home.path().join("cosign.pub"), PUBLIC_KEY).unwrap();
+
+        let output = Command::new(env!("CARGO_BIN_EXE_sherpack"))
+            .args([
+                "pull",
+                "stable/nginx:1.0.0",
+                "--verify-signature",
+                "--key",
+                "cosign.pub",
+            ])
+            .current_dir(home.path())
+            .env("XDG_CONFIG_HOME", home.path().join("config"))
+            .env("XDG_CACHE_HOME", home.path().join("cache"))
+            .output()
+            .expect("Failed to execute sherpack");
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            stderr.contains("only supported for OCI"),
+            "stderr: {}",
+            stderr
+        );
+    }
+}
@@ -10,20 +10,25 @@
 //! - Generates minimal import statements with only used macros
 
 use regex::Regex;
-use std::collections::HashSet;
+use serde::Serialize;
+use sherpack_core::{LoadedPack, ReleaseInfo, TemplateContext, Values};
+use sherpack_engine::{Engine, PackRenderer};
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
 use crate::chart::HelmChart;
 use crate::error::{ConversionWarning, ConvertError, Result, WarningCategory, WarningSeverity};
-use crate::macro_processor::MacroPostProcessor;
+use crate::hooks::rewrite_hook_annotations;
+use crate::macro_processor::{DEFAULT_INLINE_MAX_TOKENS, MacroInliner, MacroPostProcessor};
 use crate::parser;
-use crate::transformer::Transformer;
+use crate::schema::convert_json_schema;
+use crate::transformer::{FunctionSupport, Transformer};
 use crate::type_inference::TypeContext;
 
 /// Options for the converter
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct ConvertOptions {
     /// Overwrite existing output directory
     pub force: bool,
@@ -31,6 +36,28 @@ pub struct ConvertOptions {
     pub dry_run: bool,
     /// Verbose output
     pub verbose: bool,
+    /// Render the written pack with its own values to check it works
+    pub verify_render: bool,
+    /// Write a JSON [`ConversionReport`] to this path
+    pub report: Option<PathBuf>,
+    /// Inline small helper macros that are called exactly once
+    pub inline_single_use_macros: bool,
+    /// Largest macro body, in tokens, inlined by `inline_single_use_macros`
+    pub inline_max_tokens: usize,
+}
+
+impl Default for ConvertOptions {
+    fn default() -> Self {
+        Self {
+            force: false,
+            dry_run: false,
+            verbose: false,
+            verify_render: true,
+            report: None,
+            inline_single_use_macros: false,
+            inline_max_tokens: DEFAULT_INLINE_MAX_TOKENS,
+        }
+    }
 }
 
 /// Result of a conversion
@@ -44,6 +71,10 @@ pub struct ConversionResult {
     pub skipped_files: Vec<PathBuf>,
     /// Warnings generated during conversion
     pub warnings: Vec<ConversionWarning>,
+    /// Helm functions/filters encountered, tallied by support level
+    pub coverage: FunctionCoverage,
+    /// Outcome of rendering the converted pack (`None` if not verified)
+    pub render_check: Option<RenderCheck>,
 }
 
 impl ConversionResult {
@@ -53,8 +84,262 @@ impl ConversionResult {
             copied_files: Vec::new(),
             skipped_files: Vec::new(),
             warnings: Vec::new(),
+            coverage: FunctionCoverage::default(),
+            render_check: None,
+        }
+    }
+}
+
+/// Outcome of rendering a converted pack with its converted `values.yaml`
+///
+/// A failed check doesn't fail the conversion: it tells the user which
+/// templates still need manual work before the pack can be installed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct RenderCheck {
+    /// Templates that rendered successfully
+    pub rendered: Vec<String>,
+    /// Render errors, one per failing template
+    pub errors: Vec<RenderFailure>,
+    /// Non-fatal issues reported by the renderer
+    pub warnings: Vec<String>,
+}
+
+impl RenderCheck {
+    /// Whether the pack rendered without errors
+    pub fn passed(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// A template that failed to render during verification
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RenderFailure {
+    /// Template name, relative to the pack's templates directory
+    pub template: String,
+    /// Error message from the engine
+    pub message: String,
+}
+
+/// Machine-readable summary of a conversion
+///
+/// Written by the `report` option and printed by `convert --json`. File
+/// paths are relative to the output directory and entries are sorted, so
+/// reports of successive runs can be diffed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversionReport {
+    /// Chart that was converted
+    pub source: PathBuf,
+    /// Directory the pack was written to
+    pub target: PathBuf,
+    /// Whether nothing was written
+    pub dry_run: bool,
+    /// Files that were converted
+    pub converted_files: Vec<PathBuf>,
+    /// Files that were copied as-is
+    pub copied_files: Vec<PathBuf>,
+    /// Files that were skipped
+    pub skipped_files: Vec<PathBuf>,
+    /// Warnings, sorted by file and pattern
+    pub warnings: Vec<ReportWarning>,
+    /// Number of warnings per severity label (`info`, `warning`, ...)
+    pub counts: BTreeMap<&'static str, usize>,
+    /// Helm functions and filters used by the chart
+    pub coverage: ReportCoverage,
+    /// Outcome of rendering the converted pack (`None` if not verified)
+    pub render_check: Option<ReportRenderCheck>,
+}
+
+/// A [`FunctionCoverage`] as written in a [`ConversionReport`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ReportCoverage {
+    /// Number of distinct functions used
+    pub total: usize,
+    /// Functions that mapped cleanly
+    pub mapped: usize,
+    /// Functions that need review
+    pub warning: usize,
+    /// Functions without an equivalent
+    pub unsupported: usize,
+    /// Tallies keyed by Helm function name
+    pub functions: BTreeMap<String, FunctionTally>,
+}
+
+/// A [`RenderCheck`] as written in a [`ConversionReport`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReportRenderCheck {
+    /// Whether the pack rendered without errors
+    pub passed: bool,
+    #[serde(flatten)]
+    pub check: RenderCheck,
+}
+
+/// A [`ConversionWarning`] as written in a [`ConversionReport`]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct ReportWarning {
+    /// File the warning is about, relative to the output directory
+    pub file: PathBuf,
+    /// Line number (if applicable)
+    pub line: Option<usize>,
+    /// Severity label
+    pub severity: &'static str,
+    /// Category label
+    pub category: &'static str,
+    /// The Helm pattern that triggered the warning
+    pub pattern: String,
+    /// Human-readable message
+    pub message: String,
+    /// Suggested alternative or fix
+    pub suggestion: Option<String>,
+}
+
+impl ConversionReport {
+    /// Summarize the conversion of `chart_path` written to `output_path`
+    pub fn new(
+        result: &ConversionResult,
+        chart_path: &Path,
+        output_path: &Path,
+        dry_run: bool,
+    ) -> Self {
+        let relative = |path: &Path| path.strip_prefix(output_path).unwrap_or(path).to_path_buf();
+        let sorted = |paths: &[PathBuf]| {
+            let mut paths: Vec<PathBuf> = paths.iter().map(|p| relative(p)).collect();
+            paths.sort();
+            paths
+        };
+
+        let mut warnings: Vec<ReportWarning> = result
+            .warnings
+            .iter()
+            .map(|w| ReportWarning {
+                file: relative(&w.file),
+                line: w.line,
+                severity: w.severity.label(),
+                category: w.category.label(),
+                pattern: w.pattern.clone(),
+                message: w.message.clone(),
+                suggestion: w.suggestion.clone(),
+            })
+            .collect();
+        warnings.sort();
+
+        let mut counts: BTreeMap<&'static str, usize> = [
+            WarningSeverity::Info,
+            WarningSeverity::Warning,
+            WarningSeverity::Unsupported,
+            WarningSeverity::Error,
+        ]
+        .iter()
+        .map(|severity| (severity.label(), 0))
+        .collect();
+        for warning in &result.warnings {
+            *counts.entry(warning.severity.label()).or_default() += 1;
+        }
+
+        let coverage = &result.coverage;
+        Self {
+            source: chart_path.to_path_buf(),
+            target: output_path.to_path_buf(),
+            dry_run,
+            converted_files: sorted(&result.converted_files),
+            copied_files: sorted(&result.copied_files),
+            skipped_files: sorted(&result.skipped_files),
+            warnings,
+            counts,
+            coverage: ReportCoverage {
+                total: coverage.total(),
+                mapped: coverage.count(FunctionSupport::Mapped),
+                warning: coverage.count(FunctionSupport::Warning),
+                unsupported: coverage.count(FunctionSupport::Unsupported),
+                functions: coverage.functions.clone(),
+            },
+            render_check: result.render_check.clone().map(|check| ReportRenderCheck {
+                passed: check.passed(),
+                check,
+            }),
+        }
+    }
+
+    /// Write the report as pretty-printed JSON
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let json =
+            serde_json::to_string_pretty(self).map_err(|e| ConvertError::ConversionFailed {
+                file: path.to_path_buf(),
+                message: e.to_string(),
+            })?;
+        fs::write(path, json + "\n")?;
+        Ok(())
+    }
+}
+
+/// Usage of a single Helm function or filter across the chart
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FunctionTally {
+    /// Worst support level seen for this function
+    pub support: FunctionSupport,
+    /// Number of times the function was used
+    pub count: usize,
+}
+
+/// Coverage of the Helm functions and filters used by a chart
+///
+/// Helps estimate migration effort: how many functions mapped cleanly,
+/// how many need review, and how many are unsupported.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct FunctionCoverage {
+    /// Tallies keyed by Helm function name (sorted for stable output)
+    pub functions: BTreeMap<String, FunctionTally>,
+}
+
+impl FunctionCoverage {
+    /// Record one use of a function
+    pub fn record(&mut self, name: &str, support: FunctionSupport) {
+        let tally = self
+            .functions
+            .entry(name.to_string())
+            .or_insert(FunctionTally { support, count: 0 });
+        tally.support = tally.support.max(support);
+        tally.count += 1;
+    }
+
+    /// Merge another coverage report into this one
+    pub fn merge(&mut self, other: &FunctionCoverage) {
+        for (name, other_tally) in &other.functions {
+            let tally = self.functions.entry(name.clone()).or_insert(FunctionTally {
+                support: other_tally.support,
+                count: 0,
+            });
+            tally.support = tally.support.max(other_tally.support);
+            tally.count += other_tally.count;
         }
     }
+
+    /// Number of distinct functions at the given support level
+    pub fn count(&self, support: FunctionSupport) -> usize {
+        self.functions
+            .values()
+            .filter(|t| t.support == support)
+            .count()
+    }
+
+    /// Number of calls to functions at the given support level
+    pub fn occurrences(&self, support: FunctionSupport) -> usize {
+        self.functions
+            .values()
+            .filter(|t| t.support == support)
+            .map(|t| t.count)
+            .sum()
+    }
+
+    /// Number of distinct functions used
+    pub fn total(&self) -> usize {
+        self.functions.len()
+    }
+
+    /// Check if no functions were encountered
+    pub fn is_empty(&self) -> bool {
+        self.functions.is_empty()
+    }
 }
 
 /// Convert a Helm chart to a Sherpack pack
@@ -149,18 +434,10 @@ impl Converter {
             }
         }
 
-        // Copy values.schema.json -> values.schema.yaml (or keep as JSON)
+        // Convert values.schema.json -> values.schema.yaml
         let schema_json = chart_path.join("values.schema.json");
         if schema_json.exists() {
-            if !self.options.dry_run {
-                let dest = output_path.join("values.schema.json");
-                fs::copy(&schema_json, &dest)?;
-                result.copied_files.push(dest);
-            } else {
-                result
-                    .copied_files
-                    .push(output_path.join("values.schema.json"));
-            }
+            self.convert_schema(&schema_json, output_path, &mut result)?;
         }
 
         // Convert templates directory
@@ -185,9 +462,111 @@ impl Converter {
         // Copy other files (README, LICENSE, etc.)
         self.copy_extra_files(chart_path, output_path, &mut result)?;
 
+        // Smoke-test the written pack
+        if self.options.verify_render && !self.options.dry_run {
+            result.render_check = Some(Self::verify_render(output_path));
+        }
+
+        if let Some(report_path) = &self.options.report {
+            ConversionReport::new(&result, chart_path, output_path, self.options.dry_run)
+                .write(report_path)?;
+        }
+
         Ok(result)
     }
 
+    /// Translate `values.schema.json` into `values.schema.yaml`
+    ///
+    /// A schema that isn't valid JSON is copied as-is, so the pack keeps
+    /// whatever validation Helm would have applied.
+    fn convert_schema(
+        &self,
+        schema_json: &Path,
+        output_path: &Path,
+        result: &mut ConversionResult,
+    ) -> Result<()> {
+        let content = fs::read_to_string(schema_json)?;
+        let json: serde_json::Value = match serde_json::from_str(&content) {
+            Ok(json) => json,
+            Err(e) => {
+                let dest = output_path.join("values.schema.json");
+                result.warnings.push(ConversionWarning::warning(
+                    dest.clone(),
+                    "values.schema.json",
+                    &format!("Invalid JSON Schema, copied as-is: {}", e),
+                ));
+                if !self.options.dry_run {
+                    fs::copy(schema_json, &dest)?;
+                }
+                result.copied_files.push(dest);
+                return Ok(());
+            }
+        };
+
+        let dest = output_path.join("values.schema.yaml");
+        let conversion = convert_json_schema(&json, &dest);
+        if !self.options.dry_run {
+            fs::write(&dest, serde_yaml::to_string(&conversion.schema)?)?;
+        }
+        result.warnings.extend(conversion.warnings);
+        result.converted_files.push(dest);
+        Ok(())
+    }
+
+    /// Render a converted pack with its own values, as `sherpack template` would
+    fn verify_render(pack_path: &Path) -> RenderCheck {
+        let mut check = RenderCheck::default();
+
+        let pack = match LoadedPack::load(pack_path) {
+            Ok(pack) => pack,
+            Err(e) => {
+                check.errors.push(RenderFailure {
+                    template: "Pack.yaml".to_string(),
+                    message: e.to_string(),
+                });
+                return check;
+            }
+        };
+
+        let values = if pack.values_path.exists() {
+            match Values::from_file(&pack.values_path) {
+                Ok(values) => values,
+                Err(e) => {
+                    check.errors.push(RenderFailure {
+                        template: "values.yaml".to_string(),
+                        message: e.to_string(),
+                    });
+                    return check;
+                }
+            }
+        } else {
+            Values::new()
+        };
+
+        let release = ReleaseInfo::for_install("release-name", "default");
+        let context = TemplateContext::new(values, release, &pack.pack.metadata);
+
+        let engine = Engine::builder().strict(pack.pack.engine.strict).build();
+        let result = PackRenderer::new(engine).render_collect_errors(&pack, &context);
+
+        check.rendered = result.report.successful_templates.clone();
+        for (template, errors) in &result.report.errors_by_template {
+            for error in errors {
+                check.errors.push(RenderFailure {
+                    template: template.clone(),
+                    message: error.to_string(),
+                });
+            }
+        }
+        check.warnings = result
+            .report
+            .warnings()
+            .map(|issue| format!("{}: {}", issue.category, issue.message))
+            .collect();
+
+        check
+    }
+
     fn convert_templates_dir(
         &self,
         src_dir: &Path,
@@ -207,6 +586,8 @@ impl Converter {
             std::collections::HashMap::new();
         let mut defined_macros: HashSet<String> = HashSet::new();
         let mut helper_files: Vec<(PathBuf, String, String)> = Vec::new(); // (dest_path, dest_name, converted_content)
+        // Converted helpers and templates, written once all are known
+        let mut outputs: Vec<(PathBuf, String)> = Vec::new();
 
         for entry in WalkDir::new(src_dir)
             .follow_links(true)
@@ -230,7 +611,7 @@ impl Converter {
                     .to_string();
 
                 match self.convert_helpers(&content, chart_name, &dest_path) {
-                    Ok((converted, warnings)) => {
+                    Ok((converted, warnings, coverage)) => {
                         // Extract macro names from converted content and track their source file
                         let macros = extract_macro_definitions(&converted);
                         for macro_name in &macros {
@@ -242,6 +623,7 @@ impl Converter {
                         helper_files.push((dest_path.clone(), dest_name, converted));
                         result.converted_files.push(dest_path);
                         result.warnings.extend(warnings);
+                        result.coverage.merge(&coverage);
                     }
                     Err(e) => {
                         result.warnings.push(ConversionWarning {
@@ -325,10 +707,7 @@ impl Converter {
                 with_imports
             };
 
-            if !self.options.dry_run {
-                fs::create_dir_all(dest_path.parent().unwrap_or(dest_dir))?;
-                fs::write(dest_path, &final_content)?;
-            }
+            outputs.push((dest_path.clone(), final_content));
         }
 
         // Pass 3: Convert regular templates with macro awareness
@@ -375,8 +754,8 @@ impl Converter {
                 }
             };
 
-            // NOTES.txt needs conversion too - it often contains Go templates
-            // Don't skip it anymore
+            // NOTES.txt goes through the same conversion and keeps its name,
+            // which the engine renders as the pack's notes
 
             // Convert template files
             if content.contains("{{") {
@@ -388,12 +767,11 @@ impl Converter {
                     &macro_sources,
                     type_context,
                 ) {
-                    Ok((converted, warnings)) => {
-                        if !self.options.dry_run {
-                            fs::write(&dest_path, &converted)?;
-                        }
+                    Ok((converted, warnings, coverage)) => {
+                        outputs.push((dest_path.clone(), converted));
                         result.converted_files.push(dest_path.clone());
                         result.warnings.extend(warnings);
+                        result.coverage.merge(&coverage);
                     }
                     Err(e) => {
                         result.warnings.push(ConversionWarning {
@@ -420,6 +798,19 @@ impl Converter {
             }
         }
 
+        if self.options.inline_single_use_macros {
+            let mut contents: Vec<&mut String> =
+                outputs.iter_mut().map(|(_, content)| content).collect();
+            MacroInliner::new(self.options.inline_max_tokens).inline(&mut contents);
+        }
+
+        if !self.options.dry_run {
+            for (dest_path, content) in &outputs {
+                fs::create_dir_all(dest_path.parent().unwrap_or(dest_dir))?;
+                fs::write(dest_path, content)?;
+            }
+        }
+
         Ok(())
     }
 
@@ -432,7 +823,7 @@ impl Converter {
         defined_macros: &HashSet<String>,
         macro_sources: &std::collections::HashMap<String, String>,
         type_context: Option<&TypeContext>,
-    ) -> Result<(String, Vec<ConversionWarning>)> {
+    ) -> Result<(String, Vec<ConversionWarning>, FunctionCoverage)> {
         let ast = parser::parse(content)?;
         let mut transformer = Transformer::new().with_chart_prefix(chart_name);
 
@@ -479,10 +870,20 @@ impl Converter {
             converted
         };
 
+        // helm.sh/hook* annotations -> sherpack.io/hook*
+        let (final_content, hook_warnings) = rewrite_hook_annotations(&final_content, dest_path);
+
         // Convert transformer warnings
-        let warnings = self.collect_warnings(&transformer, dest_path, &final_content);
+        let mut warnings = self.collect_warnings(&transformer, dest_path, &final_content);
+        warnings.extend(hook_warnings);
 
-        Ok((final_content, warnings))
+        // Tally functions/filters used by this template
+        let mut coverage = FunctionCoverage::default();
+        for (name, support) in transformer.function_usage() {
+            coverage.record(&name, support);
+        }
+
+        Ok((final_content, warnings, coverage))
     }
 
     fn convert_template(
@@ -490,7 +891,7 @@ impl Converter {
         content: &str,
         chart_name: &str,
         dest_path: &Path,
-    ) -> Result<(String, Vec<ConversionWarning>)> {
+    ) -> Result<(String, Vec<ConversionWarning>, FunctionCoverage)> {
         // For backwards compatibility, use empty macro set and no type context
         self.convert_template_with_macros(
             content,
@@ -540,6 +941,18 @@ impl Converter {
             })
             .collect();
 
+        // Unsupported functions left as `__UNSUPPORTED_*__` placeholders;
+        // Files and genCA get dedicated warnings below
+        for (name, alternative) in transformer.unsupported_functions() {
+            if name != "genCA" && !name.starts_with("Files.") {
+                warnings.push(ConversionWarning::unsupported(
+                    dest_path.to_path_buf(),
+                    &name,
+                    alternative,
+                ));
+            }
+        }
+
         // Check for __UNSUPPORTED_ markers
         if final_content.contains("__UNSUPPORTED_FILES__") {
             warnings.push(ConversionWarning::unsupported(
@@ -566,7 +979,7 @@ impl Converter {
         content: &str,
         chart_name: &str,
         dest_path: &Path,
-    ) -> Result<(String, Vec<ConversionWarning>)> {
+    ) -> Result<(String, Vec<ConversionWarning>, FunctionCoverage)> {
         // Helpers files are just templates with define blocks
         self.convert_template(content, chart_name, dest_path)
     }
@@ -626,6 +1039,7 @@ impl Converter {
                         result.copied_files.extend(sub_result.copied_files);
                         result.skipped_files.extend(sub_result.skipped_files);
                         result.warnings.extend(sub_result.warnings);
+                        result.coverage.merge(&sub_result.coverage);
                     }
                     Err(e) => {
                         result.warnings.push(ConversionWarning {
@@ -702,14 +1116,15 @@ fn extract_macro_definitions(content: &str) -> HashSet<String> {
 
 /// Find which macros from `defined` are used in the content
 ///
-/// Scans for `macroName()` patterns that match defined macros.
+/// Scans for `macroName(...)` calls that match defined macros.
 /// Returns only the macros that are actually used.
 fn find_used_macros(content: &str, defined: &HashSet<String>) -> HashSet<String> {
     let mut used = HashSet::new();
 
     for macro_name in defined {
-        // Look for macro calls: macroName() with possible whitespace
-        let pattern = format!(r"\b{}\s*\(\s*\)", regex::escape(macro_name));
+        // Look for macro calls: macroName(...) with possible whitespace,
+        // but not method calls such as `ctx.macroName(...)`
+        let pattern = format!(r"(?:^|[^\w.]){}\s*\(", regex::escape(macro_name));
         if let Ok(re) = Regex::new(&pattern)
             && re.is_match(content)
         {
@@ -871,6 +1286,51 @@ spec:
         assert!(!output_dir.join("Pack.yaml").exists());
     }
 
+    #[test]
+    fn test_function_coverage_tallies() {
+        let chart_dir = TempDir::new().unwrap();
+        let output_base = TempDir::new().unwrap();
+        let output_dir = output_base.path().join("output");
+
+        create_test_chart(chart_dir.path());
+        fs::write(
+            chart_dir.path().join("templates/configmap.yaml"),
+            r#"apiVersion: v1
+kind: ConfigMap
+metadata:
+  name: {{ .Release.Name | quote }}
+data:
+  name: {{ .Values.name | default "app" | quote }}
+  size: {{ add .Values.a 1 }}
+  rendered: {{ tpl .Values.template . }}
+  ca: {{ genCA "my-ca" 365 }}
+  odd: {{ .Values.x | frobnicate }}
+"#,
+        )
+        .unwrap();
+
+        let result = convert(chart_dir.path(), &output_dir).unwrap();
+        let coverage = &result.coverage;
+
+        // quote is used twice and maps cleanly
+        assert_eq!(coverage.functions["quote"].count, 2);
+        assert_eq!(coverage.functions["quote"].support, FunctionSupport::Mapped);
+        assert_eq!(coverage.functions["tpl"].support, FunctionSupport::Warning);
+        assert_eq!(
+            coverage.functions["genCA"].support,
+            FunctionSupport::Unsupported
+        );
+
+        // Mapped: quote, default, add (configmap) + trunc, trimSuffix (helpers)
+        assert_eq!(coverage.count(FunctionSupport::Mapped), 5);
+        // Warning: tpl, frobnicate
+        assert_eq!(coverage.count(FunctionSupport::Warning), 2);
+        // Unsupported: genCA
+        assert_eq!(coverage.count(FunctionSupport::Unsupported), 1);
+        assert_eq!(coverage.total(), 8);
+        assert_eq!(coverage.occurrences(FunctionSupport::Mapped), 6);
+    }
+
     #[test]
     fn test_force_overwrite() {
         let chart_dir = TempDir::new().unwrap();
@@ -895,4 +1355,385 @@ spec:
         let result = convert_with_options(chart_dir.path(), &output_dir, options);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_render_check_passes_for_simple_chart() {
+        let chart_dir = TempDir::new().unwrap();
+        let output_base = TempDir::new().unwrap();
+        let output_dir = output_base.path().join("output");
+
+        create_test_chart(chart_dir.path());
+
+        let result = convert(chart_dir.path(), &output_dir).unwrap();
+        let check = result.render_check.expect("render check should run");
+
+        assert!(check.passed(), "render errors: {:?}", check.errors);
+        assert!(check.rendered.iter().any(|t| t.contains("deployment")));
+    }
+
+    #[test]
+    fn test_render_check_reports_unsupported_feature() {
+        let chart_dir = TempDir::new().unwrap();
+        let output_base = TempDir::new().unwrap();
+        let output_dir = output_base.path().join("output");
+
+        create_test_chart(chart_dir.path());
+        fs::write(
+            chart_dir.path().join("templates/secret.yaml"),
+            r#"apiVersion: v1
+kind: Secret
+metadata:
+  name: {{ .Release.Name }}-tls
+data:
+  ca: {{ genCA "my-ca" 365 }}
+"#,
+        )
+        .unwrap();
+
+        let result = convert(chart_dir.path(), &output_dir).unwrap();
+        let check = result.render_check.expect("render check should run");
+
+        assert!(!check.passed());
+        assert!(check.errors.iter().any(|e| e.template.contains("secret")));
+    }
+
+    #[test]
+    fn test_convert_notes() {
+        let chart_dir = TempDir::new().unwrap();
+        let output_base = TempDir::new().unwrap();
+        let output_dir = output_base.path().join("output");
+
+        create_test_chart(chart_dir.path());
+        fs::write(
+            chart_dir.path().join("templates/NOTES.txt"),
+            "Installed {{ .Release.Name }} on {{ getHostByName \"example.com\" }}.\n",
+        )
+        .unwrap();
+
+        let result = convert(chart_dir.path(), &output_dir).unwrap();
+        let notes_path = output_dir.join("templates/NOTES.txt");
+
+        let notes = fs::read_to_string(&notes_path).unwrap();
+        assert!(notes.contains("{{ release.name }}"), "{}", notes);
+        assert!(result.converted_files.contains(&notes_path));
+        assert!(
+            result
+                .warnings
+                .iter()
+                .any(|w| w.file == notes_path && w.severity == WarningSeverity::Unsupported)
+        );
+    }
+
+    #[test]
+    fn test_convert_values_schema() {
+        let chart_dir = TempDir::new().unwrap();
+        let output_base = TempDir::new().unwrap();
+        let output_dir = output_base.path().join("output");
+
+        create_test_chart(chart_dir.path());
+        fs::write(
+            chart_dir.path().join("values.schema.json"),
+            r#"{
+  "$schema": "http://json-schema.org/draft-07/schema#",
+  "type": "object",
+  "required": ["image"],
+  "properties": {
+    "replicaCount": {"type": "integer", "minimum": 0},
+    "image": {
+      "type": "object",
+      "required": ["repository"],
+      "properties": {
+        "repository": {"type": "string"},
+        "tag": {"type": "string", "default": "latest"}
+      }
+    },
+    "service": {"oneOf": [{"type": "object"}, {"type": "null"}]}
+  }
+}"#,
+        )
+        .unwrap();
+
+        let result = convert(chart_dir.path(), &output_dir).unwrap();
+        let schema_path = output_dir.join("values.schema.yaml");
+        assert!(result.converted_files.contains(&schema_path));
+        assert!(!output_dir.join("values.schema.json").exists());
+        assert!(
+            result
+                .warnings
+                .iter()
+                .any(|w| w.pattern == "oneOf (service)")
+        );
+
+        // The converted schema still accepts the chart's own values...
+        let validator = sherpack_core::SchemaValidator::new(
+            sherpack_core::Schema::from_file(&schema_path).unwrap(),
+        )
+        .unwrap();
+        let values = Values::from_file(output_dir.join("values.yaml")).unwrap();
+        let validation = validator.validate(values.inner());
+        assert!(validation.is_valid, "{:?}", validation.errors);
+
+        // ...and still rejects what the original rejected
+        let invalid = serde_json::json!({"replicaCount": -1, "image": {"tag": "1.0"}});
+        assert!(!validator.validate(&invalid).is_valid);
+    }
+
+    #[test]
+    fn test_convert_hook_job() {
+        let chart_dir = TempDir::new().unwrap();
+        let output_base = TempDir::new().unwrap();
+        let output_dir = output_base.path().join("output");
+
+        create_test_chart(chart_dir.path());
+        fs::write(
+            chart_dir.path().join("templates/migrate-job.yaml"),
+            r#"apiVersion: batch/v1
+kind: Job
+metadata:
+  name: {{ .Release.Name }}-migrate
+  annotations:
+    "helm.sh/hook": pre-install
+    "helm.sh/hook-weight": "-5"
+    "helm.sh/hook-delete-policy": hook-succeeded
+spec:
+  template:
+    spec:
+      restartPolicy: Never
+      containers:
+        - name: migrate
+          image: "{{ .Values.image.repository }}:{{ .Values.image.tag }}"
+"#,
+        )
+        .unwrap();
+
+        let result = convert(chart_dir.path(), &output_dir).unwrap();
+        let job = fs::read_to_string(output_dir.join("templates/migrate-job.yaml")).unwrap();
+
+        assert!(
+            job.contains(r#""sherpack.io/hook": pre-install"#),
+            "{}",
+            job
+        );
+        assert!(
+            job.contains(r#""sherpack.io/hook-weight": "-5""#),
+            "{}",
+            job
+        );
+        assert!(job.contains(r#""sherpack.io/hook-delete-policy": hook-succeeded"#));
+        assert!(!job.contains("helm.sh/hook"));
+        assert!(!result.warnings.iter().any(|w| w.pattern.contains("hook")));
+    }
+
+    #[test]
+    fn test_include_renders_with_loop_context() {
+        let chart_dir = TempDir::new().unwrap();
+        let output_base = TempDir::new().unwrap();
+        let output_dir = output_base.path().join("output");
+
+        create_test_chart(chart_dir.path());
+        fs::write(
+            chart_dir.path().join("values.yaml"),
+            "ports:\n  - name: http\n    port: 80\n  - name: https\n    port: 443\n",
+        )
+        .unwrap();
+        fs::write(
+            chart_dir.path().join("templates/_helpers.tpl"),
+            r#"{{- define "test-app.port" -}}
+name: {{ .name }}
+port: {{ .port }}
+{{- end }}
+"#,
+        )
+        .unwrap();
+        fs::write(
+            chart_dir.path().join("templates/deployment.yaml"),
+            r#"apiVersion: v1
+kind: Service
+metadata:
+  name: {{ .Release.Name }}
+spec:
+  ports:
+  {{- range .Values.ports }}
+    - {{- include "test-app.port" . | nindent 6 }}
+  {{- end }}
+"#,
+        )
+        .unwrap();
+
+        convert(chart_dir.path(), &output_dir).unwrap();
+
+        let pack = LoadedPack::load(&output_dir).unwrap();
+        let values = Values::from_file(&pack.values_path).unwrap();
+        let context = TemplateContext::new(
+            values,
+            ReleaseInfo::for_install("web", "default"),
+            &pack.pack.metadata,
+        );
+        let result = PackRenderer::new(Engine::builder().build())
+            .render(&pack, &context)
+            .unwrap();
+
+        let service = result
+            .manifests
+            .values()
+            .find(|m| m.contains("kind: Service"))
+            .expect("service rendered");
+        assert!(
+            service.contains("    -\n      name: http\n      port: 80\n"),
+            "{}",
+            service
+        );
+        assert!(
+            service.contains("      name: https\n      port: 443"),
+            "{}",
+            service
+        );
+    }
+
+    /// Converted output matches `fixtures/helm-commented-converted`,
+    /// comments and blank lines included
+    #[test]
+    fn test_golden_commented_chart() {
+        let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../fixtures");
+        let expected_dir = fixtures.join("helm-commented-converted/templates");
+        let output_base = TempDir::new().unwrap();
+        let output_dir = output_base.path().join("output");
+
+        let result = convert(&fixtures.join("helm-commented"), &output_dir).unwrap();
+        assert!(result.render_check.unwrap().passed());
+
+        for entry in fs::read_dir(&expected_dir).unwrap() {
+            let expected_path = entry.unwrap().path();
+            let name = expected_path.file_name().unwrap();
+            let expected = fs::read_to_string(&expected_path).unwrap();
+            let actual = fs::read_to_string(output_dir.join("templates").join(name)).unwrap();
+            assert_eq!(actual, expected, "{}", name.to_string_lossy());
+        }
+    }
+
+    #[test]
+    fn test_conversion_report() {
+        let chart_dir = TempDir::new().unwrap();
+        let output_base = TempDir::new().unwrap();
+        let output_dir = output_base.path().join("output");
+        let report_path = output_base.path().join("report.json");
+
+        create_test_chart(chart_dir.path());
+        fs::write(
+            chart_dir.path().join("templates/secret.yaml"),
+            "data:\n  ca: {{ genCA \"my-ca\" 365 }}\n",
+        )
+        .unwrap();
+
+        let options = ConvertOptions {
+            report: Some(report_path.clone()),
+            ..Default::default()
+        };
+        let result = convert_with_options(chart_dir.path(), &output_dir, options).unwrap();
+
+        let report: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&report_path).unwrap()).unwrap();
+        assert_eq!(
+            report["convertedFiles"].as_array().unwrap().len(),
+            result.converted_files.len()
+        );
+        assert!(
+            report["convertedFiles"]
+                .as_array()
+                .unwrap()
+                .contains(&serde_json::json!("Pack.yaml"))
+        );
+        assert_eq!(report["counts"]["error"], 0);
+        assert_eq!(
+            report["counts"]["warning"].as_u64().unwrap()
+                + report["counts"]["info"].as_u64().unwrap()
+                + report["counts"]["unsupported"].as_u64().unwrap(),
+            result.warnings.len() as u64
+        );
+
+        let warning = report["warnings"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|w| w["pattern"] == "genCA")
+            .expect("genCA warning reported");
+        assert_eq!(warning["file"], "templates/secret.yaml");
+        assert_eq!(warning["category"], "security");
+        assert!(warning["suggestion"].is_string());
+    }
+
+    #[test]
+    fn test_inline_single_use_macros() {
+        let chart_dir = TempDir::new().unwrap();
+        let output_base = TempDir::new().unwrap();
+        let output_dir = output_base.path().join("output");
+
+        create_test_chart(chart_dir.path());
+        fs::write(
+            chart_dir.path().join("templates/_helpers.tpl"),
+            r#"{{- define "test-app.name" -}}
+{{- .Chart.Name | trunc 63 -}}
+{{- end }}
+
+{{- define "test-app.labels" -}}
+app: {{ .Chart.Name }}
+{{- end }}
+"#,
+        )
+        .unwrap();
+        fs::write(
+            chart_dir.path().join("templates/deployment.yaml"),
+            r#"apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: {{ include "test-app.name" . }}
+  labels:
+    {{- include "test-app.labels" . | nindent 4 }}
+"#,
+        )
+        .unwrap();
+        fs::write(
+            chart_dir.path().join("templates/service.yaml"),
+            r#"apiVersion: v1
+kind: Service
+metadata:
+  name: {{ .Release.Name }}
+  labels:
+    {{- include "test-app.labels" . | nindent 4 }}
+"#,
+        )
+        .unwrap();
+
+        let options = ConvertOptions {
+            inline_single_use_macros: true,
+            ..Default::default()
+        };
+        let result = convert_with_options(chart_dir.path(), &output_dir, options).unwrap();
+        assert!(result.render_check.unwrap().passed());
+
+        let helpers = fs::read_to_string(output_dir.join("templates/_helpers.j2")).unwrap();
+        assert!(!helpers.contains("macro name("), "{}", helpers);
+        assert!(helpers.contains("macro labels("), "{}", helpers);
+
+        let deployment = fs::read_to_string(output_dir.join("templates/deployment.yaml")).unwrap();
+        assert!(!deployment.contains("name()"), "{}", deployment);
+        assert!(deployment.contains("import labels -%}"), "{}", deployment);
+    }
+
+    #[test]
+    fn test_render_check_skipped() {
+        let chart_dir = TempDir::new().unwrap();
+        let output_base = TempDir::new().unwrap();
+        let output_dir = output_base.path().join("output");
+
+        create_test_chart(chart_dir.path());
+
+        let options = ConvertOptions {
+            verify_render: false,
+            ..Default::default()
+        };
+
+        let result = convert_with_options(chart_dir.path(), &output_dir, options).unwrap();
+        assert!(result.render_check.is_none());
+    }
 }
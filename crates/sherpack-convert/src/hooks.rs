@@ -0,0 +1,184 @@
+//! Helm hook annotation rewriting
+//!
+//! Renames `helm.sh/hook`, `helm.sh/hook-weight` and
+//! `helm.sh/hook-delete-policy` to their `sherpack.io/*` equivalents in
+//! converted templates. Templates aren't valid YAML until rendered, so the
+//! rewrite works line by line on the annotation keys.
+
+use regex::Regex;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use crate::error::{ConversionWarning, WarningCategory, WarningSeverity};
+
+/// A `helm.sh/hook*` annotation key, optionally quoted, and its value
+static HOOK_ANNOTATION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"^(?P<indent>\s*)(?P<quote>["']?)helm\.sh/(?P<key>hook|hook-weight|hook-delete-policy)["']?(?P<sep>\s*:\s*)(?P<value>.*)$"#,
+    )
+    .expect("valid regex")
+});
+
+/// Hook events run by Sherpack's hook executor
+const SUPPORTED_EVENTS: &[&str] = &[
+    "pre-install",
+    "post-install",
+    "pre-upgrade",
+    "post-upgrade",
+    "pre-rollback",
+    "post-rollback",
+    "pre-delete",
+    "post-delete",
+    "test",
+];
+
+/// Rewrite the Helm hook annotations of a template
+///
+/// Weights and delete policies are kept as is. Hook events are kept too,
+/// except `test-success` which becomes `test`; events Sherpack doesn't run
+/// are reported as warnings against `file`.
+pub fn rewrite_hook_annotations(content: &str, file: &Path) -> (String, Vec<ConversionWarning>) {
+    let mut warnings = Vec::new();
+
+    let lines: Vec<String> = content
+        .split('\n')
+        .map(|line| {
+            let Some(caps) = HOOK_ANNOTATION.captures(line) else {
+                return line.to_string();
+            };
+
+            let key = &caps["key"];
+            let value = if key == "hook" {
+                rewrite_events(&caps["value"], file, &mut warnings)
+            } else {
+                caps["value"].to_string()
+            };
+
+            format!(
+                "{}{quote}sherpack.io/{}{quote}{}{}",
+                &caps["indent"],
+                key,
+                &caps["sep"],
+                value,
+                quote = &caps["quote"],
+            )
+        })
+        .collect();
+
+    (lines.join("\n"), warnings)
+}
+
+/// Map the comma-separated events of a `helm.sh/hook` value
+fn rewrite_events(value: &str, file: &Path, warnings: &mut Vec<ConversionWarning>) -> String {
+    // Templated values are only known once rendered
+    if value.contains("{{") || value.contains("{%") {
+        return value.to_string();
+    }
+
+    let trimmed = value.trim();
+    let (quote, events) = match trimmed.chars().next() {
+        Some(q @ ('"' | '\'')) if trimmed.len() > 1 && trimmed.ends_with(q) => {
+            (q.to_string(), &trimmed[1..trimmed.len() - 1])
+        }
+        _ => (String::new(), trimmed),
+    };
+
+    let events: Vec<&str> = events
+        .split(',')
+        .map(str::trim)
+        .filter(|e| !e.is_empty())
+        .map(|event| {
+            if event == "test-success" {
+                return "test";
+            }
+            if !SUPPORTED_EVENTS.contains(&event) {
+                warnings.push(unsupported_event(event, file));
+            }
+            event
+        })
+        .collect();
+
+    format!("{}{}{}", quote, events.join(","), quote)
+}
+
+fn unsupported_event(event: &str, file: &Path) -> ConversionWarning {
+    let suggestion = match event {
+        "crd-install" => "Move the CRDs to the pack's crds/ directory".to_string(),
+        "test-failure" => "Use a `test` hook that checks the failure itself".to_string(),
+        _ => format!("Supported hook events: {}", SUPPORTED_EVENTS.join(", ")),
+    };
+
+    ConversionWarning {
+        severity: WarningSeverity::Unsupported,
+        category: WarningCategory::UnsupportedFeature,
+        file: file.to_path_buf(),
+        line: None,
+        pattern: format!("helm.sh/hook: {}", event),
+        message: format!(
+            "Hook event '{}' is not supported by Sherpack, the hook won't run for it",
+            event
+        ),
+        suggestion: Some(suggestion),
+        doc_link: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rewrite(content: &str) -> (String, Vec<ConversionWarning>) {
+        rewrite_hook_annotations(content, Path::new("templates/job.yaml"))
+    }
+
+    #[test]
+    fn test_rewrite_pre_install_hook() {
+        let (content, warnings) = rewrite(
+            r#"metadata:
+  annotations:
+    "helm.sh/hook": pre-install,pre-upgrade
+    "helm.sh/hook-weight": "-5"
+    "helm.sh/hook-delete-policy": before-hook-creation,hook-succeeded
+    helm.sh/resource-policy: keep
+"#,
+        );
+
+        assert!(warnings.is_empty());
+        assert_eq!(
+            content,
+            r#"metadata:
+  annotations:
+    "sherpack.io/hook": pre-install,pre-upgrade
+    "sherpack.io/hook-weight": "-5"
+    "sherpack.io/hook-delete-policy": before-hook-creation,hook-succeeded
+    helm.sh/resource-policy: keep
+"#
+        );
+    }
+
+    #[test]
+    fn test_rewrite_test_hook() {
+        let (content, warnings) = rewrite("    helm.sh/hook: \"test-success\"\n");
+        assert!(warnings.is_empty());
+        assert_eq!(content, "    sherpack.io/hook: \"test\"\n");
+    }
+
+    #[test]
+    fn test_unsupported_events_warn() {
+        let (content, warnings) = rewrite("    helm.sh/hook: crd-install, test-failure\n");
+
+        assert_eq!(content, "    sherpack.io/hook: crd-install,test-failure\n");
+        let patterns: Vec<&str> = warnings.iter().map(|w| w.pattern.as_str()).collect();
+        assert_eq!(
+            patterns,
+            ["helm.sh/hook: crd-install", "helm.sh/hook: test-failure"]
+        );
+    }
+
+    #[test]
+    fn test_templated_events_kept() {
+        let (content, warnings) = rewrite("    helm.sh/hook: {{ values.hookEvents }}\n");
+        assert!(warnings.is_empty());
+        assert_eq!(content, "    sherpack.io/hook: {{ values.hookEvents }}\n");
+    }
+}
@@ -49,6 +49,9 @@
 //!     force: true,
 //!     dry_run: false,
 //!     verbose: true,
+//!     verify_render: true,
+//!     report: None,
+//!     ..Default::default()
 //! };
 //!
 //! let result = convert_with_options(
@@ -76,12 +79,20 @@ pub mod ast;
 pub mod chart;
 pub mod converter;
 pub mod error;
+pub mod hooks;
 pub mod macro_processor;
 pub mod parser;
+pub mod schema;
 pub mod transformer;
 pub mod type_inference;
 
 // Re-exports
-pub use converter::{ConversionResult, ConvertOptions, Converter, convert, convert_with_options};
+pub use converter::{
+    ConversionReport, ConversionResult, ConvertOptions, Converter, FunctionCoverage, FunctionTally,
+    RenderCheck, RenderFailure, ReportCoverage, ReportRenderCheck, ReportWarning, convert,
+    convert_with_options,
+};
 pub use error::{ConversionWarning, ConvertError, Result, WarningCategory, WarningSeverity};
+pub use schema::{SchemaConversion, convert_json_schema};
+pub use transformer::FunctionSupport;
 pub use type_inference::{InferredType, TypeContext, TypeHeuristics};
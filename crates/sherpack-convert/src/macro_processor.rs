@@ -12,10 +12,14 @@
 //!
 //! When converted to Jinja2 macros, the context is lost and variables become bare.
 //! This post-processor fixes that by searching values.yaml for matching keys.
+//!
+//! [`MacroInliner`] optionally folds small single-use macros back into their
+//! call site.
 
 use regex::Regex;
 use serde_yaml::Value;
 use std::collections::HashMap;
+use std::sync::LazyLock;
 
 /// A macro definition with its name and body
 #[derive(Debug, Clone)]
@@ -159,7 +163,7 @@ impl MacroPostProcessor {
     /// Find bare variable references in a macro body
     ///
     /// Returns variable names that are:
-    /// - Not prefixed with `values.`, `release.`, `pack.`, `capabilities.`, `_with_ctx.`
+    /// - Not prefixed with `values.`, `release.`, `pack.`, `capabilities.`, `_with_ctx.`, `ctx.`
     /// - Not Jinja2 keywords or builtin functions
     /// - Inside expressions ({{ }}) or control structures ({% %})
     /// - Not loop or set variable declarations
@@ -173,6 +177,7 @@ impl MacroPostProcessor {
             "pack.",
             "capabilities.",
             "_with_ctx.",
+            "ctx.",
             "loop.",
             "item.",
             "key.",
@@ -213,6 +218,8 @@ impl MacroPostProcessor {
             "endraw",
             "with",
             "endwith",
+            // Context parameter of generated macros
+            "ctx",
             // Common filter/function names
             "nindent",
             "indent",
@@ -229,6 +236,7 @@ impl MacroPostProcessor {
             "title",
             "dictsort",
             "merge",
+            "mergeoverwrite",
             "tpl",
             "toString",
             "semver_match",
@@ -511,6 +519,342 @@ pub struct UnresolvedVariable {
     pub reason: String,
 }
 
+/// Default token budget of [`MacroInliner`]
+pub const DEFAULT_INLINE_MAX_TOKENS: usize = 32;
+
+/// A macro definition with the details needed to inline it
+struct InlineCandidate {
+    /// Definition span, including its doc comment and trailing newline
+    span: std::ops::Range<usize>,
+    /// Context parameter name, empty for a macro without parameters
+    param: String,
+    body: String,
+    trim_start: bool,
+    trim_end: bool,
+}
+
+/// Inlines small macros that are called exactly once
+///
+/// The call site is replaced by an expression building the same string as
+/// the macro body (`"app: " ~ pack.name`), with the context parameter
+/// substituted by the call argument, and the definition is dropped along
+/// with its import. The trim markers of the body are applied when building
+/// the expression, and those of the calling tag are left untouched, so the
+/// rendered output doesn't change.
+///
+/// Only bodies made of text and `{{ }}` expressions are inlined: control
+/// structures, comments and calls to other macros keep the macro.
+#[derive(Debug, Clone)]
+pub struct MacroInliner {
+    max_tokens: usize,
+}
+
+impl Default for MacroInliner {
+    fn default() -> Self {
+        Self::new(DEFAULT_INLINE_MAX_TOKENS)
+    }
+}
+
+impl MacroInliner {
+    /// Inline macros whose body has at most `max_tokens` tokens
+    pub fn new(max_tokens: usize) -> Self {
+        Self { max_tokens }
+    }
+
+    /// Inline single-use macros across the converted templates of a pack
+    ///
+    /// Returns the names of the inlined macros.
+    pub fn inline(&self, files: &mut [&mut String]) -> Vec<String> {
+        let mut names: Vec<String> = files
+            .iter()
+            .flat_map(|content| Self::definitions(content))
+            .map(|(name, _)| name)
+            .collect();
+        names.sort();
+        names.dedup();
+
+        let mut inlined = Vec::new();
+        for name in &names {
+            if self.inline_macro(files, name, &names) {
+                inlined.push(name.clone());
+            }
+        }
+        inlined
+    }
+
+    fn inline_macro(&self, files: &mut [&mut String], name: &str, all_macros: &[String]) -> bool {
+        let defined_in: Vec<usize> = (0..files.len())
+            .filter(|i| Self::definitions(files[*i]).iter().any(|(n, _)| n == name))
+            .collect();
+        let [def_file] = defined_in[..] else {
+            return false;
+        };
+        let Some((_, candidate)) = Self::definitions(files[def_file])
+            .into_iter()
+            .find(|(n, _)| n == name)
+        else {
+            return false;
+        };
+
+        if candidate.body.contains("{%")
+            || candidate.body.contains("{#")
+            || Self::token_count(&candidate.body) > self.max_tokens
+            || all_macros
+                .iter()
+                .any(|other| !Self::calls(&candidate.body, other).is_empty())
+        {
+            return false;
+        }
+
+        let calls: Vec<(usize, std::ops::Range<usize>, String)> = files
+            .iter()
+            .enumerate()
+            .flat_map(|(i, content)| {
+                Self::calls(content, name)
+                    .into_iter()
+                    .map(move |(span, args)| (i, span, args))
+            })
+            .collect();
+        let [(call_file, ref call_span, ref args)] = calls[..] else {
+            return false;
+        };
+
+        // The context can only be substituted by an actual argument
+        let body_uses_param =
+            !candidate.param.is_empty() && Self::references(&candidate.body, &candidate.param);
+        if (body_uses_param && args.trim().is_empty())
+            || (candidate.param.is_empty() && !args.trim().is_empty())
+        {
+            return false;
+        }
+
+        let expression = format!("({})", Self::body_expression(&candidate, args.trim()));
+        files[call_file].replace_range(call_span.clone(), &expression);
+
+        // Offsets in the defining file are stale if the call was there too
+        if let Some((_, candidate)) = Self::definitions(files[def_file])
+            .into_iter()
+            .find(|(n, _)| n == name)
+        {
+            files[def_file].replace_range(candidate.span, "");
+        }
+
+        for content in files.iter_mut() {
+            **content = Self::remove_import(content, name);
+        }
+        true
+    }
+
+    /// Macro definitions of `content`, keyed by name
+    fn definitions(content: &str) -> Vec<(String, InlineCandidate)> {
+        static DEFINITION: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(
+                r"(?s)\{%-?\s*macro\s+(\w+)\s*\(\s*(\w*)\s*\)\s*(-?)%\}(.*?)\{%(-?)\s*endmacro\s*-?%\}\n?",
+            )
+            .expect("valid regex")
+        });
+
+        DEFINITION
+            .captures_iter(content)
+            .map(|caps| {
+                let span = caps.get(0).unwrap().range();
+                let start = Self::doc_comment_start(&content[..span.start]).unwrap_or(span.start);
+                let candidate = InlineCandidate {
+                    span: start..span.end,
+                    param: caps[2].to_string(),
+                    body: caps[4].to_string(),
+                    trim_start: !caps[3].is_empty(),
+                    trim_end: !caps[5].is_empty(),
+                };
+                (caps[1].to_string(), candidate)
+            })
+            .collect()
+    }
+
+    /// Start of a `{# #}` comment directly above a definition
+    fn doc_comment_start(before: &str) -> Option<usize> {
+        let trimmed = before.strip_suffix('\n').unwrap_or(before);
+        if !trimmed.ends_with("#}") {
+            return None;
+        }
+        let start = trimmed.rfind("{#")?;
+        (start == 0 || trimmed[..start].ends_with('\n')).then_some(start)
+    }
+
+    /// Calls to `name` in `content`, as the call span and its arguments
+    fn calls(content: &str, name: &str) -> Vec<(std::ops::Range<usize>, String)> {
+        let pattern = format!(r"(?:^|[^\w.])({})\s*\(", regex::escape(name));
+        let re = Regex::new(&pattern).expect("valid regex");
+
+        let mut calls = Vec::new();
+        for caps in re.captures_iter(content) {
+            let name_match = caps.get(1).unwrap();
+            let open = caps.get(0).unwrap().end();
+
+            // Skip the definition itself
+            if content[..name_match.start()].trim_end().ends_with("macro") {
+                continue;
+            }
+
+            if let Some(close) = Self::closing_paren(content, open) {
+                calls.push((
+                    name_match.start()..close + 1,
+                    content[open..close].to_string(),
+                ));
+            }
+        }
+        calls
+    }
+
+    /// Whether `body` references the variable `var`
+    fn references(body: &str, var: &str) -> bool {
+        let pattern = format!(r"(?:^|[^\w.]){}\b", regex::escape(var));
+        Regex::new(&pattern).expect("valid regex").is_match(body)
+    }
+
+    /// Index of the parenthesis closing the one opened before `open`
+    fn closing_paren(content: &str, open: usize) -> Option<usize> {
+        let mut depth = 1;
+        let mut quote = None;
+        for (i, c) in content[open..].char_indices() {
+            match (quote, c) {
+                (Some(q), c) if c == q => quote = None,
+                (Some(_), _) => {}
+                (None, '"' | '\'') => quote = Some(c),
+                (None, '(') => depth += 1,
+                (None, ')') => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(open + i);
+                    }
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// Words, string literals and punctuation of a macro body
+    fn token_count(body: &str) -> usize {
+        static TOKEN: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r#""[^"]*"|'[^']*'|\w+|[^\s\w]"#).expect("valid regex"));
+        TOKEN.find_iter(body).count()
+    }
+
+    /// Build an expression rendering to the same string as the macro body
+    fn body_expression(candidate: &InlineCandidate, argument: &str) -> String {
+        static EXPRESSION: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r"(?s)\{\{(-?)(.*?)(-?)\}\}").expect("valid regex"));
+
+        let body = &candidate.body;
+        let mut literals = Vec::new();
+        let mut expressions = Vec::new();
+        let mut last = 0;
+        for caps in EXPRESSION.captures_iter(body) {
+            let whole = caps.get(0).unwrap();
+            literals.push(body[last..whole.start()].to_string());
+            expressions.push((
+                !caps[1].is_empty(),
+                caps[2].trim().to_string(),
+                !caps[3].is_empty(),
+            ));
+            last = whole.end();
+        }
+        literals.push(body[last..].to_string());
+
+        // Apply the trim markers to the surrounding text
+        if candidate.trim_start {
+            literals[0] = literals[0].trim_start().to_string();
+        }
+        for (i, (trim_before, _, trim_after)) in expressions.iter().enumerate() {
+            if *trim_before {
+                literals[i] = literals[i].trim_end().to_string();
+            }
+            if *trim_after {
+                literals[i + 1] = literals[i + 1].trim_start().to_string();
+            }
+        }
+        if candidate.trim_end {
+            let last = literals.len() - 1;
+            literals[last] = literals[last].trim_end().to_string();
+        }
+
+        let argument = if argument
+            .chars()
+            .all(|c| c.is_alphanumeric() || "_.".contains(c))
+        {
+            argument.to_string()
+        } else {
+            format!("({})", argument)
+        };
+
+        let mut parts = Vec::new();
+        for (i, literal) in literals.iter().enumerate() {
+            if !literal.is_empty() {
+                parts.push(Self::string_literal(literal));
+            }
+            if let Some((_, expression, _)) = expressions.get(i) {
+                let expression = if candidate.param.is_empty() {
+                    expression.clone()
+                } else {
+                    Self::substitute(expression, &candidate.param, &argument)
+                };
+                parts.push(format!("({})", expression));
+            }
+        }
+
+        match parts.as_slice() {
+            [] => "\"\"".to_string(),
+            // Macros always return a string
+            [only] if !only.starts_with('"') => format!("{} | string", only),
+            _ => parts.join(" ~ "),
+        }
+    }
+
+    /// Replace references to `param` (but not `x.param`) with `argument`
+    fn substitute(expression: &str, param: &str, argument: &str) -> String {
+        let pattern = format!(r"(^|[^\w.]){}\b", regex::escape(param));
+        Regex::new(&pattern)
+            .expect("valid regex")
+            .replace_all(expression, |caps: &regex::Captures| {
+                format!("{}{}", &caps[1], argument)
+            })
+            .into_owned()
+    }
+
+    fn string_literal(text: &str) -> String {
+        let escaped = text
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+            .replace('\t', "\\t");
+        format!("\"{}\"", escaped)
+    }
+
+    /// Drop `name` from `{% from ... import %}` statements
+    fn remove_import(content: &str, name: &str) -> String {
+        static IMPORT: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(r#"(?m)^(\{%-?\s*from\s+"[^"]+"\s+import\s+)([\w\s,]+?)(\s*-?%\}\n?)"#)
+                .expect("valid regex")
+        });
+
+        IMPORT
+            .replace_all(content, |caps: &regex::Captures| {
+                let names: Vec<&str> = caps[2]
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|n| !n.is_empty() && *n != name)
+                    .collect();
+                if names.is_empty() {
+                    String::new()
+                } else {
+                    format!("{}{}{}", &caps[1], names.join(", "), &caps[3])
+                }
+            })
+            .into_owned()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -624,6 +968,12 @@ defaultBackend:
         let bare_vars = MacroPostProcessor::find_bare_variables(macro_body);
         assert!(bare_vars.contains(&"chroot".to_string()));
         assert!(bare_vars.contains(&"image".to_string()));
+
+        // The macro's context parameter is not a bare variable
+        let bare_vars = MacroPostProcessor::find_bare_variables(
+            "{{ ctx.name }}: {{ ctx | toyaml }}{% if ctx.enabled %}on{% endif %}",
+        );
+        assert!(bare_vars.is_empty(), "{:?}", bare_vars);
     }
 
     #[test]
@@ -690,4 +1040,96 @@ test
         assert!(result.contains("values.controller.image.chroot"));
         assert!(!result.contains(" chroot "));
     }
+
+    #[test]
+    fn test_inline_single_use_macro() {
+        let mut helpers = r#"{#
+Chart name
+#}
+{%- macro name(ctx) -%}
+{{- pack.name | truncate(63) -}}
+{%- endmacro %}
+{%- macro labels(ctx) -%}
+app: {{ pack.name }}
+{%- endmacro %}
+"#
+        .to_string();
+        let mut deployment = r#"{%- from "_helpers.j2" import labels, name -%}
+metadata:
+  name: {{ name() }}
+  labels:
+    {{- labels() | nindent(4) }}
+"#
+        .to_string();
+        let mut service = r#"{%- from "_helpers.j2" import labels -%}
+metadata:
+  labels:
+    {{- labels() | nindent(4) }}
+"#
+        .to_string();
+        let original_service = service.clone();
+
+        let inlined =
+            MacroInliner::default().inline(&mut [&mut helpers, &mut deployment, &mut service]);
+
+        // `labels` is called twice and stays a macro
+        assert_eq!(inlined, ["name"]);
+        assert_eq!(
+            helpers,
+            "{%- macro labels(ctx) -%}\napp: {{ pack.name }}\n{%- endmacro %}\n"
+        );
+        assert_eq!(
+            deployment,
+            r#"{%- from "_helpers.j2" import labels -%}
+metadata:
+  name: {{ ((pack.name | truncate(63)) | string) }}
+  labels:
+    {{- labels() | nindent(4) }}
+"#
+        );
+        assert_eq!(service, original_service);
+    }
+
+    #[test]
+    fn test_inline_substitutes_context() {
+        let mut helpers = r#"{%- macro port(ctx) %}
+name: {{ ctx.name }}
+port: {{- ctx.port }}{% endmacro %}
+"#
+        .to_string();
+        let mut service = r#"{%- from "_helpers.j2" import port -%}
+{%- for p in values.ports %}
+  - {{- port(p) | indent(4) }}
+{%- endfor %}
+"#
+        .to_string();
+
+        MacroInliner::default().inline(&mut [&mut helpers, &mut service]);
+
+        assert_eq!(helpers, "");
+        assert_eq!(
+            service,
+            r#"{%- for p in values.ports %}
+  - {{- ("\nname: " ~ (p.name) ~ "\nport:" ~ (p.port)) | indent(4) }}
+{%- endfor %}
+"#
+        );
+    }
+
+    #[test]
+    fn test_inline_keeps_large_and_complex_macros() {
+        let helpers = r#"{%- macro image(ctx) %}
+{%- if values.image.digest %}@{{ values.image.digest }}{% endif %}
+{%- endmacro %}
+{%- macro name(ctx) -%}
+{{ pack.name }}-{{ release.name }}
+{%- endmacro %}
+"#;
+        let mut content = format!("{}{{{{ image() }}}}{{{{ name() }}}}\n", helpers);
+
+        let inlined = MacroInliner::new(4).inline(&mut [&mut content]);
+
+        assert!(inlined.is_empty());
+        assert!(content.starts_with(helpers));
+    }
 }
@@ -334,9 +334,10 @@ fn parse_command(pair: pest::iterators::Pair<Rule>) -> Result<Command> {
                 }
             }
 
-            // Convert method call to function call
+            // Convert method call to function call, named after the full
+            // path so the receiver isn't lost (`Capabilities.APIVersions.Has`)
             if let Some(f) = field {
-                let method_name = f.path.last().cloned().unwrap_or_default();
+                let method_name = f.path.join(".");
                 if args.is_empty() {
                     Ok(Command::Field(f))
                 } else {
@@ -0,0 +1,364 @@
+//! JSON Schema → Sherpack schema conversion
+//!
+//! Translates a Helm `values.schema.json` into the simplified
+//! [`SherpSchema`] format, written as the pack's `values.schema.yaml`.
+//!
+//! The simplified format covers types, `required`, `enum`, numeric and
+//! length bounds, `pattern`, defaults, nested objects and arrays. Anything
+//! else (`oneOf`, `$ref`, `additionalProperties`, ...) is dropped with a
+//! warning, which makes validation of that property looser, never stricter.
+
+use serde_json::Value as JsonValue;
+use sherpack_core::{SherpProperty, SherpSchema, SherpType};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::error::{ConversionWarning, WarningCategory, WarningSeverity};
+
+/// Keywords that only annotate a schema and can be dropped silently
+const ANNOTATIONS: &[&str] = &[
+    "$schema",
+    "$id",
+    "$comment",
+    "title",
+    "examples",
+    "readOnly",
+    "writeOnly",
+    "deprecated",
+];
+
+/// Keywords translated to [`SherpProperty`] fields
+const TRANSLATED: &[&str] = &[
+    "type",
+    "description",
+    "default",
+    "required",
+    "enum",
+    "pattern",
+    "minimum",
+    "maximum",
+    "minLength",
+    "maxLength",
+    "properties",
+    "items",
+    "minItems",
+    "maxItems",
+];
+
+/// Result of converting a JSON Schema
+#[derive(Debug)]
+pub struct SchemaConversion {
+    /// The simplified schema
+    pub schema: SherpSchema,
+    /// Constructs that couldn't be translated
+    pub warnings: Vec<ConversionWarning>,
+}
+
+/// Convert a JSON Schema document to a Sherpack schema
+///
+/// `file` is the path reported in warnings.
+pub fn convert_json_schema(json: &JsonValue, file: &Path) -> SchemaConversion {
+    let mut converter = SchemaConverter {
+        file: file.to_path_buf(),
+        warnings: Vec::new(),
+    };
+
+    let root = converter.property("", json);
+    let schema = SherpSchema {
+        schema_version: "sherpack/v1".to_string(),
+        title: json.get("title").and_then(|t| t.as_str()).map(String::from),
+        description: root.description,
+        properties: root.properties.unwrap_or_default(),
+    };
+
+    SchemaConversion {
+        schema,
+        warnings: converter.warnings,
+    }
+}
+
+struct SchemaConverter {
+    file: PathBuf,
+    warnings: Vec<ConversionWarning>,
+}
+
+impl SchemaConverter {
+    /// Convert the schema of the value at `path` (dotted, empty for the root)
+    fn property(&mut self, path: &str, node: &JsonValue) -> SherpProperty {
+        let mut prop = SherpProperty {
+            prop_type: SherpType::Any,
+            description: None,
+            default: None,
+            required: false,
+            enum_values: None,
+            pattern: None,
+            min: None,
+            max: None,
+            min_length: None,
+            max_length: None,
+            properties: None,
+            items: None,
+            min_items: None,
+            max_items: None,
+        };
+
+        let Some(obj) = node.as_object() else {
+            // `true` / `false` schemas
+            return prop;
+        };
+
+        for key in obj.keys() {
+            if !ANNOTATIONS.contains(&key.as_str()) && !TRANSLATED.contains(&key.as_str()) {
+                self.dropped(path, key);
+            }
+        }
+
+        prop.prop_type = self.prop_type(path, obj);
+        prop.description = obj
+            .get("description")
+            .and_then(|d| d.as_str())
+            .map(String::from);
+        prop.default = obj.get("default").cloned();
+        prop.enum_values = obj.get("enum").and_then(|e| e.as_array()).cloned();
+        prop.pattern = obj
+            .get("pattern")
+            .and_then(|p| p.as_str())
+            .map(String::from);
+        prop.min = obj.get("minimum").and_then(|m| m.as_f64());
+        prop.max = obj.get("maximum").and_then(|m| m.as_f64());
+        prop.min_length = count(obj.get("minLength"));
+        prop.max_length = count(obj.get("maxLength"));
+        prop.min_items = count(obj.get("minItems"));
+        prop.max_items = count(obj.get("maxItems"));
+
+        let required: Vec<&str> = obj
+            .get("required")
+            .and_then(|r| r.as_array())
+            .map(|r| r.iter().filter_map(|name| name.as_str()).collect())
+            .unwrap_or_default();
+        let empty = serde_json::Map::new();
+        let props = obj
+            .get("properties")
+            .and_then(|p| p.as_object())
+            .unwrap_or(&empty);
+
+        for name in required.iter().filter(|name| !props.contains_key(**name)) {
+            self.warn(
+                &join(path, name),
+                "required",
+                format!(
+                    "'{}' is required but not declared in properties, the requirement was dropped",
+                    join(path, name)
+                ),
+            );
+        }
+
+        if !props.is_empty() {
+            let properties: BTreeMap<String, SherpProperty> = props
+                .iter()
+                .map(|(name, child)| {
+                    let mut child_prop = self.property(&join(path, name), child);
+                    child_prop.required = required.contains(&name.as_str());
+                    (name.clone(), child_prop)
+                })
+                .collect();
+            prop.properties = Some(properties);
+        }
+
+        match obj.get("items") {
+            Some(items) if items.is_object() => {
+                prop.items = Some(Box::new(self.property(&format!("{}[]", path), items)));
+            }
+            Some(_) => self.dropped(path, "items"),
+            None => {}
+        }
+
+        prop
+    }
+
+    /// Translate `type`, inferring it from the structure when absent
+    fn prop_type(&mut self, path: &str, obj: &serde_json::Map<String, JsonValue>) -> SherpType {
+        let types: Vec<&str> = match obj.get("type") {
+            Some(JsonValue::String(t)) => vec![t.as_str()],
+            Some(JsonValue::Array(types)) => types.iter().filter_map(|t| t.as_str()).collect(),
+            _ if obj.contains_key("properties") => vec!["object"],
+            _ if obj.contains_key("items") => vec!["array"],
+            _ => return SherpType::Any,
+        };
+
+        if types.contains(&"null") {
+            self.warn(
+                path,
+                "type",
+                format!(
+                    "'{}' accepts null, which values.schema.yaml can't express, it is no longer type-checked",
+                    display(path)
+                ),
+            );
+            return SherpType::Any;
+        }
+
+        match types.as_slice() {
+            [single] => match *single {
+                "string" => SherpType::String,
+                "number" => SherpType::Number,
+                "integer" => SherpType::Integer,
+                "boolean" => SherpType::Boolean,
+                "array" => SherpType::Array,
+                "object" => SherpType::Object,
+                other => {
+                    self.warn(
+                        path,
+                        "type",
+                        format!("Unknown type '{}' for '{}'", other, display(path)),
+                    );
+                    SherpType::Any
+                }
+            },
+            [] => SherpType::Any,
+            _ => {
+                self.warn(
+                    path,
+                    "type",
+                    format!(
+                        "'{}' accepts several types ({}), it is no longer type-checked",
+                        display(path),
+                        types.join(", ")
+                    ),
+                );
+                SherpType::Any
+            }
+        }
+    }
+
+    fn dropped(&mut self, path: &str, keyword: &str) {
+        self.warn(
+            path,
+            keyword,
+            format!(
+                "'{}' on '{}' can't be expressed in values.schema.yaml and was dropped",
+                keyword,
+                display(path)
+            ),
+        );
+    }
+
+    fn warn(&mut self, path: &str, keyword: &str, message: String) {
+        self.warnings.push(ConversionWarning {
+            severity: WarningSeverity::Warning,
+            category: WarningCategory::UnsupportedFeature,
+            file: self.file.clone(),
+            line: None,
+            pattern: if path.is_empty() {
+                keyword.to_string()
+            } else {
+                format!("{} ({})", keyword, path)
+            },
+            message,
+            suggestion: Some(
+                "Check the value with `fail` in a template if the constraint matters".to_string(),
+            ),
+            doc_link: None,
+        });
+    }
+}
+
+fn count(value: Option<&JsonValue>) -> Option<usize> {
+    value
+        .and_then(|v| v.as_u64())
+        .and_then(|n| usize::try_from(n).ok())
+}
+
+fn join(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", path, name)
+    }
+}
+
+fn display(path: &str) -> &str {
+    if path.is_empty() { "the root" } else { path }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn convert(json: JsonValue) -> SchemaConversion {
+        convert_json_schema(&json, Path::new("values.schema.yaml"))
+    }
+
+    #[test]
+    fn test_convert_properties() {
+        let result = convert(json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "title": "Values",
+            "type": "object",
+            "required": ["image"],
+            "properties": {
+                "replicaCount": {"type": "integer", "minimum": 1, "maximum": 10, "default": 1},
+                "image": {
+                    "type": "object",
+                    "required": ["repository"],
+                    "properties": {
+                        "repository": {"type": "string", "pattern": "^[a-z/]+$"},
+                        "pullPolicy": {"type": "string", "enum": ["Always", "IfNotPresent"]}
+                    }
+                },
+                "hosts": {"type": "array", "minItems": 1, "items": {"type": "string", "maxLength": 253}}
+            }
+        }));
+
+        assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+        let schema = result.schema;
+        assert_eq!(schema.title.as_deref(), Some("Values"));
+
+        let replicas = &schema.properties["replicaCount"];
+        assert_eq!(replicas.prop_type, SherpType::Integer);
+        assert_eq!((replicas.min, replicas.max), (Some(1.0), Some(10.0)));
+        assert_eq!(replicas.default, Some(json!(1)));
+        assert!(!replicas.required);
+
+        let image = &schema.properties["image"];
+        assert!(image.required);
+        let image_props = image.properties.as_ref().unwrap();
+        assert!(image_props["repository"].required);
+        assert_eq!(
+            image_props["repository"].pattern.as_deref(),
+            Some("^[a-z/]+$")
+        );
+        assert_eq!(
+            image_props["pullPolicy"].enum_values,
+            Some(vec![json!("Always"), json!("IfNotPresent")])
+        );
+
+        let hosts = &schema.properties["hosts"];
+        assert_eq!(hosts.prop_type, SherpType::Array);
+        assert_eq!(hosts.min_items, Some(1));
+        assert_eq!(hosts.items.as_ref().unwrap().max_length, Some(253));
+    }
+
+    #[test]
+    fn test_unsupported_constructs_warn() {
+        let result = convert(json!({
+            "type": "object",
+            "properties": {
+                "port": {"oneOf": [{"type": "integer"}, {"type": "string"}]},
+                "tag": {"type": ["string", "null"]},
+                "extra": {"type": "object", "additionalProperties": false}
+            }
+        }));
+
+        let patterns: Vec<&str> = result.warnings.iter().map(|w| w.pattern.as_str()).collect();
+        assert_eq!(
+            patterns,
+            ["additionalProperties (extra)", "oneOf (port)", "type (tag)"]
+        );
+
+        // Dropped constructs loosen validation
+        assert_eq!(result.schema.properties["port"].prop_type, SherpType::Any);
+        assert_eq!(result.schema.properties["tag"].prop_type, SherpType::Any);
+    }
+}
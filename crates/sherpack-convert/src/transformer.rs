@@ -19,6 +19,9 @@
 use crate::ast::*;
 use crate::type_inference::{InferredType, TypeContext, TypeHeuristics};
 use phf::phf_map;
+use serde::Serialize;
+use std::cell::RefCell;
+use std::collections::HashSet;
 
 // =============================================================================
 // FILTER MAPPINGS - Direct 1:1 conversions
@@ -83,7 +86,7 @@ static FILTER_MAP: phf::Map<&'static str, &'static str> = phf_map! {
     "keys" => "keys",
     "values" => "values",
     "merge" => "merge",
-    "mergeOverwrite" => "merge",
+    "mergeOverwrite" => "mergeoverwrite",
     "deepCopy" => "deepcopy",
 
     // Type conversion
@@ -233,17 +236,55 @@ impl TransformWarning {
     }
 }
 
+// =============================================================================
+// FUNCTION COVERAGE
+// =============================================================================
+
+/// How well a Helm function or filter is covered by the conversion
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FunctionSupport {
+    /// Converted to an equivalent Jinja2 construct
+    Mapped,
+    /// Converted, but the output needs manual review
+    Warning,
+    /// Not supported - replaced with a placeholder
+    Unsupported,
+}
+
+impl FunctionSupport {
+    /// Get the display label
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Mapped => "mapped",
+            Self::Warning => "warning",
+            Self::Unsupported => "unsupported",
+        }
+    }
+}
+
 // =============================================================================
 // TRANSFORMER
 // =============================================================================
 
+/// Parameter holding the caller's context in generated macros
+const MACRO_CONTEXT: &str = "ctx";
+
+/// Call of a generated macro, passing `context` if it isn't the root
+fn macro_call(name: &str, context: Option<String>) -> String {
+    format!("{}({})", name, context.unwrap_or_default())
+}
+
 /// Block type for tracking nested structures
+///
+/// Blocks that change the meaning of `.` keep the context they replaced,
+/// restored at their `end`.
 #[derive(Debug, Clone)]
 enum BlockType {
     If,
-    Range,
-    With,
-    Define,
+    Range(Option<String>),
+    With(Option<String>),
+    Define(Option<String>),
 }
 
 /// Transformer for converting Go template AST to idiomatic Jinja2
@@ -261,6 +302,8 @@ pub struct Transformer {
     type_context: Option<TypeContext>,
     /// Counter for auto-generated secret names (Cell for interior mutability)
     secret_counter: std::cell::Cell<usize>,
+    /// Every function/filter encountered, in order, with its support level
+    function_usage: RefCell<Vec<(String, FunctionSupport)>>,
 }
 
 impl Default for Transformer {
@@ -278,6 +321,7 @@ impl Transformer {
             context_var: None,
             type_context: None,
             secret_counter: std::cell::Cell::new(0),
+            function_usage: RefCell::new(Vec::new()),
         }
     }
 
@@ -298,6 +342,32 @@ impl Transformer {
         &self.warnings
     }
 
+    /// Get every function/filter encountered during transformation
+    pub fn function_usage(&self) -> Vec<(String, FunctionSupport)> {
+        self.function_usage.borrow().clone()
+    }
+
+    /// Unsupported functions used so far, each with its suggested alternative
+    pub fn unsupported_functions(&self) -> Vec<(String, &'static str)> {
+        let mut seen = HashSet::new();
+        self.function_usage
+            .borrow()
+            .iter()
+            .filter(|(_, support)| *support == FunctionSupport::Unsupported)
+            .filter_map(|(name, _)| {
+                let alternative = UNSUPPORTED_FEATURES.get(name.as_str())?;
+                seen.insert(name.as_str())
+                    .then(|| (name.clone(), *alternative))
+            })
+            .collect()
+    }
+
+    fn record_function(&self, name: &str, support: FunctionSupport) {
+        self.function_usage
+            .borrow_mut()
+            .push((name.to_string(), support));
+    }
+
     #[allow(dead_code)]
     fn add_warning(&mut self, warning: TransformWarning) {
         self.warnings.push(warning);
@@ -332,8 +402,15 @@ impl Transformer {
 
         match &action.body {
             // Comments: {{/* ... */}} → {# ... #}
+            //
+            // Multi-line comments keep their layout, and trim markers are
+            // kept so the rendered whitespace doesn't change
             ActionBody::Comment(text) => {
-                format!("{{# {} #}}", text.trim())
+                if text.contains('\n') {
+                    format!("{{#{}{}{}#}}", trim_left, text, trim_right)
+                } else {
+                    format!("{{#{} {} {}#}}", trim_left, text.trim(), trim_right)
+                }
             }
 
             // If: {{- if .X }} → {%- if x %}
@@ -365,19 +442,24 @@ impl Transformer {
                 let block = self.block_stack.pop();
                 let end_tag = match &block {
                     Some(BlockType::If) => "endif",
-                    Some(BlockType::Range) => "endfor",
-                    Some(BlockType::With) => "endif",
-                    Some(BlockType::Define) => "endmacro",
+                    Some(BlockType::Range(_)) => "endfor",
+                    Some(BlockType::With(_)) => "endif",
+                    Some(BlockType::Define(_)) => "endmacro",
                     None => "endif",
                 };
 
-                // Restore context after with block
-                if let Some(BlockType::With) = &block {
-                    self.context_var = None;
+                // Restore the context of the enclosing block
+                if let Some(
+                    BlockType::Range(previous)
+                    | BlockType::With(previous)
+                    | BlockType::Define(previous),
+                ) = &block
+                {
+                    self.context_var = previous.clone();
                 }
 
                 // endmacro doesn't support trim on closing
-                if matches!(block, Some(BlockType::Define)) {
+                if matches!(block, Some(BlockType::Define(_))) {
                     format!("{{%{} {} %}}", trim_left, end_tag)
                 } else if trim_right == "-" {
                     format!("{{%{} {} -%}}", trim_left, end_tag)
@@ -400,9 +482,12 @@ impl Transformer {
                         .map(|i| i.trim_start_matches('$').to_string())
                 });
 
-                self.block_stack.push(BlockType::Range);
-
+                // The collection is evaluated in the enclosing context,
+                // the body with `.` bound to the element
                 let collection = self.transform_pipeline(pipeline);
+                self.block_stack.push(BlockType::Range(
+                    self.context_var.replace(value_var.clone()),
+                ));
 
                 // Determine if this is a dictionary iteration
                 let is_dict = self.is_dict_type(&collection);
@@ -434,8 +519,8 @@ impl Transformer {
                 let ctx_value = self.transform_pipeline(pipeline);
                 let ctx_var = "_with_ctx".to_string();
 
-                self.block_stack.push(BlockType::With);
-                self.context_var = Some(ctx_var.clone());
+                self.block_stack
+                    .push(BlockType::With(self.context_var.replace(ctx_var.clone())));
 
                 // with becomes: if value, set context, use context
                 format!(
@@ -444,23 +529,32 @@ impl Transformer {
                 )
             }
 
-            // Define: {{- define "name" }} → {%- macro name() %}
+            // Define: {{- define "name" }} → {%- macro name(ctx) %}
+            //
+            // `.` in the body is the context passed by the caller
             ActionBody::Define(name) => {
                 let macro_name = self.strip_chart_prefix(name);
-                self.block_stack.push(BlockType::Define);
-                format!("{{%{} macro {}() %}}", trim_left, macro_name)
+                self.block_stack.push(BlockType::Define(
+                    self.context_var.replace(MACRO_CONTEXT.to_string()),
+                ));
+                format!(
+                    "{{%{} macro {}({}) {}%}}",
+                    trim_left, macro_name, MACRO_CONTEXT, trim_right
+                )
             }
 
-            // Template/Include: {{ template "name" . }} → {{ name() }}
-            ActionBody::Template { name, .. } => {
+            // Template/Include: {{ template "name" . }} → {{ name(ctx) }}
+            ActionBody::Template { name, pipeline } => {
                 let macro_name = self.strip_chart_prefix(name);
-                format!("{{{{ {}() }}}}", macro_name)
+                let context = pipeline.as_ref().and_then(|p| self.pipeline_context(p));
+                format!("{{{{ {} }}}}", macro_call(&macro_name, context))
             }
 
             // Block: {{- block "name" . }} → {%- block name %}
             ActionBody::Block { name, .. } => {
                 let block_name = self.strip_chart_prefix(name);
-                self.block_stack.push(BlockType::Define);
+                self.block_stack
+                    .push(BlockType::Define(self.context_var.clone()));
                 format!("{{%{} block {} %}}", trim_left, block_name)
             }
 
@@ -581,6 +675,8 @@ impl Transformer {
             _ => None,
         } {
             if let Some(length_arg) = args.first() {
+                // Works, but the generated secret name should be reviewed
+                self.record_function(name, FunctionSupport::Warning);
                 let length = self.transform_argument(length_arg);
                 let secret_name = self.next_secret_name();
                 return match charset {
@@ -598,6 +694,7 @@ impl Transformer {
 
         // 1. Check for unsupported features first
         if let Some(alternative) = UNSUPPORTED_FEATURES.get(name) {
+            self.record_function(name, FunctionSupport::Unsupported);
             // Return a placeholder with comment
             return format!(
                 "__UNSUPPORTED_{}__ {{# {} #}}",
@@ -608,20 +705,37 @@ impl Transformer {
 
         // 2. Native Jinja2 operators (most elegant conversion)
         if let Some(result) = self.transform_to_native_operator(name, args) {
+            self.record_function(name, FunctionSupport::Mapped);
             return result;
         }
 
         // 3. Special function handling
         if let Some(result) = self.transform_special_function(name, args) {
+            // tpl and lookup are preserved but behave differently at render time
+            let support = if matches!(name, "tpl" | "lookup") {
+                FunctionSupport::Warning
+            } else {
+                FunctionSupport::Mapped
+            };
+            self.record_function(name, support);
             return result;
         }
 
         // 4. Filter transformation
         if as_filter {
+            // Unknown filters are passed through unchanged and may not exist
+            let support = if FILTER_MAP.contains_key(name) || matches!(name, "default" | "contains")
+            {
+                FunctionSupport::Mapped
+            } else {
+                FunctionSupport::Warning
+            };
+            self.record_function(name, support);
             return self.transform_as_filter(name, args);
         }
 
-        // 5. Regular function call
+        // 5. Regular function call (passed through unchanged)
+        self.record_function(name, FunctionSupport::Warning);
         self.transform_as_function(name, args)
     }
 
@@ -753,11 +867,19 @@ impl Transformer {
             return Some(format!("[{}] | min", vals.join(", ")));
         }
 
-        // merge(a, b) → a | merge(b)
-        if name == "merge" && args.len() >= 2 {
+        // merge(a, b, c) → a | merge(b, c), same for mergeOverwrite
+        if (name == "merge" || name == "mergeOverwrite") && args.len() >= 2 {
             let base = self.transform_argument(&args[0]);
-            let overlay = self.transform_argument(&args[1]);
-            return Some(format!("({} | merge({}))", base, overlay));
+            let overlays: Vec<String> = args[1..]
+                .iter()
+                .map(|a| self.transform_argument(a))
+                .collect();
+            return Some(format!(
+                "({} | {}({}))",
+                base,
+                FILTER_MAP[name],
+                overlays.join(", ")
+            ));
         }
 
         // semverCompare(constraint, version) → version | semverCompare(constraint)
@@ -799,6 +921,12 @@ impl Transformer {
             return Some(format!("{} | tojson", val));
         }
 
+        // .Capabilities.APIVersions.Has "policy/v1" → capabilities.apiVersions.has("policy/v1")
+        if name == "Capabilities.APIVersions.Has" && args.len() == 1 {
+            let version = self.transform_argument(&args[0]);
+            return Some(format!("capabilities.apiVersions.has({})", version));
+        }
+
         // tpl(template, context) → tpl(template)
         if name == "tpl" && !args.is_empty() {
             let template = self.transform_argument(&args[0]);
@@ -1140,6 +1268,8 @@ impl Transformer {
             }
             "Files" => {
                 // Files access is unsupported - will be caught by function handling
+                let method = rest.first().copied().unwrap_or("");
+                self.record_function(&format!("Files.{}", method), FunctionSupport::Unsupported);
                 let full_path = std::iter::once(first)
                     .chain(rest.iter().copied())
                     .collect::<Vec<_>>()
@@ -1185,7 +1315,43 @@ impl Transformer {
             _ => "DYNAMIC_INCLUDE".to_string(),
         };
 
-        format!("{}()", name)
+        let context = args.get(1).and_then(|arg| self.argument_context(arg));
+        macro_call(&name, context)
+    }
+
+    /// Context passed to a macro for an `include` argument
+    ///
+    /// The root context (`.` outside any block, or `$`) isn't passed:
+    /// macros read `values`, `release`, ... as globals.
+    fn argument_context(&self, arg: &Argument) -> Option<String> {
+        match arg {
+            Argument::Variable(name) if name == "$" => None,
+            Argument::Variable(name) if name == "." || name.is_empty() => self.context_var.clone(),
+            Argument::Field(field) if field.path.is_empty() => {
+                if field.is_root {
+                    None
+                } else {
+                    self.context_var.clone()
+                }
+            }
+            _ => Some(self.transform_argument(arg)),
+        }
+    }
+
+    /// Context passed to a macro for a `template` pipeline
+    fn pipeline_context(&self, pipeline: &Pipeline) -> Option<String> {
+        match pipeline.commands.as_slice() {
+            [Command::Variable(name)] if name == "$" => None,
+            [Command::Variable(name)] if name == "." || name.is_empty() => self.context_var.clone(),
+            [Command::Field(field)] if field.path.is_empty() => {
+                if field.is_root {
+                    None
+                } else {
+                    self.context_var.clone()
+                }
+            }
+            _ => Some(self.transform_pipeline(pipeline)),
+        }
     }
 
     fn strip_chart_prefix(&self, name: &str) -> String {
@@ -1269,6 +1435,18 @@ mod tests {
         assert_eq!(transform("hello world"), "hello world");
     }
 
+    #[test]
+    fn test_comment_keeps_trim_and_layout() {
+        assert_eq!(
+            transform("a\n  {{- /* trimmed */ -}}\nb"),
+            "a\n  {#- trimmed -#}\nb"
+        );
+        assert_eq!(
+            transform("{{- /*\nLine one\n  Line two\n*/}}"),
+            "{#-\nLine one\n  Line two\n#}"
+        );
+    }
+
     #[test]
     fn test_comment() {
         assert_eq!(
@@ -1483,7 +1661,15 @@ mod tests {
     fn test_define() {
         assert_eq!(
             transform("{{- define \"myapp.name\" }}test{{- end }}"),
-            "{%- macro myapp_name() %}test{%- endmacro %}"
+            "{%- macro myapp_name(ctx) %}test{%- endmacro %}"
+        );
+    }
+
+    #[test]
+    fn test_define_uses_context() {
+        assert_eq!(
+            transform("{{- define \"myapp.port\" }}{{ .name }}: {{ . | toYaml }}{{- end }}"),
+            "{%- macro myapp_port(ctx) %}{{ ctx.name }}: {{ ctx | toyaml }}{%- endmacro %}"
         );
     }
 
@@ -1493,6 +1679,43 @@ mod tests {
             transform_with_prefix("{{ include \"myapp.fullname\" . }}", "myapp"),
             "{{ fullname() }}"
         );
+        assert_eq!(
+            transform_with_prefix("{{ include \"myapp.labels\" $ }}", "myapp"),
+            "{{ labels() }}"
+        );
+        assert_eq!(
+            transform_with_prefix("{{ include \"myapp.image\" .Values.image }}", "myapp"),
+            "{{ image(values.image) }}"
+        );
+    }
+
+    #[test]
+    fn test_include_in_range_passes_item() {
+        assert_eq!(
+            transform_with_prefix(
+                "{{- range .Values.ports }}{{ include \"myapp.port\" . | nindent 4 }}{{- end }}",
+                "myapp"
+            ),
+            "{%- for item in values.ports %}{{ port(item) | nindent(4) }}{%- endfor %}"
+        );
+        assert_eq!(
+            transform_with_prefix(
+                "{{- range $p := .Values.ports }}{{ template \"myapp.port\" . }}{{- end }}",
+                "myapp"
+            ),
+            "{%- for p in values.ports %}{{ port(p) }}{%- endfor %}"
+        );
+    }
+
+    #[test]
+    fn test_include_in_define_forwards_context() {
+        assert_eq!(
+            transform_with_prefix(
+                "{{- define \"myapp.labels\" }}{{ include \"myapp.name\" . }}{{- end }}",
+                "myapp"
+            ),
+            "{%- macro labels(ctx) %}{{ name(ctx) }}{%- endmacro %}"
+        );
     }
 
     // =========================================================================
@@ -1540,6 +1763,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_merge_keeps_helm_semantics() {
+        assert_eq!(
+            transform("{{ merge .Values.a .Values.b }}"),
+            "{{ (values.a | merge(values.b)) }}"
+        );
+        assert_eq!(
+            transform("{{ mergeOverwrite .Values.a .Values.b .Values.c }}"),
+            "{{ (values.a | mergeoverwrite(values.b, values.c)) }}"
+        );
+    }
+
     // =========================================================================
     // Range generation
     // =========================================================================
@@ -1579,6 +1814,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_capabilities_api_versions_has() {
+        assert_eq!(
+            transform(r#"{{ if .Capabilities.APIVersions.Has "policy/v1" }}x{{ end }}"#),
+            r#"{% if capabilities.apiVersions.has("policy/v1") %}x{% endif %}"#
+        );
+        assert_eq!(
+            transform(r#"{{ if $.Capabilities.APIVersions.Has "apps/v1/Deployment" }}x{{ end }}"#),
+            r#"{% if capabilities.apiVersions.has("apps/v1/Deployment") %}x{% endif %}"#
+        );
+    }
+
     // =========================================================================
     // Dictionary iteration with TypeContext
     // =========================================================================
@@ -1693,6 +1940,27 @@ controller:
         );
     }
 
+    // =========================================================================
+    // Function coverage
+    // =========================================================================
+
+    #[test]
+    fn test_function_usage_categories() {
+        let ast = parser::parse(
+            r#"{{ .Values.x | quote }}{{ eq .Values.a 1 }}{{ tpl .Values.t . }}{{ genCA "x" 365 }}{{ .Values.y | frobnicate }}"#,
+        )
+        .unwrap();
+        let mut transformer = Transformer::new();
+        transformer.transform(&ast);
+
+        let usage = transformer.function_usage();
+        assert!(usage.contains(&("quote".to_string(), FunctionSupport::Mapped)));
+        assert!(usage.contains(&("eq".to_string(), FunctionSupport::Mapped)));
+        assert!(usage.contains(&("tpl".to_string(), FunctionSupport::Warning)));
+        assert!(usage.contains(&("genCA".to_string(), FunctionSupport::Unsupported)));
+        assert!(usage.contains(&("frobnicate".to_string(), FunctionSupport::Warning)));
+    }
+
     #[test]
     fn test_lookup_malformed_falls_back_to_empty() {
         // Wrong arity — fall back to {} so converted templates compile
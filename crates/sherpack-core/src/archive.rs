@@ -8,12 +8,12 @@ use flate2::read::GzDecoder;
 use flate2::write::GzEncoder;
 use std::collections::HashMap;
 use std::fs::File;
-use std::io::{Read, Write};
+use std::io::{Cursor, Read, Write};
 use std::path::{Path, PathBuf};
 use tar::{Archive, Builder, Header};
 
 use crate::error::{CoreError, Result};
-use crate::manifest::Manifest;
+use crate::manifest::{HashAlgorithm, Manifest, SignatureStatus, VerificationResult};
 use crate::pack::LoadedPack;
 
 /// Create a tar.gz archive from a loaded pack
@@ -26,8 +26,17 @@ use crate::pack::LoadedPack;
 /// - values.schema.yaml (if present)
 /// - templates/* (all template files)
 pub fn create_archive(pack: &LoadedPack, output: &Path) -> Result<PathBuf> {
+    create_archive_with_hash(pack, output, HashAlgorithm::default())
+}
+
+/// Create a tar.gz archive whose MANIFEST checksums use `algorithm`
+pub fn create_archive_with_hash(
+    pack: &LoadedPack,
+    output: &Path,
+    algorithm: HashAlgorithm,
+) -> Result<PathBuf> {
     // Generate manifest
-    let manifest = Manifest::generate(pack)?;
+    let manifest = Manifest::generate_with_hash(pack, algorithm)?;
     let manifest_content = manifest.to_string();
 
     // Create output file
@@ -172,7 +181,7 @@ fn read_all_files_from_archive(archive_path: &Path) -> Result<HashMap<String, Ve
 /// Verify archive integrity by checking all file checksums
 ///
 /// Uses single-pass reading for O(n) performance instead of O(n²).
-pub fn verify_archive(archive_path: &Path) -> Result<crate::manifest::VerificationResult> {
+pub fn verify_archive(archive_path: &Path) -> Result<VerificationResult> {
     let manifest = read_manifest_from_archive(archive_path)?;
 
     // Read all files in a single pass for O(n) performance
@@ -186,6 +195,75 @@ pub fn verify_archive(archive_path: &Path) -> Result<crate::manifest::Verificati
     })
 }
 
+/// Path of the detached minisign signature for an archive (`<archive>.minisig`)
+#[must_use]
+pub fn signature_path(archive_path: &Path) -> PathBuf {
+    PathBuf::from(format!("{}.minisig", archive_path.display()))
+}
+
+/// Verify an archive's signature, then its file checksums
+///
+/// The detached signature covers the whole archive, MANIFEST included, so the
+/// MANIFEST checksums are only as trustworthy as the signature. An invalid
+/// signature marks the result invalid; a missing one is only reported in
+/// `signature_status`, leaving the policy to the caller.
+pub fn verify_archive_signed(
+    archive_path: &Path,
+    public_key: &minisign::PublicKey,
+) -> Result<VerificationResult> {
+    let status = check_signature(archive_path, public_key)?;
+
+    let mut result = verify_archive(archive_path)?;
+    if matches!(status, SignatureStatus::Invalid { .. }) {
+        result.valid = false;
+    }
+    result.signature_status = Some(status);
+
+    Ok(result)
+}
+
+/// Check the detached signature of an archive against a public key
+fn check_signature(
+    archive_path: &Path,
+    public_key: &minisign::PublicKey,
+) -> Result<SignatureStatus> {
+    let signature = match std::fs::read_to_string(signature_path(archive_path)) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(SignatureStatus::Missing);
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let signature = match minisign::SignatureBox::from_string(&signature) {
+        Ok(signature) => signature,
+        Err(e) => {
+            return Ok(SignatureStatus::Invalid {
+                reason: format!("malformed signature: {}", e),
+            });
+        }
+    };
+
+    let data = std::fs::read(archive_path)?;
+    let status = match minisign::verify(
+        public_key,
+        &signature,
+        &mut Cursor::new(&data),
+        true,
+        false,
+        false,
+    ) {
+        Ok(()) => SignatureStatus::Valid {
+            trusted_comment: signature.trusted_comment().ok(),
+        },
+        Err(e) => SignatureStatus::Invalid {
+            reason: e.to_string(),
+        },
+    };
+
+    Ok(status)
+}
+
 /// Information about a file in an archive
 #[derive(Debug, Clone)]
 pub struct ArchiveEntry {
@@ -335,6 +413,115 @@ metadata:
         assert!(result.missing.is_empty());
     }
 
+    #[test]
+    fn test_verify_archive_with_each_hash() {
+        let temp = TempDir::new().unwrap();
+        let pack_dir = temp.path().join("pack");
+        std::fs::create_dir_all(&pack_dir).unwrap();
+        create_test_pack(&pack_dir);
+        let pack = LoadedPack::load(&pack_dir).unwrap();
+
+        for algorithm in HashAlgorithm::ALL {
+            let archive_path = temp.path().join(format!("{}.tar.gz", algorithm));
+            create_archive_with_hash(&pack, &archive_path, algorithm).unwrap();
+
+            let manifest = read_manifest_from_archive(&archive_path).unwrap();
+            assert!(manifest.files.iter().all(|f| f.algorithm == algorithm));
+            assert!(verify_archive(&archive_path).unwrap().valid);
+        }
+    }
+
+    /// Package a test pack, sign it, and return the archive and public key
+    fn signed_archive(temp: &TempDir) -> (PathBuf, minisign::PublicKey) {
+        let pack_dir = temp.path().join("pack");
+        std::fs::create_dir_all(&pack_dir).unwrap();
+        create_test_pack(&pack_dir);
+
+        let pack = LoadedPack::load(&pack_dir).unwrap();
+        let archive_path = temp.path().join("test.tar.gz");
+        create_archive(&pack, &archive_path).unwrap();
+
+        let minisign::KeyPair { pk, sk } =
+            minisign::KeyPair::generate_unencrypted_keypair().unwrap();
+        let data = std::fs::read(&archive_path).unwrap();
+        let signature = minisign::sign(
+            None,
+            &sk,
+            Cursor::new(&data),
+            Some("sherpack:testpack"),
+            None,
+        )
+        .unwrap();
+        std::fs::write(signature_path(&archive_path), signature.to_string()).unwrap();
+
+        (archive_path, pk)
+    }
+
+    #[test]
+    fn test_verify_archive_signed_valid() {
+        let temp = TempDir::new().unwrap();
+        let (archive_path, pk) = signed_archive(&temp);
+
+        let result = verify_archive_signed(&archive_path, &pk).unwrap();
+        assert!(result.valid);
+        assert!(result.is_trusted());
+        assert!(matches!(
+            result.signature_status,
+            Some(SignatureStatus::Valid { .. })
+        ));
+
+        // Checksum-only verification makes no claim about the signature
+        let result = verify_archive(&archive_path).unwrap();
+        assert!(result.valid);
+        assert_eq!(result.signature_status, None);
+        assert!(!result.is_trusted());
+    }
+
+    #[test]
+    fn test_verify_archive_signed_tampered_manifest() {
+        let temp = TempDir::new().unwrap();
+        let (archive_path, pk) = signed_archive(&temp);
+
+        // Change a file and repackage, so the MANIFEST checksums match the
+        // tampered content but the signature no longer covers it
+        let tampered = temp.path().join("tampered");
+        extract_archive(&archive_path, &tampered).unwrap();
+        std::fs::write(tampered.join("values.yaml"), "replicas: 100\n").unwrap();
+        let pack = LoadedPack::load(&tampered).unwrap();
+        create_archive(&pack, &archive_path).unwrap();
+
+        assert!(verify_archive(&archive_path).unwrap().valid);
+
+        let result = verify_archive_signed(&archive_path, &pk).unwrap();
+        assert!(!result.valid);
+        assert!(result.mismatched.is_empty());
+        assert!(matches!(
+            result.signature_status,
+            Some(SignatureStatus::Invalid { .. })
+        ));
+    }
+
+    #[test]
+    fn test_verify_archive_signed_missing_or_wrong_key() {
+        let temp = TempDir::new().unwrap();
+        let (archive_path, _) = signed_archive(&temp);
+
+        let other = minisign::KeyPair::generate_unencrypted_keypair().unwrap();
+        let result = verify_archive_signed(&archive_path, &other.pk).unwrap();
+        assert!(!result.valid);
+        assert!(matches!(
+            result.signature_status,
+            Some(SignatureStatus::Invalid { .. })
+        ));
+
+        // Without a signature, checksums still decide `valid`
+        std::fs::remove_file(signature_path(&archive_path)).unwrap();
+        let result = verify_archive_signed(&archive_path, &other.pk).unwrap();
+        assert!(result.valid);
+        assert!(!result.is_trusted());
+        assert_eq!(result.signature_status, Some(SignatureStatus::Missing));
+    }
+
     #[test]
     fn test_default_archive_name() {
         let temp = TempDir::new().unwrap();
@@ -50,17 +50,81 @@ impl From<&PackMetadata> for PackInfo {
     }
 }
 
+/// API versions served by every supported Kubernetes release
+///
+/// Used when rendering offline, so that templates gating on built-in APIs
+/// behave as they would against a real cluster.
+pub const DEFAULT_API_VERSIONS: &[&str] = &[
+    "v1",
+    "admissionregistration.k8s.io/v1",
+    "apiextensions.k8s.io/v1",
+    "apiregistration.k8s.io/v1",
+    "apps/v1",
+    "authentication.k8s.io/v1",
+    "authorization.k8s.io/v1",
+    "autoscaling/v1",
+    "autoscaling/v2",
+    "batch/v1",
+    "certificates.k8s.io/v1",
+    "coordination.k8s.io/v1",
+    "discovery.k8s.io/v1",
+    "events.k8s.io/v1",
+    "networking.k8s.io/v1",
+    "node.k8s.io/v1",
+    "policy/v1",
+    "rbac.authorization.k8s.io/v1",
+    "scheduling.k8s.io/v1",
+    "storage.k8s.io/v1",
+];
+
 /// Cluster capabilities
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Capabilities {
     /// Kubernetes version
     pub kube_version: KubeVersion,
 
-    /// Available API versions
+    /// Available API versions, as `group/version` or `group/version/Kind`
     pub api_versions: Vec<String>,
 }
 
+impl Default for Capabilities {
+    fn default() -> Self {
+        Self {
+            kube_version: KubeVersion::default(),
+            api_versions: DEFAULT_API_VERSIONS.iter().map(|v| v.to_string()).collect(),
+        }
+    }
+}
+
+impl Capabilities {
+    /// Set the Kubernetes version
+    pub fn with_kube_version(mut self, kube_version: KubeVersion) -> Self {
+        self.kube_version = kube_version;
+        self
+    }
+
+    /// Add API versions on top of the current ones (duplicates are ignored)
+    pub fn with_api_versions<I, S>(mut self, api_versions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        for version in api_versions {
+            let version = version.into();
+            if !self.has_api_version(&version) {
+                self.api_versions.push(version);
+            }
+        }
+        self
+    }
+
+    /// Check whether an API version (`batch/v1`) or kind (`batch/v1/Job`) is available
+    pub fn has_api_version(&self, api_version: &str) -> bool {
+        self.api_versions.iter().any(|v| v == api_version)
+    }
+}
+
 /// Kubernetes version info
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -92,6 +156,41 @@ impl KubeVersion {
             minor: parts.get(1).unwrap_or(&"28").to_string(),
         }
     }
+
+    /// Parse a version like `1.29.0` or `v1.29`, rejecting anything whose
+    /// major and minor parts are not numeric
+    pub fn parse(version: &str) -> Option<Self> {
+        let mut parts = version.trim().trim_start_matches('v').split('.');
+        let numeric = |p: Option<&str>| p.is_some_and(|p| p.parse::<u32>().is_ok());
+
+        if numeric(parts.next()) && numeric(parts.next()) {
+            Some(Self::new(version.trim()))
+        } else {
+            None
+        }
+    }
+
+    /// SemVer form of the version, for matching `kubeVersion` constraints
+    ///
+    /// Distribution suffixes (`v1.28.3-gke.100`, minors like `30+`) are
+    /// dropped, so such a cluster matches as the release it is built from.
+    pub fn semver(&self) -> semver::Version {
+        let number = |s: &str| {
+            s.chars()
+                .take_while(char::is_ascii_digit)
+                .collect::<String>()
+                .parse()
+                .unwrap_or(0)
+        };
+        let patch = self
+            .version
+            .trim_start_matches('v')
+            .split('.')
+            .nth(2)
+            .map_or(0, number);
+
+        semver::Version::new(number(&self.major), number(&self.minor), patch)
+    }
 }
 
 /// Current template information
@@ -166,5 +265,29 @@ mod tests {
         assert_eq!(ctx.pack.name, "mypack");
         assert_eq!(ctx.release.name, "myapp");
         assert!(ctx.release.is_install);
+        assert!(ctx.capabilities.has_api_version("apps/v1"));
+    }
+
+    #[test]
+    fn test_capabilities_overrides() {
+        let caps = Capabilities::default()
+            .with_kube_version(KubeVersion::parse("1.29.0").unwrap())
+            .with_api_versions(["monitoring.coreos.com/v1", "batch/v1"]);
+
+        assert_eq!(caps.kube_version.version, "v1.29.0");
+        assert_eq!(caps.kube_version.minor, "29");
+        assert!(caps.has_api_version("monitoring.coreos.com/v1"));
+        assert!(!caps.has_api_version("example.com/v1"));
+        assert_eq!(
+            caps.api_versions
+                .iter()
+                .filter(|v| *v == "batch/v1")
+                .count(),
+            1
+        );
+
+        assert!(KubeVersion::parse("v1.30").is_some());
+        assert!(KubeVersion::parse("latest").is_none());
+        assert!(KubeVersion::parse("1").is_none());
     }
 }
@@ -38,6 +38,9 @@ pub enum CoreError {
     #[error("Values merge error: {message}")]
     ValuesMerge { message: String },
 
+    #[error("Values patch failed at '{pointer}': {message}")]
+    ValuesPatch { pointer: String, message: String },
+
     #[error("Missing required field: {field}")]
     MissingField { field: String },
 
@@ -53,6 +56,14 @@ pub enum CoreError {
     #[error("Invalid manifest: {message}")]
     InvalidManifest { message: String },
 
+    #[error("Invalid pack structure at '{path}': {message}")]
+    InvalidStructure { path: String, message: String },
+
+    #[error(
+        "Pack '{name}' is a library pack: it only provides macros and cannot be deployed on its own, add it as a dependency instead"
+    )]
+    LibraryPack { name: String },
+
     #[error("Archive error: {message}")]
     Archive { message: String },
 
@@ -61,6 +72,9 @@ pub enum CoreError {
 
     #[error("Glob pattern error: {message}")]
     GlobPattern { message: String },
+
+    #[error("Invalid secret policy: {message}")]
+    InvalidSecretPolicy { message: String },
 }
 
 impl CoreError {
@@ -29,8 +29,8 @@
 //! {% endfor %}
 //! ```
 
-use std::collections::HashMap;
-use std::path::{Path, PathBuf};
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Component, Path, PathBuf};
 use std::sync::{Arc, RwLock};
 
 use serde::{Deserialize, Serialize};
@@ -42,7 +42,7 @@ use crate::error::{CoreError, Result};
 /// This trait allows for different implementations:
 /// - `SandboxedFileProvider`: Real filesystem access (sandboxed to pack root)
 /// - `MockFileProvider`: In-memory files for testing
-/// - `ArchiveFileProvider`: Read files from a tar.gz archive (future)
+/// - `ArchiveFileProvider`: Read files straight from a packaged `.tar.gz`
 pub trait FileProvider: Send + Sync {
     /// Read the contents of a file as bytes
     fn get(&self, path: &str) -> Result<Vec<u8>>;
@@ -227,6 +227,7 @@ impl FileProvider for SandboxedFileProvider {
 
     fn glob(&self, pattern: &str) -> Result<Vec<FileEntry>> {
         // Validate the glob pattern
+        check_glob_pattern(pattern)?;
         let glob_pattern = glob::Pattern::new(pattern).map_err(|e| CoreError::GlobPattern {
             message: format!("invalid glob pattern '{}': {}", pattern, e),
         })?;
@@ -284,6 +285,178 @@ impl FileProvider for SandboxedFileProvider {
     }
 }
 
+/// Reject glob patterns that could only match outside the pack root
+///
+/// Globs are matched against paths relative to the root, so an absolute
+/// pattern or a `..` component would silently match nothing; treat both as
+/// sandbox violations instead.
+fn check_glob_pattern(pattern: &str) -> Result<()> {
+    if Path::new(pattern).is_absolute() || pattern.split(['/', '\\']).any(|c| c == "..") {
+        return Err(CoreError::FileAccess {
+            path: pattern.to_string(),
+            message: "glob pattern escapes pack directory (sandbox violation)".to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// File provider that serves files from a packaged pack archive
+///
+/// Reads go through [`read_file_from_archive`](crate::read_file_from_archive),
+/// so nothing is extracted to disk. The archive is indexed once on open;
+/// entries with absolute paths or `..` components are never served, and
+/// requests are held to the same rules as [`SandboxedFileProvider`].
+#[derive(Debug)]
+pub struct ArchiveFileProvider {
+    /// Path to the `.tar.gz` archive
+    archive: PathBuf,
+    /// Regular files contained in the archive (relative, `/`-separated)
+    files: BTreeSet<String>,
+    /// Cache of file contents to avoid re-scanning the archive
+    cache: Arc<RwLock<HashMap<String, Vec<u8>>>>,
+}
+
+impl ArchiveFileProvider {
+    /// Open an archive and index its files
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the archive cannot be read.
+    pub fn open(archive: impl AsRef<Path>) -> Result<Self> {
+        let archive = archive.as_ref().to_path_buf();
+
+        let files = crate::archive::list_archive(&archive)
+            .map_err(|e| CoreError::FileAccess {
+                path: archive.display().to_string(),
+                message: format!("failed to read pack archive: {}", e),
+            })?
+            .into_iter()
+            .filter(|entry| !entry.is_dir)
+            .filter_map(|entry| normalize_archive_path(&entry.path))
+            .collect();
+
+        Ok(Self {
+            archive,
+            files,
+            cache: Arc::new(RwLock::new(HashMap::new())),
+        })
+    }
+
+    /// Resolve a template-supplied path to an indexed archive entry
+    fn resolve_path(&self, relative: &str) -> Result<String> {
+        if Path::new(relative).is_absolute() {
+            return Err(CoreError::FileAccess {
+                path: relative.to_string(),
+                message: "absolute paths are not allowed in templates".to_string(),
+            });
+        }
+
+        let normalized = normalize_archive_path(relative).ok_or_else(|| CoreError::FileAccess {
+            path: relative.to_string(),
+            message: "path escapes pack directory (sandbox violation)".to_string(),
+        })?;
+
+        if !self.files.contains(&normalized) {
+            return Err(CoreError::FileAccess {
+                path: relative.to_string(),
+                message: "file not found".to_string(),
+            });
+        }
+
+        Ok(normalized)
+    }
+}
+
+/// Normalize an archive-relative path, rejecting anything that could escape
+/// the pack root (absolute paths, prefixes and `..` components)
+fn normalize_archive_path(path: &str) -> Option<String> {
+    let mut parts = Vec::new();
+    for component in Path::new(&path.replace('\\', "/")).components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_string_lossy().into_owned()),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+impl FileProvider for ArchiveFileProvider {
+    fn get(&self, path: &str) -> Result<Vec<u8>> {
+        let resolved = self.resolve_path(path)?;
+
+        // Check cache first
+        {
+            let cache = self.cache.read().map_err(|_| CoreError::FileAccess {
+                path: path.to_string(),
+                message: "cache lock poisoned".to_string(),
+            })?;
+
+            if let Some(content) = cache.get(&resolved) {
+                return Ok(content.clone());
+            }
+        }
+
+        let content =
+            crate::archive::read_file_from_archive(&self.archive, &resolved).map_err(|e| {
+                CoreError::FileAccess {
+                    path: path.to_string(),
+                    message: format!("failed to read file: {}", e),
+                }
+            })?;
+
+        // Update cache
+        {
+            let mut cache = self.cache.write().map_err(|_| CoreError::FileAccess {
+                path: path.to_string(),
+                message: "cache lock poisoned".to_string(),
+            })?;
+
+            cache.insert(resolved, content.clone());
+        }
+
+        Ok(content)
+    }
+
+    fn exists(&self, path: &str) -> bool {
+        self.resolve_path(path).is_ok()
+    }
+
+    fn glob(&self, pattern: &str) -> Result<Vec<FileEntry>> {
+        check_glob_pattern(pattern)?;
+        let glob_pattern = glob::Pattern::new(pattern).map_err(|e| CoreError::GlobPattern {
+            message: format!("invalid glob pattern '{}': {}", pattern, e),
+        })?;
+
+        // The index is a BTreeSet, so entries come out already sorted
+        let mut entries = Vec::new();
+        for path in self.files.iter().filter(|p| glob_pattern.matches(p)) {
+            let content = match self.get(path) {
+                Ok(bytes) => String::from_utf8_lossy(&bytes).to_string(),
+                Err(_) => continue,
+            };
+            let name = Path::new(path)
+                .file_name()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+
+            entries.push(FileEntry {
+                path: path.clone(),
+                name,
+                size: content.len(),
+                content,
+            });
+        }
+
+        Ok(entries)
+    }
+
+    fn lines(&self, path: &str) -> Result<Vec<String>> {
+        let content = self.get_string(path)?;
+        Ok(content.lines().map(String::from).collect())
+    }
+}
+
 /// Mock file provider for testing
 ///
 /// This provider stores files in memory, allowing tests to run
@@ -339,6 +512,7 @@ impl FileProvider for MockFileProvider {
     }
 
     fn glob(&self, pattern: &str) -> Result<Vec<FileEntry>> {
+        check_glob_pattern(pattern)?;
         let glob_pattern = glob::Pattern::new(pattern).map_err(|e| CoreError::GlobPattern {
             message: format!("invalid glob pattern '{}': {}", pattern, e),
         })?;
@@ -402,6 +576,12 @@ impl Files {
         Ok(Self::new(provider))
     }
 
+    /// Create a Files instance served from a packaged pack archive
+    pub fn for_archive(archive: impl AsRef<Path>) -> Result<Self> {
+        let provider = ArchiveFileProvider::open(archive)?;
+        Ok(Self::new(provider))
+    }
+
     /// Create a mock Files instance for testing
     pub fn mock() -> MockFileProvider {
         MockFileProvider::new()
@@ -550,6 +730,17 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_glob_rejects_traversal() {
+        let temp = create_test_pack();
+        let provider = SandboxedFileProvider::new(temp.path()).unwrap();
+
+        for pattern in ["../*", "config/../../*.txt", "/etc/*"] {
+            let err = provider.glob(pattern).unwrap_err().to_string();
+            assert!(err.contains("sandbox violation"), "{}: {}", pattern, err);
+        }
+    }
+
     #[test]
     fn test_mock_provider() {
         let provider = MockFileProvider::new()
@@ -647,4 +838,77 @@ mod tests {
                 .contains("invalid glob pattern")
         );
     }
+
+    fn create_test_archive(dir: &Path) -> PathBuf {
+        let archive = dir.join("pack.tar.gz");
+        let encoder = flate2::write::GzEncoder::new(
+            std::fs::File::create(&archive).unwrap(),
+            flate2::Compression::default(),
+        );
+        let mut builder = tar::Builder::new(encoder);
+        for (path, content) in [
+            ("Pack.yaml", "apiVersion: sherpack/v1\n"),
+            ("values.yaml", "replicas: 2\n"),
+            ("templates/configmap.yaml", "name: {{ release.name }}\n"),
+            ("templates/_helpers.tpl", "{% macro x() %}{% endmacro %}\n"),
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, path, content.as_bytes())
+                .unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap();
+        archive
+    }
+
+    #[test]
+    fn test_archive_provider_reads_templated_file() {
+        let temp = TempDir::new().unwrap();
+        let provider = ArchiveFileProvider::open(create_test_archive(temp.path())).unwrap();
+
+        assert_eq!(
+            provider.get_string("templates/configmap.yaml").unwrap(),
+            "name: {{ release.name }}\n"
+        );
+        assert_eq!(
+            provider.get_string("./values.yaml").unwrap(),
+            "replicas: 2\n"
+        );
+        assert!(provider.exists("Pack.yaml"));
+        assert!(!provider.exists("missing.yaml"));
+        assert_eq!(provider.lines("values.yaml").unwrap(), vec!["replicas: 2"]);
+    }
+
+    #[test]
+    fn test_archive_provider_glob() {
+        let temp = TempDir::new().unwrap();
+        let provider = ArchiveFileProvider::open(create_test_archive(temp.path())).unwrap();
+
+        let entries = provider.glob("templates/*").unwrap();
+        let paths: Vec<_> = entries.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec!["templates/_helpers.tpl", "templates/configmap.yaml"]
+        );
+        assert_eq!(entries[1].name, "configmap.yaml");
+        assert_eq!(entries[1].content, "name: {{ release.name }}\n");
+
+        assert!(provider.glob("../*").is_err());
+    }
+
+    #[test]
+    fn test_archive_provider_sandbox() {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(temp.path().join("secret.txt"), "secret").unwrap();
+        let provider = ArchiveFileProvider::open(create_test_archive(temp.path())).unwrap();
+
+        let err = provider.get("../secret.txt").unwrap_err().to_string();
+        assert!(err.contains("sandbox violation"));
+        assert!(provider.get("templates/../../secret.txt").is_err());
+        assert!(provider.get("/etc/passwd").is_err());
+        assert!(!provider.exists("../secret.txt"));
+    }
 }
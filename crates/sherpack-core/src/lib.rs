@@ -14,28 +14,37 @@ pub mod error;
 pub mod files;
 pub mod manifest;
 pub mod pack;
+pub mod provenance;
 pub mod release;
 pub mod schema;
 pub mod secrets;
 pub mod values;
 
 pub use archive::{
-    ArchiveEntry, create_archive, default_archive_name, extract_archive, list_archive,
-    read_file_from_archive, read_manifest_from_archive, verify_archive,
+    ArchiveEntry, create_archive, create_archive_with_hash, default_archive_name, extract_archive,
+    list_archive, read_file_from_archive, read_manifest_from_archive, signature_path,
+    verify_archive, verify_archive_signed,
 };
 pub use context::TemplateContext;
 pub use error::{CoreError, ValidationErrorInfo};
 pub use files::FileEntry as FilesFileEntry;
-pub use files::{FileProvider, Files, MockFileProvider, SandboxedFileProvider};
+pub use files::{
+    ArchiveFileProvider, FileProvider, Files, MockFileProvider, SandboxedFileProvider,
+};
 pub use manifest::FileEntry as ManifestFileEntry;
-pub use manifest::{Manifest, MismatchedFile, VerificationResult};
+pub use manifest::{HashAlgorithm, Manifest, MismatchedFile, SignatureStatus, VerificationResult};
 pub use pack::{
     CrdConfig, CrdManifest, CrdUninstallConfig, CrdUpgradeConfig, CrdUpgradeStrategy, Dependency,
-    LoadedPack, Pack, PackKind, PackMetadata, ResolvePolicy,
+    GitSource, ImportValue, KubeVersionConstraint, LoadedPack, Pack, PackKind, PackMetadata,
+    ResolvePolicy,
 };
+pub use provenance::{ValueOrigin, ValueSource, ValuesProvenance};
 pub use release::{Release, ReleaseInfo, ReleaseStatus};
 pub use schema::{
     Schema, SchemaValidator, SherpProperty, SherpSchema, SherpType, ValidationResult,
 };
-pub use secrets::{SecretCharset, SecretEntry, SecretGenerator, SecretState};
-pub use values::{Values, parse_set_values};
+pub use secrets::{
+    AMBIGUOUS_CHARS, PreviousSecret, SecretCharset, SecretEntry, SecretGenerator, SecretPolicy,
+    SecretState,
+};
+pub use values::{PatchOp, SetValues, Values, parse_set_literal_values, parse_set_values};
@@ -2,12 +2,15 @@
 //!
 //! The MANIFEST file is a text file included in every Sherpack archive that provides:
 //! - Package metadata (name, version, creation timestamp)
-//! - SHA256 checksums for all files
+//! - Checksums for all files, each tagged with its [`HashAlgorithm`]
 //! - Overall archive digest for quick integrity verification
+//!
+//! Every file line records the algorithm it was hashed with, so manifests
+//! written before SHA-512 and BLAKE3 support (all `sha256:`) still verify.
 
 use chrono::{DateTime, Utc};
 use semver::Version;
-use sha2::{Digest, Sha256};
+use sha2::{Digest, Sha256, Sha512};
 use std::collections::BTreeMap;
 use std::io::{BufReader, Read};
 use std::path::Path;
@@ -18,13 +21,109 @@ use crate::pack::LoadedPack;
 /// Current manifest format version
 pub const MANIFEST_VERSION: u32 = 1;
 
+/// Checksum algorithm for the files listed in a manifest
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum HashAlgorithm {
+    /// SHA-256 (the only algorithm older manifests use)
+    #[default]
+    Sha256,
+    /// SHA-512
+    Sha512,
+    /// BLAKE3
+    Blake3,
+}
+
+impl HashAlgorithm {
+    /// All supported algorithms
+    pub const ALL: [HashAlgorithm; 3] = [Self::Sha256, Self::Sha512, Self::Blake3];
+
+    /// Tag written in front of the digest (`sha256`, `sha512`, `blake3`)
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Sha256 => "sha256",
+            Self::Sha512 => "sha512",
+            Self::Blake3 => "blake3",
+        }
+    }
+
+    /// Parse an algorithm tag
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|algorithm| algorithm.name() == name)
+    }
+
+    fn hasher(self) -> Hasher {
+        match self {
+            Self::Sha256 => Hasher::Sha256(Sha256::new()),
+            Self::Sha512 => Hasher::Sha512(Sha512::new()),
+            Self::Blake3 => Hasher::Blake3(Box::new(blake3::Hasher::new())),
+        }
+    }
+}
+
+impl std::fmt::Display for HashAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl std::str::FromStr for HashAlgorithm {
+    type Err = CoreError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s).ok_or_else(|| CoreError::InvalidManifest {
+            message: format!(
+                "Unsupported hash algorithm '{}' (expected sha256, sha512 or blake3)",
+                s
+            ),
+        })
+    }
+}
+
+/// Incremental hasher for any [`HashAlgorithm`]
+enum Hasher {
+    Sha256(Sha256),
+    Sha512(Sha512),
+    Blake3(Box<blake3::Hasher>),
+}
+
+impl Hasher {
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Self::Sha256(h) => h.update(data),
+            Self::Sha512(h) => h.update(data),
+            Self::Blake3(h) => {
+                h.update(data);
+            }
+        }
+    }
+
+    fn finalize_hex(self) -> String {
+        match self {
+            Self::Sha256(h) => hex::encode(h.finalize()),
+            Self::Sha512(h) => hex::encode(h.finalize()),
+            Self::Blake3(h) => hex::encode(h.finalize().as_bytes()),
+        }
+    }
+}
+
 /// A file entry in the manifest
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FileEntry {
     /// Relative path within the archive
     pub path: String,
-    /// SHA256 hash of the file contents
-    pub sha256: String,
+    /// Algorithm the digest was computed with
+    pub algorithm: HashAlgorithm,
+    /// Hex digest of the file contents
+    pub digest: String,
+}
+
+impl FileEntry {
+    /// Checksum as written in the manifest (`<algorithm>:<digest>`)
+    pub fn checksum(&self) -> String {
+        format!("{}:{}", self.algorithm, self.digest)
+    }
 }
 
 /// Package manifest containing checksums and metadata
@@ -40,7 +139,7 @@ pub struct Manifest {
     pub created: DateTime<Utc>,
     /// Files and their checksums (sorted by path)
     pub files: Vec<FileEntry>,
-    /// Overall digest of all file checksums
+    /// Overall SHA256 digest of all file checksums
     pub digest: String,
 }
 
@@ -56,7 +155,7 @@ impl std::fmt::Display for Manifest {
         // Files section
         writeln!(f, "[files]")?;
         for entry in &self.files {
-            writeln!(f, "{} sha256:{}", entry.path, entry.sha256)?;
+            writeln!(f, "{} {}", entry.path, entry.checksum())?;
         }
         writeln!(f)?;
 
@@ -67,20 +166,25 @@ impl std::fmt::Display for Manifest {
 }
 
 impl Manifest {
-    /// Generate a manifest from a loaded pack
+    /// Generate a manifest from a loaded pack, hashing files with SHA256
     pub fn generate(pack: &LoadedPack) -> Result<Self> {
+        Self::generate_with_hash(pack, HashAlgorithm::default())
+    }
+
+    /// Generate a manifest from a loaded pack, hashing files with `algorithm`
+    pub fn generate_with_hash(pack: &LoadedPack, algorithm: HashAlgorithm) -> Result<Self> {
         let mut files = BTreeMap::new();
 
         // Add Pack.yaml
         let pack_yaml_path = pack.root.join("Pack.yaml");
         if pack_yaml_path.exists() {
-            let hash = hash_file(&pack_yaml_path)?;
+            let hash = hash_file(&pack_yaml_path, algorithm)?;
             files.insert("Pack.yaml".to_string(), hash);
         }
 
         // Add values.yaml
         if pack.values_path.exists() {
-            let hash = hash_file(&pack.values_path)?;
+            let hash = hash_file(&pack.values_path, algorithm)?;
             files.insert("values.yaml".to_string(), hash);
         }
 
@@ -88,7 +192,7 @@ impl Manifest {
         if let Some(schema_path) = &pack.schema_path
             && schema_path.exists()
         {
-            let hash = hash_file(schema_path)?;
+            let hash = hash_file(schema_path, algorithm)?;
             let rel_path = schema_path
                 .file_name()
                 .map(|n| n.to_string_lossy().to_string())
@@ -99,7 +203,7 @@ impl Manifest {
         // Add template files
         let template_files = pack.template_files()?;
         for file_path in template_files {
-            let hash = hash_file(&file_path)?;
+            let hash = hash_file(&file_path, algorithm)?;
             let rel_path = file_path
                 .strip_prefix(&pack.root)
                 .unwrap_or(&file_path)
@@ -113,7 +217,11 @@ impl Manifest {
         // Convert to FileEntry vec (already sorted by BTreeMap)
         let file_entries: Vec<FileEntry> = files
             .into_iter()
-            .map(|(path, sha256)| FileEntry { path, sha256 })
+            .map(|(path, digest)| FileEntry {
+                path,
+                algorithm,
+                digest,
+            })
             .collect();
 
         // Calculate overall digest from all file hashes
@@ -168,13 +276,16 @@ impl Manifest {
                     digest = Some(hash.to_string());
                 }
             } else if in_files_section {
-                // File line: path sha256:HASH
+                // File line: path ALGORITHM:HASH
                 if let Some((path, hash_part)) = line.rsplit_once(' ')
-                    && let Some(hash) = hash_part.strip_prefix("sha256:")
+                    && let Some((algorithm, hash)) = hash_part.split_once(':')
                 {
+                    // An entry we cannot check must not be silently dropped
+                    let algorithm = algorithm.parse::<HashAlgorithm>()?;
                     files.push(FileEntry {
                         path: path.to_string(),
-                        sha256: hash.to_string(),
+                        algorithm,
+                        digest: hash.to_string(),
                     });
                 }
             } else {
@@ -246,17 +357,19 @@ impl Manifest {
             valid: true,
             mismatched: Vec::new(),
             missing: Vec::new(),
+            signature_status: None,
         };
 
         for entry in &self.files {
             match read_file(&entry.path) {
                 Ok(content) => {
-                    let actual_hash = hash_bytes(&content);
-                    if actual_hash != entry.sha256 {
+                    let actual_hash = hash_bytes(&content, entry.algorithm);
+                    if actual_hash != entry.digest {
                         result.valid = false;
                         result.mismatched.push(MismatchedFile {
                             path: entry.path.clone(),
-                            expected: entry.sha256.clone(),
+                            algorithm: entry.algorithm,
+                            expected: entry.digest.clone(),
                             actual: actual_hash,
                         });
                     }
@@ -287,6 +400,32 @@ pub struct VerificationResult {
     pub mismatched: Vec<MismatchedFile>,
     /// Files that are missing
     pub missing: Vec<String>,
+    /// Signature covering the MANIFEST (`None` if no signature check was requested)
+    pub signature_status: Option<SignatureStatus>,
+}
+
+impl VerificationResult {
+    /// Whether the checksums match and a valid signature vouches for them
+    pub fn is_trusted(&self) -> bool {
+        self.valid && matches!(self.signature_status, Some(SignatureStatus::Valid { .. }))
+    }
+}
+
+/// Outcome of checking the signature that covers the MANIFEST
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureStatus {
+    /// Signed by the expected key
+    Valid {
+        /// Trusted comment embedded in the signature
+        trusted_comment: Option<String>,
+    },
+    /// No signature next to the archive
+    Missing,
+    /// Signature is malformed, or does not match the archive or key
+    Invalid {
+        /// Why verification failed
+        reason: String,
+    },
 }
 
 /// A file with a mismatched checksum
@@ -294,17 +433,19 @@ pub struct VerificationResult {
 pub struct MismatchedFile {
     /// File path
     pub path: String,
-    /// Expected SHA256 from manifest
+    /// Algorithm the checksums were computed with
+    pub algorithm: HashAlgorithm,
+    /// Expected digest from manifest
     pub expected: String,
-    /// Actual SHA256 of file
+    /// Actual digest of file
     pub actual: String,
 }
 
-/// Calculate SHA256 hash of a file
-fn hash_file(path: &Path) -> Result<String> {
+/// Calculate the hash of a file
+fn hash_file(path: &Path, algorithm: HashAlgorithm) -> Result<String> {
     let file = std::fs::File::open(path)?;
     let mut reader = BufReader::new(file);
-    let mut hasher = Sha256::new();
+    let mut hasher = algorithm.hasher();
     let mut buffer = [0u8; 8192];
 
     loop {
@@ -315,23 +456,30 @@ fn hash_file(path: &Path) -> Result<String> {
         hasher.update(&buffer[..bytes_read]);
     }
 
-    Ok(hex::encode(hasher.finalize()))
+    Ok(hasher.finalize_hex())
 }
 
-/// Calculate SHA256 hash of bytes
-fn hash_bytes(data: &[u8]) -> String {
-    let mut hasher = Sha256::new();
+/// Calculate the hash of bytes
+fn hash_bytes(data: &[u8], algorithm: HashAlgorithm) -> String {
+    let mut hasher = algorithm.hasher();
     hasher.update(data);
-    hex::encode(hasher.finalize())
+    hasher.finalize_hex()
 }
 
 /// Calculate overall digest from file entries
+///
+/// SHA256 entries hash as `path:digest` exactly like older manifests did;
+/// other entries also bind their algorithm tag (`path:algorithm:digest`).
 fn calculate_digest(files: &[FileEntry]) -> String {
     let mut hasher = Sha256::new();
     for entry in files {
         hasher.update(entry.path.as_bytes());
         hasher.update(b":");
-        hasher.update(entry.sha256.as_bytes());
+        if entry.algorithm != HashAlgorithm::Sha256 {
+            hasher.update(entry.algorithm.name().as_bytes());
+            hasher.update(b":");
+        }
+        hasher.update(entry.digest.as_bytes());
         hasher.update(b"\n");
     }
     hex::encode(hasher.finalize())
@@ -366,11 +514,13 @@ mod tests {
             files: vec![
                 FileEntry {
                     path: "Pack.yaml".to_string(),
-                    sha256: "abc123".to_string(),
+                    algorithm: HashAlgorithm::Sha256,
+                    digest: "abc123".to_string(),
                 },
                 FileEntry {
                     path: "values.yaml".to_string(),
-                    sha256: "def456".to_string(),
+                    algorithm: HashAlgorithm::Blake3,
+                    digest: "def456".to_string(),
                 },
             ],
             digest: "overall789".to_string(),
@@ -382,7 +532,7 @@ mod tests {
         assert_eq!(parsed.version, manifest.version);
         assert_eq!(parsed.name, manifest.name);
         assert_eq!(parsed.pack_version, manifest.pack_version);
-        assert_eq!(parsed.files.len(), manifest.files.len());
+        assert_eq!(parsed.files, manifest.files);
         assert_eq!(parsed.digest, manifest.digest);
     }
 
@@ -407,24 +557,62 @@ sha256:789xyz
         assert_eq!(manifest.pack_version, Version::new(2, 0, 0));
         assert_eq!(manifest.files.len(), 2);
         assert_eq!(manifest.files[0].path, "Pack.yaml");
-        assert_eq!(manifest.files[0].sha256, "abc123");
+        assert_eq!(manifest.files[0].algorithm, HashAlgorithm::Sha256);
+        assert_eq!(manifest.files[0].digest, "abc123");
         assert_eq!(manifest.digest, "789xyz");
     }
 
     #[test]
     fn test_hash_bytes() {
-        let hash = hash_bytes(b"hello world");
+        let hash = hash_bytes(b"hello world", HashAlgorithm::Sha256);
         assert_eq!(
             hash,
             "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9"
         );
+        assert_eq!(hash_bytes(b"hello world", HashAlgorithm::Sha512).len(), 128);
+        assert_eq!(
+            hash_bytes(b"hello world", HashAlgorithm::Blake3),
+            "d74981efa70a0c880b8d8c1985d075dbcbf679b99a5f9914e5aaf96b831a9e24"
+        );
+    }
+
+    #[test]
+    fn test_manifest_parse_rejects_unknown_algorithm() {
+        let content = r#"sherpack-manifest-version: 1
+name: testpack
+version: 2.0.0
+created: 2025-01-15T10:30:00Z
+
+[files]
+Pack.yaml md5:abc123
+
+[digest]
+sha256:789xyz
+"#;
+
+        let err = Manifest::parse(content).unwrap_err();
+        assert!(err.to_string().contains("Unsupported hash algorithm 'md5'"));
+    }
+
+    #[test]
+    fn test_sha256_digest_unchanged() {
+        // Digest of older manifests must not change with algorithm tags
+        let files = vec![FileEntry {
+            path: "a".to_string(),
+            algorithm: HashAlgorithm::Sha256,
+            digest: "00".to_string(),
+        }];
+        let mut hasher = Sha256::new();
+        hasher.update(b"a:00\n");
+        assert_eq!(calculate_digest(&files), hex::encode(hasher.finalize()));
     }
 
     #[test]
     fn test_verification() {
         let files = vec![FileEntry {
             path: "test.txt".to_string(),
-            sha256: hash_bytes(b"content"),
+            algorithm: HashAlgorithm::Sha512,
+            digest: hash_bytes(b"content", HashAlgorithm::Sha512),
         }];
         let digest = calculate_digest(&files);
 
@@ -471,5 +659,6 @@ sha256:789xyz
 
         assert!(!result.valid);
         assert_eq!(result.mismatched.len(), 1);
+        assert_eq!(result.mismatched[0].algorithm, HashAlgorithm::Sha512);
     }
 }
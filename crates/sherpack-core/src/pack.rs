@@ -1,10 +1,12 @@
 //! Pack definition and loading
 
-use semver::Version;
+use semver::{Version, VersionReq};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 
+use crate::context::KubeVersion;
 use crate::error::{CoreError, Result};
 
 /// A Sherpack Pack - equivalent to a Helm Chart
@@ -34,6 +36,91 @@ pub struct Pack {
     pub crds: CrdConfig,
 }
 
+impl Pack {
+    /// Whether this is a library pack, providing macros to its dependents
+    pub fn is_library(&self) -> bool {
+        self.kind == PackKind::Library
+    }
+
+    /// Refuse library packs, which render nothing when deployed on their own
+    pub fn ensure_deployable(&self) -> Result<()> {
+        if self.is_library() {
+            return Err(CoreError::LibraryPack {
+                name: self.metadata.name.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Check the pack definition, collecting every problem found
+    ///
+    /// Covers the API version, the pack name, the `kubeVersion` constraint
+    /// and the dependencies (names, aliases and version constraints). The
+    /// pack version is already checked as SemVer when Pack.yaml is parsed.
+    pub fn validate(&self) -> std::result::Result<(), Vec<CoreError>> {
+        let mut errors = Vec::new();
+        let invalid = |message: String| CoreError::InvalidPack { message };
+
+        if self.api_version != "sherpack/v1" {
+            errors.push(invalid(format!(
+                "Unsupported API version: {}. Expected: sherpack/v1",
+                self.api_version
+            )));
+        }
+
+        let name = &self.metadata.name;
+        if name.trim().is_empty() {
+            errors.push(CoreError::MissingField {
+                field: "metadata.name".to_string(),
+            });
+        } else if !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        {
+            errors.push(invalid(format!(
+                "Invalid pack name '{}': only letters, digits, '-', '_' and '.' are allowed",
+                name
+            )));
+        }
+
+        if let Some(kube_version) = &self.metadata.kube_version
+            && let Err(e) = KubeVersionConstraint::parse(kube_version)
+        {
+            errors.push(e);
+        }
+
+        let mut seen = HashSet::new();
+        for (i, dep) in self.dependencies.iter().enumerate() {
+            if dep.name.trim().is_empty() {
+                errors.push(CoreError::MissingField {
+                    field: format!("dependencies[{}].name", i),
+                });
+                continue;
+            }
+            if !seen.insert(dep.effective_name()) {
+                errors.push(invalid(format!(
+                    "Duplicate dependency '{}': give each occurrence its own alias",
+                    dep.effective_name()
+                )));
+            }
+            if let Err(e) = VersionReq::parse(&dep.version) {
+                errors.push(invalid(format!(
+                    "Dependency '{}': invalid version constraint '{}': {}",
+                    dep.effective_name(),
+                    dep.version,
+                    e
+                )));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
 /// CRD handling configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -127,6 +214,9 @@ impl Default for CrdUninstallConfig {
 }
 
 /// Pack type
+///
+/// Library packs only provide macros to the packs depending on them: their
+/// templates are made available to the parent, but never rendered.
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum PackKind {
@@ -183,6 +273,63 @@ pub struct PackMetadata {
     pub annotations: std::collections::HashMap<String, String>,
 }
 
+/// A `kubeVersion` constraint, e.g. `>=1.25.0-0` or `>= 1.21 < 1.30 || ^1.31`
+///
+/// As in Helm, comparators may be separated by commas or spaces, and `||`
+/// separates alternatives.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KubeVersionConstraint {
+    alternatives: Vec<VersionReq>,
+}
+
+impl KubeVersionConstraint {
+    /// Parse a constraint
+    pub fn parse(constraint: &str) -> Result<Self> {
+        let invalid = |reason: String| CoreError::InvalidPack {
+            message: format!(
+                "Invalid kubeVersion constraint '{}': {}",
+                constraint, reason
+            ),
+        };
+
+        let mut alternatives = Vec::new();
+        for alternative in constraint.split("||") {
+            // Join operators written apart from their version (`>= 1.21`)
+            let mut comparators: Vec<String> = Vec::new();
+            let mut operator = String::new();
+            for token in alternative
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|t| !t.is_empty())
+            {
+                if token.chars().all(|c| "<>=~^".contains(c)) {
+                    operator.push_str(token);
+                } else {
+                    comparators.push(format!("{}{}", std::mem::take(&mut operator), token));
+                }
+            }
+            if !operator.is_empty() || comparators.is_empty() {
+                return Err(invalid("expected a version".to_string()));
+            }
+
+            alternatives.push(
+                VersionReq::parse(&comparators.join(", ")).map_err(|e| invalid(e.to_string()))?,
+            );
+        }
+
+        Ok(Self { alternatives })
+    }
+
+    /// Whether `version` satisfies the constraint
+    pub fn matches(&self, version: &Version) -> bool {
+        self.alternatives.iter().any(|req| req.matches(version))
+    }
+
+    /// Whether a cluster running `kube_version` satisfies the constraint
+    pub fn allows(&self, kube_version: &KubeVersion) -> bool {
+        self.matches(&kube_version.semver())
+    }
+}
+
 /// Maintainer information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Maintainer {
@@ -225,9 +372,17 @@ pub struct Dependency {
     /// Version constraint (semver)
     pub version: String,
 
-    /// Repository URL
+    /// Repository URL (empty for git dependencies)
+    #[serde(default)]
     pub repository: String,
 
+    /// Git source, for packs that live in a git repository
+    ///
+    /// Used instead of `repository`. The checked-out pack must still
+    /// satisfy `version`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git: Option<GitSource>,
+
     /// Static enable/disable flag
     ///
     /// When `false`, this dependency is completely ignored during resolution.
@@ -240,6 +395,8 @@ pub struct Dependency {
     ///
     /// A dot-separated path evaluated against values.yaml.
     /// Example: `redis.enabled` checks `values.redis.enabled`.
+    /// Several comma-separated paths may be given; the first one present
+    /// in values decides. When none is present, `tags` decide.
     ///
     /// When combined with `resolve: when-enabled`, the condition is evaluated
     /// at resolution time to skip downloading disabled dependencies.
@@ -254,12 +411,85 @@ pub struct Dependency {
     pub resolve: ResolvePolicy,
 
     /// Tags for conditional inclusion
+    ///
+    /// Toggled by the top-level `tags:` map in values: the dependency is
+    /// enabled if any of its tags is true, and disabled if all the tags
+    /// set there are false. Ignored when `condition` decides.
     #[serde(default)]
     pub tags: Vec<String>,
 
     /// Alias name (overrides dependency name in templates)
     #[serde(default)]
     pub alias: Option<String>,
+
+    /// Values imported from the subchart into the parent
+    ///
+    /// Applied after the subchart's values are merged, so the parent sees
+    /// the subchart's computed values. Also accepted as `import-values`.
+    #[serde(
+        default,
+        alias = "import-values",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub import_values: Vec<ImportValue>,
+}
+
+/// A pack in a git repository
+///
+/// ```yaml
+/// git:
+///   repo: https://git.example.com/platform/packs.git
+///   ref: v1.4.0            # tag, branch or commit
+///   subpath: packs/common  # optional, defaults to the repository root
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GitSource {
+    /// Repository URL, as accepted by `git fetch`
+    pub repo: String,
+
+    /// Tag, branch or commit to check out
+    #[serde(rename = "ref")]
+    pub git_ref: String,
+
+    /// Directory of the pack inside the repository
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subpath: Option<String>,
+}
+
+/// A value imported from a subchart into its parent (Helm's `import-values`)
+///
+/// ```yaml
+/// importValues:
+///   - data                      # the subchart's `exports.data`, at the parent root
+///   - child: service.port       # explicit mapping
+///     parent: ports.backend
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ImportValue {
+    /// Import the subchart's `exports.<key>` map into the parent's root
+    Key(String),
+
+    /// Import the subchart's `child` path to the parent's `parent` path
+    Mapping { child: String, parent: String },
+}
+
+impl ImportValue {
+    /// Dot-path read from the subchart's values
+    pub fn child_path(&self) -> String {
+        match self {
+            Self::Key(key) => format!("exports.{}", key),
+            Self::Mapping { child, .. } => child.clone(),
+        }
+    }
+
+    /// Dot-path written in the parent's values (empty for the root)
+    pub fn parent_path(&self) -> &str {
+        match self {
+            Self::Key(_) => "",
+            Self::Mapping { parent, .. } => parent,
+        }
+    }
 }
 
 impl Dependency {
@@ -274,7 +504,7 @@ impl Dependency {
     /// Returns `false` if:
     /// - `enabled` is `false`
     /// - `resolve` is `Never`
-    /// - `resolve` is `WhenEnabled` and condition evaluates to `false`
+    /// - `resolve` is `WhenEnabled` and values disable it (see [`Self::enabled_by_values`])
     pub fn should_resolve(&self, values: &serde_json::Value) -> bool {
         // Static disable always wins
         if !self.enabled {
@@ -284,35 +514,53 @@ impl Dependency {
         match self.resolve {
             ResolvePolicy::Always => true,
             ResolvePolicy::Never => false,
-            ResolvePolicy::WhenEnabled => {
-                // If no condition, treat as enabled
-                let Some(condition) = &self.condition else {
-                    return true;
-                };
-
-                evaluate_condition(condition, values)
-            }
+            ResolvePolicy::WhenEnabled => self.enabled_by_values(values),
         }
     }
+
+    /// Check whether values enable this dependency, following Helm
+    ///
+    /// `condition` wins when one of its paths is present in values;
+    /// otherwise `tags` decide. With neither, the dependency is enabled.
+    pub fn enabled_by_values(&self, values: &serde_json::Value) -> bool {
+        self.condition_value(values)
+            .or_else(|| self.tags_value(values))
+            .unwrap_or(true)
+    }
+
+    /// Value of the first `condition` path present in values
+    pub fn condition_value(&self, values: &serde_json::Value) -> Option<bool> {
+        self.condition
+            .as_deref()?
+            .split(',')
+            .map(str::trim)
+            .filter(|path| !path.is_empty())
+            .find_map(|path| values_flag(path, values))
+    }
+
+    /// Whether any tag is true, if any of them is set in `values.tags`
+    pub fn tags_value(&self, values: &serde_json::Value) -> Option<bool> {
+        let tags = values.get("tags")?;
+        self.tags
+            .iter()
+            .filter_map(|tag| tags.get(tag).map(truthy))
+            .reduce(|any, enabled| any || enabled)
+    }
 }
 
-/// Evaluate a simple dot-path condition against values
+/// Look up a dot-path flag in values
 ///
 /// Supports paths like `redis.enabled`, `features.cache.memory`.
-/// Returns `true` if the path exists and is truthy.
-fn evaluate_condition(condition: &str, values: &serde_json::Value) -> bool {
-    let path: Vec<&str> = condition.split('.').collect();
-
-    let mut current = values;
-    for part in &path {
-        match current.get(*part) {
-            Some(v) => current = v,
-            None => return false, // Path doesn't exist → falsy
-        }
-    }
+/// Returns `None` if the path doesn't exist.
+fn values_flag(path: &str, values: &serde_json::Value) -> Option<bool> {
+    path.split('.')
+        .try_fold(values, |current, part| current.get(part))
+        .map(truthy)
+}
 
-    // Coerce to boolean
-    match current {
+/// Coerce a value to a boolean
+fn truthy(value: &serde_json::Value) -> bool {
+    match value {
         serde_json::Value::Bool(b) => *b,
         serde_json::Value::Null => false,
         serde_json::Value::String(s) => !s.is_empty() && s != "false" && s != "0",
@@ -360,6 +608,12 @@ pub struct LoadedPack {
 
     /// Schema file path (if present)
     pub schema_path: Option<PathBuf>,
+
+    /// Packaged `.tar.gz` of this pack, when rendering a packaged pack
+    ///
+    /// When set, the engine serves the Files API from this archive instead
+    /// of `root`.
+    pub archive: Option<PathBuf>,
 }
 
 impl LoadedPack {
@@ -415,9 +669,66 @@ impl LoadedPack {
             crds_dir,
             values_path,
             schema_path,
+            archive: None,
         })
     }
 
+    /// Check the pack, collecting every problem found
+    ///
+    /// Runs [`Pack::validate`], then checks the templates: only
+    /// `templates/NOTES.txt` may be named like the notes template (any name
+    /// containing "notes" is rendered as notes), and two templates must not
+    /// render to the same manifest (`app.yaml` and `app.yaml.j2`).
+    pub fn validate(&self) -> std::result::Result<(), Vec<CoreError>> {
+        let mut errors = self.pack.validate().err().unwrap_or_default();
+
+        let templates = match self.template_files() {
+            Ok(templates) => templates,
+            Err(e) => {
+                errors.push(e);
+                Vec::new()
+            }
+        };
+
+        let mut outputs = HashSet::new();
+        for template in &templates {
+            let name = template
+                .strip_prefix(&self.templates_dir)
+                .unwrap_or(template)
+                .to_string_lossy()
+                .replace('\\', "/");
+            let path = format!("templates/{}", name);
+            let file_name = name.rsplit('/').next().unwrap_or(&name);
+            if file_name.starts_with('_') {
+                continue;
+            }
+
+            if name.to_lowercase().contains("notes") && !name.eq_ignore_ascii_case("NOTES.txt") {
+                errors.push(CoreError::InvalidStructure {
+                    path,
+                    message: "names containing 'notes' are reserved for templates/NOTES.txt; \
+                              this template would be rendered as the release notes"
+                        .to_string(),
+                });
+                continue;
+            }
+
+            let output = name.trim_end_matches(".j2").trim_end_matches(".jinja2");
+            if !outputs.insert(output.to_string()) {
+                errors.push(CoreError::InvalidStructure {
+                    path,
+                    message: format!("another template also renders to '{}'", output),
+                });
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
     /// Find schema file, checking multiple standard locations
     fn find_schema_file(root: &Path) -> Option<PathBuf> {
         let candidates = [
@@ -721,6 +1032,54 @@ tags:
         assert_eq!(dep.tags, vec!["database", "backend"]);
     }
 
+    #[test]
+    fn test_dependency_import_values() {
+        let yaml = r#"
+name: postgresql
+version: "^12.0"
+repository: https://charts.bitnami.com
+import-values:
+  - data
+  - child: primary.service.ports
+    parent: database.ports
+"#;
+        let dep: Dependency = serde_yaml::from_str(yaml).unwrap();
+
+        assert_eq!(dep.import_values.len(), 2);
+        assert_eq!(dep.import_values[0].child_path(), "exports.data");
+        assert_eq!(dep.import_values[0].parent_path(), "");
+        assert_eq!(
+            dep.import_values[1],
+            ImportValue::Mapping {
+                child: "primary.service.ports".to_string(),
+                parent: "database.ports".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_dependency_git_source() {
+        let yaml = r#"
+name: common
+version: "^1.0"
+git:
+  repo: https://git.example.com/platform/packs.git
+  ref: v1.4.0
+  subpath: packs/common
+"#;
+        let dep: Dependency = serde_yaml::from_str(yaml).unwrap();
+
+        assert!(dep.repository.is_empty());
+        assert_eq!(
+            dep.git,
+            Some(GitSource {
+                repo: "https://git.example.com/platform/packs.git".to_string(),
+                git_ref: "v1.4.0".to_string(),
+                subpath: Some("packs/common".to_string()),
+            })
+        );
+    }
+
     #[test]
     fn test_resolve_policy_serialization() {
         assert_eq!(
@@ -742,7 +1101,7 @@ tags:
     }
 
     #[test]
-    fn test_evaluate_condition_simple_bool() {
+    fn test_values_flag_simple_bool() {
         let values = json!({
             "redis": {
                 "enabled": true
@@ -752,12 +1111,12 @@ tags:
             }
         });
 
-        assert!(evaluate_condition("redis.enabled", &values));
-        assert!(!evaluate_condition("postgresql.enabled", &values));
+        assert_eq!(values_flag("redis.enabled", &values), Some(true));
+        assert_eq!(values_flag("postgresql.enabled", &values), Some(false));
     }
 
     #[test]
-    fn test_evaluate_condition_nested_path() {
+    fn test_values_flag_nested_path() {
         let values = json!({
             "features": {
                 "cache": {
@@ -768,25 +1127,28 @@ tags:
             }
         });
 
-        assert!(evaluate_condition("features.cache.redis.enabled", &values));
-        assert!(!evaluate_condition(
-            "features.cache.memcached.enabled",
-            &values
-        ));
+        assert_eq!(
+            values_flag("features.cache.redis.enabled", &values),
+            Some(true)
+        );
+        assert_eq!(
+            values_flag("features.cache.memcached.enabled", &values),
+            None
+        );
     }
 
     #[test]
-    fn test_evaluate_condition_missing_path() {
+    fn test_values_flag_missing_path() {
         let values = json!({
             "redis": {}
         });
 
-        assert!(!evaluate_condition("redis.enabled", &values));
-        assert!(!evaluate_condition("nonexistent.path", &values));
+        assert_eq!(values_flag("redis.enabled", &values), None);
+        assert_eq!(values_flag("nonexistent.path", &values), None);
     }
 
     #[test]
-    fn test_evaluate_condition_truthy_values() {
+    fn test_values_flag_truthy_values() {
         let values = json!({
             "string_true": "yes",
             "string_false": "false",
@@ -801,17 +1163,17 @@ tags:
             "null_val": null
         });
 
-        assert!(evaluate_condition("string_true", &values));
-        assert!(!evaluate_condition("string_false", &values));
-        assert!(!evaluate_condition("string_zero", &values));
-        assert!(!evaluate_condition("string_empty", &values));
-        assert!(evaluate_condition("number_one", &values));
-        assert!(!evaluate_condition("number_zero", &values));
-        assert!(!evaluate_condition("array_empty", &values));
-        assert!(evaluate_condition("array_full", &values));
-        assert!(!evaluate_condition("object_empty", &values));
-        assert!(evaluate_condition("object_full", &values));
-        assert!(!evaluate_condition("null_val", &values));
+        assert_eq!(values_flag("string_true", &values), Some(true));
+        assert_eq!(values_flag("string_false", &values), Some(false));
+        assert_eq!(values_flag("string_zero", &values), Some(false));
+        assert_eq!(values_flag("string_empty", &values), Some(false));
+        assert_eq!(values_flag("number_one", &values), Some(true));
+        assert_eq!(values_flag("number_zero", &values), Some(false));
+        assert_eq!(values_flag("array_empty", &values), Some(false));
+        assert_eq!(values_flag("array_full", &values), Some(true));
+        assert_eq!(values_flag("object_empty", &values), Some(false));
+        assert_eq!(values_flag("object_full", &values), Some(true));
+        assert_eq!(values_flag("null_val", &values), Some(false));
     }
 
     #[test]
@@ -820,11 +1182,13 @@ tags:
             name: "redis".to_string(),
             version: "^7.0".to_string(),
             repository: "https://repo.example.com".to_string(),
+            git: None,
             enabled: false,
             condition: None,
             resolve: ResolvePolicy::Always,
             tags: vec![],
             alias: None,
+            import_values: vec![],
         };
 
         // enabled: false always wins, even with resolve: always
@@ -837,11 +1201,13 @@ tags:
             name: "redis".to_string(),
             version: "^7.0".to_string(),
             repository: "https://repo.example.com".to_string(),
+            git: None,
             enabled: true,
             condition: None,
             resolve: ResolvePolicy::Never,
             tags: vec![],
             alias: None,
+            import_values: vec![],
         };
 
         assert!(!dep.should_resolve(&json!({})));
@@ -853,11 +1219,13 @@ tags:
             name: "redis".to_string(),
             version: "^7.0".to_string(),
             repository: "https://repo.example.com".to_string(),
+            git: None,
             enabled: true,
             condition: Some("redis.enabled".to_string()),
             resolve: ResolvePolicy::Always,
             tags: vec![],
             alias: None,
+            import_values: vec![],
         };
 
         // resolve: always ignores condition
@@ -870,11 +1238,13 @@ tags:
             name: "redis".to_string(),
             version: "^7.0".to_string(),
             repository: "https://repo.example.com".to_string(),
+            git: None,
             enabled: true,
             condition: None,
             resolve: ResolvePolicy::WhenEnabled,
             tags: vec![],
             alias: None,
+            import_values: vec![],
         };
 
         // No condition = always resolve
@@ -887,16 +1257,50 @@ tags:
             name: "redis".to_string(),
             version: "^7.0".to_string(),
             repository: "https://repo.example.com".to_string(),
+            git: None,
             enabled: true,
             condition: Some("redis.enabled".to_string()),
             resolve: ResolvePolicy::WhenEnabled,
             tags: vec![],
             alias: None,
+            import_values: vec![],
         };
 
         assert!(dep.should_resolve(&json!({"redis": {"enabled": true}})));
         assert!(!dep.should_resolve(&json!({"redis": {"enabled": false}})));
-        assert!(!dep.should_resolve(&json!({}))); // Missing = false
+        assert!(dep.should_resolve(&json!({}))); // Missing = enabled, as in Helm
+    }
+
+    #[test]
+    fn test_enabled_by_values_tags() {
+        let dep = Dependency {
+            name: "redis".to_string(),
+            version: "^7.0".to_string(),
+            repository: "https://repo.example.com".to_string(),
+            git: None,
+            enabled: true,
+            condition: Some("redis.enabled,global.redis.enabled".to_string()),
+            resolve: ResolvePolicy::WhenEnabled,
+            tags: vec!["cache".to_string(), "backend".to_string()],
+            alias: None,
+            import_values: vec![],
+        };
+
+        // Tags decide when no condition path is present
+        assert!(!dep.enabled_by_values(&json!({"tags": {"cache": false}})));
+        assert!(dep.enabled_by_values(&json!({"tags": {"cache": false, "backend": true}})));
+        assert!(dep.enabled_by_values(&json!({"tags": {"frontend": false}})));
+
+        // The condition wins over tags, trying each path in turn
+        assert!(dep.enabled_by_values(&json!({
+            "tags": {"cache": false},
+            "global": {"redis": {"enabled": true}}
+        })));
+        assert!(!dep.enabled_by_values(&json!({
+            "tags": {"cache": true},
+            "redis": {"enabled": false},
+            "global": {"redis": {"enabled": true}}
+        })));
     }
 
     // ==========================================
@@ -1018,4 +1422,187 @@ crds:
 
         assert!(manifest.is_templated);
     }
+
+    fn pack_from(extra_metadata: &str, dependencies: &str) -> Pack {
+        serde_yaml::from_str(&format!(
+            "apiVersion: sherpack/v1\nmetadata:\n  name: myapp\n  version: 1.0.0\n{}{}",
+            extra_metadata, dependencies
+        ))
+        .unwrap()
+    }
+
+    fn validation_errors(pack: &Pack) -> Vec<String> {
+        pack.validate()
+            .err()
+            .unwrap_or_default()
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_library_pack_is_not_deployable() {
+        let mut pack = pack_from("", "");
+        assert!(pack.ensure_deployable().is_ok());
+
+        pack.kind = PackKind::Library;
+        let err = pack.ensure_deployable().unwrap_err();
+        assert!(matches!(err, CoreError::LibraryPack { ref name } if name == "myapp"));
+    }
+
+    #[test]
+    fn test_validate_accepts_valid_pack() {
+        let pack = pack_from(
+            "  kubeVersion: \">= 1.21.0-0 < 1.31.0 || ^1.32\"\n",
+            "dependencies:\n  - name: redis\n    version: ^7.0\n  - name: redis\n    alias: cache\n    version: \"*\"\n",
+        );
+        assert!(pack.validate().is_ok(), "{:?}", validation_errors(&pack));
+    }
+
+    #[test]
+    fn test_validate_unsupported_api_version() {
+        let mut pack = pack_from("", "");
+        pack.api_version = "sherpack/v2".to_string();
+        assert_eq!(
+            validation_errors(&pack),
+            ["Invalid Pack.yaml: Unsupported API version: sherpack/v2. Expected: sherpack/v1"]
+        );
+    }
+
+    #[test]
+    fn test_validate_pack_name() {
+        let mut pack = pack_from("", "");
+        pack.metadata.name = " ".to_string();
+        assert_eq!(
+            validation_errors(&pack),
+            ["Missing required field: metadata.name"]
+        );
+
+        pack.metadata.name = "my app/v2".to_string();
+        let errors = validation_errors(&pack);
+        assert_eq!(errors.len(), 1);
+        assert!(
+            errors[0].contains("Invalid pack name 'my app/v2'"),
+            "{errors:?}"
+        );
+    }
+
+    #[test]
+    fn test_validate_kube_version_constraint() {
+        let pack = pack_from("  kubeVersion: \">= one.two\"\n", "");
+        let errors = validation_errors(&pack);
+        assert_eq!(errors.len(), 1);
+        assert!(
+            errors[0].contains("Invalid kubeVersion constraint '>= one.two'"),
+            "{errors:?}"
+        );
+
+        let pack = pack_from("  kubeVersion: \">=\"\n", "");
+        assert_eq!(validation_errors(&pack).len(), 1);
+    }
+
+    #[test]
+    fn test_validate_duplicate_dependencies() {
+        // The same name twice, and an alias clashing with another name
+        let pack = pack_from(
+            "",
+            "dependencies:\n  - name: redis\n    version: ^7.0\n  - name: redis\n    version: ^6.0\n  - name: memcached\n    alias: redis\n    version: ^1.0\n",
+        );
+        assert_eq!(
+            validation_errors(&pack),
+            [
+                "Invalid Pack.yaml: Duplicate dependency 'redis': give each occurrence its own alias",
+                "Invalid Pack.yaml: Duplicate dependency 'redis': give each occurrence its own alias",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_dependency_fields() {
+        let pack = pack_from(
+            "",
+            "dependencies:\n  - name: \"\"\n    version: ^1.0\n  - name: redis\n    version: latest\n",
+        );
+        let errors = validation_errors(&pack);
+        assert_eq!(errors.len(), 2, "{errors:?}");
+        assert_eq!(errors[0], "Missing required field: dependencies[0].name");
+        assert!(
+            errors[1].contains("Dependency 'redis': invalid version constraint 'latest'"),
+            "{errors:?}"
+        );
+    }
+
+    #[test]
+    fn test_kube_version_constraint_matches() {
+        let matches = |constraint: &str, version: &str| {
+            KubeVersionConstraint::parse(constraint)
+                .unwrap()
+                .matches(&Version::parse(version).unwrap())
+        };
+
+        assert!(matches(">=1.25.0-0", "1.28.3"));
+        assert!(!matches(">=1.25.0-0", "1.24.9"));
+        assert!(matches(">= 1.21, < 1.30", "1.29.0"));
+        assert!(matches(">= 1.21 < 1.30", "1.29.0"));
+        assert!(!matches(">= 1.21 < 1.30", "1.30.0"));
+        assert!(matches("~1.27 || ^1.30", "1.31.2"));
+        assert!(!matches("~1.27 || ^1.30", "1.28.0"));
+    }
+
+    #[test]
+    fn test_kube_version_constraint_allows_cluster_version() {
+        let constraint = KubeVersionConstraint::parse(">= 1.27, < 1.31").unwrap();
+
+        // Distribution suffixes are not prereleases
+        let gke = KubeVersion {
+            version: "v1.30.2-gke.1".to_string(),
+            major: "1".to_string(),
+            minor: "30+".to_string(),
+        };
+        assert_eq!(gke.semver(), Version::new(1, 30, 2));
+        assert!(constraint.allows(&gke));
+
+        assert!(constraint.allows(&KubeVersion::new("1.27")));
+        assert!(!constraint.allows(&KubeVersion::new("v1.24.9")));
+        assert!(!constraint.allows(&KubeVersion::new("1.31.0")));
+    }
+
+    #[test]
+    fn test_loaded_pack_validate_templates() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |path: &str| {
+            let path = dir.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "kind: ConfigMap\n").unwrap();
+        };
+        std::fs::write(
+            dir.path().join("Pack.yaml"),
+            "apiVersion: sherpack/v1\nmetadata:\n  name: myapp\n  version: 1.0.0\n",
+        )
+        .unwrap();
+        write("templates/NOTES.txt");
+        write("templates/_notes-helpers.tpl");
+        write("templates/app.yaml");
+        write("templates/release-notes.yaml");
+        write("templates/service.yaml");
+        write("templates/service.yaml.j2");
+
+        let pack = LoadedPack::load(dir.path()).unwrap();
+        let errors: Vec<String> = pack
+            .validate()
+            .unwrap_err()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        assert_eq!(errors.len(), 2, "{errors:?}");
+        assert!(
+            errors[0].starts_with("Invalid pack structure at 'templates/release-notes.yaml'"),
+            "{errors:?}"
+        );
+        assert_eq!(
+            errors[1],
+            "Invalid pack structure at 'templates/service.yaml.j2': another template also renders to 'service.yaml'"
+        );
+    }
 }
@@ -0,0 +1,234 @@
+//! Provenance of values: which defaults, file or flag set each value
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+
+use crate::error::Result;
+use crate::values::{Values, escape_key};
+
+/// Tracks where each value came from
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ValuesProvenance {
+    /// Map from dotted path to source, with `.`, `[`, `]` and `\` in keys
+    /// escaped by a backslash as in [`Values::set`]
+    #[serde(default)]
+    pub sources: HashMap<String, ValueSource>,
+}
+
+impl ValuesProvenance {
+    /// Record the source of a value
+    pub fn record(&mut self, path: &str, source: ValueSource) {
+        self.sources.insert(path.to_string(), source);
+    }
+
+    /// Whether no source is recorded
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+
+    /// Get the source of a value
+    pub fn get_source(&self, path: &str) -> Option<&ValueSource> {
+        self.sources.get(path)
+    }
+
+    /// Record every leaf of `values` as coming from `source`
+    ///
+    /// Recording in merge order leaves the winning source for each path.
+    pub fn record_values(&mut self, values: &Values, source: &ValueSource) {
+        for (path, _) in leaf_values(values) {
+            self.record(&path, source.clone());
+        }
+    }
+
+    /// Every leaf of `values` with its recorded source, sorted by path
+    ///
+    /// Leaves without a recorded source come from pack or schema defaults.
+    pub fn origins(&self, values: &Values) -> Vec<ValueOrigin> {
+        let mut origins: Vec<ValueOrigin> = leaf_values(values)
+            .into_iter()
+            .map(|(path, value)| ValueOrigin {
+                source: self.get_source(&path).cloned(),
+                value: value.clone(),
+                path,
+            })
+            .collect();
+        origins.sort_by(|a, b| a.path.cmp(&b.path));
+        origins
+    }
+
+    /// Forget `path` and every path below it (everything for `""`)
+    pub(crate) fn forget(&mut self, path: &str) {
+        if path.is_empty() {
+            self.sources.clear();
+            return;
+        }
+        self.sources.retain(|recorded, _| {
+            recorded
+                .strip_prefix(path)
+                .is_none_or(|rest| !rest.is_empty() && !rest.starts_with('.'))
+        });
+    }
+
+    /// Replace what is recorded under `path` by the leaves of `value`
+    ///
+    /// `source` gives the source of each leaf from its path; leaves without
+    /// one are left unrecorded.
+    pub(crate) fn record_subtree(
+        &mut self,
+        path: &str,
+        value: &JsonValue,
+        source: &dyn Fn(&str) -> Option<ValueSource>,
+    ) {
+        self.forget(path);
+        for (leaf, _) in leaves_under(path.to_string(), value) {
+            if let Some(leaf_source) = source(&leaf) {
+                self.record(&leaf, leaf_source);
+            }
+        }
+    }
+
+    /// Add the sources from `other`, replacing existing paths
+    pub fn extend(&mut self, other: &ValuesProvenance) {
+        self.sources
+            .extend(other.sources.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
+
+    /// The subset of `values` supplied by the user rather than defaults
+    pub fn user_supplied(&self, values: &Values) -> Result<Values> {
+        let mut paths: Vec<&String> = self
+            .sources
+            .iter()
+            .filter(|(_, source)| !source.is_default())
+            .map(|(path, _)| path)
+            .collect();
+        paths.sort();
+
+        let mut result = Values::new();
+        for path in paths {
+            if let Some(value) = values.get(path) {
+                result.set(path, value.clone())?;
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// A value path, its value and where it came from
+#[derive(Debug, Clone, Serialize)]
+pub struct ValueOrigin {
+    /// Dotted path of the value, keys escaped as in [`Values::set`]
+    pub path: String,
+
+    /// The value itself
+    pub value: serde_json::Value,
+
+    /// Recorded source (`None` for pack or schema defaults)
+    pub source: Option<ValueSource>,
+}
+
+/// Dotted paths of the leaves of `values`, keys escaped
+///
+/// Maps are descended into; scalars, lists and empty maps are leaves.
+fn leaf_values(values: &Values) -> Vec<(String, &JsonValue)> {
+    leaves_under(String::new(), values.inner())
+}
+
+/// Leaves of `value`, with paths starting at `prefix`
+fn leaves_under(prefix: String, value: &JsonValue) -> Vec<(String, &JsonValue)> {
+    fn walk<'a>(prefix: String, value: &'a JsonValue, leaves: &mut Vec<(String, &'a JsonValue)>) {
+        match value {
+            JsonValue::Object(map) if !map.is_empty() => {
+                for (key, child) in map {
+                    let path = if prefix.is_empty() {
+                        escape_key(key)
+                    } else {
+                        format!("{}.{}", prefix, escape_key(key))
+                    };
+                    walk(path, child, leaves);
+                }
+            }
+            _ if !prefix.is_empty() => leaves.push((prefix, value)),
+            _ => {}
+        }
+    }
+
+    let mut leaves = Vec::new();
+    walk(prefix, value, &mut leaves);
+    leaves
+}
+
+/// Source of a configuration value
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum ValueSource {
+    /// Default from schema
+    SchemaDefault,
+
+    /// From pack's values.yaml
+    PackDefault,
+
+    /// From user's values file
+    ValuesFile { path: String, line: Option<u32> },
+
+    /// From --set command line flag
+    CommandLine {
+        flag: String,
+        timestamp: DateTime<Utc>,
+        user: Option<String>,
+    },
+
+    /// From environment variable
+    Environment { var: String },
+
+    /// Merged from multiple sources
+    Merged { sources: Vec<String> },
+}
+
+impl ValueSource {
+    /// Whether this is a pack or schema default rather than user input
+    pub fn is_default(&self) -> bool {
+        matches!(self, Self::SchemaDefault | Self::PackDefault)
+    }
+}
+
+impl std::fmt::Display for ValueSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SchemaDefault => write!(f, "schema default"),
+            Self::PackDefault => write!(f, "pack default"),
+            Self::ValuesFile { path, line } => {
+                if let Some(l) = line {
+                    write!(f, "{}:{}", path, l)
+                } else {
+                    write!(f, "{}", path)
+                }
+            }
+            Self::CommandLine {
+                flag,
+                timestamp,
+                user,
+            } => {
+                if let Some(u) = user {
+                    write!(
+                        f,
+                        "--set {} by {} at {}",
+                        flag,
+                        u,
+                        timestamp.format("%Y-%m-%d %H:%M")
+                    )
+                } else {
+                    write!(
+                        f,
+                        "--set {} at {}",
+                        flag,
+                        timestamp.format("%Y-%m-%d %H:%M")
+                    )
+                }
+            }
+            Self::Environment { var } => write!(f, "env ${}", var),
+            Self::Merged { sources } => write!(f, "merged from: {}", sources.join(", ")),
+        }
+    }
+}
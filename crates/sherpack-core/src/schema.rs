@@ -6,7 +6,7 @@
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::path::Path;
 
 use crate::error::{CoreError, Result, ValidationErrorInfo};
@@ -34,55 +34,55 @@ pub struct SherpProperty {
     pub prop_type: SherpType,
 
     /// Description for documentation
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
 
     /// Default value
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub default: Option<JsonValue>,
 
     /// Whether this property is required
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
     pub required: bool,
 
     /// Allowed values (enum constraint)
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub enum_values: Option<Vec<JsonValue>>,
 
     /// Pattern for string validation (regex)
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub pattern: Option<String>,
 
     /// Minimum value for numbers
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub min: Option<f64>,
 
     /// Maximum value for numbers
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub max: Option<f64>,
 
     /// Minimum length for strings
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub min_length: Option<usize>,
 
     /// Maximum length for strings
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub max_length: Option<usize>,
 
     /// Nested properties for objects
-    #[serde(default)]
-    pub properties: Option<HashMap<String, SherpProperty>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub properties: Option<BTreeMap<String, SherpProperty>>,
 
     /// Item schema for arrays
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub items: Option<Box<SherpProperty>>,
 
     /// Minimum array items
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub min_items: Option<usize>,
 
     /// Maximum array items
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub max_items: Option<usize>,
 }
 
@@ -95,15 +95,15 @@ pub struct SherpSchema {
     pub schema_version: String,
 
     /// Optional schema title
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub title: Option<String>,
 
     /// Optional schema description
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
 
     /// Property definitions
-    pub properties: HashMap<String, SherpProperty>,
+    pub properties: BTreeMap<String, SherpProperty>,
 }
 
 fn default_schema_version() -> String {
@@ -181,8 +181,57 @@ impl Schema {
 
     /// Get defaults as Values
     pub fn defaults_as_values(&self) -> Values {
-        Values(self.extract_defaults())
+        Values::from(self.extract_defaults())
     }
+
+    /// Whether a value path (e.g. `["image", "tag"]`) is declared
+    ///
+    /// Nodes that don't list their keys accept any key below them: objects
+    /// without `properties`, `additionalProperties`/`patternProperties`, and
+    /// `$ref`s (which aren't resolved). Composed schemas (`allOf`, `anyOf`,
+    /// `oneOf`) declare a path if any branch does.
+    pub fn declares<S: AsRef<str>>(&self, path: &[S]) -> bool {
+        json_schema_declares(&self.to_json_schema(), path)
+    }
+}
+
+fn json_schema_declares<S: AsRef<str>>(schema: &JsonValue, path: &[S]) -> bool {
+    let Some((key, rest)) = path.split_first() else {
+        return true;
+    };
+    let key = key.as_ref();
+
+    let branches: Vec<&JsonValue> = ["allOf", "anyOf", "oneOf"]
+        .iter()
+        .filter_map(|keyword| schema.get(keyword).and_then(|b| b.as_array()))
+        .flatten()
+        .collect();
+    if branches.iter().any(|b| json_schema_declares(b, path)) {
+        return true;
+    }
+
+    if schema.get("$ref").is_some() || schema.get("patternProperties").is_some() {
+        return true;
+    }
+
+    if let Some(property) = schema.get("properties").and_then(|p| p.get(key)) {
+        return json_schema_declares(property, rest);
+    }
+
+    if let Some(items) = schema.get("items")
+        && key.parse::<usize>().is_ok()
+    {
+        return json_schema_declares(items, rest);
+    }
+
+    match schema.get("additionalProperties") {
+        Some(JsonValue::Bool(allowed)) => return *allowed,
+        Some(additional @ JsonValue::Object(_)) => return json_schema_declares(additional, rest),
+        _ => {}
+    }
+
+    // Only an open node accepts keys it doesn't list
+    schema.get("properties").is_none() && branches.is_empty()
 }
 
 /// Detect schema format from file path and content
@@ -259,7 +308,7 @@ fn convert_sherp_to_json_schema(sherp: &SherpSchema) -> JsonValue {
     JsonValue::Object(schema)
 }
 
-fn convert_sherp_properties(props: &HashMap<String, SherpProperty>) -> (JsonValue, Vec<String>) {
+fn convert_sherp_properties(props: &BTreeMap<String, SherpProperty>) -> (JsonValue, Vec<String>) {
     let mut json_props = serde_json::Map::new();
     let mut required = Vec::new();
 
@@ -389,7 +438,7 @@ fn extract_sherp_defaults(sherp: &SherpSchema) -> JsonValue {
     extract_sherp_property_defaults(&sherp.properties)
 }
 
-fn extract_sherp_property_defaults(props: &HashMap<String, SherpProperty>) -> JsonValue {
+fn extract_sherp_property_defaults(props: &BTreeMap<String, SherpProperty>) -> JsonValue {
     let mut defaults = serde_json::Map::new();
 
     for (name, prop) in props {
@@ -512,7 +561,7 @@ impl SchemaValidator {
 
     /// Get defaults as Values
     pub fn defaults_as_values(&self) -> Values {
-        Values(self.defaults.clone())
+        Values::from(self.defaults.clone())
     }
 
     /// Get the original schema
@@ -533,6 +582,45 @@ fn format_validation_error(error: &jsonschema::ValidationError) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_schema_declares() {
+        let schema = Schema::from_sherp_schema(
+            r#"
+properties:
+  image:
+    type: object
+    properties:
+      tag:
+        type: string
+  podAnnotations:
+    type: object
+  ports:
+    type: array
+    items:
+      type: object
+      properties:
+        port:
+          type: integer
+"#,
+        )
+        .unwrap();
+
+        assert!(schema.declares(&["image", "tag"]));
+        assert!(schema.declares(&["podAnnotations", "anything"]));
+        assert!(schema.declares(&["ports", "0", "port"]));
+        assert!(!schema.declares(&["image", "digest"]));
+        assert!(!schema.declares(&["replicas"]));
+
+        let schema = Schema::JsonSchema(serde_json::json!({
+            "type": "object",
+            "properties": {"env": {"type": "object", "additionalProperties": {"type": "string"}}},
+            "anyOf": [{"properties": {"legacy": {"type": "boolean"}}}]
+        }));
+        assert!(schema.declares(&["env", "FOO"]));
+        assert!(schema.declares(&["legacy"]));
+        assert!(!schema.declares(&["other"]));
+    }
+
     #[test]
     fn test_sherp_schema_parse() {
         let yaml = r#"
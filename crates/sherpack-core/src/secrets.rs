@@ -10,6 +10,8 @@
 //! {# In templates #}
 //! {{ generate_secret("db-password", 16) }}
 //! {{ generate_secret("api-key", 32, "urlsafe") }}
+//! {{ generate_secret("admin-password", 20, "complex", exclude_ambiguous=true) }}
+//! {{ generate_secret("pin", 6, chars="2345679") }}
 //! ```
 //!
 //! # How it works
@@ -17,19 +19,53 @@
 //! 1. First `sherpack install`: generates random secrets, stores in Kubernetes Secret
 //! 2. Subsequent operations: reads existing values from state
 //! 3. Result: deterministic output, GitOps compatible
+//!
+//! # Seeded generation
+//!
+//! With a seed ([`SecretGenerator::with_seed`]), values are derived with
+//! HMAC-SHA256 from the seed, the secret name, its charset and its length
+//! instead of drawn at random, so every render reproduces the same secrets.
+//!
+//! **Seeded secrets are NOT secret.** Anyone who knows the seed can recompute
+//! every value. Use them for reproducible dev and CI environments only,
+//! never in production.
+//!
+//! # Rotation
+//!
+//! Rotating a secret generates a new current value and keeps the old one in
+//! a `previous` slot, so templates can render both while consumers switch
+//! over:
+//!
+//! ```jinja2
+//! password: {{ generate_secret("db-password", 16) }}
+//! previous-password: {{ previous_secret("db-password") }}
+//! ```
 
 use chrono::{DateTime, Utc};
 use rand::rngs::StdRng;
 use rand::{RngExt, SeedableRng};
+use ring::hmac;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use crate::error::{CoreError, Result};
+
 // =============================================================================
 // CHARSET
 // =============================================================================
 
+/// Characters easily confused with one another when read or typed
+pub const AMBIGUOUS_CHARS: &str = "0O1lI|";
+
+const LOWER: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+const UPPER: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const DIGITS: &[u8] = b"0123456789";
+/// No quotes, backslash or slash, so values embed safely in YAML, JSON and
+/// shell strings
+const SYMBOLS: &[u8] = b"!#$%&()*+,-.:;<=>?@[]^_{}~";
+
 /// Character sets for secret generation
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum SecretCharset {
     /// a-zA-Z0-9 (default)
@@ -45,11 +81,22 @@ pub enum SecretCharset {
     Base64,
     /// a-zA-Z0-9-_ (URL safe)
     UrlSafe,
+    /// a-zA-Z0-9 and symbols, with at least one character of each class
+    Complex,
+    /// Caller-provided printable ASCII characters
+    Custom { chars: String },
 }
 
 impl SecretCharset {
+    /// Create a custom charset
+    pub fn custom(chars: impl Into<String>) -> Self {
+        Self::Custom {
+            chars: chars.into(),
+        }
+    }
+
     /// Get the character set as bytes
-    pub const fn chars(&self) -> &'static [u8] {
+    pub fn chars(&self) -> &[u8] {
         match self {
             Self::Alphanumeric => b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789",
             Self::Alpha => b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ",
@@ -57,10 +104,36 @@ impl SecretCharset {
             Self::Hex => b"0123456789abcdef",
             Self::Base64 => b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/",
             Self::UrlSafe => b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_",
+            Self::Complex => {
+                b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789!#$%&()*+,-.:;<=>?@[]^_{}~"
+            }
+            Self::Custom { chars } => chars.as_bytes(),
         }
     }
 
-    /// Parse charset from string
+    /// Character classes that must each appear at least once
+    pub fn required_classes(&self) -> &'static [&'static [u8]] {
+        match self {
+            Self::Complex => &[LOWER, UPPER, DIGITS, SYMBOLS],
+            _ => &[],
+        }
+    }
+
+    /// Canonical name, as accepted by [`SecretCharset::parse`]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Alphanumeric => "alphanumeric",
+            Self::Alpha => "alpha",
+            Self::Numeric => "numeric",
+            Self::Hex => "hex",
+            Self::Base64 => "base64",
+            Self::UrlSafe => "urlsafe",
+            Self::Complex => "complex",
+            Self::Custom { .. } => "custom",
+        }
+    }
+
+    /// Parse a preset charset from string (custom charsets have no name)
     pub fn parse(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
             "alphanumeric" | "alnum" => Some(Self::Alphanumeric),
@@ -69,11 +142,123 @@ impl SecretCharset {
             "hex" => Some(Self::Hex),
             "base64" => Some(Self::Base64),
             "urlsafe" | "url" => Some(Self::UrlSafe),
+            "complex" => Some(Self::Complex),
             _ => None,
         }
     }
 }
 
+// =============================================================================
+// POLICY
+// =============================================================================
+
+/// What a generated secret may contain
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SecretPolicy {
+    /// Characters to draw from
+    pub charset: SecretCharset,
+
+    /// Leave out characters listed in [`AMBIGUOUS_CHARS`]
+    pub exclude_ambiguous: bool,
+}
+
+impl SecretPolicy {
+    /// Create a policy for a charset
+    pub fn new(charset: SecretCharset) -> Self {
+        Self {
+            charset,
+            exclude_ambiguous: false,
+        }
+    }
+
+    /// Leave out ambiguous characters such as `O` and `0`
+    pub fn without_ambiguous(mut self) -> Self {
+        self.exclude_ambiguous = true;
+        self
+    }
+
+    /// The characters a value is drawn from, without duplicates
+    pub fn alphabet(&self) -> Vec<u8> {
+        self.filter(self.charset.chars())
+    }
+
+    /// Character classes that must each appear at least once
+    fn required_classes(&self) -> Vec<Vec<u8>> {
+        self.charset
+            .required_classes()
+            .iter()
+            .map(|class| self.filter(class))
+            .collect()
+    }
+
+    fn filter(&self, chars: &[u8]) -> Vec<u8> {
+        let mut kept = Vec::with_capacity(chars.len());
+        for &c in chars {
+            let ambiguous = self.exclude_ambiguous && AMBIGUOUS_CHARS.as_bytes().contains(&c);
+            if !ambiguous && !kept.contains(&c) {
+                kept.push(c);
+            }
+        }
+        kept
+    }
+
+    /// Check that a value of `length` characters can satisfy this policy
+    pub fn validate(&self, length: usize) -> Result<()> {
+        let invalid = |message: String| Err(CoreError::InvalidSecretPolicy { message });
+
+        if let SecretCharset::Custom { chars } = &self.charset {
+            if chars.is_empty() {
+                return invalid("custom charset is empty".to_string());
+            }
+            if let Some(c) = chars.chars().find(|c| !c.is_ascii_graphic()) {
+                return invalid(format!(
+                    "custom charset may only contain printable ASCII characters, found {:?}",
+                    c
+                ));
+            }
+        }
+
+        if self.alphabet().is_empty() {
+            return invalid(format!(
+                "{} charset has no characters left once ambiguous ones ({}) are excluded",
+                self.charset.name(),
+                AMBIGUOUS_CHARS
+            ));
+        }
+
+        let required = self.charset.required_classes().len();
+        if length < required {
+            return invalid(format!(
+                "length {} is too short for the {} charset, which needs at least {} characters \
+                 (one per character class)",
+                length,
+                self.charset.name(),
+                required
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Stable description, bound into seeded derivations
+    fn describe(&self) -> String {
+        let mut description = match &self.charset {
+            SecretCharset::Custom { chars } => format!("custom:{}", chars),
+            charset => charset.name().to_string(),
+        };
+        if self.exclude_ambiguous {
+            description.push_str("+exclude-ambiguous");
+        }
+        description
+    }
+}
+
+impl From<SecretCharset> for SecretPolicy {
+    fn from(charset: SecretCharset) -> Self {
+        Self::new(charset)
+    }
+}
+
 // =============================================================================
 // SECRET ENTRY
 // =============================================================================
@@ -95,8 +280,16 @@ pub struct SecretEntry {
     #[serde(default)]
     pub charset: SecretCharset,
 
+    /// Whether ambiguous characters were left out
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub exclude_ambiguous: bool,
+
     /// The length of the secret
     pub length: usize,
+
+    /// The value this secret had before its last rotation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous: Option<PreviousSecret>,
 }
 
 impl SecretEntry {
@@ -107,7 +300,17 @@ impl SecretEntry {
             created_at: Utc::now(),
             rotated_at: None,
             charset,
+            exclude_ambiguous: false,
             length,
+            previous: None,
+        }
+    }
+
+    /// The policy new values for this secret follow
+    pub fn policy(&self) -> SecretPolicy {
+        SecretPolicy {
+            charset: self.charset.clone(),
+            exclude_ambiguous: self.exclude_ambiguous,
         }
     }
 
@@ -116,10 +319,48 @@ impl SecretEntry {
         &self.value
     }
 
+    /// Get the value from before the last rotation (if any)
+    pub fn previous_value(&self) -> Option<&str> {
+        self.previous.as_ref().map(PreviousSecret::value)
+    }
+
     /// Rotate the secret with a new value
+    ///
+    /// The current value moves to the `previous` slot, replacing any value
+    /// kept from an earlier rotation.
     pub fn rotate(&mut self, new_value: String) {
-        self.value = new_value;
-        self.rotated_at = Some(Utc::now());
+        let now = Utc::now();
+        let old_value = std::mem::replace(&mut self.value, new_value);
+        self.previous = Some(PreviousSecret {
+            value: old_value,
+            rotated_at: now,
+        });
+        self.rotated_at = Some(now);
+    }
+
+    /// Forget the previous value once nothing depends on it anymore
+    pub fn clear_previous(&mut self) -> bool {
+        self.previous.take().is_some()
+    }
+}
+
+/// A secret value superseded by a rotation
+///
+/// Kept alongside the current value for a grace period, so both can be
+/// rendered while consumers switch to the new one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreviousSecret {
+    /// The superseded value
+    value: String,
+
+    /// When the value was rotated out
+    pub rotated_at: DateTime<Utc>,
+}
+
+impl PreviousSecret {
+    /// Get the superseded value
+    pub fn value(&self) -> &str {
+        &self.value
     }
 }
 
@@ -204,6 +445,11 @@ impl SecretState {
         self.secrets.is_empty()
     }
 
+    /// Get the value a secret had before its last rotation
+    pub fn get_previous_value(&self, name: &str) -> Option<&str> {
+        self.secrets.get(name).and_then(SecretEntry::previous_value)
+    }
+
     /// Rotate a secret
     pub fn rotate(&mut self, name: &str, new_value: String) -> bool {
         if let Some(entry) = self.secrets.get_mut(name) {
@@ -214,6 +460,33 @@ impl SecretState {
             false
         }
     }
+
+    /// Drop the previous value of every secret rotated longer than `grace` ago
+    ///
+    /// Returns the names of the secrets whose previous value was dropped.
+    pub fn expire_previous(&mut self, grace: chrono::Duration) -> Vec<String> {
+        let cutoff = Utc::now() - grace;
+        let mut expired: Vec<String> = self
+            .secrets
+            .iter_mut()
+            .filter(|(_, entry)| {
+                entry
+                    .previous
+                    .as_ref()
+                    .is_some_and(|previous| previous.rotated_at <= cutoff)
+            })
+            .map(|(name, entry)| {
+                entry.clear_previous();
+                name.clone()
+            })
+            .collect();
+
+        if !expired.is_empty() {
+            self.dirty = true;
+        }
+        expired.sort();
+        expired
+    }
 }
 
 // Implement PartialEq manually to ignore dirty flag
@@ -225,8 +498,11 @@ impl PartialEq<SecretState> for SecretState {
 
 impl PartialEq for SecretEntry {
     fn eq(&self, other: &Self) -> bool {
-        // Compare only value, charset, and length (not timestamps)
-        self.value == other.value && self.charset == other.charset && self.length == other.length
+        // Compare only value, policy, and length (not timestamps)
+        self.value == other.value
+            && self.charset == other.charset
+            && self.exclude_ambiguous == other.exclude_ambiguous
+            && self.length == other.length
     }
 }
 
@@ -238,10 +514,20 @@ impl PartialEq for SecretEntry {
 ///
 /// This is the main interface for generating secrets. It maintains state
 /// to ensure idempotent generation.
-#[derive(Debug)]
 pub struct SecretGenerator {
     state: SecretState,
     rng: StdRng,
+    /// HMAC key for deterministic derivation (random generation if unset)
+    seed: Option<hmac::Key>,
+}
+
+impl std::fmt::Debug for SecretGenerator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SecretGenerator")
+            .field("state", &self.state)
+            .field("seeded", &self.is_seeded())
+            .finish_non_exhaustive()
+    }
 }
 
 impl SecretGenerator {
@@ -250,6 +536,7 @@ impl SecretGenerator {
         Self {
             state: SecretState::new(),
             rng: StdRng::from_rng(&mut rand::rng()),
+            seed: None,
         }
     }
 
@@ -258,12 +545,28 @@ impl SecretGenerator {
         Self {
             state,
             rng: StdRng::from_rng(&mut rand::rng()),
+            seed: None,
         }
     }
 
+    /// Derive new secrets from `seed` instead of generating them randomly
+    ///
+    /// The same seed always yields the same value for a given name, charset
+    /// and length. For non-production use only: the seed reveals every secret.
+    pub fn with_seed(mut self, seed: impl AsRef<[u8]>) -> Self {
+        self.seed = Some(hmac::Key::new(hmac::HMAC_SHA256, seed.as_ref()));
+        self
+    }
+
+    /// Whether new secrets are derived from a seed
+    pub fn is_seeded(&self) -> bool {
+        self.seed.is_some()
+    }
+
     /// Get or generate a secret with default charset
     pub fn get_or_generate(&mut self, name: &str, length: usize) -> String {
-        self.get_or_generate_with_charset(name, length, SecretCharset::default())
+        // The default policy accepts any length
+        self.get_or_insert(name, length, &SecretPolicy::default())
     }
 
     /// Get or generate a secret with specific charset
@@ -272,29 +575,57 @@ impl SecretGenerator {
         name: &str,
         length: usize,
         charset: SecretCharset,
-    ) -> String {
+    ) -> Result<String> {
+        self.get_or_generate_with_policy(name, length, &SecretPolicy::new(charset))
+    }
+
+    /// Get or generate a secret following a policy
+    ///
+    /// Fails if no value of `length` characters can satisfy the policy.
+    pub fn get_or_generate_with_policy(
+        &mut self,
+        name: &str,
+        length: usize,
+        policy: &SecretPolicy,
+    ) -> Result<String> {
+        policy.validate(length)?;
+        Ok(self.get_or_insert(name, length, policy))
+    }
+
+    /// Return the existing secret, or generate it with a validated policy (internal)
+    fn get_or_insert(&mut self, name: &str, length: usize, policy: &SecretPolicy) -> String {
         // Return existing secret if present
         if let Some(entry) = self.state.get(name) {
             return entry.value().to_string();
         }
 
         // Generate new secret
-        let value = self.generate_random(length, charset);
-        let entry = SecretEntry::new(value.clone(), charset, length);
+        let value = self.generate(name, length, policy, None);
+        let mut entry = SecretEntry::new(value.clone(), policy.charset.clone(), length);
+        entry.exclude_ambiguous = policy.exclude_ambiguous;
         self.state.insert(name.to_string(), entry);
 
         value
     }
 
-    /// Generate a random string (internal)
-    fn generate_random(&mut self, length: usize, charset: SecretCharset) -> String {
-        let chars = charset.chars();
-        (0..length)
-            .map(|_| {
-                let idx = self.rng.random_range(0..chars.len());
-                chars[idx] as char
-            })
-            .collect()
+    /// Generate a value, derived from the seed if one is set (internal)
+    ///
+    /// `replacing` is the value being rotated out, so seeded rotations still
+    /// produce a new value.
+    fn generate(
+        &mut self,
+        name: &str,
+        length: usize,
+        policy: &SecretPolicy,
+        replacing: Option<&str>,
+    ) -> String {
+        match &self.seed {
+            Some(key) => {
+                let mut stream = SeededStream::new(key, name, length, policy, replacing);
+                compose(&mut stream, length, policy)
+            }
+            None => compose(&mut self.rng, length, policy),
+        }
     }
 
     /// Get the current state
@@ -313,11 +644,19 @@ impl SecretGenerator {
     }
 
     /// Rotate a secret with a new random value
-    pub fn rotate(&mut self, name: &str) -> Option<String> {
-        let entry = self.state.get(name)?;
-        let new_value = self.generate_random(entry.length, entry.charset);
+    ///
+    /// The old value stays available through [`SecretEntry::previous_value`].
+    /// Returns `None` if no secret with that name was ever generated.
+    pub fn rotate(&mut self, name: &str) -> Result<Option<String>> {
+        let Some(entry) = self.state.get(name).cloned() else {
+            return Ok(None);
+        };
+        let policy = entry.policy();
+        policy.validate(entry.length)?;
+
+        let new_value = self.generate(name, entry.length, &policy, Some(entry.value()));
         self.state.rotate(name, new_value.clone());
-        Some(new_value)
+        Ok(Some(new_value))
     }
 }
 
@@ -327,6 +666,121 @@ impl Default for SecretGenerator {
     }
 }
 
+/// Source of uniformly distributed indices
+trait Draw {
+    /// An index in `0..n`
+    fn index(&mut self, n: usize) -> usize;
+}
+
+impl Draw for StdRng {
+    fn index(&mut self, n: usize) -> usize {
+        self.random_range(0..n)
+    }
+}
+
+/// Build a value from a validated policy
+///
+/// One character of each required class is drawn first, the rest from the
+/// whole alphabet, then everything is shuffled so the guaranteed characters
+/// are not always up front.
+fn compose(draw: &mut impl Draw, length: usize, policy: &SecretPolicy) -> String {
+    let alphabet = policy.alphabet();
+    let required = policy.required_classes();
+
+    let mut value: Vec<u8> = required
+        .iter()
+        .map(|class| class[draw.index(class.len())])
+        .collect();
+    while value.len() < length {
+        value.push(alphabet[draw.index(alphabet.len())]);
+    }
+
+    if !required.is_empty() {
+        for i in (1..value.len()).rev() {
+            value.swap(i, draw.index(i + 1));
+        }
+    }
+
+    value.into_iter().map(char::from).collect()
+}
+
+/// Deterministic index stream derived from a seed (HMAC-SHA256 in counter mode)
+///
+/// Each block is `HMAC(seed, info || counter)`, where `info` binds the
+/// secret name, policy, length and the value being rotated out. Indices are
+/// taken from the blocks by rejection sampling, so every index is equally
+/// likely.
+struct SeededStream<'a> {
+    key: &'a hmac::Key,
+    info: Vec<u8>,
+    counter: u32,
+    block: Vec<u8>,
+    pos: usize,
+}
+
+impl<'a> SeededStream<'a> {
+    fn new(
+        key: &'a hmac::Key,
+        name: &str,
+        length: usize,
+        policy: &SecretPolicy,
+        replacing: Option<&str>,
+    ) -> Self {
+        let mut info = Vec::new();
+        for part in [
+            "sherpack-secret-v1",
+            name,
+            &policy.describe(),
+            &length.to_string(),
+            replacing.unwrap_or_default(),
+        ] {
+            info.extend_from_slice(part.as_bytes());
+            info.push(0);
+        }
+
+        Self {
+            key,
+            info,
+            counter: 0,
+            block: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    fn next_byte(&mut self) -> u8 {
+        if self.pos == self.block.len() {
+            let mut ctx = hmac::Context::with_key(self.key);
+            ctx.update(&self.info);
+            ctx.update(&self.counter.to_be_bytes());
+            self.block = ctx.sign().as_ref().to_vec();
+            self.counter += 1;
+            self.pos = 0;
+        }
+        let byte = self.block[self.pos];
+        self.pos += 1;
+        byte
+    }
+}
+
+impl Draw for SeededStream<'_> {
+    fn index(&mut self, n: usize) -> usize {
+        // One byte is enough for any charset; shuffles may need four
+        let (width, range) = if n <= 256 {
+            (1, 1u64 << 8)
+        } else {
+            (4, 1u64 << 32)
+        };
+        // Largest multiple of `n` within the sampled range
+        let limit = range - range % n as u64;
+        loop {
+            let sample = (0..width).fold(0u64, |acc, _| acc << 8 | u64::from(self.next_byte()));
+            if sample < limit {
+                return (sample % n as u64) as usize;
+            }
+        }
+    }
+}
+
 // =============================================================================
 // TESTS
 // =============================================================================
@@ -345,6 +799,11 @@ mod tests {
     #[test]
     fn test_charset_parse() {
         assert_eq!(SecretCharset::parse("hex"), Some(SecretCharset::Hex));
+        assert_eq!(
+            SecretCharset::parse("complex"),
+            Some(SecretCharset::Complex)
+        );
+        assert_eq!(SecretCharset::parse("custom"), None);
         assert_eq!(
             SecretCharset::parse("ALPHANUMERIC"),
             Some(SecretCharset::Alphanumeric)
@@ -377,10 +836,14 @@ mod tests {
     fn test_generator_charset() {
         let mut generator = SecretGenerator::new();
 
-        let hex = generator.get_or_generate_with_charset("hex-token", 32, SecretCharset::Hex);
+        let hex = generator
+            .get_or_generate_with_charset("hex-token", 32, SecretCharset::Hex)
+            .unwrap();
         assert!(hex.chars().all(|c| c.is_ascii_hexdigit()));
 
-        let numeric = generator.get_or_generate_with_charset("pin", 6, SecretCharset::Numeric);
+        let numeric = generator
+            .get_or_generate_with_charset("pin", 6, SecretCharset::Numeric)
+            .unwrap();
         assert!(numeric.chars().all(|c| c.is_ascii_digit()));
     }
 
@@ -408,7 +871,7 @@ mod tests {
         let mut generator = SecretGenerator::new();
 
         let original = generator.get_or_generate("api-key", 32);
-        let rotated = generator.rotate("api-key").unwrap();
+        let rotated = generator.rotate("api-key").unwrap().unwrap();
 
         assert_ne!(original, rotated);
         assert_eq!(rotated.len(), 32);
@@ -418,6 +881,204 @@ mod tests {
         assert_eq!(current, rotated);
     }
 
+    #[test]
+    fn test_rotate_keeps_previous_value() {
+        let mut generator = SecretGenerator::new();
+
+        let original = generator.get_or_generate("db-password", 16);
+        assert_eq!(generator.state().get_previous_value("db-password"), None);
+
+        let rotated = generator.rotate("db-password").unwrap().unwrap();
+        let entry = generator.state().get("db-password").unwrap();
+        assert_eq!(entry.value(), rotated);
+        assert_eq!(entry.previous_value(), Some(original.as_str()));
+        assert_eq!(
+            entry.previous.as_ref().map(|p| p.rotated_at),
+            entry.rotated_at
+        );
+
+        // A second rotation only keeps the value it replaced
+        let again = generator.rotate("db-password").unwrap().unwrap();
+        let entry = generator.state().get("db-password").unwrap();
+        assert_eq!(entry.value(), again);
+        assert_eq!(entry.previous_value(), Some(rotated.as_str()));
+
+        // Round-trips through persistence
+        let json = serde_json::to_string(generator.state()).unwrap();
+        let loaded: SecretState = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            loaded.get_previous_value("db-password"),
+            Some(rotated.as_str())
+        );
+
+        assert!(generator.rotate("missing").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_expire_previous() {
+        let mut generator = SecretGenerator::new();
+        generator.get_or_generate("rotated", 16);
+        generator.get_or_generate("untouched", 16);
+        generator.rotate("rotated").unwrap().unwrap();
+
+        let mut state = generator.into_state();
+        state.mark_clean();
+
+        // Still within the grace period
+        assert!(state.expire_previous(chrono::Duration::hours(1)).is_empty());
+        assert!(!state.is_dirty());
+
+        assert_eq!(state.expire_previous(chrono::Duration::zero()), ["rotated"]);
+        assert_eq!(state.get_previous_value("rotated"), None);
+        assert!(state.is_dirty());
+    }
+
+    #[test]
+    fn test_seeded_generation_is_deterministic() {
+        let mut first = SecretGenerator::new().with_seed("dev-seed");
+        let mut second = SecretGenerator::new().with_seed("dev-seed");
+        assert!(first.is_seeded());
+
+        let password = first.get_or_generate("db-password", 24);
+        assert_eq!(second.get_or_generate("db-password", 24), password);
+        assert_eq!(password.len(), 24);
+        assert!(password.chars().all(|c| c.is_ascii_alphanumeric()));
+
+        // Name, length, charset and seed all change the value
+        assert_ne!(first.get_or_generate("api-key", 24), password);
+        let mut longer = SecretGenerator::new().with_seed("dev-seed");
+        assert!(
+            !longer
+                .get_or_generate("db-password", 25)
+                .starts_with(&password)
+        );
+        let mut hex = SecretGenerator::new().with_seed("dev-seed");
+        let token = hex
+            .get_or_generate_with_charset("db-password", 24, SecretCharset::Hex)
+            .unwrap();
+        assert!(token.chars().all(|c| c.is_ascii_hexdigit()));
+        let mut other = SecretGenerator::new().with_seed("other-seed");
+        assert_ne!(other.get_or_generate("db-password", 24), password);
+    }
+
+    #[test]
+    fn test_seeded_rotation() {
+        let mut first = SecretGenerator::new().with_seed("dev-seed");
+        let mut second = SecretGenerator::new().with_seed("dev-seed");
+
+        let original = first.get_or_generate("db-password", 16);
+        second.get_or_generate("db-password", 16);
+
+        let rotated = first.rotate("db-password").unwrap().unwrap();
+        assert_ne!(rotated, original);
+        assert_eq!(second.rotate("db-password").unwrap().unwrap(), rotated);
+    }
+
+    #[test]
+    fn test_unseeded_generation_is_random() {
+        let mut first = SecretGenerator::new();
+        let mut second = SecretGenerator::new();
+        assert!(!first.is_seeded());
+
+        assert_ne!(
+            first.get_or_generate("db-password", 32),
+            second.get_or_generate("db-password", 32)
+        );
+    }
+
+    #[test]
+    fn test_complex_charset_has_every_class() {
+        let mut generator = SecretGenerator::new();
+        let policy = SecretPolicy::new(SecretCharset::Complex);
+
+        // At the minimum length, each class appears exactly once
+        for i in 0..50 {
+            let value = generator
+                .get_or_generate_with_policy(&format!("short-{}", i), 4, &policy)
+                .unwrap();
+            assert_eq!(value.len(), 4);
+            assert!(value.chars().any(|c| c.is_ascii_lowercase()), "{}", value);
+            assert!(value.chars().any(|c| c.is_ascii_uppercase()), "{}", value);
+            assert!(value.chars().any(|c| c.is_ascii_digit()), "{}", value);
+            assert!(
+                value.chars().any(|c| SYMBOLS.contains(&(c as u8))),
+                "{}",
+                value
+            );
+        }
+
+        let mut seeded = SecretGenerator::new().with_seed("dev");
+        let value = seeded
+            .get_or_generate_with_policy("admin", 4, &policy)
+            .unwrap();
+        assert!(value.chars().any(|c| SYMBOLS.contains(&(c as u8))));
+    }
+
+    #[test]
+    fn test_exclude_ambiguous() {
+        let mut generator = SecretGenerator::new();
+        let policy = SecretPolicy::new(SecretCharset::Alphanumeric).without_ambiguous();
+
+        let value = generator
+            .get_or_generate_with_policy("readable", 512, &policy)
+            .unwrap();
+        assert!(!value.chars().any(|c| AMBIGUOUS_CHARS.contains(c)));
+
+        // The flag is kept, so rotation follows the same policy
+        assert!(generator.state().get("readable").unwrap().exclude_ambiguous);
+        let rotated = generator.rotate("readable").unwrap().unwrap();
+        assert!(!rotated.chars().any(|c| AMBIGUOUS_CHARS.contains(c)));
+    }
+
+    #[test]
+    fn test_custom_charset() {
+        let mut generator = SecretGenerator::new();
+
+        let value = generator
+            .get_or_generate_with_charset("pin", 64, SecretCharset::custom("AB"))
+            .unwrap();
+        assert!(value.chars().all(|c| c == 'A' || c == 'B'));
+
+        // Persisted and reloaded with its characters
+        let json = serde_json::to_string(generator.state()).unwrap();
+        assert!(json.contains(r#""charset":{"custom":{"chars":"AB"}}"#));
+        let state: SecretState = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            state.get("pin").unwrap().charset,
+            SecretCharset::custom("AB")
+        );
+    }
+
+    #[test]
+    fn test_impossible_policies_rejected() {
+        let mut generator = SecretGenerator::new();
+        let message =
+            |policy: SecretPolicy, length: usize| policy.validate(length).unwrap_err().to_string();
+
+        assert!(
+            message(SecretPolicy::new(SecretCharset::Complex), 3)
+                .contains("needs at least 4 characters")
+        );
+        assert!(message(SecretPolicy::new(SecretCharset::custom("")), 8).contains("empty"));
+        assert!(
+            message(SecretPolicy::new(SecretCharset::custom("ab c")), 8)
+                .contains("printable ASCII")
+        );
+        assert!(
+            message(
+                SecretPolicy::new(SecretCharset::custom("0O1")).without_ambiguous(),
+                8
+            )
+            .contains("no characters left")
+        );
+
+        let err = generator
+            .get_or_generate_with_charset("short", 2, SecretCharset::Complex)
+            .unwrap_err();
+        assert!(matches!(err, CoreError::InvalidSecretPolicy { .. }));
+        assert!(generator.state().is_empty());
+    }
+
     #[test]
     fn test_dirty_flag() {
         let mut generator = SecretGenerator::new();
@@ -2,19 +2,30 @@
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
+use std::collections::BTreeMap;
 use std::path::Path;
 
 use crate::error::{CoreError, Result};
+use crate::provenance::{ValueSource, ValuesProvenance};
 
 /// Values container with deep merge capability
+///
+/// Merges keep a side table of where each leaf came from, see
+/// [`Values::merge_tracked`]. The table isn't serialized.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(transparent)]
-pub struct Values(pub JsonValue);
+pub struct Values(pub JsonValue, #[serde(skip)] ValuesProvenance);
+
+impl From<JsonValue> for Values {
+    fn from(value: JsonValue) -> Self {
+        Self(value, ValuesProvenance::default())
+    }
+}
 
 impl Values {
     /// Create empty values
     pub fn new() -> Self {
-        Self(JsonValue::Object(serde_json::Map::new()))
+        Self::from(JsonValue::Object(serde_json::Map::new()))
     }
 
     /// Load values from a YAML file
@@ -26,13 +37,13 @@ impl Values {
     /// Parse values from YAML string
     pub fn from_yaml(yaml: &str) -> Result<Self> {
         let value: JsonValue = serde_yaml::from_str(yaml)?;
-        Ok(Self(value))
+        Ok(Self::from(value))
     }
 
     /// Parse values from JSON string
     pub fn from_json(json: &str) -> Result<Self> {
         let value: JsonValue = serde_json::from_str(json)?;
-        Ok(Self(value))
+        Ok(Self::from(value))
     }
 
     /// Deep merge another Values into this one
@@ -41,10 +52,74 @@ impl Values {
     /// - Scalars: overlay replaces base
     /// - Objects: recursive merge
     /// - Arrays: overlay replaces base (not appended)
+    ///
+    /// Replaced paths take the sources recorded in `overlay`.
     pub fn merge(&mut self, overlay: &Values) {
+        if !self.1.is_empty() || !overlay.1.is_empty() {
+            track_merge(&mut self.1, String::new(), &self.0, &overlay.0, &|path| {
+                overlay.1.get_source(path).cloned()
+            });
+        }
         deep_merge(&mut self.0, &overlay.0);
     }
 
+    /// Deep merge `other`, recording `source` for every leaf it sets
+    ///
+    /// Merging defaults, values files and flags in order leaves each path
+    /// with the source that set it last; see [`Values::provenance`].
+    pub fn merge_tracked(&mut self, other: &Values, source: ValueSource) {
+        track_merge(&mut self.1, String::new(), &self.0, &other.0, &|_| {
+            Some(source.clone())
+        });
+        deep_merge(&mut self.0, &other.0);
+    }
+
+    /// Merge values parsed from `--set` flags
+    ///
+    /// Deletions run first, so they remove keys coming from defaults and
+    /// earlier values files, while a later `--set` of the same key still
+    /// sets it. Deleting an array element shifts the following ones.
+    pub fn merge_set(&mut self, set: &SetValues) {
+        for (_, segments) in &set.deletions {
+            if remove_nested(&mut self.0, segments).is_some() {
+                // A changed array loses its source, a removed key has none
+                self.1.forget(&provenance_path(segments));
+            }
+        }
+        self.merge(&set.values);
+    }
+
+    /// Merge values parsed from `--set` flags, recording `source`
+    ///
+    /// Like [`Values::merge_set`]; arrays shortened by a deletion are
+    /// recorded as set by `source` too.
+    pub fn merge_set_tracked(&mut self, set: &SetValues, source: ValueSource) {
+        for (_, segments) in &set.deletions {
+            if remove_nested(&mut self.0, segments).is_some() {
+                let path = provenance_path(segments);
+                self.1.forget(&path);
+                if matches!(segments.last(), Some(PathSegment::Index(_))) {
+                    self.1.record(&path, source.clone());
+                }
+            }
+        }
+        self.merge_tracked(&set.values, source);
+    }
+
+    /// Where each leaf came from, as recorded by merges
+    ///
+    /// Leaves are named by dotted path, with arrays as leaves. Values loaded
+    /// or merged without a source have nothing recorded.
+    pub fn provenance(&self) -> &ValuesProvenance {
+        &self.1
+    }
+
+    /// Replace the recorded provenance, e.g. with one stored in a release
+    pub fn with_provenance(mut self, provenance: ValuesProvenance) -> Self {
+        self.1 = provenance;
+        self
+    }
+
     /// Merge multiple values in order
     pub fn merge_all(values: Vec<Values>) -> Self {
         let mut result = Values::new();
@@ -54,16 +129,43 @@ impl Values {
         result
     }
 
-    /// Set a value by dotted path (e.g., "image.tag")
+    /// Set a value by dotted path (e.g., "image.tag", "ports[0]")
+    ///
+    /// A backslash escapes `.`, `[`, `]` or `\` inside a key, as in
+    /// `annotations.example\.com/owner`.
     pub fn set(&mut self, path: &str, value: JsonValue) -> Result<()> {
-        let parts: Vec<&str> = path.split('.').collect();
-        set_nested(&mut self.0, &parts, value)
+        let segments = parse_path(path)?;
+        set_nested(&mut self.0, &segments, value);
+        self.1.forget(&provenance_path(&segments));
+        Ok(())
     }
 
-    /// Get a value by dotted path
+    /// Get a value by dotted path, using the same syntax as [`Values::set`]
     pub fn get(&self, path: &str) -> Option<&JsonValue> {
-        let parts: Vec<&str> = path.split('.').collect();
-        get_nested(&self.0, &parts)
+        let segments = parse_path(path).ok()?;
+        get_nested(&self.0, &segments)
+    }
+
+    /// Flatten into a map of dotted paths to leaf values
+    ///
+    /// Paths use the [`Values::set`] syntax (`image.tag`, `ports[0]`), so each
+    /// entry can be given back as a `--set` argument. Empty objects and arrays
+    /// are kept as leaves so [`Values::unflatten`] restores them.
+    pub fn flatten(&self) -> BTreeMap<String, JsonValue> {
+        let mut flat = BTreeMap::new();
+        flatten_into(&self.0, String::new(), &mut flat);
+        flat
+    }
+
+    /// Rebuild values from a map produced by [`Values::flatten`]
+    ///
+    /// Array entries missing from the map are filled with `null`.
+    pub fn unflatten(flat: &BTreeMap<String, JsonValue>) -> Result<Self> {
+        let mut values = Values::new();
+        for (path, value) in flat {
+            values.set(path, value.clone())?;
+        }
+        Ok(values)
     }
 
     /// Get the inner JSON value
@@ -96,6 +198,32 @@ impl Values {
         result
     }
 
+    // =========================================================================
+    // Patching
+    // =========================================================================
+
+    /// Apply an RFC 7386 JSON merge patch
+    ///
+    /// Objects in the patch are merged recursively, `null` deletes the key
+    /// and any other value (arrays included) replaces the target.
+    pub fn apply_merge_patch(&mut self, patch: &JsonValue) {
+        merge_patch(&mut self.0, patch);
+    }
+
+    /// Apply an RFC 6902 JSON patch
+    ///
+    /// Operations address values with JSON pointers (`/image/tag`,
+    /// `/ports/0`). The patch is atomic: if any operation fails, the values
+    /// are left untouched and the error names the failing pointer.
+    pub fn apply_json_patch(&mut self, ops: &[PatchOp]) -> Result<()> {
+        let mut patched = self.0.clone();
+        for op in ops {
+            op.apply(&mut patched)?;
+        }
+        self.0 = patched;
+        Ok(())
+    }
+
     // =========================================================================
     // Subchart Value Scoping
     // =========================================================================
@@ -106,6 +234,10 @@ impl Values {
     /// 1. Values under `<subchart_name>.*` in the parent, as its root values
     /// 2. Global values under `global.*` preserved as-is
     ///
+    /// If the parent also sets `<subchart_name>.global`, it is deep-merged on
+    /// top of the parent's `global` so a single subchart can override one
+    /// global key without losing the others.
+    ///
     /// The subchart's own `values.yaml` defaults are merged separately before this.
     ///
     /// # Example
@@ -129,23 +261,7 @@ impl Values {
     /// replicas: 3
     /// ```
     pub fn scope_for_subchart(&self, subchart_name: &str) -> Values {
-        let mut scoped = serde_json::Map::new();
-
-        if let JsonValue::Object(parent_obj) = &self.0 {
-            // 1. Copy global values if present
-            if let Some(global) = parent_obj.get("global") {
-                scoped.insert("global".to_string(), global.clone());
-            }
-
-            // 2. Extract subchart-specific values as root values
-            if let Some(JsonValue::Object(subchart_obj)) = parent_obj.get(subchart_name) {
-                for (k, v) in subchart_obj {
-                    scoped.insert(k.clone(), v.clone());
-                }
-            }
-        }
-
-        Values(JsonValue::Object(scoped))
+        Self::scope_json_for_subchart(&self.0, subchart_name)
     }
 
     /// Merge subchart defaults with scoped parent values
@@ -154,6 +270,11 @@ impl Values {
     /// 1. Start with subchart's own `values.yaml` defaults
     /// 2. Merge in the scoped values from parent
     ///
+    /// For `global` the precedence is, lowest first: the subchart's
+    /// `values.yaml` globals, the parent's `global`, then the parent's
+    /// `<subchart_name>.global`. Each layer is deep-merged, and since the
+    /// result becomes the subchart's values, nested subcharts inherit it.
+    ///
     /// # Arguments
     /// * `subchart_defaults` - Values from the subchart's `values.yaml`
     /// * `parent_values` - Parent's merged values
@@ -215,7 +336,7 @@ impl Values {
             );
         }
 
-        Values(JsonValue::Object(parent))
+        Values::from(JsonValue::Object(parent))
     }
 
     // =========================================================================
@@ -235,15 +356,22 @@ impl Values {
                 scoped.insert("global".to_string(), global.clone());
             }
 
-            // 2. Extract subchart-specific values as root values
+            // 2. Extract subchart-specific values as root values; a
+            // subchart-level `global` refines the parent's instead of
+            // replacing it
             if let Some(JsonValue::Object(subchart_obj)) = parent_obj.get(subchart_name) {
                 for (k, v) in subchart_obj {
-                    scoped.insert(k.clone(), v.clone());
+                    match (k.as_str(), scoped.get_mut(k)) {
+                        ("global", Some(global)) => deep_merge(global, v),
+                        _ => {
+                            scoped.insert(k.clone(), v.clone());
+                        }
+                    }
                 }
             }
         }
 
-        Values(JsonValue::Object(scoped))
+        Values::from(JsonValue::Object(scoped))
     }
 
     /// Merge subchart defaults with scoped parent values from JsonValue
@@ -280,88 +408,546 @@ fn deep_merge(base: &mut JsonValue, overlay: &JsonValue) {
     }
 }
 
-/// Set a nested value by path
-fn set_nested(value: &mut JsonValue, path: &[&str], new_value: JsonValue) -> Result<()> {
-    if path.is_empty() {
-        *value = new_value;
-        return Ok(());
+/// Record the sources of the leaves `overlay` sets when merged into `base`
+///
+/// Leaves of `overlay` replace whatever `base` had at their path, so the
+/// sources recorded below a replaced path are dropped first.
+fn track_merge(
+    provenance: &mut ValuesProvenance,
+    path: String,
+    base: &JsonValue,
+    overlay: &JsonValue,
+    source: &dyn Fn(&str) -> Option<ValueSource>,
+) {
+    match (base, overlay) {
+        (JsonValue::Object(base_map), JsonValue::Object(overlay_map)) => {
+            for (key, overlay_value) in overlay_map {
+                let child = if path.is_empty() {
+                    escape_key(key)
+                } else {
+                    format!("{}.{}", path, escape_key(key))
+                };
+                match base_map.get(key) {
+                    Some(base_value) => {
+                        track_merge(provenance, child, base_value, overlay_value, source)
+                    }
+                    None => provenance.record_subtree(&child, overlay_value, source),
+                }
+            }
+        }
+        _ => provenance.record_subtree(&path, overlay, source),
+    }
+}
+
+/// The provenance path holding the value at `segments`
+///
+/// Arrays are leaves, so the path stops before the first index.
+fn provenance_path(segments: &[PathSegment]) -> String {
+    segments
+        .iter()
+        .map_while(|segment| match segment {
+            PathSegment::Key(key) => Some(escape_key(key)),
+            PathSegment::Index(_) => None,
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// RFC 7386 merge patch of `target`
+fn merge_patch(target: &mut JsonValue, patch: &JsonValue) {
+    let JsonValue::Object(patch_map) = patch else {
+        *target = patch.clone();
+        return;
+    };
+
+    if !target.is_object() {
+        *target = JsonValue::Object(serde_json::Map::new());
+    }
+    let Some(target_map) = target.as_object_mut() else {
+        return;
+    };
+
+    for (key, value) in patch_map {
+        if value.is_null() {
+            target_map.remove(key);
+        } else {
+            merge_patch(
+                target_map.entry(key.clone()).or_insert(JsonValue::Null),
+                value,
+            );
+        }
     }
+}
 
-    let key = path[0];
-    let remaining = &path[1..];
+/// One operation of an RFC 6902 JSON patch
+///
+/// Deserializes from the standard form, e.g.
+/// `{"op": "replace", "path": "/image/tag", "value": "1.25"}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOp {
+    /// Add a value, inserting into arrays (`-` appends)
+    Add { path: String, value: JsonValue },
+    /// Remove an existing value
+    Remove { path: String },
+    /// Replace an existing value
+    Replace { path: String, value: JsonValue },
+    /// Move a value from one location to another
+    Move { from: String, path: String },
+    /// Copy a value to another location
+    Copy { from: String, path: String },
+    /// Check that a location holds the given value
+    Test { path: String, value: JsonValue },
+}
 
-    // Ensure we have an object
-    if !value.is_object() {
-        *value = JsonValue::Object(serde_json::Map::new());
+impl PatchOp {
+    fn apply(&self, doc: &mut JsonValue) -> Result<()> {
+        match self {
+            PatchOp::Add { path, value } => pointer_add(doc, path, value.clone()),
+            PatchOp::Remove { path } => pointer_remove(doc, path).map(drop),
+            PatchOp::Replace { path, value } => {
+                *pointer_get_mut(doc, path)? = value.clone();
+                Ok(())
+            }
+            PatchOp::Move { from, path } => {
+                if path.starts_with(from.as_str()) && path[from.len()..].starts_with('/') {
+                    return Err(patch_error(
+                        from,
+                        "cannot move a value into one of its children",
+                    ));
+                }
+                let value = pointer_remove(doc, from)?;
+                pointer_add(doc, path, value)
+            }
+            PatchOp::Copy { from, path } => {
+                let value = pointer_get_mut(doc, from)?.clone();
+                pointer_add(doc, path, value)
+            }
+            PatchOp::Test { path, value } => {
+                let actual = pointer_get_mut(doc, path)?;
+                if actual == value {
+                    Ok(())
+                } else {
+                    Err(patch_error(
+                        path,
+                        format!("test failed: expected {}, found {}", value, actual),
+                    ))
+                }
+            }
+        }
     }
+}
 
-    // SAFETY: We just ensured it's an object above
-    let map = value
-        .as_object_mut()
-        .expect("value should be an object after initialization");
+fn patch_error(pointer: &str, message: impl Into<String>) -> CoreError {
+    CoreError::ValuesPatch {
+        pointer: pointer.to_string(),
+        message: message.into(),
+    }
+}
+
+/// Split a JSON pointer into unescaped reference tokens
+fn pointer_tokens(pointer: &str) -> Result<Vec<String>> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    let Some(rest) = pointer.strip_prefix('/') else {
+        return Err(patch_error(pointer, "JSON pointer must start with '/'"));
+    };
+
+    rest.split('/')
+        .map(|token| {
+            let mut unescaped = String::with_capacity(token.len());
+            let mut chars = token.chars();
+            while let Some(c) = chars.next() {
+                if c != '~' {
+                    unescaped.push(c);
+                    continue;
+                }
+                match chars.next() {
+                    Some('0') => unescaped.push('~'),
+                    Some('1') => unescaped.push('/'),
+                    _ => return Err(patch_error(pointer, "invalid '~' escape")),
+                }
+            }
+            Ok(unescaped)
+        })
+        .collect()
+}
 
-    if remaining.is_empty() {
-        map.insert(key.to_string(), new_value);
+/// Parse an array index token: digits without leading zeros
+fn array_index(pointer: &str, token: &str, len: usize, allow_end: bool) -> Result<usize> {
+    let index = if allow_end && token == "-" {
+        Some(len)
+    } else if token == "0" || (!token.starts_with('0') && token.bytes().all(|b| b.is_ascii_digit()))
+    {
+        token.parse::<usize>().ok()
     } else {
-        let entry = map
-            .entry(key.to_string())
-            .or_insert_with(|| JsonValue::Object(serde_json::Map::new()));
-        set_nested(entry, remaining, new_value)?;
+        None
     }
+    .ok_or_else(|| patch_error(pointer, format!("'{}' is not an array index", token)))?;
+
+    let in_bounds = if allow_end { index <= len } else { index < len };
+    if !in_bounds {
+        return Err(patch_error(
+            pointer,
+            format!("index {} is out of bounds (length {})", index, len),
+        ));
+    }
+    Ok(index)
+}
 
+fn resolve_mut<'a>(
+    doc: &'a mut JsonValue,
+    pointer: &str,
+    tokens: &[String],
+) -> Result<&'a mut JsonValue> {
+    let mut current = doc;
+    for token in tokens {
+        current = match current {
+            JsonValue::Object(map) => map
+                .get_mut(token)
+                .ok_or_else(|| patch_error(pointer, format!("key '{}' does not exist", token)))?,
+            JsonValue::Array(items) => {
+                let index = array_index(pointer, token, items.len(), false)?;
+                &mut items[index]
+            }
+            _ => {
+                return Err(patch_error(
+                    pointer,
+                    format!("cannot look up '{}' in a scalar", token),
+                ));
+            }
+        };
+    }
+    Ok(current)
+}
+
+fn pointer_get_mut<'a>(doc: &'a mut JsonValue, pointer: &str) -> Result<&'a mut JsonValue> {
+    let tokens = pointer_tokens(pointer)?;
+    resolve_mut(doc, pointer, &tokens)
+}
+
+fn pointer_add(doc: &mut JsonValue, pointer: &str, value: JsonValue) -> Result<()> {
+    let tokens = pointer_tokens(pointer)?;
+    let Some((last, parents)) = tokens.split_last() else {
+        *doc = value;
+        return Ok(());
+    };
+
+    match resolve_mut(doc, pointer, parents)? {
+        JsonValue::Object(map) => {
+            map.insert(last.clone(), value);
+        }
+        JsonValue::Array(items) => {
+            let index = array_index(pointer, last, items.len(), true)?;
+            items.insert(index, value);
+        }
+        _ => return Err(patch_error(pointer, "parent is not an object or array")),
+    }
     Ok(())
 }
 
-/// Get a nested value by path
-fn get_nested<'a>(value: &'a JsonValue, path: &[&str]) -> Option<&'a JsonValue> {
+fn pointer_remove(doc: &mut JsonValue, pointer: &str) -> Result<JsonValue> {
+    let tokens = pointer_tokens(pointer)?;
+    let Some((last, parents)) = tokens.split_last() else {
+        return Err(patch_error(pointer, "cannot remove the whole document"));
+    };
+
+    match resolve_mut(doc, pointer, parents)? {
+        JsonValue::Object(map) => map
+            .remove(last)
+            .ok_or_else(|| patch_error(pointer, format!("key '{}' does not exist", last))),
+        JsonValue::Array(items) => {
+            let index = array_index(pointer, last, items.len(), false)?;
+            Ok(items.remove(index))
+        }
+        _ => Err(patch_error(pointer, "parent is not an object or array")),
+    }
+}
+
+/// One step of a values path
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+fn invalid_path(path: &str, reason: &str) -> CoreError {
+    CoreError::ValuesMerge {
+        message: format!("Invalid values path '{}': {}", path, reason),
+    }
+}
+
+/// Parse `image.tag`, `ports[0].name` or `a\.b` into segments
+fn parse_path(path: &str) -> Result<Vec<PathSegment>> {
     if path.is_empty() {
-        return Some(value);
+        return Err(invalid_path(path, "path is empty"));
     }
 
-    let key = path[0];
-    let remaining = &path[1..];
+    let mut segments = Vec::new();
+    let mut key = String::new();
+    // Whether `key` holds a segment still to push (it may be empty: `a..b`).
+    // A leading `[` addresses a root array, so there is no key before it.
+    let mut in_key = !path.starts_with('[');
+    let mut chars = path.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                let escaped = chars
+                    .next()
+                    .ok_or_else(|| invalid_path(path, "trailing backslash"))?;
+                key.push(escaped);
+                in_key = true;
+            }
+            '.' => {
+                if in_key {
+                    segments.push(PathSegment::Key(std::mem::take(&mut key)));
+                }
+                in_key = true;
+            }
+            '[' => {
+                if in_key {
+                    segments.push(PathSegment::Key(std::mem::take(&mut key)));
+                }
+                let mut digits = String::new();
+                loop {
+                    match chars.next() {
+                        Some(']') => break,
+                        Some(d) if d.is_ascii_digit() => digits.push(d),
+                        _ => return Err(invalid_path(path, "array index must be `[<number>]`")),
+                    }
+                }
+                let index = digits
+                    .parse()
+                    .map_err(|_| invalid_path(path, "array index must be `[<number>]`"))?;
+                segments.push(PathSegment::Index(index));
+                in_key = false;
+                if !matches!(chars.peek(), None | Some('.') | Some('[')) {
+                    return Err(invalid_path(path, "expected `.` or `[` after `]`"));
+                }
+            }
+            ']' => return Err(invalid_path(path, "unexpected `]`")),
+            _ => {
+                key.push(c);
+                in_key = true;
+            }
+        }
+    }
+
+    if in_key {
+        segments.push(PathSegment::Key(key));
+    }
+    Ok(segments)
+}
+
+/// Escape a key so [`parse_path`] reads it back as a single segment
+pub(crate) fn escape_key(key: &str) -> String {
+    let mut escaped = String::with_capacity(key.len());
+    for c in key.chars() {
+        if matches!(c, '.' | '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
 
+fn flatten_into(value: &JsonValue, prefix: String, flat: &mut BTreeMap<String, JsonValue>) {
     match value {
-        JsonValue::Object(map) => map.get(key).and_then(|v| get_nested(v, remaining)),
-        _ => None,
+        JsonValue::Object(map) if !map.is_empty() => {
+            for (key, child) in map {
+                let path = if prefix.is_empty() {
+                    escape_key(key)
+                } else {
+                    format!("{}.{}", prefix, escape_key(key))
+                };
+                flatten_into(child, path, flat);
+            }
+        }
+        JsonValue::Array(items) if !items.is_empty() => {
+            for (index, child) in items.iter().enumerate() {
+                flatten_into(child, format!("{}[{}]", prefix, index), flat);
+            }
+        }
+        // The root itself is only a leaf when it is not an object
+        _ if prefix.is_empty() => {}
+        leaf => {
+            flat.insert(prefix, leaf.clone());
+        }
+    }
+}
+
+/// Set a nested value by path, creating objects and arrays on the way
+fn set_nested(value: &mut JsonValue, path: &[PathSegment], new_value: JsonValue) {
+    let Some((segment, remaining)) = path.split_first() else {
+        *value = new_value;
+        return;
+    };
+
+    let child = match segment {
+        PathSegment::Key(key) => {
+            if !value.is_object() {
+                *value = JsonValue::Object(serde_json::Map::new());
+            }
+            let Some(map) = value.as_object_mut() else {
+                return;
+            };
+            map.entry(key.clone()).or_insert(JsonValue::Null)
+        }
+        PathSegment::Index(index) => {
+            if !value.is_array() {
+                *value = JsonValue::Array(Vec::new());
+            }
+            let Some(items) = value.as_array_mut() else {
+                return;
+            };
+            if items.len() <= *index {
+                items.resize(index + 1, JsonValue::Null);
+            }
+            &mut items[*index]
+        }
+    };
+
+    set_nested(child, remaining, new_value);
+}
+
+/// Get a nested value by path
+fn get_nested<'a>(value: &'a JsonValue, path: &[PathSegment]) -> Option<&'a JsonValue> {
+    path.iter()
+        .try_fold(value, |current, segment| match segment {
+            PathSegment::Key(key) => current.as_object()?.get(key),
+            PathSegment::Index(index) => current.as_array()?.get(*index),
+        })
+}
+
+/// Values parsed from `--set` flags, with the keys they delete
+///
+/// `key=null` and `key-` delete `key` instead of setting it. Use
+/// [`Values::merge_set`] to apply both parts in the right order.
+#[derive(Debug, Clone)]
+pub struct SetValues {
+    /// Values to merge on top
+    pub values: Values,
+    /// Paths to delete, in flag order
+    deletions: Vec<(String, Vec<PathSegment>)>,
+}
+
+impl Default for SetValues {
+    fn default() -> Self {
+        Self {
+            values: Values::new(),
+            deletions: Vec::new(),
+        }
+    }
+}
+
+impl SetValues {
+    /// Paths deleted by the flags, as written on the command line
+    pub fn deletions(&self) -> impl Iterator<Item = &str> {
+        self.deletions.iter().map(|(path, _)| path.as_str())
+    }
+}
+
+/// Remove a nested value by path, returning it if it existed
+fn remove_nested(value: &mut JsonValue, path: &[PathSegment]) -> Option<JsonValue> {
+    let (last, parents) = path.split_last()?;
+    let parent = parents
+        .iter()
+        .try_fold(value, |current, segment| match segment {
+            PathSegment::Key(key) => current.as_object_mut()?.get_mut(key),
+            PathSegment::Index(index) => current.as_array_mut()?.get_mut(*index),
+        })?;
+
+    match last {
+        PathSegment::Key(key) => parent.as_object_mut()?.remove(key),
+        PathSegment::Index(index) => {
+            let items = parent.as_array_mut()?;
+            (*index < items.len()).then(|| items.remove(*index))
+        }
     }
 }
 
 /// Parse --set arguments (key=value format)
-pub fn parse_set_values(set_args: &[String]) -> Result<Values> {
-    let mut values = Values::new();
+///
+/// `key=null` and `key-` (no `=`) delete the key; `key=` sets an empty
+/// string.
+pub fn parse_set_values(set_args: &[String]) -> Result<SetValues> {
+    let mut set = SetValues::default();
 
     for arg in set_args {
-        let (key, val) = arg.split_once('=').ok_or_else(|| CoreError::ValuesMerge {
-            message: format!("Invalid --set format: '{}'. Expected key=value", arg),
-        })?;
+        let (key, val) = match arg.split_once('=') {
+            Some((key, val)) => (key, Some(val)),
+            None => match arg.strip_suffix('-') {
+                Some(key) if !key.is_empty() => (key, None),
+                _ => {
+                    return Err(CoreError::ValuesMerge {
+                        message: format!(
+                            "Invalid --set format: '{}'. Expected key=value, or key- to delete",
+                            arg
+                        ),
+                    });
+                }
+            },
+        };
 
         // Try to parse as JSON, fallback to string
-        let json_value = if val == "true" {
-            JsonValue::Bool(true)
-        } else if val == "false" {
-            JsonValue::Bool(false)
-        } else if val == "null" {
-            JsonValue::Null
-        } else if let Ok(num) = val.parse::<i64>() {
-            JsonValue::Number(num.into())
-        } else if let Ok(num) = val.parse::<f64>() {
-            JsonValue::Number(serde_json::Number::from_f64(num).unwrap_or(0.into()))
-        } else if val.starts_with('[') || val.starts_with('{') {
-            serde_json::from_str(val).unwrap_or(JsonValue::String(val.to_string()))
-        } else {
-            JsonValue::String(val.to_string())
+        let json_value = match val {
+            None | Some("null") => {
+                let segments = parse_path(key)?;
+                // A later deletion overrides an earlier --set of the same key
+                remove_nested(&mut set.values.0, &segments);
+                set.deletions.push((key.to_string(), segments));
+                continue;
+            }
+            Some("true") => JsonValue::Bool(true),
+            Some("false") => JsonValue::Bool(false),
+            Some(val) => {
+                if let Ok(num) = val.parse::<i64>() {
+                    JsonValue::Number(num.into())
+                } else if let Ok(num) = val.parse::<f64>() {
+                    JsonValue::Number(serde_json::Number::from_f64(num).unwrap_or(0.into()))
+                } else if val.starts_with('[') || val.starts_with('{') {
+                    serde_json::from_str(val).unwrap_or(JsonValue::String(val.to_string()))
+                } else {
+                    JsonValue::String(val.to_string())
+                }
+            }
         };
 
-        values.set(key, json_value)?;
+        set.values.set(key, json_value)?;
+    }
+
+    Ok(set)
+}
+
+/// Parse --set-literal arguments (key=value format)
+///
+/// Unlike [`parse_set_values`], the key is not split on dots and the value is
+/// kept as an exact string, so `config.ini=a.b=c` yields a single top-level
+/// key `config.ini` with the value `a.b=c`.
+pub fn parse_set_literal_values(set_args: &[String]) -> Result<Values> {
+    let mut map = serde_json::Map::new();
+
+    for arg in set_args {
+        let (key, val) = arg.split_once('=').ok_or_else(|| CoreError::ValuesMerge {
+            message: format!(
+                "Invalid --set-literal format: '{}'. Expected key=value",
+                arg
+            ),
+        })?;
+
+        map.insert(key.to_string(), JsonValue::String(val.to_string()));
     }
 
-    Ok(values)
+    Ok(Values::from(JsonValue::Object(map)))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::rngs::StdRng;
+    use rand::{RngExt, SeedableRng};
 
     #[test]
     fn test_deep_merge() {
@@ -393,6 +979,146 @@ replicas: 3
         assert_eq!(base.get("replicas").unwrap(), 3);
     }
 
+    #[test]
+    fn test_merge_patch_null_deletes() {
+        let mut values = Values::from_yaml(
+            r#"
+image:
+  repository: nginx
+  tag: "1.0"
+ports: [80, 443]
+debug: true
+"#,
+        )
+        .unwrap();
+
+        values.apply_merge_patch(&serde_json::json!({
+            "image": {"tag": null, "pullPolicy": "Always"},
+            "ports": [8080],
+            "debug": null,
+            "extra": {"a": null, "b": 1}
+        }));
+
+        assert_eq!(
+            values.0,
+            serde_json::json!({
+                "image": {"repository": "nginx", "pullPolicy": "Always"},
+                "ports": [8080],
+                "extra": {"b": 1}
+            })
+        );
+    }
+
+    #[test]
+    fn test_json_patch_array_index_replace() {
+        let mut values = Values::from_yaml("ports: [80, 443]\nimage:\n  tag: \"1.0\"\n").unwrap();
+        let ops: Vec<PatchOp> = serde_json::from_value(serde_json::json!([
+            {"op": "replace", "path": "/ports/1", "value": 8443},
+            {"op": "add", "path": "/ports/-", "value": 9090},
+            {"op": "test", "path": "/image/tag", "value": "1.0"},
+            {"op": "copy", "from": "/image/tag", "path": "/appVersion"},
+            {"op": "move", "from": "/ports/0", "path": "/httpPort"},
+            {"op": "remove", "path": "/image"}
+        ]))
+        .unwrap();
+
+        values.apply_json_patch(&ops).unwrap();
+
+        assert_eq!(
+            values.0,
+            serde_json::json!({"ports": [8443, 9090], "appVersion": "1.0", "httpPort": 80})
+        );
+    }
+
+    #[test]
+    fn test_json_patch_escaped_pointer() {
+        let mut values = Values::from_json(r#"{"annotations": {"a/b~c": "x"}}"#).unwrap();
+        values
+            .apply_json_patch(&[PatchOp::Replace {
+                path: "/annotations/a~1b~0c".to_string(),
+                value: "y".into(),
+            }])
+            .unwrap();
+        assert_eq!(values.get("annotations").unwrap()["a/b~c"], "y");
+    }
+
+    #[test]
+    fn test_json_patch_invalid_pointer() {
+        let original = Values::from_yaml("ports: [80]\nname: web\n").unwrap();
+
+        let cases = [
+            (
+                PatchOp::Remove {
+                    path: "name".to_string(),
+                },
+                "name",
+                "must start with '/'",
+            ),
+            (
+                PatchOp::Replace {
+                    path: "/ports/3".to_string(),
+                    value: 1.into(),
+                },
+                "/ports/3",
+                "out of bounds",
+            ),
+            (
+                PatchOp::Replace {
+                    path: "/missing/key".to_string(),
+                    value: 1.into(),
+                },
+                "/missing/key",
+                "key 'missing' does not exist",
+            ),
+            (
+                PatchOp::Remove {
+                    path: "/ports/01".to_string(),
+                },
+                "/ports/01",
+                "not an array index",
+            ),
+            (
+                PatchOp::Remove {
+                    path: "/name/~2".to_string(),
+                },
+                "/name/~2",
+                "invalid '~' escape",
+            ),
+        ];
+
+        for (op, pointer, message) in cases {
+            let mut values = original.clone();
+            // A valid first op must be rolled back when a later one fails
+            let ops = [
+                PatchOp::Add {
+                    path: "/added".to_string(),
+                    value: true.into(),
+                },
+                op,
+            ];
+            let err = values.apply_json_patch(&ops).unwrap_err();
+            let text = err.to_string();
+            assert!(text.contains(&format!("'{}'", pointer)), "{}", text);
+            assert!(text.contains(message), "{}", text);
+            assert_eq!(values.0, original.0);
+        }
+    }
+
+    #[test]
+    fn test_json_patch_test_failure() {
+        let mut values = Values::from_yaml("replicas: 2\n").unwrap();
+        let err = values
+            .apply_json_patch(&[PatchOp::Test {
+                path: "/replicas".to_string(),
+                value: 3.into(),
+            }])
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Values patch failed at '/replicas': test failed: expected 3, found 2"
+        );
+    }
+
     #[test]
     fn test_set_nested() {
         let mut values = Values::new();
@@ -405,6 +1131,125 @@ replicas: 3
         assert_eq!(values.get("replicas").unwrap(), 3);
     }
 
+    #[test]
+    fn test_set_path_syntax() {
+        let mut values = Values::new();
+        values.set("ports[1].name", "https".into()).unwrap();
+        values
+            .set("annotations.example\\.com/owner", "ops".into())
+            .unwrap();
+        values.set("matrix[0][1]", 5.into()).unwrap();
+
+        assert_eq!(
+            values.0,
+            serde_json::json!({
+                "ports": [null, {"name": "https"}],
+                "annotations": {"example.com/owner": "ops"},
+                "matrix": [[null, 5]]
+            })
+        );
+        assert_eq!(values.get("ports[1].name").unwrap(), "https");
+        assert_eq!(
+            values.get("annotations.example\\.com/owner").unwrap(),
+            "ops"
+        );
+        assert!(values.get("ports[2]").is_none());
+
+        for bad in ["", "a[x]", "a[0]b", "a]", "a\\", "a[1"] {
+            let err = values.set(bad, 1.into()).unwrap_err();
+            assert!(err.to_string().contains("Invalid values path"), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_flatten() {
+        let values = Values::from_yaml(
+            r#"
+image:
+  tag: "1.25"
+ports:
+  - name: http
+    port: 80
+annotations:
+  example.com/team: core
+empty: {}
+none: []
+"#,
+        )
+        .unwrap();
+
+        let flat = values.flatten();
+        let keys: Vec<&str> = flat.keys().map(String::as_str).collect();
+        assert_eq!(
+            keys,
+            [
+                "annotations.example\\.com/team",
+                "empty",
+                "image.tag",
+                "none",
+                "ports[0].name",
+                "ports[0].port"
+            ]
+        );
+        assert_eq!(flat["ports[0].port"], 80);
+        assert_eq!(flat["empty"], serde_json::json!({}));
+
+        // Dumped as --set lines, string leaves come back through parse_set_values
+        let set_args: Vec<String> = flat
+            .iter()
+            .filter_map(|(path, value)| Some(format!("{}={}", path, value.as_str()?)))
+            .collect();
+        let parsed = parse_set_values(&set_args).unwrap().values;
+        assert_eq!(parsed.get("ports[0].name").unwrap(), "http");
+        assert_eq!(
+            parsed.get("annotations.example\\.com/team").unwrap(),
+            "core"
+        );
+    }
+
+    /// Build a random nested value with keys that need escaping
+    fn random_values(rng: &mut StdRng, depth: usize) -> JsonValue {
+        const KEYS: &[&str] = &["a", "b.c", "d[0]", "e\\f", "g]", "port", "x.y.z"];
+        let kind = if depth == 0 {
+            rng.random_range(0..4)
+        } else {
+            rng.random_range(0..6)
+        };
+        match kind {
+            0 => JsonValue::Null,
+            1 => JsonValue::Bool(rng.random_range(0..2) == 1),
+            2 => JsonValue::from(rng.random_range(0..1000)),
+            3 => JsonValue::from(format!("v{}", rng.random_range(0..100))),
+            4 => (0..rng.random_range(0..4))
+                .map(|_| random_values(rng, depth - 1))
+                .collect(),
+            _ => (0..rng.random_range(0..4))
+                .map(|_| {
+                    let key = KEYS[rng.random_range(0..KEYS.len())].to_string();
+                    (key, random_values(rng, depth - 1))
+                })
+                .collect::<serde_json::Map<_, _>>()
+                .into(),
+        }
+    }
+
+    #[test]
+    fn test_flatten_unflatten_round_trip() {
+        let mut rng = StdRng::seed_from_u64(0x5e7);
+
+        for _ in 0..500 {
+            let root: serde_json::Map<_, _> = (0..rng.random_range(0..5))
+                .map(|i| (format!("key{}", i), random_values(&mut rng, 4)))
+                .collect();
+            let values = Values::from(JsonValue::Object(root));
+
+            let flat = values.flatten();
+            let restored = Values::unflatten(&flat).unwrap();
+            assert_eq!(restored.0, values.0, "flattened as {:?}", flat);
+            assert_eq!(restored.flatten(), flat);
+        }
+    }
+
     #[test]
     fn test_parse_set_values() {
         let args = vec![
@@ -413,13 +1258,216 @@ replicas: 3
             "debug=true".to_string(),
         ];
 
-        let values = parse_set_values(&args).unwrap();
+        let values = parse_set_values(&args).unwrap().values;
 
         assert_eq!(values.get("image.tag").unwrap(), "v2");
         assert_eq!(values.get("replicas").unwrap(), 5);
         assert_eq!(values.get("debug").unwrap(), true);
     }
 
+    fn base_values() -> Values {
+        Values::from_yaml(
+            r#"
+image:
+  repository: nginx
+  tag: "1.0"
+  pullPolicy: Always
+ports: [80, 443, 8080]
+"#,
+        )
+        .unwrap()
+    }
+
+    fn set_args(args: &[&str]) -> SetValues {
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        parse_set_values(&args).unwrap()
+    }
+
+    #[test]
+    fn test_set_null_deletes_nested_key() {
+        let mut values = base_values();
+        let set = set_args(&["image.tag=null", "image.pullPolicy-", "missing.key=null"]);
+        assert_eq!(
+            set.deletions().collect::<Vec<_>>(),
+            ["image.tag", "image.pullPolicy", "missing.key"]
+        );
+
+        values.merge_set(&set);
+        assert_eq!(
+            values.0["image"],
+            serde_json::json!({"repository": "nginx"})
+        );
+        assert!(values.get("missing").is_none());
+    }
+
+    #[test]
+    fn test_set_deletes_array_element() {
+        let mut values = base_values();
+        values.merge_set(&set_args(&["ports[1]-"]));
+        assert_eq!(values.0["ports"], serde_json::json!([80, 8080]));
+
+        // Out of range deletions are ignored
+        values.merge_set(&set_args(&["ports[5]=null"]));
+        assert_eq!(values.0["ports"], serde_json::json!([80, 8080]));
+    }
+
+    #[test]
+    fn test_set_deletion_order() {
+        // A later --set brings the key back, a later deletion wins over a set
+        let mut values = base_values();
+        values.merge_set(&set_args(&[
+            "image.tag=null",
+            "image.tag=2.0",
+            "image.repository=redis",
+            "image.repository-",
+        ]));
+        assert_eq!(values.get("image.tag").unwrap(), 2.0);
+        assert!(values.get("image.repository").is_none());
+
+        // Deleting a parent and setting a child replaces the whole object
+        let mut values = base_values();
+        values.merge_set(&set_args(&["image=null", "image.tag=3"]));
+        assert_eq!(values.0["image"], serde_json::json!({"tag": 3}));
+    }
+
+    #[test]
+    fn test_set_empty_string_is_not_deletion() {
+        let mut values = base_values();
+        let set = set_args(&["image.tag="]);
+        assert_eq!(set.deletions().count(), 0);
+
+        values.merge_set(&set);
+        assert_eq!(values.get("image.tag").unwrap(), "");
+
+        for bad in ["image", "-"] {
+            let err = parse_set_values(&[bad.to_string()]).unwrap_err();
+            assert!(err.to_string().contains("key- to delete"), "{}", bad);
+        }
+    }
+
+    fn file_source(path: &str) -> ValueSource {
+        ValueSource::ValuesFile {
+            path: path.to_string(),
+            line: None,
+        }
+    }
+
+    #[test]
+    fn test_merge_tracked_records_last_source() {
+        let mut values = Values::new();
+        values.merge_tracked(&base_values(), ValueSource::PackDefault);
+        let prod = Values::from_yaml("image:\n  tag: \"2.0\"\nports: [443]\n").unwrap();
+        values.merge_tracked(&prod, file_source("prod.yaml"));
+
+        let provenance = values.provenance();
+        assert_eq!(
+            provenance.get_source("image.tag"),
+            Some(&file_source("prod.yaml"))
+        );
+        assert_eq!(
+            provenance.get_source("image.repository"),
+            Some(&ValueSource::PackDefault)
+        );
+        // Arrays are replaced as a whole
+        assert_eq!(
+            provenance.get_source("ports"),
+            Some(&file_source("prod.yaml"))
+        );
+    }
+
+    #[test]
+    fn test_provenance_keys_with_dots() {
+        let mut values = Values::new();
+        values.merge_tracked(
+            &Values::from_yaml(
+                "annotations:\n  example.com/owner: team\n  example:\n    com/owner: other\n",
+            )
+            .unwrap(),
+            ValueSource::PackDefault,
+        );
+        values.merge_tracked(
+            &Values::from_yaml("annotations:\n  example.com/owner: ops\n").unwrap(),
+            file_source("prod.yaml"),
+        );
+
+        let provenance = values.provenance();
+        assert_eq!(
+            provenance.get_source("annotations.example\\.com/owner"),
+            Some(&file_source("prod.yaml"))
+        );
+        assert_eq!(
+            provenance.get_source("annotations.example.com/owner"),
+            Some(&ValueSource::PackDefault)
+        );
+
+        let user = provenance.user_supplied(&values).unwrap();
+        assert_eq!(
+            user.inner(),
+            &serde_json::json!({"annotations": {"example.com/owner": "ops"}})
+        );
+    }
+
+    #[test]
+    fn test_merge_tracked_replaced_subtree() {
+        let mut values = Values::new();
+        values.merge_tracked(&base_values(), ValueSource::PackDefault);
+        let flat = Values::from_yaml("image: nginx:2.0\n").unwrap();
+        values.merge_tracked(&flat, file_source("flat.yaml"));
+
+        let provenance = values.provenance();
+        assert_eq!(
+            provenance.get_source("image"),
+            Some(&file_source("flat.yaml"))
+        );
+        assert!(provenance.get_source("image.tag").is_none());
+
+        // Untracked overlays drop the sources of what they replace
+        values.merge(&Values::from_yaml("image: nginx:3.0\n").unwrap());
+        assert!(values.provenance().get_source("image").is_none());
+        assert_eq!(
+            values.provenance().get_source("ports"),
+            Some(&ValueSource::PackDefault)
+        );
+    }
+
+    #[test]
+    fn test_merge_set_tracked_deletions() {
+        let mut values = Values::new();
+        values.merge_tracked(&base_values(), ValueSource::PackDefault);
+        let source = ValueSource::Environment {
+            var: "TEST".to_string(),
+        };
+        values.merge_set_tracked(&set_args(&["image.tag-", "ports[0]-"]), source.clone());
+
+        let provenance = values.provenance();
+        assert!(provenance.get_source("image.tag").is_none());
+        assert_eq!(provenance.get_source("ports"), Some(&source));
+        assert_eq!(
+            provenance.get_source("image.repository"),
+            Some(&ValueSource::PackDefault)
+        );
+    }
+
+    #[test]
+    fn test_parse_set_literal_values() {
+        let args = vec!["config.ini=a.b=c".to_string(), "count=5".to_string()];
+
+        let values = parse_set_literal_values(&args).unwrap();
+        let map = values.inner().as_object().unwrap();
+
+        assert_eq!(map.len(), 2);
+        assert_eq!(map["config.ini"], "a.b=c");
+        // No type inference: numbers stay strings
+        assert_eq!(map["count"], "5");
+        assert!(values.get("config").is_none());
+    }
+
+    #[test]
+    fn test_parse_set_literal_values_invalid() {
+        let args = vec!["no-equals-sign".to_string()];
+        assert!(parse_set_literal_values(&args).is_err());
+    }
+
     #[test]
     fn test_scope_for_subchart_basic() {
         let parent = Values::from_yaml(
@@ -524,6 +1572,43 @@ redis:
         assert_eq!(result.get("image.tag").unwrap(), "7.0");
     }
 
+    #[test]
+    fn test_for_subchart_global_precedence() {
+        let subchart_defaults = Values::from_yaml(
+            r#"
+global:
+  registry: docker.io
+  pullPolicy: IfNotPresent
+  storageClass: standard
+"#,
+        )
+        .unwrap();
+
+        let parent = Values::from_yaml(
+            r#"
+global:
+  registry: registry.example.com
+  pullPolicy: Always
+redis:
+  global:
+    pullPolicy: Never
+"#,
+        )
+        .unwrap();
+
+        let result = Values::for_subchart(subchart_defaults, &parent, "redis");
+
+        // Parent global overrides the subchart's defaults
+        assert_eq!(
+            result.get("global.registry").unwrap(),
+            "registry.example.com"
+        );
+        // Subchart-level global refines the parent's without replacing it
+        assert_eq!(result.get("global.pullPolicy").unwrap(), "Never");
+        // Untouched subchart default survives
+        assert_eq!(result.get("global.storageClass").unwrap(), "standard");
+    }
+
     #[test]
     fn test_export_to_parent() {
         let subchart = Values::from_yaml(
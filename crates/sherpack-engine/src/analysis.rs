@@ -0,0 +1,570 @@
+//! Static analysis of template sources
+//!
+//! Scans template tags without rendering them, for lint checks that must see
+//! every branch of a template rather than only the one the default values
+//! happen to take.
+//!
+//! The scanner understands enough Jinja to be useful, not the full grammar:
+//! it tokenizes `{{ }}` and `{% %}` tags, skips comments and `raw` blocks,
+//! and follows the statements that bind names (`set`, `for`, `with`, `macro`,
+//! `call`) so locally bound names aren't mistaken for pack values.
+
+/// A `values.<path>` read found in a template
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValueReference {
+    /// Keys below `values`, in order
+    pub path: Vec<String>,
+
+    /// Line (1-based) of the access
+    pub line: usize,
+}
+
+impl ValueReference {
+    /// Dotted form of the path (e.g. `image.tag`)
+    pub fn dotted(&self) -> String {
+        self.path.join(".")
+    }
+}
+
+/// Find the `values.<path>` reads of a template
+///
+/// Reads guarded by `| default(...)` or an `is defined` test are skipped, as
+/// are reads while `values` is rebound by a local `set`, `for`, `with`,
+/// `macro` or `call`. Dynamic lookups (`values[name]`) end the path at the
+/// last static key, and a trailing method call (`values.env.items()`) is not
+/// part of the path.
+pub fn value_references(source: &str) -> Vec<ValueReference> {
+    let lines = LineIndex::new(source);
+    let mut refs = Vec::new();
+
+    // One entry per open scope, true when it rebinds `values`
+    let mut scopes = vec![false];
+    // Open `{% set x %}...{% endset %}` blocks, true when they assign `values`
+    let mut set_blocks = Vec::new();
+
+    for tag in scan_tags(source) {
+        let shadowed = scopes.iter().any(|s| *s);
+        let tokens = &tag.tokens;
+        let mut collect = |tokens: &[Token]| {
+            if !shadowed {
+                collect_value_references(tokens, &lines, &mut refs);
+            }
+        };
+
+        if !tag.statement {
+            collect(tokens);
+            continue;
+        }
+
+        let Some(TokenKind::Ident(keyword)) = tokens.first().map(|t| &t.kind) else {
+            continue;
+        };
+
+        match *keyword {
+            "for" => {
+                let in_pos = tokens
+                    .iter()
+                    .position(|t| t.is_ident("in"))
+                    .unwrap_or(tokens.len());
+                collect(&tokens[in_pos..]);
+                scopes.push(binds_values(&tokens[1..in_pos]));
+            }
+            "with" => {
+                collect(&tokens[1..]);
+                scopes.push(assigned_names(&tokens[1..]).any(|name| name == "values"));
+            }
+            "macro" | "call" => {
+                // Defaults and call arguments are evaluated outside the body
+                collect(&tokens[1..]);
+                scopes.push(binds_values(&tokens[1..]));
+            }
+            "endfor" | "endwith" | "endmacro" | "endcall" => {
+                if scopes.len() > 1 {
+                    scopes.pop();
+                }
+            }
+            "set" => match tokens.iter().position(|t| t.is_punct('=')) {
+                Some(eq) => {
+                    collect(&tokens[eq + 1..]);
+                    if binds_values(&tokens[1..eq])
+                        && let Some(scope) = scopes.last_mut()
+                    {
+                        *scope = true;
+                    }
+                }
+                None => set_blocks.push(binds_values(&tokens[1..])),
+            },
+            "endset" => {
+                if set_blocks.pop() == Some(true)
+                    && let Some(scope) = scopes.last_mut()
+                {
+                    *scope = true;
+                }
+            }
+            _ => collect(&tokens[1..]),
+        }
+    }
+
+    refs
+}
+
+/// A global function call found in a template
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionCall {
+    /// Function name (e.g. `now`)
+    pub name: String,
+
+    /// First argument, when it is a string literal
+    pub first_arg: Option<String>,
+
+    /// Line (1-based) of the call
+    pub line: usize,
+}
+
+/// Find the global function calls of a template
+///
+/// Method calls (`x.items()`), filters with arguments (`| default(1)`),
+/// tests (`is divisibleby(3)`) and macro definitions are not global calls.
+pub fn function_calls(source: &str) -> Vec<FunctionCall> {
+    let lines = LineIndex::new(source);
+    let mut calls = Vec::new();
+
+    for tag in scan_tags(source) {
+        let tokens = &tag.tokens;
+        // `{% macro name(...) %}` defines rather than calls `name`
+        let skip = if tag.statement && tokens.first().is_some_and(|t| t.is_ident("macro")) {
+            2
+        } else {
+            0
+        };
+
+        for i in skip..tokens.len() {
+            let TokenKind::Ident(name) = tokens[i].kind else {
+                continue;
+            };
+            if !tokens.get(i + 1).is_some_and(|t| t.is_punct('(')) {
+                continue;
+            }
+            let qualified = i.checked_sub(1).is_some_and(|prev| {
+                tokens[prev].is_punct('.')
+                    || tokens[prev].is_punct('|')
+                    || tokens[prev].is_ident("is")
+            });
+            if qualified {
+                continue;
+            }
+
+            let first_arg = match (tokens.get(i + 2), tokens.get(i + 3)) {
+                (
+                    Some(Token {
+                        kind: TokenKind::Str(arg),
+                        ..
+                    }),
+                    Some(next),
+                ) if next.is_punct(',') || next.is_punct(')') => Some(arg.clone()),
+                _ => None,
+            };
+
+            calls.push(FunctionCall {
+                name: name.to_string(),
+                first_arg,
+                line: lines.line_of(tokens[i].offset),
+            });
+        }
+    }
+
+    calls
+}
+
+/// Collect `values.<path>` reads from the tokens of one tag
+fn collect_value_references(tokens: &[Token], lines: &LineIndex, refs: &mut Vec<ValueReference>) {
+    for (i, token) in tokens.iter().enumerate() {
+        // `values` itself, not an attribute named `values`
+        if !token.is_ident("values") || (i > 0 && tokens[i - 1].is_punct('.')) {
+            continue;
+        }
+
+        let mut path = Vec::new();
+        let mut j = i + 1;
+        loop {
+            match (tokens.get(j), tokens.get(j + 1), tokens.get(j + 2)) {
+                (Some(dot), Some(next), _) if dot.is_punct('.') => match &next.kind {
+                    TokenKind::Ident(name) => {
+                        path.push(name.to_string());
+                        j += 2;
+                    }
+                    _ => break,
+                },
+                (Some(open), Some(key), Some(close))
+                    if open.is_punct('[') && close.is_punct(']') =>
+                {
+                    match &key.kind {
+                        TokenKind::Str(key) => {
+                            path.push(key.clone());
+                            j += 3;
+                        }
+                        _ => break,
+                    }
+                }
+                _ => break,
+            }
+        }
+
+        // `values.env.items()` calls a method on `values.env`
+        if tokens.get(j).is_some_and(|t| t.is_punct('(')) {
+            path.pop();
+        }
+
+        if path.is_empty() || is_guarded(&tokens[j..]) {
+            continue;
+        }
+
+        refs.push(ValueReference {
+            path,
+            line: lines.line_of(token.offset),
+        });
+    }
+}
+
+/// Whether an access is followed by a `default` filter or an `is defined` test
+fn is_guarded(rest: &[Token]) -> bool {
+    let mut i = 0;
+
+    if rest.first().is_some_and(|t| t.is_ident("is")) {
+        let test = match rest.get(1) {
+            Some(t) if t.is_ident("not") => rest.get(2),
+            other => other,
+        };
+        return test.is_some_and(|t| {
+            t.is_ident("defined") || t.is_ident("undefined") || t.is_ident("none")
+        });
+    }
+
+    // Walk the filter chain: `| name` optionally followed by `(args)`
+    while rest.get(i).is_some_and(|t| t.is_punct('|')) {
+        let Some(TokenKind::Ident(filter)) = rest.get(i + 1).map(|t| &t.kind) else {
+            return false;
+        };
+        if matches!(*filter, "default" | "d") {
+            return true;
+        }
+        i += 2;
+
+        if rest.get(i).is_some_and(|t| t.is_punct('(')) {
+            let mut depth = 0;
+            while let Some(token) = rest.get(i) {
+                if token.is_punct('(') {
+                    depth += 1;
+                } else if token.is_punct(')') {
+                    depth -= 1;
+                }
+                i += 1;
+                if depth == 0 {
+                    break;
+                }
+            }
+        }
+    }
+
+    false
+}
+
+/// Whether a list of binding targets includes `values`
+fn binds_values(targets: &[Token]) -> bool {
+    targets
+        .iter()
+        .enumerate()
+        .any(|(i, t)| t.is_ident("values") && !(i > 0 && targets[i - 1].is_punct('.')))
+}
+
+/// Names assigned by `name = expr` pairs (`with` statements)
+fn assigned_names<'a>(tokens: &'a [Token<'a>]) -> impl Iterator<Item = &'a str> + 'a {
+    tokens.windows(3).filter_map(|w| match &w[0].kind {
+        TokenKind::Ident(name) if w[1].is_punct('=') && !w[2].is_punct('=') => Some(*name),
+        _ => None,
+    })
+}
+
+// ========== Tokenizer ==========
+
+/// A `{{ }}` or `{% %}` tag
+#[derive(Debug)]
+struct Tag<'a> {
+    statement: bool,
+    tokens: Vec<Token<'a>>,
+}
+
+#[derive(Debug)]
+struct Token<'a> {
+    kind: TokenKind<'a>,
+    /// Byte offset in the template source
+    offset: usize,
+}
+
+#[derive(Debug, PartialEq)]
+enum TokenKind<'a> {
+    Ident(&'a str),
+    Str(String),
+    Punct(char),
+    Number,
+}
+
+impl Token<'_> {
+    fn is_ident(&self, name: &str) -> bool {
+        self.kind == TokenKind::Ident(name)
+    }
+
+    fn is_punct(&self, c: char) -> bool {
+        self.kind == TokenKind::Punct(c)
+    }
+}
+
+/// Split a template into its tags, skipping comments and `raw` blocks
+fn scan_tags(source: &str) -> Vec<Tag<'_>> {
+    let mut tags = Vec::new();
+    let mut pos = 0;
+
+    while let Some(found) = source[pos..].find('{') {
+        let start = pos + found;
+        let (statement, close) = match source[start + 1..].chars().next() {
+            Some('{') => (false, "}}"),
+            Some('%') => (true, "%}"),
+            Some('#') => {
+                pos = source[start + 2..]
+                    .find("#}")
+                    .map_or(source.len(), |end| start + 2 + end + 2);
+                continue;
+            }
+            _ => {
+                pos = start + 1;
+                continue;
+            }
+        };
+
+        let (tokens, end) = tokenize(source, start + 2, close);
+        pos = end;
+
+        if statement && tokens.first().is_some_and(|t| t.is_ident("raw")) {
+            pos = skip_raw(source, pos);
+            continue;
+        }
+
+        tags.push(Tag { statement, tokens });
+    }
+
+    tags
+}
+
+/// Position after the `{% endraw %}` closing a raw block
+fn skip_raw(source: &str, mut pos: usize) -> usize {
+    while let Some(found) = source[pos..].find("{%") {
+        let start = pos + found;
+        let (tokens, end) = tokenize(source, start + 2, "%}");
+        if tokens.first().is_some_and(|t| t.is_ident("endraw")) {
+            return end;
+        }
+        pos = end;
+    }
+    source.len()
+}
+
+/// Tokenize a tag body starting at `pos`, up to its `close` delimiter
+///
+/// Returns the tokens and the position after the delimiter.
+fn tokenize<'a>(source: &'a str, mut pos: usize, close: &str) -> (Vec<Token<'a>>, usize) {
+    let mut tokens = Vec::new();
+    let bytes = source.as_bytes();
+
+    // Whitespace control (`{{-`, `{%+`)
+    if matches!(bytes.get(pos), Some(b'-' | b'+')) {
+        pos += 1;
+    }
+
+    while pos < source.len() {
+        let rest = &source[pos..];
+        if rest.starts_with(close) {
+            return (tokens, pos + close.len());
+        }
+        if (rest.starts_with('-') || rest.starts_with('+')) && rest[1..].starts_with(close) {
+            return (tokens, pos + 1 + close.len());
+        }
+
+        let c = rest.chars().next().unwrap_or_default();
+        let start = pos;
+
+        if c.is_whitespace() {
+            pos += c.len_utf8();
+        } else if c == '"' || c == '\'' {
+            let mut value = String::new();
+            let mut chars = rest.char_indices().skip(1);
+            pos = source.len();
+            while let Some((i, ch)) = chars.next() {
+                match ch {
+                    '\\' => {
+                        if let Some((_, escaped)) = chars.next() {
+                            value.push(escaped);
+                        }
+                    }
+                    ch if ch == c => {
+                        pos = start + i + 1;
+                        break;
+                    }
+                    ch => value.push(ch),
+                }
+            }
+            tokens.push(Token {
+                kind: TokenKind::Str(value),
+                offset: start,
+            });
+        } else if c.is_alphabetic() || c == '_' {
+            let len = rest
+                .find(|ch: char| !(ch.is_alphanumeric() || ch == '_'))
+                .unwrap_or(rest.len());
+            pos += len;
+            tokens.push(Token {
+                kind: TokenKind::Ident(&source[start..pos]),
+                offset: start,
+            });
+        } else if c.is_ascii_digit() {
+            let len = rest
+                .find(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '_'))
+                .unwrap_or(rest.len());
+            pos += len;
+            tokens.push(Token {
+                kind: TokenKind::Number,
+                offset: start,
+            });
+        } else {
+            pos += c.len_utf8();
+            tokens.push(Token {
+                kind: TokenKind::Punct(c),
+                offset: start,
+            });
+        }
+    }
+
+    (tokens, source.len())
+}
+
+/// Maps byte offsets to line numbers
+struct LineIndex {
+    starts: Vec<usize>,
+}
+
+impl LineIndex {
+    fn new(source: &str) -> Self {
+        let starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self { starts }
+    }
+
+    /// Line (1-based) containing `offset`
+    fn line_of(&self, offset: usize) -> usize {
+        self.starts.partition_point(|&start| start <= offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(source: &str) -> Vec<String> {
+        value_references(source)
+            .iter()
+            .map(ValueReference::dotted)
+            .collect()
+    }
+
+    #[test]
+    fn test_value_references_with_lines() {
+        let source = "name: {{ values.name }}\nimage: \"{{ values.image.repository }}:{{ values['image'][\"tag\"] }}\"\n";
+
+        let refs = value_references(source);
+        assert_eq!(refs.len(), 3);
+        assert_eq!(refs[0].dotted(), "name");
+        assert_eq!(refs[0].line, 1);
+        assert_eq!(refs[1].dotted(), "image.repository");
+        assert_eq!(refs[1].line, 2);
+        assert_eq!(refs[2].dotted(), "image.tag");
+    }
+
+    #[test]
+    fn test_value_references_in_statements() {
+        let source = r#"{% if values.ingress.enabled and not values.debug %}
+{% for host in values.ingress.hosts %}{{ host.name }}{% endfor %}
+{% endif %}
+{%- set port = values.service.port -%}
+{# {{ values.commented.out }} #}
+{% raw %}{{ values.raw.text }}{% endraw %}"#;
+
+        assert_eq!(
+            paths(source),
+            vec!["ingress.enabled", "debug", "ingress.hosts", "service.port"]
+        );
+    }
+
+    #[test]
+    fn test_value_references_skip_guarded_and_methods() {
+        let source = r#"{{ values.tag | default("latest") }}
+{{ values.name | lower | d("x") }}
+{% if values.extra is defined %}{% endif %}
+{% if values.other is not none %}{% endif %}
+{% for k, v in values.env.items() %}{% endfor %}
+{{ item.values.nested }}
+{{ values.labels | tojson }}"#;
+
+        assert_eq!(paths(source), vec!["env", "labels"]);
+    }
+
+    #[test]
+    fn test_value_references_ignore_local_bindings() {
+        let source = r#"{% for values in values.groups %}{{ values.name }}{% endfor %}
+{% with values = values.nested %}{{ values.inner }}{% endwith %}
+{% macro render(values) %}{{ values.anything }}{% endmacro %}
+{{ values.after }}
+{% set values = {"x": 1} %}
+{{ values.shadowed }}"#;
+
+        assert_eq!(paths(source), vec!["groups", "nested", "after"]);
+    }
+
+    #[test]
+    fn test_function_calls() {
+        let source = r#"{% macro now() %}{% endmacro %}
+stamp: {{ now() }}
+id: {{ uuidv4() | upper }}
+{{ values.env.items() }}{{ values.x | default(uuidv4()) }}
+{% if 4 is divisibleby(2) %}{{ generate_secret("db-password", 16) }}{% endif %}
+{{ generate_secret(values.name ~ "-key") }}
+{# {{ now() }} #}"#;
+
+        let calls: Vec<_> = function_calls(source)
+            .into_iter()
+            .map(|c| (c.name, c.first_arg, c.line))
+            .collect();
+        assert_eq!(
+            calls,
+            vec![
+                ("now".to_string(), None, 2),
+                ("uuidv4".to_string(), None, 3),
+                ("uuidv4".to_string(), None, 4),
+                (
+                    "generate_secret".to_string(),
+                    Some("db-password".to_string()),
+                    5
+                ),
+                ("generate_secret".to_string(), None, 6),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_set_inside_loop_is_scoped() {
+        let source = r#"{% for i in [1] %}{% set values = {} %}{{ values.local }}{% endfor %}
+{{ values.outer }}
+{% set values %}block{% endset %}{{ values.after_block }}"#;
+
+        assert_eq!(paths(source), vec!["outer"]);
+    }
+}
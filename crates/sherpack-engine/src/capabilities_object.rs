@@ -0,0 +1,164 @@
+//! MiniJinja integration for cluster capabilities
+//!
+//! Exposes `capabilities` to templates with an `apiVersions` object that
+//! behaves like a list and also answers `has()`, mirroring Helm's
+//! `.Capabilities.APIVersions.Has`. Entries are `group/version` or
+//! `group/version/Kind`; install and upgrade fill them from discovery.
+//!
+//! # Usage in Templates
+//!
+//! ```jinja2
+//! {% if capabilities.apiVersions.has("policy/v1") %}
+//! apiVersion: policy/v1
+//! {% else %}
+//! apiVersion: policy/v1beta1
+//! {% endif %}
+//!
+//! {# Still usable as a plain list #}
+//! {% if "batch/v1" in capabilities.apiVersions %}...{% endif %}
+//! ```
+
+use std::sync::Arc;
+
+use minijinja::value::{Enumerator, Object, ObjectRepr, Value, from_args};
+use minijinja::{Error, ErrorKind};
+use sherpack_core::context::Capabilities;
+
+/// List of served API versions with a `has()` method
+#[derive(Debug)]
+pub struct ApiVersionsObject {
+    versions: Vec<String>,
+}
+
+impl ApiVersionsObject {
+    /// Create from a list of API versions
+    pub fn new(versions: Vec<String>) -> Self {
+        Self { versions }
+    }
+}
+
+impl Object for ApiVersionsObject {
+    fn repr(self: &Arc<Self>) -> ObjectRepr {
+        ObjectRepr::Seq
+    }
+
+    fn get_value(self: &Arc<Self>, key: &Value) -> Option<Value> {
+        let index = key.as_usize()?;
+        self.versions.get(index).map(|v| Value::from(v.as_str()))
+    }
+
+    fn enumerate(self: &Arc<Self>) -> Enumerator {
+        Enumerator::Seq(self.versions.len())
+    }
+
+    fn call_method(
+        self: &Arc<Self>,
+        _state: &minijinja::State,
+        method: &str,
+        args: &[Value],
+    ) -> Result<Value, Error> {
+        match method {
+            "has" => {
+                let (version,): (&str,) = from_args(args)?;
+                Ok(Value::from(self.versions.iter().any(|v| v == version)))
+            }
+            _ => Err(Error::new(
+                ErrorKind::UnknownMethod,
+                format!("apiVersions has no method '{}'", method),
+            )),
+        }
+    }
+}
+
+/// Build the `capabilities` template value
+pub fn create_capabilities_value(capabilities: &Capabilities) -> Value {
+    minijinja::context! {
+        kubeVersion => Value::from_serialize(&capabilities.kube_version),
+        apiVersions => Value::from_object(ApiVersionsObject::new(capabilities.api_versions.clone())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use minijinja::Environment;
+
+    fn render(template: &str, capabilities: &Capabilities) -> String {
+        let env = Environment::new();
+        env.render_str(
+            template,
+            minijinja::context! { capabilities => create_capabilities_value(capabilities) },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_api_versions_has() {
+        let caps = Capabilities::default().with_api_versions(["monitoring.coreos.com/v1"]);
+
+        assert_eq!(
+            render(
+                r#"{% if capabilities.apiVersions.has("monitoring.coreos.com/v1") %}yes{% endif %}"#,
+                &caps
+            ),
+            "yes"
+        );
+        assert_eq!(
+            render(
+                r#"{% if not capabilities.apiVersions.has("example.com/v1") %}no{% endif %}"#,
+                &caps
+            ),
+            "no"
+        );
+    }
+
+    #[test]
+    fn test_api_versions_has_kind() {
+        let caps = Capabilities::default().with_api_versions(["apps/v1/Deployment"]);
+
+        assert_eq!(
+            render(
+                r#"{% if capabilities.apiVersions.has("apps/v1/Deployment") %}yes{% endif %}"#,
+                &caps
+            ),
+            "yes"
+        );
+        assert_eq!(
+            render(
+                r#"{% if not capabilities.apiVersions.has("apps/v1/ReplicationController") %}no{% endif %}"#,
+                &caps
+            ),
+            "no"
+        );
+    }
+
+    #[test]
+    fn test_api_versions_as_list() {
+        let caps = Capabilities {
+            api_versions: vec!["v1".to_string(), "apps/v1".to_string()],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            render(
+                r#"{% if "apps/v1" in capabilities.apiVersions %}yes{% endif %} {{ capabilities.apiVersions | join(",") }}"#,
+                &caps
+            ),
+            "yes v1,apps/v1"
+        );
+    }
+
+    #[test]
+    fn test_kube_version_fields() {
+        let caps = Capabilities::default()
+            .with_kube_version(sherpack_core::context::KubeVersion::parse("1.29.3").unwrap());
+
+        assert_eq!(
+            render(
+                "{{ capabilities.kubeVersion.version }} {{ capabilities.kubeVersion.minor }}",
+                &caps
+            ),
+            "v1.29.3 29"
+        );
+    }
+}
@@ -5,9 +5,10 @@
 
 use indexmap::IndexMap;
 use minijinja::Environment;
-use sherpack_core::{LoadedPack, SandboxedFileProvider, TemplateContext};
+use sherpack_core::{ArchiveFileProvider, LoadedPack, SandboxedFileProvider, TemplateContext};
 use std::collections::HashMap;
 
+use crate::capabilities_object::create_capabilities_value;
 use crate::error::{EngineError, RenderReport, RenderResultWithReport, Result, TemplateError};
 use crate::files_object::create_files_value_from_provider;
 use crate::filters;
@@ -29,9 +30,53 @@ pub struct RenderResult {
     pub notes: Option<String>,
 }
 
+/// How templates treat values that are not defined
+///
+/// Whatever the mode, `required`, `fail` and `must_have` still fail on a
+/// missing value, and so do filters that must parse or measure their input:
+/// `fromjson`, `int`, `semver_match`, `date`, `duration`, `length` and
+/// `first`. Guard those with `default()`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UndefinedMode {
+    /// Any use of an undefined value is an error
+    Strict,
+
+    /// Undefined values render as empty, but reading an attribute of one
+    /// fails (`values.missing.key` errors)
+    Lenient,
+
+    /// Undefined values render as empty and attribute chains through them
+    /// stay undefined (`values.missing.key` renders empty)
+    ///
+    /// The default: missing keys render empty as in Helm, and converted
+    /// charts that read optional nested values keep rendering.
+    #[default]
+    ChainableNone,
+}
+
+impl UndefinedMode {
+    fn behavior(self) -> minijinja::UndefinedBehavior {
+        match self {
+            Self::Strict => minijinja::UndefinedBehavior::Strict,
+            Self::Lenient => minijinja::UndefinedBehavior::Lenient,
+            Self::ChainableNone => minijinja::UndefinedBehavior::Chainable,
+        }
+    }
+
+    /// Mode for the legacy strict flag (`true` chains, `false` is lenient)
+    fn from_strict(strict: bool) -> Self {
+        if strict {
+            Self::ChainableNone
+        } else {
+            Self::Lenient
+        }
+    }
+}
+
 /// Template engine builder
 pub struct EngineBuilder {
-    strict_mode: bool,
+    undefined_mode: UndefinedMode,
+    fail_fast: bool,
     secret_state: Option<crate::secrets::SecretFunctionState>,
     lookup_state: Option<crate::cluster_reader::LookupState>,
 }
@@ -45,15 +90,35 @@ impl Default for EngineBuilder {
 impl EngineBuilder {
     pub fn new() -> Self {
         Self {
-            strict_mode: true,
+            undefined_mode: UndefinedMode::default(),
+            fail_fast: false,
             secret_state: None,
             lookup_state: None,
         }
     }
 
-    /// Set strict mode (fail on undefined variables)
+    /// Set strict mode (Pack.yaml `engine.strict`)
+    ///
+    /// `true` keeps the chainable default, `false` selects
+    /// [`UndefinedMode::Lenient`]. Use [`EngineBuilder::undefined_behavior`]
+    /// to fail on undefined values.
     pub fn strict(mut self, strict: bool) -> Self {
-        self.strict_mode = strict;
+        self.undefined_mode = UndefinedMode::from_strict(strict);
+        self
+    }
+
+    /// Select how undefined values are treated
+    pub fn undefined_behavior(mut self, mode: UndefinedMode) -> Self {
+        self.undefined_mode = mode;
+        self
+    }
+
+    /// Stop rendering at the first failing template
+    ///
+    /// By default every template is rendered and each failure is collected
+    /// into the [`RenderReport`].
+    pub fn fail_fast(mut self, fail_fast: bool) -> Self {
+        self.fail_fast = fail_fast;
         self
     }
 
@@ -90,7 +155,8 @@ impl EngineBuilder {
     /// Build the engine
     pub fn build(self) -> Engine {
         Engine {
-            strict_mode: self.strict_mode,
+            undefined_mode: self.undefined_mode,
+            fail_fast: self.fail_fast,
             secret_state: self.secret_state,
             lookup_state: self.lookup_state,
         }
@@ -99,7 +165,8 @@ impl EngineBuilder {
 
 /// The template engine
 pub struct Engine {
-    strict_mode: bool,
+    undefined_mode: UndefinedMode,
+    fail_fast: bool,
     secret_state: Option<crate::secrets::SecretFunctionState>,
     lookup_state: Option<crate::cluster_reader::LookupState>,
 }
@@ -115,7 +182,8 @@ impl Engine {
     /// For clearer code, prefer `Engine::strict()` or `Engine::lenient()`.
     pub fn new(strict_mode: bool) -> Self {
         Self {
-            strict_mode,
+            undefined_mode: UndefinedMode::from_strict(strict_mode),
+            fail_fast: false,
             secret_state: None,
             lookup_state: None,
         }
@@ -128,7 +196,8 @@ impl Engine {
     #[must_use]
     pub fn strict() -> Self {
         Self {
-            strict_mode: true,
+            undefined_mode: UndefinedMode::ChainableNone,
+            fail_fast: false,
             secret_state: None,
             lookup_state: None,
         }
@@ -141,7 +210,8 @@ impl Engine {
     #[must_use]
     pub fn lenient() -> Self {
         Self {
-            strict_mode: false,
+            undefined_mode: UndefinedMode::Lenient,
+            fail_fast: false,
             secret_state: None,
             lookup_state: None,
         }
@@ -153,6 +223,11 @@ impl Engine {
         EngineBuilder::new()
     }
 
+    /// Whether rendering stops at the first failing template
+    pub fn is_fail_fast(&self) -> bool {
+        self.fail_fast
+    }
+
     /// Get a reference to the secret state (if any)
     pub fn secret_state(&self) -> Option<&crate::secrets::SecretFunctionState> {
         self.secret_state.as_ref()
@@ -163,14 +238,10 @@ impl Engine {
         let mut env = Environment::new();
 
         // Configure behavior
-        // Use Chainable mode by default - allows accessing properties on undefined values
+        // Chainable by default - allows accessing properties on undefined values
         // (returns undefined instead of error), matching Helm's Go template behavior.
         // This is essential for converted charts where values may be optional.
-        if self.strict_mode {
-            env.set_undefined_behavior(minijinja::UndefinedBehavior::Chainable);
-        } else {
-            env.set_undefined_behavior(minijinja::UndefinedBehavior::Lenient);
-        }
+        env.set_undefined_behavior(self.undefined_mode.behavior());
 
         // Register custom filters
         env.add_filter("toyaml", filters::toyaml);
@@ -178,8 +249,15 @@ impl Engine {
         env.add_filter("tojson_pretty", filters::tojson_pretty);
         env.add_filter("fromjson", filters::fromjson);
         env.add_filter("fromyaml", filters::fromyaml);
+        env.add_filter("totoml", filters::totoml);
+        env.add_filter("fromtoml", filters::fromtoml);
         env.add_filter("b64encode", filters::b64encode);
         env.add_filter("b64decode", filters::b64decode);
+        env.add_filter("b32encode", filters::b32encode);
+        env.add_filter("b32decode", filters::b32decode);
+        env.add_filter("urlencode", filters::urlencode);
+        env.add_filter("urlquery", filters::urlencode);
+        env.add_filter("urldecode", filters::urldecode);
         env.add_filter("quote", filters::quote);
         env.add_filter("squote", filters::squote);
         env.add_filter("nindent", filters::nindent);
@@ -189,6 +267,7 @@ impl Engine {
         env.add_filter("haskey", filters::haskey);
         env.add_filter("keys", filters::keys);
         env.add_filter("merge", filters::merge);
+        env.add_filter("mergeoverwrite", filters::mergeoverwrite);
         env.add_filter("sha256", filters::sha256sum);
         env.add_filter("trunc", filters::trunc);
         env.add_filter("trimprefix", filters::trimprefix);
@@ -197,6 +276,8 @@ impl Engine {
         env.add_filter("kebabcase", filters::kebabcase);
         env.add_filter("tostrings", filters::tostrings);
         env.add_filter("semver_match", filters::semver_match);
+        env.add_filter("semver_sort", filters::semver_sort);
+        env.add_filter("semver_max", filters::semver_max);
         env.add_filter("int", filters::int);
         env.add_filter("float", filters::float);
         env.add_filter("abs", filters::abs);
@@ -225,9 +306,23 @@ impl Engine {
         env.add_filter("without", filters::without);
         env.add_filter("compact", filters::compact);
 
+        // Date filters: `value | date(format)` reads like Helm's `now | date "..."`
+        env.add_filter("date", |value: minijinja::Value, format: String| {
+            functions::date(format, value)
+        });
+        env.add_filter(
+            "date_in_zone",
+            |value: minijinja::Value, format: String, zone: String| {
+                functions::date_in_zone(format, value, zone)
+            },
+        );
+        env.add_filter("duration", functions::duration);
+
         // Math filters
         env.add_filter("floor", filters::floor);
         env.add_filter("ceil", filters::ceil);
+        env.add_filter("round", filters::round);
+        env.add_filter("divf", filters::divf);
 
         // Crypto filters
         env.add_filter("sha1", filters::sha1sum);
@@ -245,6 +340,7 @@ impl Engine {
 
         // Register global functions
         env.add_function("fail", functions::fail);
+        env.add_function("must_have", functions::must_have);
         env.add_function("dict", functions::dict);
         env.add_function("list", functions::list);
         env.add_function("get", functions::get);
@@ -258,12 +354,16 @@ impl Engine {
         env.add_function("toint", functions::toint);
         env.add_function("tofloat", functions::tofloat);
         env.add_function("now", functions::now);
+        env.add_function("date", functions::date);
+        env.add_function("date_in_zone", functions::date_in_zone);
+        env.add_function("duration", functions::duration);
         env.add_function("printf", functions::printf);
         env.add_function("tpl", functions::tpl);
         env.add_function("tpl_ctx", functions::tpl_ctx);
         env.add_function("lookup", functions::lookup);
         env.add_function("fromjson", filters::fromjson);
         env.add_function("fromyaml", filters::fromyaml);
+        env.add_function("fromtoml", filters::fromtoml);
 
         // Register generate_secret function if secret state is available
         if let Some(ref secret_state) = self.secret_state {
@@ -321,7 +421,7 @@ impl Engine {
             values => &context.values,
             release => &context.release,
             pack => &context.pack,
-            capabilities => &context.capabilities,
+            capabilities => create_capabilities_value(&context.capabilities),
             template => &context.template,
         };
 
@@ -377,6 +477,21 @@ impl Engine {
         &self,
         pack: &LoadedPack,
         context: &TemplateContext,
+    ) -> RenderResultWithReport {
+        self.render_pack_with_libraries(pack, context, &[])
+    }
+
+    /// Render all templates in a pack, with the templates of library packs available
+    ///
+    /// Each library is given with the name it is depended on under, and its
+    /// templates are registered as `<name>/<path>` so the pack can import
+    /// their macros, e.g. `{% import "common/_helpers.tpl" as common %}`.
+    /// Library templates are never rendered themselves.
+    pub fn render_pack_with_libraries(
+        &self,
+        pack: &LoadedPack,
+        context: &TemplateContext,
+        libraries: &[(&str, &LoadedPack)],
     ) -> RenderResultWithReport {
         let mut report = RenderReport::new();
         let mut manifests = IndexMap::new();
@@ -432,11 +547,61 @@ impl Engine {
                         Some(&context.values),
                     ),
                 );
+                if self.fail_fast {
+                    return RenderResultWithReport {
+                        manifests,
+                        notes,
+                        report,
+                    };
+                }
             }
             // Store after attempting to add (content is still valid)
             template_sources.insert(template_name, content);
         }
 
+        // Register library templates under the name of their dependency
+        for (name, library) in libraries {
+            let files = match library.template_files() {
+                Ok(files) => files,
+                Err(e) => {
+                    report.add_error(
+                        format!("<library:{}>", name),
+                        TemplateError::simple(format!("Failed to list templates: {}", e)),
+                    );
+                    continue;
+                }
+            };
+            for file_path in &files {
+                let rel_path = file_path
+                    .strip_prefix(&library.templates_dir)
+                    .unwrap_or(file_path);
+                let template_name = format!("{}/{}", name, rel_path.to_string_lossy());
+
+                let content = match std::fs::read_to_string(file_path) {
+                    Ok(c) => c,
+                    Err(e) => {
+                        report.add_error(
+                            template_name,
+                            TemplateError::simple(format!("Failed to read template: {}", e)),
+                        );
+                        continue;
+                    }
+                };
+                if let Err(e) = env.add_template_owned(template_name.clone(), content.clone()) {
+                    report.add_error(
+                        template_name.clone(),
+                        TemplateError::from_minijinja_enhanced(
+                            e,
+                            &template_name,
+                            &content,
+                            Some(&context.values),
+                        ),
+                    );
+                }
+                template_sources.insert(template_name, content);
+            }
+        }
+
         // Add context as globals so imported macros can access them
         // This is necessary because MiniJinja macros don't automatically get the render context
         env.add_global("values", minijinja::Value::from_serialize(&context.values));
@@ -447,7 +612,7 @@ impl Engine {
         env.add_global("pack", minijinja::Value::from_serialize(&context.pack));
         env.add_global(
             "capabilities",
-            minijinja::Value::from_serialize(&context.capabilities),
+            create_capabilities_value(&context.capabilities),
         );
         env.add_global(
             "template",
@@ -456,9 +621,16 @@ impl Engine {
 
         // Add Files API - provides sandboxed access to pack files from templates
         // Usage: {{ files.get("config/app.conf") }}, files.exists(), files.glob(), files.lines()
-        match SandboxedFileProvider::new(&pack.root) {
-            Ok(provider) => {
-                env.add_global("files", create_files_value_from_provider(provider));
+        // Packs loaded from an archive are served straight from the archive
+        let files_value = match &pack.archive {
+            Some(archive) => {
+                ArchiveFileProvider::open(archive).map(create_files_value_from_provider)
+            }
+            None => SandboxedFileProvider::new(&pack.root).map(create_files_value_from_provider),
+        };
+        match files_value {
+            Ok(files) => {
+                env.add_global("files", files);
             }
             Err(e) => {
                 report.add_warning(
@@ -476,7 +648,7 @@ impl Engine {
             values => &context.values,
             release => &context.release,
             pack => &context.pack,
-            capabilities => &context.capabilities,
+            capabilities => create_capabilities_value(&context.capabilities),
             template => &context.template,
         };
 
@@ -541,6 +713,9 @@ impl Engine {
                             Some(&context.values),
                         ),
                     );
+                    if self.fail_fast {
+                        break;
+                    }
                 }
             }
         }
@@ -556,6 +731,7 @@ impl Engine {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::TemplateErrorKind;
     use semver::Version;
     use sherpack_core::{PackMetadata, ReleaseInfo, Values};
 
@@ -612,6 +788,125 @@ replicas: 3
         assert!(result.contains("tag:"));
     }
 
+    fn render_error(template: &str) -> TemplateError {
+        let engine = Engine::new(true);
+        match engine.render_string(template, &create_test_context(), "test.yaml") {
+            Err(EngineError::Template(err)) => *err,
+            other => panic!("expected a template error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_required_names_values_path() {
+        let err = render_error("a: 1\npassword: {{ values.db.password | required }}");
+
+        assert_eq!(err.kind(), TemplateErrorKind::MissingValue);
+        assert_eq!(
+            err.message,
+            "required value `values.db.password` is not set (in test.yaml:2)"
+        );
+        assert_eq!(err.line(), Some(2));
+        assert_eq!(
+            err.suggestion.as_deref(),
+            Some("Set `db.password` in values.yaml or pass `--set db.password=<value>`")
+        );
+    }
+
+    #[test]
+    fn test_required_keeps_custom_message() {
+        let err = render_error(r#"{{ values.db.password | required("set a DB password") }}"#);
+        assert_eq!(err.message, "set a DB password (in test.yaml:1)");
+        assert!(
+            err.suggestion
+                .unwrap()
+                .contains("--set db.password=<value>")
+        );
+
+        // No plain values path at the access site: no suggestion to make up
+        let err = render_error(r#"{{ (values.db or {}).password | required }}"#);
+        assert_eq!(err.kind(), TemplateErrorKind::MissingValue);
+        assert_eq!(err.message, "required value is missing (in test.yaml:1)");
+        assert!(err.suggestion.is_none());
+    }
+
+    #[test]
+    fn test_must_have() {
+        let engine = Engine::new(true);
+        let result = engine
+            .render_string(
+                r#"{{ must_have("image.tag") }} {{ must_have("values.replicas") }}"#,
+                &create_test_context(),
+                "test.yaml",
+            )
+            .unwrap();
+        assert_eq!(result, "1.25 3");
+
+        let err = render_error(r#"{{ must_have("image.digest") }}"#);
+        assert_eq!(err.kind(), TemplateErrorKind::MissingValue);
+        assert_eq!(
+            err.message,
+            "required value `values.image.digest` is not set (in test.yaml:1)"
+        );
+        assert!(
+            err.suggestion
+                .unwrap()
+                .contains("--set image.digest=<value>")
+        );
+
+        let err = render_error(r#"{{ must_have("ingress.host", "ingress needs a host") }}"#);
+        assert_eq!(err.message, "ingress needs a host (in test.yaml:1)");
+        assert!(
+            err.suggestion
+                .unwrap()
+                .contains("--set ingress.host=<value>")
+        );
+    }
+
+    #[test]
+    fn test_render_encoding_filters() {
+        let engine = Engine::new(true);
+        let ctx = create_test_context();
+
+        let template = r#"path: {{ "a/b c" | urlencode }}
+query: {{ {"q": "a b", "page": 2} | urlquery }}
+back: {{ "a%2Fb" | urldecode }}
+b32: {{ "foo" | b32encode | b32decode }}"#;
+        let result = engine.render_string(template, &ctx, "test.yaml").unwrap();
+
+        assert_eq!(
+            result,
+            "path: a%2Fb%20c\nquery: page=2&q=a%20b\nback: a/b\nb32: foo"
+        );
+    }
+
+    #[test]
+    fn test_render_date_filters() {
+        let engine = Engine::new(true);
+        let ctx = create_test_context();
+
+        let template = r#"day: {{ 86400 | date("2006-01-02") }}
+zone: {{ 0 | date_in_zone("15:04", "Europe/Paris") }}
+ttl: {{ 5400 | duration }}
+fn: {{ date("%Y", 0) }}"#;
+        let result = engine.render_string(template, &ctx, "test.yaml").unwrap();
+
+        assert_eq!(
+            result,
+            "day: 1970-01-02\nzone: 01:00\nttl: 1h30m0s\nfn: 1970"
+        );
+    }
+
+    #[test]
+    fn test_render_numeric_filters() {
+        let engine = Engine::new(true);
+        let ctx = create_test_context();
+
+        let template = "replicas: {{ (values.replicas | divf(2)) | ceil }}\nratio: {{ 2 | divf(3) | round(2) }}";
+        let result = engine.render_string(template, &ctx, "test.yaml").unwrap();
+
+        assert_eq!(result, "replicas: 2\nratio: 0.67");
+    }
+
     #[test]
     fn test_render_release_info() {
         let engine = Engine::new(true);
@@ -640,6 +935,48 @@ replicas: 3
         assert_eq!(output.trim(), "value:");
     }
 
+    #[test]
+    fn test_undefined_mode_missing_nested_key() {
+        let ctx = create_test_context();
+        let render = |mode| {
+            Engine::builder()
+                .undefined_behavior(mode)
+                .build()
+                .render_string("a: {{ values.missing.key }}", &ctx, "test.yaml")
+        };
+
+        assert!(render(UndefinedMode::Strict).is_err());
+        assert!(render(UndefinedMode::Lenient).is_err());
+        assert_eq!(render(UndefinedMode::ChainableNone).unwrap().trim(), "a:");
+        assert_eq!(
+            Engine::builder().build().undefined_mode,
+            UndefinedMode::ChainableNone
+        );
+    }
+
+    #[test]
+    fn test_undefined_mode_missing_key() {
+        let ctx = create_test_context();
+        let render = |mode| {
+            Engine::builder()
+                .undefined_behavior(mode)
+                .build()
+                .render_string("a: {{ values.missing }}", &ctx, "test.yaml")
+        };
+
+        assert!(render(UndefinedMode::Strict).is_err());
+        assert_eq!(render(UndefinedMode::Lenient).unwrap().trim(), "a:");
+        assert_eq!(render(UndefinedMode::ChainableNone).unwrap().trim(), "a:");
+
+        // Defaults still apply in strict mode
+        let strict = Engine::builder()
+            .undefined_behavior(UndefinedMode::Strict)
+            .build();
+        let result =
+            strict.render_string("a: {{ values.missing | default('x') }}", &ctx, "test.yaml");
+        assert_eq!(result.unwrap().trim(), "a: x");
+    }
+
     #[test]
     fn test_chainable_typo_returns_empty() {
         // With UndefinedBehavior::Chainable, even top-level undefined vars return empty
@@ -9,8 +9,8 @@ use thiserror::Error;
 
 use crate::suggestions::{
     AVAILABLE_FILTERS, extract_filter_name, extract_function_name, extract_variable_name,
-    suggest_iteration_fix, suggest_undefined_variable, suggest_unknown_filter,
-    suggest_unknown_function,
+    suggest_iteration_fix, suggest_missing_value, suggest_undefined_variable,
+    suggest_unknown_filter, suggest_unknown_function,
 };
 
 /// Main engine error type
@@ -33,6 +33,9 @@ pub enum EngineError {
 
     #[error("Multiple template errors occurred")]
     MultipleErrors(Box<RenderReport>),
+
+    #[error("Post-renderer '{command}' failed: {message}")]
+    PostRender { command: String, message: String },
 }
 
 impl From<TemplateError> for EngineError {
@@ -54,6 +57,7 @@ pub enum TemplateErrorKind {
     TypeError,
     InvalidOperation,
     YamlParseError,
+    MissingValue,
     Other,
 }
 
@@ -68,6 +72,7 @@ impl TemplateErrorKind {
             Self::TypeError => "type",
             Self::InvalidOperation => "invalid_operation",
             Self::YamlParseError => "yaml_parse",
+            Self::MissingValue => "missing_value",
             Self::Other => "render",
         }
     }
@@ -113,6 +118,10 @@ impl TemplateError {
         // Calculate source span from line number
         let span = line.and_then(|line_num| calculate_span(template_source, line_num));
 
+        if kind == TemplateErrorKind::MissingValue {
+            return Self::missing_value(&err, message, template_name, template_source, span);
+        }
+
         // Generate suggestion based on error kind
         let suggestion = generate_suggestion(&err, &kind, None);
 
@@ -139,6 +148,10 @@ impl TemplateError {
         // Calculate source span from line number
         let span = line.and_then(|line_num| calculate_span(template_source, line_num));
 
+        if kind == TemplateErrorKind::MissingValue {
+            return Self::missing_value(&err, message, template_name, template_source, span);
+        }
+
         // Generate context-aware suggestion
         let suggestion = generate_suggestion(&err, &kind, values);
 
@@ -152,6 +165,47 @@ impl TemplateError {
         }
     }
 
+    /// Build the error for a value rejected by `required` or `must_have`
+    ///
+    /// The values path comes from `must_have`'s argument, or else from the
+    /// expression piped into `required` at the failing site.
+    fn missing_value(
+        err: &minijinja::Error,
+        message: String,
+        template_name: &str,
+        template_source: &str,
+        span: Option<SourceSpan>,
+    ) -> Self {
+        let marker = missing_value_marker(err);
+        let custom_message = marker.is_some_and(|marker| marker.custom_message);
+        let path = marker.and_then(|marker| marker.path.clone()).or_else(|| {
+            let range = err.range()?;
+            piped_values_path(template_source.get(..range.start)?)
+        });
+
+        let (message, suggestion) = match path {
+            Some(path) if custom_message => (message, Some(suggest_missing_value(&path))),
+            Some(path) => {
+                // Keep MiniJinja's " (in template:line)" like other messages
+                let location = message.rfind(" (in ").map_or("", |i| &message[i..]);
+                (
+                    format!("required value `values.{}` is not set{}", path, location),
+                    Some(suggest_missing_value(&path)),
+                )
+            }
+            None => (message, None),
+        };
+
+        Self {
+            message,
+            kind: TemplateErrorKind::MissingValue,
+            src: NamedSource::new(template_name, template_source.to_string()),
+            span,
+            suggestion,
+            context: None,
+        }
+    }
+
     /// Create a simple error without source mapping
     pub fn simple(message: impl Into<String>) -> Self {
         Self {
@@ -164,6 +218,13 @@ impl TemplateError {
         }
     }
 
+    /// Line (1-based) where the error occurred, if known
+    pub fn line(&self) -> Option<usize> {
+        let offset = self.span?.offset();
+        let before = self.src.inner().get(..offset)?;
+        Some(before.matches('\n').count() + 1)
+    }
+
     /// Add context information
     pub fn with_context(mut self, context: impl Into<String>) -> Self {
         self.context = Some(context.into());
@@ -182,6 +243,37 @@ impl TemplateError {
     }
 }
 
+/// Source attached to the errors of `required` and `must_have`
+///
+/// Lets [`TemplateError`] tell a missing value apart from other invalid
+/// operations and point at the values path to set.
+#[derive(Debug, Error)]
+#[error("required value is not set")]
+pub(crate) struct MissingValue {
+    /// Dotted path below `values`, when the caller knows it
+    pub path: Option<String>,
+    /// Whether the template supplied its own error message
+    pub custom_message: bool,
+}
+
+fn missing_value_marker(err: &minijinja::Error) -> Option<&MissingValue> {
+    std::error::Error::source(err)?.downcast_ref::<MissingValue>()
+}
+
+/// Find the `values.` path piped into a filter, given the source before it
+///
+/// `"x: {{ values.db.password | "` gives `db.password`. Anything more complex
+/// than a plain dotted path (calls, subscripts, earlier filters) gives `None`.
+fn piped_values_path(before_filter: &str) -> Option<String> {
+    let before_pipe = before_filter.trim_end().strip_suffix('|')?.trim_end();
+    let start = before_pipe
+        .rfind(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+        .map_or(0, |i| i + 1);
+    let path = before_pipe[start..].strip_prefix("values.")?;
+
+    (!path.is_empty() && path.split('.').all(|part| !part.is_empty())).then(|| path.to_string())
+}
+
 /// Categorize a MiniJinja error into our error kinds
 fn categorize_minijinja_error(err: &minijinja::Error) -> (TemplateErrorKind, String) {
     let msg = err.to_string();
@@ -191,6 +283,7 @@ fn categorize_minijinja_error(err: &minijinja::Error) -> (TemplateErrorKind, Str
     let detailed = format!("{:#}", err);
 
     let kind = match err.kind() {
+        _ if missing_value_marker(err).is_some() => TemplateErrorKind::MissingValue,
         minijinja::ErrorKind::UndefinedError => TemplateErrorKind::UndefinedVariable,
         minijinja::ErrorKind::UnknownFilter => TemplateErrorKind::UnknownFilter,
         minijinja::ErrorKind::UnknownFunction => TemplateErrorKind::UnknownFunction,
@@ -691,6 +784,32 @@ mod tests {
         assert!(report.successful_templates.is_empty());
     }
 
+    #[test]
+    fn test_piped_values_path() {
+        assert_eq!(
+            piped_values_path("x: {{ values.db.password | ").as_deref(),
+            Some("db.password")
+        );
+        assert_eq!(piped_values_path("{{values.port|").as_deref(), Some("port"));
+        assert_eq!(piped_values_path("{{ release.name | "), None);
+        assert_eq!(piped_values_path("{{ values.a | default(x) | "), None);
+        assert_eq!(piped_values_path("{{ values. | "), None);
+        assert_eq!(piped_values_path("{{ required("), None);
+    }
+
+    #[test]
+    fn test_template_error_line() {
+        let source = "a: 1\nb: {{ oops }}\nc: 3\n";
+        let error = TemplateError {
+            span: calculate_span(source, 2),
+            src: NamedSource::new("t.yaml", source.to_string()),
+            ..TemplateError::simple("undefined")
+        };
+        assert_eq!(error.line(), Some(2));
+
+        assert_eq!(TemplateError::simple("no source").line(), None);
+    }
+
     #[test]
     fn test_render_report_add_error() {
         let mut report = RenderReport::new();
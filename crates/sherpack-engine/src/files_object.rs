@@ -22,15 +22,24 @@
 //!   {{ file.name }}: {{ file.content | b64encode }}
 //! {% endfor %}
 //!
+//! {# Matched files also index by path #}
+//! {{ files.glob("config/*.yaml")["config/app.yaml"] }}
+//!
+//! {# ConfigMap / Secret data from files (keyed by file name) #}
+//! data:
+//!   {{ files.as_config("config/*.yaml") | toyaml | indent(2) }}
+//!
 //! {# Read file lines #}
 //! {% for line in files.lines("hosts.txt") %}
 //!   - {{ line }}
 //! {% endfor %}
 //! ```
 
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
-use minijinja::value::{Object, ObjectRepr, Value};
+use base64::Engine as _;
+use minijinja::value::{Enumerator, Object, ObjectRepr, Value};
 use minijinja::{Error, ErrorKind};
 use sherpack_core::files::{FileProvider, Files};
 
@@ -64,6 +73,29 @@ impl FilesObject {
     }
 }
 
+impl FilesObject {
+    fn glob(&self, pattern: &str) -> Result<GlobObject, Error> {
+        let entries = self
+            .files
+            .glob(pattern)
+            .map_err(|e| Error::new(ErrorKind::InvalidOperation, e.to_string()))?;
+
+        Ok(GlobObject {
+            entries: entries
+                .into_iter()
+                .map(|entry| {
+                    Arc::new(FileEntryObject {
+                        path: entry.path,
+                        name: entry.name,
+                        content: entry.content,
+                        size: entry.size,
+                    })
+                })
+                .collect(),
+        })
+    }
+}
+
 impl Object for FilesObject {
     fn repr(self: &Arc<Self>) -> ObjectRepr {
         ObjectRepr::Plain
@@ -102,24 +134,17 @@ impl Object for FilesObject {
 
             "glob" => {
                 let pattern = get_path_arg(args, "glob")?;
-                match self.files.glob(&pattern) {
-                    Ok(entries) => {
-                        // Convert to MiniJinja-friendly format
-                        let values: Vec<Value> = entries
-                            .into_iter()
-                            .map(|entry| {
-                                Value::from_object(FileEntryObject {
-                                    path: entry.path,
-                                    name: entry.name,
-                                    content: entry.content,
-                                    size: entry.size,
-                                })
-                            })
-                            .collect();
-                        Ok(Value::from(values))
-                    }
-                    Err(e) => Err(Error::new(ErrorKind::InvalidOperation, e.to_string())),
-                }
+                Ok(Value::from_object(self.glob(&pattern)?))
+            }
+
+            "as_config" => {
+                let pattern = get_path_arg(args, "as_config")?;
+                Ok(self.glob(&pattern)?.as_config())
+            }
+
+            "as_secrets" => {
+                let pattern = get_path_arg(args, "as_secrets")?;
+                Ok(self.glob(&pattern)?.as_secrets())
             }
 
             "lines" => {
@@ -133,7 +158,7 @@ impl Object for FilesObject {
             _ => Err(Error::new(
                 ErrorKind::UnknownMethod,
                 format!(
-                    "files object has no method '{}'. Available methods: get, get_bytes, exists, glob, lines",
+                    "files object has no method '{}'. Available methods: get, get_bytes, exists, glob, lines, as_config, as_secrets",
                     method
                 ),
             )),
@@ -154,6 +179,80 @@ fn get_path_arg(args: &[Value], method_name: &str) -> Result<String, Error> {
         })
 }
 
+/// Files matched by `files.glob()`
+///
+/// Iterates as a list of file entries (sorted by path) and can be indexed by
+/// path to get a file's content, so it also works as a path→content map.
+#[derive(Debug)]
+struct GlobObject {
+    entries: Vec<Arc<FileEntryObject>>,
+}
+
+impl GlobObject {
+    /// Map of file name to content, ready for a ConfigMap's `data`
+    fn as_config(&self) -> Value {
+        self.by_name(|entry| Value::from(entry.content.clone()))
+    }
+
+    /// Map of file name to base64 content, ready for a Secret's `data`
+    fn as_secrets(&self) -> Value {
+        self.by_name(|entry| {
+            Value::from(base64::engine::general_purpose::STANDARD.encode(&entry.content))
+        })
+    }
+
+    fn by_name(&self, value: impl Fn(&FileEntryObject) -> Value) -> Value {
+        self.entries
+            .iter()
+            .map(|entry| (entry.name.clone(), value(entry)))
+            .collect::<BTreeMap<_, _>>()
+            .into()
+    }
+}
+
+impl Object for GlobObject {
+    fn repr(self: &Arc<Self>) -> ObjectRepr {
+        ObjectRepr::Seq
+    }
+
+    fn get_value(self: &Arc<Self>, key: &Value) -> Option<Value> {
+        match key.as_str() {
+            Some(path) => self
+                .entries
+                .iter()
+                .find(|entry| entry.path == path)
+                .map(|entry| Value::from(entry.content.clone())),
+            None => self
+                .entries
+                .get(key.as_usize()?)
+                .map(|entry| Value::from_dyn_object(entry.clone())),
+        }
+    }
+
+    fn enumerate(self: &Arc<Self>) -> Enumerator {
+        Enumerator::Seq(self.entries.len())
+    }
+
+    fn call_method(
+        self: &Arc<Self>,
+        _state: &minijinja::State,
+        method: &str,
+        _args: &[Value],
+    ) -> Result<Value, Error> {
+        match method {
+            "as_config" => Ok(self.as_config()),
+            "as_secrets" => Ok(self.as_secrets()),
+            _ => Err(Error::new(
+                ErrorKind::UnknownMethod,
+                format!(
+                    "glob result has no method '{}'. Available methods: as_config, as_secrets",
+                    method
+                ),
+            )),
+        }
+    }
+}
+
 /// MiniJinja Object for file entries returned by glob
 #[derive(Debug)]
 struct FileEntryObject {
@@ -323,4 +422,51 @@ mod tests {
         let result = env.render_str(template, ()).unwrap();
         assert_eq!(result, "key: value\nother: data");
     }
+
+    #[test]
+    fn test_files_glob_by_path() {
+        let (env, _) = create_test_env();
+
+        let template = r#"{{ files.glob("config/*.yaml")["config/db.yaml"] }}|{{ files.glob("config/*.yaml") | length }}"#;
+        let result = env.render_str(template, ()).unwrap();
+
+        assert_eq!(result, "host: localhost|2");
+    }
+
+    #[test]
+    fn test_files_as_config_and_secrets() {
+        let (env, _) = create_test_env();
+
+        let config = env
+            .render_str(r#"{{ files.as_config("config/*.yaml")["db.yaml"] }}"#, ())
+            .unwrap();
+        assert_eq!(config, "host: localhost");
+
+        let secrets = env
+            .render_str(
+                r#"{% for name, data in files.glob("config/db.yaml").as_secrets() | items %}{{ name }}={{ data }}{% endfor %}"#,
+                (),
+            )
+            .unwrap();
+        assert_eq!(secrets, "db.yaml=aG9zdDogbG9jYWxob3N0");
+    }
+
+    #[test]
+    fn test_files_glob_escaping_sandbox() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("pack")).unwrap();
+        std::fs::write(dir.path().join("secret.conf"), "secret").unwrap();
+
+        let files = Files::for_pack(dir.path().join("pack")).unwrap();
+        let mut env = Environment::new();
+        env.add_global("files", create_files_value(files));
+
+        for template in [
+            r#"{{ files.glob("../*.conf") | length }}"#,
+            r#"{{ files.as_secrets("../*.conf") }}"#,
+        ] {
+            let err = env.render_str(template, ()).unwrap_err();
+            assert!(err.to_string().contains("sandbox violation"), "{}", err);
+        }
+    }
 }
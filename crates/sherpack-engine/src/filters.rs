@@ -3,9 +3,14 @@
 //! These filters extend MiniJinja with Helm-compatible functionality.
 
 use base64::Engine as _;
-use minijinja::{Error, ErrorKind, Value, value::ValueKind};
+use minijinja::{
+    Error, ErrorKind, Value,
+    value::{Rest, ValueKind},
+};
 use semver::{Version, VersionReq};
 
+use crate::error::MissingValue;
+
 /// Convert a value to YAML format
 ///
 /// Usage: {{ values.config | toyaml }}
@@ -81,6 +86,86 @@ pub fn fromyaml(value: String) -> Result<Value, Error> {
     Ok(Value::from_serialize(json_value))
 }
 
+/// Convert a map to TOML format
+///
+/// Usage: {{ values.traefik | totoml | nindent(4) }}
+///
+/// Fails on values TOML cannot represent: a top level that is not a map, and
+/// nulls anywhere in the structure.
+pub fn totoml(value: Value) -> Result<String, Error> {
+    let json_value: serde_json::Value = serde_json::to_value(&value)
+        .map_err(|e| Error::new(ErrorKind::InvalidOperation, e.to_string()))?;
+
+    if !json_value.is_object() {
+        return Err(Error::new(
+            ErrorKind::InvalidOperation,
+            format!("totoml requires a map, got {:?}", value.kind()),
+        ));
+    }
+    if let Some(path) = find_null(&json_value, String::new()) {
+        return Err(Error::new(
+            ErrorKind::InvalidOperation,
+            format!("totoml: null at '{}' cannot be represented in TOML", path),
+        ));
+    }
+
+    let toml = toml::to_string(&json_value)
+        .map_err(|e| Error::new(ErrorKind::InvalidOperation, format!("totoml: {}", e)))?;
+
+    Ok(toml.trim_end().to_string())
+}
+
+/// Path of the first null in a JSON value
+fn find_null(value: &serde_json::Value, path: String) -> Option<String> {
+    match value {
+        serde_json::Value::Null => Some(path),
+        serde_json::Value::Object(map) => map.iter().find_map(|(key, v)| {
+            let child = if path.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", path, key)
+            };
+            find_null(v, child)
+        }),
+        serde_json::Value::Array(items) => items
+            .iter()
+            .enumerate()
+            .find_map(|(i, v)| find_null(v, format!("{}[{}]", path, i))),
+        _ => None,
+    }
+}
+
+/// Parse a TOML document into a value
+///
+/// Usage as filter:    `{{ values.toml_string | fromtoml }}`
+/// Usage as function:  `{{ fromtoml(values.toml_string) }}`
+///
+/// Datetimes become their RFC 3339 strings.
+pub fn fromtoml(value: String) -> Result<Value, Error> {
+    let parsed: toml::Table = toml::from_str(&value).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidOperation,
+            format!("fromtoml: invalid TOML: {}", e),
+        )
+    })?;
+    Ok(toml_to_value(toml::Value::Table(parsed)))
+}
+
+fn toml_to_value(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(s) => Value::from(s),
+        toml::Value::Integer(i) => Value::from(i),
+        toml::Value::Float(f) => Value::from(f),
+        toml::Value::Boolean(b) => Value::from(b),
+        toml::Value::Datetime(dt) => Value::from(dt.to_string()),
+        toml::Value::Array(items) => items.into_iter().map(toml_to_value).collect(),
+        toml::Value::Table(table) => table
+            .into_iter()
+            .map(|(key, v)| (key, toml_to_value(v)))
+            .collect(),
+    }
+}
+
 /// Base64 encode a string
 ///
 /// Usage: {{ secret | b64encode }}
@@ -110,6 +195,106 @@ pub fn b64decode(value: String) -> Result<String, Error> {
     })
 }
 
+/// Base32 encode a string (RFC 4648, padded)
+///
+/// Usage: {{ values.totpSecret | b32encode }}
+#[must_use]
+pub fn b32encode(value: String) -> String {
+    data_encoding::BASE32.encode(value.as_bytes())
+}
+
+/// Base32 decode a string (RFC 4648, padded)
+///
+/// Usage: {{ encoded | b32decode }}
+pub fn b32decode(value: String) -> Result<String, Error> {
+    let decoded = data_encoding::BASE32
+        .decode(value.as_bytes())
+        .map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidOperation,
+                format!("base32 decode error: {}", e),
+            )
+        })?;
+
+    String::from_utf8(decoded).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidOperation,
+            format!("UTF-8 decode error: {}", e),
+        )
+    })
+}
+
+/// Characters left as-is by `urlencode`: the RFC 3986 unreserved set
+const URL_ENCODE_SET: &percent_encoding::AsciiSet = &percent_encoding::NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+fn percent_encode(value: &str) -> String {
+    percent_encoding::utf8_percent_encode(value, URL_ENCODE_SET).to_string()
+}
+
+/// Percent-encode a string, or a map as a query string
+///
+/// Everything but letters, digits and `-._~` is escaped, so the result is
+/// safe in a path segment or a query parameter. Map entries whose value is
+/// none are skipped.
+///
+/// Usage: {{ values.webhook.token | urlencode }}
+/// Usage: {{ dict(q="a b", page=2) | urlencode }}  → page=2&q=a%20b
+pub fn urlencode(value: Value) -> Result<String, Error> {
+    let as_text = |v: &Value| v.as_str().map_or_else(|| v.to_string(), str::to_string);
+
+    match value.kind() {
+        ValueKind::Map => {
+            let mut pairs = Vec::new();
+            for key in value.try_iter()? {
+                let item = value.get_item(&key)?;
+                if item.is_none() || item.is_undefined() {
+                    continue;
+                }
+                pairs.push(format!(
+                    "{}={}",
+                    percent_encode(&as_text(&key)),
+                    percent_encode(&as_text(&item))
+                ));
+            }
+            Ok(pairs.join("&"))
+        }
+        ValueKind::None | ValueKind::Undefined => Ok(String::new()),
+        _ => Ok(percent_encode(&as_text(&value))),
+    }
+}
+
+/// Decode a percent-encoded string
+///
+/// Usage: {{ values.encodedPath | urldecode }}
+pub fn urldecode(value: String) -> Result<String, Error> {
+    let bytes = value.as_bytes();
+    for (i, _) in value.match_indices('%') {
+        let valid = bytes
+            .get(i + 1..i + 3)
+            .is_some_and(|hex| hex.iter().all(u8::is_ascii_hexdigit));
+        if !valid {
+            return Err(Error::new(
+                ErrorKind::InvalidOperation,
+                format!("url decode error: invalid escape at byte {}", i),
+            ));
+        }
+    }
+
+    percent_encoding::percent_decode_str(&value)
+        .decode_utf8()
+        .map(|s| s.into_owned())
+        .map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidOperation,
+                format!("UTF-8 decode error: {}", e),
+            )
+        })
+}
+
 /// Quote a string with double quotes
 ///
 /// Usage: {{ name | quote }}
@@ -192,21 +377,34 @@ pub fn indent(value: String, spaces: usize) -> String {
 
 /// Require a value, fail if undefined or empty
 ///
+/// When the value is piped straight from `values`, the render error names
+/// the path to set.
+///
 /// Usage: {{ values.required_field | required("field is required") }}
 pub fn required(value: Value, message: Option<String>) -> Result<Value, Error> {
-    if value.is_undefined() || value.is_none() {
-        let msg = message.unwrap_or_else(|| "required value is missing".to_string());
-        Err(Error::new(ErrorKind::InvalidOperation, msg))
-    } else if let Some(s) = value.as_str() {
-        if s.is_empty() {
-            let msg = message.unwrap_or_else(|| "required value is empty".to_string());
-            Err(Error::new(ErrorKind::InvalidOperation, msg))
-        } else {
-            Ok(value)
-        }
+    let default_message = if value.is_undefined() || value.is_none() {
+        "required value is missing"
+    } else if value.as_str().is_some_and(str::is_empty) {
+        "required value is empty"
     } else {
-        Ok(value)
-    }
+        return Ok(value);
+    };
+
+    Err(missing_value_error(message, default_message, None))
+}
+
+/// Error for a missing required value, marked for [`crate::TemplateError`]
+pub(crate) fn missing_value_error(
+    message: Option<String>,
+    default_message: &str,
+    path: Option<String>,
+) -> Error {
+    let marker = MissingValue {
+        path,
+        custom_message: message.is_some(),
+    };
+    let message = message.unwrap_or_else(|| default_message.to_string());
+    Error::new(ErrorKind::InvalidOperation, message).with_source(marker)
 }
 
 /// Check if a value is empty
@@ -275,34 +473,56 @@ pub fn keys(value: Value) -> Result<Vec<String>, Error> {
     }
 }
 
-/// Deep merge two dicts
+/// Deep merge dicts, keeping the values already in the base (Helm's `merge`)
 ///
-/// Usage: {{ dict1 | merge(dict2) }}
-pub fn merge(base: Value, overlay: Value) -> Result<Value, Error> {
-    let mut base_json: serde_json::Value = serde_json::to_value(&base)
-        .map_err(|e| Error::new(ErrorKind::InvalidOperation, e.to_string()))?;
-    let overlay_json: serde_json::Value = serde_json::to_value(&overlay)
-        .map_err(|e| Error::new(ErrorKind::InvalidOperation, e.to_string()))?;
+/// A key from an overlay is only used when the base lacks it or holds null.
+/// Nested dicts are merged key by key.
+///
+/// Usage: {{ values.labels | merge(defaultLabels) }}
+pub fn merge(base: Value, overlays: Rest<Value>) -> Result<Value, Error> {
+    merge_all(base, &overlays, false)
+}
+
+/// Deep merge dicts, letting each overlay win (Helm's `mergeOverwrite`)
+///
+/// Null values in an overlay never replace a base value.
+///
+/// Usage: {{ defaults | mergeoverwrite(values.overrides) }}
+pub fn mergeoverwrite(base: Value, overlays: Rest<Value>) -> Result<Value, Error> {
+    merge_all(base, &overlays, true)
+}
+
+fn merge_all(base: Value, overlays: &[Value], overwrite: bool) -> Result<Value, Error> {
+    let to_json = |value: &Value| {
+        serde_json::to_value(value)
+            .map_err(|e| Error::new(ErrorKind::InvalidOperation, e.to_string()))
+    };
 
-    deep_merge_json(&mut base_json, &overlay_json);
+    let mut base_json = to_json(&base)?;
+    for overlay in overlays {
+        deep_merge_json(&mut base_json, &to_json(overlay)?, overwrite);
+    }
 
     Ok(Value::from_serialize(&base_json))
 }
 
-fn deep_merge_json(base: &mut serde_json::Value, overlay: &serde_json::Value) {
+fn deep_merge_json(base: &mut serde_json::Value, overlay: &serde_json::Value, overwrite: bool) {
     match (base, overlay) {
         (serde_json::Value::Object(base_map), serde_json::Value::Object(overlay_map)) => {
             for (key, overlay_value) in overlay_map {
                 match base_map.get_mut(key) {
-                    Some(base_value) => deep_merge_json(base_value, overlay_value),
+                    Some(base_value) => deep_merge_json(base_value, overlay_value, overwrite),
                     None => {
                         base_map.insert(key.clone(), overlay_value.clone());
                     }
                 }
             }
         }
+        (_, serde_json::Value::Null) => {}
         (base, overlay) => {
-            *base = overlay.clone();
+            if overwrite || base.is_null() {
+                *base = overlay.clone();
+            }
         }
     }
 }
@@ -509,11 +729,16 @@ pub fn semver_match(version: Value, constraint: String) -> Result<bool, Error> {
         }
     };
 
+    let req = parse_constraint(&constraint)?;
+
+    Ok(req.matches(&parsed_version))
+}
+
+fn parse_constraint(constraint: &str) -> Result<VersionReq, Error> {
     // Clean up the constraint string (handle Kubernetes-style constraints)
     let constraint_clean = constraint.trim_start_matches(|c: char| c.is_whitespace());
 
-    // Parse the constraint
-    let req = VersionReq::parse(constraint_clean)
+    VersionReq::parse(constraint_clean)
         .or_else(|_| {
             // Try to handle Kubernetes-style constraints like ">=1.31.0-0"
             let constraint_base = constraint_clean
@@ -527,9 +752,85 @@ pub fn semver_match(version: Value, constraint: String) -> Result<bool, Error> {
                 ErrorKind::InvalidOperation,
                 format!("Invalid constraint '{}': {}", constraint, e),
             )
-        })?;
+        })
+}
 
-    Ok(req.matches(&parsed_version))
+/// Parse a version for sorting, accepting a leading `v` and `major.minor`
+fn parse_version(version: &str) -> Option<Version> {
+    let clean = version.trim().trim_start_matches('v');
+    Version::parse(clean).ok().or_else(|| {
+        let (major, minor) = clean.split_once('.')?;
+        Some(Version::new(major.parse().ok()?, minor.parse().ok()?, 0))
+    })
+}
+
+/// Pair each version string of a list with its parsed form
+fn versions_of(filter: &str, list: &Value) -> Result<Vec<(String, Option<Version>)>, Error> {
+    if list.kind() != ValueKind::Seq {
+        return Err(Error::new(
+            ErrorKind::InvalidOperation,
+            format!(
+                "{} requires a list of versions, got {:?}",
+                filter,
+                list.kind()
+            ),
+        ));
+    }
+
+    list.try_iter()?
+        .map(|item| {
+            let version = item.as_str().ok_or_else(|| {
+                Error::new(
+                    ErrorKind::InvalidOperation,
+                    format!(
+                        "{}: versions must be strings, got {:?}",
+                        filter,
+                        item.kind()
+                    ),
+                )
+            })?;
+            Ok((version.to_string(), parse_version(version)))
+        })
+        .collect()
+}
+
+/// Sort versions in ascending semver order
+///
+/// Pre-releases sort before their release (`1.0.0-rc.1` < `1.0.0`). Strings
+/// that are not versions are kept, in their original order, after all valid
+/// versions.
+///
+/// Usage: {{ values.tags | semver_sort | last }}
+pub fn semver_sort(list: Value) -> Result<Vec<String>, Error> {
+    let mut versions = versions_of("semver_sort", &list)?;
+    // Stable sort: invalid entries compare equal and keep their order
+    versions.sort_by(|(_, a), (_, b)| match (a, b) {
+        (Some(a), Some(b)) => a.cmp(b),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    });
+    Ok(versions.into_iter().map(|(raw, _)| raw).collect())
+}
+
+/// Return the highest version, optionally among those matching a constraint
+///
+/// Strings that are not versions are ignored. Returns none when nothing
+/// matches.
+///
+/// Usage: {{ values.tags | semver_max("^1.2") }}
+pub fn semver_max(list: Value, constraint: Option<String>) -> Result<Value, Error> {
+    let req = constraint.as_deref().map(parse_constraint).transpose()?;
+
+    let max = versions_of("semver_max", &list)?
+        .into_iter()
+        .filter_map(|(raw, version)| Some((raw, version?)))
+        .filter(|(_, version)| req.as_ref().is_none_or(|req| req.matches(version)))
+        .max_by(|(_, a), (_, b)| a.cmp(b));
+
+    Ok(max
+        .map(|(raw, _)| Value::from(raw))
+        .unwrap_or(Value::from(())))
 }
 
 /// Convert value to integer (truncates floats)
@@ -967,6 +1268,53 @@ pub fn ceil(value: Value) -> Result<i64, Error> {
     }
 }
 
+/// Round half away from zero to `precision` decimal places
+/// {{ 2.5 | round }}  →  3
+/// {{ 1.23456 | round(2) }}  →  1.23
+///
+/// Returns an integer when precision is 0, so the result can be used for
+/// counts such as replicas.
+pub fn round(value: Value, precision: Option<i32>) -> Result<Value, Error> {
+    let f = number("round", &value)?;
+    match precision.unwrap_or(0) {
+        0 if f.is_finite() => Ok(Value::from(f.round() as i64)),
+        0 => Err(Error::new(
+            ErrorKind::InvalidOperation,
+            format!("round cannot convert {} to an integer", f),
+        )),
+        precision => {
+            let factor = 10f64.powi(precision);
+            Ok(Value::from((f * factor).round() / factor))
+        }
+    }
+}
+
+/// Float division (Helm's `divf`)
+/// {{ 7 | divf(2) }}  →  3.5
+pub fn divf(value: Value, divisor: Value) -> Result<f64, Error> {
+    let divisor = number("divf", &divisor)?;
+    if divisor == 0.0 {
+        return Err(Error::new(
+            ErrorKind::InvalidOperation,
+            "divf: division by zero",
+        ));
+    }
+    Ok(number("divf", &value)? / divisor)
+}
+
+/// Numeric value of a filter input, rejecting anything but numbers
+fn number(filter: &str, value: &Value) -> Result<f64, Error> {
+    if value.kind() == ValueKind::Number
+        && let Ok(f) = f64::try_from(value.clone())
+    {
+        return Ok(f);
+    }
+    Err(Error::new(
+        ErrorKind::InvalidOperation,
+        format!("{} requires a number, got {:?}", filter, value.kind()),
+    ))
+}
+
 // =============================================================================
 // Crypto Functions
 // =============================================================================
@@ -1158,6 +1506,54 @@ mod tests {
         assert!(err.to_string().contains("fromyaml"));
     }
 
+    #[test]
+    fn test_totoml_fromtoml_roundtrip() {
+        let original = serde_json::json!({
+            "log": { "level": "INFO" },
+            "entryPoints": {
+                "web": { "address": ":80" },
+                "websecure": { "address": ":443", "http": { "tls": true } }
+            },
+            "ports": [80, 443],
+            "ratio": 0.5,
+            "servers": [{ "url": "http://a" }, { "url": "http://b" }]
+        });
+
+        let toml = totoml(Value::from_serialize(&original)).unwrap();
+        assert!(toml.contains("[entryPoints.web]"));
+        assert!(toml.contains("[[servers]]"));
+
+        let parsed = fromtoml(toml).unwrap();
+        assert_eq!(serde_json::to_value(&parsed).unwrap(), original);
+    }
+
+    #[test]
+    fn test_totoml_rejects_null() {
+        let value = Value::from_serialize(serde_json::json!({
+            "server": { "ports": [80, null] }
+        }));
+        let err = totoml(value).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("null at 'server.ports[1]' cannot be represented in TOML")
+        );
+
+        let err = totoml(Value::from(vec![1, 2])).unwrap_err();
+        assert!(err.to_string().contains("totoml requires a map"));
+    }
+
+    #[test]
+    fn test_fromtoml_datetime_and_invalid() {
+        let v = fromtoml("created = 1979-05-27T07:32:00Z\n".to_string()).unwrap();
+        assert_eq!(
+            v.get_attr("created").unwrap().to_string(),
+            "1979-05-27T07:32:00Z"
+        );
+
+        let err = fromtoml("a = [1, 2".to_string()).unwrap_err();
+        assert!(err.to_string().contains("fromtoml: invalid TOML"));
+    }
+
     #[test]
     fn test_tojson_fromjson_roundtrip() {
         let original = Value::from_serialize(serde_json::json!({
@@ -1179,6 +1575,129 @@ mod tests {
         assert_eq!(original, decoded);
     }
 
+    #[test]
+    fn test_b32encode_padding() {
+        assert_eq!(b32encode("f".to_string()), "MY======");
+        assert_eq!(b32encode("fo".to_string()), "MZXQ====");
+        assert_eq!(b32encode("foo".to_string()), "MZXW6===");
+        assert_eq!(b32encode("foob".to_string()), "MZXW6YQ=");
+        assert_eq!(b32encode("fooba".to_string()), "MZXW6YTB");
+        assert_eq!(b32decode("MZXW6YQ=".to_string()).unwrap(), "foob");
+        assert_eq!(
+            b32decode(b32encode("hello world".to_string())).unwrap(),
+            "hello world"
+        );
+    }
+
+    #[test]
+    fn test_b32decode_invalid() {
+        let err = b32decode("MZXW6YQ".to_string()).unwrap_err();
+        assert!(err.to_string().contains("base32 decode error"));
+        let err = b32decode("mzxw6yq=".to_string()).unwrap_err();
+        assert!(err.to_string().contains("base32 decode error"));
+        // Valid base32 of the bytes [0xff, 0xfe], which are not UTF-8
+        let err = b32decode("777A====".to_string()).unwrap_err();
+        assert!(err.to_string().contains("UTF-8 decode error"));
+    }
+
+    #[test]
+    fn test_urlencode_reserved_characters() {
+        assert_eq!(
+            urlencode(Value::from("a b&c=d/e?f#g+h%")).unwrap(),
+            "a%20b%26c%3Dd%2Fe%3Ff%23g%2Bh%25"
+        );
+        assert_eq!(urlencode(Value::from("Az09-._~")).unwrap(), "Az09-._~");
+        assert_eq!(urlencode(Value::from("café")).unwrap(), "caf%C3%A9");
+        assert_eq!(urlencode(Value::from(42)).unwrap(), "42");
+        assert_eq!(urlencode(Value::from(())).unwrap(), "");
+    }
+
+    #[test]
+    fn test_urlencode_map() {
+        let query = Value::from_serialize(serde_json::json!({
+            "q": "a b",
+            "page": 2,
+            "skip": null
+        }));
+        assert_eq!(urlencode(query).unwrap(), "page=2&q=a%20b");
+    }
+
+    #[test]
+    fn test_urldecode() {
+        assert_eq!(
+            urldecode("a%20b%26c%3Dd%2Fe".to_string()).unwrap(),
+            "a b&c=d/e"
+        );
+        assert_eq!(urldecode("caf%C3%A9+x".to_string()).unwrap(), "café+x");
+
+        let err = urldecode("100%".to_string()).unwrap_err();
+        assert!(err.to_string().contains("invalid escape at byte 3"));
+        let err = urldecode("%zz".to_string()).unwrap_err();
+        assert!(err.to_string().contains("invalid escape"));
+        let err = urldecode("%FF".to_string()).unwrap_err();
+        assert!(err.to_string().contains("UTF-8 decode error"));
+    }
+
+    fn dict(json: serde_json::Value) -> Value {
+        Value::from_serialize(json)
+    }
+
+    #[test]
+    fn test_merge_keeps_base_values() {
+        let base = dict(serde_json::json!({"tier": "web", "owner": null, "nested": {"a": 1}}));
+        let overlay = dict(serde_json::json!({
+            "tier": "db",
+            "owner": "ops",
+            "extra": true,
+            "nested": {"a": 2, "b": 3}
+        }));
+
+        let merged = merge(base, Rest(vec![overlay])).unwrap();
+        assert_eq!(
+            serde_json::to_value(&merged).unwrap(),
+            serde_json::json!({
+                "tier": "web",
+                "owner": "ops",
+                "extra": true,
+                "nested": {"a": 1, "b": 3}
+            })
+        );
+    }
+
+    #[test]
+    fn test_mergeoverwrite_overlay_wins() {
+        let base = dict(serde_json::json!({"tier": "web", "keep": "me", "nested": {"a": 1}}));
+        let overlay = dict(serde_json::json!({
+            "tier": "db",
+            "keep": null,
+            "nested": {"a": 2, "b": 3}
+        }));
+
+        let merged = mergeoverwrite(base, Rest(vec![overlay])).unwrap();
+        assert_eq!(
+            serde_json::to_value(&merged).unwrap(),
+            serde_json::json!({"tier": "db", "keep": "me", "nested": {"a": 2, "b": 3}})
+        );
+    }
+
+    #[test]
+    fn test_merge_several_overlays() {
+        let base = dict(serde_json::json!({"a": 1}));
+        let first = dict(serde_json::json!({"a": 2, "b": 2}));
+        let second = dict(serde_json::json!({"b": 3, "c": 3}));
+
+        let merged = merge(base.clone(), Rest(vec![first.clone(), second.clone()])).unwrap();
+        assert_eq!(
+            serde_json::to_value(&merged).unwrap(),
+            serde_json::json!({"a": 1, "b": 2, "c": 3})
+        );
+        let merged = mergeoverwrite(base, Rest(vec![first, second])).unwrap();
+        assert_eq!(
+            serde_json::to_value(&merged).unwrap(),
+            serde_json::json!({"a": 2, "b": 3, "c": 3})
+        );
+    }
+
     #[test]
     fn test_quote() {
         assert_eq!(quote(Value::from("test")), "\"test\"");
@@ -1543,6 +2062,103 @@ mod tests {
         assert_eq!(ceil(Value::from(5)).unwrap(), 5);
     }
 
+    #[test]
+    fn test_round_filter() {
+        assert_eq!(round(Value::from(2.5), None).unwrap(), Value::from(3));
+        assert_eq!(round(Value::from(-2.5), None).unwrap(), Value::from(-3));
+        assert_eq!(round(Value::from(-2.4), None).unwrap(), Value::from(-2));
+        assert_eq!(round(Value::from(7), None).unwrap(), Value::from(7));
+
+        let result = round(Value::from(1.23456), Some(2)).unwrap();
+        assert!((f64::try_from(result).unwrap() - 1.23).abs() < 1e-9);
+        let result = round(Value::from(-1.2345), Some(3)).unwrap();
+        assert!((f64::try_from(result).unwrap() + 1.235).abs() < 1e-9);
+
+        let err = round(Value::from("2.5"), None).unwrap_err();
+        assert!(err.to_string().contains("round requires a number"));
+    }
+
+    #[test]
+    fn test_divf_filter() {
+        assert_eq!(divf(Value::from(7), Value::from(2)).unwrap(), 3.5);
+        assert_eq!(divf(Value::from(-9), Value::from(4)).unwrap(), -2.25);
+
+        let err = divf(Value::from(1), Value::from(0)).unwrap_err();
+        assert!(err.to_string().contains("divf: division by zero"));
+        let err = divf(Value::from("7"), Value::from(2)).unwrap_err();
+        assert!(err.to_string().contains("divf requires a number"));
+    }
+
+    // =========================================================================
+    // Version Function Tests
+    // =========================================================================
+
+    fn versions(list: &[&str]) -> Value {
+        Value::from_serialize(list)
+    }
+
+    #[test]
+    fn test_semver_sort_prerelease_order() {
+        let sorted = semver_sort(versions(&[
+            "1.10.0",
+            "v1.2.0",
+            "latest",
+            "1.2.0-rc.1",
+            "1.2.0-alpha",
+            "1.9",
+            "1.2.0-rc.10",
+        ]))
+        .unwrap();
+        assert_eq!(
+            sorted,
+            vec![
+                "1.2.0-alpha",
+                "1.2.0-rc.1",
+                "1.2.0-rc.10",
+                "v1.2.0",
+                "1.9",
+                "1.10.0",
+                "latest"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_semver_sort_keeps_invalid_order() {
+        let sorted = semver_sort(versions(&["main", "2.0.0", "dev", "1.0.0"])).unwrap();
+        assert_eq!(sorted, vec!["1.0.0", "2.0.0", "main", "dev"]);
+    }
+
+    #[test]
+    fn test_semver_max() {
+        let tags = versions(&["v1.2.3", "1.3.0-beta.1", "2.0.0", "1.2.10", "edge"]);
+        assert_eq!(
+            semver_max(tags.clone(), None).unwrap(),
+            Value::from("2.0.0")
+        );
+        assert_eq!(
+            semver_max(tags.clone(), Some("^1.2".to_string())).unwrap(),
+            Value::from("1.2.10")
+        );
+        assert_eq!(
+            semver_max(tags.clone(), Some(">=1.3.0-beta.0, <2".to_string())).unwrap(),
+            Value::from("1.3.0-beta.1")
+        );
+        assert!(semver_max(tags, Some(">=3".to_string())).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_semver_filters_reject_non_lists() {
+        let err = semver_sort(Value::from("1.0.0")).unwrap_err();
+        assert!(err.to_string().contains("semver_sort requires a list"));
+        let err = semver_max(Value::from(3), None).unwrap_err();
+        assert!(err.to_string().contains("semver_max requires a list"));
+        let err = semver_max(Value::from_serialize([1, 2]), None).unwrap_err();
+        assert!(err.to_string().contains("versions must be strings"));
+        let err = semver_max(versions(&["1.0.0"]), Some("nope".to_string())).unwrap_err();
+        assert!(err.to_string().contains("Invalid constraint"));
+    }
+
     // =========================================================================
     // Crypto Function Tests
     // =========================================================================
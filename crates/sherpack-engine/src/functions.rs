@@ -1,42 +1,44 @@
 //! Template functions (global functions available in templates)
 
-use minijinja::value::{Object, Rest};
+use minijinja::value::Rest;
 use minijinja::{Error, ErrorKind, State, Value};
-use std::sync::Arc;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::cell::Cell;
 
 /// Maximum recursion depth for tpl function (prevents infinite loops)
 const MAX_TPL_DEPTH: usize = 10;
 
-/// Key for storing tpl recursion depth in State's temp storage
-const TPL_DEPTH_KEY: &str = "__sherpack_tpl_depth";
+/// Fail with a custom error message
+///
+/// Usage: {{ fail("Something went wrong") }}
+pub fn fail(message: String) -> Result<Value, Error> {
+    Err(Error::new(ErrorKind::InvalidOperation, message))
+}
 
-/// Counter object for tracking tpl recursion depth
-/// Implements Object trait so it can be stored in State's temp storage
-#[derive(Debug, Default)]
-struct TplDepthCounter(AtomicUsize);
+/// Require the value at a dotted values path
+///
+/// Returns the value, or fails with an error naming the path and how to set
+/// it when the value is undefined, null or an empty string. The `values.`
+/// prefix is optional.
+///
+/// Usage: {{ must_have("database.password") | b64encode }}
+pub fn must_have(state: &State, path: String, message: Option<String>) -> Result<Value, Error> {
+    let path = path.strip_prefix("values.").unwrap_or(&path).to_string();
 
-impl Object for TplDepthCounter {
-    fn repr(self: &Arc<Self>) -> minijinja::value::ObjectRepr {
-        minijinja::value::ObjectRepr::Plain
+    let mut current = state.lookup("values").unwrap_or(Value::UNDEFINED);
+    for key in path.split('.') {
+        current = current.get_attr(key).unwrap_or(Value::UNDEFINED);
     }
-}
 
-impl TplDepthCounter {
-    fn increment(&self) -> usize {
-        self.0.fetch_add(1, Ordering::SeqCst) + 1
+    if current.is_undefined() || current.is_none() || current.as_str() == Some("") {
+        let default_message = format!("required value `values.{}` is not set", path);
+        return Err(crate::filters::missing_value_error(
+            message,
+            &default_message,
+            Some(path),
+        ));
     }
 
-    fn decrement(&self) {
-        self.0.fetch_sub(1, Ordering::SeqCst);
-    }
-}
-
-/// Fail with a custom error message
-///
-/// Usage: {{ fail("Something went wrong") }}
-pub fn fail(message: String) -> Result<Value, Error> {
-    Err(Error::new(ErrorKind::InvalidOperation, message))
+    Ok(current)
 }
 
 /// Create a dict from key-value pairs
@@ -304,6 +306,204 @@ pub fn now() -> String {
     chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string()
 }
 
+/// Format a time (Helm's `date`)
+///
+/// Usage: {{ date("2006-01-02", now()) }} or {{ values.releasedAt | date("%Y-%m-%d") }}
+///
+/// The time is a unix timestamp in seconds or an RFC 3339 string, which is
+/// what `now()` returns. The format is a Go reference layout
+/// (`2006-01-02T15:04:05Z07:00`), or a strftime string when it contains `%`.
+pub fn date(format: String, value: Value) -> Result<String, Error> {
+    let time = parse_time("date", &value)?;
+    format_time("date", &format, &time)
+}
+
+/// Format a time in a time zone (Helm's `dateInZone`)
+///
+/// Usage: {{ date_in_zone("15:04 MST", now(), "Europe/Paris") }}
+///
+/// The zone is an IANA name, `UTC`, or `Local` for the machine's zone.
+pub fn date_in_zone(format: String, value: Value, zone: String) -> Result<String, Error> {
+    let time = parse_time("date_in_zone", &value)?;
+    if zone == "Local" {
+        return format_time("date_in_zone", &format, &time.with_timezone(&chrono::Local));
+    }
+    let tz: chrono_tz::Tz = zone.parse().map_err(|_| {
+        Error::new(
+            ErrorKind::InvalidOperation,
+            format!("date_in_zone: unknown time zone '{}'", zone),
+        )
+    })?;
+    format_time("date_in_zone", &format, &time.with_timezone(&tz))
+}
+
+/// Format a number of seconds as a Go duration (Helm's `duration`)
+///
+/// Usage: {{ duration(3723) }}  →  "1h2m3s"
+pub fn duration(seconds: Value) -> Result<String, Error> {
+    let total = if let Some(i) = seconds.as_i64() {
+        i
+    } else if let Ok(f) = f64::try_from(seconds.clone()) {
+        f as i64
+    } else if let Some(i) = seconds.as_str().and_then(|s| s.trim().parse::<i64>().ok()) {
+        i
+    } else {
+        return Err(Error::new(
+            ErrorKind::InvalidOperation,
+            format!(
+                "duration requires a number of seconds, got {:?}",
+                seconds.kind()
+            ),
+        ));
+    };
+
+    if total == 0 {
+        return Ok("0s".to_string());
+    }
+
+    let sign = if total < 0 { "-" } else { "" };
+    let abs = total.unsigned_abs();
+    let (hours, minutes, secs) = (abs / 3600, abs % 3600 / 60, abs % 60);
+    Ok(if hours > 0 {
+        format!("{}{}h{}m{}s", sign, hours, minutes, secs)
+    } else if minutes > 0 {
+        format!("{}{}m{}s", sign, minutes, secs)
+    } else {
+        format!("{}{}s", sign, secs)
+    })
+}
+
+/// Parse a unix timestamp or RFC 3339 string into a UTC time
+fn parse_time(function: &str, value: &Value) -> Result<chrono::DateTime<chrono::Utc>, Error> {
+    let parsed = if let Some(secs) = value.as_i64() {
+        chrono::DateTime::from_timestamp(secs, 0)
+    } else if let Some(s) = value.as_str() {
+        match chrono::DateTime::parse_from_rfc3339(s.trim()) {
+            Ok(time) => Some(time.with_timezone(&chrono::Utc)),
+            Err(_) => s
+                .trim()
+                .parse::<i64>()
+                .ok()
+                .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0)),
+        }
+    } else if let Ok(f) = f64::try_from(value.clone()) {
+        chrono::DateTime::from_timestamp(f.floor() as i64, (f.fract() * 1e9) as u32)
+    } else {
+        None
+    };
+
+    parsed.ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidOperation,
+            format!(
+                "{}: expected a unix timestamp or RFC 3339 time, got {}",
+                function, value
+            ),
+        )
+    })
+}
+
+/// Format a time with a Go layout or strftime string
+fn format_time<Tz>(
+    function: &str,
+    format: &str,
+    time: &chrono::DateTime<Tz>,
+) -> Result<String, Error>
+where
+    Tz: chrono::TimeZone,
+    Tz::Offset: std::fmt::Display,
+{
+    use chrono::Offset;
+    use chrono::format::{Item, StrftimeItems};
+    use std::fmt::Write;
+
+    let strftime = if format.contains('%') {
+        format.to_string()
+    } else {
+        go_layout_to_strftime(format, time.offset().fix().local_minus_utc() == 0)
+    };
+
+    if StrftimeItems::new(&strftime).any(|item| matches!(item, Item::Error)) {
+        return Err(Error::new(
+            ErrorKind::InvalidOperation,
+            format!("{}: invalid format '{}'", function, format),
+        ));
+    }
+
+    let mut out = String::new();
+    write!(out, "{}", time.format(&strftime)).map_err(|_| {
+        Error::new(
+            ErrorKind::InvalidOperation,
+            format!("{}: cannot format time with '{}'", function, format),
+        )
+    })?;
+    Ok(out)
+}
+
+/// Translate a Go reference layout (`Mon Jan 2 15:04:05 MST 2006`) to strftime
+///
+/// `Z07:00` and `Z0700` print `Z` for UTC, like Go.
+fn go_layout_to_strftime(layout: &str, utc: bool) -> String {
+    // Longest tokens first so "2006" wins over "2" and "January" over "Jan"
+    const TOKENS: &[(&str, &str)] = &[
+        (".000000000", "%.9f"),
+        (".000000", "%.6f"),
+        ("January", "%B"),
+        ("Monday", "%A"),
+        ("-07:00", "%:z"),
+        ("-0700", "%z"),
+        (".000", "%.3f"),
+        ("2006", "%Y"),
+        ("Jan", "%b"),
+        ("Mon", "%a"),
+        ("MST", "%Z"),
+        ("002", "%j"),
+        ("01", "%m"),
+        ("02", "%d"),
+        ("_2", "%e"),
+        ("03", "%I"),
+        ("04", "%M"),
+        ("05", "%S"),
+        ("06", "%y"),
+        ("15", "%H"),
+        ("PM", "%p"),
+        ("pm", "%P"),
+        ("1", "%-m"),
+        ("2", "%-d"),
+        ("3", "%-I"),
+        ("4", "%-M"),
+        ("5", "%-S"),
+    ];
+
+    let mut out = String::with_capacity(layout.len() * 2);
+    let mut rest = layout;
+    'scan: while !rest.is_empty() {
+        for (zulu, offset) in [("Z07:00", "%:z"), ("Z0700", "%z")] {
+            if let Some(tail) = rest.strip_prefix(zulu) {
+                out.push_str(if utc { "Z" } else { offset });
+                rest = tail;
+                continue 'scan;
+            }
+        }
+        for (token, spec) in TOKENS {
+            if let Some(tail) = rest.strip_prefix(token) {
+                out.push_str(spec);
+                rest = tail;
+                continue 'scan;
+            }
+        }
+
+        let c = rest.chars().next().unwrap_or_default();
+        if c == '%' {
+            out.push_str("%%");
+        } else {
+            out.push(c);
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+    out
+}
+
 /// Printf-style formatting
 ///
 /// Usage: {{ printf("%s-%d", name, count) }}
@@ -375,15 +575,18 @@ pub fn printf(format: String, args: Vec<Value>) -> Result<String, Error> {
 
 /// Evaluate a string as a template (Helm's tpl function)
 ///
-/// Usage: {{ tpl(values.dynamicTemplate, ctx) }}
+/// Usage: {{ tpl(values.dynamicTemplate) }} or {{ tpl(values.dynamicTemplate, ctx) }}
 ///
 /// This allows template strings stored in values to contain Jinja expressions.
-/// The context parameter provides the variables available to the nested template.
+/// Without a context argument the nested template sees the current render
+/// context (values, release, pack, capabilities, template), like Helm's
+/// `tpl $str .`. An explicit context replaces it.
 ///
 /// ## Security Features (Sherpack improvements over Helm)
 ///
 /// - **Recursion limit**: Maximum depth of 10 to prevent infinite loops
-/// - **Source tracking**: Better error messages showing template origin
+/// - **Source tracking**: Errors name the calling template and the line
+///   inside the nested template
 ///
 /// ## Example
 ///
@@ -394,61 +597,111 @@ pub fn printf(format: String, args: Vec<Value>) -> Result<String, Error> {
 ///
 /// Then in template:
 /// ```jinja
-/// host: {{ tpl(values.host, {"release": release}) }}
+/// host: {{ tpl(values.host) }}
 /// ```
 /// Result: `host: myrelease.example.com`
-pub fn tpl(state: &State, template: String, context: Value) -> Result<String, Error> {
+pub fn tpl(state: &State, template: String, context: Option<Value>) -> Result<String, Error> {
+    render_nested(state, "tpl", &template, context)
+}
+
+/// Render a template string from within a template, with recursion guard
+fn render_nested(
+    state: &State,
+    function: &str,
+    template: &str,
+    context: Option<Value>,
+) -> Result<String, Error> {
     // Skip if no template markers present (optimization)
     if !template.contains("{{") && !template.contains("{%") {
-        return Ok(template);
+        return Ok(template.to_string());
     }
 
     // Check recursion depth to prevent infinite loops
-    let depth = increment_tpl_depth(state)?;
+    let guard = TplDepthGuard::enter()?;
+
+    let context = context.unwrap_or_else(|| current_context(state));
+    let name = format!("{} in {}", function, state.name());
 
     // Render the template string using the current environment
-    let result = state.env().render_str(&template, context).map_err(|e| {
-        // Enhance error message with tpl context
-        Error::new(
-            ErrorKind::InvalidOperation,
-            format!(
-                "tpl error (depth {}): {}\n  Template: \"{}\"",
-                depth,
-                e,
-                truncate_for_error(&template, 60)
-            ),
-        )
-    });
+    state
+        .env()
+        .template_from_named_str(&name, template)
+        .and_then(|tmpl| tmpl.render(context))
+        .map_err(|e| {
+            let location = e
+                .line()
+                .map(|line| format!(" at line {}", line))
+                .unwrap_or_default();
+            Error::new(
+                ErrorKind::InvalidOperation,
+                format!(
+                    "{} error{} (depth {}): {}\n  Template: \"{}\"",
+                    function,
+                    location,
+                    guard.depth,
+                    e.detail().unwrap_or(&e.kind().to_string()),
+                    truncate_for_error(template, 60)
+                ),
+            )
+        })
+}
+
+/// Collect the standard context variables visible at the call site
+fn current_context(state: &State) -> Value {
+    CONTEXT_VARIABLES
+        .iter()
+        .filter_map(|var| {
+            state
+                .lookup(var)
+                .filter(|v| !v.is_undefined())
+                .map(|v| (*var, v))
+        })
+        .collect()
+}
 
-    // Decrement depth after rendering (for sibling tpl calls)
-    decrement_tpl_depth(state);
+/// Context variables forwarded to nested templates
+const CONTEXT_VARIABLES: [&str; 5] = ["values", "release", "pack", "capabilities", "template"];
 
-    result
+thread_local! {
+    /// Current tpl nesting depth on this thread
+    ///
+    /// Nested templates render with a fresh `State`, so the depth can't live
+    /// in per-render temp storage.
+    static TPL_DEPTH: Cell<usize> = const { Cell::new(0) };
 }
 
-/// Increment tpl recursion depth, returning error if limit exceeded
-fn increment_tpl_depth(state: &State) -> Result<usize, Error> {
-    let counter = state.get_or_set_temp_object(TPL_DEPTH_KEY, TplDepthCounter::default);
-    let depth = counter.increment();
+/// Holds one level of tpl nesting; released on drop, including on error
+struct TplDepthGuard {
+    depth: usize,
+}
 
-    if depth > MAX_TPL_DEPTH {
-        Err(Error::new(
-            ErrorKind::InvalidOperation,
-            format!(
-                "tpl recursion depth {} exceeded maximum {} - possible infinite loop in values. \
-                 Check for circular references in template strings.",
-                depth, MAX_TPL_DEPTH
-            ),
-        ))
-    } else {
-        Ok(depth)
+impl TplDepthGuard {
+    fn enter() -> Result<Self, Error> {
+        let depth = TPL_DEPTH.with(|d| {
+            d.set(d.get() + 1);
+            d.get()
+        });
+        let guard = Self { depth };
+
+        if depth > MAX_TPL_DEPTH {
+            return Err(Error::new(
+                ErrorKind::InvalidOperation,
+                format!(
+                    "tpl recursion depth {} exceeded maximum {} - possible infinite loop in values. \
+                     Check for circular references in template strings.",
+                    depth, MAX_TPL_DEPTH
+                ),
+            ));
+        }
+
+        Ok(guard)
     }
 }
 
-/// Decrement tpl recursion depth
-fn decrement_tpl_depth(state: &State) {
-    let counter = state.get_or_set_temp_object(TPL_DEPTH_KEY, TplDepthCounter::default);
-    counter.decrement();
+impl Drop for TplDepthGuard {
+    fn drop(&mut self) {
+        TPL_DEPTH.with(|d| d.set(d.get().saturating_sub(1)));
+    }
 }
 
 /// Truncate string for error messages
@@ -523,58 +776,87 @@ pub fn lookup(api_version: String, kind: String, namespace: String, name: String
 ///
 /// Usage: {{ tpl_ctx(values.dynamicTemplate) }}
 ///
-/// This version automatically passes the full template context (values, release, pack, etc.)
-/// to the nested template, similar to Helm's `tpl $str .` pattern.
+/// Equivalent to `tpl()` without a context argument: the nested template
+/// sees values, release, pack, capabilities and template.
 ///
 /// ## Security Features
 ///
 /// - **Recursion limit**: Shares depth counter with `tpl()`, max depth 10
 /// - **Full context**: Passes values, release, pack, capabilities, template
 pub fn tpl_ctx(state: &State, template: String) -> Result<String, Error> {
-    // Skip if no template markers present (optimization)
-    if !template.contains("{{") && !template.contains("{%") {
-        return Ok(template);
-    }
+    render_nested(state, "tpl_ctx", &template, None)
+}
 
-    // Check recursion depth to prevent infinite loops
-    let depth = increment_tpl_depth(state)?;
-
-    // Build context from all available variables
-    let mut ctx = serde_json::Map::new();
-
-    // Try to lookup and add standard context variables
-    for var in ["values", "release", "pack", "capabilities", "template"] {
-        if let Some(v) = state.lookup(var)
-            && !v.is_undefined()
-            && let Ok(json_val) = serde_json::to_value(&v)
-        {
-            ctx.insert(var.to_string(), json_val);
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_date_go_layout_and_strftime() {
+        // 2009-02-13T23:31:30Z
+        let ts = Value::from(1_234_567_890);
+        assert_eq!(
+            date("2006-01-02T15:04:05Z07:00".into(), ts.clone()).unwrap(),
+            "2009-02-13T23:31:30Z"
+        );
+        assert_eq!(
+            date("Mon Jan _2 3:04PM".into(), ts.clone()).unwrap(),
+            "Fri Feb 13 11:31PM"
+        );
+        assert_eq!(date("%Y/%m/%d".into(), ts).unwrap(), "2009/02/13");
+
+        // RFC 3339 strings, as returned by now()
+        assert_eq!(
+            date(
+                "2006-01-02".into(),
+                Value::from("2024-03-01T10:00:00+02:00")
+            )
+            .unwrap(),
+            "2024-03-01"
+        );
+        assert!(date("2006".into(), Value::from(now())).is_ok());
     }
 
-    let context = Value::from_serialize(serde_json::Value::Object(ctx));
+    #[test]
+    fn test_date_errors() {
+        let err = date("2006".into(), Value::from("yesterday")).unwrap_err();
+        assert!(err.to_string().contains("date: expected a unix timestamp"));
 
-    let result = state.env().render_str(&template, context).map_err(|e| {
-        Error::new(
-            ErrorKind::InvalidOperation,
-            format!(
-                "tpl_ctx error (depth {}): {}\n  Template: \"{}\"",
-                depth,
-                e,
-                truncate_for_error(&template, 60)
-            ),
-        )
-    });
+        let err = date("%Q".into(), Value::from(0)).unwrap_err();
+        assert!(err.to_string().contains("date: invalid format '%Q'"));
+    }
 
-    // Decrement depth after rendering
-    decrement_tpl_depth(state);
+    #[test]
+    fn test_date_in_zone() {
+        let ts = Value::from(1_234_567_890);
+        assert_eq!(
+            date_in_zone(
+                "2006-01-02 15:04 MST -07:00".into(),
+                ts.clone(),
+                "Asia/Tokyo".into()
+            )
+            .unwrap(),
+            "2009-02-14 08:31 JST +09:00"
+        );
+        assert_eq!(
+            date_in_zone("15:04Z07:00".into(), ts.clone(), "UTC".into()).unwrap(),
+            "23:31Z"
+        );
 
-    result
-}
+        let err = date_in_zone("2006".into(), ts, "Mars/Olympus".into()).unwrap_err();
+        assert!(err.to_string().contains("unknown time zone 'Mars/Olympus'"));
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_duration() {
+        assert_eq!(duration(Value::from(3723)).unwrap(), "1h2m3s");
+        assert_eq!(duration(Value::from(3600)).unwrap(), "1h0m0s");
+        assert_eq!(duration(Value::from(90)).unwrap(), "1m30s");
+        assert_eq!(duration(Value::from(0)).unwrap(), "0s");
+        assert_eq!(duration(Value::from(-45)).unwrap(), "-45s");
+        assert_eq!(duration(Value::from("120")).unwrap(), "2m0s");
+        assert!(duration(Value::from("soon")).is_err());
+    }
 
     #[test]
     fn test_dict() {
@@ -693,6 +975,69 @@ mod tests {
         assert_eq!(result, "level3");
     }
 
+    #[test]
+    fn test_tpl_uses_current_context() {
+        use minijinja::Environment;
+
+        let mut env = Environment::new();
+        env.add_function("tpl", super::tpl);
+
+        let result = env
+            .render_str(
+                "{{ tpl(values.host) }}",
+                minijinja::context! {
+                    values => minijinja::context! { host => "{{ release.name }}.example.com" },
+                    release => minijinja::context! { name => "myrelease" },
+                },
+            )
+            .unwrap();
+        assert_eq!(result, "myrelease.example.com");
+    }
+
+    #[test]
+    fn test_tpl_self_reference_hits_depth_limit() {
+        use minijinja::Environment;
+
+        let mut env = Environment::new();
+        env.add_function("tpl", super::tpl);
+
+        let err = env
+            .render_str(
+                "{{ tpl(values.loop) }}",
+                minijinja::context! {
+                    values => minijinja::context! { loop => "{{ tpl(values.loop) }}" },
+                },
+            )
+            .unwrap_err();
+        assert!(
+            format!("{:#}", err).contains("exceeded maximum 10"),
+            "Expected recursion error, got: {:#}",
+            err
+        );
+
+        // The guard is released after the failure
+        assert_eq!(TPL_DEPTH.with(Cell::get), 0);
+    }
+
+    #[test]
+    fn test_tpl_error_reports_nested_line() {
+        use minijinja::Environment;
+
+        let mut env = Environment::new();
+        env.add_function("tpl", super::tpl);
+
+        let err = env
+            .render_str(
+                "{{ tpl(values.broken) }}",
+                minijinja::context! {
+                    values => minijinja::context! { broken => "ok\n{{ 1 + }}" },
+                },
+            )
+            .unwrap_err();
+        let message = format!("{:#}", err);
+        assert!(message.contains("tpl error at line 2"), "got: {}", message);
+    }
+
     #[test]
     fn test_truncate_for_error() {
         assert_eq!(truncate_for_error("short", 10), "short");
@@ -6,8 +6,13 @@
 //! - Full Jinja2 syntax support
 //! - Multi-error collection for comprehensive error reporting
 //! - Files API for accessing pack files from templates
+//! - Injectable cluster capabilities (`capabilities.apiVersions.has(...)`)
 //! - Subchart rendering with recursive support
+//! - Post-rendering through external commands
+//! - Static analysis of template sources for linting
 
+pub mod analysis;
+pub mod capabilities_object;
 pub mod cluster_reader;
 pub mod engine;
 pub mod error;
@@ -15,12 +20,15 @@ pub mod files_object;
 pub mod filters;
 pub mod functions;
 pub mod pack_renderer;
+pub mod post_renderer;
 pub mod secrets;
 pub mod subchart;
 pub mod suggestions;
 
+pub use analysis::{FunctionCall, ValueReference, function_calls, value_references};
+pub use capabilities_object::{ApiVersionsObject, create_capabilities_value};
 pub use cluster_reader::{ClusterReader, LookupState};
-pub use engine::{Engine, EngineBuilder, RenderResult};
+pub use engine::{Engine, EngineBuilder, RenderResult, UndefinedMode};
 pub use error::{
     EngineError, IssueSeverity, RenderIssue, RenderReport, RenderResultWithReport, TemplateError,
     TemplateErrorKind,
@@ -29,6 +37,7 @@ pub use files_object::{FilesObject, create_files_value, create_files_value_from_
 pub use pack_renderer::{
     PackRenderResult, PackRenderResultWithReport, PackRenderer, PackRendererBuilder,
 };
+pub use post_renderer::{ExecPostRenderer, PostRenderer};
 pub use secrets::SecretFunctionState;
 pub use subchart::{DiscoveryResult, SubchartConfig, SubchartInfo};
 pub use suggestions::{AVAILABLE_FILTERS, AVAILABLE_FUNCTIONS};
@@ -7,7 +7,8 @@ use indexmap::IndexMap;
 use std::collections::HashMap;
 
 use serde_json::Value as JsonValue;
-use sherpack_core::{Dependency, LoadedPack, TemplateContext, Values};
+use sherpack_core::context::Capabilities;
+use sherpack_core::{Dependency, ImportValue, LoadedPack, TemplateContext, Values};
 
 use crate::engine::Engine;
 use crate::error::{EngineError, RenderIssue, RenderReport, TemplateError};
@@ -20,17 +21,46 @@ pub struct PackRenderResult {
     /// Subchart manifests are prefixed: "redis/deployment.yaml"
     pub manifests: IndexMap<String, String>,
 
-    /// Post-install notes (from parent pack only)
+    /// Path of each manifest relative to the pack root, laid out like Helm:
+    /// "templates/deployment.yaml", "charts/redis/templates/deployment.yaml"
+    pub source_paths: IndexMap<String, String>,
+
+    /// Post-install notes of the parent pack
     pub notes: Option<String>,
 
+    /// Post-install notes of each enabled subchart, by qualified name
+    pub subchart_notes: IndexMap<String, String>,
+
     /// Discovery information about subcharts
     pub discovery: DiscoveryResult,
 }
 
+impl PackRenderResult {
+    /// Notes to show after install: the parent's, followed with
+    /// `include_subcharts` by each subchart's under a header of its own
+    pub fn combined_notes(&self, include_subcharts: bool) -> Option<String> {
+        let mut sections: Vec<String> = self
+            .notes
+            .iter()
+            .map(|notes| notes.trim_end().to_string())
+            .filter(|notes| !notes.is_empty())
+            .collect();
+
+        if include_subcharts {
+            for (name, notes) in &self.subchart_notes {
+                sections.push(format!("--- Subchart {} ---\n{}", name, notes.trim_end()));
+            }
+        }
+
+        (!sections.is_empty()).then(|| sections.join("\n\n"))
+    }
+}
+
 /// Orchestrates rendering of a pack and its subcharts
 pub struct PackRenderer {
     engine: Engine,
     config: SubchartConfig,
+    capabilities: Option<Capabilities>,
 }
 
 impl PackRenderer {
@@ -39,12 +69,23 @@ impl PackRenderer {
         Self {
             engine,
             config: SubchartConfig::default(),
+            capabilities: None,
         }
     }
 
     /// Create with custom configuration
     pub fn with_config(engine: Engine, config: SubchartConfig) -> Self {
-        Self { engine, config }
+        Self {
+            engine,
+            config,
+            capabilities: None,
+        }
+    }
+
+    /// Render with these capabilities instead of the context's own
+    pub fn with_capabilities(mut self, capabilities: Capabilities) -> Self {
+        self.capabilities = Some(capabilities);
+        self
     }
 
     /// Create a builder for more options
@@ -66,7 +107,22 @@ impl PackRenderer {
     ///
     /// This scans the subcharts directory (default: `charts/`) for valid packs
     /// and evaluates their conditions against the provided values.
+    ///
+    /// An aliased dependency is read from `charts/<alias>`, or else from
+    /// `charts/<name>`, so one vendored pack can back several aliases.
     pub fn discover_subcharts(&self, pack: &LoadedPack, values: &JsonValue) -> DiscoveryResult {
+        self.discover_children(pack, values, None, 1)
+    }
+
+    /// Discover the subcharts of `pack`, nested `depth` levels below the
+    /// pack being rendered, under the subchart named `parent`
+    fn discover_children(
+        &self,
+        pack: &LoadedPack,
+        values: &JsonValue,
+        parent: Option<&str>,
+        depth: usize,
+    ) -> DiscoveryResult {
         let mut result = DiscoveryResult::new();
         let subcharts_dir = pack.root.join(&self.config.subcharts_dir);
 
@@ -132,6 +188,17 @@ impl PackRenderer {
             // Find matching dependency definition
             let dependency = deps_by_name.get(dir_name.as_str()).cloned().cloned();
 
+            // A pack only used through aliases is rendered once per alias below
+            if dependency.is_none()
+                && pack
+                    .pack
+                    .dependencies
+                    .iter()
+                    .any(|d| d.alias.is_some() && d.name == dir_name)
+            {
+                continue;
+            }
+
             // Determine effective name (alias if set)
             let name = dependency
                 .as_ref()
@@ -148,12 +215,44 @@ impl PackRenderer {
                 enabled,
                 dependency,
                 disabled_reason,
+                depth,
+                parent: parent.map(String::from),
             });
         }
 
+        // Aliases without a directory of their own share their pack's
+        for dep in &pack.pack.dependencies {
+            let Some(alias) = &dep.alias else { continue };
+            let path = subcharts_dir.join(&dep.name);
+            if result.subcharts.iter().any(|s| &s.name == alias) || !path.is_dir() {
+                continue;
+            }
+
+            match LoadedPack::load(&path) {
+                Ok(subchart_pack) => {
+                    let dependency = Some(dep.clone());
+                    let (enabled, disabled_reason) = self.evaluate_condition(&dependency, values);
+                    result.subcharts.push(SubchartInfo {
+                        name: alias.clone(),
+                        path,
+                        pack: subchart_pack,
+                        enabled,
+                        dependency,
+                        disabled_reason,
+                        depth,
+                        parent: parent.map(String::from),
+                    });
+                }
+                Err(e) => result.warnings.push(format!(
+                    "Failed to load subchart '{}' for alias '{}': {}",
+                    dep.name, alias, e
+                )),
+            }
+        }
+
         // Check for missing subcharts from dependencies
         for dep in &pack.pack.dependencies {
-            if dep.enabled {
+            if dep.enabled && dep.enabled_by_values(values) {
                 let name = dep.effective_name();
                 let found = result.subcharts.iter().any(|s| s.name == name);
                 if !found {
@@ -168,7 +267,7 @@ impl PackRenderer {
         result
     }
 
-    /// Evaluate if a subchart is enabled based on its condition
+    /// Evaluate if a subchart is enabled based on its condition and tags
     fn evaluate_condition(
         &self,
         dependency: &Option<Dependency>,
@@ -187,15 +286,19 @@ impl PackRenderer {
             );
         }
 
-        // Check condition
-        if let Some(condition) = &dep.condition {
-            let condition_met = evaluate_condition_path(condition, values);
-            if !condition_met {
-                return (
-                    false,
-                    Some(format!("Condition '{}' evaluated to false", condition)),
-                );
-            }
+        // Check condition, then tags
+        if let Some(enabled) = dep.condition_value(values) {
+            let reason = format!(
+                "Condition '{}' evaluated to false",
+                dep.condition.as_deref().unwrap_or_default()
+            );
+            return (enabled, (!enabled).then_some(reason));
+        }
+        if dep.tags_value(values) == Some(false) {
+            return (
+                false,
+                Some(format!("Tags [{}] are all false", dep.tags.join(", "))),
+            );
         }
 
         (true, None)
@@ -207,7 +310,8 @@ impl PackRenderer {
     /// 1. Discovers all subcharts
     /// 2. Evaluates conditions against values
     /// 3. Renders enabled subcharts with scoped values
-    /// 4. Renders the parent pack
+    /// 4. Renders the parent pack, with the templates of library subcharts
+    ///    available for import
     /// 5. Combines all manifests
     pub fn render(
         &self,
@@ -235,7 +339,9 @@ impl PackRenderer {
 
         Ok(PackRenderResult {
             manifests: result.manifests,
+            source_paths: result.source_paths,
             notes: result.notes,
+            subchart_notes: result.subchart_notes,
             discovery: result.discovery,
         })
     }
@@ -246,19 +352,47 @@ impl PackRenderer {
         pack: &LoadedPack,
         context: &TemplateContext,
     ) -> PackRenderResultWithReport {
-        self.render_recursive(pack, context, 0)
+        match &self.capabilities {
+            Some(capabilities) => {
+                let context = context.clone().with_capabilities(capabilities.clone());
+                self.render_tree(pack, &context)
+            }
+            None => self.render_tree(pack, context),
+        }
+    }
+
+    /// Render `pack` as the root of its dependency tree
+    fn render_tree(
+        &self,
+        pack: &LoadedPack,
+        context: &TemplateContext,
+    ) -> PackRenderResultWithReport {
+        let ancestors = [pack.pack.metadata.name.clone()];
+        self.render_recursive(pack, context, 0, None, &ancestors).0
     }
 
     /// Internal recursive renderer
+    ///
+    /// Subcharts are rendered depth first, each one after the values of its
+    /// parent are resolved and before the parent's own templates, so globals
+    /// flow down and import-values flow up through every level. `name` is the
+    /// qualified name of the subchart being rendered and `ancestors` the pack
+    /// names from the root down to it, used to detect cycles.
+    ///
+    /// Returns the result along with the values the pack was rendered with.
     fn render_recursive(
         &self,
         pack: &LoadedPack,
         context: &TemplateContext,
         depth: usize,
-    ) -> PackRenderResultWithReport {
+        name: Option<&str>,
+        ancestors: &[String],
+    ) -> (PackRenderResultWithReport, JsonValue) {
         let mut report = RenderReport::new();
         let mut all_manifests = IndexMap::new();
+        let mut source_paths = IndexMap::new();
         let mut notes = None;
+        let mut subchart_notes = IndexMap::new();
 
         // Check depth limit
         if depth > self.config.max_depth {
@@ -269,16 +403,41 @@ impl PackRenderer {
                     self.config.max_depth
                 ),
             );
-            return PackRenderResultWithReport {
+            let result = PackRenderResultWithReport {
                 manifests: all_manifests,
+                source_paths,
                 notes,
+                subchart_notes,
                 report,
                 discovery: DiscoveryResult::new(),
             };
+            return (result, context.values.clone());
+        }
+
+        // Library packs only lend their templates to the packs depending on them
+        if pack.pack.is_library() {
+            report.add_warning(
+                "library",
+                format!(
+                    "Pack '{}' is a library pack, no resources rendered",
+                    pack.pack.metadata.name
+                ),
+            );
+            let result = PackRenderResultWithReport {
+                manifests: all_manifests,
+                source_paths,
+                notes,
+                subchart_notes,
+                report,
+                discovery: DiscoveryResult::new(),
+            };
+            return (result, context.values.clone());
         }
 
         // Discover subcharts
-        let discovery = self.discover_subcharts(pack, &context.values);
+        let mut discovery = self.discover_children(pack, &context.values, name, depth + 1);
+        // Subcharts found below each of this pack's subcharts
+        let mut nested: Vec<Vec<SubchartInfo>> = Vec::new();
 
         // Add discovery warnings to report
         for warning in &discovery.warnings {
@@ -306,8 +465,14 @@ impl PackRenderer {
             }
         }
 
+        // Values imported from subcharts into the parent
+        let mut imported = Values::new();
+        // Library subcharts, whose templates the parent can import
+        let mut libraries = Vec::new();
+
         // Render each enabled subchart
         for subchart in &discovery.subcharts {
+            nested.push(Vec::new());
             if !subchart.enabled {
                 // Log why it was skipped
                 if let Some(reason) = &subchart.disabled_reason {
@@ -319,6 +484,11 @@ impl PackRenderer {
                 continue;
             }
 
+            if subchart.pack.pack.is_library() {
+                libraries.push((subchart.name.clone(), subchart.pack.clone()));
+                continue;
+            }
+
             // Load subchart's default values
             let subchart_defaults = if subchart.pack.values_path.exists() {
                 match Values::from_file(&subchart.pack.values_path) {
@@ -339,22 +509,65 @@ impl PackRenderer {
             let scoped_values =
                 Values::for_subchart_json(subchart_defaults, &context.values, &subchart.name);
 
+            // A pack already rendered above this one would recurse forever
+            let subchart_pack_name = &subchart.pack.pack.metadata.name;
+            if ancestors.contains(subchart_pack_name) {
+                report.add_error(
+                    format!("<subchart:{}>", subchart.name),
+                    TemplateError::simple(format!(
+                        "Subchart dependency cycle: {} -> {}",
+                        ancestors.join(" -> "),
+                        subchart_pack_name
+                    )),
+                );
+                continue;
+            }
+
             // Create context for subchart
             let subchart_context = TemplateContext::new(
                 scoped_values,
                 context.release.clone(),
                 &subchart.pack.pack.metadata,
-            );
+            )
+            .with_capabilities(context.capabilities.clone());
 
             // Recursively render subchart (handles its own subcharts)
-            let subchart_result =
-                self.render_recursive(&subchart.pack, &subchart_context, depth + 1);
+            let qualified_name = subchart.qualified_name();
+            let mut subchart_ancestors = ancestors.to_vec();
+            subchart_ancestors.push(subchart_pack_name.clone());
+            let (subchart_result, subchart_values) = self.render_recursive(
+                &subchart.pack,
+                &subchart_context,
+                depth + 1,
+                Some(&qualified_name),
+                &subchart_ancestors,
+            );
+
+            // Import from the values the subchart was rendered with, so
+            // values it imported from its own subcharts pass on up
+            if let Some(dependency) = &subchart.dependency {
+                let subchart_values = Values::from(subchart_values);
+                for import in &dependency.import_values {
+                    if let Err(warning) = import_value(&mut imported, &subchart_values, import) {
+                        report.add_warning(
+                            "subchart_import",
+                            format!("Subchart '{}': {}", subchart.name, warning),
+                        );
+                    }
+                }
+            }
 
             // Merge subchart manifests with prefix
             for (name, manifest) in subchart_result.manifests {
                 let prefixed_name = format!("{}/{}", subchart.name, name);
                 all_manifests.insert(prefixed_name, manifest);
             }
+            for (name, path) in subchart_result.source_paths {
+                source_paths.insert(
+                    format!("{}/{}", subchart.name, name),
+                    format!("{}/{}/{}", self.config.subcharts_dir, subchart.name, path),
+                );
+            }
 
             // Merge subchart errors with prefix
             for (template, errors) in subchart_result.report.errors_by_template {
@@ -369,13 +582,68 @@ impl PackRenderer {
                 report.add_issue(issue);
             }
 
-            // Subchart notes are typically not shown (only parent's notes)
+            // Keep the subtree's discovery for diagnostics
+            let subtree = subchart_result.discovery;
+            discovery.warnings.extend(subtree.warnings);
+            discovery.missing.extend(subtree.missing);
+            if let Some(below) = nested.last_mut() {
+                *below = subtree.subcharts;
+            }
+
+            // Keep the subchart's notes, then those of its own subcharts
+            if let Some(text) = subchart_result.notes.filter(|n| !n.trim().is_empty()) {
+                subchart_notes.insert(qualified_name, text);
+            }
+            subchart_notes.extend(subchart_result.subchart_notes);
+
+            if self.engine.is_fail_fast() && report.has_errors() {
+                break;
+            }
         }
 
-        // Render parent pack
-        let parent_result = self.engine.render_pack_collect_errors(pack, context);
+        // List the tree in render order, each subchart before its own
+        let mut nested = nested.into_iter();
+        discovery.subcharts = std::mem::take(&mut discovery.subcharts)
+            .into_iter()
+            .flat_map(|subchart| std::iter::once(subchart).chain(nested.next().unwrap_or_default()))
+            .collect();
+
+        if self.engine.is_fail_fast() && report.has_errors() {
+            let result = PackRenderResultWithReport {
+                manifests: all_manifests,
+                source_paths,
+                notes,
+                subchart_notes,
+                report,
+                discovery,
+            };
+            return (result, context.values.clone());
+        }
+
+        // Render parent pack, its own values taking precedence over imports
+        let parent_context;
+        let context = if imported.is_empty() {
+            context
+        } else {
+            imported.merge(&Values::from(context.values.clone()));
+            parent_context = TemplateContext {
+                values: imported.into_inner(),
+                ..context.clone()
+            };
+            &parent_context
+        };
+        let libraries: Vec<(&str, &LoadedPack)> = libraries
+            .iter()
+            .map(|(name, library)| (name.as_str(), library))
+            .collect();
+        let parent_result = self
+            .engine
+            .render_pack_with_libraries(pack, context, &libraries);
 
         // Merge parent manifests (after subcharts for proper ordering)
+        for name in parent_result.manifests.keys() {
+            source_paths.insert(name.clone(), format!("templates/{}", name));
+        }
         all_manifests.extend(parent_result.manifests);
         notes = parent_result.notes;
 
@@ -392,12 +660,16 @@ impl PackRenderer {
             report.add_success(success);
         }
 
-        PackRenderResultWithReport {
+        let values = context.values.clone();
+        let result = PackRenderResultWithReport {
             manifests: all_manifests,
+            source_paths,
             notes,
+            subchart_notes,
             report,
             discovery,
-        }
+        };
+        (result, values)
     }
 }
 
@@ -407,9 +679,15 @@ pub struct PackRenderResultWithReport {
     /// Rendered manifests (may be partial if errors occurred)
     pub manifests: IndexMap<String, String>,
 
+    /// Path of each manifest relative to the pack root (see [`PackRenderResult`])
+    pub source_paths: IndexMap<String, String>,
+
     /// Post-install notes
     pub notes: Option<String>,
 
+    /// Post-install notes of each enabled subchart (see [`PackRenderResult`])
+    pub subchart_notes: IndexMap<String, String>,
+
     /// Error and warning report
     pub report: RenderReport,
 
@@ -430,6 +708,7 @@ pub struct PackRendererBuilder {
     strict_mode: bool,
     max_depth: Option<usize>,
     subcharts_dir: Option<String>,
+    capabilities: Option<Capabilities>,
 }
 
 impl PackRendererBuilder {
@@ -451,6 +730,15 @@ impl PackRendererBuilder {
         self
     }
 
+    /// Override the cluster capabilities seen by templates
+    ///
+    /// Lets offline rendering target a specific Kubernetes version and set
+    /// of API versions instead of the built-in defaults.
+    pub fn capabilities(mut self, capabilities: Capabilities) -> Self {
+        self.capabilities = Some(capabilities);
+        self
+    }
+
     /// Build the PackRenderer
     pub fn build(self) -> PackRenderer {
         let engine = if self.strict_mode {
@@ -470,41 +758,76 @@ impl PackRendererBuilder {
             config.strict = true;
         }
 
-        PackRenderer { engine, config }
+        PackRenderer {
+            engine,
+            config,
+            capabilities: self.capabilities,
+        }
     }
 }
 
-/// Evaluate a dot-path condition against values
+/// Copy one `import-values` entry from a subchart's values into `imported`
 ///
-/// Supports paths like "redis.enabled", "features.cache.memory"
-fn evaluate_condition_path(condition: &str, values: &serde_json::Value) -> bool {
-    let parts: Vec<&str> = condition.split('.').collect();
-
-    let mut current = values;
-    for part in &parts {
-        match current.get(part) {
-            Some(v) => current = v,
-            None => return false,
+/// Returns a warning message when the child path doesn't exist.
+fn import_value(
+    imported: &mut Values,
+    subchart_values: &Values,
+    import: &ImportValue,
+) -> Result<(), String> {
+    let child_path = import.child_path();
+    let value = subchart_values
+        .get(&child_path)
+        .ok_or_else(|| format!("import path '{}' not found", child_path))?;
+
+    let layer = match import.parent_path() {
+        "" if value.is_object() => Values::from(value.clone()),
+        "" => {
+            return Err(format!(
+                "import path '{}' is not a map and can't be merged into the root",
+                child_path
+            ));
         }
-    }
+        parent_path => {
+            let mut layer = Values::new();
+            layer
+                .set(parent_path, value.clone())
+                .map_err(|e| format!("can't import to '{}': {}", parent_path, e))?;
+            layer
+        }
+    };
 
-    // Coerce to boolean
-    match current {
-        serde_json::Value::Bool(b) => *b,
-        serde_json::Value::Null => false,
-        serde_json::Value::String(s) => !s.is_empty() && s != "false" && s != "0",
-        serde_json::Value::Number(n) => n.as_f64().map(|f| f != 0.0).unwrap_or(false),
-        serde_json::Value::Array(a) => !a.is_empty(),
-        serde_json::Value::Object(o) => !o.is_empty(),
-    }
+    imported.merge(&layer);
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn write_file(root: &std::path::Path, path: &str, content: &str) {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    fn dependency(condition: Option<&str>, tags: &[&str]) -> Option<Dependency> {
+        Some(Dependency {
+            name: "redis".to_string(),
+            version: "1.0.0".to_string(),
+            repository: String::new(),
+            git: None,
+            enabled: true,
+            condition: condition.map(String::from),
+            resolve: Default::default(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            alias: None,
+            import_values: vec![],
+        })
+    }
+
     #[test]
-    fn test_evaluate_condition_path_bool() {
+    fn test_evaluate_condition_bool() {
+        let renderer = PackRenderer::new(Engine::lenient());
         let values = serde_json::json!({
             "redis": {
                 "enabled": true
@@ -514,22 +837,32 @@ mod tests {
             }
         });
 
-        assert!(evaluate_condition_path("redis.enabled", &values));
-        assert!(!evaluate_condition_path("postgresql.enabled", &values));
+        let (enabled, _) =
+            renderer.evaluate_condition(&dependency(Some("redis.enabled"), &[]), &values);
+        assert!(enabled);
+
+        let (enabled, reason) =
+            renderer.evaluate_condition(&dependency(Some("postgresql.enabled"), &[]), &values);
+        assert!(!enabled);
+        assert!(reason.unwrap().contains("postgresql.enabled"));
     }
 
     #[test]
-    fn test_evaluate_condition_path_missing() {
+    fn test_evaluate_condition_missing_is_enabled() {
+        let renderer = PackRenderer::new(Engine::lenient());
         let values = serde_json::json!({
             "redis": {}
         });
 
-        assert!(!evaluate_condition_path("redis.enabled", &values));
-        assert!(!evaluate_condition_path("nonexistent.path", &values));
+        let (enabled, reason) =
+            renderer.evaluate_condition(&dependency(Some("redis.enabled"), &[]), &values);
+        assert!(enabled);
+        assert!(reason.is_none());
     }
 
     #[test]
-    fn test_evaluate_condition_path_truthy() {
+    fn test_evaluate_condition_truthy() {
+        let renderer = PackRenderer::new(Engine::lenient());
         let values = serde_json::json!({
             "string_yes": "yes",
             "string_empty": "",
@@ -538,13 +871,36 @@ mod tests {
             "array_full": [1, 2],
             "array_empty": []
         });
+        let enabled = |condition| {
+            renderer
+                .evaluate_condition(&dependency(Some(condition), &[]), &values)
+                .0
+        };
+
+        assert!(enabled("string_yes"));
+        assert!(!enabled("string_empty"));
+        assert!(enabled("number_one"));
+        assert!(!enabled("number_zero"));
+        assert!(enabled("array_full"));
+        assert!(!enabled("array_empty"));
+    }
+
+    #[test]
+    fn test_evaluate_condition_tags() {
+        let renderer = PackRenderer::new(Engine::lenient());
+        let values = serde_json::json!({
+            "tags": { "cache": false },
+            "redis": { "enabled": true }
+        });
+
+        let (enabled, reason) = renderer.evaluate_condition(&dependency(None, &["cache"]), &values);
+        assert!(!enabled);
+        assert!(reason.unwrap().contains("cache"));
 
-        assert!(evaluate_condition_path("string_yes", &values));
-        assert!(!evaluate_condition_path("string_empty", &values));
-        assert!(evaluate_condition_path("number_one", &values));
-        assert!(!evaluate_condition_path("number_zero", &values));
-        assert!(evaluate_condition_path("array_full", &values));
-        assert!(!evaluate_condition_path("array_empty", &values));
+        // An explicit condition wins over tags
+        let (enabled, _) =
+            renderer.evaluate_condition(&dependency(Some("redis.enabled"), &["cache"]), &values);
+        assert!(enabled);
     }
 
     #[test]
@@ -564,7 +920,9 @@ mod tests {
     fn test_pack_render_result_with_report_success() {
         let result = PackRenderResultWithReport {
             manifests: IndexMap::new(),
+            source_paths: IndexMap::new(),
             notes: None,
+            subchart_notes: IndexMap::new(),
             report: RenderReport::new(),
             discovery: DiscoveryResult::new(),
         };
@@ -665,6 +1023,16 @@ postgresql:
         // Should have redis subchart manifest (prefixed)
         assert!(result.manifests.contains_key("redis/deployment.yaml"));
 
+        // Source paths follow the pack's directory layout
+        assert_eq!(
+            result.source_paths["deployment.yaml"],
+            "templates/deployment.yaml"
+        );
+        assert_eq!(
+            result.source_paths["redis/deployment.yaml"],
+            "charts/redis/templates/deployment.yaml"
+        );
+
         // Should NOT have postgresql manifest (disabled)
         let has_postgresql = result
             .manifests
@@ -743,4 +1111,574 @@ postgresql:
             "Should use global imageRegistry"
         );
     }
+
+    #[test]
+    fn test_global_registry_reaches_subchart() {
+        use sherpack_core::ReleaseInfo;
+
+        let dir = tempfile::tempdir().unwrap();
+        let write = |path: &str, content: &str| write_file(dir.path(), path, content);
+
+        write(
+            "Pack.yaml",
+            "apiVersion: sherpack/v1\nkind: application\nmetadata:\n  name: parent\n  version: 1.0.0\n",
+        );
+        write("values.yaml", "global:\n  registry: registry.example.com\n");
+        write("templates/.keep", "");
+        write(
+            "charts/cache/Pack.yaml",
+            "apiVersion: sherpack/v1\nkind: application\nmetadata:\n  name: cache\n  version: 1.0.0\n",
+        );
+        write(
+            "charts/cache/values.yaml",
+            "global:\n  registry: docker.io\n  pullPolicy: IfNotPresent\n",
+        );
+        write(
+            "charts/cache/templates/pod.yaml",
+            "image: {{ values.global.registry }}/cache\npullPolicy: {{ values.global.pullPolicy }}\n",
+        );
+
+        let pack = LoadedPack::load(dir.path()).unwrap();
+        let values = Values::from_file(&pack.values_path).unwrap();
+        let release = ReleaseInfo::for_install("test", "default");
+        let context = TemplateContext::new(values, release, &pack.pack.metadata);
+
+        let result = PackRenderer::new(Engine::strict())
+            .render(&pack, &context)
+            .unwrap();
+
+        let manifest = result.manifests.get("cache/pod.yaml").unwrap();
+        assert!(manifest.contains("image: registry.example.com/cache"));
+        // Globals the parent doesn't set keep the subchart's defaults
+        assert!(manifest.contains("pullPolicy: IfNotPresent"));
+    }
+
+    #[test]
+    fn test_grandchild_reads_grandparent_global() {
+        use sherpack_core::ReleaseInfo;
+
+        let dir = tempfile::tempdir().unwrap();
+        let write = |path: &str, content: &str| write_file(dir.path(), path, content);
+        let pack_yaml = |name: &str, deps: &str| {
+            format!(
+                "apiVersion: sherpack/v1\nkind: application\nmetadata:\n  name: {}\n  version: 1.0.0\n{}",
+                name, deps
+            )
+        };
+
+        write("values.yaml", "global:\n  registry: registry.example.com\n");
+        write(
+            "templates/app.yaml",
+            "version: {{ values.schemaVersion | default('none') }}\n",
+        );
+        write(
+            "charts/api/Pack.yaml",
+            &pack_yaml(
+                "api",
+                "dependencies:\n  - name: db\n    version: 1.0.0\n    repository: file://charts/db\n    import-values:\n      - child: schemaVersion\n        parent: schemaVersion\n",
+            ),
+        );
+        write("charts/api/templates/api.yaml", "kind: Service\n");
+        write(
+            "Pack.yaml",
+            &pack_yaml(
+                "root",
+                "dependencies:\n  - name: api\n    version: 1.0.0\n    repository: file://charts/api\n    import-values:\n      - child: schemaVersion\n        parent: schemaVersion\n",
+            ),
+        );
+        write("charts/api/charts/db/Pack.yaml", &pack_yaml("db", ""));
+        write("charts/api/charts/db/values.yaml", "schemaVersion: 7\n");
+        write(
+            "charts/api/charts/db/templates/db.yaml",
+            "image: {{ values.global.registry }}/db\n",
+        );
+
+        let pack = LoadedPack::load(dir.path()).unwrap();
+        let values = Values::from_file(&pack.values_path).unwrap();
+        let release = ReleaseInfo::for_install("test", "default");
+        let context = TemplateContext::new(values, release, &pack.pack.metadata);
+
+        let result = PackRenderer::new(Engine::strict()).render_collect_errors(&pack, &context);
+        assert!(result.is_success(), "{:?}", result.report);
+        assert_eq!(
+            result.manifests["api/db/db.yaml"].trim(),
+            "image: registry.example.com/db"
+        );
+        // Imports pass up through every level
+        assert_eq!(result.manifests["app.yaml"].trim(), "version: 7");
+
+        let tree: Vec<_> = result
+            .discovery
+            .subcharts
+            .iter()
+            .map(|s| (s.qualified_name(), s.depth))
+            .collect();
+        assert_eq!(
+            tree,
+            vec![("api".to_string(), 1), ("api/db".to_string(), 2)]
+        );
+    }
+
+    #[test]
+    fn test_subchart_notes_are_aggregated() {
+        use sherpack_core::ReleaseInfo;
+
+        let dir = tempfile::tempdir().unwrap();
+        let write = |path: &str, content: &str| write_file(dir.path(), path, content);
+        let pack_yaml = |name: &str| {
+            format!(
+                "apiVersion: sherpack/v1\nkind: application\nmetadata:\n  name: {}\n  version: 1.0.0\n",
+                name
+            )
+        };
+
+        write("Pack.yaml", &pack_yaml("web"));
+        write("templates/app.yaml", "kind: Service\n");
+        write(
+            "templates/NOTES.txt",
+            "Visit {{ release.name }}.example.com\n",
+        );
+        write("charts/redis/Pack.yaml", &pack_yaml("redis"));
+        write("charts/redis/templates/redis.yaml", "kind: StatefulSet\n");
+        write(
+            "charts/redis/templates/NOTES.txt",
+            "Redis password is in secret {{ release.name }}-redis\n",
+        );
+        write("charts/cache/Pack.yaml", &pack_yaml("cache"));
+        write("charts/cache/templates/cache.yaml", "kind: Deployment\n");
+
+        let pack = LoadedPack::load(dir.path()).unwrap();
+        let release = ReleaseInfo::for_install("shop", "default");
+        let context = TemplateContext::new(Values::new(), release, &pack.pack.metadata);
+        let result = PackRenderer::new(Engine::lenient())
+            .render(&pack, &context)
+            .unwrap();
+
+        // Subcharts without notes are left out
+        assert_eq!(result.subchart_notes.keys().collect::<Vec<_>>(), ["redis"]);
+        assert_eq!(
+            result.combined_notes(false).as_deref(),
+            Some("Visit shop.example.com")
+        );
+        assert_eq!(
+            result.combined_notes(true).as_deref(),
+            Some(
+                "Visit shop.example.com\n\n\
+                 --- Subchart redis ---\n\
+                 Redis password is in secret shop-redis"
+            )
+        );
+    }
+
+    #[test]
+    fn test_subchart_cycle_is_an_error() {
+        use sherpack_core::ReleaseInfo;
+
+        let dir = tempfile::tempdir().unwrap();
+        let write = |path: &str, content: &str| write_file(dir.path(), path, content);
+        let pack_yaml = |name: &str| {
+            format!(
+                "apiVersion: sherpack/v1\nkind: application\nmetadata:\n  name: {}\n  version: 1.0.0\n",
+                name
+            )
+        };
+
+        write("Pack.yaml", &pack_yaml("root"));
+        write("templates/app.yaml", "kind: ConfigMap\n");
+        write("charts/a/Pack.yaml", &pack_yaml("a"));
+        write("charts/a/charts/b/Pack.yaml", &pack_yaml("b"));
+        write("charts/a/charts/b/charts/a/Pack.yaml", &pack_yaml("a"));
+
+        let pack = LoadedPack::load(dir.path()).unwrap();
+        let release = ReleaseInfo::for_install("test", "default");
+        let context = TemplateContext::new(Values::new(), release, &pack.pack.metadata);
+
+        let result = PackRenderer::new(Engine::strict()).render_collect_errors(&pack, &context);
+        let errors = &result.report.errors_by_template["a/b/<subchart:a>"];
+        assert!(
+            errors[0]
+                .to_string()
+                .contains("Subchart dependency cycle: root -> a -> b -> a"),
+            "{}",
+            errors[0]
+        );
+    }
+
+    #[test]
+    fn test_import_value_forms() {
+        let subchart = Values::from_yaml(
+            r#"
+exports:
+  data:
+    host: db.local
+service:
+  port: 5432
+"#,
+        )
+        .unwrap();
+        let mut imported = Values::new();
+
+        import_value(
+            &mut imported,
+            &subchart,
+            &ImportValue::Key("data".to_string()),
+        )
+        .unwrap();
+        import_value(
+            &mut imported,
+            &subchart,
+            &ImportValue::Mapping {
+                child: "service.port".to_string(),
+                parent: "database.port".to_string(),
+            },
+        )
+        .unwrap();
+
+        assert_eq!(imported.get("host").unwrap(), "db.local");
+        assert_eq!(imported.get("database.port").unwrap(), 5432);
+
+        let err = import_value(
+            &mut imported,
+            &subchart,
+            &ImportValue::Key("missing".to_string()),
+        )
+        .unwrap_err();
+        assert!(err.contains("exports.missing"));
+    }
+
+    #[test]
+    fn test_import_values_from_subchart() {
+        use sherpack_core::ReleaseInfo;
+
+        let dir = tempfile::tempdir().unwrap();
+        let write = |path: &str, content: &str| write_file(dir.path(), path, content);
+
+        write(
+            "Pack.yaml",
+            r#"apiVersion: sherpack/v1
+kind: application
+metadata:
+  name: parent
+  version: 1.0.0
+dependencies:
+  - name: db
+    version: "1.0.0"
+    repository: "file://charts/db"
+    import-values:
+      - child: service.port
+        parent: database.port
+      - child: service.missing
+        parent: database.other
+"#,
+        );
+        write("values.yaml", "db:\n  service:\n    port: 5433\n");
+        write("templates/app.yaml", "port: {{ values.database.port }}\n");
+        write(
+            "charts/db/Pack.yaml",
+            "apiVersion: sherpack/v1\nkind: application\nmetadata:\n  name: db\n  version: 1.0.0\n",
+        );
+        write("charts/db/values.yaml", "service:\n  port: 5432\n");
+        write("charts/db/templates/.keep", "");
+
+        let pack = LoadedPack::load(dir.path()).unwrap();
+        let values = Values::from_file(&pack.values_path).unwrap();
+        let release = ReleaseInfo::for_install("test", "default");
+        let context = TemplateContext::new(values, release, &pack.pack.metadata);
+
+        let result = PackRenderer::new(Engine::strict()).render_collect_errors(&pack, &context);
+
+        assert!(result.is_success());
+        // The parent sees the subchart's computed value, not its default
+        assert_eq!(
+            result.manifests.get("app.yaml").unwrap().trim(),
+            "port: 5433"
+        );
+        assert!(result.report.issues.iter().any(|issue| {
+            issue.category == "subchart_import" && issue.message.contains("service.missing")
+        }));
+    }
+
+    #[test]
+    fn test_library_dependency_provides_macros() {
+        use sherpack_core::ReleaseInfo;
+
+        let dir = tempfile::tempdir().unwrap();
+        let write = |path: &str, content: &str| write_file(dir.path(), path, content);
+
+        write(
+            "Pack.yaml",
+            r#"apiVersion: sherpack/v1
+kind: application
+metadata:
+  name: parent
+  version: 1.0.0
+dependencies:
+  - name: common
+    version: "1.0.0"
+    repository: "file://charts/common"
+"#,
+        );
+        write(
+            "values.yaml",
+            "app: web
+",
+        );
+        write(
+            "templates/app.yaml",
+            "{% import \"common/_helpers.j2\" as common %}\nname: {{ common.fullname(values.app) }}\n",
+        );
+        write(
+            "charts/common/Pack.yaml",
+            "apiVersion: sherpack/v1\nkind: library\nmetadata:\n  name: common\n  version: 1.0.0\n",
+        );
+        write(
+            "charts/common/templates/_helpers.j2",
+            "{% macro fullname(app) %}{{ release.name }}-{{ app }}{% endmacro %}\n",
+        );
+        write("charts/common/templates/stray.yaml", "kind: ConfigMap\n");
+
+        let pack = LoadedPack::load(dir.path()).unwrap();
+        let values = Values::from_file(&pack.values_path).unwrap();
+        let release = ReleaseInfo::for_install("test", "default");
+        let context = TemplateContext::new(values, release, &pack.pack.metadata);
+
+        let result = PackRenderer::new(Engine::strict()).render_collect_errors(&pack, &context);
+
+        assert!(result.is_success(), "{:?}", result.report);
+        // The library's macros are importable, its own templates are not rendered
+        assert_eq!(
+            result.manifests.get("app.yaml").unwrap().trim(),
+            "name: test-web"
+        );
+        assert_eq!(result.manifests.len(), 1);
+
+        // On its own, a library pack renders nothing
+        let library = LoadedPack::load(dir.path().join("charts/common")).unwrap();
+        let context = TemplateContext::new(
+            Values::new(),
+            ReleaseInfo::for_install("test", "default"),
+            &library.pack.metadata,
+        );
+        let result = PackRenderer::new(Engine::strict()).render_collect_errors(&library, &context);
+        assert!(result.manifests.is_empty());
+        assert!(
+            result
+                .report
+                .issues
+                .iter()
+                .any(|issue| issue.category == "library")
+        );
+    }
+
+    #[test]
+    fn test_render_aliased_dependency_twice() {
+        use sherpack_core::ReleaseInfo;
+
+        let dir = tempfile::tempdir().unwrap();
+        let write = |path: &str, content: &str| write_file(dir.path(), path, content);
+
+        write(
+            "Pack.yaml",
+            r#"apiVersion: sherpack/v1
+kind: application
+metadata:
+  name: parent
+  version: 1.0.0
+dependencies:
+  - name: redis
+    version: "1.0.0"
+    repository: "file://charts/redis"
+    alias: primary
+  - name: redis
+    version: "1.0.0"
+    repository: "file://charts/redis"
+    alias: replica
+"#,
+        );
+        write(
+            "values.yaml",
+            "primary:\n  role: master\nreplica:\n  replicas: 3\n",
+        );
+        write("templates/app.yaml", "kind: ConfigMap\n");
+        write(
+            "charts/redis/Pack.yaml",
+            "apiVersion: sherpack/v1\nkind: application\nmetadata:\n  name: redis\n  version: 1.0.0\n",
+        );
+        write(
+            "charts/redis/values.yaml",
+            "role: standalone\nreplicas: 1\n",
+        );
+        write(
+            "charts/redis/templates/redis.yaml",
+            "role: {{ values.role }}\nreplicas: {{ values.replicas }}\n",
+        );
+
+        let pack = LoadedPack::load(dir.path()).unwrap();
+        let values = Values::from_file(&pack.values_path).unwrap();
+        let release = ReleaseInfo::for_install("test", "default");
+        let context = TemplateContext::new(values, release, &pack.pack.metadata);
+
+        let result = PackRenderer::new(Engine::strict()).render_collect_errors(&pack, &context);
+
+        assert!(result.is_success());
+        assert!(result.discovery.missing.is_empty());
+        // Each alias is scoped under its own values; the shared pack isn't rendered itself
+        assert_eq!(
+            result.manifests.get("primary/redis.yaml").unwrap().trim(),
+            "role: master\nreplicas: 1"
+        );
+        assert_eq!(
+            result.manifests.get("replica/redis.yaml").unwrap().trim(),
+            "role: standalone\nreplicas: 3"
+        );
+        assert!(!result.manifests.keys().any(|k| k.starts_with("redis/")));
+    }
+
+    #[test]
+    fn test_render_reports_every_broken_template() {
+        use sherpack_core::ReleaseInfo;
+
+        let dir = tempfile::tempdir().unwrap();
+        let write = |path: &str, content: &str| write_file(dir.path(), path, content);
+
+        write(
+            "Pack.yaml",
+            "apiVersion: sherpack/v1\nkind: application\nmetadata:\n  name: broken\n  version: 1.0.0\n",
+        );
+        write("templates/a-syntax.yaml", "name: {{ values.name\n");
+        write("templates/b-ok.yaml", "kind: ConfigMap\n");
+        write(
+            "templates/c-runtime.yaml",
+            "replicas: {{ fail(\"no replicas\") }}\n",
+        );
+
+        let pack = LoadedPack::load(dir.path()).unwrap();
+        let release = ReleaseInfo::for_install("test", "default");
+        let context = TemplateContext::new(Values::new(), release, &pack.pack.metadata);
+
+        let result = PackRenderer::new(Engine::strict()).render_collect_errors(&pack, &context);
+        let report = &result.report;
+        assert_eq!(report.templates_with_errors(), 2);
+        assert!(report.errors_by_template.contains_key("a-syntax.yaml"));
+        assert!(
+            report.errors_by_template["a-syntax.yaml"][0]
+                .line()
+                .is_some()
+        );
+        assert!(report.errors_by_template.contains_key("c-runtime.yaml"));
+        assert!(result.manifests.contains_key("b-ok.yaml"));
+
+        let engine = Engine::builder().fail_fast(true).build();
+        let result = PackRenderer::new(engine).render_collect_errors(&pack, &context);
+        assert_eq!(result.report.templates_with_errors(), 1);
+        assert!(
+            result
+                .report
+                .errors_by_template
+                .contains_key("a-syntax.yaml")
+        );
+    }
+
+    #[test]
+    fn test_render_archive_pack_reads_files_from_archive() {
+        use sherpack_core::ReleaseInfo;
+
+        let dir = tempfile::tempdir().unwrap();
+        let write = |path: &str, content: &str| write_file(dir.path(), path, content);
+
+        write(
+            "Pack.yaml",
+            "apiVersion: sherpack/v1\nkind: application\nmetadata:\n  name: packaged\n  version: 1.0.0\n",
+        );
+        write("values.yaml", "greeting: hello\n");
+        write(
+            "templates/cm.yaml",
+            "data: {{ files.get(\"values.yaml\") | trim }}\n",
+        );
+
+        let mut pack = LoadedPack::load(dir.path()).unwrap();
+        let archive = dir.path().join("packaged.tar.gz");
+        sherpack_core::create_archive(&pack, &archive).unwrap();
+        pack.archive = Some(archive);
+
+        // Files must come from the archive, not the directory
+        write("values.yaml", "greeting: tampered\n");
+
+        let release = ReleaseInfo::for_install("test", "default");
+        let context = TemplateContext::new(Values::new(), release, &pack.pack.metadata);
+        let result = PackRenderer::new(Engine::strict())
+            .render(&pack, &context)
+            .unwrap();
+        assert_eq!(result.manifests["cm.yaml"].trim(), "data: greeting: hello");
+    }
+
+    #[test]
+    fn test_render_skips_disabled_dependencies() {
+        use sherpack_core::ReleaseInfo;
+
+        let dir = tempfile::tempdir().unwrap();
+        let write = |path: &str, content: &str| write_file(dir.path(), path, content);
+
+        write(
+            "Pack.yaml",
+            r#"apiVersion: sherpack/v1
+kind: application
+metadata:
+  name: parent
+  version: 1.0.0
+dependencies:
+  - name: redis
+    version: "1.0.0"
+    repository: "file://charts/redis"
+    condition: redis.enabled
+  - name: api
+    version: "1.0.0"
+    repository: "file://charts/api"
+    tags: [backend]
+  - name: worker
+    version: "1.0.0"
+    repository: "file://charts/worker"
+    tags: [backend]
+"#,
+        );
+        write("templates/app.yaml", "kind: ConfigMap\n");
+        for name in ["redis", "api", "worker"] {
+            write(
+                &format!("charts/{}/Pack.yaml", name),
+                &format!(
+                    "apiVersion: sherpack/v1\nkind: application\nmetadata:\n  name: {}\n  version: 1.0.0\n",
+                    name
+                ),
+            );
+            write(
+                &format!("charts/{}/templates/cm.yaml", name),
+                &format!("name: {}\n", name),
+            );
+        }
+
+        let pack = LoadedPack::load(dir.path()).unwrap();
+        let render = |values: &str| {
+            let values = Values::from_yaml(values).unwrap();
+            let release = ReleaseInfo::for_install("test", "default");
+            let context = TemplateContext::new(values, release, &pack.pack.metadata);
+            let result = PackRenderer::new(Engine::strict()).render_collect_errors(&pack, &context);
+            assert!(result.is_success());
+            let mut names: Vec<_> = result
+                .manifests
+                .keys()
+                .filter_map(|k| k.split_once('/').map(|(name, _)| name.to_string()))
+                .collect();
+            names.sort();
+            names
+        };
+
+        // Missing condition path and unset tags leave everything enabled
+        assert_eq!(render("{}"), ["api", "redis", "worker"]);
+
+        // A false condition disables its subchart
+        assert_eq!(render("redis:\n  enabled: false\n"), ["api", "worker"]);
+
+        // A tag toggles its whole group
+        assert_eq!(render("tags:\n  backend: false\n"), ["redis"]);
+    }
 }
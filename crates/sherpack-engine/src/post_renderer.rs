@@ -0,0 +1,130 @@
+//! Post-rendering of manifests by external tools
+//!
+//! A post-renderer receives the rendered manifest (all documents joined with
+//! `---`) and returns a transformed manifest. This is the hook for tools like
+//! kustomize that need to patch output before it is applied, similar to
+//! Helm's `--post-renderer`.
+
+use std::fmt::Debug;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::error::{EngineError, Result};
+
+/// Transforms a rendered multi-document manifest
+pub trait PostRenderer: Debug + Send + Sync {
+    /// Return the transformed manifest
+    fn post_render(&self, manifest: &str) -> Result<String>;
+}
+
+/// Post-renderer that pipes the manifest through an external command
+///
+/// The manifest is written to the command's stdin and the transformed YAML
+/// is read from its stdout. A non-zero exit status is an error carrying the
+/// command's stderr.
+#[derive(Debug, Clone)]
+pub struct ExecPostRenderer {
+    command: PathBuf,
+    args: Vec<String>,
+}
+
+impl ExecPostRenderer {
+    /// Create a post-renderer running `command`
+    pub fn new(command: impl Into<PathBuf>) -> Self {
+        Self {
+            command: command.into(),
+            args: Vec::new(),
+        }
+    }
+
+    /// Pass extra arguments to the command
+    pub fn with_args(mut self, args: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.args = args.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// The command being run
+    pub fn command(&self) -> &Path {
+        &self.command
+    }
+
+    fn error(&self, message: impl std::fmt::Display) -> EngineError {
+        EngineError::PostRender {
+            command: self.command.display().to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl PostRenderer for ExecPostRenderer {
+    fn post_render(&self, manifest: &str) -> Result<String> {
+        let mut child = Command::new(&self.command)
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| self.error(e))?;
+
+        // Feed stdin from another thread so a command that streams its output
+        // can't deadlock on a full pipe
+        let mut stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| self.error("stdin unavailable"))?;
+        let input = manifest.to_string();
+        let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+
+        let output = child.wait_with_output().map_err(|e| self.error(e))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(self.error(format!("{}: {}", output.status, stderr.trim())));
+        }
+
+        // Commands may legitimately stop reading early; only report a failed
+        // write once the command itself succeeded
+        match writer.join() {
+            Ok(Err(e)) if e.kind() != std::io::ErrorKind::BrokenPipe => {
+                return Err(self.error(e));
+            }
+            Err(_) => return Err(self.error("stdin writer panicked")),
+            _ => {}
+        }
+
+        String::from_utf8(output.stdout).map_err(|_| self.error("output is not valid UTF-8"))
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exec_post_renderer_transforms_stdin() {
+        let renderer = ExecPostRenderer::new("sed").with_args(["s/replicas: 1/replicas: 3/"]);
+
+        let output = renderer
+            .post_render("kind: Deployment\nspec:\n  replicas: 1\n")
+            .unwrap();
+
+        assert_eq!(output, "kind: Deployment\nspec:\n  replicas: 3\n");
+    }
+
+    #[test]
+    fn test_exec_post_renderer_failure() {
+        let renderer = ExecPostRenderer::new("sh").with_args(["-c", "echo broken >&2; exit 3"]);
+
+        let err = renderer.post_render("kind: ConfigMap\n").unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("sh"));
+        assert!(message.contains("broken"));
+    }
+
+    #[test]
+    fn test_exec_post_renderer_missing_command() {
+        let renderer = ExecPostRenderer::new("/nonexistent/post-renderer");
+        assert!(renderer.post_render("kind: ConfigMap\n").is_err());
+    }
+}
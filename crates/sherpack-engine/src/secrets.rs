@@ -12,7 +12,16 @@
 //! # Generate a 32-char hex secret
 //! token: {{ generate_secret("api-token", 32, "hex") }}
 //!
-//! # Supported charsets: alphanumeric, alpha, numeric, hex, base64, urlsafe
+//! # Supported charsets: alphanumeric, alpha, numeric, hex, base64, urlsafe, complex
+//!
+//! # At least one lowercase, uppercase, digit and symbol, without O/0, l/1...
+//! admin: {{ generate_secret("admin-password", 20, "complex", exclude_ambiguous=true) }}
+//!
+//! # Only the given characters
+//! pin: {{ generate_secret("pin", 6, chars="2345679") }}
+//!
+//! # Value before the last `sherpack secret rotate` (empty if never rotated)
+//! old-password: {{ previous_secret("db-password") }}
 //! ```
 //!
 //! # How It Works
@@ -23,6 +32,10 @@
 //! 2. **Subsequent renders**: Same values are returned from state
 //! 3. **Result**: Deterministic output, GitOps compatible
 //!
+//! With [`SecretFunctionState::with_seed`], values are derived from the seed
+//! instead, so renders are reproducible even without persisted state. This
+//! is meant for dev and CI environments only: the seed reveals every secret.
+//!
 //! # Integration
 //!
 //! ```rust,no_run
@@ -45,8 +58,9 @@
 //! }
 //! ```
 
+use minijinja::value::Kwargs;
 use minijinja::{Environment, Error, ErrorKind};
-use sherpack_core::{SecretCharset, SecretGenerator, SecretState};
+use sherpack_core::{SecretCharset, SecretGenerator, SecretPolicy, SecretState};
 use std::sync::Arc;
 
 /// Wrapper around SecretGenerator for MiniJinja integration
@@ -82,6 +96,24 @@ impl SecretFunctionState {
         }
     }
 
+    /// Derive new secrets from `seed` instead of generating them randomly
+    ///
+    /// **Not for production**: anyone who knows the seed can recompute every
+    /// generated secret.
+    pub fn with_seed(self, seed: impl AsRef<[u8]>) -> Self {
+        {
+            let mut generator = self.generator.lock().unwrap();
+            let unseeded = std::mem::take(&mut *generator);
+            *generator = unseeded.with_seed(seed);
+        }
+        self
+    }
+
+    /// Whether new secrets are derived from a seed
+    pub fn is_seeded(&self) -> bool {
+        self.generator.lock().unwrap().is_seeded()
+    }
+
     /// Check if any new secrets were generated
     pub fn is_dirty(&self) -> bool {
         self.generator.lock().unwrap().is_dirty()
@@ -97,26 +129,52 @@ impl SecretFunctionState {
         state.into_state()
     }
 
-    /// Register the `generate_secret` function on a MiniJinja environment
+    /// Register the `generate_secret` and `previous_secret` functions on a
+    /// MiniJinja environment
     ///
     /// # Arguments accepted by the function
     ///
     /// - `name` (required): Unique identifier for this secret
     /// - `length` (required): Length of the secret in characters
-    /// - `charset` (optional): One of: alphanumeric, alpha, numeric, hex, base64, urlsafe
+    /// - `charset` (optional): One of: alphanumeric, alpha, numeric, hex, base64,
+    ///   urlsafe, complex
+    /// - `chars=` (optional keyword): Custom characters to draw from, instead of `charset`
+    /// - `exclude_ambiguous=` (optional keyword): Leave out characters like `O`/`0`
     ///
     /// # Example
     ///
     /// ```jinja2
     /// {{ generate_secret("my-password", 24) }}
     /// {{ generate_secret("hex-token", 32, "hex") }}
+    /// {{ generate_secret("admin", 16, "complex", exclude_ambiguous=true) }}
+    /// {{ previous_secret("my-password") }}
     /// ```
+    ///
+    /// `previous_secret(name)` returns the value the secret had before its
+    /// last rotation, or an empty string if it was never rotated.
     pub fn register(&self, env: &mut Environment<'static>) {
+        let generator = Arc::clone(&self.generator);
+        env.add_function(
+            "previous_secret",
+            move |name: String| -> Result<String, Error> {
+                let secret_gen = generator.lock().unwrap();
+                Ok(secret_gen
+                    .state()
+                    .get_previous_value(&name)
+                    .unwrap_or_default()
+                    .to_string())
+            },
+        );
+
         let generator = Arc::clone(&self.generator);
 
         env.add_function(
             "generate_secret",
-            move |name: String, length: i64, charset: Option<String>| -> Result<String, Error> {
+            move |name: String,
+                  length: i64,
+                  charset: Option<String>,
+                  kwargs: Kwargs|
+                  -> Result<String, Error> {
                 // Validate name
                 if name.is_empty() {
                     return Err(Error::new(
@@ -140,29 +198,47 @@ impl SecretFunctionState {
                     ));
                 }
 
-                // Parse optional charset
-                let charset = match charset {
-                    Some(ref charset_str) => {
+                let chars: Option<String> = kwargs.get("chars")?;
+                let exclude_ambiguous: Option<bool> = kwargs.get("exclude_ambiguous")?;
+                kwargs.assert_all_used()?;
+
+                // Parse optional charset, or custom characters
+                let charset = match (charset, chars) {
+                    (Some(_), Some(_)) => {
+                        return Err(Error::new(
+                            ErrorKind::InvalidOperation,
+                            "generate_secret: pass either a charset or chars=, not both",
+                        ));
+                    }
+                    (None, Some(chars)) => SecretCharset::custom(chars),
+                    (Some(ref charset_str), None) => {
                         SecretCharset::parse(charset_str).ok_or_else(|| {
                             Error::new(
                                 ErrorKind::InvalidOperation,
                                 format!(
                                     "generate_secret: unknown charset '{}'. Valid options: \
-                                 alphanumeric, alpha, numeric, hex, base64, urlsafe",
+                                 alphanumeric, alpha, numeric, hex, base64, urlsafe, complex \
+                                 (or chars=\"...\" for custom characters)",
                                     charset_str
                                 ),
                             )
                         })?
                     }
-                    None => SecretCharset::default(),
+                    (None, None) => SecretCharset::default(),
                 };
+                let mut policy = SecretPolicy::new(charset);
+                policy.exclude_ambiguous = exclude_ambiguous.unwrap_or(false);
 
                 // Generate or retrieve the secret
                 let mut secret_gen = generator.lock().unwrap();
-                let secret =
-                    secret_gen.get_or_generate_with_charset(&name, length as usize, charset);
-
-                Ok(secret)
+                secret_gen
+                    .get_or_generate_with_policy(&name, length as usize, &policy)
+                    .map_err(|e| {
+                        Error::new(
+                            ErrorKind::InvalidOperation,
+                            format!("generate_secret: {}", e),
+                        )
+                    })
             },
         );
     }
@@ -255,6 +331,63 @@ mod tests {
         assert!(err.to_string().contains("unknown charset"));
     }
 
+    #[test]
+    fn test_generate_secret_policies() {
+        let mut env = Environment::new();
+        let state = SecretFunctionState::new();
+        state.register(&mut env);
+
+        let complex = env
+            .render_str(r#"{{ generate_secret("admin", 4, "complex") }}"#, ())
+            .unwrap();
+        assert_eq!(complex.len(), 4);
+        assert!(complex.chars().any(|c| c.is_ascii_punctuation()));
+
+        let custom = env
+            .render_str(r#"{{ generate_secret("pin", 32, chars="xyz") }}"#, ())
+            .unwrap();
+        assert!(custom.chars().all(|c| "xyz".contains(c)));
+
+        let readable = env
+            .render_str(
+                r#"{{ generate_secret("readable", 256, exclude_ambiguous=true) }}"#,
+                (),
+            )
+            .unwrap();
+        assert!(
+            !readable
+                .chars()
+                .any(|c| sherpack_core::AMBIGUOUS_CHARS.contains(c))
+        );
+    }
+
+    #[test]
+    fn test_generate_secret_invalid_policies() {
+        let mut env = Environment::new();
+        let state = SecretFunctionState::new();
+        state.register(&mut env);
+
+        let err = env
+            .render_str(r#"{{ generate_secret("admin", 3, "complex") }}"#, ())
+            .unwrap_err();
+        assert!(err.to_string().contains("needs at least 4 characters"));
+
+        let err = env
+            .render_str(r#"{{ generate_secret("pin", 6, "hex", chars="abc") }}"#, ())
+            .unwrap_err();
+        assert!(err.to_string().contains("not both"));
+
+        let err = env
+            .render_str(r#"{{ generate_secret("pin", 6, chars="") }}"#, ())
+            .unwrap_err();
+        assert!(err.to_string().contains("custom charset is empty"));
+
+        assert!(
+            env.render_str(r#"{{ generate_secret("pin", 6, symbols=true) }}"#, ())
+                .is_err()
+        );
+    }
+
     #[test]
     fn test_generate_secret_missing_args() {
         let mut env = Environment::new();
@@ -340,6 +473,57 @@ mod tests {
         assert!(!state2.is_dirty());
     }
 
+    #[test]
+    fn test_previous_secret_after_rotation() {
+        let mut generator = sherpack_core::SecretGenerator::new();
+        let original = generator.get_or_generate("db-password", 16);
+        let rotated = generator.rotate("db-password").unwrap().unwrap();
+
+        let state = SecretFunctionState::with_state(generator.into_state());
+        let mut env = Environment::new();
+        state.register(&mut env);
+
+        let result = env
+            .render_str(
+                r#"{{ generate_secret("db-password", 16) }}/{{ previous_secret("db-password") }}"#,
+                (),
+            )
+            .unwrap();
+        assert_eq!(result, format!("{}/{}", rotated, original));
+    }
+
+    #[test]
+    fn test_previous_secret_never_rotated() {
+        let state = SecretFunctionState::new();
+        let mut env = Environment::new();
+        state.register(&mut env);
+
+        let result = env
+            .render_str(
+                r#"{{ generate_secret("api-key", 16) | length }}[{{ previous_secret("api-key") }}]"#,
+                (),
+            )
+            .unwrap();
+        assert_eq!(result, "16[]");
+    }
+
+    #[test]
+    fn test_seeded_renders_are_reproducible() {
+        let template = r#"{{ generate_secret("db-password", 20) }}"#;
+        let render = |state: SecretFunctionState| {
+            let mut env = Environment::new();
+            state.register(&mut env);
+            env.render_str(template, ()).unwrap()
+        };
+
+        let seeded = SecretFunctionState::new().with_seed("dev");
+        assert!(seeded.is_seeded());
+        let first = render(seeded);
+        assert_eq!(render(SecretFunctionState::new().with_seed("dev")), first);
+        assert_ne!(render(SecretFunctionState::new().with_seed("ci")), first);
+        assert!(!SecretFunctionState::new().is_seeded());
+    }
+
     #[test]
     fn test_multiple_secrets_in_template() {
         let state = SecretFunctionState::new();
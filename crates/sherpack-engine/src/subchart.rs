@@ -74,9 +74,23 @@ pub struct SubchartInfo {
 
     /// Reason if disabled
     pub disabled_reason: Option<String>,
+
+    /// Nesting depth (1 for subcharts of the pack being rendered)
+    pub depth: usize,
+
+    /// Qualified name of the parent subchart (`None` at depth 1)
+    pub parent: Option<String>,
 }
 
 impl SubchartInfo {
+    /// Name qualified by its parents, as used for manifest names (`api/redis`)
+    pub fn qualified_name(&self) -> String {
+        match &self.parent {
+            Some(parent) => format!("{}/{}", parent, self.name),
+            None => self.name.clone(),
+        }
+    }
+
     /// Check if this subchart should be rendered
     pub fn should_render(&self) -> bool {
         self.enabled
@@ -92,6 +106,9 @@ impl SubchartInfo {
 #[derive(Debug, Default)]
 pub struct DiscoveryResult {
     /// Successfully discovered subcharts
+    ///
+    /// After a render this holds the whole dependency tree, each subchart
+    /// listed before its own subcharts.
     pub subcharts: Vec<SubchartInfo>,
 
     /// Warnings during discovery (e.g., invalid Pack.yaml)
@@ -15,8 +15,15 @@ pub const AVAILABLE_FILTERS: &[&str] = &[
     "toyaml",
     "tojson",
     "tojson_pretty",
+    "totoml",
+    "fromtoml",
     "b64encode",
     "b64decode",
+    "b32encode",
+    "b32decode",
+    "urlencode",
+    "urlquery",
+    "urldecode",
     "quote",
     "squote",
     "nindent",
@@ -26,6 +33,7 @@ pub const AVAILABLE_FILTERS: &[&str] = &[
     "haskey",
     "keys",
     "merge",
+    "mergeoverwrite",
     "sha256",
     "trunc",
     "trimprefix",
@@ -33,6 +41,14 @@ pub const AVAILABLE_FILTERS: &[&str] = &[
     "snakecase",
     "kebabcase",
     "tostrings", // Convert list elements to strings
+    "date",
+    "date_in_zone",
+    "duration",
+    "ceil",
+    "floor",
+    "divf",
+    "semver_sort",
+    "semver_max",
     // Built-in MiniJinja filters
     "default",
     "upper",
@@ -68,13 +84,13 @@ pub const AVAILABLE_FILTERS: &[&str] = &[
     "safe",
     "escape",
     "e",
-    "urlencode",
 ];
 
 /// All registered functions in the engine
 pub const AVAILABLE_FUNCTIONS: &[&str] = &[
     // Custom Sherpack functions
     "fail",
+    "must_have",
     "dict",
     "list",
     "get",
@@ -85,6 +101,9 @@ pub const AVAILABLE_FUNCTIONS: &[&str] = &[
     "toint",
     "tofloat",
     "now",
+    "date",
+    "date_in_zone",
+    "duration",
     "printf",
     "tpl",     // Dynamic template evaluation
     "tpl_ctx", // Dynamic template with full context
@@ -238,6 +257,16 @@ pub fn suggest_unknown_function(func_name: &str) -> Option<String> {
     }
 }
 
+/// Tell the user how to set a missing value
+///
+/// `path` is the dotted path below `values`.
+pub fn suggest_missing_value(path: &str) -> String {
+    format!(
+        "Set `{}` in values.yaml or pass `--set {}=<value>`",
+        path, path
+    )
+}
+
 /// Extract available keys from a JSON value at a given path
 pub fn extract_available_keys(values: &JsonValue, path: &str) -> Vec<String> {
     let parts: Vec<&str> = path.split('.').filter(|s| !s.is_empty()).collect();
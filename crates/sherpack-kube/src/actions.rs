@@ -1,9 +1,13 @@
-//! Action options for install, upgrade, uninstall, and rollback operations
+//! Action options for install, upgrade, uninstall, rollback, and test operations
 
 use chrono::Duration;
 use serde::{Deserialize, Serialize};
+use sherpack_engine::PostRenderer;
+use std::sync::Arc;
 
 use crate::health::HealthCheckConfig;
+use crate::progress::ProgressSink;
+use crate::release::ValuesProvenance;
 use crate::storage::LargeReleaseStrategy;
 
 /// Options for install operation
@@ -27,9 +31,27 @@ pub struct InstallOptions {
     /// Automatically rollback on failure (only with wait=true)
     pub atomic: bool,
 
+    /// Timeout for the wait of an atomic install (defaults to `timeout`)
+    pub atomic_timeout: Option<Duration>,
+
     /// Create namespace if it doesn't exist
     pub create_namespace: bool,
 
+    /// Adopt existing resources that no release manages instead of failing
+    pub take_ownership: bool,
+
+    /// After applying, warn about fields the server changed from the manifest
+    pub verify_apply: bool,
+
+    /// Print container logs of hook Pods and Jobs while they run
+    pub hook_logs: bool,
+
+    /// Append the notes of enabled subcharts to the release notes
+    pub render_subchart_notes: bool,
+
+    /// Deploy even if the cluster doesn't satisfy the pack's `kubeVersion`
+    pub skip_kube_version_check: bool,
+
     /// Strategy for large releases
     pub large_release_strategy: LargeReleaseStrategy,
 
@@ -37,11 +59,20 @@ pub struct InstallOptions {
     pub skip_schema_validation: bool,
 
     /// Dry run mode (don't actually apply)
-    pub dry_run: bool,
+    pub dry_run: DryRunMode,
 
     /// Show diff before applying
     pub show_diff: bool,
 
+    /// Transform rendered (non-hook) manifests before they are applied
+    pub post_renderer: Option<Arc<dyn PostRenderer>>,
+
+    /// Receives a progress event for every resource transition
+    pub progress: Option<Arc<dyn ProgressSink>>,
+
+    /// Where the user-supplied values came from
+    pub values_provenance: ValuesProvenance,
+
     /// Custom labels to add to the release
     pub labels: std::collections::HashMap<String, String>,
 
@@ -74,15 +105,38 @@ impl InstallOptions {
         self
     }
 
+    /// Set a dedicated timeout for the atomic wait
+    pub fn with_atomic_timeout(mut self, timeout: Duration) -> Self {
+        self.atomic_timeout = Some(timeout);
+        self
+    }
+
+    /// Timeout for the post-apply wait
+    ///
+    /// `atomic_timeout` in atomic mode, falling back to `timeout`.
+    pub fn wait_timeout(&self) -> Option<Duration> {
+        if self.atomic {
+            self.atomic_timeout.or(self.timeout)
+        } else {
+            self.timeout
+        }
+    }
+
     /// Enable health checks
     pub fn with_health_check(mut self, config: HealthCheckConfig) -> Self {
         self.health_check = Some(config);
         self
     }
 
-    /// Enable dry-run mode
+    /// Enable client-side dry-run mode
     pub fn dry_run(mut self) -> Self {
-        self.dry_run = true;
+        self.dry_run = DryRunMode::Client;
+        self
+    }
+
+    /// Enable server-side dry-run mode
+    pub fn server_dry_run(mut self) -> Self {
+        self.dry_run = DryRunMode::Server;
         self
     }
 
@@ -114,15 +168,36 @@ pub struct UpgradeOptions {
     /// Automatically rollback on failure
     pub atomic: bool,
 
+    /// Timeout for the wait of an atomic upgrade (defaults to `timeout`)
+    pub atomic_timeout: Option<Duration>,
+
     /// Install if release doesn't exist
     pub install: bool,
 
+    /// With `install`, adopt existing resources that no release manages
+    pub take_ownership: bool,
+
     /// Force resource updates through delete/recreate
     pub force: bool,
 
     /// Strategy for immutable field conflicts
     pub immutable_strategy: ImmutableStrategy,
 
+    /// Allow the `recreate` strategy to delete and recreate stateful kinds
+    pub confirm_stateful_recreation: bool,
+
+    /// After applying, warn about fields the server changed from the manifest
+    pub verify_apply: bool,
+
+    /// Print container logs of hook Pods and Jobs while they run
+    pub hook_logs: bool,
+
+    /// Append the notes of enabled subcharts to the release notes
+    pub render_subchart_notes: bool,
+
+    /// Deploy even if the cluster doesn't satisfy the pack's `kubeVersion`
+    pub skip_kube_version_check: bool,
+
     /// Skip schema validation
     pub skip_schema_validation: bool,
 
@@ -133,7 +208,7 @@ pub struct UpgradeOptions {
     pub reuse_values: bool,
 
     /// Dry run mode
-    pub dry_run: bool,
+    pub dry_run: DryRunMode,
 
     /// Show diff before applying
     pub show_diff: bool,
@@ -141,6 +216,15 @@ pub struct UpgradeOptions {
     /// Skip hooks
     pub no_hooks: bool,
 
+    /// Transform rendered (non-hook) manifests before they are applied
+    pub post_renderer: Option<Arc<dyn PostRenderer>>,
+
+    /// Receives a progress event for every resource transition
+    pub progress: Option<Arc<dyn ProgressSink>>,
+
+    /// Where the user-supplied values came from
+    pub values_provenance: ValuesProvenance,
+
     /// Maximum history to keep
     pub max_history: Option<u32>,
 
@@ -175,6 +259,23 @@ impl UpgradeOptions {
         self
     }
 
+    /// Set a dedicated timeout for the atomic wait
+    pub fn with_atomic_timeout(mut self, timeout: Duration) -> Self {
+        self.atomic_timeout = Some(timeout);
+        self
+    }
+
+    /// Timeout for the post-apply wait
+    ///
+    /// `atomic_timeout` in atomic mode, falling back to `timeout`.
+    pub fn wait_timeout(&self) -> Option<Duration> {
+        if self.atomic {
+            self.atomic_timeout.or(self.timeout)
+        } else {
+            self.timeout
+        }
+    }
+
     /// Enable force mode
     pub fn with_force(mut self) -> Self {
         self.force = true;
@@ -215,6 +316,12 @@ pub struct UninstallOptions {
     /// Cascade deletion (delete dependents)
     pub cascade: DeletionCascade,
 
+    /// Also delete resources labeled for the release but missing from its manifest
+    pub delete_orphans: bool,
+
+    /// Delete resources by category only, ignoring delete/sync waves
+    pub no_delete_waves: bool,
+
     /// Description for the uninstall
     pub description: Option<String>,
 }
@@ -271,6 +378,9 @@ pub struct RollbackOptions {
     /// Strategy for immutable field conflicts
     pub immutable_strategy: ImmutableStrategy,
 
+    /// Allow the `recreate` strategy to delete and recreate stateful kinds
+    pub confirm_stateful_recreation: bool,
+
     /// Strategy for PVCs
     pub pvc_strategy: PvcStrategy,
 
@@ -323,6 +433,39 @@ impl RollbackOptions {
     }
 }
 
+/// Options for running a release's test hooks
+#[derive(Debug, Clone, Default)]
+pub struct TestOptions {
+    /// Release name
+    pub name: String,
+
+    /// Target namespace
+    pub namespace: String,
+
+    /// Timeout for each test that doesn't declare its own
+    pub timeout: Option<Duration>,
+
+    /// Collect container logs of the test Pods and Jobs
+    pub logs: bool,
+
+    /// Print container logs of the test Pods and Jobs while they run
+    pub stream_logs: bool,
+
+    /// Keep test resources instead of applying their cleanup policy
+    pub keep: bool,
+}
+
+impl TestOptions {
+    /// Create default test options
+    pub fn new(name: impl Into<String>, namespace: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            namespace: namespace.into(),
+            ..Default::default()
+        }
+    }
+}
+
 /// Strategy for handling immutable field conflicts
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
@@ -361,6 +504,55 @@ impl std::str::FromStr for ImmutableStrategy {
     }
 }
 
+/// How a dry run is performed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DryRunMode {
+    /// Apply for real (default)
+    #[default]
+    None,
+
+    /// Render only, without contacting the API server
+    Client,
+
+    /// Send the apply to the API server with `dryRun=All`, so admission
+    /// webhooks and server-side defaulting run without persisting anything
+    Server,
+}
+
+impl DryRunMode {
+    /// Whether this is any kind of dry run
+    pub fn is_enabled(self) -> bool {
+        self != Self::None
+    }
+}
+
+impl std::fmt::Display for DryRunMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "none"),
+            Self::Client => write!(f, "client"),
+            Self::Server => write!(f, "server"),
+        }
+    }
+}
+
+impl std::str::FromStr for DryRunMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(Self::None),
+            "client" => Ok(Self::Client),
+            "server" => Ok(Self::Server),
+            _ => Err(format!(
+                "unknown dry-run mode: {} (expected client or server)",
+                s
+            )),
+        }
+    }
+}
+
 /// Strategy for handling PVCs during rollback
 #[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
@@ -439,6 +631,24 @@ mod tests {
         assert!(opts.wait);
         assert!(opts.atomic);
         assert!(opts.install);
+        assert_eq!(opts.wait_timeout(), Some(Duration::minutes(10)));
+    }
+
+    #[test]
+    fn test_atomic_timeout_falls_back_to_timeout() {
+        let opts = InstallOptions::new("myapp", "default")
+            .with_atomic(Duration::minutes(5))
+            .with_atomic_timeout(Duration::minutes(2));
+        assert_eq!(opts.wait_timeout(), Some(Duration::minutes(2)));
+
+        // Only atomic operations use the atomic timeout
+        let opts = UpgradeOptions {
+            atomic: false,
+            ..UpgradeOptions::new("myapp", "default")
+                .with_atomic(Duration::minutes(5))
+                .with_atomic_timeout(Duration::minutes(2))
+        };
+        assert_eq!(opts.wait_timeout(), Some(Duration::minutes(5)));
     }
 
     #[test]
@@ -462,4 +672,21 @@ mod tests {
             ImmutableStrategy::Fail
         );
     }
+
+    #[test]
+    fn test_dry_run_mode() {
+        assert_eq!("server".parse::<DryRunMode>().unwrap(), DryRunMode::Server);
+        assert_eq!("Client".parse::<DryRunMode>().unwrap(), DryRunMode::Client);
+        assert!("all".parse::<DryRunMode>().is_err());
+
+        assert!(!InstallOptions::new("a", "b").dry_run.is_enabled());
+        assert_eq!(
+            InstallOptions::new("a", "b").dry_run().dry_run,
+            DryRunMode::Client
+        );
+        assert_eq!(
+            InstallOptions::new("a", "b").server_dry_run().dry_run,
+            DryRunMode::Server
+        );
+    }
 }
@@ -22,12 +22,22 @@ pub mod sherpack {
     pub const HOOK_RETRIES: &str = "sherpack.io/hook-retries";
     /// Sync wave for ordering resources
     pub const SYNC_WAVE: &str = "sherpack.io/sync-wave";
+    /// Wave used when deleting the resource (overrides the sync wave)
+    pub const DELETE_WAVE: &str = "sherpack.io/delete-wave";
     /// Wait for another resource before applying
     pub const WAIT_FOR: &str = "sherpack.io/wait-for";
     /// Custom health check configuration
     pub const HEALTH_CHECK: &str = "sherpack.io/health-check";
     /// Skip waiting for this resource
     pub const SKIP_WAIT: &str = "sherpack.io/skip-wait";
+    /// `status.conditions` type that must be `"True"` for the resource to be healthy
+    pub const HEALTH_CONDITION: &str = "sherpack.io/health-condition";
+    /// JSONPath field (optionally `=expected`) that decides readiness
+    pub const HEALTH_JSONPATH: &str = "sherpack.io/health-jsonpath";
+    /// Gate later waves on this resource succeeding
+    pub const WAVE_GATE: &str = "sherpack.io/wave-gate";
+    /// Comma-separated field paths to ignore when diffing this resource
+    pub const DIFF_IGNORE: &str = "sherpack.io/diff-ignore";
 }
 
 /// Helm-compatible annotations (for migration)
@@ -85,6 +95,16 @@ pub fn parse_sync_wave(annotations: &BTreeMap<String, String>) -> i32 {
         .unwrap_or(0)
 }
 
+/// Parse delete wave, falling back to the sync wave
+///
+/// Resources are deleted from the highest wave down, so whatever a wave
+/// depends on outlives it.
+pub fn parse_delete_wave(annotations: &BTreeMap<String, String>) -> i32 {
+    get_sherpack_annotation(annotations, sherpack::DELETE_WAVE)
+        .and_then(|s| s.parse().ok())
+        .unwrap_or_else(|| parse_sync_wave(annotations))
+}
+
 /// Parse wait-for dependencies
 /// Format: "kind/name" or "kind/name,kind/name"
 pub fn parse_wait_for(annotations: &BTreeMap<String, String>) -> Vec<ResourceRef> {
@@ -204,6 +224,13 @@ pub fn should_skip_wait(annotations: &BTreeMap<String, String>) -> bool {
         .unwrap_or(false)
 }
 
+/// Check if the resource gates later waves
+pub fn is_wave_gate(annotations: &BTreeMap<String, String>) -> bool {
+    get_sherpack_annotation(annotations, sherpack::WAVE_GATE)
+        .map(|s| s.to_lowercase() == "true" || s == "1")
+        .unwrap_or(false)
+}
+
 /// Reference to a Kubernetes resource
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ResourceRef {
@@ -311,6 +338,21 @@ mod tests {
         assert_eq!(parse_sync_wave(&empty), 0);
     }
 
+    #[test]
+    fn test_parse_delete_wave() {
+        let annotations = make_annotations(&[("sherpack.io/sync-wave", "2")]);
+        assert_eq!(parse_delete_wave(&annotations), 2);
+
+        let annotations = make_annotations(&[
+            ("sherpack.io/sync-wave", "2"),
+            ("sherpack.io/delete-wave", "-5"),
+        ]);
+        assert_eq!(parse_delete_wave(&annotations), -5);
+
+        let annotations = make_annotations(&[("sherpack.io/delete-wave", "late")]);
+        assert_eq!(parse_delete_wave(&annotations), 0);
+    }
+
     #[test]
     fn test_parse_wait_for() {
         let annotations = make_annotations(&[("sherpack.io/wait-for", "Deployment/postgres")]);
@@ -5,19 +5,104 @@
 
 use std::sync::Arc;
 
-use sherpack_core::{LoadedPack, ReleaseInfo, TemplateContext, Values};
-use sherpack_engine::Engine;
+use kube::api::{Api, DeleteParams, DynamicObject};
+use kube::discovery::Discovery;
+use sherpack_core::context::{Capabilities, KubeVersion};
+use sherpack_core::{KubeVersionConstraint, LoadedPack, ReleaseInfo, TemplateContext, Values};
 use sherpack_engine::cluster_reader::ClusterReader;
-
-use crate::actions::{InstallOptions, RollbackOptions, UninstallOptions, UpgradeOptions};
-use crate::diff::{DiffEngine, DiffResult};
+use sherpack_engine::{Engine, PackRenderer, SecretFunctionState};
+
+use crate::actions::{
+    DryRunMode, InstallOptions, RollbackOptions, TestOptions, UninstallOptions, UpgradeOptions,
+};
+use crate::diff::{
+    DiffEngine, DiffResult, ResourceKey, parse_manifest_resources, tracked_resource_api,
+};
 use crate::error::{KubeError, Result};
-use crate::health::{HealthCheckConfig, HealthChecker, HealthStatus};
-use crate::hooks::{HookExecutor, HookPhase, parse_hooks_from_manifest};
+use crate::health::{HealthCheckConfig, HealthChecker, HealthStatus, WaitCondition};
+use crate::hooks::{HookExecutor, HookPhase, HookResult, HookRun, parse_hooks_from_manifest};
 use crate::lookup::KubeClusterReader;
-use crate::release::{ReleaseState, StoredRelease};
-use crate::resources::ResourceManager;
-use crate::storage::StorageDriver;
+use crate::post_render::post_render_manifest;
+use crate::progress::{ProgressEvent, ProgressFormat, ProgressSink, ResourceStatus};
+use crate::release::{DEFAULT_OPERATION_TIMEOUT, ReleaseState, StoredRelease, lock_owner};
+use crate::resources::{ImmutablePolicy, OperationSummary, ReleaseOwnership, ResourceManager};
+use crate::storage::{ReleaseFilter, StorageDriver};
+use crate::waves::{
+    ClusterWaveApplier, ExecutionPlan, Resource, WaveExecutionConfig, WaveExecutor,
+};
+
+/// Kubernetes API server information
+#[derive(Debug, Clone)]
+pub struct ServerInfo {
+    /// Whether the API server answered the version request
+    pub reachable: bool,
+
+    /// Server version (the default version when unreachable)
+    pub kube_version: KubeVersion,
+
+    /// Platform reported by the server (e.g. `linux/amd64`)
+    pub platform: Option<String>,
+}
+
+/// Outcome of resuming a stuck or failed release with [`KubeClient::resume`]
+#[derive(Debug, Clone)]
+pub struct RecoveryReport {
+    /// The release after recovery
+    pub release: StoredRelease,
+    /// Resources that were missing from the cluster and have been created
+    pub reapplied: Vec<String>,
+    /// Resources that were already present (applied again, idempotently)
+    pub already_present: Vec<String>,
+    /// Hooks that were run again
+    pub hooks: Vec<HookResult>,
+}
+
+impl RecoveryReport {
+    /// Whether the release was already deployed, so nothing was done
+    pub fn was_noop(&self) -> bool {
+        self.reapplied.is_empty() && self.already_present.is_empty() && self.hooks.is_empty()
+    }
+}
+
+impl ServerInfo {
+    /// Build from the API server's `/version` response
+    ///
+    /// Managed distributions report minors such as `30+`, so only the
+    /// leading digits are kept.
+    pub fn from_version_info(info: &k8s_openapi::apimachinery::pkg::version::Info) -> Self {
+        let major = leading_digits(&info.major);
+        let minor = leading_digits(&info.minor);
+
+        let kube_version = if major.is_empty() || minor.is_empty() {
+            KubeVersion::new(&info.git_version)
+        } else {
+            KubeVersion {
+                version: info.git_version.clone(),
+                major,
+                minor,
+            }
+        };
+
+        Self {
+            reachable: true,
+            kube_version,
+            platform: Some(info.platform.clone()).filter(|p| !p.is_empty()),
+        }
+    }
+
+    /// Placeholder for a cluster that could not be reached
+    pub fn unreachable() -> Self {
+        Self {
+            reachable: false,
+            kube_version: KubeVersion::default(),
+            platform: None,
+        }
+    }
+}
+
+fn leading_digits(s: &str) -> String {
+    s.chars().take_while(|c| c.is_ascii_digit()).collect()
+}
 
 /// High-level Kubernetes client for Sherpack
 pub struct KubeClient<S: StorageDriver> {
@@ -29,6 +114,10 @@ pub struct KubeClient<S: StorageDriver> {
 
     /// Diff engine
     diff_engine: DiffEngine,
+
+    /// Age past which a pending state without a timeout of its own (a
+    /// recovery) no longer locks its release
+    lock_timeout: chrono::Duration,
 }
 
 impl<S: StorageDriver> KubeClient<S> {
@@ -41,9 +130,30 @@ impl<S: StorageDriver> KubeClient<S> {
             client,
             storage,
             diff_engine,
+            lock_timeout: DEFAULT_OPERATION_TIMEOUT,
         })
     }
 
+    /// Create a KubeClient for a kubeconfig file and context
+    ///
+    /// `path` defaults to `$KUBECONFIG` or `~/.kube/config` and `context` to
+    /// its current context; see [`crate::kubeconfig::load_config`].
+    pub async fn from_context(
+        storage: S,
+        path: Option<&std::path::Path>,
+        context: Option<&str>,
+    ) -> Result<Self> {
+        let config = crate::kubeconfig::load_config(path, context).await?;
+        Self::from_config(storage, config)
+    }
+
+    /// Create from a client configuration
+    pub fn from_config(storage: S, config: kube::Config) -> Result<Self> {
+        let client = kube::Client::try_from(config)?;
+
+        Ok(Self::with_client(client, storage))
+    }
+
     /// Create with an existing Kubernetes client
     pub fn with_client(client: kube::Client, storage: S) -> Self {
         let diff_engine = DiffEngine::new();
@@ -52,9 +162,21 @@ impl<S: StorageDriver> KubeClient<S> {
             client,
             storage,
             diff_engine,
+            lock_timeout: DEFAULT_OPERATION_TIMEOUT,
         }
     }
 
+    /// Set the age past which a recovery is considered stale
+    ///
+    /// Pending operations lock their release until their own timeout has
+    /// passed; recoveries have none and use this one. Until then other
+    /// operations fail with [`KubeError::ReleaseLocked`]. Stale locks must be
+    /// cleared with [`Self::recover`].
+    pub fn with_lock_timeout(mut self, timeout: chrono::Duration) -> Self {
+        self.lock_timeout = timeout;
+        self
+    }
+
     /// Get the underlying Kubernetes client
     pub fn kube_client(&self) -> &kube::Client {
         &self.client
@@ -65,17 +187,82 @@ impl<S: StorageDriver> KubeClient<S> {
         &self.storage
     }
 
+    /// Query the API server version
+    ///
+    /// Transport failures are reported as an unreachable server rather than
+    /// an error; errors returned by the API server itself are propagated.
+    pub async fn server_info(&self) -> Result<ServerInfo> {
+        match self.client.apiserver_version().await {
+            Ok(info) => Ok(ServerInfo::from_version_info(&info)),
+            Err(e @ kube::Error::Api(_)) => Err(e.into()),
+            Err(e) => {
+                tracing::debug!("API server unreachable: {}", e);
+                Ok(ServerInfo::unreachable())
+            }
+        }
+    }
+
+    /// Check the server version against the pack's `kubeVersion` constraint
+    ///
+    /// Passes when the pack sets no constraint, or when the server can't be
+    /// reached and so reports no version.
+    pub async fn check_kube_version(&self, pack: &LoadedPack) -> Result<()> {
+        let Some(constraint) = &pack.pack.metadata.kube_version else {
+            return Ok(());
+        };
+        let info = self.server_info().await?;
+        if !info.reachable {
+            tracing::debug!("Skipping kubeVersion check, API server unreachable");
+            return Ok(());
+        }
+
+        if KubeVersionConstraint::parse(constraint)?.allows(&info.kube_version) {
+            Ok(())
+        } else {
+            Err(KubeError::KubeVersionMismatch {
+                pack: pack.pack.metadata.name.clone(),
+                constraint: constraint.clone(),
+                version: info.kube_version.version,
+            })
+        }
+    }
+
+    /// Capabilities of the live cluster, for install/upgrade renders
+    ///
+    /// API versions come from discovery. Falls back to the default
+    /// capabilities if the server can't be queried.
+    async fn live_capabilities(&self) -> Capabilities {
+        match self.server_info().await {
+            Ok(info) if info.reachable => {
+                let api_versions = match Discovery::new(self.client.clone()).run().await {
+                    Ok(discovery) => discovered_api_versions(&discovery),
+                    Err(e) => {
+                        // Restricted RBAC can forbid discovery; keep the defaults
+                        tracing::debug!("API discovery failed: {}", e);
+                        Capabilities::default().api_versions
+                    }
+                };
+                Capabilities {
+                    kube_version: info.kube_version,
+                    api_versions,
+                }
+            }
+            _ => Capabilities::default(),
+        }
+    }
+
     /// Build a render-time engine with cluster-aware `lookup()`.
     ///
     /// Used by install/upgrade so templates can read existing cluster
-    /// state. The reader holds its own discovery cache. After rendering,
+    /// state, and `generate_secret()` / `previous_secret()` resolve against
+    /// `secrets`. The reader holds its own discovery cache. After rendering,
     /// pull warnings via `engine.lookup_state().take_warnings()` so users
     /// see the GitOps non-determinism notices.
     ///
     /// The per-call lookup timeout can be overridden via the
     /// `SHERPACK_LOOKUP_TIMEOUT_SECS` environment variable. Default is
     /// 5 seconds. A timed-out lookup resolves to `{}` (Helm-compat).
-    async fn engine_with_lookup(&self) -> Engine {
+    async fn engine_with_lookup(&self, secrets: &SecretFunctionState) -> Engine {
         match KubeClusterReader::new(self.client.clone()).await {
             Ok(mut reader) => {
                 if let Ok(secs) = std::env::var("SHERPACK_LOOKUP_TIMEOUT_SECS")
@@ -88,6 +275,7 @@ impl<S: StorageDriver> KubeClient<S> {
                 Engine::builder()
                     .strict(true)
                     .with_cluster_reader(arc)
+                    .with_secret_state(secrets.clone())
                     .build()
             }
             Err(e) => {
@@ -98,7 +286,10 @@ impl<S: StorageDriver> KubeClient<S> {
                     "Cluster discovery for lookup() failed; lookup() will return empty: {}",
                     e
                 );
-                Engine::builder().strict(true).build()
+                Engine::builder()
+                    .strict(true)
+                    .with_secret_state(secrets.clone())
+                    .build()
             }
         }
     }
@@ -113,6 +304,31 @@ impl<S: StorageDriver> KubeClient<S> {
         }
     }
 
+    /// Notes of a release: the pack's own, followed by each subchart's
+    /// when `include_subcharts` is set
+    ///
+    /// Subcharts are rendered for their notes only; what gets applied is
+    /// still the manifest rendered from the pack itself.
+    async fn release_notes(
+        &self,
+        pack: &LoadedPack,
+        context: &TemplateContext,
+        secrets: &SecretFunctionState,
+        notes: Option<String>,
+        include_subcharts: bool,
+    ) -> Result<Option<String>> {
+        if !include_subcharts {
+            return Ok(notes);
+        }
+
+        let renderer = PackRenderer::new(self.engine_with_lookup(secrets).await);
+        let mut rendered = renderer
+            .render(pack, context)
+            .map_err(|e| KubeError::Template(e.to_string()))?;
+        rendered.notes = notes;
+        Ok(rendered.combined_notes(true))
+    }
+
     // ========== Install ==========
 
     /// Install a pack as a new release
@@ -122,6 +338,11 @@ impl<S: StorageDriver> KubeClient<S> {
         values: Values,
         options: &InstallOptions,
     ) -> Result<StoredRelease> {
+        validate_pack(pack)?;
+        if !options.skip_kube_version_check {
+            self.check_kube_version(pack).await?;
+        }
+
         // Check if release already exists
         if self
             .storage
@@ -136,30 +357,46 @@ impl<S: StorageDriver> KubeClient<S> {
 
         // Create template context
         let release_info = ReleaseInfo::for_install(&options.name, &options.namespace);
-        let context = TemplateContext::new(values.clone(), release_info, &pack.pack.metadata);
+        let context = TemplateContext::new(values.clone(), release_info, &pack.pack.metadata)
+            .with_capabilities(self.live_capabilities().await);
 
         // Render templates with cluster-aware lookup() enabled
-        let engine = self.engine_with_lookup().await;
+        let secrets = SecretFunctionState::new();
+        let engine = self.engine_with_lookup(&secrets).await;
         let render_result = engine
             .render_pack(pack, &context)
             .map_err(|e| KubeError::Template(e.to_string()))?;
         Self::surface_lookup_warnings(&engine);
 
         // Create release
+        let mut manifest = render_result
+            .manifests
+            .values()
+            .cloned()
+            .collect::<Vec<_>>()
+            .join("\n---\n");
+        if let Some(renderer) = &options.post_renderer {
+            manifest = post_render_manifest(&manifest, renderer.as_ref())?;
+        }
         let mut release = StoredRelease::for_install(
             options.name.clone(),
             options.namespace.clone(),
             pack.pack.metadata.clone(),
             values,
-            render_result
-                .manifests
-                .values()
-                .cloned()
-                .collect::<Vec<_>>()
-                .join("\n---\n"),
+            manifest,
         );
-        release.notes = render_result.notes;
+        release.notes = self
+            .release_notes(
+                pack,
+                &context,
+                &secrets,
+                render_result.notes,
+                options.render_subchart_notes,
+            )
+            .await?;
         release.hooks = parse_hooks_from_manifest(&release.manifest);
+        release.values_provenance = options.values_provenance.clone();
+        release.secrets = secrets.take_state();
 
         // Add custom labels
         for (k, v) in &options.labels {
@@ -167,32 +404,52 @@ impl<S: StorageDriver> KubeClient<S> {
         }
 
         // Dry run - just return what would be created
-        if options.dry_run {
+        if options.dry_run.is_enabled() {
+            if options.dry_run == DryRunMode::Server {
+                self.validate_on_server(&release).await?;
+            }
             return Ok(release);
         }
 
         // Show diff if requested
         if options.show_diff {
             // For install, show all resources as additions
-            println!("Resources to be created:");
-            for manifest in render_result.manifests.keys() {
-                println!("  + {}", manifest);
+            eprintln!("Resources to be created:");
+            let mut keys: Vec<_> = parse_manifest_resources(&release.manifest)
+                .into_keys()
+                .map(|key| format!("{}/{}", key.kind, key.name))
+                .collect();
+            keys.sort();
+            for key in keys {
+                eprintln!("  + {}", key);
             }
         }
 
-        // Store the pending release
-        self.storage.create(&release).await?;
+        // Store the pending release, locking it for the install
+        self.lock(&release).await?;
+        let (namespace, name, version) = (
+            release.namespace.clone(),
+            release.name.clone(),
+            release.version,
+        );
+        let result = self.install_locked(release, options).await;
+        self.unlock_on_error(&namespace, &name, version, result)
+            .await
+    }
 
+    /// Install steps run while holding the release's lock
+    async fn install_locked(
+        &self,
+        mut release: StoredRelease,
+        options: &InstallOptions,
+    ) -> Result<StoredRelease> {
         // Execute pre-install hooks
-        let mut hook_executor = HookExecutor::new();
-        if let Err(e) = hook_executor
-            .execute_phase(
-                &release.hooks,
-                HookPhase::PreInstall,
-                &release.name,
-                release.version,
-                &self.client,
-            )
+        let mut hook_executor = HookExecutor::new().with_log_streaming(options.hook_logs);
+        if let Some(progress) = &options.progress {
+            hook_executor = hook_executor.with_progress(progress.clone());
+        }
+        if let Err(e) = self
+            .run_hooks(&mut hook_executor, &mut release, HookPhase::PreInstall)
             .await
         {
             release.mark_failed(e.to_string(), true);
@@ -200,38 +457,46 @@ impl<S: StorageDriver> KubeClient<S> {
             return Err(e);
         }
 
-        // Apply manifests to cluster
+        // Apply manifests to cluster, checking who owns existing resources
+        let ownership =
+            ReleaseOwnership::new(&release.name, &release.namespace, options.take_ownership);
         if let Err(e) = self
-            .apply_manifest(&release.namespace, &release.manifest)
+            .apply_manifest(
+                &release.namespace,
+                &release.manifest,
+                options.timeout,
+                ownership,
+                ImmutablePolicy::default(),
+                options.verify_apply,
+                options
+                    .health_check
+                    .as_ref()
+                    .map_or(&[], |h| h.wait_for.as_slice()),
+                options.progress.clone(),
+            )
             .await
         {
             release.mark_failed(e.to_string(), true);
             self.storage.update(&release).await?;
 
             if options.atomic {
-                // Cleanup on failure
-                let _ = self.cleanup_release(&release).await;
+                self.remove_failed_install(&mut release).await?;
             }
 
             return Err(e);
         }
 
         // Execute during-install hooks
-        let _ = hook_executor
-            .execute_phase(
-                &release.hooks,
-                HookPhase::DuringInstall,
-                &release.name,
-                release.version,
-                &self.client,
-            )
+        let _ = self
+            .run_hooks(&mut hook_executor, &mut release, HookPhase::DuringInstall)
             .await;
 
         // Wait for resources if requested
         if options.wait {
-            // TODO: Use timeout to configure health checker
-            let _timeout = options.timeout.unwrap_or(chrono::Duration::minutes(5));
-            let health_config = options.health_check.clone().unwrap_or_default();
+            let mut health_config = options.health_check.clone().unwrap_or_default();
+            if let Some(timeout) = options.wait_timeout() {
+                health_config.timeout = timeout;
+            }
             let checker = HealthChecker::new(health_config);
 
             let status = checker.check(&release, &self.client).await?;
@@ -241,7 +506,7 @@ impl<S: StorageDriver> KubeClient<S> {
                 self.storage.update(&release).await?;
 
                 if options.atomic {
-                    let _ = self.cleanup_release(&release).await;
+                    self.remove_failed_install(&mut release).await?;
                 }
 
                 return Err(KubeError::HealthCheckFailed {
@@ -252,20 +517,17 @@ impl<S: StorageDriver> KubeClient<S> {
         }
 
         // Execute post-install hooks
-        let _ = hook_executor
-            .execute_phase(
-                &release.hooks,
-                HookPhase::PostInstall,
-                &release.name,
-                release.version,
-                &self.client,
-            )
+        let _ = self
+            .run_hooks(&mut hook_executor, &mut release, HookPhase::PostInstall)
             .await;
 
         // Mark as deployed
         release.mark_deployed();
         self.storage.update(&release).await?;
 
+        self.prune_history(&release.namespace, &release.name, None)
+            .await;
+
         Ok(release)
     }
 
@@ -278,6 +540,11 @@ impl<S: StorageDriver> KubeClient<S> {
         values: Values,
         options: &UpgradeOptions,
     ) -> Result<StoredRelease> {
+        validate_pack(pack)?;
+        if !options.skip_kube_version_check {
+            self.check_kube_version(pack).await?;
+        }
+
         // Get existing release
         let existing = match self
             .storage
@@ -298,8 +565,16 @@ impl<S: StorageDriver> KubeClient<S> {
                 timeout: options.timeout,
                 health_check: options.health_check.clone(),
                 atomic: options.atomic,
+                atomic_timeout: options.atomic_timeout,
+                take_ownership: options.take_ownership,
+                verify_apply: options.verify_apply,
+                hook_logs: options.hook_logs,
+                render_subchart_notes: options.render_subchart_notes,
+                skip_kube_version_check: options.skip_kube_version_check,
                 dry_run: options.dry_run,
                 show_diff: options.show_diff,
+                post_renderer: options.post_renderer.clone(),
+                values_provenance: options.values_provenance.clone(),
                 labels: options.labels.clone(),
                 description: options.description.clone(),
                 ..Default::default()
@@ -309,59 +584,63 @@ impl<S: StorageDriver> KubeClient<S> {
 
         let existing = existing.unwrap();
 
-        // Check for stuck state
-        if existing.state.is_pending() {
-            if existing.is_stuck() {
-                return Err(KubeError::StuckRelease {
-                    name: existing.name.clone(),
-                    status: existing.state.status_name().to_string(),
-                    elapsed: existing
-                        .state
-                        .elapsed()
-                        .map(|d| format!("{} seconds", d.num_seconds()))
-                        .unwrap_or_else(|| "unknown".to_string()),
-                });
-            } else {
-                return Err(KubeError::OperationInProgress {
-                    name: existing.name.clone(),
-                    status: existing.state.to_string(),
-                });
-            }
-        }
+        // Refuse to run alongside another operation
+        self.check_lock(&existing)?;
 
-        // Merge values
+        // Merge values; reused values keep their recorded sources
+        let values = values.with_provenance(options.values_provenance.clone());
         let final_values = if options.reset_values {
             values
         } else if options.reuse_values {
-            let mut merged = existing.values.clone();
+            let mut merged = existing
+                .values
+                .clone()
+                .with_provenance(existing.values_provenance.clone());
             merged.merge(&values);
             merged
         } else {
             values
         };
+        let values_provenance = final_values.provenance().clone();
 
         // Create template context
         let release_info =
             ReleaseInfo::for_upgrade(&options.name, &options.namespace, existing.version + 1);
-        let context = TemplateContext::new(final_values.clone(), release_info, &pack.pack.metadata);
+        let context = TemplateContext::new(final_values.clone(), release_info, &pack.pack.metadata)
+            .with_capabilities(self.live_capabilities().await);
 
-        // Render templates with cluster-aware lookup() enabled
-        let engine = self.engine_with_lookup().await;
+        // Render templates with cluster-aware lookup() enabled, reusing the
+        // secrets generated by earlier revisions
+        let secrets = SecretFunctionState::with_state(existing.secrets.clone());
+        let engine = self.engine_with_lookup(&secrets).await;
         let render_result = engine
             .render_pack(pack, &context)
             .map_err(|e| KubeError::Template(e.to_string()))?;
         Self::surface_lookup_warnings(&engine);
 
         // Create new release
-        let manifest = render_result
+        let mut manifest = render_result
             .manifests
             .values()
             .cloned()
             .collect::<Vec<_>>()
             .join("\n---\n");
+        if let Some(renderer) = &options.post_renderer {
+            manifest = post_render_manifest(&manifest, renderer.as_ref())?;
+        }
         let mut release = StoredRelease::for_upgrade(&existing, final_values, manifest);
-        release.notes = render_result.notes;
+        release.notes = self
+            .release_notes(
+                pack,
+                &context,
+                &secrets,
+                render_result.notes,
+                options.render_subchart_notes,
+            )
+            .await?;
         release.hooks = parse_hooks_from_manifest(&release.manifest);
+        release.secrets = secrets.take_state();
+        release.values_provenance = values_provenance;
 
         // Add custom labels
         for (k, v) in &options.labels {
@@ -371,75 +650,114 @@ impl<S: StorageDriver> KubeClient<S> {
         // Show diff if requested
         if options.show_diff {
             let diff = self.diff_engine.diff_releases(&existing, &release);
-            println!("Changes: {}", self.diff_engine.summary(&diff));
+            eprintln!("Changes: {}", self.diff_engine.summary(&diff));
             // TODO: Print detailed diff
         }
 
         // Dry run
-        if options.dry_run {
+        if options.dry_run.is_enabled() {
+            if options.dry_run == DryRunMode::Server {
+                self.validate_on_server(&release).await?;
+            }
             return Ok(release);
         }
 
-        // Store the pending release
-        self.storage.create(&release).await?;
+        // Store the pending release, locking it for the upgrade
+        self.lock(&release).await?;
+        let (namespace, name, version) = (
+            release.namespace.clone(),
+            release.name.clone(),
+            release.version,
+        );
+        let result = self.upgrade_locked(release, existing, options).await;
+        self.unlock_on_error(&namespace, &name, version, result)
+            .await
+    }
 
+    /// Upgrade steps run while holding the release's lock
+    async fn upgrade_locked(
+        &self,
+        mut release: StoredRelease,
+        existing: StoredRelease,
+        options: &UpgradeOptions,
+    ) -> Result<StoredRelease> {
         // Mark previous as superseded
         let mut prev = existing;
         prev.mark_superseded();
         self.storage.update(&prev).await?;
 
         // Execute pre-upgrade hooks
-        let mut hook_executor = HookExecutor::new();
+        let mut hook_executor = HookExecutor::new().with_log_streaming(options.hook_logs);
+        if let Some(progress) = &options.progress {
+            hook_executor = hook_executor.with_progress(progress.clone());
+        }
         if !options.no_hooks
-            && let Err(e) = hook_executor
-                .execute_phase(
-                    &release.hooks,
-                    HookPhase::PreUpgrade,
-                    &release.name,
-                    release.version,
-                    &self.client,
-                )
+            && let Err(e) = self
+                .run_hooks(&mut hook_executor, &mut release, HookPhase::PreUpgrade)
                 .await
         {
             release.mark_failed(e.to_string(), true);
             self.storage.update(&release).await?;
 
             if options.atomic {
-                return self.rollback_to(&release, prev.version).await;
+                return self
+                    .rollback_to(&release, prev.version, options.wait_timeout())
+                    .await;
             }
             return Err(e);
         }
 
-        // Apply manifests
-        if let Err(e) = self
-            .apply_manifest(&release.namespace, &release.manifest)
+        // Apply manifests, then delete what the new revision dropped
+        let applied = match self
+            .apply_manifest(
+                &release.namespace,
+                &release.manifest,
+                options.timeout,
+                ReleaseOwnership::deployed(&release.name, &release.namespace),
+                ImmutablePolicy {
+                    strategy: options.immutable_strategy,
+                    recreate_stateful: options.confirm_stateful_recreation,
+                },
+                options.verify_apply,
+                options
+                    .health_check
+                    .as_ref()
+                    .map_or(&[], |h| h.wait_for.as_slice()),
+                options.progress.clone(),
+            )
             .await
         {
+            Ok(_) => {
+                self.delete_removed(&prev, &release, options.progress.clone())
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+        if let Err(e) = applied {
             release.mark_failed(e.to_string(), true);
             self.storage.update(&release).await?;
 
             if options.atomic {
-                return self.rollback_to(&release, prev.version).await;
+                return self
+                    .rollback_to(&release, prev.version, options.wait_timeout())
+                    .await;
             }
             return Err(e);
         }
 
         // Execute during-upgrade hooks
         if !options.no_hooks {
-            let _ = hook_executor
-                .execute_phase(
-                    &release.hooks,
-                    HookPhase::DuringUpgrade,
-                    &release.name,
-                    release.version,
-                    &self.client,
-                )
+            let _ = self
+                .run_hooks(&mut hook_executor, &mut release, HookPhase::DuringUpgrade)
                 .await;
         }
 
         // Wait for resources
         if options.wait {
-            let health_config = options.health_check.clone().unwrap_or_default();
+            let mut health_config = options.health_check.clone().unwrap_or_default();
+            if let Some(timeout) = options.wait_timeout() {
+                health_config.timeout = timeout;
+            }
             let checker = HealthChecker::new(health_config);
 
             let status = checker.check(&release, &self.client).await?;
@@ -449,7 +767,9 @@ impl<S: StorageDriver> KubeClient<S> {
                 self.storage.update(&release).await?;
 
                 if options.atomic {
-                    return self.rollback_to(&release, prev.version).await;
+                    return self
+                        .rollback_to(&release, prev.version, options.wait_timeout())
+                        .await;
                 }
 
                 return Err(KubeError::HealthCheckFailed {
@@ -461,14 +781,8 @@ impl<S: StorageDriver> KubeClient<S> {
 
         // Execute post-upgrade hooks
         if !options.no_hooks {
-            let _ = hook_executor
-                .execute_phase(
-                    &release.hooks,
-                    HookPhase::PostUpgrade,
-                    &release.name,
-                    release.version,
-                    &self.client,
-                )
+            let _ = self
+                .run_hooks(&mut hook_executor, &mut release, HookPhase::PostUpgrade)
                 .await;
         }
 
@@ -477,10 +791,8 @@ impl<S: StorageDriver> KubeClient<S> {
         self.storage.update(&release).await?;
 
         // Cleanup old releases
-        if let Some(max_history) = options.max_history {
-            self.cleanup_history(&release.namespace, &release.name, max_history)
-                .await?;
-        }
+        self.prune_history(&release.namespace, &release.name, options.max_history)
+            .await;
 
         Ok(release)
     }
@@ -495,35 +807,67 @@ impl<S: StorageDriver> KubeClient<S> {
             .get_latest(&options.namespace, &options.name)
             .await?;
 
-        // Update state
-        release.state = ReleaseState::PendingUninstall {
-            started_at: chrono::Utc::now(),
-            timeout: options.timeout.unwrap_or(chrono::Duration::minutes(5)),
-        };
-        self.storage.update(&release).await?;
+        // Refuse to run alongside another operation
+        self.check_lock(&release)?;
 
         // Dry run
         if options.dry_run {
             return Ok(release);
         }
 
+        // Update state, locking the release for the uninstall unless another
+        // operation changed it since it was read
+        let read_state = std::mem::replace(
+            &mut release.state,
+            ReleaseState::PendingUninstall {
+                started_at: chrono::Utc::now(),
+                timeout: options.timeout.unwrap_or(chrono::Duration::minutes(5)),
+                owner: Some(lock_owner()),
+            },
+        );
+        if !self.storage.update_if(&release, &read_state).await? {
+            return Err(self.lost_lock(&release).await);
+        }
+
+        let (namespace, name, version) = (
+            release.namespace.clone(),
+            release.name.clone(),
+            release.version,
+        );
+        let result = self.uninstall_locked(release, options).await;
+        self.unlock_on_error(&namespace, &name, version, result)
+            .await
+    }
+
+    /// Uninstall steps run while holding the release's lock
+    async fn uninstall_locked(
+        &self,
+        mut release: StoredRelease,
+        options: &UninstallOptions,
+    ) -> Result<StoredRelease> {
         // Execute pre-delete hooks
         let mut hook_executor = HookExecutor::new();
         if !options.no_hooks {
-            let _ = hook_executor
-                .execute_phase(
-                    &release.hooks,
-                    HookPhase::PreDelete,
-                    &release.name,
-                    release.version,
-                    &self.client,
-                )
+            let _ = self
+                .run_hooks(&mut hook_executor, &mut release, HookPhase::PreDelete)
                 .await;
         }
 
-        // Delete resources
+        // Find orphans while the release's own resources still exist
+        let orphans = if options.delete_orphans {
+            self.find_orphans(&release).await?
+        } else {
+            Vec::new()
+        };
+
+        // Delete resources, highest wave first unless disabled
         if let Err(e) = self
-            .delete_manifest(&release.namespace, &release.manifest)
+            .delete_manifest(
+                &release.namespace,
+                &release.manifest,
+                !options.no_delete_waves,
+                None,
+            )
             .await
         {
             release.mark_failed(e.to_string(), true);
@@ -531,16 +875,16 @@ impl<S: StorageDriver> KubeClient<S> {
             return Err(e);
         }
 
+        if let Err(e) = self.delete_orphans(&release.namespace, &orphans).await {
+            release.mark_failed(e.to_string(), true);
+            self.storage.update(&release).await?;
+            return Err(e);
+        }
+
         // Execute post-delete hooks
         if !options.no_hooks {
-            let _ = hook_executor
-                .execute_phase(
-                    &release.hooks,
-                    HookPhase::PostDelete,
-                    &release.name,
-                    release.version,
-                    &self.client,
-                )
+            let _ = self
+                .run_hooks(&mut hook_executor, &mut release, HookPhase::PostDelete)
                 .await;
         }
 
@@ -558,6 +902,50 @@ impl<S: StorageDriver> KubeClient<S> {
         Ok(release)
     }
 
+    // ========== Orphans ==========
+
+    /// Find resources labeled for a release but missing from its manifest
+    ///
+    /// These are left behind when a previous apply created resources that
+    /// later revisions dropped, so uninstalling the manifest won't remove them.
+    pub async fn find_orphans(&self, release: &StoredRelease) -> Result<Vec<ResourceKey>> {
+        let manifest_resources = parse_manifest_resources(&release.manifest);
+        let extra = self
+            .diff_engine
+            .find_extra_cluster_resources(&self.client, release, &manifest_resources)
+            .await?;
+
+        Ok(extra.into_iter().map(|(key, _)| key).collect())
+    }
+
+    /// Delete orphaned resources found by [`Self::find_orphans`]
+    pub async fn delete_orphans(&self, namespace: &str, orphans: &[ResourceKey]) -> Result<()> {
+        let mut errors = Vec::new();
+
+        for key in orphans {
+            let Some(ar) = tracked_resource_api(&key.kind) else {
+                continue;
+            };
+            let ns = key.namespace.as_deref().unwrap_or(namespace);
+            let api: Api<DynamicObject> = Api::namespaced_with(self.client.clone(), ns, &ar);
+
+            match api.delete(&key.name, &DeleteParams::default()).await {
+                Ok(_) => {}
+                Err(kube::Error::Api(e)) if e.code == 404 => {}
+                Err(e) => errors.push(format!("{}/{}: {}", key.kind, key.name, e)),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(KubeError::InvalidConfig(format!(
+                "Failed to delete orphaned resources: {}",
+                errors.join("; ")
+            )));
+        }
+
+        Ok(())
+    }
+
     // ========== Rollback ==========
 
     /// Rollback to a previous revision
@@ -600,10 +988,13 @@ impl<S: StorageDriver> KubeClient<S> {
 
         let current = &history[0];
 
+        // Refuse to run alongside another operation
+        self.check_lock(current)?;
+
         // Show diff if requested
         if options.show_diff {
             let diff = self.diff_engine.diff_releases(current, target);
-            println!("Rollback changes: {}", self.diff_engine.summary(&diff));
+            eprintln!("Rollback changes: {}", self.diff_engine.summary(&diff));
         }
 
         // Dry run
@@ -618,11 +1009,28 @@ impl<S: StorageDriver> KubeClient<S> {
             started_at: chrono::Utc::now(),
             timeout: options.timeout.unwrap_or(chrono::Duration::minutes(5)),
             target_version,
+            owner: Some(lock_owner()),
         };
 
-        // Store pending release
-        self.storage.create(&release).await?;
+        // Store pending release, locking it for the rollback
+        self.lock(&release).await?;
+        let (namespace, name, version) = (
+            release.namespace.clone(),
+            release.name.clone(),
+            release.version,
+        );
+        let result = self.rollback_locked(release, current, options).await;
+        self.unlock_on_error(&namespace, &name, version, result)
+            .await
+    }
 
+    /// Rollback steps run while holding the release's lock
+    async fn rollback_locked(
+        &self,
+        mut release: StoredRelease,
+        current: &StoredRelease,
+        options: &RollbackOptions,
+    ) -> Result<StoredRelease> {
         // Mark current as superseded
         let mut prev = current.clone();
         prev.mark_superseded();
@@ -631,14 +1039,8 @@ impl<S: StorageDriver> KubeClient<S> {
         // Execute pre-rollback hooks
         let mut hook_executor = HookExecutor::new();
         if !options.no_hooks
-            && let Err(e) = hook_executor
-                .execute_phase(
-                    &release.hooks,
-                    HookPhase::PreRollback,
-                    &release.name,
-                    release.version,
-                    &self.client,
-                )
+            && let Err(e) = self
+                .run_hooks(&mut hook_executor, &mut release, HookPhase::PreRollback)
                 .await
         {
             release.mark_failed(e.to_string(), true);
@@ -646,11 +1048,30 @@ impl<S: StorageDriver> KubeClient<S> {
             return Err(e);
         }
 
-        // Apply target manifest
-        if let Err(e) = self
-            .apply_manifest(&release.namespace, &release.manifest)
+        // Apply target manifest, then delete what the target doesn't render
+        let applied = match self
+            .apply_manifest(
+                &release.namespace,
+                &release.manifest,
+                options.timeout,
+                ReleaseOwnership::deployed(&release.name, &release.namespace),
+                ImmutablePolicy {
+                    strategy: options.immutable_strategy,
+                    recreate_stateful: options.confirm_stateful_recreation,
+                },
+                false,
+                options
+                    .health_check
+                    .as_ref()
+                    .map_or(&[], |h| h.wait_for.as_slice()),
+                None,
+            )
             .await
         {
+            Ok(_) => self.delete_removed(current, &release, None).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = applied {
             release.mark_failed(e.to_string(), true);
             self.storage.update(&release).await?;
             return Err(e);
@@ -675,14 +1096,8 @@ impl<S: StorageDriver> KubeClient<S> {
 
         // Execute post-rollback hooks
         if !options.no_hooks {
-            let _ = hook_executor
-                .execute_phase(
-                    &release.hooks,
-                    HookPhase::PostRollback,
-                    &release.name,
-                    release.version,
-                    &self.client,
-                )
+            let _ = self
+                .run_hooks(&mut hook_executor, &mut release, HookPhase::PostRollback)
                 .await;
         }
 
@@ -691,10 +1106,8 @@ impl<S: StorageDriver> KubeClient<S> {
         self.storage.update(&release).await?;
 
         // Cleanup old releases
-        if let Some(max_history) = options.max_history {
-            self.cleanup_history(&release.namespace, &release.name, max_history)
-                .await?;
-        }
+        self.prune_history(&release.namespace, &release.name, options.max_history)
+            .await;
 
         Ok(release)
     }
@@ -711,6 +1124,17 @@ impl<S: StorageDriver> KubeClient<S> {
         self.storage.list(ns, None, false).await
     }
 
+    /// List releases matching `filter`
+    pub async fn list_filtered(
+        &self,
+        namespace: Option<&str>,
+        all_namespaces: bool,
+        filter: &ReleaseFilter,
+    ) -> Result<Vec<StoredRelease>> {
+        let ns = if all_namespaces { None } else { namespace };
+        self.storage.list_filtered(ns, filter).await
+    }
+
     /// Get release history
     pub async fn history(&self, namespace: &str, name: &str) -> Result<Vec<StoredRelease>> {
         self.storage.history(namespace, name).await
@@ -721,6 +1145,48 @@ impl<S: StorageDriver> KubeClient<S> {
         self.storage.get_latest(namespace, name).await
     }
 
+    // ========== Test ==========
+
+    /// Run the test hooks of the latest revision of a release
+    ///
+    /// Tests run in weight order and stop at the first failing test whose
+    /// failure policy fails the operation. A failed test is reported in the
+    /// returned results rather than as an error.
+    pub async fn test(&self, options: &TestOptions) -> Result<Vec<HookResult>> {
+        let release = self
+            .storage
+            .get_latest(&options.namespace, &options.name)
+            .await?;
+
+        // Hooks may not have been parsed into older releases
+        let hooks = if release.hooks.is_empty() {
+            parse_hooks_from_manifest(&release.manifest)
+        } else {
+            release.hooks.clone()
+        };
+
+        let mut executor = HookExecutor::with_namespace(&release.namespace)
+            .with_logs(options.logs)
+            .with_log_streaming(options.stream_logs)
+            .with_keep_resources(options.keep);
+        if let Some(timeout) = options.timeout {
+            executor = executor.with_default_timeout(timeout);
+        }
+
+        // Failures are already recorded in the executor's results
+        let _ = executor
+            .execute_phase(
+                &hooks,
+                HookPhase::Test,
+                &release.name,
+                release.version,
+                &self.client,
+            )
+            .await;
+
+        Ok(executor.results)
+    }
+
     /// Get health status
     pub async fn health(
         &self,
@@ -757,55 +1223,265 @@ impl<S: StorageDriver> KubeClient<S> {
             )));
         }
 
+        // Only stale locks: a younger operation may still be running
+        if !self.is_lock_stale(&release.state) {
+            return Err(self.locked(&release));
+        }
+
         release.mark_failed("Manually recovered from stuck state".to_string(), true);
         self.storage.update(&release).await?;
 
         Ok(release)
     }
 
-    // ========== Internal Helpers ==========
+    /// Resume a release whose operation was interrupted
+    ///
+    /// Re-applies the stored manifest with Server-Side Apply, so resources
+    /// that made it to the cluster are left as they are and missing ones are
+    /// created, then runs the post-operation hooks again and marks the
+    /// release deployed. Works on stale pending releases and recoverable
+    /// failures; a deployed release is left untouched, so this is safe to
+    /// run repeatedly.
+    pub async fn resume(&self, namespace: &str, name: &str) -> Result<RecoveryReport> {
+        let mut release = self.storage.get_latest(namespace, name).await?;
 
-    /// Create a ResourceManager for Kubernetes operations
-    async fn resource_manager(&self) -> Result<ResourceManager> {
-        ResourceManager::new(self.client.clone()).await
-    }
+        if matches!(release.state, ReleaseState::Deployed) {
+            return Ok(RecoveryReport {
+                release,
+                reapplied: Vec::new(),
+                already_present: Vec::new(),
+                hooks: Vec::new(),
+            });
+        }
 
-    /// Apply a manifest to the cluster using Server-Side Apply
-    async fn apply_manifest(&self, namespace: &str, manifest: &str) -> Result<()> {
-        let manager = self.resource_manager().await?;
-        let summary = manager.apply_manifest(namespace, manifest, false).await?;
+        let phase = resume_phase(&release)?;
 
-        if !summary.is_success() {
-            let errors: Vec<String> = summary
-                .failed
-                .iter()
-                .map(|(name, err)| format!("{}: {}", name, err))
-                .collect();
-            return Err(KubeError::InvalidConfig(format!(
-                "Failed to apply resources: {}",
-                errors.join("; ")
-            )));
+        // Only stale locks: a younger operation may still be running
+        if release.state.is_pending() && !self.is_lock_stale(&release.state) {
+            return Err(self.locked(&release));
         }
 
-        Ok(())
-    }
-
-    /// Apply a manifest in dry-run mode (validate without applying)
-    #[allow(dead_code)]
-    async fn apply_manifest_dry_run(
+        // Hold the release while resuming
+        let attempt = match &release.state {
+            ReleaseState::Recovering { attempt, .. } => attempt + 1,
+            _ => 1,
+        };
+        let from_status = match &release.state {
+            ReleaseState::Recovering { from_status, .. } => from_status.clone(),
+            state => state.status_name().to_string(),
+        };
+        release.state = ReleaseState::Recovering {
+            from_status,
+            attempt,
+            started_at: chrono::Utc::now(),
+        };
+        self.storage.update(&release).await?;
+
+        let result = self.resume_locked(&mut release, phase).await;
+        self.unlock_on_error(namespace, name, release.version, result)
+            .await
+    }
+
+    async fn resume_locked(
+        &self,
+        release: &mut StoredRelease,
+        phase: HookPhase,
+    ) -> Result<RecoveryReport> {
+        let manager = self
+            .resource_manager()
+            .await?
+            .with_ownership(ReleaseOwnership::deployed(
+                &release.name,
+                &release.namespace,
+            ));
+        let summary = manager
+            .apply_manifest(&release.namespace, &release.manifest, false)
+            .await?;
+
+        if !summary.is_success() {
+            let failures: Vec<String> = summary
+                .failed
+                .iter()
+                .map(|(resource, error)| format!("{}: {}", resource, error))
+                .collect();
+            return Err(KubeError::InvalidConfig(format!(
+                "failed to re-apply {} resource(s): {}",
+                failures.len(),
+                failures.join("; ")
+            )));
+        }
+
+        let (reapplied, already_present): (Vec<_>, Vec<_>) = summary
+            .applied
+            .iter()
+            .map(|result| {
+                let key = match &result.namespace {
+                    Some(ns) => format!("{}/{}/{}", ns, result.kind, result.name),
+                    None => format!("{}/{}", result.kind, result.name),
+                };
+                (key, result.created)
+            })
+            .partition(|(_, created)| *created);
+
+        let mut hook_executor = HookExecutor::new();
+        self.run_hooks(&mut hook_executor, release, phase).await?;
+
+        release.mark_deployed();
+        self.storage.update(release).await?;
+
+        Ok(RecoveryReport {
+            release: release.clone(),
+            reapplied: reapplied.into_iter().map(|(key, _)| key).collect(),
+            already_present: already_present.into_iter().map(|(key, _)| key).collect(),
+            hooks: hook_executor.results,
+        })
+    }
+
+    // ========== Server-Side Dry Run ==========
+
+    /// Apply a manifest with `dryRun=All` (validate without persisting)
+    ///
+    /// Admission webhooks and server-side defaulting run as for a real
+    /// apply. Each entry of [`OperationSummary::applied`] carries the object
+    /// the server would have stored.
+    pub async fn server_dry_run(
         &self,
         namespace: &str,
         manifest: &str,
-    ) -> Result<crate::resources::OperationSummary> {
+    ) -> Result<OperationSummary> {
         let manager = self.resource_manager().await?;
         manager.apply_manifest(namespace, manifest, true).await
     }
 
+    // ========== Internal Helpers ==========
+
+    /// Create a ResourceManager for Kubernetes operations
+    async fn resource_manager(&self) -> Result<ResourceManager> {
+        ResourceManager::new(self.client.clone()).await
+    }
+
+    /// Apply a manifest to the cluster using Server-Side Apply
+    ///
+    /// Resources are applied wave by wave; `timeout` bounds the wait on
+    /// each wave gate.
+    ///
+    /// Resources are stamped as `ownership`'s release's, and existing ones
+    /// must belong to it or be adopted. Adopted resources are reported
+    /// through `progress`, like resources the server changed with `verify`,
+    /// or on the terminal without it. With `verify`, resources
+    /// the server changed from the manifest are warned about through
+    /// `progress`, or on the terminal without it. `wait_for`
+    /// conditions on a wave's resources must hold before the next wave.
+    #[allow(clippy::too_many_arguments)]
+    async fn apply_manifest(
+        &self,
+        namespace: &str,
+        manifest: &str,
+        timeout: Option<chrono::Duration>,
+        ownership: ReleaseOwnership,
+        immutable: ImmutablePolicy,
+        verify: bool,
+        wait_for: &[WaitCondition],
+        progress: Option<Arc<dyn ProgressSink>>,
+    ) -> Result<()> {
+        let plan = ExecutionPlan::from_manifest(manifest)?;
+        let manager = self
+            .resource_manager()
+            .await?
+            .with_ownership(ownership)
+            .with_immutable_policy(immutable)
+            .with_apply_verification(verify);
+        let applier = ClusterWaveApplier::new(manager, self.client.clone(), namespace);
+
+        let mut config = WaveExecutionConfig {
+            wait_for: wait_for.to_vec(),
+            ..Default::default()
+        };
+        if let Some(t) = timeout.and_then(|t| t.to_std().ok()) {
+            config.timeout = t;
+        }
+
+        let mut executor = WaveExecutor::new(applier, config);
+        if let Some(progress) = &progress {
+            executor = executor.with_progress(progress.clone());
+        }
+        executor.execute(&plan).await?;
+
+        let warnings = executor.applier().warnings();
+        let adopted = executor.applier().adopted();
+        if !warnings.is_empty() || !adopted.is_empty() {
+            let progress = progress.unwrap_or_else(|| ProgressFormat::Terminal.sink());
+            for (resource, changes) in warnings {
+                let (kind, name) = kind_and_name(&resource);
+                progress.emit(&ProgressEvent::warning(
+                    kind,
+                    name,
+                    format!("changed by the server after apply\n{}", changes),
+                ));
+            }
+            for resource in &adopted {
+                let (kind, name) = kind_and_name(resource);
+                progress.emit(
+                    &ProgressEvent::new(kind, name, ResourceStatus::Applied)
+                        .with_message("adopted into the release"),
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Run a server-side dry run of a release manifest
+    ///
+    /// Rejected resources fail the dry run; resources the server could not
+    /// validate yet (missing CRD or namespace) are only warned about.
+    async fn validate_on_server(&self, release: &StoredRelease) -> Result<()> {
+        let summary = self
+            .server_dry_run(&release.namespace, &release.manifest)
+            .await?;
+
+        for (name, reason) in &summary.skipped {
+            tracing::warn!("{}: {}", name, reason);
+        }
+
+        if !summary.is_success() {
+            return Err(KubeError::DryRunRejected {
+                failures: summary
+                    .failed
+                    .iter()
+                    .map(|(name, err)| format!("{}: {}", name, err))
+                    .collect(),
+            });
+        }
+
+        Ok(())
+    }
+
     /// Delete resources from a manifest
-    async fn delete_manifest(&self, namespace: &str, manifest: &str) -> Result<()> {
-        let manager = self.resource_manager().await?;
+    ///
+    /// Resources annotated `resource-policy: keep` are left in place and
+    /// reported as skipped through `progress`, or on the terminal without it.
+    async fn delete_manifest(
+        &self,
+        namespace: &str,
+        manifest: &str,
+        ordered: bool,
+        progress: Option<Arc<dyn ProgressSink>>,
+    ) -> Result<()> {
+        let manager = self.resource_manager().await?.with_ordered_delete(ordered);
         let summary = manager.delete_manifest(namespace, manifest, false).await?;
 
+        if !summary.retained.is_empty() {
+            let progress = progress.unwrap_or_else(|| ProgressFormat::Terminal.sink());
+            for resource in &summary.retained {
+                let (kind, name) = kind_and_name(resource);
+                progress.emit(
+                    &ProgressEvent::new(kind, name, ResourceStatus::Skipped)
+                        .with_message("retained (resource-policy: keep)"),
+                );
+            }
+        }
+
         if !summary.is_success() {
             let errors: Vec<String> = summary
                 .failed
@@ -821,18 +1497,52 @@ impl<S: StorageDriver> KubeClient<S> {
         Ok(())
     }
 
+    /// Delete resources of `previous` that `current` no longer renders
+    ///
+    /// Run after an upgrade or rollback applied `current`, so the cluster
+    /// only keeps what the new revision renders (and kept resources).
+    async fn delete_removed(
+        &self,
+        previous: &StoredRelease,
+        current: &StoredRelease,
+        progress: Option<Arc<dyn ProgressSink>>,
+    ) -> Result<()> {
+        let removed = removed_manifest(&previous.manifest, &current.manifest);
+        if removed.is_empty() {
+            return Ok(());
+        }
+        self.delete_manifest(&current.namespace, &removed, true, progress)
+            .await
+    }
+
     /// Cleanup failed release resources
     async fn cleanup_release(&self, release: &StoredRelease) -> Result<()> {
         // Delete all resources from the manifest
-        self.delete_manifest(&release.namespace, &release.manifest)
+        self.delete_manifest(&release.namespace, &release.manifest, true, None)
             .await
     }
 
+    /// Remove the resources of a failed atomic install
+    ///
+    /// The release is recorded as rolled back once its resources are gone,
+    /// and stays failed if they couldn't be deleted.
+    async fn remove_failed_install(&self, release: &mut StoredRelease) -> Result<()> {
+        if self.cleanup_release(release).await.is_ok() {
+            let error = failure_reason(release);
+            release.mark_atomic_rollback(None, error);
+            self.storage.update(release).await?;
+        }
+        Ok(())
+    }
+
     /// Rollback to a specific version (internal, used for atomic operations)
+    ///
+    /// The failed revision is recorded as rolled back, keeping its error.
     async fn rollback_to(
         &self,
         current: &StoredRelease,
         target_version: u32,
+        timeout: Option<chrono::Duration>,
     ) -> Result<StoredRelease> {
         // Verify the target release exists
         let _target = self
@@ -846,35 +1556,1297 @@ impl<S: StorageDriver> KubeClient<S> {
             namespace: current.namespace.clone(),
             revision: target_version,
             wait: true,
-            timeout: Some(chrono::Duration::minutes(5)),
+            timeout: Some(timeout.unwrap_or(chrono::Duration::minutes(5))),
             ..Default::default()
         };
 
         // Perform the rollback
-        self.rollback(&options).await
+        let rolled_back = self.rollback(&options).await?;
+
+        // The rollback marked the failed revision superseded, record why
+        let mut failed = self
+            .storage
+            .get(&current.namespace, &current.name, current.version)
+            .await?;
+        failed.mark_atomic_rollback(Some(target_version), failure_reason(current));
+        self.storage.update(&failed).await?;
+
+        Ok(rolled_back)
     }
 
-    /// Cleanup old releases beyond max_history
-    async fn cleanup_history(&self, namespace: &str, name: &str, max_history: u32) -> Result<()> {
-        let history = self.storage.history(namespace, name).await?;
+    /// Whether a pending state has outlived its operation's timeout
+    ///
+    /// States without a timeout of their own use the lock timeout.
+    fn is_lock_stale(&self, state: &ReleaseState) -> bool {
+        let timeout = state.timeout().unwrap_or(self.lock_timeout);
+        state.elapsed().is_none_or(|elapsed| elapsed >= timeout)
+    }
+
+    /// Lock error naming the operation pending on `release`
+    fn locked(&self, release: &StoredRelease) -> KubeError {
+        let elapsed = release
+            .state
+            .elapsed()
+            .map(|d| format!("{} seconds", d.num_seconds()))
+            .unwrap_or_else(|| "unknown".to_string());
+        KubeError::ReleaseLocked {
+            name: release.name.clone(),
+            holder: format!(
+                "{} of revision {} by {}, started {} ago",
+                release.state.status_name(),
+                release.version,
+                release.state.owner().unwrap_or("an unknown process"),
+                elapsed
+            ),
+        }
+    }
 
-        if history.len() as u32 <= max_history {
+    /// Fail if an operation is pending on `latest`, the newest revision
+    ///
+    /// An operation within its timeout holds the release; an older one is
+    /// reported stuck, to be cleared with [`Self::recover`].
+    fn check_lock(&self, latest: &StoredRelease) -> Result<()> {
+        if !latest.state.is_pending() {
             return Ok(());
         }
 
-        // Delete oldest releases beyond max_history
+        if !self.is_lock_stale(&latest.state) {
+            return Err(self.locked(latest));
+        }
+
+        Err(KubeError::StuckRelease {
+            name: latest.name.clone(),
+            status: latest.state.status_name().to_string(),
+            elapsed: latest
+                .state
+                .elapsed()
+                .map(|d| format!("{} seconds", d.num_seconds()))
+                .unwrap_or_else(|| "unknown".to_string()),
+        })
+    }
+
+    /// Store a new pending revision, which locks the release
+    ///
+    /// Storing is the optimistic lock: when a concurrent operation already
+    /// stored this revision, the release is reported locked by it.
+    async fn lock(&self, release: &StoredRelease) -> Result<()> {
+        match self.storage.create(release).await {
+            Err(KubeError::ReleaseAlreadyExists { .. }) => Err(self.lost_lock(release).await),
+            other => other,
+        }
+    }
+
+    /// Lock error for `release`, after a concurrent operation stored it first
+    async fn lost_lock(&self, release: &StoredRelease) -> KubeError {
+        let holder = match self
+            .storage
+            .get(&release.namespace, &release.name, release.version)
+            .await
+        {
+            Ok(holder) => holder,
+            Err(e) => return e,
+        };
+        if holder.state.is_pending() {
+            return self.locked(&holder);
+        }
+        KubeError::ReleaseLocked {
+            name: release.name.clone(),
+            holder: format!(
+                "revision {} changed concurrently (now {})",
+                holder.version,
+                holder.state.status_name()
+            ),
+        }
+    }
+
+    /// Run the hooks of `phase`, recording how each went on `release`
+    async fn run_hooks(
+        &self,
+        executor: &mut HookExecutor,
+        release: &mut StoredRelease,
+        phase: HookPhase,
+    ) -> Result<()> {
+        let ran = executor.results.len();
+        let result = executor
+            .execute_phase(
+                &release.hooks,
+                phase,
+                &release.name,
+                release.version,
+                &self.client,
+            )
+            .await;
+        release
+            .hook_runs
+            .extend(executor.results[ran..].iter().map(HookRun::from));
+        result
+    }
+
+    /// Mark revision `version` failed if `result` failed while it is pending
+    ///
+    /// Failures already recorded (failed, rolled back) are kept; this only
+    /// releases the lock of operations interrupted by an early error.
+    async fn unlock_on_error<T>(
+        &self,
+        namespace: &str,
+        name: &str,
+        version: u32,
+        result: Result<T>,
+    ) -> Result<T> {
+        let Err(e) = &result else {
+            return result;
+        };
+
+        match self.storage.get(namespace, name, version).await {
+            Ok(mut release) if release.state.is_pending() => {
+                release.mark_failed(e.to_string(), true);
+                if let Err(update) = self.storage.update(&release).await {
+                    tracing::warn!(
+                        "Failed to release the lock of {}/{}: {}",
+                        namespace,
+                        name,
+                        update
+                    );
+                }
+            }
+            Ok(_) => {}
+            Err(get) => tracing::warn!(
+                "Failed to release the lock of {}/{}: {}",
+                namespace,
+                name,
+                get
+            ),
+        }
+
+        result
+    }
+
+    /// Delete revisions beyond `max_history`, returning their versions
+    ///
+    /// Without `max_history`, the storage driver's limit applies; 0 keeps
+    /// every revision. The deployed revision is kept even when it is older
+    /// than the most recent `max_history`. Pruning runs after an operation
+    /// succeeded, so failures are only warned about.
+    async fn prune_history(
+        &self,
+        namespace: &str,
+        name: &str,
+        max_history: Option<u32>,
+    ) -> Vec<u32> {
+        let Some(max_history) = max_history
+            .or_else(|| self.storage.max_history())
+            .filter(|&n| n > 0)
+        else {
+            return Vec::new();
+        };
+
+        // Newest first
+        let history = match self.storage.history(namespace, name).await {
+            Ok(history) => history,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to read the history of {}/{}: {}",
+                    namespace,
+                    name,
+                    e
+                );
+                return Vec::new();
+            }
+        };
+
+        let mut pruned = Vec::new();
         for release in history.iter().skip(max_history as usize) {
-            self.storage
-                .delete(namespace, name, release.version)
-                .await?;
+            if matches!(release.state, ReleaseState::Deployed) {
+                continue;
+            }
+            match self.storage.delete(namespace, name, release.version).await {
+                Ok(_) => pruned.push(release.version),
+                Err(e) => tracing::warn!(
+                    "Failed to prune revision {} of {}/{}: {}",
+                    release.version,
+                    namespace,
+                    name,
+                    e
+                ),
+            }
         }
 
-        Ok(())
+        pruned
+    }
+}
+
+/// Kind and name of a `[namespace/]Kind/name` resource key
+fn kind_and_name(resource: &str) -> (&str, &str) {
+    let mut parts = resource.rsplitn(3, '/');
+    let name = parts.next().unwrap_or_default();
+    let kind = parts.next().unwrap_or_default();
+    (kind, name)
+}
+
+/// Manifest of the resources in `previous` missing from `current`
+///
+/// Hooks are left out, their delete policy decides when they go.
+fn removed_manifest(previous: &str, current: &str) -> String {
+    let current = parse_manifest_resources(current);
+    let mut removed: Vec<(ResourceKey, String)> = parse_manifest_resources(previous)
+        .into_iter()
+        .filter(|(key, doc)| {
+            !current.contains_key(key)
+                && !Resource::from_yaml(doc).is_ok_and(|resource| resource.is_hook)
+        })
+        .collect();
+    removed.sort_by(|(a, _), (b, _)| (&a.kind, &a.name).cmp(&(&b.kind, &b.name)));
+
+    removed
+        .into_iter()
+        .map(|(_, doc)| doc)
+        .collect::<Vec<_>>()
+        .join("\n---\n")
+}
+
+/// Reject library packs and packs with structural problems, the latter with
+/// the messages of `sherpack lint`
+fn validate_pack(pack: &LoadedPack) -> Result<()> {
+    pack.pack
+        .ensure_deployable()
+        .map_err(|e| KubeError::Pack(e.to_string()))?;
+    pack.validate().map_err(|errors| {
+        KubeError::Pack(
+            errors
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("; "),
+        )
+    })
+}
+
+/// Error recorded on a failed release
+fn failure_reason(release: &StoredRelease) -> String {
+    match &release.state {
+        ReleaseState::Failed { reason, .. } => reason.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// API versions served by the cluster, like Helm's `.Capabilities.APIVersions`
+///
+/// Each version is listed as `group/version` and, for every kind it serves,
+/// as `group/version/Kind`.
+fn discovered_api_versions(discovery: &Discovery) -> Vec<String> {
+    let mut api_versions = Vec::new();
+    for group in discovery.groups() {
+        for version in group.versions() {
+            let group_version = if group.name().is_empty() {
+                version.to_string()
+            } else {
+                format!("{}/{}", group.name(), version)
+            };
+            for (resource, _) in group.versioned_resources(version) {
+                api_versions.push(format!("{}/{}", group_version, resource.kind));
+            }
+            api_versions.push(group_version);
+        }
+    }
+    api_versions
+}
+
+/// Hook phase completing the operation `release` was interrupted in
+///
+/// A failed release carries no record of its operation: the first revision
+/// is treated as an install, later ones as upgrades.
+fn resume_phase(release: &StoredRelease) -> Result<HookPhase> {
+    let status = match &release.state {
+        ReleaseState::Recovering { from_status, .. } => from_status.as_str(),
+        state => state.status_name(),
+    };
+
+    match (status, &release.state) {
+        ("pending-install", _) => Ok(HookPhase::PostInstall),
+        ("pending-upgrade", _) => Ok(HookPhase::PostUpgrade),
+        ("pending-rollback", _) => Ok(HookPhase::PostRollback),
+        (
+            "failed",
+            ReleaseState::Failed {
+                recoverable: false,
+                reason,
+                ..
+            },
+        ) => Err(KubeError::InvalidConfig(format!(
+            "release '{}' failed unrecoverably: {}",
+            release.name, reason
+        ))),
+        ("failed", _) if release.version == 1 => Ok(HookPhase::PostInstall),
+        ("failed", _) => Ok(HookPhase::PostUpgrade),
+        (other, _) => Err(KubeError::InvalidConfig(format!(
+            "release '{}' cannot be resumed from state {}",
+            release.name, other
+        ))),
     }
 }
 
 #[cfg(test)]
 mod tests {
-    // Integration tests would go here
-    // They require a running Kubernetes cluster
+    use super::*;
+    use crate::storage::MockStorageDriver;
+    use http::{Request, Response};
+    use kube::client::Body;
+
+    #[tokio::test]
+    async fn test_server_info_parses_version() {
+        let (service, mut handle) = tower_test::mock::pair::<Request<Body>, Response<Body>>();
+
+        let server = tokio::spawn(async move {
+            let (request, send) = handle.next_request().await.expect("service not called");
+            assert_eq!(request.uri().path(), "/version");
+
+            let body = serde_json::json!({
+                "major": "1",
+                "minor": "30+",
+                "gitVersion": "v1.30.2-gke.1",
+                "gitCommit": "abc123",
+                "gitTreeState": "clean",
+                "buildDate": "2024-06-01T00:00:00Z",
+                "goVersion": "go1.22.4",
+                "compiler": "gc",
+                "platform": "linux/amd64"
+            });
+            send.send_response(
+                Response::builder()
+                    .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                    .unwrap(),
+            );
+        });
+
+        let client = KubeClient::with_client(
+            kube::Client::new(service, "default"),
+            MockStorageDriver::new(),
+        );
+        let info = client.server_info().await.unwrap();
+        server.await.unwrap();
+
+        assert!(info.reachable);
+        assert_eq!(info.kube_version.major, "1");
+        assert_eq!(info.kube_version.minor, "30");
+        assert_eq!(info.kube_version.version, "v1.30.2-gke.1");
+        assert_eq!(info.platform.as_deref(), Some("linux/amd64"));
+    }
+
+    #[tokio::test]
+    async fn test_check_kube_version() {
+        let (service, mut handle) = tower_test::mock::pair::<Request<Body>, Response<Body>>();
+        let server = tokio::spawn(async move {
+            while let Some((request, send)) = handle.next_request().await {
+                assert_eq!(request.uri().path(), "/version");
+                let body = serde_json::json!({
+                    "major": "1",
+                    "minor": "30+",
+                    "gitVersion": "v1.30.2-gke.1",
+                    "gitCommit": "abc123",
+                    "gitTreeState": "clean",
+                    "buildDate": "2024-06-01T00:00:00Z",
+                    "goVersion": "go1.22.4",
+                    "compiler": "gc",
+                    "platform": "linux/amd64"
+                });
+                send.send_response(
+                    Response::builder()
+                        .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                        .unwrap(),
+                );
+            }
+        });
+
+        let client = KubeClient::with_client(
+            kube::Client::new(service, "default"),
+            MockStorageDriver::new(),
+        );
+        let dir = tempfile::tempdir().unwrap();
+        let pack = |constraint: &str| {
+            std::fs::write(
+                dir.path().join("Pack.yaml"),
+                format!(
+                    "apiVersion: sherpack/v1\nmetadata:\n  name: web\n  version: 1.0.0\n  kubeVersion: \"{}\"\n",
+                    constraint
+                ),
+            )
+            .unwrap();
+            LoadedPack::load(dir.path()).unwrap()
+        };
+
+        // The distribution suffix doesn't make it a prerelease
+        client.check_kube_version(&pack(">= 1.27")).await.unwrap();
+        client
+            .check_kube_version(&pack(">=1.25.0-0, <1.31.0"))
+            .await
+            .unwrap();
+
+        let err = client
+            .check_kube_version(&pack("< 1.29"))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            &err,
+            KubeError::KubeVersionMismatch { pack, constraint, version }
+                if pack == "web" && constraint == "< 1.29" && version == "v1.30.2-gke.1"
+        ));
+
+        drop(client);
+        server.await.unwrap();
+    }
+
+    #[test]
+    fn test_server_info_falls_back_to_git_version() {
+        let info = k8s_openapi::apimachinery::pkg::version::Info {
+            git_version: "v1.29.4".to_string(),
+            ..Default::default()
+        };
+
+        let server = ServerInfo::from_version_info(&info);
+        assert_eq!(server.kube_version.major, "1");
+        assert_eq!(server.kube_version.minor, "29");
+        assert!(server.platform.is_none());
+    }
+
+    fn orphan_test_release() -> StoredRelease {
+        let pack = serde_json::from_value(serde_json::json!({
+            "name": "web",
+            "version": "1.0.0"
+        }))
+        .unwrap();
+        let manifest = "apiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: web-config\n";
+
+        StoredRelease::for_install(
+            "web".to_string(),
+            "default".to_string(),
+            pack,
+            Values::new(),
+            manifest.to_string(),
+        )
+    }
+
+    /// Serve list/delete requests, returning `(method, path)` of each call
+    ///
+    /// ConfigMap lists contain the release's own ConfigMap plus a stale one;
+    /// every other kind is empty.
+    fn spawn_orphan_server(
+        mut handle: tower_test::mock::Handle<Request<Body>, Response<Body>>,
+    ) -> tokio::task::JoinHandle<Vec<(String, String)>> {
+        tokio::spawn(async move {
+            let mut calls = Vec::new();
+
+            while let Some((request, send)) = handle.next_request().await {
+                let path = request.uri().path().to_string();
+                calls.push((request.method().to_string(), path.clone()));
+
+                let labeled = |name: &str| {
+                    serde_json::json!({
+                        "apiVersion": "v1",
+                        "kind": "ConfigMap",
+                        "metadata": {
+                            "name": name,
+                            "namespace": "default",
+                            "labels": {
+                                "app.kubernetes.io/managed-by": "sherpack",
+                                "sherpack.io/release-name": "web"
+                            }
+                        }
+                    })
+                };
+
+                let body = if request.method() == http::Method::DELETE {
+                    labeled("web-stale")
+                } else if path.ends_with("/configmaps") {
+                    serde_json::json!({
+                        "apiVersion": "v1",
+                        "kind": "ConfigMapList",
+                        "metadata": {},
+                        "items": [labeled("web-config"), labeled("web-stale")]
+                    })
+                } else {
+                    serde_json::json!({"apiVersion": "v1", "kind": "List", "metadata": {}, "items": []})
+                };
+
+                send.send_response(
+                    Response::builder()
+                        .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                        .unwrap(),
+                );
+            }
+
+            calls
+        })
+    }
+
+    #[tokio::test]
+    async fn test_find_orphans_reports_without_deleting() {
+        let (service, handle) = tower_test::mock::pair::<Request<Body>, Response<Body>>();
+        let server = spawn_orphan_server(handle);
+
+        let client = KubeClient::with_client(
+            kube::Client::new(service, "default"),
+            MockStorageDriver::new(),
+        );
+        let orphans = client.find_orphans(&orphan_test_release()).await.unwrap();
+        drop(client);
+        let calls = server.await.unwrap();
+
+        assert_eq!(orphans.len(), 1);
+        assert_eq!(orphans[0].kind, "ConfigMap");
+        assert_eq!(orphans[0].name, "web-stale");
+        assert!(calls.iter().all(|(method, _)| method == "GET"));
+    }
+
+    #[tokio::test]
+    async fn test_delete_orphans_removes_stale_resource() {
+        let (service, handle) = tower_test::mock::pair::<Request<Body>, Response<Body>>();
+        let server = spawn_orphan_server(handle);
+
+        let client = KubeClient::with_client(
+            kube::Client::new(service, "default"),
+            MockStorageDriver::new(),
+        );
+        let orphans = client.find_orphans(&orphan_test_release()).await.unwrap();
+        client.delete_orphans("default", &orphans).await.unwrap();
+        drop(client);
+        let calls = server.await.unwrap();
+
+        let deletes: Vec<_> = calls
+            .iter()
+            .filter(|(method, _)| method == "DELETE")
+            .map(|(_, path)| path.as_str())
+            .collect();
+        assert_eq!(
+            deletes,
+            vec!["/api/v1/namespaces/default/configmaps/web-stale"]
+        );
+    }
+
+    /// Serve core API discovery (ConfigMaps and PVCs), applies and deletes,
+    /// returning `(method, path)` of each call
+    fn spawn_delete_server(
+        mut handle: tower_test::mock::Handle<Request<Body>, Response<Body>>,
+    ) -> tokio::task::JoinHandle<Vec<(String, String)>> {
+        tokio::spawn(async move {
+            let mut calls = Vec::new();
+
+            while let Some((request, send)) = handle.next_request().await {
+                let path = request.uri().path().to_string();
+                calls.push((request.method().to_string(), path.clone()));
+
+                let body = match (request.method(), path.as_str()) {
+                    (&http::Method::DELETE, _) => serde_json::json!({
+                        "apiVersion": "v1",
+                        "kind": "Status",
+                        "metadata": {},
+                        "status": "Success"
+                    }),
+                    (&http::Method::PATCH, _) => serde_json::json!({
+                        "apiVersion": "v1",
+                        "kind": "ConfigMap",
+                        "metadata": {
+                            "name": path.rsplit('/').next().unwrap_or_default(),
+                            "namespace": "default"
+                        }
+                    }),
+                    (_, "/api") => serde_json::json!({
+                        "kind": "APIVersions",
+                        "versions": ["v1"],
+                        "serverAddressByClientCIDRs": []
+                    }),
+                    (_, "/api/v1") => serde_json::json!({
+                        "kind": "APIResourceList",
+                        "groupVersion": "v1",
+                        "resources": [{
+                            "name": "configmaps",
+                            "singularName": "configmap",
+                            "namespaced": true,
+                            "kind": "ConfigMap",
+                            "verbs": ["get", "list", "create", "update", "patch", "delete"]
+                        }, {
+                            "name": "persistentvolumeclaims",
+                            "singularName": "persistentvolumeclaim",
+                            "namespaced": true,
+                            "kind": "PersistentVolumeClaim",
+                            "verbs": ["get", "list", "create", "update", "patch", "delete"]
+                        }]
+                    }),
+                    _ => {
+                        serde_json::json!({"kind": "APIGroupList", "apiVersion": "v1", "groups": []})
+                    }
+                };
+
+                send.send_response(
+                    Response::builder()
+                        .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                        .unwrap(),
+                );
+            }
+
+            calls
+        })
+    }
+
+    #[tokio::test]
+    async fn test_hook_runs_recorded_on_release() {
+        let (service, handle) = tower_test::mock::pair::<Request<Body>, Response<Body>>();
+        let server = spawn_delete_server(handle);
+        let client = KubeClient::with_client(
+            kube::Client::new(service, "default"),
+            MockStorageDriver::new(),
+        );
+
+        let mut release = orphan_test_release();
+        release.hooks = parse_hooks_from_manifest(
+            "apiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: seed\n  annotations:\n    \
+             sherpack.io/hook: pre-install,post-install\n",
+        );
+        let mut executor = HookExecutor::new();
+        for phase in [HookPhase::PreInstall, HookPhase::PostInstall] {
+            client
+                .run_hooks(&mut executor, &mut release, phase)
+                .await
+                .unwrap();
+        }
+        drop(client);
+        server.await.unwrap();
+
+        let runs: Vec<_> = release
+            .hook_runs
+            .iter()
+            .map(|run| (run.name.as_str(), run.phase, run.success, run.timed_out))
+            .collect();
+        assert_eq!(
+            runs,
+            [
+                ("seed", HookPhase::PreInstall, true, false),
+                ("seed", HookPhase::PostInstall, true, false),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_kept_pvc_survives_uninstall() {
+        let (service, handle) = tower_test::mock::pair::<Request<Body>, Response<Body>>();
+        let server = spawn_delete_server(handle);
+
+        let mut release = orphan_test_release();
+        release.manifest.push_str(
+            "---\napiVersion: v1\nkind: PersistentVolumeClaim\nmetadata:\n  name: web-data\n  \
+             annotations:\n    helm.sh/resource-policy: keep\n",
+        );
+        release.mark_deployed();
+        let client = KubeClient::with_client(
+            kube::Client::new(service, "default"),
+            MockStorageDriver::with_releases(vec![release]),
+        );
+
+        client
+            .uninstall(&UninstallOptions::new("web", "default"))
+            .await
+            .unwrap();
+        drop(client);
+        let deletes: Vec<_> = server
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|(method, _)| method == "DELETE")
+            .map(|(_, path)| path)
+            .collect();
+
+        assert_eq!(
+            deletes,
+            ["/api/v1/namespaces/default/configmaps/web-config"]
+        );
+    }
+
+    /// The `web` release deployed with a ConfigMap and a kept PVC, and a
+    /// pack whose next revision only renders the ConfigMap
+    fn kept_pvc_upgrade_setup() -> (MockStorageDriver, tempfile::TempDir) {
+        let mut release = orphan_test_release();
+        release.manifest.push_str(
+            "---\napiVersion: v1\nkind: PersistentVolumeClaim\nmetadata:\n  name: web-data\n  \
+             annotations:\n    helm.sh/resource-policy: keep\n---\napiVersion: v1\nkind: \
+             ConfigMap\nmetadata:\n  name: web-legacy\n",
+        );
+        release.mark_deployed();
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("Pack.yaml"),
+            "apiVersion: sherpack/v1\nkind: application\nmetadata:\n  name: web\n  version: 1.0.0\n",
+        )
+        .unwrap();
+        std::fs::create_dir(dir.path().join("templates")).unwrap();
+        std::fs::write(
+            dir.path().join("templates/config.yaml"),
+            "apiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: web-config\n",
+        )
+        .unwrap();
+
+        (MockStorageDriver::with_releases(vec![release]), dir)
+    }
+
+    #[tokio::test]
+    async fn test_kept_pvc_survives_upgrade_dropping_it() {
+        let (storage, dir) = kept_pvc_upgrade_setup();
+        let pack = LoadedPack::load(dir.path()).unwrap();
+        let (service, handle) = tower_test::mock::pair::<Request<Body>, Response<Body>>();
+        let server = spawn_delete_server(handle);
+        let client = KubeClient::with_client(kube::Client::new(service, "default"), storage);
+
+        let mut options = UpgradeOptions::new("web", "default");
+        options.skip_kube_version_check = true;
+        client
+            .upgrade(&pack, Values::new(), &options)
+            .await
+            .unwrap();
+        drop(client);
+        let deletes: Vec<_> = server
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|(method, _)| method == "DELETE")
+            .map(|(_, path)| path)
+            .collect();
+
+        assert_eq!(
+            deletes,
+            ["/api/v1/namespaces/default/configmaps/web-legacy"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rollback_deletes_resources_the_target_lacks() {
+        let mut history = release_history(2, 2);
+        history[1]
+            .manifest
+            .push_str("---\napiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: web-legacy\n");
+        let (service, handle) = tower_test::mock::pair::<Request<Body>, Response<Body>>();
+        let server = spawn_delete_server(handle);
+        let client = KubeClient::with_client(
+            kube::Client::new(service, "default"),
+            MockStorageDriver::with_releases(history),
+        );
+
+        client
+            .rollback(&RollbackOptions::new("web", "default").to_revision(1))
+            .await
+            .unwrap();
+        drop(client);
+        let deletes: Vec<_> = server
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|(method, _)| method == "DELETE")
+            .map(|(_, path)| path)
+            .collect();
+
+        assert_eq!(
+            deletes,
+            ["/api/v1/namespaces/default/configmaps/web-legacy"]
+        );
+    }
+
+    #[test]
+    fn test_removed_manifest_skips_hooks_and_current() {
+        let previous = "apiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: web-config\n\
+            ---\napiVersion: v1\nkind: Secret\nmetadata:\n  name: web-old\n\
+            ---\napiVersion: batch/v1\nkind: Job\nmetadata:\n  name: migrate\n  \
+            annotations:\n    sherpack.io/hook: pre-upgrade\n";
+        let current = "apiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: web-config\n";
+
+        assert_eq!(
+            removed_manifest(previous, current),
+            "apiVersion: v1\nkind: Secret\nmetadata:\n  name: web-old"
+        );
+        assert_eq!(removed_manifest(current, current), "");
+    }
+
+    #[tokio::test]
+    async fn test_failed_atomic_install_recorded_as_rolled_back() {
+        let (service, handle) = tower_test::mock::pair::<Request<Body>, Response<Body>>();
+        let server = spawn_delete_server(handle);
+
+        let client = KubeClient::with_client(
+            kube::Client::new(service, "default"),
+            MockStorageDriver::new(),
+        );
+        let mut release = orphan_test_release();
+        release.mark_failed("configmap/web-config not ready".to_string(), true);
+        client.storage().create(&release).await.unwrap();
+
+        client.remove_failed_install(&mut release).await.unwrap();
+        let stored = client.storage().get("default", "web", 1).await.unwrap();
+        drop(client);
+        let calls = server.await.unwrap();
+
+        assert!(calls.contains(&(
+            "DELETE".to_string(),
+            "/api/v1/namespaces/default/configmaps/web-config".to_string()
+        )));
+        assert_eq!(stored.state.status_name(), "rolled-back");
+        assert_eq!(
+            stored.state.to_string(),
+            "rolled back, install removed: configmap/web-config not ready"
+        );
+    }
+
+    #[derive(Debug, Default)]
+    struct EventLog(std::sync::Mutex<Vec<ProgressEvent>>);
+
+    impl ProgressSink for EventLog {
+        fn emit(&self, event: &ProgressEvent) {
+            self.0.lock().unwrap().push(event.clone());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_install_reports_adopted_resources() {
+        let (_, dir) = kept_pvc_upgrade_setup();
+        let pack = LoadedPack::load(dir.path()).unwrap();
+        let (service, handle) = tower_test::mock::pair::<Request<Body>, Response<Body>>();
+        let server = spawn_resume_server(handle, &["web-config"]);
+        let client = KubeClient::with_client(
+            kube::Client::new(service, "default"),
+            MockStorageDriver::new(),
+        );
+
+        let events = Arc::new(EventLog::default());
+        let mut options = InstallOptions::new("web", "default");
+        options.skip_kube_version_check = true;
+        options.take_ownership = true;
+        options.progress = Some(events.clone());
+        client
+            .install(&pack, Values::new(), &options)
+            .await
+            .unwrap();
+        drop(client);
+        server.await.unwrap();
+
+        let events = events.0.lock().unwrap();
+        let adopted: Vec<_> = events
+            .iter()
+            .filter(|e| e.message.as_deref() == Some("adopted into the release"))
+            .map(|e| (e.key(), e.phase))
+            .collect();
+        assert_eq!(
+            adopted,
+            [("ConfigMap/web-config".to_string(), ResourceStatus::Applied)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_install_refuses_library_pack() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("Pack.yaml"),
+            "apiVersion: sherpack/v1\nkind: library\nmetadata:\n  name: common\n  version: 1.0.0\n",
+        )
+        .unwrap();
+        std::fs::create_dir(dir.path().join("templates")).unwrap();
+        let pack = LoadedPack::load(dir.path()).unwrap();
+
+        // Refused before any request reaches the cluster
+        let (service, _handle) = tower_test::mock::pair::<Request<Body>, Response<Body>>();
+        let client = KubeClient::with_client(
+            kube::Client::new(service, "default"),
+            MockStorageDriver::new(),
+        );
+        let err = client
+            .install(&pack, Values::new(), &InstallOptions::new("web", "default"))
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, KubeError::Pack(_)));
+        assert!(
+            err.to_string().contains("'common' is a library pack"),
+            "{err}"
+        );
+    }
+
+    /// Revisions 1..=`count` of the `web` release, `deployed` deployed and
+    /// the others superseded
+    fn release_history(count: u32, deployed: u32) -> Vec<StoredRelease> {
+        (1..=count)
+            .map(|version| {
+                let mut release = orphan_test_release();
+                release.version = version;
+                if version == deployed {
+                    release.mark_deployed();
+                } else {
+                    release.mark_superseded();
+                }
+                release
+            })
+            .collect()
+    }
+
+    fn stored_versions(storage: &MockStorageDriver) -> Vec<u32> {
+        let mut versions: Vec<u32> = storage.all_releases().iter().map(|r| r.version).collect();
+        versions.sort();
+        versions
+    }
+
+    #[tokio::test]
+    async fn test_history_pruned_past_max_history() {
+        let storage = MockStorageDriver::with_releases(release_history(5, 5)).with_max_history(3);
+        let (service, _handle) = tower_test::mock::pair::<Request<Body>, Response<Body>>();
+        let client =
+            KubeClient::with_client(kube::Client::new(service, "default"), storage.clone());
+
+        // The storage limit applies when the operation sets none
+        assert_eq!(client.prune_history("default", "web", None).await, [2, 1]);
+        assert_eq!(stored_versions(&storage), [3, 4, 5]);
+
+        // The operation's limit wins, 0 keeps everything
+        assert!(
+            client
+                .prune_history("default", "web", Some(0))
+                .await
+                .is_empty()
+        );
+        assert_eq!(
+            client.prune_history("default", "web", Some(1)).await,
+            [4, 3]
+        );
+        assert_eq!(stored_versions(&storage), [5]);
+    }
+
+    #[tokio::test]
+    async fn test_history_pruning_keeps_deployed_revision() {
+        // Revisions 2 to 4 failed or were rolled back, 1 is still deployed
+        let storage = MockStorageDriver::with_releases(release_history(4, 1));
+        let (service, _handle) = tower_test::mock::pair::<Request<Body>, Response<Body>>();
+        let client =
+            KubeClient::with_client(kube::Client::new(service, "default"), storage.clone());
+
+        assert_eq!(client.prune_history("default", "web", Some(2)).await, [2]);
+        assert_eq!(stored_versions(&storage), [1, 3, 4]);
+    }
+
+    /// Deployed revision 1 of `web` and revision 2 pending an upgrade
+    /// started `age` ago by another process
+    fn pending_upgrade_storage(
+        age: chrono::Duration,
+        timeout: chrono::Duration,
+    ) -> MockStorageDriver {
+        let mut releases = release_history(1, 1);
+        let mut pending = StoredRelease::for_upgrade(&releases[0], Values::new(), String::new());
+        pending.state = ReleaseState::PendingUpgrade {
+            started_at: chrono::Utc::now() - age,
+            timeout,
+            previous_version: 1,
+            owner: Some("alice@ci (pid 7)".to_string()),
+        };
+        releases.push(pending);
+        MockStorageDriver::with_releases(releases)
+    }
+
+    fn storage_client(storage: MockStorageDriver) -> KubeClient<MockStorageDriver> {
+        let (service, _handle) = tower_test::mock::pair::<Request<Body>, Response<Body>>();
+        KubeClient::with_client(kube::Client::new(service, "default"), storage)
+    }
+
+    #[tokio::test]
+    async fn test_pending_operation_locks_release() {
+        let storage =
+            pending_upgrade_storage(chrono::Duration::seconds(30), DEFAULT_OPERATION_TIMEOUT);
+        let client = storage_client(storage.clone());
+
+        let err = client
+            .uninstall(&UninstallOptions::new("web", "default"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, KubeError::ReleaseLocked { .. }), "{err}");
+        assert!(
+            err.to_string()
+                .contains("pending-upgrade of revision 2 by alice@ci (pid 7)")
+        );
+
+        let err = client
+            .rollback(&RollbackOptions::new("web", "default").to_revision(1))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, KubeError::ReleaseLocked { .. }), "{err}");
+
+        // A running operation's lock can't be recovered
+        let err = client.recover("default", "web").await.unwrap_err();
+        assert!(matches!(err, KubeError::ReleaseLocked { .. }), "{err}");
+        let latest = storage.get_latest("default", "web").await.unwrap();
+        assert_eq!(latest.state.status_name(), "pending-upgrade");
+    }
+
+    #[tokio::test]
+    async fn test_stale_lock_cleared_by_recover() {
+        let storage =
+            pending_upgrade_storage(chrono::Duration::minutes(10), DEFAULT_OPERATION_TIMEOUT);
+        let client = storage_client(storage.clone());
+
+        let err = client
+            .uninstall(&UninstallOptions::new("web", "default"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, KubeError::StuckRelease { .. }), "{err}");
+
+        let recovered = client.recover("default", "web").await.unwrap();
+        assert_eq!(recovered.state.status_name(), "failed");
+
+        let mut options = UninstallOptions::new("web", "default");
+        options.dry_run = true;
+        client.uninstall(&options).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_operation_locks_release_for_its_own_timeout() {
+        // A long `--wait` upgrade, past the default lock timeout
+        let storage =
+            pending_upgrade_storage(chrono::Duration::minutes(10), chrono::Duration::minutes(30));
+        let client = storage_client(storage.clone());
+
+        let err = client
+            .uninstall(&UninstallOptions::new("web", "default"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, KubeError::ReleaseLocked { .. }), "{err}");
+        let err = client.recover("default", "web").await.unwrap_err();
+        assert!(matches!(err, KubeError::ReleaseLocked { .. }), "{err}");
+
+        // The lock timeout doesn't shorten it
+        let client = storage_client(storage).with_lock_timeout(chrono::Duration::seconds(10));
+        let err = client
+            .uninstall(&UninstallOptions::new("web", "default"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, KubeError::ReleaseLocked { .. }), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_lock_timeout_is_configurable() {
+        let storage =
+            pending_upgrade_storage(chrono::Duration::seconds(30), DEFAULT_OPERATION_TIMEOUT);
+        let mut recovering = storage.get("default", "web", 2).await.unwrap();
+        recovering.state = ReleaseState::Recovering {
+            from_status: "pending-upgrade".to_string(),
+            attempt: 1,
+            started_at: chrono::Utc::now() - chrono::Duration::seconds(30),
+        };
+        storage.update(&recovering).await.unwrap();
+
+        // Recoveries have no timeout of their own
+        let client = storage_client(storage.clone());
+        let err = client
+            .uninstall(&UninstallOptions::new("web", "default"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, KubeError::ReleaseLocked { .. }), "{err}");
+
+        let client = storage_client(storage).with_lock_timeout(chrono::Duration::seconds(10));
+        let err = client
+            .uninstall(&UninstallOptions::new("web", "default"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, KubeError::StuckRelease { .. }), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_uninstall_reported_locked() {
+        let storage = MockStorageDriver::with_releases(release_history(1, 1));
+        let client = storage_client(storage.clone());
+
+        // Both read the deployed revision before either locked it
+        let deployed = storage.get_latest("default", "web").await.unwrap();
+        let uninstalling = |owner: &str| {
+            let mut release = deployed.clone();
+            release.state = ReleaseState::PendingUninstall {
+                started_at: chrono::Utc::now(),
+                timeout: DEFAULT_OPERATION_TIMEOUT,
+                owner: Some(owner.to_string()),
+            };
+            release
+        };
+        let (mine, theirs) = (uninstalling("alice"), uninstalling("bob"));
+
+        assert!(storage.update_if(&mine, &deployed.state).await.unwrap());
+        assert!(!storage.update_if(&theirs, &deployed.state).await.unwrap());
+
+        let err = client.lost_lock(&theirs).await;
+        assert!(matches!(err, KubeError::ReleaseLocked { .. }), "{err}");
+        assert!(
+            err.to_string()
+                .contains("pending-uninstall of revision 1 by alice"),
+            "{err}"
+        );
+        let stored = storage.get("default", "web", 1).await.unwrap();
+        assert_eq!(stored.state.owner(), Some("alice"));
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_upgrade_reported_locked() {
+        let storage = MockStorageDriver::with_releases(release_history(1, 1));
+        let first = storage_client(storage.clone());
+        let second = storage_client(storage.clone());
+
+        // Both read revision 1 before either stored revision 2
+        let deployed = storage.get_latest("default", "web").await.unwrap();
+        let mine = StoredRelease::for_upgrade(&deployed, Values::new(), String::new());
+        let theirs = StoredRelease::for_upgrade(&deployed, Values::new(), String::new());
+
+        first.lock(&mine).await.unwrap();
+        let err = second.lock(&theirs).await.unwrap_err();
+        assert!(matches!(err, KubeError::ReleaseLocked { .. }), "{err}");
+        assert!(err.to_string().contains("pending-upgrade of revision 2"));
+    }
+
+    #[tokio::test]
+    async fn test_lock_released_on_error() {
+        let storage = MockStorageDriver::with_releases(release_history(1, 1));
+        let client = storage_client(storage.clone());
+        let deployed = storage.get_latest("default", "web").await.unwrap();
+        let release = StoredRelease::for_upgrade(&deployed, Values::new(), String::new());
+        client.lock(&release).await.unwrap();
+
+        // Success leaves the state to the operation
+        client
+            .unlock_on_error("default", "web", 2, Ok(()))
+            .await
+            .unwrap();
+        let stored = storage.get("default", "web", 2).await.unwrap();
+        assert!(stored.state.is_pending());
+
+        let failed: Result<()> = Err(KubeError::Template("undefined value".to_string()));
+        client
+            .unlock_on_error("default", "web", 2, failed)
+            .await
+            .unwrap_err();
+        let stored = storage.get("default", "web", 2).await.unwrap();
+        assert_eq!(
+            stored.state.to_string(),
+            "failed: template error: undefined value"
+        );
+    }
+
+    /// Serve discovery and ConfigMap get/apply requests against an
+    /// in-memory set of existing ConfigMaps, returning the applied names
+    fn spawn_resume_server(
+        mut handle: tower_test::mock::Handle<Request<Body>, Response<Body>>,
+        existing: &[&str],
+    ) -> tokio::task::JoinHandle<Vec<String>> {
+        let mut existing: std::collections::HashSet<String> =
+            existing.iter().map(|s| s.to_string()).collect();
+
+        tokio::spawn(async move {
+            let mut applied = Vec::new();
+
+            while let Some((request, send)) = handle.next_request().await {
+                let path = request.uri().path().to_string();
+                let name = path.rsplit('/').next().unwrap_or_default().to_string();
+                let config_map = serde_json::json!({
+                    "apiVersion": "v1",
+                    "kind": "ConfigMap",
+                    "metadata": {"name": name, "namespace": "default"}
+                });
+
+                let (status, body) = match (request.method(), path.as_str()) {
+                    (_, "/api") => (
+                        200,
+                        serde_json::json!({
+                            "kind": "APIVersions",
+                            "versions": ["v1"],
+                            "serverAddressByClientCIDRs": []
+                        }),
+                    ),
+                    (_, "/api/v1") => (
+                        200,
+                        serde_json::json!({
+                            "kind": "APIResourceList",
+                            "groupVersion": "v1",
+                            "resources": [{
+                                "name": "configmaps",
+                                "singularName": "configmap",
+                                "namespaced": true,
+                                "kind": "ConfigMap",
+                                "verbs": ["get", "list", "create", "update", "patch", "delete"]
+                            }]
+                        }),
+                    ),
+                    (_, "/apis") => (
+                        200,
+                        serde_json::json!({"kind": "APIGroupList", "apiVersion": "v1", "groups": []}),
+                    ),
+                    (&http::Method::PATCH, _) => {
+                        applied.push(name.clone());
+                        existing.insert(name);
+                        (200, config_map)
+                    }
+                    (_, _) if existing.contains(&name) => (200, config_map),
+                    _ => (
+                        404,
+                        serde_json::json!({
+                            "apiVersion": "v1",
+                            "kind": "Status",
+                            "metadata": {},
+                            "status": "Failure",
+                            "message": format!("configmaps \"{}\" not found", name),
+                            "reason": "NotFound",
+                            "code": 404
+                        }),
+                    ),
+                };
+
+                send.send_response(
+                    Response::builder()
+                        .status(status)
+                        .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                        .unwrap(),
+                );
+            }
+
+            applied
+        })
+    }
+
+    #[tokio::test]
+    async fn test_resume_reapplies_missing_resources() {
+        let mut release = orphan_test_release();
+        release.manifest = "apiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: web-config\n---\napiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: web-extra\n".to_string();
+        release.state = ReleaseState::PendingInstall {
+            started_at: chrono::Utc::now() - chrono::Duration::hours(1),
+            timeout: DEFAULT_OPERATION_TIMEOUT,
+            owner: None,
+        };
+        let storage = MockStorageDriver::with_releases(vec![release]);
+
+        let (service, handle) = tower_test::mock::pair::<Request<Body>, Response<Body>>();
+        let server = spawn_resume_server(handle, &["web-config"]);
+        let client =
+            KubeClient::with_client(kube::Client::new(service, "default"), storage.clone());
+
+        let report = client.resume("default", "web").await.unwrap();
+        assert_eq!(report.reapplied, ["default/ConfigMap/web-extra"]);
+        assert_eq!(report.already_present, ["default/ConfigMap/web-config"]);
+        assert_eq!(report.release.state.status_name(), "deployed");
+
+        // Running it again does nothing
+        let again = client.resume("default", "web").await.unwrap();
+        assert!(again.was_noop());
+
+        drop(client);
+        let mut applied = server.await.unwrap();
+        applied.sort();
+        assert_eq!(applied, ["web-config", "web-extra"]);
+
+        let stored = storage.get_latest("default", "web").await.unwrap();
+        assert_eq!(stored.state.status_name(), "deployed");
+    }
+
+    #[tokio::test]
+    async fn test_resume_respects_active_lock() {
+        let storage =
+            pending_upgrade_storage(chrono::Duration::seconds(30), DEFAULT_OPERATION_TIMEOUT);
+        let client = storage_client(storage);
+
+        let err = client.resume("default", "web").await.unwrap_err();
+        assert!(matches!(err, KubeError::ReleaseLocked { .. }), "{err}");
+    }
 }
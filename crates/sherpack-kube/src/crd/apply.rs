@@ -3,11 +3,12 @@
 //! This module provides the CrdManager for applying and managing CRDs
 //! in a Kubernetes cluster.
 
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use kube::{
     Client,
-    api::{Api, DynamicObject, Patch, PatchParams},
+    api::{Api, DynamicObject, ListParams, Patch, PatchParams, PostParams},
     core::GroupVersionKind,
     discovery::{ApiResource, Discovery},
 };
@@ -301,6 +302,150 @@ impl CrdManager {
         Ok(results)
     }
 
+    /// Check whether a CRD still records objects stored in an older version
+    ///
+    /// True when `status.storedVersions` lists a version other than the
+    /// current storage version.
+    pub async fn needs_storage_migration(&self, crd_name: &str) -> Result<bool> {
+        let Some(crd) = self.get_crd(crd_name).await? else {
+            return Ok(false);
+        };
+        let Some(version) = storage_version(&crd.data) else {
+            return Ok(false);
+        };
+
+        let stale = crd
+            .data
+            .get("status")
+            .and_then(|s| s.get("storedVersions"))
+            .and_then(|v| v.as_array())
+            .is_some_and(|stored| stored.iter().any(|v| v.as_str() != Some(&version)));
+        Ok(stale)
+    }
+
+    /// Re-write every custom resource of a CRD in its storage version
+    ///
+    /// Each resource is read and written back unchanged, which makes the API
+    /// server store it in the current storage version (converting it through
+    /// the CRD's conversion webhook if it has one). Resources that fail, for
+    /// instance because the webhook rejects them, are reported instead of
+    /// aborting the migration.
+    ///
+    /// Once every resource was re-written, the CRD's `status.storedVersions`
+    /// is reset to the storage version, so the old versions can be removed
+    /// from the CRD in a later release.
+    pub async fn migrate_storage(&self, crd_name: &str) -> Result<StorageMigration> {
+        let crd = self.require_crd(crd_name).await?;
+        let (resource, namespaced) = custom_resource_api(crd_name, &crd)?;
+        let kind = resource.kind.clone();
+
+        let mut migration = StorageMigration {
+            crd: crd_name.to_string(),
+            version: resource.version.clone(),
+            migrated: 0,
+            failed: Vec::new(),
+        };
+
+        let all: Api<DynamicObject> = Api::all_with(self.client.clone(), &resource);
+        let list = all
+            .list(&ListParams::default())
+            .await
+            .map_err(|e| KubeError::InvalidConfig(migration_error(crd_name, e)))?;
+
+        for item in list.items {
+            let name = item.metadata.name.clone().unwrap_or_default();
+            let namespace = item.metadata.namespace.clone();
+            let api: Api<DynamicObject> = match (&namespace, namespaced) {
+                (Some(ns), true) => Api::namespaced_with(self.client.clone(), ns, &resource),
+                _ => Api::all_with(self.client.clone(), &resource),
+            };
+            let display = match &namespace {
+                Some(ns) => format!("{}/{}/{}", ns, kind, name),
+                None => format!("{}/{}", kind, name),
+            };
+
+            // GET + PUT of the unchanged object
+            let rewritten = match api.get(&name).await {
+                Ok(current) => api.replace(&name, &PostParams::default(), &current).await,
+                Err(e) => Err(e),
+            };
+            match rewritten {
+                Ok(_) => migration.migrated += 1,
+                // Deleted since the list, nothing left to migrate
+                Err(kube::Error::Api(resp)) if resp.code == 404 => {}
+                Err(e) => migration
+                    .failed
+                    .push((display, migration_error(crd_name, e))),
+            }
+        }
+
+        if migration.is_success() {
+            let crds: Api<DynamicObject> = Api::all_with(
+                self.client.clone(),
+                &ApiResource::erase::<k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::CustomResourceDefinition>(&()),
+            );
+            let status = serde_json::json!({"status": {"storedVersions": [migration.version]}});
+            crds.patch_status(crd_name, &PatchParams::default(), &Patch::Merge(&status))
+                .await
+                .map_err(KubeError::Api)?;
+        }
+
+        Ok(migration)
+    }
+
+    /// Back up a CRD, and optionally all of its custom resources, to files
+    ///
+    /// Writes `crd.yaml` (and `resources.yaml`) into a new timestamped
+    /// `<dir>/<crd>-<timestamp>/` directory. Server-managed metadata and
+    /// status are stripped so the files can be re-applied with
+    /// `kubectl apply -f <backup>`.
+    pub async fn backup(
+        &self,
+        crd_name: &str,
+        dir: &Path,
+        include_resources: bool,
+    ) -> Result<CrdBackup> {
+        let crd = self.require_crd(crd_name).await?;
+
+        let resources = if include_resources {
+            let (resource, _) = custom_resource_api(crd_name, &crd)?;
+            let api: Api<DynamicObject> = Api::all_with(self.client.clone(), &resource);
+            api.list(&ListParams::default())
+                .await
+                .map_err(KubeError::Api)?
+                .items
+        } else {
+            Vec::new()
+        };
+
+        let timestamp = chrono::Utc::now().format("%Y%m%dT%H%M%SZ");
+        let path = dir.join(format!("{}-{}", crd_name, timestamp));
+        std::fs::create_dir_all(&path)?;
+
+        std::fs::write(path.join("crd.yaml"), backup_yaml(crd)?)?;
+        if include_resources {
+            let docs = resources
+                .iter()
+                .cloned()
+                .map(backup_yaml)
+                .collect::<Result<Vec<_>>>()?;
+            std::fs::write(path.join("resources.yaml"), docs.join("---\n"))?;
+        }
+
+        Ok(CrdBackup {
+            crd: crd_name.to_string(),
+            path,
+            resources: resources.len(),
+        })
+    }
+
+    /// Get a CRD that must exist
+    async fn require_crd(&self, crd_name: &str) -> Result<DynamicObject> {
+        self.get_crd(crd_name)
+            .await?
+            .ok_or_else(|| KubeError::InvalidConfig(format!("CRD {} not found", crd_name)))
+    }
+
     /// Count CustomResources of a CRD type
     ///
     /// Used before CRD deletion to warn about data loss.
@@ -358,6 +503,120 @@ impl CrdManager {
     }
 }
 
+/// Storage version of a CRD (the version with `storage: true`)
+fn storage_version(crd: &serde_json::Value) -> Option<String> {
+    crd.get("spec")?
+        .get("versions")?
+        .as_array()?
+        .iter()
+        .find(|v| v.get("storage").and_then(|s| s.as_bool()) == Some(true))?
+        .get("name")?
+        .as_str()
+        .map(String::from)
+}
+
+/// API of a CRD's custom resources in its storage version, and whether
+/// they are namespaced
+pub(super) fn custom_resource_api(
+    crd_name: &str,
+    crd: &DynamicObject,
+) -> Result<(ApiResource, bool)> {
+    let spec = crd.data.get("spec");
+    let field = |value: Option<&serde_json::Value>, name: &str| {
+        value
+            .and_then(|v| v.get(name))
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string()
+    };
+    let names = spec.and_then(|s| s.get("names"));
+    let version = storage_version(&crd.data).ok_or_else(|| {
+        KubeError::InvalidConfig(format!("CRD {} has no storage version", crd_name))
+    })?;
+
+    let resource = ApiResource::from_gvk_with_plural(
+        &GroupVersionKind::gvk(&field(spec, "group"), &version, &field(names, "kind")),
+        &field(names, "plural"),
+    );
+    Ok((resource, field(spec, "scope") != "Cluster"))
+}
+
+/// Serialize an object for a backup, without server-managed fields
+fn backup_yaml(mut obj: DynamicObject) -> Result<String> {
+    let metadata = &mut obj.metadata;
+    metadata.managed_fields = None;
+    metadata.resource_version = None;
+    metadata.uid = None;
+    metadata.creation_timestamp = None;
+    metadata.generation = None;
+    if let Some(data) = obj.data.as_object_mut() {
+        data.remove("status");
+    }
+
+    serde_yaml::to_string(&obj).map_err(|e| KubeError::Serialization(e.to_string()))
+}
+
+/// Describe an error re-writing custom resources, calling out the
+/// conversion webhook when it is the one failing
+fn migration_error(crd_name: &str, error: kube::Error) -> String {
+    let message = error.to_string();
+    if message.contains("conversion webhook") {
+        format!("conversion webhook for {} failed: {}", crd_name, message)
+    } else {
+        message
+    }
+}
+
+/// Files written by [`CrdManager::backup`]
+#[derive(Debug, Clone)]
+pub struct CrdBackup {
+    /// CRD name
+    pub crd: String,
+    /// Backup directory
+    pub path: PathBuf,
+    /// Number of custom resources saved
+    pub resources: usize,
+}
+
+impl CrdBackup {
+    /// Command restoring this backup
+    pub fn restore_command(&self) -> String {
+        format!("kubectl apply -f {}", self.path.display())
+    }
+}
+
+/// Result of re-writing a CRD's custom resources in its storage version
+#[derive(Debug, Clone)]
+pub struct StorageMigration {
+    /// CRD name
+    pub crd: String,
+    /// Storage version the resources were written in
+    pub version: String,
+    /// Number of resources re-written
+    pub migrated: usize,
+    /// Resources that could not be re-written (with reasons)
+    pub failed: Vec<(String, String)>,
+}
+
+impl StorageMigration {
+    /// Check if every resource was re-written
+    pub fn is_success(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    /// Get a display message for this migration
+    pub fn message(&self) -> String {
+        let mut message = format!(
+            "migrated {} resource(s) of CRD {} to {}",
+            self.migrated, self.crd, self.version
+        );
+        if !self.failed.is_empty() {
+            message.push_str(&format!(", {} failed", self.failed.len()));
+        }
+        message
+    }
+}
+
 /// Result of applying a CRD
 #[derive(Debug, Clone)]
 pub struct CrdApplyResult {
@@ -525,6 +784,231 @@ mod tests {
         assert!(updated.message().contains("updated"));
     }
 
+    const WIDGETS_CRD: &str =
+        "/apis/apiextensions.k8s.io/v1/customresourcedefinitions/widgets.example.com";
+    const WIDGETS_CRD_STATUS: &str =
+        "/apis/apiextensions.k8s.io/v1/customresourcedefinitions/widgets.example.com/status";
+
+    /// Serve a `widgets.example.com` CRD stored as v2 with two widgets,
+    /// failing the conversion webhook when `broken` is written back and
+    /// `webhook_fails` is set; returns `(method, path, body)` of each call
+    fn spawn_migration_server(
+        mut handle: tower_test::mock::Handle<
+            http::Request<kube::client::Body>,
+            http::Response<kube::client::Body>,
+        >,
+        webhook_fails: bool,
+    ) -> tokio::task::JoinHandle<Vec<(String, String, String)>> {
+        tokio::spawn(async move {
+            let mut calls = Vec::new();
+
+            while let Some((request, send)) = handle.next_request().await {
+                let method = request.method().clone();
+                let path = request.uri().path().to_string();
+                let body = request.into_body().collect_bytes().await.unwrap();
+                calls.push((
+                    method.to_string(),
+                    path.clone(),
+                    String::from_utf8_lossy(&body).to_string(),
+                ));
+
+                let widget = |ns: &str, name: &str| {
+                    serde_json::json!({
+                        "apiVersion": "example.com/v2",
+                        "kind": "Widget",
+                        "metadata": {"name": name, "namespace": ns, "resourceVersion": "1"}
+                    })
+                };
+                let (status, body) = match path.as_str() {
+                    WIDGETS_CRD | WIDGETS_CRD_STATUS => (
+                        200,
+                        serde_json::json!({
+                            "apiVersion": "apiextensions.k8s.io/v1",
+                            "kind": "CustomResourceDefinition",
+                            "metadata": {"name": "widgets.example.com"},
+                            "spec": {
+                                "group": "example.com",
+                                "scope": "Namespaced",
+                                "names": {"kind": "Widget", "plural": "widgets"},
+                                "versions": [
+                                    {"name": "v1", "served": true, "storage": false},
+                                    {"name": "v2", "served": true, "storage": true}
+                                ]
+                            }
+                        }),
+                    ),
+                    "/apis/example.com/v2/widgets" => (
+                        200,
+                        serde_json::json!({
+                            "apiVersion": "example.com/v2",
+                            "kind": "WidgetList",
+                            "metadata": {"resourceVersion": "1"},
+                            "items": [widget("default", "gadget"), widget("tools", "broken")]
+                        }),
+                    ),
+                    "/apis/example.com/v2/namespaces/tools/widgets/broken"
+                        if webhook_fails && method == http::Method::PUT =>
+                    {
+                        (
+                            500,
+                            serde_json::json!({
+                                "apiVersion": "v1",
+                                "kind": "Status",
+                                "status": "Failure",
+                                "message": "conversion webhook for example.com/v2, Kind=Widget failed: connection refused",
+                                "reason": "InternalError",
+                                "code": 500
+                            }),
+                        )
+                    }
+                    other => {
+                        let parts: Vec<&str> = other.split('/').collect();
+                        (200, widget(parts[5], parts[7]))
+                    }
+                };
+
+                send.send_response(
+                    http::Response::builder()
+                        .status(status)
+                        .body(kube::client::Body::from(serde_json::to_vec(&body).unwrap()))
+                        .unwrap(),
+                );
+            }
+
+            calls
+        })
+    }
+
+    #[tokio::test]
+    async fn test_migrate_storage_rewrites_each_resource() {
+        let (service, handle) = tower_test::mock::pair::<
+            http::Request<kube::client::Body>,
+            http::Response<kube::client::Body>,
+        >();
+        let server = spawn_migration_server(handle, true);
+
+        let manager = CrdManager::new(Client::new(service, "default"));
+        let migration = manager
+            .migrate_storage("widgets.example.com")
+            .await
+            .unwrap();
+        drop(manager);
+        let calls = server.await.unwrap();
+
+        let puts: Vec<&str> = calls
+            .iter()
+            .filter(|(method, _, _)| method == "PUT")
+            .map(|(_, path, _)| path.as_str())
+            .collect();
+        assert_eq!(
+            puts,
+            [
+                "/apis/example.com/v2/namespaces/default/widgets/gadget",
+                "/apis/example.com/v2/namespaces/tools/widgets/broken",
+            ]
+        );
+
+        assert_eq!(migration.version, "v2");
+        assert_eq!(migration.migrated, 1);
+        assert_eq!(migration.failed.len(), 1);
+        assert_eq!(migration.failed[0].0, "tools/Widget/broken");
+        assert!(
+            migration.failed[0]
+                .1
+                .starts_with("conversion webhook for widgets.example.com failed")
+        );
+        assert!(migration.message().contains("1 failed"));
+
+        // storedVersions is left alone while a resource is still unmigrated
+        assert!(!calls.iter().any(|(method, _, _)| method == "PATCH"));
+    }
+
+    #[tokio::test]
+    async fn test_migrate_storage_resets_stored_versions() {
+        let (service, handle) = tower_test::mock::pair::<
+            http::Request<kube::client::Body>,
+            http::Response<kube::client::Body>,
+        >();
+        let server = spawn_migration_server(handle, false);
+
+        let manager = CrdManager::new(Client::new(service, "default"));
+        let migration = manager
+            .migrate_storage("widgets.example.com")
+            .await
+            .unwrap();
+        drop(manager);
+        let calls = server.await.unwrap();
+
+        assert_eq!(migration.migrated, 2);
+        assert!(migration.is_success());
+
+        let patches: Vec<_> = calls
+            .iter()
+            .filter(|(method, _, _)| method == "PATCH")
+            .collect();
+        assert_eq!(patches.len(), 1);
+        assert_eq!(patches[0].1, WIDGETS_CRD_STATUS);
+        let body: serde_json::Value = serde_json::from_str(&patches[0].2).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({"status": {"storedVersions": ["v2"]}})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_backup_writes_crd_and_resources() {
+        let (service, handle) = tower_test::mock::pair::<
+            http::Request<kube::client::Body>,
+            http::Response<kube::client::Body>,
+        >();
+        let server = spawn_migration_server(handle, true);
+        let dir = tempfile::tempdir().unwrap();
+
+        let manager = CrdManager::new(Client::new(service, "default"));
+        let backup = manager
+            .backup("widgets.example.com", dir.path(), true)
+            .await
+            .unwrap();
+        drop(manager);
+        server.await.unwrap();
+
+        assert_eq!(backup.resources, 2);
+        assert_eq!(backup.path.parent(), Some(dir.path()));
+        let dir_name = backup.path.file_name().unwrap().to_string_lossy();
+        assert!(dir_name.starts_with("widgets.example.com-"), "{dir_name}");
+        assert_eq!(
+            backup.restore_command(),
+            format!("kubectl apply -f {}", backup.path.display())
+        );
+
+        let crd: serde_yaml::Value =
+            serde_yaml::from_str(&std::fs::read_to_string(backup.path.join("crd.yaml")).unwrap())
+                .unwrap();
+        assert_eq!(crd["kind"], "CustomResourceDefinition");
+        assert_eq!(crd["metadata"]["name"], "widgets.example.com");
+        assert_eq!(crd["spec"]["versions"][1]["storage"], true);
+
+        let resources = std::fs::read_to_string(backup.path.join("resources.yaml")).unwrap();
+        let docs: Vec<serde_yaml::Value> = resources
+            .split("---\n")
+            .map(|doc| serde_yaml::from_str(doc).unwrap())
+            .collect();
+        assert_eq!(docs.len(), 2);
+        assert_eq!(docs[0]["metadata"]["name"], "gadget");
+        assert_eq!(docs[1]["metadata"]["namespace"], "tools");
+        assert!(!resources.contains("resourceVersion"));
+    }
+
+    #[test]
+    fn test_storage_version() {
+        let crd = serde_json::json!({"spec": {"versions": [
+            {"name": "v1alpha1", "storage": false},
+            {"name": "v1", "storage": true}
+        ]}});
+        assert_eq!(storage_version(&crd), Some("v1".to_string()));
+        assert_eq!(storage_version(&serde_json::json!({"spec": {}})), None);
+    }
+
     #[test]
     fn test_crd_upgrade_result() {
         let mut result = CrdUpgradeResult::default();
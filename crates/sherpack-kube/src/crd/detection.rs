@@ -10,10 +10,13 @@
 //! Unlike Helm which forces a choice between templating (templates/) and
 //! protection (crds/), Sherpack allows both. This module enables:
 //!
-//! 1. **Templated CRDs in crds/**: Files with `{{` or `{%` are rendered
+//! 1. **Static CRDs in crds/**: Files with `{{` or `{%` are flagged, since
+//!    crds/ is applied as-is and never rendered
 //! 2. **Protected CRDs in templates/**: Auto-detected and protected
 //! 3. **Smart lint warnings**: Suggest optimal placement
 
+use serde::Serialize;
+
 use super::policy::{CrdLocation, CrdPolicy, DetectedCrd};
 
 /// Check if content contains Jinja templating syntax
@@ -176,7 +179,7 @@ pub struct CrdLintWarning {
 pub enum CrdLintCode {
     /// CRD found in templates/ instead of crds/
     CrdInTemplates,
-    /// Templated CRD in crds/ (informational)
+    /// Templated CRD in crds/, which is applied without rendering
     TemplatedCrdInCrdsDir,
     /// Non-CRD file in crds/ directory
     NonCrdInCrdsDir,
@@ -212,11 +215,23 @@ impl CrdLintWarning {
         self
     }
 
+    /// Rule name, for machine-readable output
+    pub fn rule(&self) -> &'static str {
+        match self.code {
+            CrdLintCode::CrdInTemplates => "crd_in_templates",
+            CrdLintCode::TemplatedCrdInCrdsDir => "templated_crd_in_crds_dir",
+            CrdLintCode::NonCrdInCrdsDir => "non_crd_in_crds_dir",
+            CrdLintCode::NoPolicyAnnotation => "no_policy_annotation",
+            CrdLintCode::SharedCrdInTemplates => "shared_crd_in_templates",
+            CrdLintCode::ExternalPolicyInPack => "external_policy_in_pack",
+        }
+    }
+
     /// Get severity (for display)
     pub fn severity(&self) -> LintSeverity {
         match self.code {
             CrdLintCode::CrdInTemplates => LintSeverity::Info,
-            CrdLintCode::TemplatedCrdInCrdsDir => LintSeverity::Info,
+            CrdLintCode::TemplatedCrdInCrdsDir => LintSeverity::Error,
             CrdLintCode::NonCrdInCrdsDir => LintSeverity::Error,
             CrdLintCode::NoPolicyAnnotation => LintSeverity::Info,
             CrdLintCode::SharedCrdInTemplates => LintSeverity::Warning,
@@ -226,7 +241,8 @@ impl CrdLintWarning {
 }
 
 /// Lint severity levels
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum LintSeverity {
     /// Informational note
     Info,
@@ -275,26 +291,17 @@ pub fn lint_crds(
         }
     }
 
-    // Check templated CRDs in crds/
+    // Check templated CRDs in crds/ (applied as-is, so templating would break them)
     for templated in templated_files {
-        let mut warning = CrdLintWarning::new(
-            CrdLintCode::TemplatedCrdInCrdsDir,
-            &templated.path,
-            "Templated CRD in crds/ directory",
-        );
-
-        if templated.has_control_flow() {
-            warning.suggestion = Some(
-                "File uses control flow ({% ... %}). Ensure conditionals \
-                 don't accidentally exclude required CRDs."
-                    .to_string(),
-            );
-        } else {
-            warning.suggestion =
-                Some("File uses templating. Will be rendered before installation.".to_string());
+        warnings.push(templated_crd_warning(&templated.path, None));
+    }
+    for crd in crds_dir_crds {
+        if crd.location.is_templated() || contains_jinja_syntax(&crd.content) {
+            warnings.push(templated_crd_warning(
+                crd.location.path().display().to_string(),
+                Some(&crd.name),
+            ));
         }
-
-        warnings.push(warning);
     }
 
     // Check for external policy on pack-defined CRDs
@@ -318,6 +325,21 @@ pub fn lint_crds(
     warnings
 }
 
+/// Build the error for a crds/ file that contains Jinja syntax
+fn templated_crd_warning(path: impl Into<String>, crd_name: Option<&str>) -> CrdLintWarning {
+    let mut warning = CrdLintWarning::new(
+        CrdLintCode::TemplatedCrdInCrdsDir,
+        path,
+        "Templated CRD in crds/ directory: files in crds/ are applied without rendering",
+    )
+    .with_suggestion(
+        "Move the CRD to templates/ (it stays protected from deletion there), \
+         or remove the templating to keep it in crds/.",
+    );
+    warning.crd_name = crd_name.map(str::to_string);
+    warning
+}
+
 /// Check if a manifest is a CRD
 pub fn is_crd_manifest(content: &str) -> bool {
     // Quick check before parsing
@@ -459,6 +481,36 @@ metadata:
 
         assert_eq!(warnings.len(), 1);
         assert_eq!(warnings[0].code, CrdLintCode::TemplatedCrdInCrdsDir);
+        assert_eq!(warnings[0].severity(), LintSeverity::Error);
+        assert!(
+            warnings[0]
+                .suggestion
+                .as_ref()
+                .unwrap()
+                .contains("templates/")
+        );
+    }
+
+    #[test]
+    fn test_lint_static_vs_templated_crd_in_crds_dir() {
+        let static_crd = DetectedCrd::new(
+            "widgets.example.com",
+            "kind: CustomResourceDefinition\nmetadata:\n  name: widgets.example.com\n",
+            CrdLocation::crds_directory("widgets.yaml", false),
+        );
+        assert!(lint_crds(&[static_crd], &[], &[]).is_empty());
+
+        let templated_crd = DetectedCrd::new(
+            "gadgets.example.com",
+            "kind: CustomResourceDefinition\nmetadata:\n  name: gadgets.example.com\n  labels:\n    app: {{ release.name }}\n",
+            CrdLocation::crds_directory("gadgets.yaml", false),
+        );
+        let warnings = lint_crds(&[templated_crd], &[], &[]);
+
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].code, CrdLintCode::TemplatedCrdInCrdsDir);
+        assert_eq!(warnings[0].crd_name.as_deref(), Some("gadgets.example.com"));
+        assert_eq!(warnings[0].path, "gadgets.yaml");
     }
 
     #[test]
@@ -107,7 +107,9 @@ pub use strategy::{
 };
 
 // Apply types
-pub use apply::{CrdApplyResult, CrdManager, CrdUpgradeResult, ResourceCategory};
+pub use apply::{
+    CrdApplyResult, CrdBackup, CrdManager, CrdUpgradeResult, ResourceCategory, StorageMigration,
+};
 
 // Policy types (Phase 3)
 pub use policy::{
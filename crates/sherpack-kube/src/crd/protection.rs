@@ -13,12 +13,12 @@
 use kube::{
     Client,
     api::{Api, DynamicObject, ListParams},
-    core::GroupVersionKind,
-    discovery::{ApiCapabilities, ApiResource, Discovery, Scope},
 };
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::PathBuf;
 
+use super::apply::{CrdManager, custom_resource_api};
 use super::policy::{CrdPolicy, DetectedCrd};
 use crate::error::{KubeError, Result};
 
@@ -37,6 +37,9 @@ pub struct CrdDeletionImpact {
     pub deletion_allowed: bool,
     /// Reason if deletion is blocked
     pub blocked_reason: Option<String>,
+    /// Backup of the CRD and its resources taken before deletion
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup: Option<PathBuf>,
 }
 
 impl CrdDeletionImpact {
@@ -53,6 +56,7 @@ impl CrdDeletionImpact {
             } else {
                 Some(format!("Policy '{}' does not allow deletion", policy))
             },
+            backup: None,
         }
     }
 
@@ -74,9 +78,16 @@ impl CrdDeletionImpact {
             } else {
                 Some(format!("Policy '{}' does not allow deletion", policy))
             },
+            backup: None,
         }
     }
 
+    /// Record where the CRD was backed up
+    pub fn with_backup(mut self, path: impl Into<PathBuf>) -> Self {
+        self.backup = Some(path.into());
+        self
+    }
+
     /// Check if this deletion would cause data loss
     pub fn has_data_loss(&self) -> bool {
         self.total_resources > 0
@@ -114,6 +125,15 @@ impl DeletionImpactSummary {
         Self::default()
     }
 
+    /// Count the custom resources that deleting `crds` would remove
+    ///
+    /// Nothing is deleted; this only lists resources, per CRD and namespace.
+    pub async fn analyze_impact(client: &Client, crds: &[DetectedCrd]) -> Result<Self> {
+        CrdProtection::new(client.clone())
+            .analyze_multi_deletion_impact(crds)
+            .await
+    }
+
     /// Add a CRD impact
     pub fn add(&mut self, impact: CrdDeletionImpact) {
         if !impact.deletion_allowed {
@@ -176,21 +196,12 @@ impl CrdProtection {
             return Ok(CrdDeletionImpact::empty(crd_name, policy));
         }
 
-        // Parse CRD name to get group and resource
-        let Some((plural, group)) = parse_crd_name(crd_name) else {
+        // A CRD name is always <plural>.<group>
+        if parse_crd_name(crd_name).is_none() {
             return Ok(CrdDeletionImpact::empty(crd_name, policy));
-        };
-
-        // Run discovery to find the API resource
-        let discovery = Discovery::new(self.client.clone())
-            .run()
-            .await
-            .map_err(KubeError::Api)?;
+        }
 
-        // Try to find the resource in discovery
-        let by_namespace = self
-            .count_resources_by_namespace(&discovery, &group, &plural)
-            .await?;
+        let by_namespace = self.count_resources_by_namespace(crd_name).await?;
 
         Ok(CrdDeletionImpact::with_resources(
             crd_name,
@@ -214,68 +225,34 @@ impl CrdProtection {
         Ok(summary)
     }
 
-    /// Count resources by namespace for a given API group/resource
-    async fn count_resources_by_namespace(
-        &self,
-        discovery: &Discovery,
-        group: &str,
-        plural: &str,
-    ) -> Result<HashMap<String, usize>> {
-        // Try to find API versions (v1, v1beta1, v1alpha1)
-        for version in &["v1", "v1beta1", "v1alpha1", "v2", "v2beta1"] {
-            let gvk = GroupVersionKind {
-                group: group.to_string(),
-                version: version.to_string(),
-                kind: plural.to_string(), // Note: this should be kind, not plural
-            };
-
-            if let Some((ar, caps)) = discovery.resolve_gvk(&gvk) {
-                return self.count_with_api_resource(&ar, &caps).await;
-            }
-        }
-
-        // CRD might not have any resources yet
-        Ok(HashMap::new())
-    }
-
-    /// Count resources using a discovered API resource
-    async fn count_with_api_resource(
-        &self,
-        ar: &ApiResource,
-        caps: &ApiCapabilities,
-    ) -> Result<HashMap<String, usize>> {
+    /// Count a CRD's custom resources by namespace
+    ///
+    /// Resources are listed in the CRD's storage version across all
+    /// namespaces; cluster-scoped ones are counted under `""`.
+    async fn count_resources_by_namespace(&self, crd_name: &str) -> Result<HashMap<String, usize>> {
         let mut by_namespace = HashMap::new();
 
-        if caps.scope == Scope::Namespaced {
-            // List across all namespaces
-            let api: Api<DynamicObject> = Api::all_with(self.client.clone(), ar);
-            match api.list(&ListParams::default()).await {
-                Ok(list) => {
-                    for item in list.items {
-                        let ns = item
-                            .metadata
-                            .namespace
-                            .unwrap_or_else(|| "default".to_string());
-                        *by_namespace.entry(ns).or_insert(0) += 1;
-                    }
-                }
-                Err(kube::Error::Api(resp)) if resp.code == 404 => {
-                    // Resource type doesn't exist, return empty
-                }
-                Err(e) => return Err(KubeError::Api(e)),
-            }
-        } else {
-            // Cluster-scoped
-            let api: Api<DynamicObject> = Api::all_with(self.client.clone(), ar);
-            match api.list(&ListParams::default()).await {
-                Ok(list) => {
-                    if !list.items.is_empty() {
-                        by_namespace.insert("".to_string(), list.items.len());
-                    }
+        // CRD not installed, or not serving anything yet
+        let Some(crd) = CrdManager::new(self.client.clone())
+            .get_crd(crd_name)
+            .await?
+        else {
+            return Ok(by_namespace);
+        };
+        let Ok((resource, _)) = custom_resource_api(crd_name, &crd) else {
+            return Ok(by_namespace);
+        };
+
+        let api: Api<DynamicObject> = Api::all_with(self.client.clone(), &resource);
+        match api.list(&ListParams::default()).await {
+            Ok(list) => {
+                for item in list.items {
+                    let ns = item.metadata.namespace.unwrap_or_default();
+                    *by_namespace.entry(ns).or_insert(0) += 1;
                 }
-                Err(kube::Error::Api(resp)) if resp.code == 404 => {}
-                Err(e) => return Err(KubeError::Api(e)),
             }
+            Err(kube::Error::Api(resp)) if resp.code == 404 => {}
+            Err(e) => return Err(KubeError::Api(e)),
         }
 
         Ok(by_namespace)
@@ -303,6 +280,8 @@ pub struct DeletionConfirmation {
     pub required_flags: Vec<String>,
     /// Human-readable explanation
     pub explanation: String,
+    /// Backups the deleted CRDs can be restored from
+    pub backups: Vec<PathBuf>,
 }
 
 impl DeletionConfirmation {
@@ -312,6 +291,7 @@ impl DeletionConfirmation {
             required: false,
             required_flags: vec![],
             explanation: "No confirmation required".to_string(),
+            backups: vec![],
         }
     }
 
@@ -321,11 +301,38 @@ impl DeletionConfirmation {
             required: true,
             required_flags: flags,
             explanation: explanation.into(),
+            backups: vec![],
+        }
+    }
+
+    /// How to restore the backups, if any were taken
+    pub fn restore_hint(&self) -> Option<String> {
+        if self.backups.is_empty() {
+            return None;
         }
+        let commands: Vec<String> = self
+            .backups
+            .iter()
+            .map(|path| format!("  kubectl apply -f {}", path.display()))
+            .collect();
+        Some(format!(
+            "Backups written; to restore run:\n{}",
+            commands.join("\n")
+        ))
     }
 
     /// Generate confirmation requirements from impact summary
     pub fn from_impact(summary: &DeletionImpactSummary) -> Self {
+        let mut confirmation = Self::from_impact_counts(summary);
+        confirmation.backups = summary
+            .crds
+            .iter()
+            .filter_map(|impact| impact.backup.clone())
+            .collect();
+        confirmation
+    }
+
+    fn from_impact_counts(summary: &DeletionImpactSummary) -> Self {
         if summary.has_blocked() {
             return Self::required(
                 format!(
@@ -369,6 +376,151 @@ impl DeletionConfirmation {
 mod tests {
     use super::*;
 
+    fn crd_json(plural: &str, kind: &str, scope: &str) -> serde_json::Value {
+        serde_json::json!({
+            "apiVersion": "apiextensions.k8s.io/v1",
+            "kind": "CustomResourceDefinition",
+            "metadata": {"name": format!("{}.example.com", plural)},
+            "spec": {
+                "group": "example.com",
+                "scope": scope,
+                "names": {"kind": kind, "plural": plural},
+                "versions": [{"name": "v1", "served": true, "storage": true}]
+            }
+        })
+    }
+
+    fn list_json(kind: &str, items: &[(Option<&str>, &str)]) -> serde_json::Value {
+        let items: Vec<_> = items
+            .iter()
+            .map(|(ns, name)| {
+                serde_json::json!({
+                    "apiVersion": "example.com/v1",
+                    "kind": kind,
+                    "metadata": {"name": name, "namespace": ns}
+                })
+            })
+            .collect();
+        serde_json::json!({
+            "apiVersion": "example.com/v1",
+            "kind": format!("{}List", kind),
+            "metadata": {"resourceVersion": "1"},
+            "items": items
+        })
+    }
+
+    /// Cluster with namespaced widgets, cluster-scoped gizmos, and no
+    /// `missing.example.com` CRD; returns `(method, path)` of each call
+    fn spawn_impact_server(
+        mut handle: tower_test::mock::Handle<
+            http::Request<kube::client::Body>,
+            http::Response<kube::client::Body>,
+        >,
+    ) -> tokio::task::JoinHandle<Vec<(String, String)>> {
+        tokio::spawn(async move {
+            let mut calls = Vec::new();
+            let crds = "/apis/apiextensions.k8s.io/v1/customresourcedefinitions";
+
+            while let Some((request, send)) = handle.next_request().await {
+                let path = request.uri().path().to_string();
+                calls.push((request.method().to_string(), path.clone()));
+
+                let (status, body) = match path.strip_prefix(crds) {
+                    Some("/widgets.example.com") => {
+                        (200, crd_json("widgets", "Widget", "Namespaced"))
+                    }
+                    Some("/gizmos.example.com") => (200, crd_json("gizmos", "Gizmo", "Cluster")),
+                    Some(_) => (
+                        404,
+                        serde_json::json!({
+                            "apiVersion": "v1",
+                            "kind": "Status",
+                            "status": "Failure",
+                            "reason": "NotFound",
+                            "code": 404
+                        }),
+                    ),
+                    None if path == "/apis/example.com/v1/widgets" => (
+                        200,
+                        list_json(
+                            "Widget",
+                            &[
+                                (Some("default"), "a"),
+                                (Some("default"), "b"),
+                                (Some("tools"), "c"),
+                            ],
+                        ),
+                    ),
+                    None => (200, list_json("Gizmo", &[(None, "x"), (None, "y")])),
+                };
+
+                send.send_response(
+                    http::Response::builder()
+                        .status(status)
+                        .body(kube::client::Body::from(serde_json::to_vec(&body).unwrap()))
+                        .unwrap(),
+                );
+            }
+
+            calls
+        })
+    }
+
+    fn detected(name: &str, policy: Option<&str>) -> DetectedCrd {
+        let annotations = policy
+            .map(|p| format!("\n  annotations:\n    sherpack.io/crd-policy: {}", p))
+            .unwrap_or_default();
+        DetectedCrd::new(
+            name,
+            format!(
+                "apiVersion: apiextensions.k8s.io/v1\nkind: CustomResourceDefinition\nmetadata:\n  name: {}{}\n",
+                name, annotations
+            ),
+            super::super::policy::CrdLocation::templates("templates/crds.yaml"),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_analyze_impact_counts_per_crd_and_namespace() {
+        let (service, handle) = tower_test::mock::pair::<
+            http::Request<kube::client::Body>,
+            http::Response<kube::client::Body>,
+        >();
+        let server = spawn_impact_server(handle);
+        let client = Client::new(service, "default");
+
+        let crds = [
+            detected("widgets.example.com", None),
+            detected("gizmos.example.com", None),
+            detected("missing.example.com", None),
+            detected("shared.example.com", Some("shared")),
+        ];
+        let summary = DeletionImpactSummary::analyze_impact(&client, &crds)
+            .await
+            .unwrap();
+        drop(client);
+        let calls = server.await.unwrap();
+
+        // Only reads: nothing is deleted
+        assert!(calls.iter().all(|(method, _)| method == "GET"), "{calls:?}");
+
+        assert_eq!(summary.total_crds, 3);
+        assert_eq!(summary.total_resources, 5);
+        assert_eq!(summary.blocked_crds, ["shared.example.com"]);
+
+        let widgets = &summary.crds[0];
+        assert_eq!(widgets.total_resources, 3);
+        assert_eq!(widgets.by_namespace["default"], 2);
+        assert_eq!(widgets.by_namespace["tools"], 1);
+
+        let gizmos = &summary.crds[1];
+        assert_eq!(gizmos.total_resources, 2);
+        assert_eq!(gizmos.by_namespace[""], 2);
+
+        assert_eq!(summary.crds[2].total_resources, 0);
+        assert_eq!(summary.affected_namespaces(), ["", "default", "tools"]);
+    }
+
     #[test]
     fn test_parse_crd_name() {
         let (plural, group) = parse_crd_name("certificates.cert-manager.io").unwrap();
@@ -501,6 +653,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_deletion_confirmation_references_backup() {
+        let mut summary = DeletionImpactSummary::new();
+        summary.add(
+            CrdDeletionImpact::empty("tests.example.com", CrdPolicy::Managed)
+                .with_backup("/backups/tests.example.com-20260101T000000Z"),
+        );
+
+        let confirmation = DeletionConfirmation::from_impact(&summary);
+        assert_eq!(confirmation.backups.len(), 1);
+        assert!(
+            confirmation
+                .restore_hint()
+                .unwrap()
+                .contains("kubectl apply -f /backups/tests.example.com-20260101T000000Z")
+        );
+
+        assert!(
+            DeletionConfirmation::not_required()
+                .restore_hint()
+                .is_none()
+        );
+    }
+
     #[test]
     fn test_deletion_confirmation_from_blocked() {
         let mut summary = DeletionImpactSummary::new();
@@ -37,9 +37,11 @@ use kube::discovery::{ApiResource, Scope};
 use kube::{Client, Discovery};
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256};
 use similar::{ChangeTag, TextDiff};
 use std::collections::{BTreeMap, HashMap, HashSet};
 
+use crate::annotations::sherpack::DIFF_IGNORE;
 use crate::error::{KubeError, Result};
 use crate::release::StoredRelease;
 
@@ -63,10 +65,15 @@ const IGNORED_ANNOTATIONS: &[&str] = &[
     "deployment.kubernetes.io/revision",
     "meta.helm.sh/release-name",
     "meta.helm.sh/release-namespace",
+    "sherpack.io/release-namespace",
 ];
 
 /// Labels to optionally ignore when comparing
-const OPTIONALLY_IGNORED_LABELS: &[&str] = &["app.kubernetes.io/managed-by", "helm.sh/chart"];
+const OPTIONALLY_IGNORED_LABELS: &[&str] = &[
+    "app.kubernetes.io/managed-by",
+    "helm.sh/chart",
+    "sherpack.io/release-name",
+];
 
 /// Diff engine for release comparison and drift detection
 pub struct DiffEngine {
@@ -78,6 +85,8 @@ pub struct DiffEngine {
     pub ignore_paths: HashSet<String>,
     /// Ignore label differences for managed-by labels
     pub ignore_management_labels: bool,
+    /// Show Secret `data`/`stringData` values in plaintext instead of hashes
+    pub reveal_secrets: bool,
 }
 
 impl DiffEngine {
@@ -88,6 +97,7 @@ impl DiffEngine {
             ignore_status: true,
             ignore_paths: HashSet::new(),
             ignore_management_labels: true,
+            reveal_secrets: false,
         }
     }
 
@@ -109,6 +119,15 @@ impl DiffEngine {
         self
     }
 
+    /// Show Secret values in diff output
+    ///
+    /// By default, Secret `data` and `stringData` values are replaced with a
+    /// stable hash so changes are still visible without leaking the values.
+    pub fn reveal_secrets(mut self, reveal: bool) -> Self {
+        self.reveal_secrets = reveal;
+        self
+    }
+
     /// Compare two releases
     pub fn diff_releases(&self, old: &StoredRelease, new: &StoredRelease) -> DiffResult {
         let old_resources = parse_manifest_resources(&old.manifest);
@@ -144,7 +163,9 @@ impl DiffEngine {
                         name: key.name.clone(),
                         namespace: key.namespace.clone(),
                         change_type: ChangeType::Added,
-                        diff: Some(DiffContent::new_addition(new_content)),
+                        diff: Some(DiffContent::new_addition(
+                            &self.mask_secret_data(new_content),
+                        )),
                         is_drift: false,
                         source: DiffSource::ReleaseComparison,
                     });
@@ -161,7 +182,9 @@ impl DiffEngine {
                     name: key.name.clone(),
                     namespace: key.namespace.clone(),
                     change_type: ChangeType::Removed,
-                    diff: Some(DiffContent::new_removal(old_content)),
+                    diff: Some(DiffContent::new_removal(
+                        &self.mask_secret_data(old_content),
+                    )),
                     is_drift: false,
                     source: DiffSource::ReleaseComparison,
                 });
@@ -235,7 +258,9 @@ impl DiffEngine {
                         name: key.name.clone(),
                         namespace: key.namespace.clone(),
                         change_type: ChangeType::Missing,
-                        diff: Some(DiffContent::new_removal(manifest_content)),
+                        diff: Some(DiffContent::new_removal(
+                            &self.mask_secret_data(manifest_content),
+                        )),
                         is_drift: true,
                         source: DiffSource::ClusterDrift,
                     });
@@ -259,7 +284,7 @@ impl DiffEngine {
         // Check for extra resources in cluster that aren't in manifest
         // (resources that might have been added manually)
         let extra_resources = self
-            .find_extra_cluster_resources(client, &discovery, release, &manifest_resources)
+            .find_extra_cluster_resources(client, release, &manifest_resources)
             .await?;
 
         for (key, live_yaml) in extra_resources {
@@ -269,7 +294,9 @@ impl DiffEngine {
                 name: key.name.clone(),
                 namespace: key.namespace.clone(),
                 change_type: ChangeType::Extra,
-                diff: Some(DiffContent::new_addition(&live_yaml)),
+                diff: Some(DiffContent::new_addition(
+                    &self.mask_secret_data(&live_yaml),
+                )),
                 is_drift: true,
                 source: DiffSource::ClusterDrift,
             });
@@ -376,7 +403,7 @@ impl DiffEngine {
                 name: key.name.clone(),
                 namespace: key.namespace.clone(),
                 change_type: ChangeType::Added,
-                diff: Some(DiffContent::new_addition(d)),
+                diff: Some(DiffContent::new_addition(&self.mask_secret_data(d))),
                 is_drift: false,
                 source: DiffSource::ThreeWay,
             }),
@@ -388,7 +415,7 @@ impl DiffEngine {
                 name: key.name.clone(),
                 namespace: key.namespace.clone(),
                 change_type: ChangeType::Removed,
-                diff: Some(DiffContent::new_removal(l)),
+                diff: Some(DiffContent::new_removal(&self.mask_secret_data(l))),
                 is_drift: false,
                 source: DiffSource::ThreeWay,
             }),
@@ -440,7 +467,7 @@ impl DiffEngine {
                 name: key.name.clone(),
                 namespace: key.namespace.clone(),
                 change_type: ChangeType::Extra,
-                diff: Some(DiffContent::new_addition(live)),
+                diff: Some(DiffContent::new_addition(&self.mask_secret_data(live))),
                 is_drift: true,
                 source: DiffSource::ThreeWay,
             }),
@@ -455,7 +482,7 @@ impl DiffEngine {
                 name: key.name.clone(),
                 namespace: key.namespace.clone(),
                 change_type: ChangeType::Added,
-                diff: Some(DiffContent::new_addition(d)),
+                diff: Some(DiffContent::new_addition(&self.mask_secret_data(d))),
                 is_drift: false, // Adopting existing resource
                 source: DiffSource::ThreeWay,
             }),
@@ -513,10 +540,11 @@ impl DiffEngine {
     }
 
     /// Find extra resources in cluster managed by this release but not in manifest
-    async fn find_extra_cluster_resources(
+    ///
+    /// Only the kinds in [`TRACKED_RESOURCE_TYPES`] are queried.
+    pub(crate) async fn find_extra_cluster_resources(
         &self,
         client: &Client,
-        _discovery: &Discovery,
         release: &StoredRelease,
         manifest_resources: &HashMap<ResourceKey, String>,
     ) -> Result<Vec<(ResourceKey, String)>> {
@@ -528,30 +556,9 @@ impl DiffEngine {
             release.name
         );
 
-        // Check common resource types for extras
-        // This is a simplified approach - a full implementation would check all types
-        let resource_types = [
-            ("v1", "ConfigMap"),
-            ("v1", "Secret"),
-            ("v1", "Service"),
-            ("apps/v1", "Deployment"),
-            ("apps/v1", "StatefulSet"),
-            ("apps/v1", "DaemonSet"),
-            ("batch/v1", "Job"),
-            ("batch/v1", "CronJob"),
-        ];
-
-        for (api_version, kind) in resource_types {
-            let ar = match kind {
-                "ConfigMap" => ApiResource::erase::<k8s_openapi::api::core::v1::ConfigMap>(&()),
-                "Secret" => ApiResource::erase::<k8s_openapi::api::core::v1::Secret>(&()),
-                "Service" => ApiResource::erase::<k8s_openapi::api::core::v1::Service>(&()),
-                "Deployment" => ApiResource::erase::<k8s_openapi::api::apps::v1::Deployment>(&()),
-                "StatefulSet" => ApiResource::erase::<k8s_openapi::api::apps::v1::StatefulSet>(&()),
-                "DaemonSet" => ApiResource::erase::<k8s_openapi::api::apps::v1::DaemonSet>(&()),
-                "Job" => ApiResource::erase::<k8s_openapi::api::batch::v1::Job>(&()),
-                "CronJob" => ApiResource::erase::<k8s_openapi::api::batch::v1::CronJob>(&()),
-                _ => continue,
+        for (api_version, kind) in TRACKED_RESOURCE_TYPES {
+            let Some(ar) = tracked_resource_api(kind) else {
+                continue;
             };
 
             let api: Api<DynamicObject> =
@@ -572,8 +579,16 @@ impl DiffEngine {
                             namespace,
                         };
 
-                        // Check if this resource is in the manifest
-                        if !manifest_resources.contains_key(&key) {
+                        // Manifests usually omit the release namespace
+                        let unqualified = ResourceKey {
+                            namespace: None,
+                            ..key.clone()
+                        };
+                        let in_manifest = manifest_resources.contains_key(&key)
+                            || (key.namespace.as_deref() == Some(release.namespace.as_str())
+                                && manifest_resources.contains_key(&unqualified));
+
+                        if !in_manifest {
                             let yaml = serde_yaml::to_string(&obj).unwrap_or_default();
                             extra.push((key, yaml));
                         }
@@ -586,6 +601,31 @@ impl DiffEngine {
         Ok(extra)
     }
 
+    /// Compare a resource as applied with the object the server persisted
+    ///
+    /// Only the fields set in `desired` are compared, so server defaults are
+    /// ignored while mutated values and items added to desired lists (like
+    /// an injected sidecar container) are reported. Returns `None` when the
+    /// server kept the desired state.
+    ///
+    /// A Secret's `stringData` is compared as the `data` the server stores
+    /// it as, and Secret values are masked in the returned diff.
+    pub fn verify_applied(&self, desired: &str, live: &str) -> Option<DiffContent> {
+        let mut desired_value: JsonValue = serde_yaml::from_str(desired).ok()?;
+        fold_string_data(&mut desired_value);
+        let live_value: JsonValue = serde_yaml::from_str(live).ok()?;
+        let projected = project_onto(&live_value, &desired_value);
+
+        let desired_normalized = self.mask_secret_data(
+            &self.normalize_resource(&serde_yaml::to_string(&desired_value).ok()?),
+        );
+        let live_normalized = self
+            .mask_secret_data(&self.normalize_resource(&serde_yaml::to_string(&projected).ok()?));
+
+        (desired_normalized != live_normalized)
+            .then(|| self.compute_text_diff(&desired_normalized, &live_normalized))
+    }
+
     /// Normalize a resource for comparison by stripping server-managed fields
     fn normalize_resource(&self, content: &str) -> String {
         // Parse as JSON for easier manipulation
@@ -594,6 +634,9 @@ impl DiffEngine {
             Err(_) => return content.to_string(),
         };
 
+        // Per-resource ignore paths, read before any metadata is stripped
+        let annotated_paths = annotated_ignore_paths(&value);
+
         // Remove server-managed fields
         self.strip_server_managed_fields(&mut value);
 
@@ -613,7 +656,7 @@ impl DiffEngine {
         }
 
         // Remove custom ignored paths
-        for path in &self.ignore_paths {
+        for path in self.ignore_paths.iter().chain(&annotated_paths) {
             self.remove_json_path(&mut value, path);
         }
 
@@ -657,8 +700,11 @@ impl DiffEngine {
     }
 
     /// Remove a JSON path from a value
+    ///
+    /// Path segments are separated by `.`; use `\.` for a literal dot in a key.
     fn remove_json_path(&self, value: &mut JsonValue, path: &str) {
-        let parts: Vec<&str> = path.split('.').collect();
+        let parts = split_json_path(path);
+        let parts: Vec<&str> = parts.iter().map(String::as_str).collect();
         Self::remove_path_recursive(value, &parts);
     }
 
@@ -681,9 +727,40 @@ impl DiffEngine {
         }
     }
 
+    /// Replace Secret `data`/`stringData` values with a stable hash
+    ///
+    /// Identical values hash identically, so a changed key still shows up
+    /// as changed in the diff while the value itself never appears.
+    fn mask_secret_data(&self, content: &str) -> String {
+        if self.reveal_secrets {
+            return content.to_string();
+        }
+
+        let mut value: JsonValue = match serde_yaml::from_str(content) {
+            Ok(v) => v,
+            Err(_) => return content.to_string(),
+        };
+
+        if value.get("kind").and_then(|k| k.as_str()) != Some("Secret") {
+            return content.to_string();
+        }
+
+        for field in ["data", "stringData"] {
+            if let Some(entries) = value.get_mut(field).and_then(|d| d.as_object_mut()) {
+                for entry in entries.values_mut() {
+                    *entry = JsonValue::String(mask_value(entry));
+                }
+            }
+        }
+
+        serde_yaml::to_string(&value).unwrap_or_else(|_| content.to_string())
+    }
+
     /// Compute a text diff between two strings
     fn compute_text_diff(&self, old: &str, new: &str) -> DiffContent {
-        let diff = TextDiff::from_lines(old, new);
+        let old = self.mask_secret_data(old);
+        let new = self.mask_secret_data(new);
+        let diff = TextDiff::from_lines(&old, &new);
         let mut lines = Vec::new();
 
         for change in diff.iter_all_changes() {
@@ -866,6 +943,119 @@ impl Default for DiffEngine {
     }
 }
 
+/// Read the `sherpack.io/diff-ignore` paths declared on a resource
+fn annotated_ignore_paths(value: &JsonValue) -> Vec<String> {
+    value
+        .pointer("/metadata/annotations")
+        .and_then(|a| a.get(DIFF_IGNORE))
+        .and_then(|v| v.as_str())
+        .map(|paths| {
+            paths
+                .split(',')
+                .map(str::trim)
+                .filter(|p| !p.is_empty())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Move a Secret's `stringData` into `data`, base64-encoded, as the API
+/// server does on write
+fn fold_string_data(value: &mut JsonValue) {
+    use base64::Engine;
+
+    if value.get("kind").and_then(|k| k.as_str()) != Some("Secret") {
+        return;
+    }
+    let Some(JsonValue::Object(string_data)) =
+        value.as_object_mut().and_then(|o| o.remove("stringData"))
+    else {
+        return;
+    };
+
+    let data = value.as_object_mut().map(|o| {
+        o.entry("data")
+            .or_insert_with(|| JsonValue::Object(Default::default()))
+    });
+    if let Some(JsonValue::Object(data)) = data {
+        for (key, entry) in string_data {
+            let plain = entry
+                .as_str()
+                .map(String::from)
+                .unwrap_or_else(|| entry.to_string());
+            data.insert(
+                key,
+                JsonValue::String(base64::engine::general_purpose::STANDARD.encode(plain)),
+            );
+        }
+    }
+}
+
+/// Keep the parts of `live` that `desired` sets
+///
+/// Lists are matched by position; live items beyond the desired ones are
+/// kept whole so that injected items show up.
+fn project_onto(live: &JsonValue, desired: &JsonValue) -> JsonValue {
+    match (live, desired) {
+        (JsonValue::Object(live), JsonValue::Object(desired)) => JsonValue::Object(
+            desired
+                .iter()
+                .filter_map(|(key, desired)| {
+                    live.get(key)
+                        .map(|live| (key.clone(), project_onto(live, desired)))
+                })
+                .collect(),
+        ),
+        (JsonValue::Array(live), JsonValue::Array(desired)) => JsonValue::Array(
+            live.iter()
+                .enumerate()
+                .map(|(i, live)| match desired.get(i) {
+                    Some(desired) => project_onto(live, desired),
+                    None => live.clone(),
+                })
+                .collect(),
+        ),
+        (live, _) => live.clone(),
+    }
+}
+
+/// Split a dotted path into segments, treating `\.` as a literal dot
+fn split_json_path(path: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut chars = path.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'.') => {
+                current.push('.');
+                chars.next();
+            }
+            '.' => parts.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    parts.push(current);
+
+    parts
+}
+
+/// Hash a secret value into a short, stable placeholder
+///
+/// Already masked values are kept, so masking twice is harmless.
+fn mask_value(value: &JsonValue) -> String {
+    let raw = match value {
+        JsonValue::String(s) if s.starts_with("<sha256:") && s.ends_with('>') => {
+            return s.clone();
+        }
+        JsonValue::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    let digest = hex::encode(Sha256::digest(raw.as_bytes()));
+    format!("<sha256:{}>", &digest[..16])
+}
+
 /// Parse apiVersion into (group, version)
 fn parse_api_version(api_version: &str) -> (String, String) {
     if let Some((group, version)) = api_version.split_once('/') {
@@ -1121,6 +1311,37 @@ pub enum LineType {
     Context,
 }
 
+/// Namespaced kinds checked for resources that are labeled for a release
+/// but missing from its manifest
+///
+/// This is a simplified approach - a full implementation would check all types.
+pub(crate) const TRACKED_RESOURCE_TYPES: [(&str, &str); 8] = [
+    ("v1", "ConfigMap"),
+    ("v1", "Secret"),
+    ("v1", "Service"),
+    ("apps/v1", "Deployment"),
+    ("apps/v1", "StatefulSet"),
+    ("apps/v1", "DaemonSet"),
+    ("batch/v1", "Job"),
+    ("batch/v1", "CronJob"),
+];
+
+/// API resource for one of the [`TRACKED_RESOURCE_TYPES`]
+pub(crate) fn tracked_resource_api(kind: &str) -> Option<ApiResource> {
+    let ar = match kind {
+        "ConfigMap" => ApiResource::erase::<k8s_openapi::api::core::v1::ConfigMap>(&()),
+        "Secret" => ApiResource::erase::<k8s_openapi::api::core::v1::Secret>(&()),
+        "Service" => ApiResource::erase::<k8s_openapi::api::core::v1::Service>(&()),
+        "Deployment" => ApiResource::erase::<k8s_openapi::api::apps::v1::Deployment>(&()),
+        "StatefulSet" => ApiResource::erase::<k8s_openapi::api::apps::v1::StatefulSet>(&()),
+        "DaemonSet" => ApiResource::erase::<k8s_openapi::api::apps::v1::DaemonSet>(&()),
+        "Job" => ApiResource::erase::<k8s_openapi::api::batch::v1::Job>(&()),
+        "CronJob" => ApiResource::erase::<k8s_openapi::api::batch::v1::CronJob>(&()),
+        _ => return None,
+    };
+    Some(ar)
+}
+
 /// Key for identifying a Kubernetes resource
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ResourceKey {
@@ -1215,10 +1436,12 @@ mod tests {
             values_provenance: Default::default(),
             manifest: manifest.to_string(),
             hooks: vec![],
+            hook_runs: vec![],
             labels: Default::default(),
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
             notes: None,
+            secrets: Default::default(),
         }
     }
 
@@ -1398,6 +1621,182 @@ status:
         assert!(normalized.contains("replicas: 1"));
     }
 
+    #[test]
+    fn test_diff_ignore_annotation_hides_injected_field() {
+        let engine = DiffEngine::new();
+
+        let manifest = r#"
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: web
+  annotations:
+    sherpack.io/diff-ignore: "spec.template.metadata.annotations.sidecar\\.istio\\.io/status"
+spec:
+  template:
+    metadata:
+      annotations:
+        app.example.com/owner: team-a
+"#;
+
+        let live = r#"
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: web
+  annotations:
+    sherpack.io/diff-ignore: "spec.template.metadata.annotations.sidecar\\.istio\\.io/status"
+spec:
+  template:
+    metadata:
+      annotations:
+        app.example.com/owner: team-a
+        sidecar.istio.io/status: '{"initContainers":["istio-init"]}'
+"#;
+
+        assert_eq!(
+            engine.normalize_resource(manifest),
+            engine.normalize_resource(live)
+        );
+
+        // Without the annotation the injected field is perpetual drift
+        let strip = |s: &str| s.replace("sherpack.io/diff-ignore", "other.io/note");
+        assert_ne!(
+            engine.normalize_resource(&strip(manifest)),
+            engine.normalize_resource(&strip(live))
+        );
+    }
+
+    #[test]
+    fn test_diff_ignore_annotation_comma_separated() {
+        let engine = DiffEngine::new();
+
+        let resource = r#"
+apiVersion: v1
+kind: ConfigMap
+metadata:
+  name: test
+  annotations:
+    sherpack.io/diff-ignore: "data.generated, data.config\\.ini"
+data:
+  generated: "123"
+  config.ini: "x=1"
+  config: "kept"
+"#;
+
+        let normalized = engine.normalize_resource(resource);
+
+        assert!(!normalized.contains("generated: '123'"));
+        assert!(!normalized.contains("config.ini"));
+        assert!(normalized.contains("config: kept"));
+    }
+
+    #[test]
+    fn test_verify_applied_ignores_defaults_and_reports_mutations() {
+        let desired = r#"
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: web
+spec:
+  replicas: 2
+  template:
+    spec:
+      containers:
+        - name: web
+          image: nginx:1.25
+"#;
+        let defaulted = r#"
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: web
+  uid: 1234
+spec:
+  replicas: 2
+  revisionHistoryLimit: 10
+  template:
+    spec:
+      containers:
+        - name: web
+          image: nginx:1.25
+          imagePullPolicy: IfNotPresent
+status:
+  replicas: 2
+"#;
+        let engine = DiffEngine::new();
+        assert!(engine.verify_applied(desired, defaulted).is_none());
+
+        let mutated = r#"
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: web
+spec:
+  replicas: 3
+  template:
+    spec:
+      containers:
+        - name: web
+          image: nginx:1.25
+        - name: istio-proxy
+          image: istio/proxyv2
+"#;
+        let diff = engine.verify_applied(desired, mutated).unwrap();
+        let unified = diff.to_unified_diff();
+        assert!(unified.contains("-  replicas: 2"));
+        assert!(unified.contains("+  replicas: 3"));
+        assert!(unified.contains("+      - image: istio/proxyv2"));
+    }
+
+    #[test]
+    fn test_verify_applied_secret_string_data() {
+        let desired = r#"
+apiVersion: v1
+kind: Secret
+metadata:
+  name: db
+stringData:
+  password: hunter2
+data:
+  user: YWRtaW4=
+"#;
+        // The server stores stringData base64-encoded in data
+        let live = r#"
+apiVersion: v1
+kind: Secret
+metadata:
+  name: db
+  uid: 1234
+data:
+  password: aHVudGVyMg==
+  user: YWRtaW4=
+type: Opaque
+"#;
+        let engine = DiffEngine::new();
+        assert!(engine.verify_applied(desired, live).is_none());
+
+        // A changed value is reported without revealing either value
+        let mutated = live.replace("aHVudGVyMg==", "cm90YXRlZA==");
+        let unified = engine
+            .verify_applied(desired, &mutated)
+            .unwrap()
+            .to_unified_diff();
+        assert!(unified.contains("password"), "{unified}");
+        for secret in ["hunter2", "aHVudGVyMg==", "rotated", "cm90YXRlZA=="] {
+            assert!(!unified.contains(secret), "{unified}");
+        }
+    }
+
+    #[test]
+    fn test_split_json_path_escaped_dots() {
+        assert_eq!(
+            split_json_path(r"metadata.annotations.sidecar\.istio\.io/status"),
+            vec!["metadata", "annotations", "sidecar.istio.io/status"]
+        );
+        assert_eq!(split_json_path("spec.replicas"), vec!["spec", "replicas"]);
+    }
+
     #[test]
     fn test_diff_summary() {
         let engine = DiffEngine::new();
@@ -1580,6 +1979,71 @@ status:
         assert_eq!(result.changes_by_type(ChangeType::Modified).len(), 0);
     }
 
+    const SECRET_V1: &str = "apiVersion: v1\nkind: Secret\nmetadata:\n  name: db\ndata:\n  password: c2VjcmV0MQ==\n  user: YWRtaW4=\nstringData:\n  token: plain-token-1";
+    const SECRET_V2: &str = "apiVersion: v1\nkind: Secret\nmetadata:\n  name: db\ndata:\n  password: c2VjcmV0Mg==\n  user: YWRtaW4=\nstringData:\n  token: plain-token-1";
+
+    #[test]
+    fn test_secret_values_masked_in_diff() {
+        let engine = DiffEngine::new();
+
+        let old = test_release(SECRET_V1);
+        let mut new = old.clone();
+        new.version = 2;
+        new.manifest = SECRET_V2.to_string();
+
+        let diff = engine.diff_releases(&old, &new);
+        assert_eq!(diff.changes.len(), 1);
+        assert_eq!(diff.changes[0].change_type, ChangeType::Modified);
+
+        let unified = diff.changes[0].diff.as_ref().unwrap().to_unified_diff();
+        assert!(!unified.contains("c2VjcmV0MQ=="));
+        assert!(!unified.contains("c2VjcmV0Mg=="));
+        assert!(!unified.contains("plain-token-1"));
+        assert!(unified.contains("<sha256:"));
+
+        // Only the changed key shows up as a change
+        let content = diff.changes[0].diff.as_ref().unwrap();
+        assert_eq!(content.added_count(), 1);
+        assert_eq!(content.removed_count(), 1);
+    }
+
+    #[test]
+    fn test_secret_values_masked_on_addition() {
+        let engine = DiffEngine::new();
+
+        let old = test_release("apiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: cm1");
+        let mut new = old.clone();
+        new.version = 2;
+        new.manifest = format!("{}\n---\n{}", old.manifest, SECRET_V1);
+
+        let diff = engine.diff_releases(&old, &new);
+        let unified = diff.changes[0].diff.as_ref().unwrap().to_unified_diff();
+        assert!(!unified.contains("c2VjcmV0MQ=="));
+        assert!(unified.contains("password: <sha256:"));
+    }
+
+    #[test]
+    fn test_reveal_secrets() {
+        let engine = DiffEngine::new().reveal_secrets(true);
+
+        let old = test_release(SECRET_V1);
+        let mut new = old.clone();
+        new.version = 2;
+        new.manifest = SECRET_V2.to_string();
+
+        let diff = engine.diff_releases(&old, &new);
+        let unified = diff.changes[0].diff.as_ref().unwrap().to_unified_diff();
+        assert!(unified.contains("c2VjcmV0MQ=="));
+        assert!(unified.contains("c2VjcmV0Mg=="));
+    }
+
+    #[test]
+    fn test_mask_ignores_non_secrets() {
+        let engine = DiffEngine::new();
+        let cm = "apiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: cm\ndata:\n  key: value";
+        assert_eq!(engine.mask_secret_data(cm), cm);
+    }
+
     #[test]
     fn test_ignore_custom_path() {
         let engine = DiffEngine::new().ignore_path("spec.replicas");
@@ -21,11 +21,11 @@ pub enum KubeError {
     #[error("release '{name}' already exists in namespace '{namespace}'")]
     ReleaseAlreadyExists { name: String, namespace: String },
 
-    /// Another operation is in progress (with recovery hint)
+    /// Another operation holds the release (with recovery hint)
     #[error(
-        "another operation is in progress for release '{name}': {status}\nHint: Run `sherpack recover {name}` to recover from stuck state"
+        "release '{name}' is locked by another operation: {holder}\nHint: Wait for it to finish, or run `sherpack recover {name}` once the lock is stale"
     )]
-    OperationInProgress { name: String, status: String },
+    ReleaseLocked { name: String, holder: String },
 
     /// Release is in a stuck state (can be auto-recovered)
     #[error(
@@ -45,6 +45,14 @@ pub enum KubeError {
         message: String,
     },
 
+    /// Hook did not finish within its timeout
+    #[error("hook '{hook_name}' timed out during {phase} after {seconds}s")]
+    HookTimeout {
+        hook_name: String,
+        phase: String,
+        seconds: i64,
+    },
+
     /// Health check failed
     #[error("health check failed for release '{name}': {message}")]
     HealthCheckFailed { name: String, message: String },
@@ -101,12 +109,66 @@ pub enum KubeError {
     )]
     ImmutableFieldConflict { resource: String, field: String },
 
+    /// Recreating a stateful resource needs explicit confirmation
+    #[error(
+        "recreating {resource} would delete it and may lose its data\nHint: Use --confirm-stateful-recreation to recreate it anyway, or --immutable-strategy=skip to leave it unchanged"
+    )]
+    StatefulRecreateNotConfirmed { resource: String },
+
     /// Drift detected
     #[error(
         "drift detected in {count} resource(s)\nHint: Use `sherpack diff {name}` to see changes, or --force to override"
     )]
     DriftDetected { name: String, count: usize },
 
+    /// A wave gate resource did not succeed
+    #[error("wave {wave} gate '{resource}' failed: {message}")]
+    WaveGateFailed {
+        wave: i32,
+        resource: String,
+        message: String,
+    },
+
+    /// The API server rejected resources during a server-side dry run
+    #[error("server-side dry run rejected {} resource(s): {}", .failures.len(), .failures.join("; "))]
+    DryRunRejected { failures: Vec<String> },
+
+    /// The cluster does not serve this resource type (e.g. its CRD is missing)
+    #[error("unknown resource type {api_version}/{kind} for '{name}' (is its CRD installed?)")]
+    UnknownResourceType {
+        api_version: String,
+        kind: String,
+        name: String,
+    },
+
+    /// `wait-for` annotations form a dependency cycle
+    #[error("wait-for dependency cycle: {}", .cycle.join(" -> "))]
+    WaveCycle { cycle: Vec<String> },
+
+    /// One or more resources in a wave failed to apply
+    #[error("wave {wave}: {} resource(s) failed: {}", .failures.len(), .failures.join("; "))]
+    WaveApplyFailed { wave: i32, failures: Vec<String> },
+
+    /// An existing resource isn't managed by any release
+    #[error(
+        "{resource} already exists and is not managed by Sherpack\nHint: Use --take-ownership to adopt it into the release"
+    )]
+    ResourceNotOwned { resource: String },
+
+    /// An existing resource belongs to another release
+    #[error("{resource} is managed by release '{owner}', refusing to take it over")]
+    ResourceOwnedByRelease { resource: String, owner: String },
+
+    /// The cluster doesn't satisfy the pack's `kubeVersion` constraint
+    #[error(
+        "pack '{pack}' requires Kubernetes {constraint}, but the cluster runs {version}\nHint: Use --skip-kube-version-check to deploy anyway"
+    )]
+    KubeVersionMismatch {
+        pack: String,
+        constraint: String,
+        version: String,
+    },
+
     /// Invalid manifest
     #[error("invalid manifest: {0}")]
     InvalidManifest(String),
@@ -4,6 +4,9 @@
 //! - Check deployment/statefulset readiness with REAL Kubernetes API calls
 //! - Custom HTTP health checks
 //! - Command-based health checks (exec into pods)
+//! - Annotation-driven checks for any kind (`sherpack.io/health-condition`,
+//!   `sherpack.io/health-jsonpath`), e.g. operator-managed custom resources
+//! - Explicit wait-for conditions (`--wait-for 'Certificate/web-tls: condition=Ready'`)
 //! - Automatic rollback on failure
 //!
 //! Unlike Helm's broken `--wait` flag, Sherpack properly verifies:
@@ -13,12 +16,15 @@
 
 use chrono::{DateTime, Duration, Utc};
 use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, StatefulSet};
-use k8s_openapi::api::batch::v1::Job;
+use k8s_openapi::api::batch::v1::{CronJob, Job};
 use k8s_openapi::api::core::v1::Pod;
-use kube::api::{Api, ListParams};
+use kube::api::{Api, DynamicObject, GroupVersionKind, ListParams};
+use kube::discovery::Scope;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde_json::Value as JsonValue;
+use std::collections::{BTreeMap, HashMap};
 
+use crate::annotations;
 use crate::error::{KubeError, Result};
 use crate::release::StoredRelease;
 
@@ -38,6 +44,10 @@ pub struct HealthCheckConfig {
     #[serde(default)]
     pub check_daemonsets: bool,
 
+    /// Wait for Jobs to complete (and CronJobs' last Job to succeed)
+    #[serde(default = "default_true")]
+    pub check_jobs: bool,
+
     /// Custom HTTP health checks
     #[serde(default)]
     pub http_checks: Vec<HttpHealthCheck>,
@@ -55,6 +65,15 @@ pub struct HealthCheckConfig {
     #[serde(default = "default_health_interval")]
     #[serde(with = "duration_serde")]
     pub interval: Duration,
+
+    /// Timeout for a single Job to complete (defaults to `timeout`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(with = "option_duration_serde")]
+    pub job_timeout: Option<Duration>,
+
+    /// Conditions to wait for in addition to default readiness
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub wait_for: Vec<WaitCondition>,
 }
 
 impl Default for HealthCheckConfig {
@@ -63,10 +82,13 @@ impl Default for HealthCheckConfig {
             check_deployments: true,
             check_statefulsets: true,
             check_daemonsets: false,
+            check_jobs: true,
             http_checks: Vec::new(),
             command_checks: Vec::new(),
             timeout: default_health_timeout(),
             interval: default_health_interval(),
+            job_timeout: None,
+            wait_for: Vec::new(),
         }
     }
 }
@@ -156,6 +178,10 @@ pub struct HealthStatus {
     /// Command check results
     pub command_checks: Vec<CheckResult>,
 
+    /// Results of the explicit wait-for conditions
+    #[serde(default)]
+    pub conditions: Vec<ResourceHealth>,
+
     /// When the check was performed
     pub checked_at: DateTime<Utc>,
 
@@ -170,6 +196,11 @@ impl HealthStatus {
         self.resources.iter().filter(|r| !r.healthy).collect()
     }
 
+    /// Get all wait-for conditions that are not met
+    pub fn unmet_conditions(&self) -> Vec<&ResourceHealth> {
+        self.conditions.iter().filter(|c| !c.healthy).collect()
+    }
+
     /// Get all failed checks
     pub fn failed_checks(&self) -> Vec<&CheckResult> {
         self.http_checks
@@ -186,6 +217,7 @@ impl HealthStatus {
         } else {
             let unhealthy = self.unhealthy_resources();
             let failed = self.failed_checks();
+            let unmet = self.unmet_conditions();
 
             let mut parts = Vec::new();
             if !unhealthy.is_empty() {
@@ -194,6 +226,24 @@ impl HealthStatus {
             if !failed.is_empty() {
                 parts.push(format!("{} checks failed", failed.len()));
             }
+            if !unmet.is_empty() {
+                let unmet: Vec<String> = unmet
+                    .iter()
+                    .map(|c| {
+                        let mut line = format!(
+                            "{}/{}: {}",
+                            c.kind,
+                            c.name,
+                            c.condition.as_deref().unwrap_or_default()
+                        );
+                        if let Some(message) = &c.message {
+                            line.push_str(&format!(" ({})", message));
+                        }
+                        line
+                    })
+                    .collect();
+                parts.push(format!("wait-for conditions unmet: {}", unmet.join("; ")));
+            }
 
             format!("Unhealthy: {}", parts.join(", "))
         }
@@ -223,6 +273,14 @@ pub struct ResourceHealth {
 
     /// Additional status message
     pub message: Option<String>,
+
+    /// Condition or JSONPath evaluated by an annotation-driven check
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+
+    /// Last transition time of the evaluated condition
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_transition_time: Option<DateTime<Utc>>,
 }
 
 impl ResourceHealth {
@@ -235,6 +293,549 @@ impl ResourceHealth {
     }
 }
 
+/// Annotation-driven readiness check for an arbitrary resource
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HealthProbe {
+    /// `status.conditions[type=X].status` must be `"True"`
+    Condition(String),
+    /// Field at a JSONPath must equal `expected`, or be `true` when unset
+    JsonPath {
+        path: String,
+        expected: Option<String>,
+    },
+}
+
+impl HealthProbe {
+    /// Parse probes from `sherpack.io/health-condition` and
+    /// `sherpack.io/health-jsonpath` annotations
+    pub fn from_annotations(annotations: &BTreeMap<String, String>) -> Vec<Self> {
+        let mut probes = Vec::new();
+
+        if let Some(condition) = annotations
+            .get(annotations::sherpack::HEALTH_CONDITION)
+            .map(|c| c.trim())
+            .filter(|c| !c.is_empty())
+        {
+            probes.push(HealthProbe::Condition(condition.to_string()));
+        }
+
+        if let Some(expr) = annotations
+            .get(annotations::sherpack::HEALTH_JSONPATH)
+            .map(|p| p.trim())
+            .filter(|p| !p.is_empty())
+        {
+            let (path, expected) = split_jsonpath_expectation(expr);
+            probes.push(HealthProbe::JsonPath {
+                path: path.to_string(),
+                expected: expected.map(String::from),
+            });
+        }
+
+        probes
+    }
+
+    /// Parse probes from a resource's annotations
+    fn from_yaml(yaml: &serde_yaml::Value) -> Vec<Self> {
+        let annotations: BTreeMap<String, String> = yaml
+            .get("metadata")
+            .and_then(|m| m.get("annotations"))
+            .and_then(|a| serde_yaml::from_value(a.clone()).ok())
+            .unwrap_or_default();
+        Self::from_annotations(&annotations)
+    }
+
+    /// Human-readable description, reported in [`ResourceHealth::condition`]
+    pub fn describe(&self) -> String {
+        match self {
+            HealthProbe::Condition(c) => c.clone(),
+            HealthProbe::JsonPath {
+                path,
+                expected: Some(e),
+            } => format!("{}={}", path, e),
+            HealthProbe::JsonPath {
+                path,
+                expected: None,
+            } => path.clone(),
+        }
+    }
+}
+
+/// A condition explicitly waited for (`--wait-for`)
+///
+/// Written `Kind/name: condition=Type` or `Kind/name: jsonpath={.path}=value`,
+/// e.g. `Certificate/web-tls: condition=Ready`. The kind is matched without
+/// regard to case.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct WaitCondition {
+    /// Kind of the resource
+    pub kind: String,
+    /// Name of the resource
+    pub name: String,
+    /// What must hold on the resource
+    pub probe: HealthProbe,
+}
+
+impl WaitCondition {
+    /// Whether this condition is about the resource `kind`/`name`
+    pub fn targets(&self, kind: &str, name: &str) -> bool {
+        self.kind.eq_ignore_ascii_case(kind) && self.name == name
+    }
+}
+
+impl std::str::FromStr for WaitCondition {
+    type Err = KubeError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = |reason: &str| {
+            KubeError::InvalidConfig(format!("invalid wait-for condition '{}': {}", s, reason))
+        };
+
+        let (target, spec) = s
+            .split_once(':')
+            .ok_or_else(|| invalid("expected 'Kind/name: condition=Type'"))?;
+        let (kind, name) = target
+            .trim()
+            .split_once('/')
+            .filter(|(kind, name)| !kind.is_empty() && !name.is_empty())
+            .ok_or_else(|| invalid("expected Kind/name before ':'"))?;
+
+        let spec = spec.trim();
+        let probe = if let Some(condition) = spec.strip_prefix("condition=") {
+            HealthProbe::Condition(condition.trim().to_string())
+        } else if let Some(expr) = spec.strip_prefix("jsonpath=") {
+            let (path, expected) = split_jsonpath_expectation(expr.trim());
+            HealthProbe::JsonPath {
+                path: path.to_string(),
+                expected: expected.map(String::from),
+            }
+        } else {
+            return Err(invalid(
+                "expected condition=<type> or jsonpath=<path>[=<value>]",
+            ));
+        };
+        if probe.describe().is_empty() {
+            return Err(invalid("empty condition"));
+        }
+
+        Ok(Self {
+            kind: kind.to_string(),
+            name: name.to_string(),
+            probe,
+        })
+    }
+}
+
+impl std::fmt::Display for WaitCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let spec = match self.probe {
+            HealthProbe::Condition(_) => "condition",
+            HealthProbe::JsonPath { .. } => "jsonpath",
+        };
+        write!(
+            f,
+            "{}/{}: {}={}",
+            self.kind,
+            self.name,
+            spec,
+            self.probe.describe()
+        )
+    }
+}
+
+impl TryFrom<String> for WaitCondition {
+    type Error = KubeError;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<WaitCondition> for String {
+    fn from(condition: WaitCondition) -> Self {
+        condition.to_string()
+    }
+}
+
+/// A resource checked through annotation-driven probes
+#[derive(Debug, Clone)]
+struct ProbedResource {
+    api_version: String,
+    kind: String,
+    name: String,
+    namespace: Option<String>,
+    probes: Vec<HealthProbe>,
+}
+
+/// Find resources carrying health probe annotations
+fn parse_probed_resources(manifest: &str) -> Vec<ProbedResource> {
+    let mut resources = Vec::new();
+
+    for doc in manifest.split("---") {
+        let yaml: serde_yaml::Value = match serde_yaml::from_str(doc.trim()) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+
+        let probes = HealthProbe::from_yaml(&yaml);
+        if probes.is_empty() {
+            continue;
+        }
+
+        let field = |v: Option<&serde_yaml::Value>| v.and_then(|v| v.as_str()).map(String::from);
+        let metadata = yaml.get("metadata");
+        let (Some(api_version), Some(kind), Some(name)) = (
+            field(yaml.get("apiVersion")),
+            field(yaml.get("kind")),
+            field(metadata.and_then(|m| m.get("name"))),
+        ) else {
+            continue;
+        };
+
+        resources.push(ProbedResource {
+            api_version,
+            kind,
+            name,
+            namespace: field(metadata.and_then(|m| m.get("namespace"))),
+            probes,
+        });
+    }
+
+    resources
+}
+
+/// `apiVersion`, `kind` and namespace of the manifest resource a wait-for
+/// condition targets
+fn find_wait_target(
+    manifest: &str,
+    condition: &WaitCondition,
+) -> Option<(String, String, Option<String>)> {
+    manifest.split("---").find_map(|doc| {
+        let yaml: serde_yaml::Value = serde_yaml::from_str(doc.trim()).ok()?;
+        let field = |v: Option<&serde_yaml::Value>| v.and_then(|v| v.as_str()).map(String::from);
+        let metadata = yaml.get("metadata");
+        let kind = field(yaml.get("kind"))?;
+        let name = field(metadata.and_then(|m| m.get("name")))?;
+
+        condition.targets(&kind, &name).then(|| {
+            (
+                field(yaml.get("apiVersion")).unwrap_or_default(),
+                kind,
+                field(metadata.and_then(|m| m.get("namespace"))),
+            )
+        })
+    })
+}
+
+/// Evaluate probes against a live object; the first failing probe wins
+fn evaluate_probes(
+    kind: &str,
+    name: &str,
+    namespace: &str,
+    object: &JsonValue,
+    probes: &[HealthProbe],
+) -> ResourceHealth {
+    let mut health = ResourceHealth {
+        kind: kind.to_string(),
+        name: name.to_string(),
+        namespace: namespace.to_string(),
+        healthy: true,
+        ready: None,
+        desired: None,
+        message: None,
+        condition: None,
+        last_transition_time: None,
+    };
+
+    for probe in probes {
+        health.condition = Some(probe.describe());
+        health.last_transition_time = None;
+
+        match probe {
+            HealthProbe::Condition(condition_type) => {
+                let condition = object
+                    .pointer("/status/conditions")
+                    .and_then(|c| c.as_array())
+                    .and_then(|conds| {
+                        conds.iter().find(|c| {
+                            c.get("type").and_then(|t| t.as_str()) == Some(condition_type)
+                        })
+                    });
+
+                let Some(condition) = condition else {
+                    health.healthy = false;
+                    health.message = Some(format!("Condition {} not reported", condition_type));
+                    return health;
+                };
+
+                health.last_transition_time = condition
+                    .get("lastTransitionTime")
+                    .and_then(|t| t.as_str())
+                    .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+                    .map(|t| t.with_timezone(&Utc));
+
+                let status = condition.get("status").and_then(|s| s.as_str());
+                if status != Some("True") {
+                    health.healthy = false;
+                    health.message = condition
+                        .get("message")
+                        .or_else(|| condition.get("reason"))
+                        .and_then(|m| m.as_str())
+                        .map(String::from)
+                        .or_else(|| {
+                            Some(format!(
+                                "Condition {} is {}",
+                                condition_type,
+                                status.unwrap_or("Unknown")
+                            ))
+                        });
+                    return health;
+                }
+            }
+            HealthProbe::JsonPath { path, expected } => {
+                let value = json_path_lookup(object, path);
+                let actual = value.map(|v| match v {
+                    JsonValue::String(s) => s.clone(),
+                    other => other.to_string(),
+                });
+
+                let passed = match (expected, &actual) {
+                    (Some(e), Some(a)) => a == e,
+                    (None, Some(a)) => a == "true" || a == "True",
+                    (_, None) => false,
+                };
+
+                if !passed {
+                    health.healthy = false;
+                    health.message = Some(match actual {
+                        Some(a) => format!("{} is {}", path, a),
+                        None => format!("{} not found", path),
+                    });
+                    return health;
+                }
+            }
+        }
+    }
+
+    health
+}
+
+/// Kinds whose readiness means "ran to completion"
+fn is_job_kind(kind: &str) -> bool {
+    matches!(kind, "Job" | "CronJob")
+}
+
+/// Evaluate a Job's completion, plus whether it has failed permanently
+///
+/// A Job is healthy once `status.succeeded >= spec.completions` (default 1)
+/// or it reports `Complete=True`; it has failed once it reports `Failed=True`.
+fn evaluate_job(name: &str, namespace: &str, job: &Job) -> (ResourceHealth, bool) {
+    let status = job.status.as_ref();
+    let completions = job.spec.as_ref().and_then(|s| s.completions).unwrap_or(1);
+    let succeeded = status.and_then(|s| s.succeeded).unwrap_or(0);
+    let failed = status.and_then(|s| s.failed).unwrap_or(0);
+    let active = status.and_then(|s| s.active).unwrap_or(0);
+
+    let condition = |type_: &str| {
+        status.and_then(|s| s.conditions.as_ref()).and_then(|c| {
+            c.iter()
+                .find(|cond| cond.type_ == type_ && cond.status == "True")
+        })
+    };
+
+    let complete = condition("Complete");
+    let failed_condition = condition("Failed");
+
+    let healthy = failed_condition.is_none() && (complete.is_some() || succeeded >= completions);
+    let failed_permanently = failed_condition.is_some();
+
+    let message = if let Some(cond) = failed_condition {
+        Some(
+            cond.message
+                .clone()
+                .or_else(|| cond.reason.clone())
+                .unwrap_or_else(|| format!("Job failed with {} failures", failed)),
+        )
+    } else if !healthy {
+        Some(format!(
+            "Running: {} active, {}/{} succeeded",
+            active, succeeded, completions
+        ))
+    } else {
+        None
+    };
+
+    let health = ResourceHealth {
+        kind: "Job".to_string(),
+        name: name.to_string(),
+        namespace: namespace.to_string(),
+        healthy,
+        ready: Some(succeeded),
+        desired: Some(completions),
+        message,
+        condition: None,
+        last_transition_time: None,
+    };
+
+    (health, failed_permanently)
+}
+
+/// Evaluate a Pod that is expected to run to completion
+///
+/// Healthy once it reaches `Succeeded`; failed once it reaches `Failed`.
+fn evaluate_pod_completion(name: &str, namespace: &str, pod: &Pod) -> (ResourceHealth, bool) {
+    let status = pod.status.as_ref();
+    let phase = status.and_then(|s| s.phase.as_deref()).unwrap_or("Pending");
+
+    let healthy = phase == "Succeeded";
+    let failed = phase == "Failed";
+
+    let message = if failed {
+        // Prefer the pod-level reason, then the first failed container's
+        let container_reason =
+            status
+                .and_then(|s| s.container_statuses.as_ref())
+                .and_then(|containers| {
+                    containers.iter().find_map(|c| {
+                        let terminated = c.state.as_ref()?.terminated.as_ref()?;
+                        (terminated.exit_code != 0).then(|| {
+                            format!(
+                                "container {} exited with code {}",
+                                c.name, terminated.exit_code
+                            )
+                        })
+                    })
+                });
+        Some(
+            status
+                .and_then(|s| s.message.clone().or_else(|| s.reason.clone()))
+                .or(container_reason)
+                .unwrap_or_else(|| "Pod failed".to_string()),
+        )
+    } else if !healthy {
+        Some(format!("Pod is {}", phase))
+    } else {
+        None
+    };
+
+    let health = ResourceHealth {
+        kind: "Pod".to_string(),
+        name: name.to_string(),
+        namespace: namespace.to_string(),
+        healthy,
+        ready: None,
+        desired: None,
+        message,
+        condition: None,
+        last_transition_time: None,
+    };
+
+    (health, failed)
+}
+
+/// Evaluate a CronJob from the most recent Job it owns
+fn evaluate_cronjob(name: &str, namespace: &str, jobs: &[Job]) -> (ResourceHealth, bool) {
+    let last_job = jobs
+        .iter()
+        .filter(|job| {
+            job.metadata
+                .owner_references
+                .as_ref()
+                .is_some_and(|owners| owners.iter().any(|o| o.kind == "CronJob" && o.name == name))
+        })
+        .max_by(|a, b| {
+            a.metadata
+                .creation_timestamp
+                .cmp(&b.metadata.creation_timestamp)
+        });
+
+    let Some(job) = last_job else {
+        let health = ResourceHealth {
+            kind: "CronJob".to_string(),
+            name: name.to_string(),
+            namespace: namespace.to_string(),
+            healthy: true,
+            ready: None,
+            desired: None,
+            message: Some("No Job scheduled yet".to_string()),
+            condition: None,
+            last_transition_time: None,
+        };
+        return (health, false);
+    };
+
+    let job_name = job.metadata.name.as_deref().unwrap_or("unnamed");
+    let (mut health, failed) = evaluate_job(job_name, namespace, job);
+
+    health.message = Some(match health.message.take() {
+        Some(msg) => format!("Last Job {}: {}", job_name, msg),
+        None => format!("Last Job {} succeeded", job_name),
+    });
+    health.kind = "CronJob".to_string();
+    health.name = name.to_string();
+
+    (health, failed)
+}
+
+/// Split `path=expected`, ignoring `=` inside brackets
+fn split_jsonpath_expectation(expr: &str) -> (&str, Option<&str>) {
+    let mut depth = 0i32;
+    for (i, c) in expr.char_indices() {
+        match c {
+            '[' | '{' | '(' => depth += 1,
+            ']' | '}' | ')' => depth -= 1,
+            '=' if depth == 0 => {
+                let expected = expr[i + 1..].trim().trim_matches('"');
+                return (expr[..i].trim(), Some(expected));
+            }
+            _ => {}
+        }
+    }
+    (expr, None)
+}
+
+/// Resolve a simple JSONPath such as `{.status.conditions[type=Ready].status}`
+///
+/// Supports dotted fields, `[n]` indexes and `[key=value]` /
+/// `[?(@.key=="value")]` selectors on arrays.
+fn json_path_lookup<'a>(value: &'a JsonValue, path: &str) -> Option<&'a JsonValue> {
+    let path = path.trim().trim_start_matches('{').trim_end_matches('}');
+    let path = path.trim_start_matches('$').trim_start_matches('.');
+
+    let mut current = value;
+    let mut rest = path;
+
+    while !rest.is_empty() {
+        if let Some(stripped) = rest.strip_prefix('[') {
+            let end = stripped.find(']')?;
+            let selector = &stripped[..end];
+            rest = stripped[end + 1..].trim_start_matches('.');
+
+            let items = current.as_array()?;
+            current = match selector.parse::<usize>() {
+                Ok(index) => items.get(index)?,
+                Err(_) => {
+                    let selector = selector
+                        .trim_start_matches("?(")
+                        .trim_end_matches(')')
+                        .trim_start_matches("@.");
+                    let (key, expected) = selector.split_once('=')?;
+                    let expected = expected.trim_start_matches('=').trim().trim_matches('"');
+                    items.iter().find(|item| {
+                        item.get(key.trim()).and_then(|v| v.as_str()) == Some(expected)
+                    })?
+                }
+            };
+        } else {
+            let end = rest.find(['.', '[']).unwrap_or(rest.len());
+            current = current.get(&rest[..end])?;
+            rest = rest[end..].trim_start_matches('.');
+        }
+    }
+
+    Some(current)
+}
+
 /// Result of an individual health check
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CheckResult {
@@ -275,21 +876,57 @@ impl HealthChecker {
         let mut resources = Vec::new();
         let mut http_checks = Vec::new();
         let mut command_checks = Vec::new();
+        let mut conditions = Vec::new();
 
         // Parse manifest to find resources to check
         let resource_refs = self.parse_resources(&release.manifest);
+        let probed = parse_probed_resources(&release.manifest);
+        let waits = self.wait_targets(client, &release.manifest).await?;
 
         // Check resources with retry
         loop {
             resources.clear();
 
+            let mut failed = false;
+
             for (kind, name) in &resource_refs {
+                let (mut health, permanently_failed) = self
+                    .check_resource_status(client, &release.namespace, kind, name)
+                    .await?;
+
+                if !health.healthy
+                    && is_job_kind(kind)
+                    && let Some(job_timeout) = self.config.job_timeout
+                    && Utc::now() - start >= job_timeout
+                {
+                    health.message = Some(format!(
+                        "{} did not complete within {}s",
+                        kind,
+                        job_timeout.num_seconds()
+                    ));
+                    failed = true;
+                }
+
+                failed |= permanently_failed;
+                resources.push(health);
+            }
+
+            for target in &probed {
                 let health = self
-                    .check_resource(client, &release.namespace, kind, name)
+                    .check_probed_resource(client, &release.namespace, target)
                     .await?;
                 resources.push(health);
             }
 
+            // Check explicit wait-for conditions
+            conditions.clear();
+            for target in &waits {
+                let health = self
+                    .check_probed_resource(client, &release.namespace, target)
+                    .await?;
+                conditions.push(health);
+            }
+
             // Check HTTP endpoints
             for http_check in &self.config.http_checks {
                 let result = self.check_http(http_check).await;
@@ -308,25 +945,28 @@ impl HealthChecker {
             let all_resources_healthy = resources.iter().all(|r| r.healthy);
             let all_http_healthy = http_checks.iter().all(|c| c.success);
             let all_cmd_healthy = command_checks.iter().all(|c| c.success);
+            let all_conditions_met = conditions.iter().all(|c| c.healthy);
 
-            if all_resources_healthy && all_http_healthy && all_cmd_healthy {
+            if all_resources_healthy && all_http_healthy && all_cmd_healthy && all_conditions_met {
                 return Ok(HealthStatus {
                     healthy: true,
                     resources,
                     http_checks,
                     command_checks,
+                    conditions,
                     checked_at: Utc::now(),
                     duration: Utc::now().signed_duration_since(start),
                 });
             }
 
-            // Check timeout
-            if Utc::now() >= deadline {
+            // Stop on permanent failure or timeout
+            if failed || Utc::now() >= deadline {
                 return Ok(HealthStatus {
                     healthy: false,
                     resources,
                     http_checks,
                     command_checks,
+                    conditions,
                     checked_at: Utc::now(),
                     duration: Utc::now().signed_duration_since(start),
                 });
@@ -357,6 +997,21 @@ impl HealthChecker {
             resources.push(health);
         }
 
+        for target in &parse_probed_resources(&release.manifest) {
+            let health = self
+                .check_probed_resource(client, &release.namespace, target)
+                .await?;
+            resources.push(health);
+        }
+
+        let mut conditions = Vec::new();
+        for target in &self.wait_targets(client, &release.manifest).await? {
+            let health = self
+                .check_probed_resource(client, &release.namespace, target)
+                .await?;
+            conditions.push(health);
+        }
+
         let mut http_checks = Vec::new();
         for http_check in &self.config.http_checks {
             let result = self.check_http(http_check).await;
@@ -373,13 +1028,15 @@ impl HealthChecker {
 
         let healthy = resources.iter().all(|r| r.healthy)
             && http_checks.iter().all(|c| c.success)
-            && command_checks.iter().all(|c| c.success);
+            && command_checks.iter().all(|c| c.success)
+            && conditions.iter().all(|c| c.healthy);
 
         Ok(HealthStatus {
             healthy,
             resources,
             http_checks,
             command_checks,
+            conditions,
             checked_at: Utc::now(),
             duration: Utc::now().signed_duration_since(start),
         })
@@ -407,15 +1064,33 @@ impl HealthChecker {
                 .and_then(|n| n.as_str())
                 .unwrap_or("");
 
+            let annotations: BTreeMap<String, String> = yaml
+                .get("metadata")
+                .and_then(|m| m.get("annotations"))
+                .and_then(|a| serde_yaml::from_value(a.clone()).ok())
+                .unwrap_or_default();
+
+            // Hook Jobs are awaited by the hook executor, not here
+            let is_hook = annotations::get_annotation(
+                &annotations,
+                annotations::sherpack::HOOK,
+                annotations::helm::HOOK,
+            )
+            .is_some();
+
             // Only check supported resource types
             let should_check = match kind {
                 "Deployment" => self.config.check_deployments,
                 "StatefulSet" => self.config.check_statefulsets,
                 "DaemonSet" => self.config.check_daemonsets,
+                "Job" | "CronJob" => self.config.check_jobs && !is_hook,
                 _ => false,
             };
 
-            if should_check && !name.is_empty() {
+            // Annotated resources are checked by their probes instead
+            let has_probes = !HealthProbe::from_annotations(&annotations).is_empty();
+
+            if should_check && !has_probes && !name.is_empty() {
                 resources.push((kind.to_string(), name.to_string()));
             }
         }
@@ -423,6 +1098,225 @@ impl HealthChecker {
         resources
     }
 
+    /// Resolve the resources targeted by the configured wait-for conditions
+    ///
+    /// The `apiVersion` comes from the release manifest, or from discovery
+    /// for resources created by other means (e.g. by an operator).
+    async fn wait_targets(
+        &self,
+        client: &kube::Client,
+        manifest: &str,
+    ) -> Result<Vec<ProbedResource>> {
+        let mut discovery = None;
+        let mut targets = Vec::new();
+
+        for condition in &self.config.wait_for {
+            let (api_version, kind, namespace) = match find_wait_target(manifest, condition) {
+                Some(target) => target,
+                None => {
+                    if discovery.is_none() {
+                        discovery = Some(
+                            kube::discovery::Discovery::new(client.clone())
+                                .run()
+                                .await
+                                .map_err(KubeError::KubeApi)?,
+                        );
+                    }
+                    let api_resource = discovery
+                        .iter()
+                        .flat_map(|d| d.groups())
+                        .flat_map(|g| g.recommended_resources())
+                        .map(|(ar, _)| ar)
+                        .find(|ar| condition.kind.eq_ignore_ascii_case(&ar.kind))
+                        .ok_or_else(|| {
+                            KubeError::InvalidConfig(format!(
+                                "wait-for condition '{}': kind {} is not served by the cluster",
+                                condition, condition.kind
+                            ))
+                        })?;
+                    (api_resource.api_version, api_resource.kind, None)
+                }
+            };
+
+            targets.push(ProbedResource {
+                api_version,
+                kind,
+                name: condition.name.clone(),
+                namespace,
+                probes: vec![condition.probe.clone()],
+            });
+        }
+
+        Ok(targets)
+    }
+
+    /// Wait for a wait-for condition on a resource of `api_version`/`kind`
+    ///
+    /// Polls at the configured interval until the condition holds or the
+    /// timeout elapses, returning the final result.
+    pub async fn wait_for_condition(
+        &self,
+        client: &kube::Client,
+        namespace: &str,
+        api_version: &str,
+        kind: &str,
+        condition: &WaitCondition,
+    ) -> Result<ResourceHealth> {
+        let target = ProbedResource {
+            api_version: api_version.to_string(),
+            kind: kind.to_string(),
+            name: condition.name.clone(),
+            namespace: None,
+            probes: vec![condition.probe.clone()],
+        };
+        let deadline = Utc::now() + self.config.timeout;
+
+        loop {
+            let health = self
+                .check_probed_resource(client, namespace, &target)
+                .await?;
+
+            if health.healthy || Utc::now() >= deadline {
+                return Ok(health);
+            }
+
+            tokio::time::sleep(self.config.interval.to_std().unwrap_or_default()).await;
+        }
+    }
+
+    /// Check a resource using its annotation-driven probes
+    async fn check_probed_resource(
+        &self,
+        client: &kube::Client,
+        namespace: &str,
+        target: &ProbedResource,
+    ) -> Result<ResourceHealth> {
+        let (group, version) = match target.api_version.split_once('/') {
+            Some((g, v)) => (g, v),
+            None => ("", target.api_version.as_str()),
+        };
+        let gvk = GroupVersionKind::gvk(group, version, &target.kind);
+        let (api_resource, capabilities) = kube::discovery::pinned_kind(client, &gvk)
+            .await
+            .map_err(KubeError::KubeApi)?;
+
+        let namespace = target.namespace.as_deref().unwrap_or(namespace);
+        let api: Api<DynamicObject> = match capabilities.scope {
+            Scope::Cluster => Api::all_with(client.clone(), &api_resource),
+            Scope::Namespaced => Api::namespaced_with(client.clone(), namespace, &api_resource),
+        };
+
+        let object = match api.get(&target.name).await {
+            Ok(obj) => serde_json::to_value(&obj)?,
+            Err(kube::Error::Api(ae)) if ae.code == 404 => {
+                return Ok(ResourceHealth {
+                    kind: target.kind.clone(),
+                    name: target.name.clone(),
+                    namespace: namespace.to_string(),
+                    healthy: false,
+                    ready: None,
+                    desired: None,
+                    message: Some(format!("{} not found", target.kind)),
+                    condition: target.probes.first().map(HealthProbe::describe),
+                    last_transition_time: None,
+                });
+            }
+            Err(e) => return Err(KubeError::KubeApi(e)),
+        };
+
+        Ok(evaluate_probes(
+            &target.kind,
+            &target.name,
+            namespace,
+            &object,
+            &target.probes,
+        ))
+    }
+
+    /// Wait for a single resource to become healthy
+    ///
+    /// Polls at the configured interval until the resource is healthy or the
+    /// timeout elapses. A Job that fails permanently returns immediately.
+    pub async fn wait_for_resource(
+        &self,
+        client: &kube::Client,
+        namespace: &str,
+        kind: &str,
+        name: &str,
+    ) -> Result<ResourceHealth> {
+        let timeout = if is_job_kind(kind) {
+            self.config.job_timeout.unwrap_or(self.config.timeout)
+        } else {
+            self.config.timeout
+        };
+        let deadline = Utc::now() + timeout;
+
+        loop {
+            let (health, failed) = self
+                .check_resource_status(client, namespace, kind, name)
+                .await?;
+
+            if health.healthy || failed || Utc::now() >= deadline {
+                return Ok(health);
+            }
+
+            tokio::time::sleep(self.config.interval.to_std().unwrap_or_default()).await;
+        }
+    }
+
+    /// Wait for a Job or Pod to run to completion
+    ///
+    /// Unlike [`wait_for_resource`](Self::wait_for_resource), a Pod is only
+    /// healthy once it has `Succeeded`, which is what test and hook Pods need.
+    /// Returns the final health, plus whether the resource failed (as opposed
+    /// to still running when the timeout elapsed).
+    pub async fn wait_for_completion(
+        &self,
+        client: &kube::Client,
+        namespace: &str,
+        kind: &str,
+        name: &str,
+    ) -> Result<(ResourceHealth, bool)> {
+        let deadline = Utc::now() + self.config.job_timeout.unwrap_or(self.config.timeout);
+
+        loop {
+            let (health, failed) = match kind {
+                "Pod" => self.pod_completion_status(client, namespace, name).await?,
+                _ => {
+                    self.check_resource_status(client, namespace, kind, name)
+                        .await?
+                }
+            };
+
+            if health.healthy || failed || Utc::now() >= deadline {
+                return Ok((health, failed));
+            }
+
+            tokio::time::sleep(self.config.interval.to_std().unwrap_or_default()).await;
+        }
+    }
+
+    /// Check a resource, plus whether it has failed permanently
+    ///
+    /// Only Jobs and CronJobs can fail permanently; other kinds may still
+    /// become ready later.
+    async fn check_resource_status(
+        &self,
+        client: &kube::Client,
+        namespace: &str,
+        kind: &str,
+        name: &str,
+    ) -> Result<(ResourceHealth, bool)> {
+        match kind {
+            "Job" => self.job_status(client, namespace, name).await,
+            "CronJob" => self.cronjob_status(client, namespace, name).await,
+            _ => Ok((
+                self.check_resource(client, namespace, kind, name).await?,
+                false,
+            )),
+        }
+    }
+
     /// Check a single Kubernetes resource
     async fn check_resource(
         &self,
@@ -436,6 +1330,7 @@ impl HealthChecker {
             "StatefulSet" => self.check_statefulset(client, namespace, name).await,
             "DaemonSet" => self.check_daemonset(client, namespace, name).await,
             "Job" => self.check_job(client, namespace, name).await,
+            "CronJob" => Ok(self.cronjob_status(client, namespace, name).await?.0),
             _ => {
                 // Unknown resource types are considered ready
                 Ok(ResourceHealth {
@@ -446,6 +1341,8 @@ impl HealthChecker {
                     ready: None,
                     desired: None,
                     message: Some("Unknown resource type, skipping check".to_string()),
+                    condition: None,
+                    last_transition_time: None,
                 })
             }
         }
@@ -476,6 +1373,8 @@ impl HealthChecker {
                     ready: Some(0),
                     desired: Some(0),
                     message: Some("Deployment not found".to_string()),
+                    condition: None,
+                    last_transition_time: None,
                 });
             }
             Err(e) => return Err(KubeError::KubeApi(e)),
@@ -530,6 +1429,8 @@ impl HealthChecker {
             ready: Some(ready),
             desired: Some(desired),
             message,
+            condition: None,
+            last_transition_time: None,
         })
     }
 
@@ -557,6 +1458,8 @@ impl HealthChecker {
                     ready: Some(0),
                     desired: Some(0),
                     message: Some("StatefulSet not found".to_string()),
+                    condition: None,
+                    last_transition_time: None,
                 });
             }
             Err(e) => return Err(KubeError::KubeApi(e)),
@@ -593,6 +1496,8 @@ impl HealthChecker {
             ready: Some(ready),
             desired: Some(desired),
             message,
+            condition: None,
+            last_transition_time: None,
         })
     }
 
@@ -620,6 +1525,8 @@ impl HealthChecker {
                     ready: Some(0),
                     desired: Some(0),
                     message: Some("DaemonSet not found".to_string()),
+                    condition: None,
+                    last_transition_time: None,
                 });
             }
             Err(e) => return Err(KubeError::KubeApi(e)),
@@ -650,6 +1557,8 @@ impl HealthChecker {
             ready: Some(ready),
             desired: Some(desired),
             message,
+            condition: None,
+            last_transition_time: None,
         })
     }
 
@@ -663,12 +1572,22 @@ impl HealthChecker {
         namespace: &str,
         name: &str,
     ) -> Result<ResourceHealth> {
+        Ok(self.job_status(client, namespace, name).await?.0)
+    }
+
+    /// Job health, plus whether the Job has failed permanently
+    async fn job_status(
+        &self,
+        client: &kube::Client,
+        namespace: &str,
+        name: &str,
+    ) -> Result<(ResourceHealth, bool)> {
         let api: Api<Job> = Api::namespaced(client.clone(), namespace);
 
         let job = match api.get(name).await {
             Ok(j) => j,
             Err(kube::Error::Api(ae)) if ae.code == 404 => {
-                return Ok(ResourceHealth {
+                let health = ResourceHealth {
                     kind: "Job".to_string(),
                     name: name.to_string(),
                     namespace: namespace.to_string(),
@@ -676,57 +1595,84 @@ impl HealthChecker {
                     ready: Some(0),
                     desired: Some(1),
                     message: Some("Job not found".to_string()),
-                });
+                    condition: None,
+                    last_transition_time: None,
+                };
+                return Ok((health, false));
             }
             Err(e) => return Err(KubeError::KubeApi(e)),
         };
 
-        let status = job.status.as_ref();
-        let succeeded = status.and_then(|s| s.succeeded).unwrap_or(0);
-        let failed = status.and_then(|s| s.failed).unwrap_or(0);
-        let active = status.and_then(|s| s.active).unwrap_or(0);
-
-        // Check job conditions for failure
-        let has_failed_condition = status
-            .and_then(|s| s.conditions.as_ref())
-            .map(|c| {
-                c.iter()
-                    .any(|cond| cond.type_ == "Failed" && cond.status == "True")
-            })
-            .unwrap_or(false);
-
-        let healthy = succeeded > 0;
-        let failed_permanently = has_failed_condition || (failed > 0 && active == 0);
-
-        let message = if failed_permanently {
-            // Get failure reason
-            let reason = status
-                .and_then(|s| s.conditions.as_ref())
-                .and_then(|c| {
-                    c.iter()
-                        .find(|cond| cond.type_ == "Failed")
-                        .and_then(|cond| cond.message.clone())
-                })
-                .unwrap_or_else(|| format!("Job failed with {} failures", failed));
-            Some(reason)
-        } else if !healthy {
-            Some(format!(
-                "Running: {} active, {} succeeded",
-                active, succeeded
-            ))
-        } else {
-            None
-        };
+        Ok(evaluate_job(name, namespace, &job))
+    }
 
-        Ok(ResourceHealth {
-            kind: "Job".to_string(),
-            name: name.to_string(),
-            namespace: namespace.to_string(),
-            healthy,
-            ready: Some(succeeded),
-            desired: Some(1),
-            message,
-        })
+    /// Pod completion, plus whether the Pod has failed
+    async fn pod_completion_status(
+        &self,
+        client: &kube::Client,
+        namespace: &str,
+        name: &str,
+    ) -> Result<(ResourceHealth, bool)> {
+        let api: Api<Pod> = Api::namespaced(client.clone(), namespace);
+
+        match api.get(name).await {
+            Ok(pod) => Ok(evaluate_pod_completion(name, namespace, &pod)),
+            Err(kube::Error::Api(ae)) if ae.code == 404 => Ok((
+                ResourceHealth {
+                    kind: "Pod".to_string(),
+                    name: name.to_string(),
+                    namespace: namespace.to_string(),
+                    healthy: false,
+                    ready: None,
+                    desired: None,
+                    message: Some("Pod not found".to_string()),
+                    condition: None,
+                    last_transition_time: None,
+                },
+                false,
+            )),
+            Err(e) => Err(KubeError::KubeApi(e)),
+        }
+    }
+
+    /// CronJob health, based on its most recently scheduled Job
+    ///
+    /// A CronJob that has not scheduled any Job yet is considered healthy.
+    async fn cronjob_status(
+        &self,
+        client: &kube::Client,
+        namespace: &str,
+        name: &str,
+    ) -> Result<(ResourceHealth, bool)> {
+        let cronjobs: Api<CronJob> = Api::namespaced(client.clone(), namespace);
+
+        if let Err(e) = cronjobs.get(name).await {
+            return match e {
+                kube::Error::Api(ae) if ae.code == 404 => Ok((
+                    ResourceHealth {
+                        kind: "CronJob".to_string(),
+                        name: name.to_string(),
+                        namespace: namespace.to_string(),
+                        healthy: false,
+                        ready: None,
+                        desired: None,
+                        message: Some("CronJob not found".to_string()),
+                        condition: None,
+                        last_transition_time: None,
+                    },
+                    false,
+                )),
+                e => Err(KubeError::KubeApi(e)),
+            };
+        }
+
+        let jobs: Api<Job> = Api::namespaced(client.clone(), namespace);
+        let jobs = jobs
+            .list(&ListParams::default())
+            .await
+            .map_err(KubeError::KubeApi)?;
+
+        Ok(evaluate_cronjob(name, namespace, &jobs.items))
     }
 
     /// Execute an HTTP health check
@@ -920,6 +1866,26 @@ mod duration_serde {
     }
 }
 
+mod option_duration_serde {
+    use chrono::Duration;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S>(duration: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        duration.map(|d| d.num_seconds()).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let seconds = Option::<i64>::deserialize(deserializer)?;
+        Ok(seconds.map(Duration::seconds))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -937,6 +1903,8 @@ mod tests {
             } else {
                 Some("Not ready".to_string())
             },
+            condition: None,
+            last_transition_time: None,
         }
     }
 
@@ -952,9 +1920,12 @@ mod tests {
                 ready: Some(3),
                 desired: Some(3),
                 message: None,
+                condition: None,
+                last_transition_time: None,
             }],
             http_checks: vec![],
             command_checks: vec![],
+            conditions: vec![],
             checked_at: Utc::now(),
             duration: Duration::seconds(1),
         };
@@ -975,9 +1946,12 @@ mod tests {
                 ready: Some(0),
                 desired: Some(3),
                 message: Some("Waiting for pods".to_string()),
+                condition: None,
+                last_transition_time: None,
             }],
             http_checks: vec![],
             command_checks: vec![],
+            conditions: vec![],
             checked_at: Utc::now(),
             duration: Duration::seconds(300),
         };
@@ -1021,6 +1995,8 @@ metadata:
         assert!(config.check_deployments);
         assert!(config.check_statefulsets);
         assert!(!config.check_daemonsets);
+        assert!(config.check_jobs);
+        assert!(config.job_timeout.is_none());
         assert!(config.http_checks.is_empty());
         assert!(config.command_checks.is_empty());
         assert_eq!(config.timeout.num_seconds(), 300);
@@ -1157,6 +2133,147 @@ metadata:
         assert!(resources.is_empty());
     }
 
+    const POSTGRES_CR: &str = r#"
+apiVersion: postgresql.cnpg.io/v1
+kind: Cluster
+metadata:
+  name: db
+  annotations:
+    sherpack.io/health-condition: Ready
+spec:
+  instances: 3
+---
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: app
+  annotations:
+    sherpack.io/health-jsonpath: "{.status.phase}=Running"
+"#;
+
+    #[test]
+    fn test_parse_probed_resources() {
+        let probed = parse_probed_resources(POSTGRES_CR);
+        assert_eq!(probed.len(), 2);
+        assert_eq!(probed[0].api_version, "postgresql.cnpg.io/v1");
+        assert_eq!(probed[0].kind, "Cluster");
+        assert_eq!(
+            probed[0].probes,
+            vec![HealthProbe::Condition("Ready".to_string())]
+        );
+        assert_eq!(
+            probed[1].probes,
+            vec![HealthProbe::JsonPath {
+                path: "{.status.phase}".to_string(),
+                expected: Some("Running".to_string()),
+            }]
+        );
+
+        // The annotated Deployment is checked by its probe, not twice
+        let checker = HealthChecker::new(HealthCheckConfig::default());
+        assert!(checker.parse_resources(POSTGRES_CR).is_empty());
+    }
+
+    #[test]
+    fn test_evaluate_condition_probe() {
+        let object = serde_json::json!({
+            "status": {
+                "conditions": [
+                    {"type": "Progressing", "status": "True"},
+                    {
+                        "type": "Ready",
+                        "status": "True",
+                        "lastTransitionTime": "2024-05-01T10:00:00Z"
+                    }
+                ]
+            }
+        });
+        let probes = vec![HealthProbe::Condition("Ready".to_string())];
+
+        let health = evaluate_probes("Cluster", "db", "default", &object, &probes);
+        assert!(health.healthy);
+        assert_eq!(health.condition.as_deref(), Some("Ready"));
+        assert_eq!(
+            health.last_transition_time.unwrap().to_rfc3339(),
+            "2024-05-01T10:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_evaluate_condition_probe_not_ready() {
+        let object = serde_json::json!({
+            "status": {
+                "conditions": [{
+                    "type": "Ready",
+                    "status": "False",
+                    "message": "Waiting for instances",
+                    "lastTransitionTime": "2024-05-01T10:00:00Z"
+                }]
+            }
+        });
+        let probes = vec![HealthProbe::Condition("Ready".to_string())];
+
+        let health = evaluate_probes("Cluster", "db", "default", &object, &probes);
+        assert!(!health.healthy);
+        assert_eq!(health.message.as_deref(), Some("Waiting for instances"));
+        assert!(health.last_transition_time.is_some());
+
+        // Missing status entirely
+        let health = evaluate_probes("Cluster", "db", "default", &serde_json::json!({}), &probes);
+        assert!(!health.healthy);
+        assert!(health.message.unwrap().contains("not reported"));
+    }
+
+    #[test]
+    fn test_evaluate_jsonpath_probe() {
+        let object = serde_json::json!({
+            "status": {
+                "phase": "Running",
+                "replicas": 3,
+                "conditions": [{"type": "Synced", "status": "True"}]
+            }
+        });
+
+        let probe = |path: &str, expected: Option<&str>| HealthProbe::JsonPath {
+            path: path.to_string(),
+            expected: expected.map(String::from),
+        };
+
+        let cases = [
+            (probe("{.status.phase}", Some("Running")), true),
+            (probe(".status.replicas", Some("3")), true),
+            (probe("status.conditions[type=Synced].status", None), true),
+            (
+                probe(
+                    "$.status.conditions[?(@.type==\"Synced\")].status",
+                    Some("True"),
+                ),
+                true,
+            ),
+            (probe("status.conditions[0].type", Some("Synced")), true),
+            (probe("status.phase", Some("Pending")), false),
+            (probe("status.missing", None), false),
+        ];
+
+        for (p, expected) in cases {
+            let health =
+                evaluate_probes("Thing", "x", "default", &object, std::slice::from_ref(&p));
+            assert_eq!(health.healthy, expected, "probe {:?}", p);
+        }
+    }
+
+    #[test]
+    fn test_split_jsonpath_expectation() {
+        assert_eq!(
+            split_jsonpath_expectation("{.status.phase}=Running"),
+            ("{.status.phase}", Some("Running"))
+        );
+        assert_eq!(
+            split_jsonpath_expectation("status.conditions[type=Ready].status"),
+            ("status.conditions[type=Ready].status", None)
+        );
+    }
+
     #[test]
     fn test_health_status_all_healthy() {
         let status = HealthStatus {
@@ -1172,6 +2289,7 @@ metadata:
                 response_time: Duration::milliseconds(50),
             }],
             command_checks: vec![],
+            conditions: vec![],
             checked_at: Utc::now(),
             duration: Duration::seconds(1),
         };
@@ -1192,6 +2310,7 @@ metadata:
                 response_time: Duration::milliseconds(5000),
             }],
             command_checks: vec![],
+            conditions: vec![],
             checked_at: Utc::now(),
             duration: Duration::seconds(5),
         };
@@ -1239,6 +2358,8 @@ metadata:
             ready: Some(3),
             desired: Some(3),
             message: None,
+            condition: None,
+            last_transition_time: None,
         };
 
         assert_eq!(health.kind, "Deployment");
@@ -1304,6 +2425,7 @@ metadata:
             resources: vec![test_resource_health("Deployment", "app", true)],
             http_checks: vec![],
             command_checks: vec![],
+            conditions: vec![],
             checked_at: Utc::now(),
             duration: Duration::seconds(1),
         };
@@ -1326,6 +2448,7 @@ metadata:
             ],
             http_checks: vec![],
             command_checks: vec![],
+            conditions: vec![],
             checked_at: Utc::now(),
             duration: Duration::seconds(1),
         };
@@ -1333,4 +2456,369 @@ metadata:
         let unhealthy = status.resources.iter().filter(|r| !r.healthy).count();
         assert_eq!(unhealthy, 2);
     }
+
+    fn job_from_json(value: serde_json::Value) -> Job {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_evaluate_job_waits_for_all_completions() {
+        let job = job_from_json(serde_json::json!({
+            "metadata": {"name": "migrate"},
+            "spec": {"completions": 3, "template": {}},
+            "status": {"succeeded": 2, "active": 1}
+        }));
+
+        let (health, failed) = evaluate_job("migrate", "default", &job);
+        assert!(!health.healthy);
+        assert!(!failed);
+        assert_eq!(health.ready, Some(2));
+        assert_eq!(health.desired, Some(3));
+        assert_eq!(
+            health.message.as_deref(),
+            Some("Running: 1 active, 2/3 succeeded")
+        );
+    }
+
+    #[test]
+    fn test_evaluate_job_complete_condition() {
+        let job = job_from_json(serde_json::json!({
+            "metadata": {"name": "migrate"},
+            "spec": {"completions": 3, "template": {}},
+            "status": {
+                "succeeded": 1,
+                "conditions": [{"type": "Complete", "status": "True"}]
+            }
+        }));
+
+        let (health, failed) = evaluate_job("migrate", "default", &job);
+        assert!(health.healthy);
+        assert!(!failed);
+    }
+
+    #[test]
+    fn test_evaluate_job_failed_condition_surfaces_reason() {
+        let job = job_from_json(serde_json::json!({
+            "metadata": {"name": "migrate"},
+            "spec": {"template": {}},
+            "status": {
+                "failed": 6,
+                "conditions": [{
+                    "type": "Failed",
+                    "status": "True",
+                    "reason": "BackoffLimitExceeded"
+                }]
+            }
+        }));
+
+        let (health, failed) = evaluate_job("migrate", "default", &job);
+        assert!(!health.healthy);
+        assert!(failed);
+        assert_eq!(health.message.as_deref(), Some("BackoffLimitExceeded"));
+    }
+
+    #[test]
+    fn test_evaluate_job_failed_pods_still_retrying() {
+        let job = job_from_json(serde_json::json!({
+            "metadata": {"name": "migrate"},
+            "spec": {"template": {}},
+            "status": {"failed": 2}
+        }));
+
+        let (health, failed) = evaluate_job("migrate", "default", &job);
+        assert!(!health.healthy);
+        assert!(!failed);
+    }
+
+    #[test]
+    fn test_evaluate_pod_completion() {
+        let pod = |status: serde_json::Value| -> Pod {
+            serde_json::from_value(serde_json::json!({
+                "metadata": {"name": "smoke-test"},
+                "status": status
+            }))
+            .unwrap()
+        };
+
+        let (health, failed) = evaluate_pod_completion(
+            "smoke-test",
+            "default",
+            &pod(serde_json::json!({"phase": "Running"})),
+        );
+        assert!(!health.healthy);
+        assert!(!failed);
+        assert_eq!(health.message.as_deref(), Some("Pod is Running"));
+
+        let (health, failed) = evaluate_pod_completion(
+            "smoke-test",
+            "default",
+            &pod(serde_json::json!({"phase": "Succeeded"})),
+        );
+        assert!(health.healthy);
+        assert!(!failed);
+
+        let (health, failed) = evaluate_pod_completion(
+            "smoke-test",
+            "default",
+            &pod(serde_json::json!({
+                "phase": "Failed",
+                "containerStatuses": [{
+                    "name": "curl",
+                    "image": "curlimages/curl",
+                    "imageID": "",
+                    "ready": false,
+                    "restartCount": 0,
+                    "state": {"terminated": {"exitCode": 7}}
+                }]
+            })),
+        );
+        assert!(!health.healthy);
+        assert!(failed);
+        assert_eq!(
+            health.message.as_deref(),
+            Some("container curl exited with code 7")
+        );
+    }
+
+    #[test]
+    fn test_evaluate_cronjob_uses_latest_owned_job() {
+        let owned = |name: &str, created: &str, status: serde_json::Value| {
+            job_from_json(serde_json::json!({
+                "metadata": {
+                    "name": name,
+                    "creationTimestamp": created,
+                    "ownerReferences": [{
+                        "apiVersion": "batch/v1",
+                        "kind": "CronJob",
+                        "name": "backup",
+                        "uid": "1"
+                    }]
+                },
+                "spec": {"template": {}},
+                "status": status
+            }))
+        };
+        let jobs = vec![
+            owned(
+                "backup-1",
+                "2024-01-01T00:00:00Z",
+                serde_json::json!({"succeeded": 1}),
+            ),
+            owned(
+                "backup-2",
+                "2024-01-02T00:00:00Z",
+                serde_json::json!({
+                    "conditions": [{"type": "Failed", "status": "True", "reason": "DeadlineExceeded"}]
+                }),
+            ),
+            job_from_json(serde_json::json!({
+                "metadata": {"name": "other", "creationTimestamp": "2024-01-03T00:00:00Z"},
+                "spec": {"template": {}},
+                "status": {"succeeded": 1}
+            })),
+        ];
+
+        let (health, failed) = evaluate_cronjob("backup", "default", &jobs);
+        assert_eq!(health.kind, "CronJob");
+        assert_eq!(health.name, "backup");
+        assert!(!health.healthy);
+        assert!(failed);
+        assert_eq!(
+            health.message.as_deref(),
+            Some("Last Job backup-2: DeadlineExceeded")
+        );
+    }
+
+    #[test]
+    fn test_evaluate_cronjob_without_jobs() {
+        let (health, failed) = evaluate_cronjob("backup", "default", &[]);
+        assert!(health.healthy);
+        assert!(!failed);
+    }
+
+    #[test]
+    fn test_parse_resources_jobs_skip_hooks() {
+        let checker = HealthChecker::new(HealthCheckConfig::default());
+
+        let manifest = r#"
+---
+apiVersion: batch/v1
+kind: Job
+metadata:
+  name: seed
+---
+apiVersion: batch/v1
+kind: Job
+metadata:
+  name: migrate
+  annotations:
+    sherpack.io/hook: pre-install
+---
+apiVersion: batch/v1
+kind: CronJob
+metadata:
+  name: backup
+"#;
+
+        let resources = checker.parse_resources(manifest);
+        assert_eq!(
+            resources,
+            vec![
+                ("Job".to_string(), "seed".to_string()),
+                ("CronJob".to_string(), "backup".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_job_timeout_deserialization() {
+        let config: HealthCheckConfig =
+            serde_yaml::from_str("timeout: 600\njobTimeout: 120\n").unwrap();
+        assert_eq!(config.job_timeout, Some(Duration::seconds(120)));
+        assert_eq!(config.timeout.num_seconds(), 600);
+
+        let config: HealthCheckConfig = serde_yaml::from_str("timeout: 600\n").unwrap();
+        assert!(config.job_timeout.is_none());
+    }
+
+    #[test]
+    fn test_parse_wait_condition() {
+        let condition: WaitCondition = "Certificate/web-tls: condition=Ready".parse().unwrap();
+        assert_eq!(condition.kind, "Certificate");
+        assert_eq!(condition.name, "web-tls");
+        assert_eq!(condition.probe, HealthProbe::Condition("Ready".to_string()));
+        assert!(condition.targets("certificate", "web-tls"));
+        assert_eq!(
+            condition.to_string(),
+            "Certificate/web-tls: condition=Ready"
+        );
+
+        let condition: WaitCondition = "Database/main: jsonpath={.status.phase}=Running"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            condition.probe,
+            HealthProbe::JsonPath {
+                path: "{.status.phase}".to_string(),
+                expected: Some("Running".to_string()),
+            }
+        );
+        assert_eq!(
+            condition.to_string(),
+            "Database/main: jsonpath={.status.phase}=Running"
+        );
+
+        for invalid in [
+            "Certificate/web-tls",
+            "web-tls: condition=Ready",
+            "Certificate/web-tls: ready",
+            "Certificate/web-tls: condition=",
+        ] {
+            assert!(invalid.parse::<WaitCondition>().is_err(), "{invalid}");
+        }
+    }
+
+    /// Serve a cert-manager `Certificate` whose `Ready` condition is `ready`
+    fn spawn_certificate_server(
+        mut handle: tower_test::mock::Handle<
+            http::Request<kube::client::Body>,
+            http::Response<kube::client::Body>,
+        >,
+        ready: &'static str,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            while let Some((request, send)) = handle.next_request().await {
+                let response = match request.uri().path() {
+                    "/apis/cert-manager.io/v1" => serde_json::json!({
+                        "kind": "APIResourceList",
+                        "groupVersion": "cert-manager.io/v1",
+                        "resources": [{
+                            "name": "certificates",
+                            "singularName": "certificate",
+                            "namespaced": true,
+                            "kind": "Certificate",
+                            "verbs": ["get", "list"]
+                        }]
+                    }),
+                    "/apis/cert-manager.io/v1/namespaces/default/certificates/web-tls" => {
+                        serde_json::json!({
+                            "apiVersion": "cert-manager.io/v1",
+                            "kind": "Certificate",
+                            "metadata": {"name": "web-tls", "namespace": "default"},
+                            "status": {"conditions": [{
+                                "type": "Ready",
+                                "status": ready,
+                                "reason": "Issuing",
+                                "message": "Issuing certificate as Secret does not exist"
+                            }]}
+                        })
+                    }
+                    path => panic!("unexpected request {}", path),
+                };
+
+                send.send_response(
+                    http::Response::builder()
+                        .body(kube::client::Body::from(
+                            serde_json::to_vec(&response).unwrap(),
+                        ))
+                        .unwrap(),
+                );
+            }
+        })
+    }
+
+    async fn check_certificate(ready: &'static str) -> HealthStatus {
+        let (service, handle) = tower_test::mock::pair::<
+            http::Request<kube::client::Body>,
+            http::Response<kube::client::Body>,
+        >();
+        let server = spawn_certificate_server(handle, ready);
+        let client = kube::Client::new(service, "default");
+
+        let pack: sherpack_core::PackMetadata = serde_json::from_value(serde_json::json!({
+            "name": "web",
+            "version": "1.0.0"
+        }))
+        .unwrap();
+        let release = StoredRelease::for_install(
+            "web".to_string(),
+            "default".to_string(),
+            pack,
+            sherpack_core::Values::new(),
+            "apiVersion: cert-manager.io/v1\nkind: Certificate\nmetadata:\n  name: web-tls\n"
+                .to_string(),
+        );
+        let checker = HealthChecker::new(HealthCheckConfig {
+            timeout: Duration::zero(),
+            wait_for: vec!["Certificate/web-tls: condition=Ready".parse().unwrap()],
+            ..Default::default()
+        });
+
+        let status = checker.check(&release, &client).await.unwrap();
+        drop(client);
+        server.await.unwrap();
+        status
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_condition_met() {
+        let status = check_certificate("True").await;
+
+        assert!(status.healthy, "{}", status.summary());
+        assert_eq!(status.conditions.len(), 1);
+        assert!(status.unmet_conditions().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_condition_unmet_is_reported() {
+        let status = check_certificate("False").await;
+
+        assert!(!status.healthy);
+        assert_eq!(status.unmet_conditions().len(), 1);
+        assert_eq!(
+            status.summary(),
+            "Unhealthy: wait-for conditions unmet: Certificate/web-tls: Ready \
+             (Issuing certificate as Secret does not exist)"
+        );
+    }
 }
@@ -6,10 +6,29 @@
 //! - Better cleanup policies including "keep last N"
 //! - "During" phase hooks (after resources created, before ready)
 //! - Explicit timeouts
+//! - Live container logs of hook Pods and Jobs
+
+use std::collections::HashSet;
+use std::sync::Arc;
 
 use chrono::{DateTime, Duration, Utc};
+use k8s_openapi::api::core::v1::Pod;
+use kube::api::{Api, DynamicObject, GroupVersionKind, ListParams, LogParams, TypeMeta};
+use kube::discovery::Scope;
 use serde::{Deserialize, Serialize};
 
+use crate::health::{HealthCheckConfig, HealthChecker};
+use crate::progress::{ProgressEvent, ProgressFormat, ProgressSink};
+
+/// Log lines shown for a failed hook whose logs were not streamed
+const FAILURE_LOG_LINES: i64 = 20;
+
+/// How often hook Pods are checked for containers to stream
+const LOG_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// How long log streams may take to drain once their hook has finished
+const LOG_DRAIN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
 /// Hook execution phase
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
@@ -140,10 +159,10 @@ pub struct Hook {
     #[serde(default)]
     pub on_failure: HookFailurePolicy,
 
-    /// Timeout for hook execution
-    #[serde(default = "default_hook_timeout")]
-    #[serde(with = "duration_serde")]
-    pub timeout: Duration,
+    /// Timeout for hook execution (`None` uses the executor's default)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(with = "option_duration_serde")]
+    pub timeout: Option<Duration>,
 
     /// Cleanup policy after hook completes
     #[serde(default)]
@@ -223,6 +242,21 @@ pub enum HookCleanupPolicy {
     KeepLast(u32),
 }
 
+impl HookCleanupPolicy {
+    /// Whether the hook resource is deleted right after it finishes
+    ///
+    /// Timeouts count as failures. Policies that act later (before the next
+    /// run, after a delay, keep last N) are not handled here.
+    pub fn cleans_up_after(&self, succeeded: bool) -> bool {
+        match self {
+            HookCleanupPolicy::Always => true,
+            HookCleanupPolicy::OnSuccess => succeeded,
+            HookCleanupPolicy::OnFailure => !succeeded,
+            _ => false,
+        }
+    }
+}
+
 /// Result of executing a hook
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HookResult {
@@ -246,6 +280,14 @@ pub struct HookResult {
 
     /// Number of retry attempts
     pub attempts: u32,
+
+    /// Whether the hook failed by exceeding its timeout
+    #[serde(default)]
+    pub timed_out: bool,
+
+    /// Container logs, when the executor collects them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logs: Option<String>,
 }
 
 impl HookResult {
@@ -259,6 +301,8 @@ impl HookResult {
             started_at,
             completed_at: Utc::now(),
             attempts: 1,
+            timed_out: false,
+            logs: None,
         }
     }
 
@@ -278,21 +322,79 @@ impl HookResult {
             started_at,
             completed_at: Utc::now(),
             attempts,
+            timed_out: false,
+            logs: None,
         }
     }
 
+    /// Mark this result as a timeout
+    pub fn with_timed_out(mut self, timed_out: bool) -> Self {
+        self.timed_out = timed_out;
+        self
+    }
+
+    /// Attach collected container logs
+    pub fn with_logs(mut self, logs: Option<String>) -> Self {
+        self.logs = logs;
+        self
+    }
+
     /// Duration of execution
     pub fn duration(&self) -> Duration {
         self.completed_at.signed_duration_since(self.started_at)
     }
 }
 
+/// Outcome of a hook, kept on the release revision it ran for
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HookRun {
+    /// Hook name
+    pub name: String,
+
+    /// Phase it ran in
+    pub phase: HookPhase,
+
+    /// Whether it succeeded
+    pub success: bool,
+
+    /// Whether it failed by exceeding its timeout
+    #[serde(default)]
+    pub timed_out: bool,
+
+    /// Time from the start of its first attempt to the end of its last one
+    #[serde(with = "duration_serde")]
+    pub duration: Duration,
+}
+
+impl From<&HookResult> for HookRun {
+    fn from(result: &HookResult) -> Self {
+        Self {
+            name: result.name.clone(),
+            phase: result.phase,
+            success: result.success,
+            timed_out: result.timed_out,
+            duration: result.duration(),
+        }
+    }
+}
+
 /// Hook executor for running hooks against a Kubernetes cluster
 pub struct HookExecutor {
     /// Results of executed hooks
     pub results: Vec<HookResult>,
     /// Namespace to execute hooks in
     namespace: String,
+    /// Timeout for hooks without a `sherpack.io/hook-timeout` annotation
+    default_timeout: Duration,
+    /// Skip cleanup after hooks finish, regardless of their policy
+    keep_resources: bool,
+    /// Collect container logs of hook Pods and Jobs into their results
+    capture_logs: bool,
+    /// Report container logs of hook Pods and Jobs while they run
+    stream_logs: bool,
+    /// Receives streamed log lines and the logs of failed hooks
+    progress: Arc<dyn ProgressSink>,
 }
 
 impl HookExecutor {
@@ -301,6 +403,11 @@ impl HookExecutor {
         Self {
             results: Vec::new(),
             namespace: "default".to_string(),
+            default_timeout: default_hook_timeout(),
+            keep_resources: false,
+            capture_logs: false,
+            stream_logs: false,
+            progress: ProgressFormat::Terminal.sink(),
         }
     }
 
@@ -309,9 +416,53 @@ impl HookExecutor {
         Self {
             results: Vec::new(),
             namespace: namespace.to_string(),
+            default_timeout: default_hook_timeout(),
+            keep_resources: false,
+            capture_logs: false,
+            stream_logs: false,
+            progress: ProgressFormat::Terminal.sink(),
         }
     }
 
+    /// Set the timeout for hooks that don't declare their own
+    pub fn with_default_timeout(mut self, timeout: Duration) -> Self {
+        self.default_timeout = timeout;
+        self
+    }
+
+    /// Keep hook resources instead of applying their cleanup policy
+    pub fn with_keep_resources(mut self, keep: bool) -> Self {
+        self.keep_resources = keep;
+        self
+    }
+
+    /// Collect container logs before hook resources are cleaned up
+    pub fn with_logs(mut self, capture: bool) -> Self {
+        self.capture_logs = capture;
+        self
+    }
+
+    /// Report container logs of hook Pods and Jobs as they run
+    ///
+    /// Each line is a log event naming its Pod and container. Without
+    /// streaming, the last lines of a failed hook are reported once it has
+    /// finished.
+    pub fn with_log_streaming(mut self, stream: bool) -> Self {
+        self.stream_logs = stream;
+        self
+    }
+
+    /// Report log lines through `progress` instead of the terminal
+    pub fn with_progress(mut self, progress: Arc<dyn ProgressSink>) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    /// Effective timeout for a hook
+    pub fn timeout_for(&self, hook: &Hook) -> Duration {
+        hook.timeout.unwrap_or(self.default_timeout)
+    }
+
     /// Execute all hooks for a given phase
     ///
     /// Returns Ok(()) if all hooks succeeded or were configured to continue on failure.
@@ -324,33 +475,40 @@ impl HookExecutor {
         revision: u32,
         client: &kube::Client,
     ) -> crate::Result<()> {
-        // Filter and sort hooks for this phase
-        let mut phase_hooks: Vec<&Hook> = hooks.iter().filter(|h| h.runs_in_phase(phase)).collect();
-
-        phase_hooks.sort_by_key(|h| h.weight);
-
-        for hook in phase_hooks {
+        for hook in hooks_for_phase(hooks, phase) {
             let started_at = Utc::now();
             let unique_name = hook.unique_name(release_name, phase, revision);
 
             // Execute the hook
             let result = self
-                .execute_single_hook(client, hook, &unique_name, phase, started_at)
+                .run_attempt(client, hook, &unique_name, phase, started_at)
+                .await;
+            let logs = self
+                .finish_attempt(client, hook, &unique_name, result.is_ok())
                 .await;
 
             match result {
-                Ok(r) => self.results.push(r),
+                Ok(r) => self.results.push(r.with_logs(logs)),
                 Err(e) => {
+                    let timed_out = matches!(e, crate::KubeError::HookTimeout { .. });
                     let error_msg = e.to_string();
                     match hook.on_failure {
                         HookFailurePolicy::FailOperation => {
-                            self.results.push(HookResult::failure(
-                                hook.name.clone(),
-                                phase,
-                                error_msg.clone(),
-                                started_at,
-                                1,
-                            ));
+                            self.results.push(
+                                HookResult::failure(
+                                    hook.name.clone(),
+                                    phase,
+                                    error_msg.clone(),
+                                    started_at,
+                                    1,
+                                )
+                                .with_timed_out(timed_out)
+                                .with_logs(logs.clone()),
+                            );
+                            // Keep timeouts distinct so the release records which hook hung
+                            if timed_out {
+                                return Err(e);
+                            }
                             return Err(crate::KubeError::HookFailed {
                                 hook_name: hook.name.clone(),
                                 phase: phase.to_string(),
@@ -358,23 +516,31 @@ impl HookExecutor {
                             });
                         }
                         HookFailurePolicy::Continue => {
-                            self.results.push(HookResult::failure(
-                                hook.name.clone(),
-                                phase,
-                                error_msg,
-                                started_at,
-                                1,
-                            ));
+                            self.results.push(
+                                HookResult::failure(
+                                    hook.name.clone(),
+                                    phase,
+                                    error_msg,
+                                    started_at,
+                                    1,
+                                )
+                                .with_timed_out(timed_out)
+                                .with_logs(logs.clone()),
+                            );
                             // Continue to next hook
                         }
                         HookFailurePolicy::Rollback => {
-                            self.results.push(HookResult::failure(
-                                hook.name.clone(),
-                                phase,
-                                error_msg.clone(),
-                                started_at,
-                                1,
-                            ));
+                            self.results.push(
+                                HookResult::failure(
+                                    hook.name.clone(),
+                                    phase,
+                                    error_msg.clone(),
+                                    started_at,
+                                    1,
+                                )
+                                .with_timed_out(timed_out)
+                                .with_logs(logs.clone()),
+                            );
                             return Err(crate::KubeError::HookFailed {
                                 hook_name: hook.name.clone(),
                                 phase: phase.to_string(),
@@ -386,51 +552,61 @@ impl HookExecutor {
                             backoff,
                         } => {
                             let mut attempts = 1;
-                            #[allow(unused_assignments)]
-                            let mut last_error = error_msg; // Initial error that triggered retry
-
-                            while attempts < max_attempts {
+                            // Error of the latest attempt, starting with the
+                            // one that triggered the retries
+                            let mut last_error = e;
+                            let mut last_logs = logs;
+
+                            let succeeded = loop {
+                                if attempts >= max_attempts {
+                                    break None;
+                                }
                                 tokio::time::sleep(backoff.to_std().unwrap_or_default()).await;
                                 attempts += 1;
 
-                                match self
-                                    .execute_single_hook(
-                                        client,
-                                        hook,
-                                        &unique_name,
+                                let result = self
+                                    .run_attempt(client, hook, &unique_name, phase, started_at)
+                                    .await;
+                                let logs = self
+                                    .finish_attempt(client, hook, &unique_name, result.is_ok())
+                                    .await;
+                                if logs.is_some() {
+                                    last_logs = logs;
+                                }
+
+                                match result {
+                                    Ok(r) => break Some(r),
+                                    Err(e) => last_error = e,
+                                }
+                            };
+
+                            let Some(r) = succeeded else {
+                                self.results.push(
+                                    HookResult::failure(
+                                        hook.name.clone(),
                                         phase,
+                                        last_error.to_string(),
                                         started_at,
+                                        attempts,
                                     )
-                                    .await
-                                {
-                                    Ok(r) => {
-                                        let mut success_result = r;
-                                        success_result.attempts = attempts;
-                                        self.results.push(success_result);
-                                        break;
-                                    }
-                                    Err(e) => {
-                                        last_error = e.to_string();
-                                        if attempts >= max_attempts {
-                                            self.results.push(HookResult::failure(
-                                                hook.name.clone(),
-                                                phase,
-                                                last_error.clone(),
-                                                started_at,
-                                                attempts,
-                                            ));
-                                            return Err(crate::KubeError::HookFailed {
-                                                hook_name: hook.name.clone(),
-                                                phase: phase.to_string(),
-                                                message: format!(
-                                                    "{} (after {} attempts)",
-                                                    last_error, attempts
-                                                ),
-                                            });
-                                        }
-                                    }
-                                }
-                            }
+                                    .with_timed_out(matches!(
+                                        last_error,
+                                        crate::KubeError::HookTimeout { .. }
+                                    ))
+                                    .with_logs(last_logs),
+                                );
+                                return Err(crate::KubeError::HookFailed {
+                                    hook_name: hook.name.clone(),
+                                    phase: phase.to_string(),
+                                    message: format!(
+                                        "{} (after {} attempts)",
+                                        last_error, attempts
+                                    ),
+                                });
+                            };
+                            let mut success_result = r.with_logs(last_logs);
+                            success_result.attempts = attempts;
+                            self.results.push(success_result);
                         }
                     }
                 }
@@ -440,6 +616,36 @@ impl HookExecutor {
         Ok(())
     }
 
+    /// Execute a hook once, tailing its logs when streaming is enabled
+    async fn run_attempt(
+        &self,
+        client: &kube::Client,
+        hook: &Hook,
+        unique_name: &str,
+        phase: HookPhase,
+        started_at: DateTime<Utc>,
+    ) -> crate::Result<HookResult> {
+        let kind = resource_type(&hook.resource).kind;
+        let tail = (self.stream_logs && matches!(kind.as_str(), "Job" | "Pod")).then(|| {
+            LogTail::start(
+                client,
+                &self.namespace,
+                &kind,
+                unique_name,
+                self.progress.clone(),
+            )
+        });
+
+        let result = self
+            .execute_single_hook(client, hook, unique_name, phase, started_at)
+            .await;
+
+        if let Some(tail) = tail {
+            tail.finish().await;
+        }
+        result
+    }
+
     /// Execute a single hook by creating a Kubernetes Job
     async fn execute_single_hook(
         &self,
@@ -450,7 +656,7 @@ impl HookExecutor {
         started_at: DateTime<Utc>,
     ) -> crate::Result<HookResult> {
         use k8s_openapi::api::batch::v1::Job;
-        use kube::api::{Api, PostParams};
+        use kube::api::{Patch, PatchParams, PostParams};
         use kube::runtime::wait::{await_condition, conditions};
 
         // Parse the hook resource as YAML
@@ -489,17 +695,15 @@ impl HookExecutor {
             }
         }
 
-        let kind = resource
-            .get("kind")
-            .and_then(|v| v.as_str())
-            .unwrap_or("Unknown");
+        let type_meta = resource_type(&hook.resource);
+        let kind = type_meta.kind.as_str();
 
         // Handle cleanup policy: BeforeNextRun - delete existing hook if present
         if matches!(
             hook.cleanup,
             HookCleanupPolicy::BeforeNextRun | HookCleanupPolicy::Always
         ) {
-            self.cleanup_existing_hook(client, kind, unique_name)
+            self.cleanup_existing_hook(client, &type_meta, unique_name)
                 .await?;
         }
 
@@ -521,13 +725,13 @@ impl HookExecutor {
             let job_name = created_job.metadata.name.as_deref().unwrap_or(unique_name);
 
             // Wait for job completion with timeout
-            let timeout = hook
-                .timeout
+            let timeout = self.timeout_for(hook);
+            let wait_timeout = timeout
                 .to_std()
                 .unwrap_or(std::time::Duration::from_secs(300));
             let condition = await_condition(jobs.clone(), job_name, conditions::is_job_completed());
 
-            match tokio::time::timeout(timeout, condition).await {
+            match tokio::time::timeout(wait_timeout, condition).await {
                 Ok(Ok(Some(completed_job))) => {
                     // Check if job succeeded
                     let status = completed_job.status.as_ref();
@@ -535,25 +739,10 @@ impl HookExecutor {
                     let failed = status.and_then(|s| s.failed).unwrap_or(0);
 
                     if succeeded > 0 {
-                        // Handle cleanup on success
-                        if matches!(
-                            hook.cleanup,
-                            HookCleanupPolicy::OnSuccess | HookCleanupPolicy::Always
-                        ) {
-                            let _ = self.cleanup_hook(client, "Job", unique_name).await;
-                        }
                         Ok(HookResult::success(hook.name.clone(), phase, started_at))
                     } else {
                         let error_msg = format!("Job failed with {} failures", failed);
 
-                        // Handle cleanup on failure
-                        if matches!(
-                            hook.cleanup,
-                            HookCleanupPolicy::OnFailure | HookCleanupPolicy::Always
-                        ) {
-                            let _ = self.cleanup_hook(client, "Job", unique_name).await;
-                        }
-
                         Err(crate::KubeError::HookFailed {
                             hook_name: hook.name.clone(),
                             phase: phase.to_string(),
@@ -574,131 +763,197 @@ impl HookExecutor {
                     phase: phase.to_string(),
                     message: format!("Wait condition failed: {}", e),
                 }),
-                Err(_) => {
-                    // Timeout
-                    Err(crate::KubeError::HookFailed {
-                        hook_name: hook.name.clone(),
-                        phase: phase.to_string(),
-                        message: format!(
-                            "Hook timed out after {:?}",
-                            hook.timeout.to_std().unwrap_or_default()
-                        ),
-                    })
-                }
+                Err(_) => Err(crate::KubeError::HookTimeout {
+                    hook_name: hook.name.clone(),
+                    phase: phase.to_string(),
+                    seconds: timeout.num_seconds(),
+                }),
             }
         } else {
-            // For non-Job resources (ConfigMaps, Secrets, etc.), just create them
-            // Use dynamic client for generic resources
-            let yaml_string = serde_yaml::to_string(&resource).map_err(|e| {
-                crate::KubeError::InvalidManifest(format!("Failed to serialize resource: {}", e))
+            // For non-Job resources (ConfigMaps, Secrets, Pods, etc.), server-side
+            // apply them through the same client as the release resources
+            let obj: DynamicObject = serde_yaml::from_value(resource).map_err(|e| {
+                crate::KubeError::InvalidManifest(format!("Failed to parse {}: {}", kind, e))
             })?;
-
-            // Use kubectl apply as fallback for generic resources
-            let output = tokio::process::Command::new("kubectl")
-                .args(["apply", "-f", "-", "-n", &self.namespace, "-o", "name"])
-                .stdin(std::process::Stdio::piped())
-                .stdout(std::process::Stdio::piped())
-                .stderr(std::process::Stdio::piped())
-                .spawn()
+            let api = self.dynamic_api(client, &type_meta).await?;
+            let params = PatchParams::apply(crate::resources::FIELD_MANAGER).force();
+            api.patch(unique_name, &params, &Patch::Apply(&obj))
+                .await
                 .map_err(|e| crate::KubeError::HookFailed {
                     hook_name: hook.name.clone(),
                     phase: phase.to_string(),
-                    message: format!("Failed to spawn kubectl: {}", e),
+                    message: format!("Failed to apply {}: {}", kind, e),
                 })?;
 
-            use tokio::io::AsyncWriteExt;
-            let mut child = output;
-            if let Some(stdin) = child.stdin.as_mut() {
-                stdin.write_all(yaml_string.as_bytes()).await.map_err(|e| {
-                    crate::KubeError::HookFailed {
-                        hook_name: hook.name.clone(),
-                        phase: phase.to_string(),
-                        message: format!("Failed to write to kubectl stdin: {}", e),
-                    }
-                })?;
+            // Pods (typically test hooks) run to completion like Jobs
+            if kind == "Pod" {
+                self.wait_for_pod(client, hook, unique_name, phase).await?;
             }
 
-            let output =
-                child
-                    .wait_with_output()
-                    .await
-                    .map_err(|e| crate::KubeError::HookFailed {
-                        hook_name: hook.name.clone(),
-                        phase: phase.to_string(),
-                        message: format!("Failed to wait for kubectl: {}", e),
-                    })?;
-
-            if output.status.success() {
-                Ok(HookResult::success(hook.name.clone(), phase, started_at))
-            } else {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                Err(crate::KubeError::HookFailed {
-                    hook_name: hook.name.clone(),
-                    phase: phase.to_string(),
-                    message: format!("kubectl apply failed: {}", stderr),
-                })
+            Ok(HookResult::success(hook.name.clone(), phase, started_at))
+        }
+    }
+
+    /// Wait for a hook Pod to succeed, within the hook's timeout
+    async fn wait_for_pod(
+        &self,
+        client: &kube::Client,
+        hook: &Hook,
+        unique_name: &str,
+        phase: HookPhase,
+    ) -> crate::Result<()> {
+        let timeout = self.timeout_for(hook);
+        let checker = HealthChecker::new(HealthCheckConfig {
+            timeout,
+            ..Default::default()
+        });
+
+        let (health, failed) = checker
+            .wait_for_completion(client, &self.namespace, "Pod", unique_name)
+            .await?;
+
+        if health.healthy {
+            Ok(())
+        } else if failed {
+            Err(crate::KubeError::HookFailed {
+                hook_name: hook.name.clone(),
+                phase: phase.to_string(),
+                message: health.message.unwrap_or_else(|| "Pod failed".to_string()),
+            })
+        } else {
+            Err(crate::KubeError::HookTimeout {
+                hook_name: hook.name.clone(),
+                phase: phase.to_string(),
+                seconds: timeout.num_seconds(),
+            })
+        }
+    }
+
+    /// Collect logs and apply the cleanup policy once an attempt has finished
+    ///
+    /// Timeouts count as failures. Logs are read first, since cleanup may
+    /// delete the Pods they come from.
+    async fn finish_attempt(
+        &self,
+        client: &kube::Client,
+        hook: &Hook,
+        unique_name: &str,
+        succeeded: bool,
+    ) -> Option<String> {
+        let type_meta = resource_type(&hook.resource);
+        let kind = type_meta.kind.as_str();
+
+        let logs = if self.capture_logs {
+            self.hook_logs(client, kind, unique_name, None).await
+        } else if !succeeded {
+            let logs = self
+                .hook_logs(client, kind, unique_name, Some(FAILURE_LOG_LINES))
+                .await;
+            if let Some(logs) = logs.as_ref().filter(|_| !self.stream_logs) {
+                self.progress.emit(&ProgressEvent::warning(
+                    kind,
+                    unique_name,
+                    format!("last log lines of failed hook {}\n{}", hook.name, logs),
+                ));
+            }
+            logs
+        } else {
+            None
+        };
+
+        if !self.keep_resources && hook.cleanup.cleans_up_after(succeeded) {
+            let _ = self.cleanup_hook(client, &type_meta, unique_name).await;
+        }
+
+        logs
+    }
+
+    /// Container logs of a hook Pod, or of the Pods created by a hook Job
+    ///
+    /// With `tail`, only the last `tail` lines of each container are read.
+    async fn hook_logs(
+        &self,
+        client: &kube::Client,
+        kind: &str,
+        name: &str,
+        tail: Option<i64>,
+    ) -> Option<String> {
+        let pods: Api<Pod> = Api::namespaced(client.clone(), &self.namespace);
+        let targets = hook_pods(&pods, kind, name).await?;
+
+        let mut output = String::new();
+        for pod in &targets {
+            let pod_name = pod.metadata.name.as_deref().unwrap_or(name);
+            for container in pod.spec.iter().flat_map(|spec| &spec.containers) {
+                let params = LogParams {
+                    container: Some(container.name.clone()),
+                    tail_lines: tail,
+                    ..Default::default()
+                };
+                if let Ok(logs) = pods.logs(pod_name, &params).await {
+                    output.push_str(&format!(
+                        "==> {}/{} <==\n{}",
+                        pod_name, container.name, logs
+                    ));
+                    if !logs.ends_with('\n') {
+                        output.push('\n');
+                    }
+                }
             }
         }
+
+        (!output.is_empty()).then_some(output)
     }
 
     /// Clean up existing hook resource before creating new one
     async fn cleanup_existing_hook(
         &self,
         client: &kube::Client,
-        kind: &str,
+        type_meta: &TypeMeta,
         name: &str,
     ) -> crate::Result<()> {
-        self.cleanup_hook(client, kind, name).await
+        self.cleanup_hook(client, type_meta, name).await
     }
 
     /// Clean up a hook resource
     async fn cleanup_hook(
         &self,
         client: &kube::Client,
-        kind: &str,
+        type_meta: &TypeMeta,
         name: &str,
     ) -> crate::Result<()> {
-        use k8s_openapi::api::batch::v1::Job;
-        use k8s_openapi::api::core::v1::{ConfigMap, Secret};
-        use kube::api::{Api, DeleteParams};
-
-        let dp = DeleteParams::default();
-
-        match kind {
-            "Job" => {
-                let api: Api<Job> = Api::namespaced(client.clone(), &self.namespace);
-                // Use propagation policy to delete pods too
-                let dp = DeleteParams {
-                    propagation_policy: Some(kube::api::PropagationPolicy::Background),
-                    ..Default::default()
-                };
-                let _ = api.delete(name, &dp).await;
-            }
-            "ConfigMap" => {
-                let api: Api<ConfigMap> = Api::namespaced(client.clone(), &self.namespace);
-                let _ = api.delete(name, &dp).await;
-            }
-            "Secret" => {
-                let api: Api<Secret> = Api::namespaced(client.clone(), &self.namespace);
-                let _ = api.delete(name, &dp).await;
-            }
-            _ => {
-                // Use kubectl for other resource types
-                let _ = tokio::process::Command::new("kubectl")
-                    .args([
-                        "delete",
-                        kind,
-                        name,
-                        "-n",
-                        &self.namespace,
-                        "--ignore-not-found",
-                    ])
-                    .output()
-                    .await;
-            }
+        use kube::api::DeleteParams;
+
+        let api = self.dynamic_api(client, type_meta).await?;
+        // Background propagation also deletes the Pods of a Job
+        match api.delete(name, &DeleteParams::background()).await {
+            Ok(_) => Ok(()),
+            Err(kube::Error::Api(ae)) if ae.code == 404 => Ok(()),
+            Err(e) => Err(crate::KubeError::KubeApi(e)),
         }
+    }
 
-        Ok(())
+    /// Api for a hook resource type, resolved through discovery
+    ///
+    /// Namespaced types live in the executor's namespace.
+    async fn dynamic_api(
+        &self,
+        client: &kube::Client,
+        type_meta: &TypeMeta,
+    ) -> crate::Result<Api<DynamicObject>> {
+        let gvk = GroupVersionKind::try_from(type_meta).map_err(|e| {
+            crate::KubeError::InvalidManifest(format!("Invalid hook apiVersion: {}", e))
+        })?;
+        let (api_resource, capabilities) = kube::discovery::pinned_kind(client, &gvk)
+            .await
+            .map_err(crate::KubeError::KubeApi)?;
+
+        Ok(match capabilities.scope {
+            Scope::Cluster => Api::all_with(client.clone(), &api_resource),
+            Scope::Namespaced => {
+                Api::namespaced_with(client.clone(), &self.namespace, &api_resource)
+            }
+        })
     }
 
     /// Get all results for a phase
@@ -723,6 +978,160 @@ impl Default for HookExecutor {
     }
 }
 
+/// Pods of a hook Pod or Job, or `None` for other kinds or on API errors
+async fn hook_pods(pods: &Api<Pod>, kind: &str, name: &str) -> Option<Vec<Pod>> {
+    match kind {
+        "Pod" => Some(vec![pods.get(name).await.ok()?]),
+        "Job" => {
+            let lp = ListParams::default().labels(&format!("job-name={}", name));
+            Some(pods.list(&lp).await.ok()?.items)
+        }
+        _ => None,
+    }
+}
+
+/// Background tail of the container logs of a running hook
+///
+/// Pods are polled until the hook finishes, and each container is followed
+/// from the moment it starts, so multi-container Pods and the new Pods of a
+/// retried Job are all streamed.
+struct LogTail {
+    stop: tokio::sync::watch::Sender<bool>,
+    task: tokio::task::JoinHandle<Vec<String>>,
+}
+
+impl LogTail {
+    /// Start tailing the Pods of hook `name`, reporting lines to `progress`
+    fn start(
+        client: &kube::Client,
+        namespace: &str,
+        kind: &str,
+        name: &str,
+        progress: Arc<dyn ProgressSink>,
+    ) -> Self {
+        let pods: Api<Pod> = Api::namespaced(client.clone(), namespace);
+        let (stop, stopped) = tokio::sync::watch::channel(false);
+        let task = tokio::spawn(tail_hook_logs(
+            pods,
+            kind.to_string(),
+            name.to_string(),
+            progress,
+            stopped,
+        ));
+        Self { stop, task }
+    }
+
+    /// Stop looking for new containers and let the open streams drain
+    ///
+    /// Returns the `pod/container` streams that were followed.
+    async fn finish(self) -> Vec<String> {
+        let _ = self.stop.send(true);
+        let abort = self.task.abort_handle();
+        match tokio::time::timeout(LOG_DRAIN_TIMEOUT, self.task).await {
+            Ok(streamed) => streamed.unwrap_or_default(),
+            Err(_) => {
+                abort.abort();
+                Vec::new()
+            }
+        }
+    }
+}
+
+/// Follow every container of a hook's Pods until `stopped` fires
+async fn tail_hook_logs(
+    pods: Api<Pod>,
+    kind: String,
+    name: String,
+    progress: Arc<dyn ProgressSink>,
+    mut stopped: tokio::sync::watch::Receiver<bool>,
+) -> Vec<String> {
+    let mut streamed = HashSet::new();
+    let mut streams = tokio::task::JoinSet::new();
+
+    loop {
+        // One last pass after the hook finished catches Pods that ran quickly
+        let stopping = *stopped.borrow();
+
+        for pod in hook_pods(&pods, &kind, &name).await.unwrap_or_default() {
+            let Some(pod_name) = pod.metadata.name.clone() else {
+                continue;
+            };
+            let containers: Vec<String> = pod
+                .spec
+                .iter()
+                .flat_map(|spec| {
+                    spec.init_containers
+                        .iter()
+                        .flatten()
+                        .chain(&spec.containers)
+                })
+                .map(|container| container.name.clone())
+                .collect();
+            for container in containers {
+                let source = format!("{}/{}", pod_name, container);
+                if streamed.contains(&source) {
+                    continue;
+                }
+                let params = LogParams {
+                    container: Some(container.clone()),
+                    follow: true,
+                    ..Default::default()
+                };
+                // Fails until the container has started; retried on the next poll
+                if let Ok(stream) = pods.log_stream(&pod_name, &params).await {
+                    streams.spawn(emit_log_lines(
+                        stream,
+                        pod_name.clone(),
+                        container,
+                        progress.clone(),
+                    ));
+                    streamed.insert(source);
+                }
+            }
+        }
+
+        if stopping {
+            break;
+        }
+        tokio::select! {
+            _ = stopped.changed() => {}
+            _ = tokio::time::sleep(LOG_POLL_INTERVAL) => {}
+        }
+    }
+
+    while streams.join_next().await.is_some() {}
+
+    let mut streamed: Vec<String> = streamed.into_iter().collect();
+    streamed.sort();
+    streamed
+}
+
+/// Report each line of a container's log stream as a log event
+async fn emit_log_lines(
+    stream: impl futures::AsyncBufRead,
+    pod: String,
+    container: String,
+    progress: Arc<dyn ProgressSink>,
+) {
+    use futures::{AsyncBufReadExt, StreamExt};
+
+    let mut lines = std::pin::pin!(stream.lines());
+    while let Some(Ok(line)) = lines.next().await {
+        progress.emit(&ProgressEvent::log(&pod, &container, line));
+    }
+}
+
+/// API version and kind of a hook resource; the kind is "Unknown" if it can't be read
+fn resource_type(resource: &str) -> TypeMeta {
+    let resource = serde_yaml::from_str::<serde_yaml::Value>(resource).unwrap_or_default();
+    let field = |key: &str| resource.get(key).and_then(|v| v.as_str()).map(String::from);
+
+    TypeMeta {
+        api_version: field("apiVersion").unwrap_or_default(),
+        kind: field("kind").unwrap_or_else(|| "Unknown".to_string()),
+    }
+}
+
 /// Parse hooks from template annotations
 pub fn parse_hooks_from_manifest(manifest: &str) -> Vec<Hook> {
     let mut hooks = Vec::new();
@@ -770,8 +1179,7 @@ pub fn parse_hooks_from_manifest(manifest: &str) -> Vec<Hook> {
                         annotations
                             .get(serde_yaml::Value::String("helm.sh/hook-weight".to_string()))
                     })
-                    .and_then(|v| v.as_str())
-                    .and_then(|s| s.parse().ok())
+                    .and_then(parse_hook_weight)
                     .unwrap_or(0);
 
                 let cleanup = annotations
@@ -787,13 +1195,21 @@ pub fn parse_hooks_from_manifest(manifest: &str) -> Vec<Hook> {
                     .map(parse_cleanup_policy)
                     .unwrap_or_default();
 
+                let timeout = annotations
+                    .get(serde_yaml::Value::String(
+                        crate::annotations::sherpack::HOOK_TIMEOUT.to_string(),
+                    ))
+                    .and_then(|v| v.as_str())
+                    .and_then(crate::annotations::parse_duration)
+                    .and_then(|d| Duration::from_std(d).ok());
+
                 hooks.push(Hook {
                     name,
                     phases,
                     resource: doc.to_string(),
                     weight,
                     on_failure: HookFailurePolicy::default(),
-                    timeout: default_hook_timeout(),
+                    timeout,
                     cleanup,
                 });
             }
@@ -803,6 +1219,27 @@ pub fn parse_hooks_from_manifest(manifest: &str) -> Vec<Hook> {
     hooks
 }
 
+/// Hooks that run in `phase`, in execution order
+///
+/// Hooks are sorted ascending by weight; hooks with the same weight keep
+/// their manifest order.
+pub fn hooks_for_phase(hooks: &[Hook], phase: HookPhase) -> Vec<&Hook> {
+    let mut phase_hooks: Vec<&Hook> = hooks.iter().filter(|h| h.runs_in_phase(phase)).collect();
+
+    // Stable sort: ties fall back to manifest order
+    phase_hooks.sort_by_key(|h| h.weight);
+    phase_hooks
+}
+
+/// Parse a hook weight, quoted (`"-5"`, as Kubernetes requires) or not
+fn parse_hook_weight(value: &serde_yaml::Value) -> Option<i32> {
+    match value {
+        serde_yaml::Value::String(s) => s.trim().parse().ok(),
+        serde_yaml::Value::Number(n) => n.as_i64().and_then(|n| i32::try_from(n).ok()),
+        _ => None,
+    }
+}
+
 /// Parse comma-separated hook phases
 fn parse_hook_phases(s: &str) -> Vec<HookPhase> {
     s.split(',')
@@ -854,6 +1291,26 @@ mod duration_serde {
     }
 }
 
+mod option_duration_serde {
+    use chrono::Duration;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S>(duration: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        duration.map(|d| d.num_seconds()).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let seconds = Option::<i64>::deserialize(deserializer)?;
+        Ok(seconds.map(Duration::seconds))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -865,7 +1322,7 @@ mod tests {
             resource: "apiVersion: batch/v1\nkind: Job".to_string(),
             weight: 0,
             on_failure: HookFailurePolicy::default(),
-            timeout: default_hook_timeout(),
+            timeout: None,
             cleanup: HookCleanupPolicy::default(),
         }
     }
@@ -991,6 +1448,163 @@ spec:
         assert_eq!(hooks[1].name, "post-hook");
     }
 
+    #[test]
+    fn test_parse_hook_timeout_annotation() {
+        let manifest = r#"
+---
+apiVersion: batch/v1
+kind: Job
+metadata:
+  name: slow-migration
+  annotations:
+    sherpack.io/hook: pre-upgrade
+    sherpack.io/hook-timeout: "120s"
+---
+apiVersion: batch/v1
+kind: Job
+metadata:
+  name: default-timeout
+  annotations:
+    sherpack.io/hook: pre-upgrade
+"#;
+
+        let hooks = parse_hooks_from_manifest(manifest);
+        assert_eq!(hooks[0].timeout, Some(Duration::seconds(120)));
+        assert_eq!(hooks[1].timeout, None);
+
+        let executor = HookExecutor::new().with_default_timeout(Duration::seconds(30));
+        assert_eq!(executor.timeout_for(&hooks[0]), Duration::seconds(120));
+        assert_eq!(executor.timeout_for(&hooks[1]), Duration::seconds(30));
+
+        // Without an explicit default, hooks fall back to 5 minutes
+        assert_eq!(
+            HookExecutor::new().timeout_for(&hooks[1]),
+            Duration::minutes(5)
+        );
+    }
+
+    #[test]
+    fn test_cleanup_policy_after_timeout() {
+        // Timed-out hooks are cleaned like failed ones
+        assert!(HookCleanupPolicy::Always.cleans_up_after(false));
+        assert!(HookCleanupPolicy::OnFailure.cleans_up_after(false));
+        assert!(!HookCleanupPolicy::OnSuccess.cleans_up_after(false));
+        assert!(!HookCleanupPolicy::Never.cleans_up_after(false));
+        assert!(!HookCleanupPolicy::BeforeNextRun.cleans_up_after(false));
+
+        assert!(HookCleanupPolicy::OnSuccess.cleans_up_after(true));
+        assert!(!HookCleanupPolicy::OnFailure.cleans_up_after(true));
+    }
+
+    #[test]
+    fn test_hook_timeout_error_and_result() {
+        let err = crate::KubeError::HookTimeout {
+            hook_name: "slow-migration".to_string(),
+            phase: HookPhase::PreUpgrade.to_string(),
+            seconds: 120,
+        };
+        assert_eq!(
+            err.to_string(),
+            "hook 'slow-migration' timed out during pre-upgrade after 120s"
+        );
+
+        let result = HookResult::failure(
+            "slow-migration".to_string(),
+            HookPhase::PreUpgrade,
+            err.to_string(),
+            Utc::now(),
+            1,
+        )
+        .with_timed_out(true);
+
+        let json = serde_json::to_string(&result).unwrap();
+        let deserialized: HookResult = serde_json::from_str(&json).unwrap();
+        assert!(deserialized.timed_out);
+        assert!(!deserialized.success);
+    }
+
+    #[test]
+    fn test_hooks_for_phase_sorted_by_weight() {
+        let manifest = r#"
+---
+apiVersion: batch/v1
+kind: Job
+metadata:
+  name: uses-secret
+  annotations:
+    sherpack.io/hook: pre-install
+    sherpack.io/hook-weight: "0"
+---
+apiVersion: v1
+kind: Secret
+metadata:
+  name: job-secret
+  annotations:
+    helm.sh/hook: pre-install
+    helm.sh/hook-weight: "-5"
+---
+apiVersion: batch/v1
+kind: Job
+metadata:
+  name: post-job
+  annotations:
+    sherpack.io/hook: post-install
+    sherpack.io/hook-weight: "-10"
+"#;
+
+        let hooks = parse_hooks_from_manifest(manifest);
+        let order: Vec<&str> = hooks_for_phase(&hooks, HookPhase::PreInstall)
+            .iter()
+            .map(|h| h.name.as_str())
+            .collect();
+
+        assert_eq!(order, vec!["job-secret", "uses-secret"]);
+    }
+
+    #[test]
+    fn test_hooks_for_phase_ties_keep_manifest_order() {
+        let manifest = r#"
+---
+apiVersion: v1
+kind: ConfigMap
+metadata:
+  name: first
+  annotations:
+    sherpack.io/hook: pre-upgrade
+---
+apiVersion: v1
+kind: ConfigMap
+metadata:
+  name: second
+  annotations:
+    sherpack.io/hook: pre-upgrade
+    sherpack.io/hook-weight: "0"
+---
+apiVersion: v1
+kind: ConfigMap
+metadata:
+  name: early
+  annotations:
+    sherpack.io/hook: pre-upgrade
+    sherpack.io/hook-weight: -1
+---
+apiVersion: v1
+kind: ConfigMap
+metadata:
+  name: third
+  annotations:
+    sherpack.io/hook: pre-upgrade
+"#;
+
+        let hooks = parse_hooks_from_manifest(manifest);
+        let order: Vec<&str> = hooks_for_phase(&hooks, HookPhase::PreUpgrade)
+            .iter()
+            .map(|h| h.name.as_str())
+            .collect();
+
+        assert_eq!(order, vec!["early", "first", "second", "third"]);
+    }
+
     #[test]
     fn test_helm_compatibility() {
         let manifest = r#"
@@ -1094,6 +1708,32 @@ spec:
         assert_eq!(result.attempts, 3);
     }
 
+    #[test]
+    fn test_hook_run_from_result() {
+        let started = Utc::now() - Duration::seconds(90);
+        let result = HookResult::failure(
+            "migrate-db".to_string(),
+            HookPhase::PreUpgrade,
+            "hook timed out".to_string(),
+            started,
+            1,
+        )
+        .with_timed_out(true);
+
+        let run = HookRun::from(&result);
+        assert_eq!(run.name, "migrate-db");
+        assert_eq!(run.phase, HookPhase::PreUpgrade);
+        assert!(!run.success);
+        assert!(run.timed_out);
+        assert_eq!(run.duration.num_seconds(), 90);
+
+        let json = serde_json::to_value(&run).unwrap();
+        assert_eq!(json["timedOut"], true);
+        assert_eq!(json["duration"], 90);
+        let stored: HookRun = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&stored).unwrap(), json);
+    }
+
     #[test]
     fn test_hook_executor_new() {
         let executor = HookExecutor::new();
@@ -1187,7 +1827,7 @@ spec:
                 max_attempts: 3,
                 backoff: Duration::seconds(10),
             },
-            timeout: Duration::minutes(2),
+            timeout: Some(Duration::minutes(2)),
             cleanup: HookCleanupPolicy::KeepLast(3),
         };
 
@@ -1242,4 +1882,415 @@ data:
             assert!(executor.results.is_empty());
         }
     }
+
+    /// Serve a `hook-pod` with an init container and two containers, and
+    /// their logs; returns the query of each log request
+    fn spawn_log_server(
+        mut handle: tower_test::mock::Handle<
+            http::Request<kube::client::Body>,
+            http::Response<kube::client::Body>,
+        >,
+    ) -> tokio::task::JoinHandle<Vec<String>> {
+        tokio::spawn(async move {
+            let mut log_queries = Vec::new();
+
+            while let Some((request, send)) = handle.next_request().await {
+                let path = request.uri().path().to_string();
+                let query = request.uri().query().unwrap_or_default().to_string();
+
+                let body = if path.ends_with("/pods/hook-pod/log") {
+                    log_queries.push(query.clone());
+                    let container = query
+                        .split('&')
+                        .find_map(|p| p.strip_prefix("container="))
+                        .unwrap_or_default();
+                    format!("hello from {}\n", container).into_bytes()
+                } else {
+                    serde_json::to_vec(&serde_json::json!({
+                        "apiVersion": "v1",
+                        "kind": "Pod",
+                        "metadata": {"name": "hook-pod", "namespace": "default"},
+                        "spec": {
+                            "initContainers": [{"name": "setup"}],
+                            "containers": [{"name": "main"}, {"name": "sidecar"}]
+                        }
+                    }))
+                    .unwrap()
+                };
+
+                send.send_response(
+                    http::Response::builder()
+                        .body(kube::client::Body::from(body))
+                        .unwrap(),
+                );
+            }
+
+            log_queries
+        })
+    }
+
+    /// Sink keeping every event it receives
+    #[derive(Debug, Default)]
+    struct EventLog(std::sync::Mutex<Vec<ProgressEvent>>);
+
+    impl ProgressSink for EventLog {
+        fn emit(&self, event: &ProgressEvent) {
+            self.0.lock().unwrap().push(event.clone());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_log_tail_follows_every_container() {
+        let (service, handle) = tower_test::mock::pair::<
+            http::Request<kube::client::Body>,
+            http::Response<kube::client::Body>,
+        >();
+        let server = spawn_log_server(handle);
+        let client = kube::Client::new(service, "default");
+
+        let events = Arc::new(EventLog::default());
+        let tail = LogTail::start(&client, "default", "Pod", "hook-pod", events.clone());
+        let streamed = tail.finish().await;
+        drop(client);
+        let queries = server.await.unwrap();
+
+        assert_eq!(
+            streamed,
+            ["hook-pod/main", "hook-pod/setup", "hook-pod/sidecar"]
+        );
+        let mut lines: Vec<String> = events
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|e| {
+                assert_eq!(e.event_type, crate::progress::EventType::Log);
+                format!(
+                    "{}/{}: {}",
+                    e.name,
+                    e.container.as_deref().unwrap_or_default(),
+                    e.message.as_deref().unwrap_or_default()
+                )
+            })
+            .collect();
+        lines.sort();
+        assert_eq!(
+            lines,
+            [
+                "hook-pod/main: hello from main",
+                "hook-pod/setup: hello from setup",
+                "hook-pod/sidecar: hello from sidecar",
+            ]
+        );
+        assert_eq!(queries.len(), 3);
+        assert!(
+            queries.iter().all(|q| q.contains("follow=true")),
+            "{queries:?}"
+        );
+    }
+
+    /// Response of the API server to a hook ConfigMap call: discovery, a
+    /// delete of a missing object or an apply
+    fn resource_response(method: &str, path: &str) -> (u16, serde_json::Value) {
+        match (method, path) {
+            ("DELETE", _) => (
+                404,
+                serde_json::json!({
+                    "apiVersion": "v1",
+                    "kind": "Status",
+                    "metadata": {},
+                    "status": "Failure",
+                    "reason": "NotFound",
+                    "code": 404
+                }),
+            ),
+            ("PATCH", _) => (
+                200,
+                serde_json::json!({
+                    "apiVersion": "v1",
+                    "kind": "ConfigMap",
+                    "metadata": {
+                        "name": path.rsplit('/').next().unwrap_or_default(),
+                        "namespace": "hooks"
+                    }
+                }),
+            ),
+            _ => (
+                200,
+                serde_json::json!({
+                    "kind": "APIResourceList",
+                    "groupVersion": "v1",
+                    "resources": [{
+                        "name": "configmaps",
+                        "singularName": "configmap",
+                        "namespaced": true,
+                        "kind": "ConfigMap",
+                        "verbs": ["get", "list", "create", "update", "patch", "delete"]
+                    }]
+                }),
+            ),
+        }
+    }
+
+    /// Serve core API discovery (ConfigMaps), applies and deletes, returning
+    /// `(method, path, content type)` of each call
+    fn spawn_resource_server(
+        mut handle: tower_test::mock::Handle<
+            http::Request<kube::client::Body>,
+            http::Response<kube::client::Body>,
+        >,
+    ) -> tokio::task::JoinHandle<Vec<(String, String, String)>> {
+        tokio::spawn(async move {
+            let mut calls = Vec::new();
+
+            while let Some((request, send)) = handle.next_request().await {
+                let path = request.uri().path().to_string();
+                let content_type = request
+                    .headers()
+                    .get(http::header::CONTENT_TYPE)
+                    .and_then(|v| v.to_str().ok())
+                    .unwrap_or_default()
+                    .to_string();
+                calls.push((request.method().to_string(), path.clone(), content_type));
+
+                let (status, body) = resource_response(request.method().as_str(), &path);
+
+                send.send_response(
+                    http::Response::builder()
+                        .status(status)
+                        .body(kube::client::Body::from(serde_json::to_vec(&body).unwrap()))
+                        .unwrap(),
+                );
+            }
+
+            calls
+        })
+    }
+
+    #[tokio::test]
+    async fn test_resource_hook_goes_through_client() {
+        let (service, handle) = tower_test::mock::pair::<
+            http::Request<kube::client::Body>,
+            http::Response<kube::client::Body>,
+        >();
+        let server = spawn_resource_server(handle);
+        let client = kube::Client::new(service, "default");
+
+        let hook = Hook {
+            resource:
+                "apiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: seed\ndata:\n  key: value\n"
+                    .to_string(),
+            cleanup: HookCleanupPolicy::Always,
+            ..test_hook("seed", vec![HookPhase::PreInstall])
+        };
+        let mut executor = HookExecutor::with_namespace("hooks");
+        executor
+            .execute_phase(&[hook], HookPhase::PreInstall, "web", 1, &client)
+            .await
+            .unwrap();
+        drop(client);
+        let calls = server.await.unwrap();
+
+        let path = "/api/v1/namespaces/hooks/configmaps/web-seed-pre-install-v1";
+        let calls: Vec<(&str, &str, &str)> = calls
+            .iter()
+            .filter(|(_, p, _)| p != "/api/v1")
+            .map(|(m, p, c)| (m.as_str(), p.as_str(), c.as_str()))
+            .collect();
+        assert_eq!(
+            calls,
+            [
+                ("DELETE", path, "application/json"),
+                ("PATCH", path, "application/apply-patch+yaml"),
+                ("DELETE", path, "application/json"),
+            ]
+        );
+        assert!(executor.results[0].success);
+    }
+
+    #[tokio::test]
+    async fn test_retried_hook_records_last_failure() {
+        for max_attempts in [1, 2] {
+            let (service, mut handle) = tower_test::mock::pair::<
+                http::Request<kube::client::Body>,
+                http::Response<kube::client::Body>,
+            >();
+            // Every apply is rejected
+            let server = tokio::spawn(async move {
+                let mut applies = 0;
+                while let Some((request, send)) = handle.next_request().await {
+                    let path = request.uri().path().to_string();
+                    let (status, body) = if request.method() == http::Method::PATCH {
+                        applies += 1;
+                        (
+                            500,
+                            serde_json::json!({
+                                "apiVersion": "v1",
+                                "kind": "Status",
+                                "metadata": {},
+                                "status": "Failure",
+                                "message": "etcd unavailable",
+                                "code": 500
+                            }),
+                        )
+                    } else {
+                        resource_response(request.method().as_str(), &path)
+                    };
+                    send.send_response(
+                        http::Response::builder()
+                            .status(status)
+                            .body(kube::client::Body::from(serde_json::to_vec(&body).unwrap()))
+                            .unwrap(),
+                    );
+                }
+                applies
+            });
+            let client = kube::Client::new(service, "default");
+
+            let hook = Hook {
+                resource: "apiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: seed\n".to_string(),
+                on_failure: HookFailurePolicy::Retry {
+                    max_attempts,
+                    backoff: Duration::zero(),
+                },
+                cleanup: HookCleanupPolicy::Never,
+                ..test_hook("seed", vec![HookPhase::PreInstall])
+            };
+            let mut executor = HookExecutor::new();
+            let err = executor
+                .execute_phase(&[hook], HookPhase::PreInstall, "web", 1, &client)
+                .await
+                .unwrap_err();
+            drop(client);
+
+            assert_eq!(server.await.unwrap(), max_attempts);
+            assert!(
+                err.to_string()
+                    .contains(&format!("after {} attempts", max_attempts)),
+                "{err}"
+            );
+            let [result] = executor.results.as_slice() else {
+                panic!("{:?}", executor.results);
+            };
+            assert!(!result.success);
+            assert!(!result.timed_out);
+            assert_eq!(result.attempts, max_attempts);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resource_hook_impersonates() {
+        use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt};
+
+        let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+
+        // A real connection, so that the headers set by the client
+        // configuration are on the wire
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let mut impersonated = Vec::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                let mut stream = tokio::io::BufReader::new(stream);
+                let mut request_line = String::new();
+                stream.read_line(&mut request_line).await.unwrap();
+                let mut content_length = 0;
+                let mut identity = Vec::new();
+                loop {
+                    let mut line = String::new();
+                    stream.read_line(&mut line).await.unwrap();
+                    let Some((name, value)) = line.trim_end().split_once(": ") else {
+                        break;
+                    };
+                    match name.to_ascii_lowercase().as_str() {
+                        "content-length" => content_length = value.parse().unwrap(),
+                        "impersonate-user" | "impersonate-group" => {
+                            identity.push(value.to_string())
+                        }
+                        _ => {}
+                    }
+                }
+                let mut body = vec![0; content_length];
+                stream.read_exact(&mut body).await.unwrap();
+
+                let mut parts = request_line.split_whitespace();
+                let (method, path) = (parts.next().unwrap(), parts.next().unwrap());
+                let path = path.split('?').next().unwrap().to_string();
+                let (status, body) = resource_response(method, &path);
+                let body = serde_json::to_vec(&body).unwrap();
+                let head = format!(
+                    "HTTP/1.1 {} X\r\nContent-Type: application/json\r\n\
+                     Content-Length: {}\r\nConnection: close\r\n\r\n",
+                    status,
+                    body.len()
+                );
+                let stream = stream.get_mut();
+                stream.write_all(head.as_bytes()).await.unwrap();
+                stream.write_all(&body).await.unwrap();
+
+                // The cleanup after the hook ran is the last request
+                let done = method == "DELETE"
+                    && impersonated
+                        .iter()
+                        .any(|(r, _): &(String, _)| r.starts_with("DELETE"));
+                impersonated.push((format!("{} {}", method, path), identity));
+                if done {
+                    break;
+                }
+            }
+            impersonated
+        });
+
+        let mut config = kube::Config::new(url.parse().unwrap());
+        crate::kubeconfig::impersonate(&mut config, Some("jane"), &["auditors".to_string()])
+            .unwrap();
+        let client = kube::Client::try_from(config).unwrap();
+
+        let hook = Hook {
+            resource: "apiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: seed\n".to_string(),
+            cleanup: HookCleanupPolicy::Always,
+            ..test_hook("seed", vec![HookPhase::PreInstall])
+        };
+        let mut executor = HookExecutor::with_namespace("hooks");
+        executor
+            .execute_phase(&[hook], HookPhase::PreInstall, "web", 1, &client)
+            .await
+            .unwrap();
+        let requests = server.await.unwrap();
+
+        let path = "/api/v1/namespaces/hooks/configmaps/web-seed-pre-install-v1";
+        assert!(
+            requests
+                .iter()
+                .any(|(r, _)| *r == format!("PATCH {}", path)),
+            "{requests:?}"
+        );
+        for (request, identity) in &requests {
+            assert_eq!(identity, &["jane", "auditors"], "{request}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_hook_logs_are_tailed() {
+        let (service, handle) = tower_test::mock::pair::<
+            http::Request<kube::client::Body>,
+            http::Response<kube::client::Body>,
+        >();
+        let server = spawn_log_server(handle);
+        let client = kube::Client::new(service, "default");
+
+        let logs = HookExecutor::new()
+            .hook_logs(&client, "Pod", "hook-pod", Some(FAILURE_LOG_LINES))
+            .await
+            .unwrap();
+        drop(client);
+        let queries = server.await.unwrap();
+
+        assert!(logs.contains("==> hook-pod/main <==\nhello from main\n"));
+        assert!(logs.contains("==> hook-pod/sidecar <==\nhello from sidecar\n"));
+        assert!(
+            queries.iter().all(|q| q.contains("tailLines=20")),
+            "{queries:?}"
+        );
+    }
 }
@@ -0,0 +1,306 @@
+//! Kubeconfig file and context selection
+//!
+//! By default the client is configured like `kubectl`: `$KUBECONFIG` or
+//! `~/.kube/config` with its current context, falling back to the in-cluster
+//! service account. An explicit kubeconfig file or context overrides this.
+//! Requests can also impersonate a user and groups (`--as`, `--as-group`).
+
+use std::path::Path;
+
+use http::{HeaderName, HeaderValue};
+use kube::config::{KubeConfigOptions, Kubeconfig};
+
+use crate::error::{KubeError, Result};
+
+/// Where Kubernetes mounts the service account of a Pod
+pub const SERVICE_ACCOUNT_DIR: &str = "/var/run/secrets/kubernetes.io/serviceaccount";
+
+/// Client configuration from a kubeconfig file and context
+///
+/// `path` defaults to `$KUBECONFIG` or `~/.kube/config`, `context` to the
+/// file's current context. Without either and without a kubeconfig, the
+/// in-cluster service account is used when running inside a Pod.
+pub async fn load_config(path: Option<&Path>, context: Option<&str>) -> Result<kube::Config> {
+    let kubeconfig = match read_kubeconfig(path) {
+        Ok(kubeconfig) => kubeconfig,
+        Err(e) if path.is_some() || context.is_some() => return Err(e),
+        // No kubeconfig: running in a Pod, use its service account
+        Err(e) => {
+            let (Ok(host), Ok(port)) = (
+                std::env::var("KUBERNETES_SERVICE_HOST"),
+                std::env::var("KUBERNETES_SERVICE_PORT"),
+            ) else {
+                return Err(e);
+            };
+            tracing::debug!("No kubeconfig found, using the in-cluster service account");
+            return in_cluster_config(Path::new(SERVICE_ACCOUNT_DIR), &host, &port).await;
+        }
+    };
+    let options = KubeConfigOptions {
+        context: context.map(String::from),
+        ..Default::default()
+    };
+
+    kube::Config::from_custom_kubeconfig(kubeconfig, &options)
+        .await
+        .map_err(|e| KubeError::InvalidConfig(kubeconfig_error(path, e)))
+}
+
+/// Client configuration for the service account mounted in `dir`
+///
+/// `dir` holds the `token`, `ca.crt` and `namespace` files, and the API
+/// server is reached at `host`:`port`. The token is re-read as it rotates.
+pub async fn in_cluster_config(dir: &Path, host: &str, port: &str) -> Result<kube::Config> {
+    let host = if host.contains(':') {
+        format!("[{}]", host)
+    } else {
+        host.to_string()
+    };
+    let mut context = serde_json::json!({"cluster": "in-cluster", "user": "service-account"});
+    if let Some(namespace) = read_namespace(dir) {
+        context["namespace"] = namespace.into();
+    }
+
+    let kubeconfig: Kubeconfig = serde_json::from_value(serde_json::json!({
+        "clusters": [{
+            "name": "in-cluster",
+            "cluster": {
+                "server": format!("https://{}:{}", host, port),
+                "certificate-authority": dir.join("ca.crt"),
+            }
+        }],
+        "users": [{
+            "name": "service-account",
+            "user": {"tokenFile": dir.join("token")}
+        }],
+        "contexts": [{"name": "in-cluster", "context": context}],
+        "current-context": "in-cluster"
+    }))?;
+
+    kube::Config::from_custom_kubeconfig(kubeconfig, &KubeConfigOptions::default())
+        .await
+        .map_err(|e| KubeError::InvalidConfig(format!("service account {}: {}", dir.display(), e)))
+}
+
+/// Impersonate `user` and `groups` on every request made with `config`
+pub fn impersonate(config: &mut kube::Config, user: Option<&str>, groups: &[String]) -> Result<()> {
+    if user.is_none() && groups.is_empty() {
+        return Ok(());
+    }
+
+    tracing::debug!(
+        "Impersonating user {} with groups [{}]",
+        user.unwrap_or("(none)"),
+        groups.join(", ")
+    );
+    config.headers.extend(impersonation_headers(user, groups)?);
+    Ok(())
+}
+
+/// `Impersonate-User` and `Impersonate-Group` headers, one per group
+pub fn impersonation_headers(
+    user: Option<&str>,
+    groups: &[String],
+) -> Result<Vec<(HeaderName, HeaderValue)>> {
+    let value = |v: &str| {
+        HeaderValue::from_str(v)
+            .map_err(|_| KubeError::InvalidConfig(format!("invalid impersonation value '{}'", v)))
+    };
+
+    let mut headers = Vec::with_capacity(groups.len() + 1);
+    if let Some(user) = user {
+        headers.push((HeaderName::from_static("impersonate-user"), value(user)?));
+    }
+    for group in groups {
+        headers.push((HeaderName::from_static("impersonate-group"), value(group)?));
+    }
+    Ok(headers)
+}
+
+/// Default namespace of a kubeconfig context, if it sets one
+///
+/// Without a kubeconfig, the namespace of the in-cluster service account is
+/// used. Unreadable kubeconfigs and unknown contexts yield `None`, leaving
+/// the connection itself to report them.
+pub fn context_namespace(path: Option<&Path>, context: Option<&str>) -> Option<String> {
+    let kubeconfig = match read_kubeconfig(path) {
+        Ok(kubeconfig) => kubeconfig,
+        Err(_) if path.is_none() && context.is_none() => {
+            return read_namespace(Path::new(SERVICE_ACCOUNT_DIR));
+        }
+        Err(_) => return None,
+    };
+    let name = context.or(kubeconfig.current_context.as_deref())?;
+
+    kubeconfig
+        .contexts
+        .iter()
+        .find(|named| named.name == name)?
+        .context
+        .as_ref()?
+        .namespace
+        .clone()
+}
+
+/// Namespace file of a mounted service account
+fn read_namespace(dir: &Path) -> Option<String> {
+    let namespace = std::fs::read_to_string(dir.join("namespace")).ok()?;
+    let namespace = namespace.trim();
+    (!namespace.is_empty()).then(|| namespace.to_string())
+}
+
+fn read_kubeconfig(path: Option<&Path>) -> Result<Kubeconfig> {
+    match path {
+        Some(path) => Kubeconfig::read_from(path),
+        None => Kubeconfig::read(),
+    }
+    .map_err(|e| KubeError::InvalidConfig(kubeconfig_error(path, e)))
+}
+
+fn kubeconfig_error(path: Option<&Path>, error: impl std::fmt::Display) -> String {
+    match path {
+        Some(path) => format!("kubeconfig {}: {}", path.display(), error),
+        None => format!("kubeconfig: {}", error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KUBECONFIG: &str = r#"
+apiVersion: v1
+kind: Config
+current-context: dev
+clusters:
+  - name: dev-cluster
+    cluster:
+      server: https://dev.example.com:6443
+  - name: prod-cluster
+    cluster:
+      server: https://prod.example.com:6443
+users:
+  - name: dev-user
+    user:
+      username: dev-admin
+      password: dev-password
+  - name: prod-user
+    user:
+      username: prod-admin
+      password: prod-password
+contexts:
+  - name: dev
+    context:
+      cluster: dev-cluster
+      user: dev-user
+  - name: prod
+    context:
+      cluster: prod-cluster
+      user: prod-user
+      namespace: payments
+"#;
+
+    fn fixture() -> tempfile::NamedTempFile {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), KUBECONFIG).unwrap();
+        file
+    }
+
+    #[tokio::test]
+    async fn test_explicit_context_selects_cluster_and_user() {
+        let file = fixture();
+
+        let config = load_config(Some(file.path()), Some("prod")).await.unwrap();
+        assert_eq!(config.cluster_url, "https://prod.example.com:6443/");
+        assert_eq!(config.default_namespace, "payments");
+        assert_eq!(config.auth_info.username.as_deref(), Some("prod-admin"));
+
+        // Without a context, the file's current context is used
+        let config = load_config(Some(file.path()), None).await.unwrap();
+        assert_eq!(config.cluster_url, "https://dev.example.com:6443/");
+        assert_eq!(config.default_namespace, "default");
+        assert_eq!(config.auth_info.username.as_deref(), Some("dev-admin"));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_context_fails() {
+        let file = fixture();
+        let err = load_config(Some(file.path()), Some("staging"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("staging"), "{err}");
+    }
+
+    const CA_CERT: &str = "-----BEGIN CERTIFICATE-----
+MIIBejCCASGgAwIBAgIUfbj9LvdIVYVK/TpLZlood4BN0SgwCgYIKoZIzj0EAwIw
+EjEQMA4GA1UEAwwHdGVzdC1jYTAgFw0yNjEwMTcwMDU4MDdaGA8yMTI2MDkyMzAw
+NTgwN1owEjEQMA4GA1UEAwwHdGVzdC1jYTBZMBMGByqGSM49AgEGCCqGSM49AwEH
+A0IABCKNzASMyZmMDM+JNWexlIGe4vwolGf5WyUyamB/OdgC1VgOex0FSnHpfEgJ
+MnqNs9PeFKaSmen5pEhE8w5oczSjUzBRMB0GA1UdDgQWBBTRg9jSlLLpUdw6To+9
+uqCSHxxlQDAfBgNVHSMEGDAWgBTRg9jSlLLpUdw6To+9uqCSHxxlQDAPBgNVHRMB
+Af8EBTADAQH/MAoGCCqGSM49BAMCA0cAMEQCIFvdPT9Tafq+81kdZKFO60lKBlxE
+ZfK7u9TBBolDN1dIAiBh7Jg8vECttDnVzBXVWwlqRNUvDI4gX0qlGAODZFE3VA==
+-----END CERTIFICATE-----
+";
+
+    #[tokio::test]
+    async fn test_in_cluster_config_from_mounted_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("token"), "sa-token").unwrap();
+        std::fs::write(dir.path().join("ca.crt"), CA_CERT).unwrap();
+        std::fs::write(dir.path().join("namespace"), "operators\n").unwrap();
+
+        let config = in_cluster_config(dir.path(), "10.96.0.1", "443")
+            .await
+            .unwrap();
+
+        assert_eq!(config.cluster_url, "https://10.96.0.1:443/");
+        assert_eq!(config.default_namespace, "operators");
+        assert_eq!(config.root_cert.map(|certs| certs.len()), Some(1));
+        assert_eq!(
+            config.auth_info.token_file.as_deref(),
+            dir.path().join("token").to_str()
+        );
+
+        // IPv6 hosts are bracketed; a missing namespace file means `default`
+        std::fs::remove_file(dir.path().join("namespace")).unwrap();
+        let config = in_cluster_config(dir.path(), "fd00::1", "6443")
+            .await
+            .unwrap();
+        assert_eq!(config.cluster_url, "https://[fd00::1]:6443/");
+        assert_eq!(config.default_namespace, "default");
+    }
+
+    #[test]
+    fn test_impersonation_headers() {
+        let groups = vec!["system:masters".to_string(), "auditors".to_string()];
+        let headers = impersonation_headers(Some("jane@example.com"), &groups).unwrap();
+        let headers: Vec<(&str, &str)> = headers
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.to_str().unwrap()))
+            .collect();
+
+        assert_eq!(
+            headers,
+            [
+                ("impersonate-user", "jane@example.com"),
+                ("impersonate-group", "system:masters"),
+                ("impersonate-group", "auditors"),
+            ]
+        );
+
+        assert!(impersonation_headers(None, &[]).unwrap().is_empty());
+        assert!(impersonation_headers(Some("bad\nuser"), &[]).is_err());
+    }
+
+    #[test]
+    fn test_context_namespace() {
+        let file = fixture();
+        assert_eq!(
+            context_namespace(Some(file.path()), Some("prod")).as_deref(),
+            Some("payments")
+        );
+        assert_eq!(context_namespace(Some(file.path()), None), None);
+        assert_eq!(context_namespace(Some(file.path()), Some("staging")), None);
+    }
+}
@@ -9,6 +9,8 @@
 //! - **Sync Waves**: Resource ordering with wait-for dependencies
 //! - **Progress Reporting**: Real-time feedback during deployment operations
 //! - **Annotations**: Helm-compatible annotation parsing with Sherpack extensions
+//! - **Kubeconfig**: Pick the kubeconfig file and context to connect with
+//! - **OpenAPI Validation**: Check rendered manifests against the cluster's resource schemas
 
 pub mod actions;
 pub mod annotations;
@@ -18,29 +20,50 @@ pub mod diff;
 pub mod error;
 pub mod health;
 pub mod hooks;
+pub mod kubeconfig;
 pub mod lookup;
+pub mod openapi;
+pub mod post_render;
 pub mod progress;
 pub mod release;
 pub mod resources;
 pub mod storage;
 pub mod waves;
 
-pub use actions::{InstallOptions, RollbackOptions, UninstallOptions, UpgradeOptions};
+pub use actions::{
+    DryRunMode, InstallOptions, RollbackOptions, TestOptions, UninstallOptions, UpgradeOptions,
+};
 pub use annotations::{DeletePolicy, FailurePolicy, ResourceRef};
-pub use client::KubeClient;
-pub use diff::{ChangeType, DiffEngine, DiffResult, ResourceChange};
+pub use client::{KubeClient, RecoveryReport, ServerInfo};
+pub use diff::{ChangeType, DiffEngine, DiffResult, ResourceChange, ResourceKey};
 pub use error::{KubeError, Result};
-pub use health::{HealthCheckConfig, HealthChecker, HealthStatus, ResourceHealth};
-pub use hooks::{Hook, HookCleanupPolicy, HookExecutor, HookFailurePolicy, HookPhase};
+pub use health::{
+    HealthCheckConfig, HealthChecker, HealthProbe, HealthStatus, ResourceHealth, WaitCondition,
+};
+pub use hooks::{
+    Hook, HookCleanupPolicy, HookExecutor, HookFailurePolicy, HookPhase, HookResult, HookRun,
+    hooks_for_phase,
+};
 pub use lookup::KubeClusterReader;
-pub use progress::{ProgressReporter, ResourceState, ResourceStatus};
-pub use release::{ReleaseState, StoredRelease, ValueSource, ValuesProvenance};
-pub use resources::{ApplyResult, DeleteResult, OperationSummary, ResourceManager};
+pub use openapi::{OpenApiValidator, SchemaViolation};
+pub use post_render::{post_render_manifest, split_hook_documents};
+pub use progress::{
+    EventType, JsonProgressReporter, ProgressEvent, ProgressFormat, ProgressReporter, ProgressSink,
+    ResourceState, ResourceStatus,
+};
+pub use release::{ReleaseState, StoredRelease, ValueOrigin, ValueSource, ValuesProvenance};
+pub use resources::{
+    ApplyResult, DeleteResult, ImmutableConflict, ImmutablePolicy, OperationSummary,
+    ReleaseOwnership, ResourceManager,
+};
 pub use storage::{
     CompressionMethod, LargeReleaseStrategy, MockStorageDriver, OperationCounts, StorageConfig,
     StorageDriver,
 };
-pub use waves::{ExecutionPlan, Resource, Wave, WaveExecutionConfig};
+pub use waves::{
+    ClusterWaveApplier, ExecutionPlan, GateFailure, Resource, Wave, WaveApplier,
+    WaveExecutionConfig, WaveExecutionReport, WaveExecutor, WaveFailurePolicy,
+};
 // CRD handling - Phase 2 Safe Updates
 pub use crd::{
     ChangeKind,
@@ -49,6 +72,7 @@ pub use crd::{
     // Analysis types
     CrdAnalyzer,
     CrdApplyResult,
+    CrdBackup,
     CrdChange,
     CrdManager,
     CrdParser,
@@ -61,6 +85,7 @@ pub use crd::{
     ResourceCategory,
     SafeStrategy,
     SkipStrategy,
+    StorageMigration,
     UpgradeDecision,
     // Strategy types
     UpgradeStrategy,
@@ -0,0 +1,520 @@
+//! Validation of rendered manifests against the cluster's OpenAPI schemas
+//!
+//! The API server publishes one OpenAPI v3 document per group-version under
+//! `/openapi/v3`. `OpenApiValidator` fetches the documents a manifest needs on
+//! demand and walks each resource against the schema of its kind, reporting
+//! fields the schema does not declare and values of the wrong type - the
+//! mistakes that otherwise only surface at apply time.
+//!
+//! Kinds the cluster publishes no schema for (an uninstalled CRD, or a CRD
+//! without a structural schema) are skipped rather than reported.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use kube::Client;
+use serde_json::Value as JsonValue;
+
+use crate::error::{KubeError, Result};
+
+/// Default timeout for each request to the OpenAPI endpoints
+pub const DEFAULT_OPENAPI_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A field of a rendered resource the cluster schema rejects
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaViolation {
+    /// Template the resource was rendered from
+    pub source: String,
+    /// Position of the document within the template (1-based)
+    pub document: usize,
+    /// Resource as `Kind/name`
+    pub resource: String,
+    /// Path of the offending field (e.g. `.spec.replicass`)
+    pub path: String,
+    /// What is wrong with it
+    pub message: String,
+}
+
+impl std::fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} (document {}) {}: {}: {}",
+            self.source, self.document, self.resource, self.path, self.message
+        )
+    }
+}
+
+/// Validates manifests against the OpenAPI v3 schemas served by a cluster
+pub struct OpenApiValidator {
+    client: Client,
+    timeout: Duration,
+    /// Group-version (`api/v1`, `apis/apps/v1`) to its document URL
+    paths: HashMap<String, String>,
+    /// Fetched documents; `None` when the cluster has none for the group-version
+    documents: HashMap<String, Option<JsonValue>>,
+}
+
+impl OpenApiValidator {
+    /// Connect with the default kubeconfig and fetch the OpenAPI index
+    pub async fn connect() -> Result<Self> {
+        let client = Client::try_default().await?;
+        Self::new(client).await
+    }
+
+    /// Fetch the OpenAPI index through an existing client
+    pub async fn new(client: Client) -> Result<Self> {
+        Self::with_timeout(client, DEFAULT_OPENAPI_TIMEOUT).await
+    }
+
+    /// Like [`OpenApiValidator::new`], with a custom per-request timeout
+    pub async fn with_timeout(client: Client, timeout: Duration) -> Result<Self> {
+        let index = fetch_json(&client, "/openapi/v3", timeout).await?;
+
+        let paths = index
+            .get("paths")
+            .and_then(JsonValue::as_object)
+            .ok_or_else(|| KubeError::InvalidManifest("OpenAPI index has no paths".to_string()))?
+            .iter()
+            .filter_map(|(group_version, entry)| {
+                let url = entry.get("serverRelativeURL")?.as_str()?;
+                Some((group_version.clone(), url.to_string()))
+            })
+            .collect();
+
+        Ok(Self {
+            client,
+            timeout,
+            paths,
+            documents: HashMap::new(),
+        })
+    }
+
+    /// Validate every resource of a rendered template
+    ///
+    /// `source` names the template in the returned violations.
+    pub async fn validate_manifest(
+        &mut self,
+        source: &str,
+        manifest: &str,
+    ) -> Result<Vec<SchemaViolation>> {
+        let mut violations = Vec::new();
+
+        for (document, resource) in manifest_documents(manifest) {
+            let object = match resource {
+                Ok(object) => object,
+                Err(message) => {
+                    violations.push(SchemaViolation {
+                        source: source.to_string(),
+                        document,
+                        resource: "<unparsable>".to_string(),
+                        path: ".".to_string(),
+                        message,
+                    });
+                    continue;
+                }
+            };
+
+            let Some(api_version) = object.get("apiVersion").and_then(JsonValue::as_str) else {
+                continue;
+            };
+            let Some(kind) = object.get("kind").and_then(JsonValue::as_str) else {
+                continue;
+            };
+
+            let group_version = group_version_path(api_version);
+            let Some(openapi) = self.document(&group_version).await? else {
+                continue;
+            };
+            let Some(schema) = kind_schema(openapi, api_version, kind) else {
+                continue;
+            };
+
+            let name = object
+                .pointer("/metadata/name")
+                .and_then(JsonValue::as_str)
+                .unwrap_or("unnamed");
+
+            let mut errors = Vec::new();
+            check_value(openapi, schema, &object, String::new(), &mut errors);
+            violations.extend(errors.into_iter().map(|(path, message)| SchemaViolation {
+                source: source.to_string(),
+                document,
+                resource: format!("{}/{}", kind, name),
+                path,
+                message,
+            }));
+        }
+
+        Ok(violations)
+    }
+
+    /// Fetch (once) the OpenAPI document of a group-version
+    async fn document(&mut self, group_version: &str) -> Result<Option<&JsonValue>> {
+        if !self.documents.contains_key(group_version) {
+            let document = match self.paths.get(group_version) {
+                Some(url) => Some(fetch_json(&self.client, url, self.timeout).await?),
+                None => None,
+            };
+            self.documents.insert(group_version.to_string(), document);
+        }
+
+        Ok(self.documents[group_version].as_ref())
+    }
+}
+
+/// GET a JSON document from the API server
+async fn fetch_json(client: &Client, url: &str, timeout: Duration) -> Result<JsonValue> {
+    let request = http::Request::get(url)
+        .body(Vec::new())
+        .map_err(|e| KubeError::InvalidConfig(format!("invalid OpenAPI URL '{}': {}", url, e)))?;
+
+    tokio::time::timeout(timeout, client.request::<JsonValue>(request))
+        .await
+        .map_err(|_| KubeError::Timeout(format!("{}s fetching {}", timeout.as_secs(), url)))?
+        .map_err(KubeError::from)
+}
+
+/// Split a manifest into its non-empty documents, numbered from 1
+fn manifest_documents(
+    manifest: &str,
+) -> impl Iterator<Item = (usize, std::result::Result<JsonValue, String>)> + '_ {
+    manifest
+        .split("---")
+        .map(str::trim)
+        .filter(|doc| {
+            // Skip empty and comment-only documents
+            doc.lines()
+                .any(|l| !l.trim().is_empty() && !l.trim().starts_with('#'))
+        })
+        .enumerate()
+        .map(|(index, doc)| {
+            let object =
+                serde_yaml::from_str::<JsonValue>(doc).map_err(|e| format!("invalid YAML: {}", e));
+            (index + 1, object)
+        })
+}
+
+/// OpenAPI path of an apiVersion: `v1` -> `api/v1`, `apps/v1` -> `apis/apps/v1`
+fn group_version_path(api_version: &str) -> String {
+    if api_version.contains('/') {
+        format!("apis/{}", api_version)
+    } else {
+        format!("api/{}", api_version)
+    }
+}
+
+/// Find the schema declaring `x-kubernetes-group-version-kind` for a kind
+fn kind_schema<'a>(openapi: &'a JsonValue, api_version: &str, kind: &str) -> Option<&'a JsonValue> {
+    let (group, version) = api_version.rsplit_once('/').unwrap_or(("", api_version));
+
+    openapi
+        .pointer("/components/schemas")?
+        .as_object()?
+        .values()
+        .find(|schema| {
+            schema
+                .get("x-kubernetes-group-version-kind")
+                .and_then(JsonValue::as_array)
+                .is_some_and(|gvks| {
+                    gvks.iter().any(|gvk| {
+                        gvk.get("group").and_then(JsonValue::as_str).unwrap_or("") == group
+                            && gvk.get("version").and_then(JsonValue::as_str) == Some(version)
+                            && gvk.get("kind").and_then(JsonValue::as_str) == Some(kind)
+                    })
+                })
+        })
+}
+
+/// Follow `$ref` (possibly wrapped in a single-element `allOf`) to the real schema
+fn resolve<'a>(openapi: &'a JsonValue, mut schema: &'a JsonValue) -> &'a JsonValue {
+    // Bounded to survive malformed self-referencing documents
+    for _ in 0..32 {
+        if let Some(reference) = schema.get("$ref").and_then(JsonValue::as_str) {
+            match reference
+                .strip_prefix('#')
+                .and_then(|pointer| openapi.pointer(pointer))
+            {
+                Some(target) => schema = target,
+                None => break,
+            }
+        } else if let Some([inner]) = schema
+            .get("allOf")
+            .and_then(JsonValue::as_array)
+            .map(Vec::as_slice)
+        {
+            schema = inner;
+        } else {
+            break;
+        }
+    }
+    schema
+}
+
+/// Check a value against its schema, collecting `(path, message)` errors
+fn check_value(
+    openapi: &JsonValue,
+    schema: &JsonValue,
+    value: &JsonValue,
+    path: String,
+    errors: &mut Vec<(String, String)>,
+) {
+    let schema = resolve(openapi, schema);
+
+    // Nulls are dropped by the API server; anything goes below these markers
+    if value.is_null()
+        || flag(schema, "x-kubernetes-preserve-unknown-fields")
+        || schema.get("oneOf").is_some()
+        || schema.get("anyOf").is_some()
+    {
+        return;
+    }
+
+    let display_path = if path.is_empty() { "." } else { &path };
+
+    // int-or-string and quantities accept both representations
+    let format = schema.get("format").and_then(JsonValue::as_str);
+    if flag(schema, "x-kubernetes-int-or-string") || matches!(format, Some("int-or-string")) {
+        if !(value.is_string() || value.is_i64() || value.is_u64()) {
+            errors.push((
+                display_path.to_string(),
+                format!("expected integer or string, got {}", json_type(value)),
+            ));
+        }
+        return;
+    }
+    if matches!(format, Some("quantity")) {
+        if !(value.is_string() || value.is_number()) {
+            errors.push((
+                display_path.to_string(),
+                format!("expected quantity, got {}", json_type(value)),
+            ));
+        }
+        return;
+    }
+
+    if let Some(expected) = schema.get("type").and_then(JsonValue::as_str) {
+        let matches = match expected {
+            "object" => value.is_object(),
+            "array" => value.is_array(),
+            "string" => value.is_string(),
+            "integer" => value.is_i64() || value.is_u64(),
+            "number" => value.is_number(),
+            "boolean" => value.is_boolean(),
+            _ => true,
+        };
+        if !matches {
+            errors.push((
+                display_path.to_string(),
+                format!("expected {}, got {}", expected, json_type(value)),
+            ));
+            return;
+        }
+    }
+
+    match value {
+        JsonValue::Object(fields) => {
+            let properties = schema.get("properties").and_then(JsonValue::as_object);
+            let additional = schema.get("additionalProperties");
+
+            for (key, field) in fields {
+                let field_path = format!("{}.{}", path, key);
+                match (properties.and_then(|p| p.get(key)), additional) {
+                    (Some(field_schema), _) => {
+                        check_value(openapi, field_schema, field, field_path, errors)
+                    }
+                    (None, Some(JsonValue::Bool(false))) => {
+                        errors.push((field_path, "field not declared in schema".to_string()));
+                    }
+                    (None, Some(JsonValue::Bool(true))) => {}
+                    (None, Some(field_schema)) => {
+                        check_value(openapi, field_schema, field, field_path, errors)
+                    }
+                    // A schema listing its properties declares all of them
+                    (None, None) if properties.is_some() => {
+                        errors.push((field_path, "field not declared in schema".to_string()));
+                    }
+                    (None, None) => {}
+                }
+            }
+        }
+        JsonValue::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    check_value(
+                        openapi,
+                        item_schema,
+                        item,
+                        format!("{}[{}]", path, index),
+                        errors,
+                    );
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn flag(schema: &JsonValue, name: &str) -> bool {
+    schema.get(name).and_then(JsonValue::as_bool) == Some(true)
+}
+
+fn json_type(value: &JsonValue) -> &'static str {
+    match value {
+        JsonValue::Null => "null",
+        JsonValue::Bool(_) => "boolean",
+        JsonValue::Number(n) if n.is_f64() => "number",
+        JsonValue::Number(_) => "integer",
+        JsonValue::String(_) => "string",
+        JsonValue::Array(_) => "array",
+        JsonValue::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Trimmed-down `apis/apps/v1` document in the shape the API server serves
+    fn apps_v1() -> JsonValue {
+        json!({
+            "components": {
+                "schemas": {
+                    "io.k8s.api.apps.v1.Deployment": {
+                        "type": "object",
+                        "properties": {
+                            "apiVersion": { "type": "string" },
+                            "kind": { "type": "string" },
+                            "metadata": {
+                                "allOf": [{ "$ref": "#/components/schemas/io.k8s.apimachinery.pkg.apis.meta.v1.ObjectMeta" }]
+                            },
+                            "spec": {
+                                "allOf": [{ "$ref": "#/components/schemas/io.k8s.api.apps.v1.DeploymentSpec" }]
+                            }
+                        },
+                        "x-kubernetes-group-version-kind": [
+                            { "group": "apps", "kind": "Deployment", "version": "v1" }
+                        ]
+                    },
+                    "io.k8s.api.apps.v1.DeploymentSpec": {
+                        "type": "object",
+                        "properties": {
+                            "replicas": { "type": "integer", "format": "int32" },
+                            "strategy": {
+                                "type": "object",
+                                "properties": {
+                                    "rollingUpdate": {
+                                        "type": "object",
+                                        "properties": {
+                                            "maxSurge": { "x-kubernetes-int-or-string": true }
+                                        }
+                                    }
+                                }
+                            },
+                            "containers": {
+                                "type": "array",
+                                "items": {
+                                    "type": "object",
+                                    "properties": { "image": { "type": "string" } }
+                                }
+                            }
+                        }
+                    },
+                    "io.k8s.apimachinery.pkg.apis.meta.v1.ObjectMeta": {
+                        "type": "object",
+                        "properties": {
+                            "name": { "type": "string" },
+                            "labels": {
+                                "type": "object",
+                                "additionalProperties": { "type": "string" }
+                            }
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    fn check(object: JsonValue) -> Vec<(String, String)> {
+        let openapi = apps_v1();
+        let schema = kind_schema(&openapi, "apps/v1", "Deployment").unwrap();
+        let mut errors = Vec::new();
+        check_value(&openapi, schema, &object, String::new(), &mut errors);
+        errors
+    }
+
+    #[test]
+    fn test_valid_resource() {
+        let errors = check(json!({
+            "apiVersion": "apps/v1",
+            "kind": "Deployment",
+            "metadata": { "name": "web", "labels": { "app": "web" } },
+            "spec": {
+                "replicas": 3,
+                "strategy": { "rollingUpdate": { "maxSurge": "25%" } },
+                "containers": [{ "image": "nginx" }]
+            }
+        }));
+        assert!(errors.is_empty(), "{:?}", errors);
+    }
+
+    #[test]
+    fn test_unknown_and_invalid_fields() {
+        let errors = check(json!({
+            "apiVersion": "apps/v1",
+            "kind": "Deployment",
+            "metadata": { "name": "web", "labels": { "app": 1 } },
+            "spec": {
+                "replicass": 3,
+                "containers": [{ "image": "nginx", "imagee": "nginx" }],
+                "strategy": { "rollingUpdate": { "maxSurge": true } }
+            }
+        }));
+
+        assert_eq!(
+            errors,
+            vec![
+                (
+                    ".metadata.labels.app".to_string(),
+                    "expected string, got integer".to_string()
+                ),
+                (
+                    ".spec.containers[0].imagee".to_string(),
+                    "field not declared in schema".to_string()
+                ),
+                (
+                    ".spec.replicass".to_string(),
+                    "field not declared in schema".to_string()
+                ),
+                (
+                    ".spec.strategy.rollingUpdate.maxSurge".to_string(),
+                    "expected integer or string, got boolean".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_kind_schema_lookup() {
+        let openapi = apps_v1();
+        assert!(kind_schema(&openapi, "apps/v1", "Deployment").is_some());
+        assert!(kind_schema(&openapi, "apps/v1", "StatefulSet").is_none());
+        assert!(kind_schema(&openapi, "apps/v2", "Deployment").is_none());
+    }
+
+    #[test]
+    fn test_group_version_path() {
+        assert_eq!(group_version_path("v1"), "api/v1");
+        assert_eq!(group_version_path("apps/v1"), "apis/apps/v1");
+    }
+
+    #[test]
+    fn test_manifest_documents_positions() {
+        let manifest = "# leading comment only\n---\nkind: A\n---\n\n---\nkind: B\n";
+        let docs: Vec<_> = manifest_documents(manifest)
+            .map(|(index, object)| (index, object.unwrap()["kind"].clone()))
+            .collect();
+        assert_eq!(docs, vec![(1, json!("A")), (2, json!("B"))]);
+    }
+}
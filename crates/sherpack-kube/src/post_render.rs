@@ -0,0 +1,170 @@
+//! Post-rendering of release manifests
+//!
+//! Hook documents are set aside before the post-renderer runs: they are
+//! executed by the hook runner and never reach the post-renderer (as in
+//! Helm). The transformed output is split back into documents and checked
+//! to be Kubernetes resources before it becomes the release manifest.
+
+use std::collections::BTreeMap;
+
+use sherpack_engine::PostRenderer;
+
+use crate::annotations;
+use crate::diff::ResourceKey;
+use crate::error::{KubeError, Result};
+
+/// Split a manifest into (resource documents, hook documents)
+pub fn split_hook_documents(manifest: &str) -> (Vec<&str>, Vec<&str>) {
+    manifest
+        .split("---")
+        .map(str::trim)
+        .filter(|doc| !doc.is_empty())
+        .partition(|doc| !is_hook_document(doc))
+}
+
+/// Run a post-renderer over the non-hook documents of a manifest
+///
+/// Returns the transformed documents followed by the untouched hooks.
+pub fn post_render_manifest(manifest: &str, renderer: &dyn PostRenderer) -> Result<String> {
+    let (resources, hooks) = split_hook_documents(manifest);
+
+    let rendered = renderer.post_render(&resources.join("\n---\n"))?;
+
+    let mut documents = Vec::with_capacity(resources.len() + hooks.len());
+    for (index, doc) in rendered
+        .split("---")
+        .map(str::trim)
+        .filter(|doc| !doc.is_empty() && !is_comment_only(doc))
+        .enumerate()
+    {
+        resource_key(doc).map_err(|e| {
+            KubeError::InvalidManifest(format!("post-renderer output document {}: {}", index, e))
+        })?;
+        documents.push(doc);
+    }
+    documents.extend(hooks);
+
+    Ok(documents.join("\n---\n"))
+}
+
+/// Identify a document, failing if it isn't a Kubernetes resource
+fn resource_key(doc: &str) -> std::result::Result<ResourceKey, String> {
+    let yaml: serde_yaml::Value = serde_yaml::from_str(doc).map_err(|e| e.to_string())?;
+
+    let field = |value: Option<&serde_yaml::Value>, name: &str| {
+        value
+            .and_then(|v| v.as_str())
+            .map(String::from)
+            .ok_or_else(|| format!("missing {}", name))
+    };
+    let metadata = yaml.get("metadata");
+
+    Ok(ResourceKey {
+        api_version: field(yaml.get("apiVersion"), "apiVersion")?,
+        kind: field(yaml.get("kind"), "kind")?,
+        name: field(metadata.and_then(|m| m.get("name")), "metadata.name")?,
+        namespace: metadata
+            .and_then(|m| m.get("namespace"))
+            .and_then(|n| n.as_str())
+            .map(String::from),
+    })
+}
+
+fn is_hook_document(doc: &str) -> bool {
+    let Ok(yaml) = serde_yaml::from_str::<serde_yaml::Value>(doc) else {
+        return false;
+    };
+    let annotations: BTreeMap<String, String> = yaml
+        .get("metadata")
+        .and_then(|m| m.get("annotations"))
+        .and_then(|a| serde_yaml::from_value(a.clone()).ok())
+        .unwrap_or_default();
+
+    annotations::get_annotation(
+        &annotations,
+        annotations::sherpack::HOOK,
+        annotations::helm::HOOK,
+    )
+    .is_some()
+}
+
+fn is_comment_only(doc: &str) -> bool {
+    doc.lines()
+        .all(|l| l.trim().is_empty() || l.trim().starts_with('#'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Records its input and returns a fixed output
+    #[derive(Debug)]
+    struct FixedRenderer {
+        output: String,
+        seen: Mutex<Option<String>>,
+    }
+
+    impl FixedRenderer {
+        fn new(output: &str) -> Self {
+            Self {
+                output: output.to_string(),
+                seen: Mutex::new(None),
+            }
+        }
+    }
+
+    impl PostRenderer for FixedRenderer {
+        fn post_render(&self, manifest: &str) -> sherpack_engine::error::Result<String> {
+            *self.seen.lock().unwrap() = Some(manifest.to_string());
+            Ok(self.output.clone())
+        }
+    }
+
+    const MANIFEST: &str = r#"apiVersion: v1
+kind: ConfigMap
+metadata:
+  name: app
+---
+apiVersion: batch/v1
+kind: Job
+metadata:
+  name: migrate
+  annotations:
+    sherpack.io/hook: pre-install
+"#;
+
+    #[test]
+    fn test_hooks_bypass_post_renderer() {
+        let renderer = FixedRenderer::new(
+            "---\napiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: app\n  labels:\n    team: a\n",
+        );
+
+        let output = post_render_manifest(MANIFEST, &renderer).unwrap();
+
+        let seen = renderer.seen.lock().unwrap().clone().unwrap();
+        assert!(seen.contains("kind: ConfigMap"));
+        assert!(!seen.contains("kind: Job"));
+
+        let resources = crate::diff::parse_manifest_resources(&output);
+        assert_eq!(resources.len(), 2);
+        assert!(output.contains("team: a"));
+        assert!(output.contains("sherpack.io/hook: pre-install"));
+    }
+
+    #[test]
+    fn test_invalid_post_renderer_output() {
+        let renderer = FixedRenderer::new("apiVersion: v1\nmetadata:\n  name: app\n");
+
+        let err = post_render_manifest(MANIFEST, &renderer).unwrap_err();
+        assert!(err.to_string().contains("missing kind"));
+    }
+
+    #[test]
+    fn test_split_hook_documents() {
+        let (resources, hooks) = split_hook_documents(MANIFEST);
+        assert_eq!(resources.len(), 1);
+        assert_eq!(hooks.len(), 1);
+        assert!(hooks[0].contains("migrate"));
+    }
+}
@@ -5,14 +5,185 @@
 //! - Health check progress (ready/desired replicas)
 //! - Wave execution progress
 //! - Hook execution status
+//!
+//! Every resource transition is described by a [`ProgressEvent`] and handed
+//! to a [`ProgressSink`]; the terminal and NDJSON reporters only differ in
+//! how they print the same events. Warnings about a resource and container
+//! log lines travel the same way, so `--progress json` stays valid NDJSON.
 
 use std::collections::HashMap;
 use std::io::{self, Write};
+use std::sync::Mutex;
 use std::time::{Duration, Instant};
 
+use chrono::{DateTime, Utc};
 use console::{Term, style};
+use serde::{Deserialize, Serialize};
+
+/// What a [`ProgressEvent`] reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventType {
+    /// The resource entered a new phase
+    #[default]
+    Resource,
+    /// Something about the resource needs attention (in `message`)
+    Warning,
+    /// A line of container output (in `message`)
+    Log,
+}
+
+/// A resource state transition, warning or log line
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProgressEvent {
+    /// What the event reports
+    #[serde(rename = "type", default)]
+    pub event_type: EventType,
+    /// Resource kind
+    pub kind: String,
+    /// Resource name
+    pub name: String,
+    /// New phase of the resource, or its phase when a warning or log line
+    /// was raised
+    pub phase: ResourceStatus,
+    /// Ready replicas, when known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ready: Option<i32>,
+    /// Desired replicas, when known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub desired: Option<i32>,
+    /// Container a log line comes from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub container: Option<String>,
+    /// Detail about the transition (e.g. a failure reason), the warning or
+    /// the log line
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// When the transition happened
+    pub timestamp: DateTime<Utc>,
+}
+
+impl ProgressEvent {
+    /// Create an event for a resource entering `phase` now
+    pub fn new(kind: impl Into<String>, name: impl Into<String>, phase: ResourceStatus) -> Self {
+        Self {
+            event_type: EventType::Resource,
+            kind: kind.into(),
+            name: name.into(),
+            phase,
+            ready: None,
+            desired: None,
+            container: None,
+            message: None,
+            timestamp: Utc::now(),
+        }
+    }
+
+    /// Create a warning about an applied resource
+    pub fn warning(
+        kind: impl Into<String>,
+        name: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            event_type: EventType::Warning,
+            ..Self::new(kind, name, ResourceStatus::Applied).with_message(message)
+        }
+    }
+
+    /// Create an event for a line a Pod's container wrote
+    pub fn log(
+        pod: impl Into<String>,
+        container: impl Into<String>,
+        line: impl Into<String>,
+    ) -> Self {
+        Self {
+            event_type: EventType::Log,
+            container: Some(container.into()),
+            ..Self::new("Pod", pod, ResourceStatus::WaitingForReady).with_message(line)
+        }
+    }
+
+    /// Attach a message
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    /// Attach readiness counts
+    pub fn with_readiness(mut self, ready: Option<i32>, desired: Option<i32>) -> Self {
+        self.ready = ready;
+        self.desired = desired;
+        self
+    }
+
+    /// Resource key (Kind/name)
+    pub fn key(&self) -> String {
+        format!("{}/{}", self.kind, self.name)
+    }
+
+    fn from_state(resource: &ResourceState) -> Self {
+        Self {
+            message: resource.message.clone(),
+            ..Self::new(&resource.kind, &resource.name, resource.status)
+                .with_readiness(resource.ready, resource.desired)
+        }
+    }
+}
+
+/// Receiver of resource progress events
+pub trait ProgressSink: std::fmt::Debug + Send + Sync {
+    /// Report a resource transition
+    fn emit(&self, event: &ProgressEvent);
+}
+
+/// How progress is reported on the command line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProgressFormat {
+    /// Human-readable lines on stderr
+    #[default]
+    Terminal,
+    /// One JSON object per line (NDJSON) on stdout
+    Json,
+}
+
+impl ProgressFormat {
+    /// Create the reporter for this format
+    pub fn sink(self) -> std::sync::Arc<dyn ProgressSink> {
+        match self {
+            Self::Terminal => std::sync::Arc::new(ProgressReporter::new()),
+            Self::Json => std::sync::Arc::new(JsonProgressReporter::new()),
+        }
+    }
+}
+
+impl std::fmt::Display for ProgressFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Terminal => write!(f, "terminal"),
+            Self::Json => write!(f, "json"),
+        }
+    }
+}
+
+impl std::str::FromStr for ProgressFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "terminal" => Ok(Self::Terminal),
+            "json" => Ok(Self::Json),
+            _ => Err(format!(
+                "unknown progress format: {} (expected terminal or json)",
+                s
+            )),
+        }
+    }
+}
 
 /// Progress reporter for deployment operations
+#[derive(Debug)]
 pub struct ProgressReporter {
     /// Terminal for output
     term: Term,
@@ -42,7 +213,8 @@ pub struct ResourceState {
 }
 
 /// Status of a resource
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ResourceStatus {
     Pending,
     Applying,
@@ -176,25 +348,7 @@ impl ProgressReporter {
     /// Print resource update
     fn print_resource_update(&self, key: &str) {
         if let Some(resource) = self.resources.get(key) {
-            let styled_symbol = resource.status.styled_symbol();
-
-            let readiness = match (resource.ready, resource.desired) {
-                (Some(r), Some(d)) => format!(" ({}/{})", r, d),
-                _ => String::new(),
-            };
-
-            let message = resource
-                .message
-                .as_ref()
-                .map(|m| format!(" - {}", style(m).dim()))
-                .unwrap_or_default();
-
-            let line = format!(
-                "  {} {}/{}{}{}",
-                styled_symbol, resource.kind, resource.name, readiness, message
-            );
-
-            let _ = writeln!(io::stderr(), "{}", line);
+            self.emit(&ProgressEvent::from_state(resource));
         }
     }
 
@@ -339,6 +493,58 @@ impl ProgressReporter {
     }
 }
 
+impl ProgressSink for ProgressReporter {
+    fn emit(&self, event: &ProgressEvent) {
+        let message = event.message.as_deref().unwrap_or_default();
+        match event.event_type {
+            EventType::Resource => {}
+            EventType::Warning => {
+                let mut lines = message.lines();
+                let _ = writeln!(
+                    io::stderr(),
+                    "  {} {}: {}",
+                    style("⚠").yellow(),
+                    event.key(),
+                    lines.next().unwrap_or_default()
+                );
+                for line in lines {
+                    let _ = writeln!(io::stderr(), "      {}", line);
+                }
+                return;
+            }
+            EventType::Log => {
+                let source = match &event.container {
+                    Some(container) => format!("[{}/{}]", event.name, container),
+                    None => format!("[{}]", event.name),
+                };
+                let _ = writeln!(io::stderr(), "{} {}", style(source).dim(), message);
+                return;
+            }
+        }
+
+        let readiness = match (event.ready, event.desired) {
+            (Some(r), Some(d)) => format!(" ({}/{})", r, d),
+            _ => String::new(),
+        };
+
+        let message = event
+            .message
+            .as_ref()
+            .map(|m| format!(" - {}", style(m).dim()))
+            .unwrap_or_default();
+
+        let _ = writeln!(
+            io::stderr(),
+            "  {} {}/{}{}{}",
+            event.phase.styled_symbol(),
+            event.kind,
+            event.name,
+            readiness,
+            message
+        );
+    }
+}
+
 impl Default for ProgressReporter {
     fn default() -> Self {
         Self::new()
@@ -369,88 +575,138 @@ impl Default for QuietProgressReporter {
 }
 
 /// JSON progress reporter for CI/CD integration
+///
+/// Writes one JSON object per line (NDJSON): an event per resource
+/// transition, then a `summary` line from [`print_summary`](Self::print_summary).
 pub struct JsonProgressReporter {
-    resources: HashMap<String, ResourceState>,
+    resources: Mutex<HashMap<String, ResourceState>>,
+    writer: Mutex<Box<dyn Write + Send>>,
 }
 
 impl JsonProgressReporter {
+    /// Create a reporter writing to stdout
     pub fn new() -> Self {
+        Self::with_writer(io::stdout())
+    }
+
+    /// Create a reporter writing to `writer`
+    pub fn with_writer(writer: impl Write + Send + 'static) -> Self {
         Self {
-            resources: HashMap::new(),
+            resources: Mutex::new(HashMap::new()),
+            writer: Mutex::new(Box::new(writer)),
         }
     }
 
     pub fn add_resource(&mut self, kind: &str, name: &str) {
         let key = format!("{}/{}", kind, name);
-        self.resources.insert(
-            key,
-            ResourceState {
-                kind: kind.to_string(),
-                name: name.to_string(),
-                status: ResourceStatus::Pending,
-                ready: None,
-                desired: None,
-                message: None,
-                last_update: Instant::now(),
-            },
-        );
+        self.resources
+            .get_mut()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(
+                key,
+                ResourceState {
+                    kind: kind.to_string(),
+                    name: name.to_string(),
+                    status: ResourceStatus::Pending,
+                    ready: None,
+                    desired: None,
+                    message: None,
+                    last_update: Instant::now(),
+                },
+            );
     }
 
     pub fn update_status(&mut self, key: &str, status: ResourceStatus) {
-        if let Some(resource) = self.resources.get_mut(key) {
-            resource.status = status;
-            self.emit_event(key, "status_changed");
+        let event = {
+            let resources = self.resources.get_mut().unwrap_or_else(|e| e.into_inner());
+            resources.get_mut(key).map(|resource| {
+                resource.status = status;
+                ProgressEvent::from_state(resource)
+            })
+        };
+        if let Some(event) = event {
+            self.emit(&event);
         }
     }
 
     pub fn update_readiness(&mut self, key: &str, ready: i32, desired: i32) {
-        if let Some(resource) = self.resources.get_mut(key) {
-            resource.ready = Some(ready);
-            resource.desired = Some(desired);
-            if ready == desired {
-                resource.status = ResourceStatus::Ready;
-            }
-            self.emit_event(key, "readiness_changed");
+        let event = {
+            let resources = self.resources.get_mut().unwrap_or_else(|e| e.into_inner());
+            resources.get_mut(key).map(|resource| {
+                resource.ready = Some(ready);
+                resource.desired = Some(desired);
+                if ready == desired {
+                    resource.status = ResourceStatus::Ready;
+                }
+                ProgressEvent::from_state(resource)
+            })
+        };
+        if let Some(event) = event {
+            self.emit(&event);
         }
     }
 
-    fn emit_event(&self, key: &str, event_type: &str) {
-        if let Some(resource) = self.resources.get(key) {
-            let event = serde_json::json!({
-                "type": event_type,
-                "resource": {
-                    "kind": resource.kind,
-                    "name": resource.name,
-                    "status": format!("{:?}", resource.status),
-                    "ready": resource.ready,
-                    "desired": resource.desired,
-                    "message": resource.message,
-                }
-            });
-            println!("{}", event);
-        }
+    fn write_line(&self, line: &serde_json::Value) {
+        let mut writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        let _ = writeln!(writer, "{}", line);
+        let _ = writer.flush();
     }
 
     pub fn print_summary(&self) {
-        let summary: Vec<_> = self
-            .resources
+        let resources = self.resources.lock().unwrap_or_else(|e| e.into_inner());
+        let mut summary: Vec<_> = resources
             .values()
             .map(|r| {
                 serde_json::json!({
                     "kind": r.kind,
                     "name": r.name,
-                    "status": format!("{:?}", r.status),
+                    "phase": r.status,
                     "ready": r.ready,
                     "desired": r.desired,
                 })
             })
             .collect();
+        summary.sort_by_key(|r| r.to_string());
+        drop(resources);
 
-        let output = serde_json::json!({
+        self.write_line(&serde_json::json!({
             "type": "summary",
             "resources": summary,
-        });
-        println!("{}", output);
+        }));
+    }
+}
+
+impl ProgressSink for JsonProgressReporter {
+    fn emit(&self, event: &ProgressEvent) {
+        // Track the last known state so the summary covers every resource
+        if event.event_type == EventType::Resource {
+            let mut resources = self.resources.lock().unwrap_or_else(|e| e.into_inner());
+            let state = resources
+                .entry(event.key())
+                .or_insert_with(|| ResourceState {
+                    kind: event.kind.clone(),
+                    name: event.name.clone(),
+                    status: event.phase,
+                    ready: None,
+                    desired: None,
+                    message: None,
+                    last_update: Instant::now(),
+                });
+            state.status = event.phase;
+            state.ready = event.ready.or(state.ready);
+            state.desired = event.desired.or(state.desired);
+            state.message = event.message.clone();
+            state.last_update = Instant::now();
+        }
+
+        self.write_line(&serde_json::to_value(event).unwrap_or_default());
+    }
+}
+
+impl std::fmt::Debug for JsonProgressReporter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JsonProgressReporter")
+            .finish_non_exhaustive()
     }
 }
 
@@ -545,4 +801,56 @@ mod tests {
         reporter.fail("Deployment/app1", "ImagePullBackOff");
         assert!(reporter.any_failed());
     }
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(std::sync::Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_json_reporter_warning_and_log_events() {
+        let buffer = SharedBuffer::default();
+        let reporter = JsonProgressReporter::with_writer(buffer.clone());
+
+        reporter.emit(&ProgressEvent::new(
+            "ConfigMap",
+            "web",
+            ResourceStatus::Applied,
+        ));
+        reporter.emit(&ProgressEvent::warning(
+            "ConfigMap",
+            "web",
+            "changed by the server after apply\n-  key: a\n+  key: b",
+        ));
+        reporter.emit(&ProgressEvent::log("migrate-x7k2p", "migrate", "done"));
+        reporter.print_summary();
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let events: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(events.len(), 4);
+        assert_eq!(events[1]["type"], "warning");
+        assert!(events[1]["message"].as_str().unwrap().contains("+  key: b"));
+        assert_eq!(events[2]["type"], "log");
+        assert_eq!(events[2]["name"], "migrate-x7k2p");
+        assert_eq!(events[2]["container"], "migrate");
+        assert_eq!(events[2]["message"], "done");
+
+        // Only resource transitions make it into the summary
+        let resources = events[3]["resources"].as_array().unwrap();
+        assert_eq!(resources.len(), 1);
+        assert_eq!(resources[0]["phase"], "applied");
+    }
 }
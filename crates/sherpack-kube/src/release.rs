@@ -2,12 +2,25 @@
 
 use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
-use sherpack_core::{PackMetadata, Values};
+use sherpack_core::{PackMetadata, SecretState, Values};
+// Provenance types live in sherpack-core so `Values` can track them
+pub use sherpack_core::{ValueOrigin, ValueSource, ValuesProvenance};
 use std::collections::HashMap;
 
 /// Default timeout for pending operations (5 minutes)
 pub const DEFAULT_OPERATION_TIMEOUT: Duration = Duration::minutes(5);
 
+/// Identifies this process as the holder of a pending state
+///
+/// Formatted as `user@host (pid N)`, from `USER`/`USERNAME` and `HOSTNAME`.
+pub fn lock_owner() -> String {
+    let user = std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "unknown".to_string());
+    let host = std::env::var("HOSTNAME").unwrap_or_else(|_| "localhost".to_string());
+    format!("{}@{} (pid {})", user, host, std::process::id())
+}
+
 /// A stored release with full metadata and provenance tracking
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -41,6 +54,10 @@ pub struct StoredRelease {
     #[serde(default)]
     pub hooks: Vec<crate::hooks::Hook>,
 
+    /// Outcome of the hooks run for this revision
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hook_runs: Vec<crate::hooks::HookRun>,
+
     /// Custom labels for filtering/querying
     #[serde(default)]
     pub labels: HashMap<String, String>,
@@ -54,6 +71,10 @@ pub struct StoredRelease {
     /// Notes from NOTES.txt (if present)
     #[serde(default)]
     pub notes: Option<String>,
+
+    /// Secrets generated by `generate_secret()`, reused on later renders
+    #[serde(default, skip_serializing_if = "SecretState::is_empty")]
+    pub secrets: SecretState,
 }
 
 impl StoredRelease {
@@ -73,16 +94,19 @@ impl StoredRelease {
             state: ReleaseState::PendingInstall {
                 started_at: now,
                 timeout: DEFAULT_OPERATION_TIMEOUT,
+                owner: Some(lock_owner()),
             },
             pack,
             values,
             values_provenance: ValuesProvenance::default(),
             manifest,
             hooks: Vec::new(),
+            hook_runs: Vec::new(),
             labels: HashMap::new(),
             created_at: now,
             updated_at: now,
             notes: None,
+            secrets: SecretState::default(),
         }
     }
 
@@ -97,16 +121,19 @@ impl StoredRelease {
                 started_at: now,
                 timeout: DEFAULT_OPERATION_TIMEOUT,
                 previous_version: previous.version,
+                owner: Some(lock_owner()),
             },
             pack: previous.pack.clone(),
             values,
             values_provenance: previous.values_provenance.clone(),
             manifest,
             hooks: previous.hooks.clone(),
+            hook_runs: Vec::new(),
             labels: previous.labels.clone(),
             created_at: now,
             updated_at: now,
             notes: previous.notes.clone(),
+            secrets: previous.secrets.clone(),
         }
     }
 
@@ -123,6 +150,7 @@ impl StoredRelease {
                 | ReleaseState::Failed { .. }
                 | ReleaseState::Uninstalled
                 | ReleaseState::Superseded
+                | ReleaseState::AtomicRollback { .. }
         )
     }
 
@@ -153,6 +181,19 @@ impl StoredRelease {
         self.updated_at = Utc::now();
     }
 
+    /// Record that an atomic operation failed and was rolled back
+    ///
+    /// `from_version` is the revision restored by the rollback, `None` when
+    /// a failed install was removed.
+    pub fn mark_atomic_rollback(&mut self, from_version: Option<u32>, error: String) {
+        self.state = ReleaseState::AtomicRollback {
+            from_version,
+            error,
+            rolled_back_at: Utc::now(),
+        };
+        self.updated_at = Utc::now();
+    }
+
     /// Mark the release as uninstalled
     pub fn mark_uninstalled(&mut self) {
         self.state = ReleaseState::Uninstalled;
@@ -193,11 +234,22 @@ pub enum ReleaseState {
     /// Replaced by a newer revision
     Superseded,
 
+    /// Failed during an atomic install/upgrade and rolled back automatically
+    AtomicRollback {
+        /// Revision restored by the rollback (`None` for an install)
+        from_version: Option<u32>,
+        error: String,
+        rolled_back_at: DateTime<Utc>,
+    },
+
     /// Installation in progress
     PendingInstall {
         started_at: DateTime<Utc>,
         #[serde(with = "duration_serde")]
         timeout: Duration,
+        /// Process holding the release while the operation runs
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        owner: Option<String>,
     },
 
     /// Upgrade in progress
@@ -206,6 +258,9 @@ pub enum ReleaseState {
         #[serde(with = "duration_serde")]
         timeout: Duration,
         previous_version: u32,
+        /// Process holding the release while the operation runs
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        owner: Option<String>,
     },
 
     /// Rollback in progress
@@ -214,6 +269,9 @@ pub enum ReleaseState {
         #[serde(with = "duration_serde")]
         timeout: Duration,
         target_version: u32,
+        /// Process holding the release while the operation runs
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        owner: Option<String>,
     },
 
     /// Uninstallation in progress
@@ -221,6 +279,9 @@ pub enum ReleaseState {
         started_at: DateTime<Utc>,
         #[serde(with = "duration_serde")]
         timeout: Duration,
+        /// Process holding the release while the operation runs
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        owner: Option<String>,
     },
 
     /// Recovery in progress (from stuck state)
@@ -251,6 +312,7 @@ impl ReleaseState {
             Self::PendingInstall {
                 started_at,
                 timeout,
+                ..
             }
             | Self::PendingUpgrade {
                 started_at,
@@ -265,6 +327,7 @@ impl ReleaseState {
             | Self::PendingUninstall {
                 started_at,
                 timeout,
+                ..
             } => now.signed_duration_since(*started_at) > *timeout,
             Self::Recovering { started_at, .. } => {
                 // Recovery timeout: 2 minutes
@@ -287,6 +350,30 @@ impl ReleaseState {
         }
     }
 
+    /// Timeout the operation of a pending state was started with
+    ///
+    /// `None` for recoveries and settled states.
+    pub fn timeout(&self) -> Option<Duration> {
+        match self {
+            Self::PendingInstall { timeout, .. }
+            | Self::PendingUpgrade { timeout, .. }
+            | Self::PendingRollback { timeout, .. }
+            | Self::PendingUninstall { timeout, .. } => Some(*timeout),
+            _ => None,
+        }
+    }
+
+    /// Process holding a pending state, when it was recorded
+    pub fn owner(&self) -> Option<&str> {
+        match self {
+            Self::PendingInstall { owner, .. }
+            | Self::PendingUpgrade { owner, .. }
+            | Self::PendingRollback { owner, .. }
+            | Self::PendingUninstall { owner, .. } => owner.as_deref(),
+            _ => None,
+        }
+    }
+
     /// Auto-recover from stale pending state
     pub fn auto_recover(&self) -> Option<ReleaseState> {
         if self.is_stale() {
@@ -307,6 +394,7 @@ impl ReleaseState {
             Self::Failed { .. } => "failed",
             Self::Uninstalled => "uninstalled",
             Self::Superseded => "superseded",
+            Self::AtomicRollback { .. } => "rolled-back",
             Self::PendingInstall { .. } => "pending-install",
             Self::PendingUpgrade { .. } => "pending-upgrade",
             Self::PendingRollback { .. } => "pending-rollback",
@@ -320,6 +408,16 @@ impl std::fmt::Display for ReleaseState {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Failed { reason, .. } => write!(f, "failed: {}", reason),
+            Self::AtomicRollback {
+                from_version: Some(version),
+                error,
+                ..
+            } => write!(f, "rolled back to revision {}: {}", version, error),
+            Self::AtomicRollback {
+                from_version: None,
+                error,
+                ..
+            } => write!(f, "rolled back, install removed: {}", error),
             Self::Recovering {
                 from_status,
                 attempt,
@@ -332,93 +430,6 @@ impl std::fmt::Display for ReleaseState {
     }
 }
 
-/// Tracks where each value came from
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
-pub struct ValuesProvenance {
-    /// Map from JSON path to source
-    #[serde(default)]
-    pub sources: HashMap<String, ValueSource>,
-}
-
-impl ValuesProvenance {
-    /// Record the source of a value
-    pub fn record(&mut self, path: &str, source: ValueSource) {
-        self.sources.insert(path.to_string(), source);
-    }
-
-    /// Get the source of a value
-    pub fn get_source(&self, path: &str) -> Option<&ValueSource> {
-        self.sources.get(path)
-    }
-}
-
-/// Source of a configuration value
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-#[serde(tag = "type", rename_all = "kebab-case")]
-pub enum ValueSource {
-    /// Default from schema
-    SchemaDefault,
-
-    /// From pack's values.yaml
-    PackDefault,
-
-    /// From user's values file
-    ValuesFile { path: String, line: Option<u32> },
-
-    /// From --set command line flag
-    CommandLine {
-        flag: String,
-        timestamp: DateTime<Utc>,
-        user: Option<String>,
-    },
-
-    /// From environment variable
-    Environment { var: String },
-
-    /// Merged from multiple sources
-    Merged { sources: Vec<String> },
-}
-
-impl std::fmt::Display for ValueSource {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::SchemaDefault => write!(f, "schema default"),
-            Self::PackDefault => write!(f, "pack default"),
-            Self::ValuesFile { path, line } => {
-                if let Some(l) = line {
-                    write!(f, "{}:{}", path, l)
-                } else {
-                    write!(f, "{}", path)
-                }
-            }
-            Self::CommandLine {
-                flag,
-                timestamp,
-                user,
-            } => {
-                if let Some(u) = user {
-                    write!(
-                        f,
-                        "--set {} by {} at {}",
-                        flag,
-                        u,
-                        timestamp.format("%Y-%m-%d %H:%M")
-                    )
-                } else {
-                    write!(
-                        f,
-                        "--set {} at {}",
-                        flag,
-                        timestamp.format("%Y-%m-%d %H:%M")
-                    )
-                }
-            }
-            Self::Environment { var } => write!(f, "env ${}", var),
-            Self::Merged { sources } => write!(f, "merged from: {}", sources.join(", ")),
-        }
-    }
-}
-
 /// Serialization helper for chrono::Duration
 mod duration_serde {
     use chrono::Duration;
@@ -451,6 +462,7 @@ mod tests {
         let state = ReleaseState::PendingInstall {
             started_at: old_time,
             timeout: Duration::minutes(5),
+            owner: None,
         };
 
         assert!(state.is_stale());
@@ -462,6 +474,7 @@ mod tests {
         let state = ReleaseState::PendingInstall {
             started_at: Utc::now(),
             timeout: Duration::minutes(5),
+            owner: None,
         };
 
         assert!(!state.is_stale());
@@ -475,11 +488,57 @@ mod tests {
             ReleaseState::PendingInstall {
                 started_at: Utc::now(),
                 timeout: Duration::minutes(5),
+                owner: None,
             }
             .is_pending()
         );
     }
 
+    #[test]
+    fn test_pending_state_owner() {
+        let previous = StoredRelease::for_install(
+            "web".to_string(),
+            "default".to_string(),
+            serde_json::from_value(serde_json::json!({"name": "web", "version": "1.0.0"})).unwrap(),
+            Values::new(),
+            String::new(),
+        );
+        let release = StoredRelease::for_upgrade(&previous, Values::new(), String::new());
+        assert_eq!(release.state.owner(), Some(lock_owner().as_str()));
+
+        // Records written before owners were tracked
+        let state: ReleaseState = serde_json::from_value(serde_json::json!({
+            "status": "pending-upgrade",
+            "started_at": "2024-01-01T00:00:00Z",
+            "timeout": 300,
+            "previous_version": 1
+        }))
+        .unwrap();
+        assert!(state.is_pending());
+        assert_eq!(state.owner(), None);
+    }
+
+    #[test]
+    fn test_atomic_rollback_state() {
+        let state = ReleaseState::AtomicRollback {
+            from_version: Some(2),
+            error: "deployment/web not ready".to_string(),
+            rolled_back_at: Utc::now(),
+        };
+        assert!(!state.is_pending());
+        assert_eq!(state.status_name(), "rolled-back");
+        assert_eq!(
+            state.to_string(),
+            "rolled back to revision 2: deployment/web not ready"
+        );
+
+        let json = serde_json::to_value(&state).unwrap();
+        assert_eq!(json["status"], "atomic-rollback");
+        assert_eq!(json["from_version"], 2);
+        let roundtrip: ReleaseState = serde_json::from_value(json).unwrap();
+        assert_eq!(roundtrip, state);
+    }
+
     #[test]
     fn test_storage_key() {
         let release = StoredRelease::for_install(
@@ -504,4 +563,79 @@ mod tests {
 
         assert_eq!(release.storage_key(), "sh.sherpack.release.v1.myapp.v1");
     }
+
+    #[test]
+    fn test_secret_state_carried_across_revisions() {
+        let pack: PackMetadata = serde_yaml::from_str("name: test\nversion: 1.0.0\n").unwrap();
+        let mut release = StoredRelease::for_install(
+            "myapp".to_string(),
+            "default".to_string(),
+            pack,
+            Values::new(),
+            String::new(),
+        );
+
+        // Releases without generated secrets serialize as before
+        let json = serde_json::to_value(&release).unwrap();
+        assert!(json.get("secrets").is_none());
+
+        let mut generator = sherpack_core::SecretGenerator::new();
+        let password = generator.get_or_generate("db-password", 16);
+        release.secrets = generator.into_state();
+
+        let json = serde_json::to_string(&release).unwrap();
+        let loaded: StoredRelease = serde_json::from_str(&json).unwrap();
+        let next = StoredRelease::for_upgrade(&loaded, Values::new(), String::new());
+        assert_eq!(
+            next.secrets.get_value("db-password"),
+            Some(password.as_str())
+        );
+    }
+
+    #[test]
+    fn test_provenance_user_supplied() {
+        let values = Values::from_yaml(
+            r#"
+image:
+  repository: nginx
+  tag: "1.25"
+replicas: 3
+ports: [80, 443]
+"#,
+        )
+        .unwrap();
+
+        let mut provenance = ValuesProvenance::default();
+        provenance.record_values(&values, &ValueSource::PackDefault);
+        provenance.record_values(
+            &Values::from_yaml("image:\n  tag: \"1.25\"\nports: [80, 443]").unwrap(),
+            &ValueSource::ValuesFile {
+                path: "prod.yaml".to_string(),
+                line: None,
+            },
+        );
+
+        assert_eq!(
+            provenance.get_source("image.repository"),
+            Some(&ValueSource::PackDefault)
+        );
+        assert_eq!(
+            provenance.get_source("ports").map(ToString::to_string),
+            Some("prod.yaml".to_string())
+        );
+
+        let origins = provenance.origins(&values);
+        let paths: Vec<&str> = origins.iter().map(|o| o.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            ["image.repository", "image.tag", "ports", "replicas"]
+        );
+        assert_eq!(origins[3].value, serde_json::json!(3));
+
+        let user = provenance.user_supplied(&values).unwrap();
+        assert_eq!(
+            user.inner(),
+            &serde_json::json!({"image": {"tag": "1.25"}, "ports": [80, 443]})
+        );
+    }
 }
@@ -9,6 +9,11 @@
 //! - Proper ordering for creation (dependencies first) and deletion (reverse)
 //! - Support for resource policy annotations (keep on delete)
 //! - Retry logic for transient conflicts
+//! - Ownership checks, with opt-in adoption of pre-existing resources
+//! - Immutable-field conflicts resolved by an [`ImmutableStrategy`]
+//! - Optional post-apply verification of server-side mutations
+
+use std::cmp::Reverse;
 
 use kube::{
     Client,
@@ -17,11 +22,14 @@ use kube::{
     discovery::{ApiCapabilities, ApiResource, Discovery, Scope},
 };
 
+use crate::actions::ImmutableStrategy;
+use crate::annotations;
 use crate::crd::ResourceCategory;
+use crate::diff::{DiffEngine, LineType};
 use crate::error::{KubeError, Result};
 
 /// Field manager name for Server-Side Apply
-const FIELD_MANAGER: &str = "sherpack";
+pub(crate) const FIELD_MANAGER: &str = "sherpack";
 
 /// Annotation to keep resource on uninstall (Helm-compatible)
 const RESOURCE_POLICY_ANNOTATION: &str = "helm.sh/resource-policy";
@@ -30,6 +38,131 @@ const RESOURCE_POLICY_KEEP: &str = "keep";
 /// Sherpack-specific annotation
 const SHERPACK_RESOURCE_POLICY: &str = "sherpack.io/resource-policy";
 
+/// Ownership metadata stamped on every resource a release applies
+const MANAGED_BY_LABEL: &str = "app.kubernetes.io/managed-by";
+const MANAGED_BY_SHERPACK: &str = "sherpack";
+const RELEASE_NAME_LABEL: &str = "sherpack.io/release-name";
+const RELEASE_NAMESPACE_ANNOTATION: &str = "sherpack.io/release-namespace";
+
+/// Kinds whose data may be lost when they are deleted and recreated
+const STATEFUL_KINDS: &[&str] = &["StatefulSet", "PersistentVolumeClaim", "PersistentVolume"];
+
+/// How long a recreated resource may take to go away before it is created again
+const RECREATE_DELETE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(60);
+const RECREATE_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Release applying a manifest, checked against existing resources
+#[derive(Debug, Clone)]
+pub struct ReleaseOwnership {
+    /// Release name
+    pub release_name: String,
+    /// Release namespace
+    pub release_namespace: String,
+    /// Adopt existing resources that no release manages
+    pub take_ownership: bool,
+    /// The release is already deployed, so existing resources without
+    /// ownership metadata are its own, applied before it was stamped
+    pub deployed: bool,
+}
+
+impl ReleaseOwnership {
+    /// Create an ownership check for a release
+    pub fn new(
+        release_name: impl Into<String>,
+        release_namespace: impl Into<String>,
+        take_ownership: bool,
+    ) -> Self {
+        Self {
+            release_name: release_name.into(),
+            release_namespace: release_namespace.into(),
+            take_ownership,
+            deployed: false,
+        }
+    }
+
+    /// Create an ownership check for an upgrade or rollback of a deployed release
+    pub fn deployed(release_name: impl Into<String>, release_namespace: impl Into<String>) -> Self {
+        Self {
+            deployed: true,
+            ..Self::new(release_name, release_namespace, false)
+        }
+    }
+
+    /// Whether applying over `existing` adopts it into the release
+    ///
+    /// Fails if it belongs to another release, or to no release while
+    /// `take_ownership` and `deployed` are both off.
+    fn claim(&self, existing: &DynamicObject, resource: &str) -> Result<bool> {
+        let owner = existing
+            .metadata
+            .labels
+            .as_ref()
+            .and_then(|labels| labels.get(RELEASE_NAME_LABEL));
+        let owner_namespace = existing
+            .metadata
+            .annotations
+            .as_ref()
+            .and_then(|annotations| annotations.get(RELEASE_NAMESPACE_ANNOTATION));
+
+        match (owner, owner_namespace) {
+            (Some(name), namespace)
+                if *name == self.release_name
+                    && namespace.is_none_or(|ns| *ns == self.release_namespace) =>
+            {
+                Ok(false)
+            }
+            (Some(name), namespace) => Err(KubeError::ResourceOwnedByRelease {
+                resource: resource.to_string(),
+                owner: match namespace {
+                    Some(ns) => format!("{}/{}", ns, name),
+                    None => name.clone(),
+                },
+            }),
+            (None, _) if self.deployed => Ok(false),
+            (None, _) if self.take_ownership => Ok(true),
+            (None, _) => Err(KubeError::ResourceNotOwned {
+                resource: resource.to_string(),
+            }),
+        }
+    }
+
+    /// Add the ownership label and annotation to an object to apply
+    ///
+    /// A `managed-by` label the manifest already sets is kept.
+    fn stamp(&self, obj: &mut DynamicObject) {
+        let labels = obj.metadata.labels.get_or_insert_with(Default::default);
+        labels
+            .entry(MANAGED_BY_LABEL.to_string())
+            .or_insert_with(|| MANAGED_BY_SHERPACK.to_string());
+        labels.insert(RELEASE_NAME_LABEL.to_string(), self.release_name.clone());
+        obj.metadata
+            .annotations
+            .get_or_insert_with(Default::default)
+            .insert(
+                RELEASE_NAMESPACE_ANNOTATION.to_string(),
+                self.release_namespace.clone(),
+            );
+    }
+}
+
+/// How [`ResourceManager`] resolves immutable-field conflicts
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImmutablePolicy {
+    /// What to do with a resource whose update changes an immutable field
+    pub strategy: ImmutableStrategy,
+    /// Allow `recreate` on stateful kinds (StatefulSet, PVC, PV)
+    pub recreate_stateful: bool,
+}
+
+/// An immutable-field conflict met while applying a resource
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImmutableConflict {
+    /// Fields the API server refused to change (e.g. `spec.clusterIP`)
+    pub fields: Vec<String>,
+    /// Action taken: [`ImmutableStrategy::Recreate`] or [`ImmutableStrategy::Skip`]
+    pub action: ImmutableStrategy,
+}
+
 /// Result of applying a single resource
 #[derive(Debug, Clone)]
 pub struct ApplyResult {
@@ -41,6 +174,14 @@ pub struct ApplyResult {
     pub namespace: Option<String>,
     /// Whether it was created (true) or updated (false)
     pub created: bool,
+    /// Whether an existing resource was adopted by the release
+    pub adopted: bool,
+    /// Immutable-field conflict and how it was resolved, if any
+    pub immutable_conflict: Option<ImmutableConflict>,
+    /// Object as returned by the API server, including server-side defaults
+    ///
+    /// For dry runs this is what the server *would* have persisted.
+    pub object: Option<DynamicObject>,
 }
 
 /// Result of deleting a single resource
@@ -54,6 +195,8 @@ pub struct DeleteResult {
     pub namespace: Option<String>,
     /// Whether it was actually deleted (false if skipped due to policy)
     pub deleted: bool,
+    /// Whether it was kept by `resource-policy: keep`
+    pub retained: bool,
     /// Reason if not deleted
     pub skip_reason: Option<String>,
 }
@@ -67,6 +210,16 @@ pub struct OperationSummary {
     pub failed: Vec<(String, String)>,
     /// Skipped resources (e.g., due to policy)
     pub skipped: Vec<(String, String)>,
+    /// Detailed results of successful applies, and of resources left
+    /// unchanged by [`ImmutableStrategy::Skip`]
+    pub applied: Vec<ApplyResult>,
+    /// Pre-existing resources adopted by the release (also in `succeeded`)
+    pub adopted: Vec<String>,
+    /// Resources left in place by `resource-policy: keep` on delete
+    pub retained: Vec<String>,
+    /// Applied resources the server changed from the desired state, with
+    /// the changed lines (see [`ResourceManager::with_apply_verification`])
+    pub warnings: Vec<(String, String)>,
 }
 
 impl OperationSummary {
@@ -77,21 +230,30 @@ impl OperationSummary {
 
     /// Get total count
     pub fn total(&self) -> usize {
-        self.succeeded.len() + self.failed.len() + self.skipped.len()
+        self.succeeded.len() + self.failed.len() + self.skipped.len() + self.retained.len()
     }
 
     /// Format as human-readable summary
     pub fn summary(&self) -> String {
-        let mut parts = Vec::with_capacity(3); // At most 3 parts
+        let mut parts = Vec::with_capacity(6); // At most 6 parts
         if !self.succeeded.is_empty() {
             parts.push(format!("{} succeeded", self.succeeded.len()));
         }
+        if !self.adopted.is_empty() {
+            parts.push(format!("{} adopted", self.adopted.len()));
+        }
         if !self.failed.is_empty() {
             parts.push(format!("{} failed", self.failed.len()));
         }
         if !self.skipped.is_empty() {
             parts.push(format!("{} skipped", self.skipped.len()));
         }
+        if !self.retained.is_empty() {
+            parts.push(format!("{} retained", self.retained.len()));
+        }
+        if !self.warnings.is_empty() {
+            parts.push(format!("{} mutated by the server", self.warnings.len()));
+        }
         if parts.is_empty() {
             "No resources processed".to_string()
         } else {
@@ -123,6 +285,16 @@ impl ParsedResource {
         }
     }
 
+    /// Wave this resource is deleted in
+    fn delete_wave(&self) -> i32 {
+        self.obj
+            .metadata
+            .annotations
+            .as_ref()
+            .map(annotations::parse_delete_wave)
+            .unwrap_or(0)
+    }
+
     /// Check if resource has keep policy annotation
     fn has_keep_policy(&self) -> bool {
         self.obj
@@ -150,6 +322,14 @@ pub struct ResourceManager {
     client: Client,
     /// Cached discovery information
     discovery: Discovery,
+    /// Release whose ownership of existing resources is checked on apply
+    ownership: Option<ReleaseOwnership>,
+    /// Resolution of immutable-field conflicts
+    immutable: ImmutablePolicy,
+    /// Delete from the highest delete wave down
+    ordered_delete: bool,
+    /// Compare applied resources with what the server persisted
+    verify_apply: bool,
 }
 
 impl ResourceManager {
@@ -160,12 +340,68 @@ impl ResourceManager {
             .await
             .map_err(KubeError::Api)?;
 
-        Ok(Self { client, discovery })
+        Ok(Self {
+            client,
+            discovery,
+            ownership: None,
+            immutable: ImmutablePolicy::default(),
+            ordered_delete: true,
+            verify_apply: false,
+        })
     }
 
     /// Create from existing client and discovery (for reuse)
     pub fn with_discovery(client: Client, discovery: Discovery) -> Self {
-        Self { client, discovery }
+        Self {
+            client,
+            discovery,
+            ownership: None,
+            immutable: ImmutablePolicy::default(),
+            ordered_delete: true,
+            verify_apply: false,
+        }
+    }
+
+    /// Apply resources on behalf of `ownership`'s release
+    ///
+    /// Every applied resource carries the release's ownership label and
+    /// annotation. Applying over a resource of another release fails. A
+    /// resource that no release manages fails too, unless `take_ownership`
+    /// is set, in which case it is adopted and listed in
+    /// [`OperationSummary::adopted`], or the release is `deployed`.
+    pub fn with_ownership(mut self, ownership: ReleaseOwnership) -> Self {
+        self.ownership = Some(ownership);
+        self
+    }
+
+    /// Resolve immutable-field conflicts with `policy` instead of failing
+    ///
+    /// `recreate` deletes the resource and applies it again; stateful kinds
+    /// are only recreated when `policy.recreate_stateful` is set. `skip`
+    /// leaves the resource unchanged and reports it as skipped.
+    pub fn with_immutable_policy(mut self, policy: ImmutablePolicy) -> Self {
+        self.immutable = policy;
+        self
+    }
+
+    /// Delete resources by wave, highest first (the default)
+    ///
+    /// Waves come from `sherpack.io/delete-wave`, falling back to
+    /// `sherpack.io/sync-wave`. When disabled, only the resource category
+    /// decides the deletion order.
+    pub fn with_ordered_delete(mut self, ordered: bool) -> Self {
+        self.ordered_delete = ordered;
+        self
+    }
+
+    /// Fetch each resource after applying it and report server-side mutations
+    ///
+    /// Fields set in the manifest that the server changed (an admission
+    /// webhook rewriting the spec, an injected sidecar) are listed in
+    /// [`OperationSummary::warnings`]. Dry runs are not verified.
+    pub fn with_apply_verification(mut self, verify: bool) -> Self {
+        self.verify_apply = verify;
+        self
     }
 
     /// Refresh discovery cache (call after CRD changes)
@@ -182,7 +418,12 @@ impl ResourceManager {
     /// # Arguments
     /// * `namespace` - Default namespace for namespaced resources without explicit namespace
     /// * `manifest` - YAML manifest (can contain multiple documents separated by ---)
-    /// * `dry_run` - If true, validate without applying
+    /// * `dry_run` - If true, the server validates the apply (`dryRun=All`)
+    ///   without persisting anything
+    ///
+    /// In dry-run mode, resources the server cannot validate yet (custom
+    /// resources whose CRD is not installed, objects in a namespace that
+    /// does not exist) are reported as skipped instead of failing.
     ///
     /// # Returns
     /// Summary of applied resources
@@ -192,8 +433,13 @@ impl ResourceManager {
         manifest: &str,
         dry_run: bool,
     ) -> Result<OperationSummary> {
-        let resources = self.parse_manifest(manifest, namespace)?;
-        self.apply_resources(&resources, dry_run).await
+        let mut skipped = Vec::new();
+        let resources =
+            self.parse_manifest(manifest, namespace, dry_run.then_some(&mut skipped))?;
+
+        let mut summary = self.apply_resources(&resources, dry_run).await?;
+        summary.skipped.extend(skipped);
+        Ok(summary)
     }
 
     /// Delete resources from a manifest
@@ -211,15 +457,19 @@ impl ResourceManager {
         manifest: &str,
         dry_run: bool,
     ) -> Result<OperationSummary> {
-        let resources = self.parse_manifest(manifest, namespace)?;
+        let resources = self.parse_manifest(manifest, namespace, None)?;
         self.delete_resources(&resources, dry_run).await
     }
 
     /// Parse a YAML manifest into ParsedResource list
+    ///
+    /// When `unknown` is given, documents of a type the cluster does not
+    /// serve are recorded there instead of failing the whole manifest.
     fn parse_manifest(
         &self,
         manifest: &str,
         default_namespace: &str,
+        mut unknown: Option<&mut Vec<(String, String)>>,
     ) -> Result<Vec<ParsedResource>> {
         let mut resources = Vec::new();
 
@@ -239,6 +489,21 @@ impl ResourceManager {
 
             match self.parse_single_document(doc, default_namespace) {
                 Ok(resource) => resources.push(resource),
+                Err(KubeError::UnknownResourceType {
+                    api_version,
+                    kind,
+                    name,
+                }) if unknown.is_some() => {
+                    if let Some(unknown) = unknown.as_deref_mut() {
+                        unknown.push((
+                            format!("{}/{}", kind, name),
+                            format!(
+                                "{} {} is not served by the cluster yet (CRD not installed?); not validated",
+                                api_version, kind
+                            ),
+                        ));
+                    }
+                }
                 Err(e) => {
                     // Include document index in error for debugging
                     return Err(KubeError::InvalidConfig(format!(
@@ -267,17 +532,26 @@ impl ResourceManager {
         let gvk = gvk_from_type_meta(type_meta);
 
         // Resolve GVK to ApiResource using discovery
-        let (api_resource, capabilities) = self.discovery.resolve_gvk(&gvk).ok_or_else(|| {
-            KubeError::InvalidConfig(format!(
-                "Unknown resource type: {}/{}",
-                type_meta.api_version, type_meta.kind
-            ))
-        })?;
+        let (api_resource, capabilities) =
+            self.discovery
+                .resolve_gvk(&gvk)
+                .ok_or_else(|| KubeError::UnknownResourceType {
+                    api_version: type_meta.api_version.clone(),
+                    kind: type_meta.kind.clone(),
+                    name: obj
+                        .metadata
+                        .name
+                        .clone()
+                        .unwrap_or_else(|| "unnamed".to_string()),
+                })?;
 
         // Apply default namespace for namespaced resources
         if capabilities.scope == Scope::Namespaced && obj.metadata.namespace.is_none() {
             obj.metadata.namespace = Some(default_namespace.to_string());
         }
+        if let Some(ownership) = &self.ownership {
+            ownership.stamp(&mut obj);
+        }
 
         Ok(ParsedResource {
             obj,
@@ -303,12 +577,51 @@ impl ResourceManager {
 
             match self.apply_single_resource(resource, dry_run).await {
                 Ok(result) => {
-                    let action = if result.created {
+                    if let Some(conflict) = result
+                        .immutable_conflict
+                        .as_ref()
+                        .filter(|c| c.action == ImmutableStrategy::Skip)
+                    {
+                        summary.skipped.push((
+                            name,
+                            format!(
+                                "immutable field(s) {} left unchanged",
+                                conflict.fields.join(", ")
+                            ),
+                        ));
+                        summary.applied.push(result);
+                        continue;
+                    }
+
+                    let action = if result.immutable_conflict.is_some() {
+                        "recreated"
+                    } else if result.created {
                         "created"
+                    } else if result.adopted {
+                        "adopted"
                     } else {
                         "configured"
                     };
                     summary.succeeded.push(format!("{} ({})", name, action));
+                    if self.verify_apply && !dry_run {
+                        match self.verify_resource(resource).await {
+                            Ok(Some(changes)) => summary.warnings.push((name.clone(), changes)),
+                            Ok(None) => {}
+                            Err(e) => summary
+                                .warnings
+                                .push((name.clone(), format!("could not verify: {}", e))),
+                        }
+                    }
+                    if result.adopted {
+                        summary.adopted.push(name);
+                    }
+                    summary.applied.push(result);
+                }
+                // A dry run can't create missing namespaces or CRDs first
+                Err(KubeError::Api(kube::Error::Api(resp))) if dry_run && resp.code == 404 => {
+                    summary
+                        .skipped
+                        .push((name, format!("not validated: {}", resp.message)));
                 }
                 Err(e) => {
                     summary.failed.push((name, e.to_string()));
@@ -332,7 +645,15 @@ impl ResourceManager {
         let api = self.api_for_resource(resource);
 
         // Check if resource exists (to determine created vs updated)
-        let exists = api.get_opt(name).await.map_err(KubeError::Api)?.is_some();
+        let existing = api.get_opt(name).await.map_err(KubeError::Api)?;
+        let exists = existing.is_some();
+
+        let adopt = match (&self.ownership, &existing) {
+            (Some(ownership), Some(existing)) => {
+                ownership.claim(existing, &resource.display_name())?
+            }
+            _ => false,
+        };
 
         // Build patch params for Server-Side Apply
         let mut params = PatchParams::apply(FIELD_MANAGER);
@@ -343,22 +664,146 @@ impl ResourceManager {
         }
 
         // Perform Server-Side Apply
-        let _result = api
-            .patch(name, &params, &Patch::Apply(&resource.obj))
-            .await
-            .map_err(|e| {
-                KubeError::InvalidConfig(format!(
-                    "Failed to apply {}: {}",
-                    resource.display_name(),
-                    e
-                ))
-            })?;
+        let applied = api.patch(name, &params, &Patch::Apply(&resource.obj)).await;
+        let fields = match &applied {
+            Err(kube::Error::Api(resp)) if exists && resp.code == 422 => {
+                immutable_fields(&resp.message)
+            }
+            _ => Vec::new(),
+        };
+        if !fields.is_empty() {
+            let mut result = self
+                .resolve_immutable_conflict(&api, resource, &params, fields, dry_run)
+                .await?;
+            result.adopted = adopt;
+            return Ok(result);
+        }
+
+        let object = applied.map_err(|e| match e {
+            kube::Error::Api(ref resp) if dry_run && resp.code == 404 => KubeError::Api(e),
+            e => KubeError::InvalidConfig(format!(
+                "Failed to apply {}: {}",
+                resource.display_name(),
+                e
+            )),
+        })?;
 
         Ok(ApplyResult {
             kind: resource.gvk.kind.clone(),
             name: name.to_string(),
             namespace: resource.obj.metadata.namespace.clone(),
             created: !exists,
+            adopted: adopt,
+            immutable_conflict: None,
+            object: Some(object),
+        })
+    }
+
+    /// Diff an applied resource against its live state
+    ///
+    /// Returns the changed lines, or `None` when the server kept the
+    /// desired state.
+    async fn verify_resource(&self, resource: &ParsedResource) -> Result<Option<String>> {
+        let name = resource.obj.metadata.name.as_deref().unwrap_or_default();
+        let live = self
+            .api_for_resource(resource)
+            .get(name)
+            .await
+            .map_err(KubeError::Api)?;
+
+        let to_yaml = |obj: &DynamicObject| serde_yaml::to_string(obj).map_err(KubeError::from);
+        let diff = DiffEngine::new().verify_applied(&to_yaml(&resource.obj)?, &to_yaml(&live)?);
+
+        Ok(diff.map(|diff| {
+            diff.lines
+                .iter()
+                .filter(|line| line.line_type != LineType::Context)
+                .map(|line| match line.line_type {
+                    LineType::Added => format!("+{}", line.content),
+                    _ => format!("-{}", line.content),
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        }))
+    }
+
+    /// Act on an update rejected for changing immutable `fields`
+    async fn resolve_immutable_conflict(
+        &self,
+        api: &Api<DynamicObject>,
+        resource: &ParsedResource,
+        params: &PatchParams,
+        fields: Vec<String>,
+        dry_run: bool,
+    ) -> Result<ApplyResult> {
+        let resource_name = resource.display_name();
+        let name = resource.obj.metadata.name.as_deref().unwrap_or_default();
+        let kind = resource.gvk.kind.as_str();
+
+        let object = match self.immutable.strategy {
+            ImmutableStrategy::Fail => {
+                return Err(KubeError::ImmutableFieldConflict {
+                    resource: resource_name,
+                    field: fields.join(", "),
+                });
+            }
+            ImmutableStrategy::Skip => {
+                tracing::warn!(
+                    "{}: immutable field(s) {} changed, leaving the resource unchanged",
+                    resource_name,
+                    fields.join(", ")
+                );
+                None
+            }
+            ImmutableStrategy::Recreate => {
+                if STATEFUL_KINDS.contains(&kind) && !self.immutable.recreate_stateful {
+                    return Err(KubeError::StatefulRecreateNotConfirmed {
+                        resource: resource_name,
+                    });
+                }
+
+                let delete_params = DeleteParams {
+                    propagation_policy: Some(kube::api::PropagationPolicy::Background),
+                    dry_run,
+                    ..Default::default()
+                };
+                api.delete(name, &delete_params).await.map_err(|e| {
+                    KubeError::InvalidConfig(format!(
+                        "Failed to delete {} to recreate it: {}",
+                        resource_name, e
+                    ))
+                })?;
+
+                // A dry run can't validate the creation of an object that still exists
+                if dry_run {
+                    None
+                } else {
+                    wait_until_deleted(api, name, &resource_name).await?;
+                    let object = api
+                        .patch(name, params, &Patch::Apply(&resource.obj))
+                        .await
+                        .map_err(|e| {
+                            KubeError::InvalidConfig(format!(
+                                "Failed to recreate {}: {}",
+                                resource_name, e
+                            ))
+                        })?;
+                    Some(object)
+                }
+            }
+        };
+
+        Ok(ApplyResult {
+            kind: kind.to_string(),
+            name: name.to_string(),
+            namespace: resource.obj.metadata.namespace.clone(),
+            created: false,
+            adopted: false,
+            immutable_conflict: Some(ImmutableConflict {
+                fields,
+                action: self.immutable.strategy,
+            }),
+            object,
         })
     }
 
@@ -376,17 +821,11 @@ impl ResourceManager {
         for resource in sorted {
             let name = resource.display_name();
 
-            // Check for keep policy
-            if resource.has_keep_policy() {
-                summary
-                    .skipped
-                    .push((name, "resource-policy: keep".to_string()));
-                continue;
-            }
-
             match self.delete_single_resource(resource, dry_run).await {
                 Ok(result) => {
-                    if result.deleted {
+                    if result.retained {
+                        summary.retained.push(name);
+                    } else if result.deleted {
                         summary.succeeded.push(format!("{} (deleted)", name));
                     } else if let Some(reason) = result.skip_reason {
                         summary.skipped.push((name, reason));
@@ -416,6 +855,18 @@ impl ResourceManager {
             KubeError::InvalidConfig("Resource missing metadata.name".to_string())
         })?;
 
+        // Kept resources outlive the release
+        if resource.has_keep_policy() {
+            return Ok(DeleteResult {
+                kind: resource.gvk.kind.clone(),
+                name: name.to_string(),
+                namespace: resource.obj.metadata.namespace.clone(),
+                deleted: false,
+                retained: true,
+                skip_reason: Some("resource-policy: keep".to_string()),
+            });
+        }
+
         let api = self.api_for_resource(resource);
 
         // Build delete params
@@ -432,6 +883,7 @@ impl ResourceManager {
                 name: name.to_string(),
                 namespace: resource.obj.metadata.namespace.clone(),
                 deleted: true,
+                retained: false,
                 skip_reason: None,
             }),
             Err(kube::Error::Api(resp)) if resp.code == 404 => Ok(DeleteResult {
@@ -439,6 +891,7 @@ impl ResourceManager {
                 name: name.to_string(),
                 namespace: resource.obj.metadata.namespace.clone(),
                 deleted: false,
+                retained: false,
                 skip_reason: Some("not found".to_string()),
             }),
             Err(e) => Err(KubeError::Api(e)),
@@ -476,24 +929,23 @@ impl ResourceManager {
     /// Sort resources for deletion (reverse of creation order)
     fn sort_for_delete<'a>(&self, resources: &'a [ParsedResource]) -> Vec<&'a ParsedResource> {
         let mut sorted: Vec<&ParsedResource> = resources.iter().collect();
-        sorted.sort_by(|a, b| {
-            let cat_a = ResourceCategory::from_resource(
-                &a.gvk.kind,
-                a.obj
+        sorted.sort_by_key(|resource| {
+            let wave = if self.ordered_delete {
+                resource.delete_wave()
+            } else {
+                0
+            };
+            let category = ResourceCategory::from_resource(
+                &resource.gvk.kind,
+                resource
+                    .obj
                     .types
                     .as_ref()
                     .map(|t| t.api_version.as_str())
                     .unwrap_or("v1"),
             );
-            let cat_b = ResourceCategory::from_resource(
-                &b.gvk.kind,
-                b.obj
-                    .types
-                    .as_ref()
-                    .map(|t| t.api_version.as_str())
-                    .unwrap_or("v1"),
-            );
-            cat_b.cmp(&cat_a) // Reverse order
+            // Reverse order
+            (Reverse(wave), Reverse(category))
         });
         sorted
     }
@@ -528,6 +980,61 @@ impl ResourceManager {
     }
 }
 
+/// Poll until `name` is gone, so that it can be created again
+async fn wait_until_deleted(
+    api: &Api<DynamicObject>,
+    name: &str,
+    resource_name: &str,
+) -> Result<()> {
+    let deadline = tokio::time::Instant::now() + RECREATE_DELETE_TIMEOUT;
+    while api.get_opt(name).await.map_err(KubeError::Api)?.is_some() {
+        if tokio::time::Instant::now() >= deadline {
+            return Err(KubeError::Timeout(format!(
+                "{} still exists {:?} after deleting it to recreate it",
+                resource_name, RECREATE_DELETE_TIMEOUT
+            )));
+        }
+        tokio::time::sleep(RECREATE_POLL_INTERVAL).await;
+    }
+    Ok(())
+}
+
+/// Fields an API server rejection names as immutable
+///
+/// Parses the message of a 422 `Invalid` response, such as
+/// `Service "web" is invalid: spec.clusterIP: Invalid value: "10.0.0.1": field is immutable`.
+/// Several errors are listed in brackets, comma-separated; only those about
+/// immutability are kept. Returns an empty list for any other rejection.
+fn immutable_fields(message: &str) -> Vec<String> {
+    const MARKERS: &[&str] = &[": Invalid value: ", ": Forbidden: "];
+
+    // Start of each `<field>: <error type>: ` error, with its field
+    let mut errors: Vec<(usize, &str)> = MARKERS
+        .iter()
+        .flat_map(|marker| message.match_indices(marker))
+        .filter_map(|(pos, _)| {
+            let start = message[..pos]
+                .rfind(|c: char| !(c.is_ascii_alphanumeric() || "._-[]".contains(c)))
+                .map_or(0, |i| i + 1);
+            // Skip the `[` opening a list of errors
+            let field = message[start..pos].trim_start_matches('[');
+            (!field.is_empty()).then_some((pos, field))
+        })
+        .collect();
+    errors.sort_by_key(|(pos, _)| *pos);
+
+    let mut fields = Vec::new();
+    for (i, (pos, field)) in errors.iter().enumerate() {
+        let end = errors.get(i + 1).map_or(message.len(), |(next, _)| *next);
+        let detail = &message[*pos..end];
+        let immutable = detail.contains("immutable") || detail.contains("Forbidden: updates to");
+        if immutable && !fields.iter().any(|f| f == field) {
+            fields.push(field.to_string());
+        }
+    }
+    fields
+}
+
 /// Convert TypeMeta to GroupVersionKind
 ///
 /// This function parses the apiVersion field to extract group and version:
@@ -682,6 +1189,9 @@ mod tests {
             name: "my-app".to_string(),
             namespace: Some("default".to_string()),
             created: true,
+            adopted: false,
+            immutable_conflict: None,
+            object: None,
         };
 
         assert!(result.created);
@@ -696,6 +1206,9 @@ mod tests {
             name: "config".to_string(),
             namespace: Some("default".to_string()),
             created: false,
+            adopted: false,
+            immutable_conflict: None,
+            object: None,
         };
 
         assert!(!result.created);
@@ -708,6 +1221,7 @@ mod tests {
             name: "old-pod".to_string(),
             namespace: Some("default".to_string()),
             deleted: true,
+            retained: false,
             skip_reason: None,
         };
 
@@ -722,10 +1236,12 @@ mod tests {
             name: "important-secret".to_string(),
             namespace: Some("default".to_string()),
             deleted: false,
+            retained: true,
             skip_reason: Some("resource-policy: keep".to_string()),
         };
 
         assert!(!result.deleted);
+        assert!(result.retained);
         assert_eq!(
             result.skip_reason,
             Some("resource-policy: keep".to_string())
@@ -739,6 +1255,7 @@ mod tests {
             name: "admin-role".to_string(),
             namespace: None,
             deleted: true,
+            retained: false,
             skip_reason: None,
         };
 
@@ -753,6 +1270,810 @@ mod tests {
         assert_eq!(RESOURCE_POLICY_KEEP, "keep");
     }
 
+    /// Manager whose discovery knows no types, backed by a client that
+    /// must never be called
+    fn undiscovered_manager() -> ResourceManager {
+        let (service, _handle) = tower_test::mock::pair::<
+            http::Request<kube::client::Body>,
+            http::Response<kube::client::Body>,
+        >();
+        let client = Client::new(service, "default");
+        ResourceManager::with_discovery(client.clone(), Discovery::new(client))
+    }
+
+    const WIDGET: &str = r#"
+apiVersion: example.com/v1
+kind: Widget
+metadata:
+  name: gadget
+"#;
+
+    fn parsed(api_version: &str, kind: &str, name: &str, wave: Option<&str>) -> ParsedResource {
+        let mut doc = serde_json::json!({
+            "apiVersion": api_version,
+            "kind": kind,
+            "metadata": {"name": name}
+        });
+        if let Some(wave) = wave {
+            doc["metadata"]["annotations"] = serde_json::json!({"sherpack.io/sync-wave": wave});
+        }
+        let obj: DynamicObject = serde_json::from_value(doc).unwrap();
+        let gvk = gvk_from_type_meta(obj.types.as_ref().unwrap());
+
+        ParsedResource {
+            api_resource: ApiResource::from_gvk(&gvk),
+            gvk,
+            obj,
+            capabilities: ApiCapabilities {
+                scope: Scope::Namespaced,
+                subresources: vec![],
+                operations: vec![],
+            },
+        }
+    }
+
+    fn delete_order(manager: &ResourceManager, resources: &[ParsedResource]) -> Vec<String> {
+        manager
+            .sort_for_delete(resources)
+            .iter()
+            .map(|r| r.display_name())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_delete_higher_waves_first() {
+        let crd = "apiextensions.k8s.io/v1";
+        let resources = vec![
+            parsed(crd, "CustomResourceDefinition", "widgets.example.com", None),
+            parsed("v1", "ConfigMap", "settings", Some("1")),
+            parsed("example.com/v1", "Widget", "gadget", Some("2")),
+            parsed("v1", "Service", "web", Some("1")),
+        ];
+
+        let manager = undiscovered_manager();
+        assert_eq!(
+            delete_order(&manager, &resources),
+            [
+                "Widget/gadget",
+                "Service/web",
+                "ConfigMap/settings",
+                "CustomResourceDefinition/widgets.example.com",
+            ]
+        );
+
+        // A delete-wave overrides the sync wave
+        let mut resources = resources;
+        resources[0]
+            .obj
+            .metadata
+            .annotations
+            .get_or_insert_default()
+            .insert("sherpack.io/delete-wave".to_string(), "3".to_string());
+        assert_eq!(
+            delete_order(&manager, &resources)[0],
+            "CustomResourceDefinition/widgets.example.com"
+        );
+
+        // Without waves only the category decides
+        let manager = undiscovered_manager().with_ordered_delete(false);
+        assert_eq!(
+            delete_order(&manager, &resources),
+            [
+                "Widget/gadget",
+                "Service/web",
+                "ConfigMap/settings",
+                "CustomResourceDefinition/widgets.example.com",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_skips_types_without_crd() {
+        let manager = undiscovered_manager();
+
+        let summary = manager
+            .apply_manifest("default", WIDGET, true)
+            .await
+            .unwrap();
+
+        assert!(summary.is_success());
+        assert!(summary.applied.is_empty());
+        assert_eq!(summary.skipped.len(), 1);
+        assert_eq!(summary.skipped[0].0, "Widget/gadget");
+        assert!(summary.skipped[0].1.contains("CRD not installed"));
+    }
+
+    #[tokio::test]
+    async fn test_apply_fails_on_types_without_crd() {
+        let manager = undiscovered_manager();
+
+        let err = manager
+            .apply_manifest("default", WIDGET, false)
+            .await
+            .unwrap_err();
+
+        let message = err.to_string();
+        assert!(message.contains("example.com/v1/Widget"));
+        assert!(message.contains("is its CRD installed?"));
+    }
+
+    fn config_map(labels: serde_json::Value) -> DynamicObject {
+        serde_json::from_value(serde_json::json!({
+            "apiVersion": "v1",
+            "kind": "ConfigMap",
+            "metadata": {"name": "web-config", "namespace": "default", "labels": labels}
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_ownership_claim() {
+        let ownership = ReleaseOwnership::new("web", "default", false);
+        let resource = "default/ConfigMap/web-config";
+
+        // Already owned by this release
+        let owned = config_map(serde_json::json!({"sherpack.io/release-name": "web"}));
+        assert!(!ownership.claim(&owned, resource).unwrap());
+
+        // Owned by another release, even with --take-ownership
+        let other = config_map(serde_json::json!({"sherpack.io/release-name": "api"}));
+        for take_ownership in [false, true] {
+            let ownership = ReleaseOwnership::new("web", "default", take_ownership);
+            let err = ownership.claim(&other, resource).unwrap_err();
+            assert!(matches!(
+                err,
+                KubeError::ResourceOwnedByRelease { ref owner, .. } if owner == "api"
+            ));
+        }
+
+        // Unmanaged: adopted only with --take-ownership
+        let unmanaged = config_map(serde_json::json!({"app": "web"}));
+        let err = ownership.claim(&unmanaged, resource).unwrap_err();
+        assert!(err.to_string().contains("--take-ownership"));
+        let ownership = ReleaseOwnership::new("web", "default", true);
+        assert!(ownership.claim(&unmanaged, resource).unwrap());
+
+        // A deployed release owns what it applied before it was stamped
+        let ownership = ReleaseOwnership::deployed("web", "default");
+        assert!(!ownership.claim(&unmanaged, resource).unwrap());
+        assert!(ownership.claim(&other, resource).is_err());
+    }
+
+    #[test]
+    fn test_ownership_claim_checks_release_namespace() {
+        let ownership = ReleaseOwnership::new("web", "staging", true);
+        let mut existing = config_map(serde_json::json!({"sherpack.io/release-name": "web"}));
+        existing.metadata.annotations = Some(
+            [(
+                RELEASE_NAMESPACE_ANNOTATION.to_string(),
+                "default".to_string(),
+            )]
+            .into(),
+        );
+
+        let err = ownership
+            .claim(&existing, "default/ConfigMap/web-config")
+            .unwrap_err();
+        assert!(err.to_string().contains("'default/web'"));
+    }
+
+    /// Serve discovery for ConfigMaps, an existing unmanaged `web-config`
+    /// and patches, returning `(method, content type, body)` of each patch
+    fn spawn_adoption_server(
+        mut handle: tower_test::mock::Handle<
+            http::Request<kube::client::Body>,
+            http::Response<kube::client::Body>,
+        >,
+    ) -> tokio::task::JoinHandle<Vec<(String, serde_json::Value)>> {
+        tokio::spawn(async move {
+            let mut patches = Vec::new();
+
+            while let Some((request, send)) = handle.next_request().await {
+                let path = request.uri().path().to_string();
+                let content_type = request
+                    .headers()
+                    .get(http::header::CONTENT_TYPE)
+                    .and_then(|v| v.to_str().ok())
+                    .unwrap_or_default()
+                    .to_string();
+                let is_patch = request.method() == http::Method::PATCH;
+                let body = request.into_body().collect_bytes().await.unwrap();
+
+                let response = match path.as_str() {
+                    "/api" => serde_json::json!({
+                        "kind": "APIVersions",
+                        "versions": ["v1"],
+                        "serverAddressByClientCIDRs": []
+                    }),
+                    "/api/v1" => serde_json::json!({
+                        "kind": "APIResourceList",
+                        "groupVersion": "v1",
+                        "resources": [{
+                            "name": "configmaps",
+                            "singularName": "configmap",
+                            "namespaced": true,
+                            "kind": "ConfigMap",
+                            "verbs": ["get", "list", "create", "update", "patch", "delete"]
+                        }]
+                    }),
+                    "/apis" => {
+                        serde_json::json!({"kind": "APIGroupList", "apiVersion": "v1", "groups": []})
+                    }
+                    _ => {
+                        if is_patch {
+                            patches.push((content_type, serde_json::from_slice(&body).unwrap()));
+                        }
+                        serde_json::to_value(config_map(serde_json::json!({"app": "web"}))).unwrap()
+                    }
+                };
+
+                send.send_response(
+                    http::Response::builder()
+                        .body(kube::client::Body::from(
+                            serde_json::to_vec(&response).unwrap(),
+                        ))
+                        .unwrap(),
+                );
+            }
+
+            patches
+        })
+    }
+
+    const WEB_CONFIG: &str = r#"
+apiVersion: v1
+kind: ConfigMap
+metadata:
+  name: web-config
+data:
+  key: value
+"#;
+
+    /// Serve discovery for ConfigMaps and a webhook that rewrites `data`
+    /// of every applied ConfigMap
+    fn spawn_mutating_server(
+        mut handle: tower_test::mock::Handle<
+            http::Request<kube::client::Body>,
+            http::Response<kube::client::Body>,
+        >,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut created = false;
+
+            while let Some((request, send)) = handle.next_request().await {
+                let path = request.uri().path().to_string();
+                let is_patch = request.method() == http::Method::PATCH;
+
+                let mutated = serde_json::json!({
+                    "apiVersion": "v1",
+                    "kind": "ConfigMap",
+                    "metadata": {"name": "web-config", "namespace": "default", "uid": "1"},
+                    "data": {"key": "rewritten", "injected": "true"}
+                });
+                let (status, response) = match path.as_str() {
+                    "/api" => (
+                        200,
+                        serde_json::json!({
+                            "kind": "APIVersions",
+                            "versions": ["v1"],
+                            "serverAddressByClientCIDRs": []
+                        }),
+                    ),
+                    "/api/v1" => (
+                        200,
+                        serde_json::json!({
+                            "kind": "APIResourceList",
+                            "groupVersion": "v1",
+                            "resources": [{
+                                "name": "configmaps",
+                                "singularName": "configmap",
+                                "namespaced": true,
+                                "kind": "ConfigMap",
+                                "verbs": ["get", "list", "create", "update", "patch", "delete"]
+                            }]
+                        }),
+                    ),
+                    "/apis" => (
+                        200,
+                        serde_json::json!({"kind": "APIGroupList", "apiVersion": "v1", "groups": []}),
+                    ),
+                    _ if is_patch => {
+                        created = true;
+                        (200, mutated)
+                    }
+                    _ if created => (200, mutated),
+                    _ => (
+                        404,
+                        serde_json::json!({
+                            "apiVersion": "v1",
+                            "kind": "Status",
+                            "status": "Failure",
+                            "reason": "NotFound",
+                            "code": 404
+                        }),
+                    ),
+                };
+
+                send.send_response(
+                    http::Response::builder()
+                        .status(status)
+                        .body(kube::client::Body::from(
+                            serde_json::to_vec(&response).unwrap(),
+                        ))
+                        .unwrap(),
+                );
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_apply_verification_reports_webhook_mutation() {
+        let (service, handle) = tower_test::mock::pair::<
+            http::Request<kube::client::Body>,
+            http::Response<kube::client::Body>,
+        >();
+        let server = spawn_mutating_server(handle);
+
+        let manager = ResourceManager::new(Client::new(service, "default"))
+            .await
+            .unwrap()
+            .with_apply_verification(true);
+        let summary = manager
+            .apply_manifest("default", WEB_CONFIG, false)
+            .await
+            .unwrap();
+        drop(manager);
+        server.await.unwrap();
+
+        assert!(summary.is_success(), "{:?}", summary.failed);
+        assert_eq!(summary.warnings.len(), 1);
+        let (name, changes) = &summary.warnings[0];
+        assert_eq!(name, "default/ConfigMap/web-config");
+        assert!(changes.contains("-  key: value"), "{changes}");
+        assert!(changes.contains("+  key: rewritten"), "{changes}");
+        // Fields the manifest doesn't set are not reported
+        assert!(!changes.contains("injected"), "{changes}");
+        assert!(summary.summary().contains("1 mutated by the server"));
+    }
+
+    #[tokio::test]
+    async fn test_apply_adopts_unmanaged_resource() {
+        let (service, handle) = tower_test::mock::pair::<
+            http::Request<kube::client::Body>,
+            http::Response<kube::client::Body>,
+        >();
+        let server = spawn_adoption_server(handle);
+
+        let manager = ResourceManager::new(Client::new(service, "default"))
+            .await
+            .unwrap()
+            .with_ownership(ReleaseOwnership::new("web", "default", true));
+        let summary = manager
+            .apply_manifest("default", WEB_CONFIG, false)
+            .await
+            .unwrap();
+        drop(manager);
+        let patches = server.await.unwrap();
+
+        assert!(summary.is_success(), "{:?}", summary.failed);
+        assert_eq!(summary.adopted, ["default/ConfigMap/web-config"]);
+        assert!(summary.applied[0].adopted);
+        assert!(summary.summary().contains("1 adopted"));
+
+        // The server-side apply carries the ownership metadata
+        assert_eq!(patches.len(), 1);
+        assert_eq!(patches[0].0, "application/apply-patch+yaml");
+        assert_eq!(
+            patches[0].1["metadata"]["labels"]["sherpack.io/release-name"],
+            "web"
+        );
+        assert_eq!(
+            patches[0].1["metadata"]["annotations"]["sherpack.io/release-namespace"],
+            "default"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_apply_refuses_unmanaged_resource_without_take_ownership() {
+        let (service, handle) = tower_test::mock::pair::<
+            http::Request<kube::client::Body>,
+            http::Response<kube::client::Body>,
+        >();
+        let server = spawn_adoption_server(handle);
+
+        let manager = ResourceManager::new(Client::new(service, "default"))
+            .await
+            .unwrap()
+            .with_ownership(ReleaseOwnership::new("web", "default", false));
+        let summary = manager
+            .apply_manifest("default", WEB_CONFIG, false)
+            .await
+            .unwrap();
+        drop(manager);
+        let patches = server.await.unwrap();
+
+        assert!(patches.is_empty());
+        assert!(summary.adopted.is_empty());
+        assert_eq!(summary.failed.len(), 1);
+        assert!(summary.failed[0].1.contains("not managed by Sherpack"));
+    }
+
+    /// Serve discovery for ConfigMaps and keep what is applied, like a cluster
+    fn spawn_stateful_server(
+        mut handle: tower_test::mock::Handle<
+            http::Request<kube::client::Body>,
+            http::Response<kube::client::Body>,
+        >,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut stored: Option<serde_json::Value> = None;
+
+            while let Some((request, send)) = handle.next_request().await {
+                let path = request.uri().path().to_string();
+                let is_patch = request.method() == http::Method::PATCH;
+                let body = request.into_body().collect_bytes().await.unwrap();
+
+                let (status, response) = match path.as_str() {
+                    "/api" => (
+                        200,
+                        serde_json::json!({
+                            "kind": "APIVersions",
+                            "versions": ["v1"],
+                            "serverAddressByClientCIDRs": []
+                        }),
+                    ),
+                    "/api/v1" => (
+                        200,
+                        serde_json::json!({
+                            "kind": "APIResourceList",
+                            "groupVersion": "v1",
+                            "resources": [{
+                                "name": "configmaps",
+                                "singularName": "configmap",
+                                "namespaced": true,
+                                "kind": "ConfigMap",
+                                "verbs": ["get", "list", "create", "update", "patch", "delete"]
+                            }]
+                        }),
+                    ),
+                    "/apis" => (
+                        200,
+                        serde_json::json!({"kind": "APIGroupList", "apiVersion": "v1", "groups": []}),
+                    ),
+                    _ if is_patch => {
+                        let applied: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                        stored = Some(applied.clone());
+                        (200, applied)
+                    }
+                    _ => match &stored {
+                        Some(object) => (200, object.clone()),
+                        None => (
+                            404,
+                            serde_json::json!({
+                                "apiVersion": "v1",
+                                "kind": "Status",
+                                "status": "Failure",
+                                "reason": "NotFound",
+                                "code": 404
+                            }),
+                        ),
+                    },
+                };
+
+                send.send_response(
+                    http::Response::builder()
+                        .status(status)
+                        .body(kube::client::Body::from(
+                            serde_json::to_vec(&response).unwrap(),
+                        ))
+                        .unwrap(),
+                );
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_releases_sharing_a_resource_name() {
+        let (service, handle) = tower_test::mock::pair::<
+            http::Request<kube::client::Body>,
+            http::Response<kube::client::Body>,
+        >();
+        let server = spawn_stateful_server(handle);
+        let client = Client::new(service, "default");
+        let apply = |ownership: ReleaseOwnership| {
+            let client = client.clone();
+            async move {
+                ResourceManager::new(client)
+                    .await
+                    .unwrap()
+                    .with_ownership(ownership)
+                    .apply_manifest("default", WEB_CONFIG, false)
+                    .await
+                    .unwrap()
+            }
+        };
+
+        // Release api creates web-config
+        let summary = apply(ReleaseOwnership::new("api", "default", false)).await;
+        assert_eq!(
+            summary.succeeded,
+            ["default/ConfigMap/web-config (created)"]
+        );
+
+        // Release web may not take it over, even with --take-ownership
+        let summary = apply(ReleaseOwnership::new("web", "default", true)).await;
+        assert!(summary.adopted.is_empty());
+        assert_eq!(summary.failed.len(), 1);
+        assert!(
+            summary.failed[0]
+                .1
+                .contains("managed by release 'default/api'"),
+            "{}",
+            summary.failed[0].1
+        );
+
+        // Installing api again finds its own resource (e.g. a kept PVC)
+        let summary = apply(ReleaseOwnership::new("api", "default", false)).await;
+        assert!(summary.is_success(), "{:?}", summary.failed);
+        assert!(summary.adopted.is_empty());
+
+        drop(client);
+        server.await.unwrap();
+    }
+
+    #[test]
+    fn test_immutable_fields() {
+        assert_eq!(
+            immutable_fields(
+                r#"Service "web" is invalid: spec.clusterIP: Invalid value: "10.0.0.2": field is immutable"#
+            ),
+            ["spec.clusterIP"]
+        );
+        assert_eq!(
+            immutable_fields(
+                r#"Deployment.apps "web" is invalid: [spec.selector: Invalid value: v1.LabelSelector{MatchLabels:map[string]string{"app":"web", "tier":"front"}}: field is immutable, spec.template.metadata.labels: Invalid value: map[string]string{"app":"web"}: `selector` does not match template `labels`, spec.selector.matchLabels[tier]: Invalid value: "front": field is immutable]"#
+            ),
+            ["spec.selector", "spec.selector.matchLabels[tier]"]
+        );
+        assert_eq!(
+            immutable_fields(
+                r#"PersistentVolumeClaim "data" is invalid: spec: Forbidden: spec is immutable after creation except resources.requests for bound claims"#
+            ),
+            ["spec"]
+        );
+        assert_eq!(
+            immutable_fields(
+                r#"StatefulSet.apps "db" is invalid: spec: Forbidden: updates to statefulset spec for fields other than 'replicas', 'template' and 'updateStrategy' are forbidden"#
+            ),
+            ["spec"]
+        );
+        assert!(
+            immutable_fields(
+                r#"Deployment.apps "web" is invalid: spec.replicas: Invalid value: -1: must be greater than or equal to 0"#
+            )
+            .is_empty()
+        );
+    }
+
+    /// Serve discovery for ConfigMaps and PVCs, and an existing object that
+    /// rejects every apply as changing an immutable field until it has been
+    /// deleted. Returns the method of each request past discovery.
+    fn spawn_immutable_server(
+        mut handle: tower_test::mock::Handle<
+            http::Request<kube::client::Body>,
+            http::Response<kube::client::Body>,
+        >,
+    ) -> tokio::task::JoinHandle<Vec<String>> {
+        tokio::spawn(async move {
+            let mut methods = Vec::new();
+            let mut deleted = false;
+
+            while let Some((request, send)) = handle.next_request().await {
+                let path = request.uri().path().to_string();
+                let method = request.method().clone();
+
+                let (status, response) = match path.as_str() {
+                    "/api" => (
+                        200,
+                        serde_json::json!({
+                            "kind": "APIVersions",
+                            "versions": ["v1"],
+                            "serverAddressByClientCIDRs": []
+                        }),
+                    ),
+                    "/api/v1" => (
+                        200,
+                        serde_json::json!({
+                            "kind": "APIResourceList",
+                            "groupVersion": "v1",
+                            "resources": [
+                                {
+                                    "name": "configmaps",
+                                    "singularName": "configmap",
+                                    "namespaced": true,
+                                    "kind": "ConfigMap",
+                                    "verbs": ["get", "create", "patch", "delete"]
+                                },
+                                {
+                                    "name": "persistentvolumeclaims",
+                                    "singularName": "persistentvolumeclaim",
+                                    "namespaced": true,
+                                    "kind": "PersistentVolumeClaim",
+                                    "verbs": ["get", "create", "patch", "delete"]
+                                }
+                            ]
+                        }),
+                    ),
+                    "/apis" => (
+                        200,
+                        serde_json::json!({"kind": "APIGroupList", "apiVersion": "v1", "groups": []}),
+                    ),
+                    _ => {
+                        methods.push(method.to_string());
+                        let status = |code: u16, reason: &str, message: &str| {
+                            serde_json::json!({
+                                "kind": "Status",
+                                "apiVersion": "v1",
+                                "status": if code < 300 { "Success" } else { "Failure" },
+                                "message": message,
+                                "reason": reason,
+                                "code": code
+                            })
+                        };
+                        let object = serde_json::to_value(config_map(
+                            serde_json::json!({"app.kubernetes.io/managed-by": "sherpack"}),
+                        ))
+                        .unwrap();
+
+                        match method {
+                            http::Method::GET if deleted => {
+                                (404, status(404, "NotFound", "web-config not found"))
+                            }
+                            http::Method::PATCH if !deleted => (
+                                422,
+                                status(
+                                    422,
+                                    "Invalid",
+                                    "ConfigMap \"web-config\" is invalid: data: Forbidden: field is immutable when `immutable` is set",
+                                ),
+                            ),
+                            http::Method::DELETE => {
+                                deleted = true;
+                                (200, status(200, "", ""))
+                            }
+                            _ => (200, object),
+                        }
+                    }
+                };
+
+                send.send_response(
+                    http::Response::builder()
+                        .status(status)
+                        .body(kube::client::Body::from(
+                            serde_json::to_vec(&response).unwrap(),
+                        ))
+                        .unwrap(),
+                );
+            }
+
+            methods
+        })
+    }
+
+    async fn apply_with_immutable_policy(
+        manifest: &str,
+        policy: ImmutablePolicy,
+    ) -> (Result<OperationSummary>, Vec<String>) {
+        let (service, handle) = tower_test::mock::pair::<
+            http::Request<kube::client::Body>,
+            http::Response<kube::client::Body>,
+        >();
+        let server = spawn_immutable_server(handle);
+
+        let manager = ResourceManager::new(Client::new(service, "default"))
+            .await
+            .unwrap()
+            .with_immutable_policy(policy);
+        let summary = manager.apply_manifest("default", manifest, false).await;
+        drop(manager);
+        (summary, server.await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_immutable_conflict_fails_by_default() {
+        let (summary, methods) =
+            apply_with_immutable_policy(WEB_CONFIG, ImmutablePolicy::default()).await;
+        let summary = summary.unwrap();
+
+        assert_eq!(methods, ["GET", "PATCH"]);
+        assert_eq!(summary.failed.len(), 1);
+        assert!(
+            summary.failed[0]
+                .1
+                .contains("cannot modify immutable field 'data'")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_immutable_conflict_recreates() {
+        let policy = ImmutablePolicy {
+            strategy: ImmutableStrategy::Recreate,
+            recreate_stateful: false,
+        };
+        let (summary, methods) = apply_with_immutable_policy(WEB_CONFIG, policy).await;
+        let summary = summary.unwrap();
+
+        assert!(summary.is_success(), "{:?}", summary.failed);
+        assert_eq!(methods, ["GET", "PATCH", "DELETE", "GET", "PATCH"]);
+        assert_eq!(
+            summary.succeeded,
+            ["default/ConfigMap/web-config (recreated)"]
+        );
+        assert_eq!(
+            summary.applied[0].immutable_conflict,
+            Some(ImmutableConflict {
+                fields: vec!["data".to_string()],
+                action: ImmutableStrategy::Recreate,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_immutable_conflict_skips() {
+        let policy = ImmutablePolicy {
+            strategy: ImmutableStrategy::Skip,
+            recreate_stateful: false,
+        };
+        let (summary, methods) = apply_with_immutable_policy(WEB_CONFIG, policy).await;
+        let summary = summary.unwrap();
+
+        assert_eq!(methods, ["GET", "PATCH"]);
+        assert!(summary.is_success());
+        assert!(summary.succeeded.is_empty());
+        assert_eq!(
+            summary.skipped,
+            [(
+                "default/ConfigMap/web-config".to_string(),
+                "immutable field(s) data left unchanged".to_string()
+            )]
+        );
+        assert_eq!(
+            summary.applied[0]
+                .immutable_conflict
+                .as_ref()
+                .unwrap()
+                .action,
+            ImmutableStrategy::Skip
+        );
+    }
+
+    #[tokio::test]
+    async fn test_recreate_refuses_stateful_kinds_without_confirmation() {
+        let pvc = r#"
+apiVersion: v1
+kind: PersistentVolumeClaim
+metadata:
+  name: web-config
+spec:
+  storageClassName: fast
+"#;
+        let policy = ImmutablePolicy {
+            strategy: ImmutableStrategy::Recreate,
+            recreate_stateful: false,
+        };
+        let (summary, methods) = apply_with_immutable_policy(pvc, policy).await;
+        let summary = summary.unwrap();
+
+        // Never deleted
+        assert_eq!(methods, ["GET", "PATCH"]);
+        assert_eq!(summary.failed.len(), 1);
+        assert!(
+            summary.failed[0]
+                .1
+                .contains("--confirm-stateful-recreation")
+        );
+    }
+
     #[test]
     fn test_field_manager_constant() {
         assert_eq!(FIELD_MANAGER, "sherpack");
@@ -15,7 +15,7 @@ use super::{
     decode_from_storage, encode_for_storage, storage_labels,
 };
 use crate::error::{KubeError, Result};
-use crate::release::StoredRelease;
+use crate::release::{ReleaseState, StoredRelease};
 
 /// Kubernetes ConfigMap storage driver
 pub struct ConfigMapDriver {
@@ -121,6 +121,10 @@ impl ConfigMapDriver {
 
 #[async_trait]
 impl StorageDriver for ConfigMapDriver {
+    fn max_history(&self) -> Option<u32> {
+        Some(self.config.max_history).filter(|&n| n > 0)
+    }
+
     async fn get(&self, namespace: &str, name: &str, version: u32) -> Result<StoredRelease> {
         let api = self.configmaps_api(namespace);
         let key = format!("sh.sherpack.release.v1.{}.v{}", name, version);
@@ -240,6 +244,34 @@ impl StorageDriver for ConfigMapDriver {
         Ok(())
     }
 
+    async fn update_if(&self, release: &StoredRelease, expected: &ReleaseState) -> Result<bool> {
+        let api = self.configmaps_api(&release.namespace);
+        let key = release.storage_key();
+
+        let stored = match api.get(&key).await {
+            Ok(cm) => cm,
+            Err(kube::Error::Api(e)) if e.code == 404 => {
+                return Err(KubeError::ReleaseNotFound {
+                    name: release.name.clone(),
+                    namespace: release.namespace.clone(),
+                });
+            }
+            Err(e) => return Err(e.into()),
+        };
+        if self.parse_configmap(&stored)?.state != *expected {
+            return Ok(false);
+        }
+
+        // Replacing at the version read fails if another write came first
+        let mut cm = self.build_configmap(release)?;
+        cm.metadata.resource_version = stored.metadata.resource_version;
+        match api.replace(&key, &PostParams::default(), &cm).await {
+            Ok(_) => Ok(true),
+            Err(kube::Error::Api(e)) if e.code == 409 => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
     async fn delete(&self, namespace: &str, name: &str, version: u32) -> Result<StoredRelease> {
         let release = self.get(namespace, name, version).await?;
         let api = self.configmaps_api(namespace);
@@ -68,6 +68,10 @@ impl FileDriver {
 
 #[async_trait]
 impl StorageDriver for FileDriver {
+    fn max_history(&self) -> Option<u32> {
+        Some(self.config.max_history).filter(|&n| n > 0)
+    }
+
     async fn get(&self, namespace: &str, name: &str, version: u32) -> Result<StoredRelease> {
         let path = self.release_path(namespace, name, version);
 
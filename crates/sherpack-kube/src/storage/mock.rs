@@ -9,7 +9,7 @@ use std::sync::{Arc, RwLock};
 
 use super::StorageDriver;
 use crate::error::{KubeError, Result};
-use crate::release::StoredRelease;
+use crate::release::{ReleaseState, StoredRelease};
 
 /// Type alias for the nested storage structure: namespace -> name -> version -> release
 type ReleaseStore = HashMap<String, HashMap<String, HashMap<u32, StoredRelease>>>;
@@ -21,6 +21,8 @@ pub struct MockStorageDriver {
     store: Arc<RwLock<ReleaseStore>>,
     /// Track operation counts for assertions
     operations: Arc<RwLock<OperationCounts>>,
+    /// Revisions kept per release (see [`StorageDriver::max_history`])
+    max_history: Option<u32>,
 }
 
 /// Counts of operations performed for testing assertions
@@ -39,9 +41,16 @@ impl MockStorageDriver {
         Self {
             store: Arc::new(RwLock::new(HashMap::new())),
             operations: Arc::new(RwLock::new(OperationCounts::default())),
+            max_history: None,
         }
     }
 
+    /// Keep at most `max_history` revisions per release
+    pub fn with_max_history(mut self, max_history: u32) -> Self {
+        self.max_history = Some(max_history);
+        self
+    }
+
     /// Create with pre-populated releases
     pub fn with_releases(releases: Vec<StoredRelease>) -> Self {
         let driver = Self::new();
@@ -100,6 +109,10 @@ impl Default for MockStorageDriver {
 
 #[async_trait]
 impl StorageDriver for MockStorageDriver {
+    fn max_history(&self) -> Option<u32> {
+        self.max_history
+    }
+
     async fn get(&self, namespace: &str, name: &str, version: u32) -> Result<StoredRelease> {
         {
             let mut ops = self.operations.write().unwrap();
@@ -239,6 +252,29 @@ impl StorageDriver for MockStorageDriver {
         Ok(())
     }
 
+    async fn update_if(&self, release: &StoredRelease, expected: &ReleaseState) -> Result<bool> {
+        {
+            let mut ops = self.operations.write().unwrap();
+            ops.updates += 1;
+        }
+
+        let mut store = self.store.write().unwrap();
+        let stored = store
+            .get_mut(&release.namespace)
+            .and_then(|ns| ns.get_mut(&release.name))
+            .and_then(|versions| versions.get_mut(&release.version))
+            .ok_or_else(|| KubeError::ReleaseNotFound {
+                name: release.name.clone(),
+                namespace: release.namespace.clone(),
+            })?;
+
+        if stored.state != *expected {
+            return Ok(false);
+        }
+        *stored = release.clone();
+        Ok(true)
+    }
+
     async fn delete(&self, namespace: &str, name: &str, version: u32) -> Result<StoredRelease> {
         {
             let mut ops = self.operations.write().unwrap();
@@ -310,10 +346,12 @@ mod tests {
             values_provenance: Default::default(),
             manifest: "apiVersion: v1\nkind: ConfigMap".to_string(),
             hooks: vec![],
+            hook_runs: vec![],
             labels: Default::default(),
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
             notes: None,
+            secrets: Default::default(),
         }
     }
 
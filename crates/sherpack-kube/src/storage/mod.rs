@@ -24,7 +24,7 @@ pub use mock::{MockStorageDriver, OperationCounts};
 pub use secrets::SecretsDriver;
 
 use crate::error::{KubeError, Result};
-use crate::release::StoredRelease;
+use crate::release::{ReleaseState, StoredRelease};
 use async_trait::async_trait;
 
 /// Maximum size for a single Kubernetes Secret/ConfigMap (1MB - some overhead)
@@ -58,12 +58,36 @@ pub trait StorageDriver: Send + Sync {
     /// Update an existing release
     async fn update(&self, release: &StoredRelease) -> Result<()>;
 
+    /// Update an existing release only if its stored state is still `expected`
+    ///
+    /// Returns `false`, storing nothing, when the release changed since it was
+    /// read. This is the optimistic lock of operations that don't store a new
+    /// revision. The default compares then updates; drivers backed by the API
+    /// server replace the object at the version they compared.
+    async fn update_if(&self, release: &StoredRelease, expected: &ReleaseState) -> Result<bool> {
+        let stored = self
+            .get(&release.namespace, &release.name, release.version)
+            .await?;
+        if stored.state != *expected {
+            return Ok(false);
+        }
+        self.update(release).await?;
+        Ok(true)
+    }
+
     /// Delete a specific release version
     async fn delete(&self, namespace: &str, name: &str, version: u32) -> Result<StoredRelease>;
 
     /// Delete all versions of a release
     async fn delete_all(&self, namespace: &str, name: &str) -> Result<Vec<StoredRelease>>;
 
+    /// Revisions kept per release when an operation sets no limit of its own
+    ///
+    /// `None` keeps every revision.
+    fn max_history(&self) -> Option<u32> {
+        None
+    }
+
     /// Check if a release exists
     async fn exists(&self, namespace: &str, name: &str) -> Result<bool> {
         match self.get_latest(namespace, name).await {
@@ -72,6 +96,119 @@ pub trait StorageDriver: Send + Sync {
             Err(e) => Err(e),
         }
     }
+
+    /// List releases matching `filter`, optionally limited to a namespace
+    ///
+    /// Only the latest revision of each release is considered, except when
+    /// filtering on [`StatusFilter::Superseded`].
+    async fn list_filtered(
+        &self,
+        namespace: Option<&str>,
+        filter: &ReleaseFilter,
+    ) -> Result<Vec<StoredRelease>> {
+        let include_superseded = filter.status == Some(StatusFilter::Superseded);
+        let mut releases = self.list(namespace, None, include_superseded).await?;
+        releases.retain(|release| filter.matches(release));
+        Ok(releases)
+    }
+}
+
+/// Release status matched by a [`ReleaseFilter`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusFilter {
+    /// Successfully deployed
+    Deployed,
+    /// Failed, including atomic operations that were rolled back
+    Failed,
+    /// Replaced by a newer revision
+    Superseded,
+    /// An operation (or a recovery) is in progress
+    Pending,
+}
+
+impl StatusFilter {
+    /// Whether `state` falls under this status
+    pub fn matches(self, state: &ReleaseState) -> bool {
+        match self {
+            Self::Deployed => matches!(state, ReleaseState::Deployed),
+            Self::Failed => matches!(
+                state,
+                ReleaseState::Failed { .. } | ReleaseState::AtomicRollback { .. }
+            ),
+            Self::Superseded => matches!(state, ReleaseState::Superseded),
+            Self::Pending => state.is_pending(),
+        }
+    }
+}
+
+impl std::fmt::Display for StatusFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Deployed => write!(f, "deployed"),
+            Self::Failed => write!(f, "failed"),
+            Self::Superseded => write!(f, "superseded"),
+            Self::Pending => write!(f, "pending"),
+        }
+    }
+}
+
+impl std::str::FromStr for StatusFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "deployed" => Ok(Self::Deployed),
+            "failed" => Ok(Self::Failed),
+            "superseded" => Ok(Self::Superseded),
+            "pending" => Ok(Self::Pending),
+            _ => Err(format!(
+                "unknown release status: {} (expected deployed, failed, superseded or pending)",
+                s
+            )),
+        }
+    }
+}
+
+/// Criteria a listed release must meet; empty criteria match everything
+#[derive(Debug, Clone, Default)]
+pub struct ReleaseFilter {
+    /// Release status
+    pub status: Option<StatusFilter>,
+    /// Labels that must all be present with these values
+    pub selector: Vec<(String, String)>,
+    /// Name of the pack the release was installed from
+    pub pack: Option<String>,
+}
+
+impl ReleaseFilter {
+    /// Parse a `key=value[,key=value...]` label selector
+    pub fn parse_selector(selector: &str) -> std::result::Result<Vec<(String, String)>, String> {
+        selector
+            .split(',')
+            .map(str::trim)
+            .filter(|term| !term.is_empty())
+            .map(|term| match term.split_once('=') {
+                Some((key, value)) if !key.trim().is_empty() => {
+                    Ok((key.trim().to_string(), value.trim().to_string()))
+                }
+                _ => Err(format!("invalid selector '{}': expected key=value", term)),
+            })
+            .collect()
+    }
+
+    /// Whether `release` meets every criterion
+    pub fn matches(&self, release: &StoredRelease) -> bool {
+        self.status
+            .is_none_or(|status| status.matches(&release.state))
+            && self
+                .pack
+                .as_ref()
+                .is_none_or(|pack| *pack == release.pack.name)
+            && self
+                .selector
+                .iter()
+                .all(|(key, value)| release.labels.get(key) == Some(value))
+    }
 }
 
 /// Storage configuration
@@ -83,7 +220,7 @@ pub struct StorageConfig {
     /// Strategy for handling large releases
     pub large_release_strategy: LargeReleaseStrategy,
 
-    /// Maximum number of revisions to keep per release
+    /// Maximum number of revisions to keep per release (0 keeps them all)
     pub max_history: u32,
 }
 
@@ -233,7 +370,6 @@ pub fn storage_labels(release: &StoredRelease) -> std::collections::BTreeMap<Str
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::release::ReleaseState;
     use sherpack_core::{PackMetadata, Values};
 
     fn test_release() -> StoredRelease {
@@ -482,4 +618,116 @@ mod tests {
             matches!(deserialized.state, ReleaseState::Failed { reason, .. } if reason == "Test failure")
         );
     }
+
+    /// Latest revisions: `web` deployed (team=a), `api` failed (team=b),
+    /// `db` pending from another pack; `web` also has a superseded revision
+    fn mixed_releases() -> MockStorageDriver {
+        let release = |name: &str, version: u32, pack: &str, team: &str, state: ReleaseState| {
+            let mut release = test_release();
+            release.name = name.to_string();
+            release.version = version;
+            release.pack.name = pack.to_string();
+            release.labels.insert("team".to_string(), team.to_string());
+            release.state = state;
+            release
+        };
+        let failed = ReleaseState::Failed {
+            reason: "boom".to_string(),
+            recoverable: true,
+            failed_at: chrono::Utc::now(),
+        };
+        let pending = ReleaseState::PendingInstall {
+            started_at: chrono::Utc::now(),
+            timeout: crate::release::DEFAULT_OPERATION_TIMEOUT,
+            owner: None,
+        };
+
+        MockStorageDriver::with_releases(vec![
+            release("web", 1, "web-pack", "a", ReleaseState::Superseded),
+            release("web", 2, "web-pack", "a", ReleaseState::Deployed),
+            release("api", 1, "web-pack", "b", failed),
+            release("db", 1, "db-pack", "a", pending),
+        ])
+    }
+
+    async fn listed(storage: &MockStorageDriver, filter: ReleaseFilter) -> Vec<String> {
+        let mut names: Vec<String> = storage
+            .list_filtered(None, &filter)
+            .await
+            .unwrap()
+            .iter()
+            .map(|r| format!("{}@{}", r.name, r.version))
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[tokio::test]
+    async fn test_list_filtered_by_status() {
+        let storage = mixed_releases();
+        let by_status = |status| ReleaseFilter {
+            status: Some(status),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            listed(&storage, ReleaseFilter::default()).await,
+            ["api@1", "db@1", "web@2"]
+        );
+        assert_eq!(
+            listed(&storage, by_status(StatusFilter::Deployed)).await,
+            ["web@2"]
+        );
+        assert_eq!(
+            listed(&storage, by_status(StatusFilter::Failed)).await,
+            ["api@1"]
+        );
+        assert_eq!(
+            listed(&storage, by_status(StatusFilter::Pending)).await,
+            ["db@1"]
+        );
+        assert_eq!(
+            listed(&storage, by_status(StatusFilter::Superseded)).await,
+            ["web@1"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_filtered_by_selector_and_pack() {
+        let storage = mixed_releases();
+
+        let selector = ReleaseFilter {
+            selector: ReleaseFilter::parse_selector("team=a").unwrap(),
+            ..Default::default()
+        };
+        assert_eq!(listed(&storage, selector).await, ["db@1", "web@2"]);
+
+        let combined = ReleaseFilter {
+            selector: ReleaseFilter::parse_selector("team=a").unwrap(),
+            pack: Some("web-pack".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(listed(&storage, combined).await, ["web@2"]);
+
+        let no_match = ReleaseFilter {
+            selector: ReleaseFilter::parse_selector("team=a, tier=frontend").unwrap(),
+            ..Default::default()
+        };
+        assert!(listed(&storage, no_match).await.is_empty());
+    }
+
+    #[test]
+    fn test_parse_selector() {
+        assert_eq!(
+            ReleaseFilter::parse_selector("team=a,env=prod").unwrap(),
+            [
+                ("team".to_string(), "a".to_string()),
+                ("env".to_string(), "prod".to_string())
+            ]
+        );
+        assert!(ReleaseFilter::parse_selector("team").is_err());
+        assert!(ReleaseFilter::parse_selector("=a").is_err());
+        assert_eq!("FAILED".parse::<StatusFilter>(), Ok(StatusFilter::Failed));
+        assert!("unknown".parse::<StatusFilter>().is_err());
+    }
 }
@@ -19,7 +19,7 @@ use super::{
     decode_from_storage, encode_for_storage, storage_labels,
 };
 use crate::error::{KubeError, Result};
-use crate::release::StoredRelease;
+use crate::release::{ReleaseState, StoredRelease};
 
 /// Storage strategy based on size
 enum StorageStrategy {
@@ -182,6 +182,10 @@ impl SecretsDriver {
 
 #[async_trait]
 impl StorageDriver for SecretsDriver {
+    fn max_history(&self) -> Option<u32> {
+        Some(self.config.max_history).filter(|&n| n > 0)
+    }
+
     async fn get(&self, namespace: &str, name: &str, version: u32) -> Result<StoredRelease> {
         let api = self.secrets_api(namespace);
         let key = format!("sh.sherpack.release.v1.{}.v{}", name, version);
@@ -393,6 +397,51 @@ impl StorageDriver for SecretsDriver {
         Ok(())
     }
 
+    async fn update_if(&self, release: &StoredRelease, expected: &ReleaseState) -> Result<bool> {
+        let api = self.secrets_api(&release.namespace);
+        let key = release.storage_key();
+
+        let stored = match api.get(&key).await {
+            Ok(secret) => secret,
+            Err(kube::Error::Api(e)) if e.code == 404 => {
+                return Err(KubeError::ReleaseNotFound {
+                    name: release.name.clone(),
+                    namespace: release.namespace.clone(),
+                });
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let is_chunked = chunked::is_chunked_index(&stored);
+        let current = if is_chunked {
+            self.parse_chunked_secret(&stored).await?
+        } else {
+            self.parse_secret(&stored)?
+        };
+        if current.state != *expected {
+            return Ok(false);
+        }
+
+        let encoded = encode_for_storage(release, &self.config)?;
+        match self.check_size(&encoded)? {
+            // Replacing at the version read fails if another write came first
+            StorageStrategy::Single(data) if !is_chunked => {
+                let mut secret = self.build_secret(release, &data);
+                secret.metadata.resource_version = stored.metadata.resource_version;
+                match api.replace(&key, &PostParams::default(), &secret).await {
+                    Ok(_) => Ok(true),
+                    Err(kube::Error::Api(e)) if e.code == 409 => Ok(false),
+                    Err(e) => Err(e.into()),
+                }
+            }
+            // Chunked releases span several Secrets, which can't be replaced
+            // at once
+            _ => {
+                self.update(release).await?;
+                Ok(true)
+            }
+        }
+    }
+
     async fn delete(&self, namespace: &str, name: &str, version: u32) -> Result<StoredRelease> {
         let release = self.get(namespace, name, version).await?;
         let api = self.secrets_api(namespace);
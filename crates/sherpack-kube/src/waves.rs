@@ -31,15 +31,39 @@
 //!   annotations:
 //!     sherpack.io/sync-wave: "2"  # Waits for wave 1
 //! ```
+//!
+//! # Gates
+//!
+//! A resource annotated with `sherpack.io/wave-gate: "true"` (typically a
+//! smoke-test Job) gates every later wave: the [`WaveExecutor`] waits for it
+//! to succeed and, on failure, stops according to [`WaveFailurePolicy`].
+//! Explicit [`WaitCondition`]s (`--wait-for`) on a wave's resources gate
+//! later waves the same way, so a later wave can wait for e.g. a
+//! `Certificate` of an earlier wave to become `Ready`.
+//!
+//! # Parallelism
+//!
+//! Resources within a wave are applied concurrently, up to
+//! [`WaveExecutionConfig::max_parallel`] at a time. A `wait-for` dependency
+//! on a resource of the same wave delays the dependent resource until its
+//! dependency has been applied and is ready. CRDs and Namespaces are
+//! applied before the rest of their wave.
 
 use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
 use std::time::Duration;
 
+use async_trait::async_trait;
+use futures::stream::{FuturesUnordered, StreamExt};
 use serde::{Deserialize, Serialize};
 use serde_yaml::Value;
 
 use crate::annotations::{self, ResourceRef};
+use crate::crd::ResourceCategory;
 use crate::error::{KubeError, Result};
+use crate::health::{HealthCheckConfig, HealthChecker, ResourceHealth, WaitCondition};
+use crate::progress::{ProgressEvent, ProgressSink, ResourceStatus};
+use crate::resources::ResourceManager;
 
 /// A parsed Kubernetes resource with its metadata
 #[derive(Debug, Clone)]
@@ -64,6 +88,8 @@ pub struct Resource {
     pub hook_phases: Vec<String>,
     /// Whether to skip waiting for this resource
     pub skip_wait: bool,
+    /// Whether later waves are gated on this resource succeeding
+    pub is_gate: bool,
 }
 
 impl Resource {
@@ -99,6 +125,7 @@ impl Resource {
         let wave = annotations::parse_sync_wave(&annotations);
         let dependencies = annotations::parse_wait_for(&annotations);
         let skip_wait = annotations::should_skip_wait(&annotations);
+        let is_gate = annotations::is_wave_gate(&annotations);
 
         // Check if it's a hook
         let hook_value = annotations::get_annotation(
@@ -122,6 +149,7 @@ impl Resource {
             is_hook,
             hook_phases,
             skip_wait,
+            is_gate,
         })
     }
 
@@ -134,6 +162,16 @@ impl Resource {
     pub fn as_ref(&self) -> ResourceRef {
         ResourceRef::new(&self.kind, &self.name)
     }
+
+    /// Installation category (CRDs and Namespaces come first)
+    pub fn category(&self) -> ResourceCategory {
+        let api_version = self
+            .value
+            .get("apiVersion")
+            .and_then(|v| v.as_str())
+            .unwrap_or("v1");
+        ResourceCategory::from_resource(&self.kind, api_version)
+    }
 }
 
 /// A wave of resources to apply together
@@ -155,6 +193,20 @@ impl Wave {
     pub fn resource_keys(&self) -> Vec<String> {
         self.resources.iter().map(|r| r.key()).collect()
     }
+
+    /// Get the gate resources in this wave
+    pub fn gates(&self) -> Vec<&Resource> {
+        self.resources.iter().filter(|r| r.is_gate).collect()
+    }
+
+    /// Render this wave's resources as a multi-document manifest
+    pub fn manifest(&self) -> String {
+        self.resources
+            .iter()
+            .map(|r| r.yaml.as_str())
+            .collect::<Vec<_>>()
+            .join("\n---\n")
+    }
 }
 
 /// Execution plan for a release
@@ -231,6 +283,11 @@ impl ExecutionPlan {
             });
         }
 
+        // Refuse plans that could never finish
+        if let Some(cycle) = find_dependency_cycle(&dependencies, &resource_index) {
+            return Err(KubeError::WaveCycle { cycle });
+        }
+
         // Convert to Wave structs
         let waves: Vec<Wave> = waves_map
             .into_iter()
@@ -315,6 +372,67 @@ impl ExecutionPlan {
     }
 }
 
+/// Find a cycle in the `wait-for` graph, as a chain of resource keys
+///
+/// The chain starts and ends with the same resource (`A -> B -> A`).
+/// Dependencies on resources outside the plan are ignored.
+fn find_dependency_cycle(
+    dependencies: &HashMap<String, Vec<ResourceRef>>,
+    resources: &HashMap<String, Resource>,
+) -> Option<Vec<String>> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum Mark {
+        Visiting,
+        Done,
+    }
+
+    fn visit(
+        key: &str,
+        dependencies: &HashMap<String, Vec<ResourceRef>>,
+        resources: &HashMap<String, Resource>,
+        marks: &mut HashMap<String, Mark>,
+        path: &mut Vec<String>,
+    ) -> Option<Vec<String>> {
+        match marks.get(key) {
+            Some(Mark::Done) => return None,
+            Some(Mark::Visiting) => {
+                // Back edge: the cycle is the path suffix starting at `key`
+                let start = path.iter().position(|k| k == key).unwrap_or(0);
+                let mut cycle = path[start..].to_vec();
+                cycle.push(key.to_string());
+                return Some(cycle);
+            }
+            None => {}
+        }
+
+        marks.insert(key.to_string(), Mark::Visiting);
+        path.push(key.to_string());
+
+        for dep in dependencies.get(key).into_iter().flatten() {
+            let dep_key = dep.to_string();
+            if !resources.contains_key(&dep_key) {
+                continue;
+            }
+            if let Some(cycle) = visit(&dep_key, dependencies, resources, marks, path) {
+                return Some(cycle);
+            }
+        }
+
+        path.pop();
+        marks.insert(key.to_string(), Mark::Done);
+        None
+    }
+
+    let mut marks = HashMap::new();
+    let mut keys: Vec<&String> = dependencies.keys().collect();
+    keys.sort();
+
+    keys.into_iter().find_map(|key| {
+        let mut path = Vec::new();
+        visit(key, dependencies, resources, &mut marks, &mut path)
+    })
+}
+
 /// Summary of an execution plan for display
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExecutionPlanSummary {
@@ -372,6 +490,12 @@ pub struct WaveExecutionConfig {
     pub atomic: bool,
     /// Whether to show progress
     pub show_progress: bool,
+    /// What to do when a gate resource fails
+    pub failure_policy: WaveFailurePolicy,
+    /// Maximum number of resources applied concurrently within a wave
+    pub max_parallel: usize,
+    /// Conditions on a wave's resources that must hold before the next wave
+    pub wait_for: Vec<WaitCondition>,
 }
 
 impl Default for WaveExecutionConfig {
@@ -381,10 +505,449 @@ impl Default for WaveExecutionConfig {
             timeout: Duration::from_secs(300),
             atomic: false,
             show_progress: true,
+            failure_policy: WaveFailurePolicy::default(),
+            max_parallel: 8,
+            wait_for: Vec::new(),
         }
     }
 }
 
+/// Behavior when a wave gate fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WaveFailurePolicy {
+    /// Stop the plan; later waves are not applied
+    #[default]
+    Halt,
+    /// Record the failure and keep applying later waves
+    Continue,
+}
+
+/// A gate resource that did not succeed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GateFailure {
+    /// Wave the gate belongs to
+    pub wave: i32,
+    /// Gate resource key (Kind/name)
+    pub resource: String,
+    /// Failure reason
+    pub message: String,
+}
+
+/// Outcome of executing a plan
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WaveExecutionReport {
+    /// Waves that were applied, in order
+    pub applied_waves: Vec<i32>,
+    /// Gates that failed (only non-empty with [`WaveFailurePolicy::Continue`])
+    pub failed_gates: Vec<GateFailure>,
+}
+
+/// Cluster operations used by the [`WaveExecutor`]
+#[async_trait]
+pub trait WaveApplier: Send + Sync {
+    /// Apply a single resource
+    async fn apply_resource(&self, resource: &Resource) -> Result<()>;
+
+    /// Wait for a resource (a gate or a `wait-for` dependency) to be ready,
+    /// returning its final health
+    async fn wait_for_resource(
+        &self,
+        resource: &Resource,
+        timeout: Duration,
+    ) -> Result<ResourceHealth>;
+
+    /// Wait for an explicit condition on a resource to hold, returning the
+    /// final result
+    async fn wait_for_condition(
+        &self,
+        resource: &Resource,
+        condition: &WaitCondition,
+        timeout: Duration,
+    ) -> Result<ResourceHealth>;
+}
+
+/// Applies an [`ExecutionPlan`] wave by wave, enforcing gates
+pub struct WaveExecutor<A: WaveApplier> {
+    applier: A,
+    config: WaveExecutionConfig,
+    progress: Option<Arc<dyn ProgressSink>>,
+}
+
+impl<A: WaveApplier> WaveExecutor<A> {
+    /// Create a new executor
+    pub fn new(applier: A, config: WaveExecutionConfig) -> Self {
+        Self {
+            applier,
+            config,
+            progress: None,
+        }
+    }
+
+    /// Report every resource transition to `sink`
+    pub fn with_progress(mut self, sink: Arc<dyn ProgressSink>) -> Self {
+        self.progress = Some(sink);
+        self
+    }
+
+    fn report(&self, event: impl FnOnce() -> ProgressEvent) {
+        if let Some(sink) = &self.progress {
+            sink.emit(&event());
+        }
+    }
+
+    /// Wait for a resource (or a condition on it), reporting the wait and
+    /// its outcome
+    async fn wait_for(
+        &self,
+        resource: &Resource,
+        condition: Option<&WaitCondition>,
+    ) -> Result<ResourceHealth> {
+        self.report(|| {
+            ProgressEvent::new(
+                &resource.kind,
+                &resource.name,
+                ResourceStatus::WaitingForReady,
+            )
+        });
+
+        let result = match condition {
+            Some(condition) => {
+                self.applier
+                    .wait_for_condition(resource, condition, self.config.timeout)
+                    .await
+            }
+            None => {
+                self.applier
+                    .wait_for_resource(resource, self.config.timeout)
+                    .await
+            }
+        };
+
+        self.report(|| match &result {
+            Ok(health) => {
+                let phase = if health.healthy {
+                    ResourceStatus::Ready
+                } else {
+                    ResourceStatus::Failed
+                };
+                let event = ProgressEvent::new(&resource.kind, &resource.name, phase)
+                    .with_readiness(health.ready, health.desired);
+                match &health.message {
+                    Some(message) => event.with_message(message),
+                    None => event,
+                }
+            }
+            Err(e) => ProgressEvent::new(&resource.kind, &resource.name, ResourceStatus::Failed)
+                .with_message(e.to_string()),
+        });
+
+        result
+    }
+
+    /// The applier resources are applied with
+    pub fn applier(&self) -> &A {
+        &self.applier
+    }
+
+    /// Execute the plan
+    ///
+    /// With [`WaveFailurePolicy::Halt`], a failed gate returns
+    /// [`KubeError::WaveGateFailed`] and no later wave is applied.
+    pub async fn execute(&self, plan: &ExecutionPlan) -> Result<WaveExecutionReport> {
+        let mut report = WaveExecutionReport::default();
+
+        for wave in &plan.waves {
+            if wave.is_empty() {
+                continue;
+            }
+
+            self.apply_wave(wave).await?;
+            report.applied_waves.push(wave.number);
+
+            for gate in wave.gates() {
+                let health = self.wait_for(gate, None).await?;
+
+                if !health.healthy {
+                    self.gate_failed(
+                        &mut report,
+                        GateFailure {
+                            wave: wave.number,
+                            resource: gate.key(),
+                            message: health
+                                .message
+                                .unwrap_or_else(|| "gate did not succeed".to_string()),
+                        },
+                    )?;
+                }
+            }
+
+            for resource in &wave.resources {
+                for condition in self
+                    .config
+                    .wait_for
+                    .iter()
+                    .filter(|c| c.targets(&resource.kind, &resource.name))
+                {
+                    let health = self.wait_for(resource, Some(condition)).await?;
+
+                    if !health.healthy {
+                        self.gate_failed(
+                            &mut report,
+                            GateFailure {
+                                wave: wave.number,
+                                resource: condition.to_string(),
+                                message: health
+                                    .message
+                                    .unwrap_or_else(|| "condition not met".to_string()),
+                            },
+                        )?;
+                    }
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Stop on a failed gate, or record it with [`WaveFailurePolicy::Continue`]
+    fn gate_failed(&self, report: &mut WaveExecutionReport, failure: GateFailure) -> Result<()> {
+        match self.config.failure_policy {
+            WaveFailurePolicy::Halt => Err(KubeError::WaveGateFailed {
+                wave: failure.wave,
+                resource: failure.resource,
+                message: failure.message,
+            }),
+            WaveFailurePolicy::Continue => {
+                tracing::warn!(
+                    "Wave {} gate {} failed: {}",
+                    failure.wave,
+                    failure.resource,
+                    failure.message
+                );
+                report.failed_gates.push(failure);
+                Ok(())
+            }
+        }
+    }
+
+    /// Apply a wave's resources concurrently, respecting in-wave `wait-for`
+    ///
+    /// After the first failure no new resource is started; resources already
+    /// in flight finish and every failure is reported.
+    async fn apply_wave(&self, wave: &Wave) -> Result<()> {
+        let in_wave: HashSet<String> = wave.resource_keys().into_iter().collect();
+
+        // Resources other resources of this wave wait for
+        let awaited: HashSet<String> = wave
+            .resources
+            .iter()
+            .flat_map(|r| r.dependencies.iter().map(|d| d.to_string()))
+            .filter(|key| in_wave.contains(key))
+            .collect();
+
+        // CRDs, Namespaces and their quotas must exist before anything else
+        let foundation: HashSet<String> = wave
+            .resources
+            .iter()
+            .filter(|r| r.category() <= ResourceCategory::NamespaceConfig)
+            .map(|r| r.key())
+            .collect();
+
+        let mut pending: Vec<&Resource> = wave.resources.iter().collect();
+        let mut done: HashSet<String> = HashSet::new();
+        let mut failures: Vec<String> = Vec::new();
+        let mut running = FuturesUnordered::new();
+        let max_parallel = self.config.max_parallel.max(1);
+
+        loop {
+            if failures.is_empty() {
+                let mut i = 0;
+                while i < pending.len() && running.len() < max_parallel {
+                    let resource = pending[i];
+                    let ready = (foundation.contains(&resource.key())
+                        || foundation.iter().all(|key| done.contains(key)))
+                        && resource.dependencies.iter().all(|dep| {
+                            let key = dep.to_string();
+                            !in_wave.contains(&key) || done.contains(&key)
+                        });
+
+                    if ready {
+                        let resource = pending.remove(i);
+                        let await_ready = awaited.contains(&resource.key());
+                        running.push(self.apply_resource(resource, await_ready));
+                    } else {
+                        i += 1;
+                    }
+                }
+            }
+
+            match running.next().await {
+                Some((key, Ok(()))) => {
+                    done.insert(key);
+                }
+                Some((key, Err(e))) => failures.push(format!("{}: {}", key, e)),
+                None => break,
+            }
+        }
+
+        if failures.is_empty() && !pending.is_empty() {
+            failures = pending
+                .iter()
+                .map(|r| format!("{}: wait-for dependencies never became ready", r.key()))
+                .collect();
+        }
+
+        if !failures.is_empty() {
+            return Err(KubeError::WaveApplyFailed {
+                wave: wave.number,
+                failures,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Apply one resource, then wait for it if others in its wave depend on it
+    async fn apply_resource(&self, resource: &Resource, await_ready: bool) -> (String, Result<()>) {
+        let result = async {
+            self.report(|| {
+                ProgressEvent::new(&resource.kind, &resource.name, ResourceStatus::Applying)
+            });
+            if let Err(e) = self.applier.apply_resource(resource).await {
+                self.report(|| {
+                    ProgressEvent::new(&resource.kind, &resource.name, ResourceStatus::Failed)
+                        .with_message(e.to_string())
+                });
+                return Err(e);
+            }
+            self.report(|| {
+                ProgressEvent::new(&resource.kind, &resource.name, ResourceStatus::Applied)
+            });
+
+            if await_ready {
+                let health = self.wait_for(resource, None).await?;
+
+                if !health.healthy {
+                    return Err(KubeError::InvalidConfig(format!(
+                        "not ready: {}",
+                        health.message.unwrap_or_else(|| "unhealthy".to_string())
+                    )));
+                }
+            }
+
+            Ok(())
+        }
+        .await;
+
+        (resource.key(), result)
+    }
+}
+
+/// [`WaveApplier`] backed by a live cluster
+pub struct ClusterWaveApplier {
+    manager: ResourceManager,
+    client: kube::Client,
+    namespace: String,
+    adopted: std::sync::Mutex<Vec<String>>,
+    warnings: std::sync::Mutex<Vec<(String, String)>>,
+}
+
+impl ClusterWaveApplier {
+    /// Create an applier targeting the given default namespace
+    pub fn new(manager: ResourceManager, client: kube::Client, namespace: &str) -> Self {
+        Self {
+            manager,
+            client,
+            namespace: namespace.to_string(),
+            adopted: std::sync::Mutex::new(Vec::new()),
+            warnings: std::sync::Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Pre-existing resources adopted so far (see [`ResourceManager::with_ownership`])
+    pub fn adopted(&self) -> Vec<String> {
+        self.adopted
+            .lock()
+            .map(|adopted| adopted.clone())
+            .unwrap_or_default()
+    }
+
+    /// Server-side mutations found so far (see [`ResourceManager::with_apply_verification`])
+    pub fn warnings(&self) -> Vec<(String, String)> {
+        self.warnings
+            .lock()
+            .map(|warnings| warnings.clone())
+            .unwrap_or_default()
+    }
+}
+
+#[async_trait]
+impl WaveApplier for ClusterWaveApplier {
+    async fn apply_resource(&self, resource: &Resource) -> Result<()> {
+        let summary = self
+            .manager
+            .apply_manifest(&self.namespace, &resource.yaml, false)
+            .await?;
+
+        if let Ok(mut adopted) = self.adopted.lock() {
+            adopted.extend(summary.adopted);
+        }
+        if let Ok(mut warnings) = self.warnings.lock() {
+            warnings.extend(summary.warnings);
+        }
+
+        match summary.failed.into_iter().next() {
+            Some((_, err)) => Err(KubeError::InvalidConfig(err)),
+            None => Ok(()),
+        }
+    }
+
+    async fn wait_for_resource(
+        &self,
+        resource: &Resource,
+        timeout: Duration,
+    ) -> Result<ResourceHealth> {
+        let namespace = resource.namespace.as_deref().unwrap_or(&self.namespace);
+
+        health_checker(timeout)
+            .wait_for_resource(&self.client, namespace, &resource.kind, &resource.name)
+            .await
+    }
+
+    async fn wait_for_condition(
+        &self,
+        resource: &Resource,
+        condition: &WaitCondition,
+        timeout: Duration,
+    ) -> Result<ResourceHealth> {
+        let namespace = resource.namespace.as_deref().unwrap_or(&self.namespace);
+        let api_version = resource
+            .value
+            .get("apiVersion")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default();
+
+        health_checker(timeout)
+            .wait_for_condition(
+                &self.client,
+                namespace,
+                api_version,
+                &resource.kind,
+                condition,
+            )
+            .await
+    }
+}
+
+/// Health checker waiting at most `timeout`
+fn health_checker(timeout: Duration) -> HealthChecker {
+    HealthChecker::new(HealthCheckConfig {
+        timeout: chrono::Duration::from_std(timeout)
+            .unwrap_or_else(|_| chrono::Duration::minutes(5)),
+        ..Default::default()
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -586,4 +1149,543 @@ data:
         let resource = Resource::from_yaml(yaml).unwrap();
         assert!(resource.skip_wait);
     }
+
+    /// Records applied resources and fails the listed resources and gates
+    struct MockApplier {
+        applied: std::sync::Mutex<Vec<(i32, String)>>,
+        events: std::sync::Mutex<Vec<String>>,
+        failing_resources: HashSet<String>,
+        failing_gates: HashSet<String>,
+        in_flight: std::sync::atomic::AtomicUsize,
+        max_in_flight: std::sync::atomic::AtomicUsize,
+    }
+
+    impl MockApplier {
+        fn new(failing_gates: &[&str]) -> Self {
+            Self {
+                applied: std::sync::Mutex::new(Vec::new()),
+                events: std::sync::Mutex::new(Vec::new()),
+                failing_resources: HashSet::new(),
+                failing_gates: failing_gates.iter().map(|s| s.to_string()).collect(),
+                in_flight: std::sync::atomic::AtomicUsize::new(0),
+                max_in_flight: std::sync::atomic::AtomicUsize::new(0),
+            }
+        }
+
+        fn failing_resources(mut self, keys: &[&str]) -> Self {
+            self.failing_resources = keys.iter().map(|s| s.to_string()).collect();
+            self
+        }
+
+        /// Applied wave numbers, in order, without repeats
+        fn applied(&self) -> Vec<i32> {
+            let mut waves: Vec<i32> = Vec::new();
+            for (wave, _) in self.applied.lock().unwrap().iter() {
+                if waves.last() != Some(wave) {
+                    waves.push(*wave);
+                }
+            }
+            waves
+        }
+
+        fn events(&self) -> Vec<String> {
+            self.events.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait]
+    impl WaveApplier for &MockApplier {
+        async fn apply_resource(&self, resource: &Resource) -> Result<()> {
+            use std::sync::atomic::Ordering;
+
+            let current = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(current, Ordering::SeqCst);
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("apply {}", resource.key()));
+
+            tokio::time::sleep(Duration::from_millis(10)).await;
+
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            self.applied
+                .lock()
+                .unwrap()
+                .push((resource.wave, resource.key()));
+
+            if self.failing_resources.contains(&resource.key()) {
+                return Err(KubeError::InvalidConfig("admission denied".to_string()));
+            }
+            Ok(())
+        }
+
+        async fn wait_for_resource(
+            &self,
+            resource: &Resource,
+            _timeout: Duration,
+        ) -> Result<ResourceHealth> {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("ready {}", resource.key()));
+
+            let failed = self.failing_gates.contains(&resource.key());
+            Ok(ResourceHealth {
+                kind: resource.kind.clone(),
+                name: resource.name.clone(),
+                namespace: "default".to_string(),
+                healthy: !failed,
+                ready: Some(if failed { 0 } else { 1 }),
+                desired: Some(1),
+                message: failed.then(|| "BackoffLimitExceeded".to_string()),
+                condition: None,
+                last_transition_time: None,
+            })
+        }
+
+        async fn wait_for_condition(
+            &self,
+            resource: &Resource,
+            condition: &WaitCondition,
+            _timeout: Duration,
+        ) -> Result<ResourceHealth> {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("wait-for {}", condition));
+
+            let met = !self.failing_gates.contains(&resource.key());
+            Ok(ResourceHealth {
+                kind: resource.kind.clone(),
+                name: resource.name.clone(),
+                namespace: "default".to_string(),
+                healthy: met,
+                ready: None,
+                desired: None,
+                message: (!met).then(|| "Issuing certificate".to_string()),
+                condition: Some(condition.probe.describe()),
+                last_transition_time: None,
+            })
+        }
+    }
+
+    const GATED_MANIFEST: &str = r#"
+apiVersion: v1
+kind: ConfigMap
+metadata:
+  name: config
+---
+apiVersion: batch/v1
+kind: Job
+metadata:
+  name: smoke-test
+  annotations:
+    sherpack.io/sync-wave: "1"
+    sherpack.io/wave-gate: "true"
+---
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: app
+  annotations:
+    sherpack.io/sync-wave: "2"
+"#;
+
+    #[test]
+    fn test_wait_for_cycle_detected() {
+        let manifest = r#"
+apiVersion: v1
+kind: ConfigMap
+metadata:
+  name: a
+  annotations:
+    sherpack.io/wait-for: "ConfigMap/b"
+---
+apiVersion: v1
+kind: ConfigMap
+metadata:
+  name: b
+  annotations:
+    sherpack.io/wait-for: "ConfigMap/a"
+"#;
+
+        let err = ExecutionPlan::from_manifest(manifest).unwrap_err();
+        match &err {
+            KubeError::WaveCycle { cycle } => {
+                assert_eq!(cycle, &["ConfigMap/a", "ConfigMap/b", "ConfigMap/a"]);
+            }
+            other => panic!("unexpected error: {}", other),
+        }
+        assert_eq!(
+            err.to_string(),
+            "wait-for dependency cycle: ConfigMap/a -> ConfigMap/b -> ConfigMap/a"
+        );
+    }
+
+    #[test]
+    fn test_wait_for_longer_cycle_and_acyclic_chain() {
+        let manifest = r#"
+apiVersion: v1
+kind: ConfigMap
+metadata:
+  name: root
+  annotations:
+    sherpack.io/wait-for: "Secret/x"
+---
+apiVersion: v1
+kind: Secret
+metadata:
+  name: x
+  annotations:
+    sherpack.io/wait-for: "Service/y"
+---
+apiVersion: v1
+kind: Service
+metadata:
+  name: y
+  annotations:
+    sherpack.io/wait-for: "Secret/x,Deployment/outside-plan"
+"#;
+
+        let err = ExecutionPlan::from_manifest(manifest).unwrap_err();
+        assert!(matches!(
+            err,
+            KubeError::WaveCycle { ref cycle } if cycle == &["Secret/x", "Service/y", "Secret/x"]
+        ));
+
+        // A dependency chain without a back edge is fine
+        let chain = manifest.replace(
+            "Secret/x,Deployment/outside-plan",
+            "Deployment/outside-plan",
+        );
+        assert!(ExecutionPlan::from_manifest(&chain).is_ok());
+    }
+
+    #[test]
+    fn test_parse_wave_gate() {
+        let plan = ExecutionPlan::from_manifest(GATED_MANIFEST).unwrap();
+        let gates: Vec<String> = plan
+            .all_resources()
+            .iter()
+            .filter(|r| r.is_gate)
+            .map(|r| r.key())
+            .collect();
+        assert_eq!(gates, vec!["Job/smoke-test"]);
+    }
+
+    #[tokio::test]
+    async fn test_failed_gate_halts_later_waves() {
+        let plan = ExecutionPlan::from_manifest(GATED_MANIFEST).unwrap();
+        let applier = MockApplier::new(&["Job/smoke-test"]);
+        let executor = WaveExecutor::new(&applier, WaveExecutionConfig::default());
+
+        let err = executor.execute(&plan).await.unwrap_err();
+
+        match err {
+            KubeError::WaveGateFailed {
+                wave,
+                resource,
+                message,
+            } => {
+                assert_eq!(wave, 1);
+                assert_eq!(resource, "Job/smoke-test");
+                assert_eq!(message, "BackoffLimitExceeded");
+            }
+            other => panic!("unexpected error: {}", other),
+        }
+        assert_eq!(applier.applied(), vec![0, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_failed_gate_continue_policy() {
+        let plan = ExecutionPlan::from_manifest(GATED_MANIFEST).unwrap();
+        let applier = MockApplier::new(&["Job/smoke-test"]);
+        let config = WaveExecutionConfig {
+            failure_policy: WaveFailurePolicy::Continue,
+            ..Default::default()
+        };
+
+        let report = WaveExecutor::new(&applier, config)
+            .execute(&plan)
+            .await
+            .unwrap();
+
+        assert_eq!(report.applied_waves, vec![0, 1, 2]);
+        assert_eq!(report.failed_gates.len(), 1);
+        assert_eq!(report.failed_gates[0].resource, "Job/smoke-test");
+    }
+
+    #[tokio::test]
+    async fn test_passing_gate_applies_all_waves() {
+        let plan = ExecutionPlan::from_manifest(GATED_MANIFEST).unwrap();
+        let applier = MockApplier::new(&[]);
+
+        let report = WaveExecutor::new(&applier, WaveExecutionConfig::default())
+            .execute(&plan)
+            .await
+            .unwrap();
+
+        assert_eq!(applier.applied(), vec![0, 1, 2]);
+        assert!(report.failed_gates.is_empty());
+    }
+
+    const CERTIFICATE_MANIFEST: &str = r#"
+apiVersion: cert-manager.io/v1
+kind: Certificate
+metadata:
+  name: web-tls
+---
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: web
+  annotations:
+    sherpack.io/sync-wave: "1"
+"#;
+
+    fn certificate_wait_config() -> WaveExecutionConfig {
+        WaveExecutionConfig {
+            wait_for: vec!["certificate/web-tls: condition=Ready".parse().unwrap()],
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_later_wave_waits_for_condition() {
+        let plan = ExecutionPlan::from_manifest(CERTIFICATE_MANIFEST).unwrap();
+        let applier = MockApplier::new(&[]);
+
+        WaveExecutor::new(&applier, certificate_wait_config())
+            .execute(&plan)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            applier.events(),
+            vec![
+                "apply Certificate/web-tls",
+                "wait-for certificate/web-tls: condition=Ready",
+                "apply Deployment/web",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unmet_condition_halts_later_waves() {
+        let plan = ExecutionPlan::from_manifest(CERTIFICATE_MANIFEST).unwrap();
+        let applier = MockApplier::new(&["Certificate/web-tls"]);
+
+        let err = WaveExecutor::new(&applier, certificate_wait_config())
+            .execute(&plan)
+            .await
+            .unwrap_err();
+
+        match err {
+            KubeError::WaveGateFailed {
+                wave,
+                resource,
+                message,
+            } => {
+                assert_eq!(wave, 0);
+                assert_eq!(resource, "certificate/web-tls: condition=Ready");
+                assert_eq!(message, "Issuing certificate");
+            }
+            other => panic!("unexpected error: {}", other),
+        }
+        assert_eq!(applier.applied(), vec![0]);
+    }
+
+    /// Shared buffer the JSON reporter writes into
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_progress_events_emitted_as_ndjson() {
+        let manifest = r#"
+apiVersion: v1
+kind: ConfigMap
+metadata:
+  name: config
+---
+apiVersion: batch/v1
+kind: Job
+metadata:
+  name: migrate
+  annotations:
+    sherpack.io/sync-wave: "1"
+    sherpack.io/wave-gate: "true"
+"#;
+        let plan = ExecutionPlan::from_manifest(manifest).unwrap();
+        let applier = MockApplier::new(&[]);
+        let buffer = SharedBuffer::default();
+        let reporter = Arc::new(crate::progress::JsonProgressReporter::with_writer(
+            buffer.clone(),
+        ));
+
+        WaveExecutor::new(&applier, WaveExecutionConfig::default())
+            .with_progress(reporter.clone())
+            .execute(&plan)
+            .await
+            .unwrap();
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let events: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        let transitions: Vec<String> = events
+            .iter()
+            .map(|e| format!("{}/{} {}", e["kind"], e["name"], e["phase"]).replace('"', ""))
+            .collect();
+        assert_eq!(
+            transitions,
+            vec![
+                "ConfigMap/config applying",
+                "ConfigMap/config applied",
+                "Job/migrate applying",
+                "Job/migrate applied",
+                "Job/migrate waiting_for_ready",
+                "Job/migrate ready",
+            ]
+        );
+        assert!(events.iter().all(|e| e["type"] == "resource"));
+        assert!(events.iter().all(|e| e["timestamp"].is_string()));
+        assert_eq!(events[5]["ready"], 1);
+        assert_eq!(events[5]["desired"], 1);
+
+        reporter.print_summary();
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let summary: serde_json::Value =
+            serde_json::from_str(output.lines().last().unwrap()).unwrap();
+        assert_eq!(summary["type"], "summary");
+        assert_eq!(summary["resources"].as_array().unwrap().len(), 2);
+    }
+
+    fn config_maps(count: usize) -> String {
+        (0..count)
+            .map(|i| {
+                format!(
+                    "apiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: cm-{}\n",
+                    i
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("---\n")
+    }
+
+    #[tokio::test]
+    async fn test_wave_resources_applied_in_parallel_up_to_bound() {
+        let plan = ExecutionPlan::from_manifest(&config_maps(6)).unwrap();
+        let applier = MockApplier::new(&[]);
+        let config = WaveExecutionConfig {
+            max_parallel: 3,
+            ..Default::default()
+        };
+
+        WaveExecutor::new(&applier, config)
+            .execute(&plan)
+            .await
+            .unwrap();
+
+        assert_eq!(applier.applied.lock().unwrap().len(), 6);
+        assert_eq!(
+            applier
+                .max_in_flight
+                .load(std::sync::atomic::Ordering::SeqCst),
+            3
+        );
+    }
+
+    #[tokio::test]
+    async fn test_wave_respects_in_wave_wait_for() {
+        let manifest = r#"
+apiVersion: batch/v1
+kind: Job
+metadata:
+  name: migrate
+  annotations:
+    sherpack.io/wait-for: "Deployment/postgres"
+---
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: postgres
+---
+apiVersion: v1
+kind: ConfigMap
+metadata:
+  name: unrelated
+"#;
+        let plan = ExecutionPlan::from_manifest(manifest).unwrap();
+        let applier = MockApplier::new(&[]);
+
+        WaveExecutor::new(&applier, WaveExecutionConfig::default())
+            .execute(&plan)
+            .await
+            .unwrap();
+
+        let events = applier.events();
+        let position = |event: &str| events.iter().position(|e| e == event).unwrap();
+        assert!(position("apply Deployment/postgres") < position("ready Deployment/postgres"));
+        assert!(position("ready Deployment/postgres") < position("apply Job/migrate"));
+        // Independent resources don't wait for the dependency chain
+        assert!(position("apply ConfigMap/unrelated") < position("apply Job/migrate"));
+    }
+
+    #[tokio::test]
+    async fn test_wave_applies_namespaces_first() {
+        let manifest = format!(
+            "{}---\napiVersion: v1\nkind: Namespace\nmetadata:\n  name: team\n",
+            config_maps(2)
+        );
+        let plan = ExecutionPlan::from_manifest(&manifest).unwrap();
+        let applier = MockApplier::new(&[]);
+
+        WaveExecutor::new(&applier, WaveExecutionConfig::default())
+            .execute(&plan)
+            .await
+            .unwrap();
+
+        let applied = applier.applied.lock().unwrap();
+        assert_eq!(applied[0].1, "Namespace/team");
+    }
+
+    #[tokio::test]
+    async fn test_wave_failure_reports_all_failures() {
+        let manifest = format!(
+            "{}---\napiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: later\n  annotations:\n    sherpack.io/sync-wave: \"1\"\n",
+            config_maps(4)
+        );
+        let plan = ExecutionPlan::from_manifest(&manifest).unwrap();
+        let applier =
+            MockApplier::new(&[]).failing_resources(&["ConfigMap/cm-1", "ConfigMap/cm-2"]);
+
+        let err = WaveExecutor::new(&applier, WaveExecutionConfig::default())
+            .execute(&plan)
+            .await
+            .unwrap_err();
+
+        match err {
+            KubeError::WaveApplyFailed { wave, failures } => {
+                assert_eq!(wave, 0);
+                assert_eq!(failures.len(), 2);
+                assert!(failures.iter().any(|f| f.starts_with("ConfigMap/cm-1")));
+                assert!(failures.iter().any(|f| f.starts_with("ConfigMap/cm-2")));
+            }
+            other => panic!("unexpected error: {}", other),
+        }
+        assert_eq!(applier.applied(), vec![0]);
+    }
 }
@@ -1,11 +1,14 @@
 //! Unified repository backend trait
 //!
-//! Provides a single interface for all repository types (HTTP, OCI, File)
+//! Provides a single interface for all repository types (HTTP, OCI, File),
+//! and a cache-only backend for offline use.
 
 use async_trait::async_trait;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
-use crate::config::{Repository, RepositoryType};
+use crate::cache::{ArchiveCache, IndexCache};
+use crate::config::{HttpSettings, Repository, RepositoryType};
 use crate::credentials::{CredentialStore, ResolvedCredentials};
 use crate::error::{RepoError, Result};
 use crate::http::HttpRepository;
@@ -39,6 +42,9 @@ pub trait RepositoryBackend: Send + Sync {
     /// Get a specific version of a pack
     async fn get_version(&mut self, name: &str, version: &str) -> Result<PackEntry>;
 
+    /// List all versions of a pack
+    async fn list_versions(&mut self, name: &str) -> Result<Vec<PackEntry>>;
+
     /// Find best matching version for a constraint
     async fn find_best_match(&mut self, name: &str, constraint: &str) -> Result<PackEntry>;
 
@@ -52,14 +58,33 @@ pub trait RepositoryBackend: Send + Sync {
     async fn exists(&mut self, name: &str, version: Option<&str>) -> Result<bool>;
 }
 
+/// Options for [`create_backend`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BackendOptions {
+    /// Retry and timeout settings for HTTP repositories
+    pub http: HttpSettings,
+
+    /// Serve everything from the local caches, without network access
+    pub offline: bool,
+}
+
 /// Create a repository backend from configuration
+///
+/// In offline mode, every repository type gets a backend reading the
+/// [`IndexCache`] and [`ArchiveCache`], which fails with
+/// [`RepoError::Offline`] when something isn't cached.
 pub async fn create_backend(
     repo: Repository,
     credentials: Option<ResolvedCredentials>,
+    options: BackendOptions,
 ) -> Result<Box<dyn RepositoryBackend>> {
+    if options.offline {
+        return Ok(Box::new(CacheBackend::open(repo)?));
+    }
+
     match repo.repo_type {
         RepositoryType::Http => {
-            let http = HttpRepository::new(repo, credentials)?;
+            let http = HttpRepository::with_settings(repo, credentials, options.http)?;
             Ok(Box::new(HttpBackend(http)))
         }
         RepositoryType::Oci => {
@@ -78,6 +103,7 @@ pub async fn create_backend_by_name(
     name: &str,
     config: &crate::config::RepositoryConfig,
     cred_store: &CredentialStore,
+    offline: bool,
 ) -> Result<Box<dyn RepositoryBackend>> {
     let repo = config
         .get(name)
@@ -86,13 +112,16 @@ pub async fn create_backend_by_name(
         })?
         .clone();
 
-    let credentials = if let Some(cred_ref) = &repo.credential_ref {
-        cred_store.get(cred_ref).and_then(|c| c.resolve().ok())
-    } else {
-        cred_store.get(&repo.name).and_then(|c| c.resolve().ok())
-    };
+    let credentials = cred_store.resolve_for(
+        repo.credential_ref.as_ref().unwrap_or(&repo.name),
+        &repo.url,
+    );
 
-    create_backend(repo, credentials).await
+    let options = BackendOptions {
+        http: config.http,
+        offline,
+    };
+    create_backend(repo, credentials, options).await
 }
 
 // ============ HTTP Backend Wrapper ============
@@ -136,6 +165,11 @@ impl RepositoryBackend for HttpBackend {
         self.0.get_version(name, version).await
     }
 
+    async fn list_versions(&mut self, name: &str) -> Result<Vec<PackEntry>> {
+        let index = self.0.fetch_index().await?;
+        Ok(index.get(name).cloned().unwrap_or_default())
+    }
+
     async fn find_best_match(&mut self, name: &str, constraint: &str) -> Result<PackEntry> {
         self.0.find_best_match(name, constraint).await
     }
@@ -279,6 +313,20 @@ impl RepositoryBackend for OciBackend {
         }
     }
 
+    async fn list_versions(&mut self, name: &str) -> Result<Vec<PackEntry>> {
+        let tags = self.0.list_tags(name).await?;
+        Ok(tags
+            .into_iter()
+            .filter(|tag| semver::Version::parse(tag).is_ok())
+            .map(|tag| PackEntry {
+                name: name.to_string(),
+                urls: vec![format!("{}/{}:{}", self.0.url(), name, tag)],
+                version: tag,
+                ..Default::default()
+            })
+            .collect())
+    }
+
     async fn find_best_match(&mut self, name: &str, constraint: &str) -> Result<PackEntry> {
         // List tags and find best match
         let tags = self.0.list_tags(name).await?;
@@ -429,6 +477,11 @@ impl RepositoryBackend for FileBackend {
             })
     }
 
+    async fn list_versions(&mut self, name: &str) -> Result<Vec<PackEntry>> {
+        let all = self.list().await?;
+        Ok(all.into_iter().filter(|p| p.name == name).collect())
+    }
+
     async fn find_best_match(&mut self, name: &str, constraint: &str) -> Result<PackEntry> {
         let req = semver::VersionReq::parse(constraint)?;
         let all = self.list().await?;
@@ -483,6 +536,172 @@ impl RepositoryBackend for FileBackend {
     }
 }
 
+// ============ Cache Backend (offline) ============
+
+/// Serves a repository from the local caches only
+struct CacheBackend {
+    repo: Repository,
+    index: Mutex<IndexCache>,
+    archives: ArchiveCache,
+}
+
+impl CacheBackend {
+    fn open(repo: Repository) -> Result<Self> {
+        Ok(Self::new(repo, IndexCache::open()?, ArchiveCache::open()?))
+    }
+
+    fn new(repo: Repository, index: IndexCache, archives: ArchiveCache) -> Self {
+        Self {
+            repo,
+            index: Mutex::new(index),
+            archives,
+        }
+    }
+
+    fn not_cached(&self, what: impl std::fmt::Display) -> RepoError {
+        RepoError::Offline {
+            what: format!("{} from repository '{}'", what, self.repo.name),
+        }
+    }
+
+    /// Cached versions of a pack, highest first
+    fn cached_versions(&self, name: &str) -> Result<Vec<PackEntry>> {
+        let index = self.index.lock().unwrap_or_else(|e| e.into_inner());
+        let mut versions: Vec<PackEntry> = index
+            .get_pack_versions(&self.repo.name, name)?
+            .into_iter()
+            .map(PackEntry::from)
+            .collect();
+
+        versions.sort_by(|a, b| {
+            let va = semver::Version::parse(&a.version).ok();
+            let vb = semver::Version::parse(&b.version).ok();
+            vb.cmp(&va)
+        });
+        Ok(versions)
+    }
+}
+
+#[async_trait]
+impl RepositoryBackend for CacheBackend {
+    fn name(&self) -> &str {
+        &self.repo.name
+    }
+
+    fn url(&self) -> &str {
+        &self.repo.url
+    }
+
+    fn repo_type(&self) -> RepositoryType {
+        self.repo.repo_type.clone()
+    }
+
+    async fn refresh(&mut self) -> Result<()> {
+        Err(RepoError::Offline {
+            what: format!("a fresh index of repository '{}'", self.repo.name),
+        })
+    }
+
+    async fn search(&mut self, query: &str) -> Result<Vec<PackEntry>> {
+        let index = self.index.lock().unwrap_or_else(|e| e.into_inner());
+        Ok(index
+            .search_in_repo(&self.repo.name, query)?
+            .into_iter()
+            .map(PackEntry::from)
+            .collect())
+    }
+
+    async fn list(&mut self) -> Result<Vec<PackEntry>> {
+        let index = self.index.lock().unwrap_or_else(|e| e.into_inner());
+        match index.list_latest(&self.repo.name) {
+            Ok(packs) => Ok(packs.into_iter().map(PackEntry::from).collect()),
+            Err(RepoError::RepositoryNotFound { .. }) => Err(self.not_cached("the index")),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn get_latest(&mut self, name: &str) -> Result<PackEntry> {
+        self.cached_versions(name)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| self.not_cached(format!("pack '{}'", name)))
+    }
+
+    async fn get_version(&mut self, name: &str, version: &str) -> Result<PackEntry> {
+        if let Some(entry) = self
+            .cached_versions(name)?
+            .into_iter()
+            .find(|p| p.version == version)
+        {
+            return Ok(entry);
+        }
+
+        // An archive pulled by exact version is enough, even without an index
+        if self.archives.contains(&self.repo.url, name, version) {
+            return Ok(PackEntry {
+                name: name.to_string(),
+                version: version.to_string(),
+                ..Default::default()
+            });
+        }
+
+        Err(self.not_cached(format!("{}@{}", name, version)))
+    }
+
+    async fn list_versions(&mut self, name: &str) -> Result<Vec<PackEntry>> {
+        self.cached_versions(name)
+    }
+
+    async fn find_best_match(&mut self, name: &str, constraint: &str) -> Result<PackEntry> {
+        let req = semver::VersionReq::parse(constraint)?;
+        let versions = self.cached_versions(name)?;
+        if versions.is_empty() {
+            return Err(self.not_cached(format!("pack '{}'", name)));
+        }
+
+        versions
+            .iter()
+            .find(|p| {
+                semver::Version::parse(&p.version)
+                    .map(|v| req.matches(&v))
+                    .unwrap_or(false)
+            })
+            .cloned()
+            .ok_or_else(|| RepoError::UnsatisfiableConstraint {
+                name: name.to_string(),
+                constraint: constraint.to_string(),
+                available: versions
+                    .iter()
+                    .map(|p| p.version.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            })
+    }
+
+    async fn download(&self, name: &str, version: &str) -> Result<Vec<u8>> {
+        self.archives
+            .get(&self.repo.url, name, version)
+            .ok_or_else(|| self.not_cached(format!("archive {}-{}", name, version)))
+    }
+
+    async fn download_to(&self, name: &str, version: &str, dest: &Path) -> Result<()> {
+        let data = self.download(name, version).await?;
+        extract_archive(&data, dest)?;
+        Ok(())
+    }
+
+    async fn exists(&mut self, name: &str, version: Option<&str>) -> Result<bool> {
+        let versions = self.cached_versions(name)?;
+        Ok(match version {
+            Some(v) => {
+                versions.iter().any(|p| p.version == v)
+                    || self.archives.contains(&self.repo.url, name, v)
+            }
+            None => !versions.is_empty(),
+        })
+    }
+}
+
 /// Extract a tar.gz archive
 fn extract_archive(data: &[u8], dest: &Path) -> Result<()> {
     use flate2::read::GzDecoder;
@@ -531,4 +750,62 @@ mod tests {
         let file_repo = Repository::new("test", "file:///path/to/repo").unwrap();
         assert_eq!(file_repo.repo_type, RepositoryType::File);
     }
+
+    #[tokio::test]
+    async fn test_cache_backend_offline() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let repo = Repository::new("stable", "https://charts.example.com").unwrap();
+
+        let mut index = IndexCache::open_memory().unwrap();
+        index
+            .upsert_repository("stable", &repo.url, "http", None)
+            .unwrap();
+        index
+            .add_packs(
+                "stable",
+                &[
+                    PackEntry {
+                        name: "nginx".to_string(),
+                        version: "1.2.0".to_string(),
+                        ..Default::default()
+                    },
+                    PackEntry {
+                        name: "redis".to_string(),
+                        version: "2.0.0".to_string(),
+                        ..Default::default()
+                    },
+                ],
+            )
+            .unwrap();
+        let archives = ArchiveCache::at(dir.path());
+        archives.put(&repo.url, "nginx", "1.2.0", b"nginx").unwrap();
+
+        let mut backend = CacheBackend::new(repo, index, archives);
+
+        let latest = backend.get_latest("nginx").await.unwrap();
+        assert_eq!(latest.version, "1.2.0");
+        assert_eq!(
+            backend
+                .find_best_match("nginx", "^1.0")
+                .await
+                .unwrap()
+                .version,
+            "1.2.0"
+        );
+        assert_eq!(backend.download("nginx", "1.2.0").await.unwrap(), b"nginx");
+
+        // Indexed but never downloaded
+        let err = backend.download("redis", "2.0.0").await.unwrap_err();
+        assert!(matches!(err, RepoError::Offline { .. }));
+        assert!(err.to_string().contains("redis-2.0.0"));
+
+        assert!(matches!(
+            backend.get_latest("postgres").await.unwrap_err(),
+            RepoError::Offline { .. }
+        ));
+        assert!(matches!(
+            backend.refresh().await.unwrap_err(),
+            RepoError::Offline { .. }
+        ));
+    }
 }
@@ -4,20 +4,30 @@
 //! - WAL mode for better concurrency
 //! - FTS5 full-text search
 //! - Auto-recovery on corruption
+//! - Expiry: indices older than a TTL are stale and can be pruned
+//!
+//! Downloaded pack archives are kept next to it by [`ArchiveCache`], so
+//! packs can be served without network access.
 
 use chrono::{DateTime, Utc};
 use rusqlite::{Connection, OpenFlags, params};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
+use crate::credentials::CacheValidators;
 use crate::error::{RepoError, Result};
 use crate::index::PackEntry;
 
 /// SQLite cache for repository indices
 pub struct IndexCache {
     conn: Connection,
+    ttl: Option<Duration>,
 }
 
 impl IndexCache {
+    /// How long a cached index stays fresh by default
+    pub const DEFAULT_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
     /// Open or create cache at default location
     pub fn open() -> Result<Self> {
         let path = Self::default_path()?;
@@ -49,7 +59,10 @@ impl IndexCache {
             }
         };
 
-        let mut cache = Self { conn };
+        let mut cache = Self {
+            conn,
+            ttl: Some(Self::DEFAULT_TTL),
+        };
         cache.init()?;
         Ok(cache)
     }
@@ -57,11 +70,25 @@ impl IndexCache {
     /// Open in-memory cache (for testing)
     pub fn open_memory() -> Result<Self> {
         let conn = Connection::open_in_memory()?;
-        let mut cache = Self { conn };
+        let mut cache = Self {
+            conn,
+            ttl: Some(Self::DEFAULT_TTL),
+        };
         cache.init()?;
         Ok(cache)
     }
 
+    /// Set how long a cached index stays fresh (`None` never expires it)
+    pub fn with_ttl(mut self, ttl: Option<Duration>) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// How long a cached index stays fresh
+    pub fn ttl(&self) -> Option<Duration> {
+        self.ttl
+    }
+
     /// Get default cache path
     pub fn default_path() -> Result<PathBuf> {
         let cache_dir = dirs::cache_dir().ok_or_else(|| RepoError::CacheError {
@@ -85,8 +112,10 @@ impl IndexCache {
                 url TEXT NOT NULL,
                 repo_type TEXT NOT NULL,
                 etag TEXT,
+                last_modified TEXT,
                 last_updated INTEGER,
-                pack_count INTEGER DEFAULT 0
+                pack_count INTEGER DEFAULT 0,
+                not_modified_hits INTEGER DEFAULT 0
             );
 
             -- Packs table
@@ -139,34 +168,100 @@ impl IndexCache {
             "#,
         )?;
 
+        // Columns added after the first release
+        self.ensure_column("repositories", "last_modified", "TEXT")?;
+        self.ensure_column("repositories", "not_modified_hits", "INTEGER DEFAULT 0")?;
+
+        Ok(())
+    }
+
+    /// Add a column to a table created by an older version
+    fn ensure_column(&self, table: &str, column: &str, definition: &str) -> Result<()> {
+        let mut stmt = self
+            .conn
+            .prepare(&format!("SELECT name FROM pragma_table_info('{}')", table))?;
+        let exists = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .filter_map(|name| name.ok())
+            .any(|name| name == column);
+
+        if !exists {
+            self.conn.execute(
+                &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+                [],
+            )?;
+        }
         Ok(())
     }
 
     /// Add or update a repository
+    ///
+    /// `validators` are those of the index the packs were read from.
     pub fn upsert_repository(
         &mut self,
         name: &str,
         url: &str,
         repo_type: &str,
-        etag: Option<&str>,
+        validators: Option<&CacheValidators>,
     ) -> Result<i64> {
+        let validators = validators.cloned().unwrap_or_default();
         self.conn.execute(
             r#"
-            INSERT INTO repositories (name, url, repo_type, etag, last_updated)
-            VALUES (?1, ?2, ?3, ?4, ?5)
+            INSERT INTO repositories (name, url, repo_type, etag, last_modified, last_updated)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
             ON CONFLICT(name) DO UPDATE SET
                 url = excluded.url,
                 repo_type = excluded.repo_type,
                 etag = excluded.etag,
+                last_modified = excluded.last_modified,
                 last_updated = excluded.last_updated
             "#,
-            params![name, url, repo_type, etag, Utc::now().timestamp()],
+            params![
+                name,
+                url,
+                repo_type,
+                validators.etag,
+                validators.last_modified,
+                Utc::now().timestamp()
+            ],
         )?;
 
         let id = self.conn.last_insert_rowid();
         Ok(id)
     }
 
+    /// Validators of the cached index of a repository
+    ///
+    /// Empty if the repository isn't cached or was cached from another URL,
+    /// so the next fetch is unconditional.
+    pub fn validators(&self, name: &str, url: &str) -> Result<CacheValidators> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT etag, last_modified FROM repositories WHERE name = ?1 AND url = ?2")?;
+        let validators = stmt
+            .query_row([name, url], |row| {
+                Ok(CacheValidators {
+                    etag: row.get(0)?,
+                    last_modified: row.get(1)?,
+                })
+            })
+            .ok();
+        Ok(validators.unwrap_or_default())
+    }
+
+    /// Record that the server confirmed the cached index is current (`304`)
+    pub fn record_not_modified(&mut self, name: &str) -> Result<()> {
+        self.conn.execute(
+            r#"
+            UPDATE repositories
+            SET not_modified_hits = not_modified_hits + 1, last_updated = ?2
+            WHERE name = ?1
+            "#,
+            params![name, Utc::now().timestamp()],
+        )?;
+        Ok(())
+    }
+
     /// Get repository ID by name
     pub fn get_repository_id(&self, name: &str) -> Result<Option<i64>> {
         let mut stmt = self
@@ -402,6 +497,65 @@ impl IndexCache {
         Ok(packs)
     }
 
+    /// Oldest `last_updated` that is still fresh, if indices expire
+    fn fresh_since(&self) -> Option<i64> {
+        let ttl = i64::try_from(self.ttl?.as_secs()).unwrap_or(i64::MAX);
+        Some(Utc::now().timestamp().saturating_sub(ttl))
+    }
+
+    /// Whether the cached index of a repository is older than the TTL
+    ///
+    /// A repository that isn't cached is not expired.
+    pub fn is_expired(&self, repo_name: &str) -> Result<bool> {
+        Ok(self.expired_repositories()?.iter().any(|r| r == repo_name))
+    }
+
+    /// Repositories whose cached index is older than the TTL
+    pub fn expired_repositories(&self) -> Result<Vec<String>> {
+        let Some(fresh_since) = self.fresh_since() else {
+            return Ok(Vec::new());
+        };
+
+        let mut stmt = self.conn.prepare(
+            "SELECT name FROM repositories WHERE COALESCE(last_updated, 0) < ?1 ORDER BY name",
+        )?;
+        let names = stmt
+            .query_map([fresh_since], |row| row.get(0))?
+            .collect::<std::result::Result<Vec<String>, _>>()?;
+        Ok(names)
+    }
+
+    /// Remove expired repositories and their packs, then compact the database
+    ///
+    /// Returns the names of the removed repositories.
+    pub fn prune(&mut self) -> Result<Vec<String>> {
+        let expired = self.expired_repositories()?;
+
+        let tx = self.conn.transaction()?;
+        for name in &expired {
+            tx.execute(
+                "DELETE FROM packs WHERE repo_id IN (SELECT id FROM repositories WHERE name = ?1)",
+                [name],
+            )?;
+            tx.execute("DELETE FROM repositories WHERE name = ?1", [name])?;
+        }
+        tx.commit()?;
+
+        self.vacuum()?;
+        Ok(expired)
+    }
+
+    /// Merge the FTS5 index segments and reclaim free pages
+    fn vacuum(&mut self) -> Result<()> {
+        self.conn.execute_batch(
+            r#"
+            INSERT INTO packs_fts(packs_fts) VALUES('optimize');
+            VACUUM;
+            "#,
+        )?;
+        Ok(())
+    }
+
     /// Get cache statistics
     pub fn stats(&self) -> Result<CacheStats> {
         let repo_count: i64 =
@@ -412,6 +566,12 @@ impl IndexCache {
             .conn
             .query_row("SELECT COUNT(*) FROM packs", [], |r| r.get(0))?;
 
+        let not_modified_hits: i64 = self.conn.query_row(
+            "SELECT COALESCE(SUM(not_modified_hits), 0) FROM repositories",
+            [],
+            |r| r.get(0),
+        )?;
+
         let oldest_update: Option<i64> = self
             .conn
             .query_row("SELECT MIN(last_updated) FROM repositories", [], |r| {
@@ -422,8 +582,11 @@ impl IndexCache {
         Ok(CacheStats {
             repository_count: repo_count as usize,
             pack_count: pack_count as usize,
+            not_modified_hits: not_modified_hits as usize,
             oldest_update: oldest_update
                 .map(|ts| DateTime::from_timestamp(ts, 0).unwrap_or_default()),
+            expired: self.expired_repositories()?,
+            ttl: self.ttl,
         })
     }
 
@@ -435,7 +598,7 @@ impl IndexCache {
             DELETE FROM repositories;
             "#,
         )?;
-        Ok(())
+        self.vacuum()
     }
 
     /// Rebuild FTS index (for recovery)
@@ -464,12 +627,99 @@ pub struct CachedPack {
     pub download_url: Option<String>,
 }
 
+impl From<CachedPack> for PackEntry {
+    fn from(pack: CachedPack) -> Self {
+        PackEntry {
+            name: pack.name,
+            version: pack.version,
+            app_version: pack.app_version,
+            description: pack.description,
+            keywords: pack.keywords,
+            deprecated: pack.deprecated,
+            digest: pack.digest,
+            urls: pack.download_url.into_iter().collect(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Pack archives downloaded from repositories
+///
+/// Archives are stored as `<root>/<repository>/<name>-<version>.tgz`, where
+/// the repository directory is derived from its URL: the same pack name in
+/// two repositories never collides, and a renamed repository keeps its
+/// archives.
+#[derive(Debug, Clone)]
+pub struct ArchiveCache {
+    root: PathBuf,
+}
+
+impl ArchiveCache {
+    /// Open the archive cache at the default location
+    pub fn open() -> Result<Self> {
+        Ok(Self::at(Self::default_path()?))
+    }
+
+    /// Use a specific directory
+    pub fn at(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Get default archive directory
+    pub fn default_path() -> Result<PathBuf> {
+        let cache_dir = dirs::cache_dir().ok_or_else(|| RepoError::CacheError {
+            message: "Could not determine cache directory".to_string(),
+        })?;
+        Ok(cache_dir.join("sherpack").join("archives"))
+    }
+
+    /// Path of an archive, whether or not it is cached
+    pub fn path(&self, repo_url: &str, name: &str, version: &str) -> PathBuf {
+        use sha2::{Digest, Sha256};
+        let repo_key = hex::encode(Sha256::digest(repo_url.trim_end_matches('/').as_bytes()));
+
+        self.root
+            .join(&repo_key[..16])
+            .join(format!("{}-{}.tgz", name, version))
+    }
+
+    /// Read a cached archive
+    pub fn get(&self, repo_url: &str, name: &str, version: &str) -> Option<Vec<u8>> {
+        std::fs::read(self.path(repo_url, name, version)).ok()
+    }
+
+    /// Whether an archive is cached
+    pub fn contains(&self, repo_url: &str, name: &str, version: &str) -> bool {
+        self.path(repo_url, name, version).is_file()
+    }
+
+    /// Store a downloaded archive
+    pub fn put(&self, repo_url: &str, name: &str, version: &str, data: &[u8]) -> Result<()> {
+        let path = self.path(repo_url, name, version);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        // Write then rename, so an interrupted download never looks cached
+        let partial = path.with_extension("tgz.partial");
+        std::fs::write(&partial, data)?;
+        std::fs::rename(&partial, &path)?;
+        Ok(())
+    }
+}
+
 /// Cache statistics
 #[derive(Debug, Clone)]
 pub struct CacheStats {
     pub repository_count: usize,
     pub pack_count: usize,
+    /// Index updates answered with `304 Not Modified`
+    pub not_modified_hits: usize,
     pub oldest_update: Option<DateTime<Utc>>,
+    /// Repositories whose index is older than the TTL
+    pub expired: Vec<String>,
+    /// How long an index stays fresh (`None`: never expires)
+    pub ttl: Option<Duration>,
 }
 
 #[cfg(test)]
@@ -567,6 +817,111 @@ mod tests {
         assert_eq!(latest.len(), 2);
     }
 
+    #[test]
+    fn test_validators_and_not_modified_hits() {
+        let mut cache = IndexCache::open_memory().unwrap();
+        let validators = CacheValidators {
+            etag: Some("\"abc\"".to_string()),
+            last_modified: Some("Wed, 21 Oct 2015 07:28:00 GMT".to_string()),
+        };
+        cache
+            .upsert_repository(
+                "bitnami",
+                "https://charts.bitnami.com",
+                "http",
+                Some(&validators),
+            )
+            .unwrap();
+
+        assert_eq!(
+            cache
+                .validators("bitnami", "https://charts.bitnami.com")
+                .unwrap(),
+            validators
+        );
+        // A moved repository must not send validators of the old URL
+        assert!(
+            cache
+                .validators("bitnami", "https://mirror.example.com")
+                .unwrap()
+                .is_empty()
+        );
+
+        cache.record_not_modified("bitnami").unwrap();
+        cache.record_not_modified("bitnami").unwrap();
+        assert_eq!(cache.stats().unwrap().not_modified_hits, 2);
+    }
+
+    #[test]
+    fn test_archive_cache() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let archives = ArchiveCache::at(dir.path());
+
+        assert!(
+            archives
+                .get("https://charts.example.com", "nginx", "1.0.0")
+                .is_none()
+        );
+        archives
+            .put("https://charts.example.com/", "nginx", "1.0.0", b"archive")
+            .unwrap();
+
+        // Trailing slashes don't change the repository
+        assert_eq!(
+            archives.get("https://charts.example.com", "nginx", "1.0.0"),
+            Some(b"archive".to_vec())
+        );
+        assert!(!archives.contains("https://other.example.com", "nginx", "1.0.0"));
+        assert!(!archives.contains("https://charts.example.com", "nginx", "1.0.1"));
+    }
+
+    #[test]
+    fn test_ttl_and_prune() {
+        let mut cache = IndexCache::open_memory().unwrap();
+        for name in ["bitnami", "stable"] {
+            cache
+                .upsert_repository(name, "https://charts.example.com", "http", None)
+                .unwrap();
+            cache.add_packs(name, &sample_packs()).unwrap();
+        }
+
+        // Updated two days ago
+        let two_days_ago = Utc::now().timestamp() - 2 * 24 * 60 * 60;
+        cache
+            .conn
+            .execute(
+                "UPDATE repositories SET last_updated = ?1 WHERE name = 'stable'",
+                [two_days_ago],
+            )
+            .unwrap();
+
+        assert!(cache.is_expired("stable").unwrap());
+        assert!(!cache.is_expired("bitnami").unwrap());
+        assert_eq!(cache.stats().unwrap().expired, ["stable"]);
+
+        // Without a TTL nothing expires
+        let mut cache = cache.with_ttl(None);
+        assert!(cache.expired_repositories().unwrap().is_empty());
+        assert!(cache.prune().unwrap().is_empty());
+
+        let mut cache = cache.with_ttl(Some(IndexCache::DEFAULT_TTL));
+        assert_eq!(cache.prune().unwrap(), ["stable"]);
+
+        let stats = cache.stats().unwrap();
+        assert_eq!(stats.repository_count, 1);
+        assert_eq!(stats.pack_count, sample_packs().len());
+        assert!(stats.expired.is_empty());
+
+        // The FTS index no longer finds the pruned packs
+        assert!(
+            cache
+                .search("nginx")
+                .unwrap()
+                .iter()
+                .all(|p| p.repo_name == "bitnami")
+        );
+    }
+
     #[test]
     fn test_remove_repository() {
         let mut cache = IndexCache::open_memory().unwrap();
@@ -5,6 +5,7 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use crate::error::{RepoError, Result};
 
@@ -19,6 +20,14 @@ pub struct RepositoryConfig {
     /// Configured repositories
     #[serde(default)]
     pub repositories: Vec<Repository>,
+
+    /// Retry and timeout settings for HTTP repositories
+    #[serde(default, skip_serializing_if = "HttpSettings::is_default")]
+    pub http: HttpSettings,
+
+    /// Settings of the local index cache
+    #[serde(default, skip_serializing_if = "CacheSettings::is_default")]
+    pub cache: CacheSettings,
 }
 
 fn default_api_version() -> String {
@@ -30,10 +39,85 @@ impl Default for RepositoryConfig {
         Self {
             api_version: default_api_version(),
             repositories: Vec::new(),
+            http: HttpSettings::default(),
+            cache: CacheSettings::default(),
+        }
+    }
+}
+
+/// Retry and timeout settings for HTTP repository requests
+///
+/// Only GET requests are retried, on connection errors, timeouts and 5xx
+/// responses. The delay doubles after each attempt (with jitter) up to
+/// [`HttpSettings::MAX_BACKOFF`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HttpSettings {
+    /// Attempts per request, including the first one
+    pub max_attempts: u32,
+
+    /// Timeout of a single attempt, in seconds
+    pub timeout_seconds: u64,
+
+    /// Delay before the first retry, in milliseconds
+    pub initial_backoff_ms: u64,
+}
+
+impl HttpSettings {
+    /// Upper bound of the delay between two attempts
+    pub const MAX_BACKOFF: Duration = Duration::from_secs(10);
+
+    /// Timeout of a single attempt
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_seconds)
+    }
+
+    /// Delay before retrying after `attempt` (1-based) failed, without jitter
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u64.saturating_pow(attempt.saturating_sub(1));
+        Duration::from_millis(self.initial_backoff_ms.saturating_mul(factor)).min(Self::MAX_BACKOFF)
+    }
+
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl Default for HttpSettings {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            timeout_seconds: 30,
+            initial_backoff_ms: 500,
         }
     }
 }
 
+/// Settings of the local index cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CacheSettings {
+    /// Hours a cached index stays fresh; `0` never expires it
+    pub ttl_hours: u64,
+}
+
+impl CacheSettings {
+    /// How long a cached index stays fresh, if it expires at all
+    pub fn ttl(&self) -> Option<Duration> {
+        (self.ttl_hours > 0).then(|| Duration::from_secs(self.ttl_hours * 60 * 60))
+    }
+
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl Default for CacheSettings {
+    fn default() -> Self {
+        Self { ttl_hours: 24 }
+    }
+}
+
 impl RepositoryConfig {
     /// Load configuration from default location
     pub fn load() -> Result<Self> {
@@ -299,4 +383,37 @@ mod tests {
         let parsed: RepositoryConfig = serde_yaml::from_str(&yaml).unwrap();
         assert_eq!(parsed.repositories.len(), 1);
     }
+
+    #[test]
+    fn test_http_settings() {
+        let config: RepositoryConfig =
+            serde_yaml::from_str("http:\n  maxAttempts: 5\n  timeoutSeconds: 10\n").unwrap();
+        assert_eq!(config.http.max_attempts, 5);
+        assert_eq!(config.http.timeout(), Duration::from_secs(10));
+        assert_eq!(config.http.initial_backoff_ms, 500);
+
+        assert_eq!(config.http.backoff(1), Duration::from_millis(500));
+        assert_eq!(config.http.backoff(3), Duration::from_secs(2));
+        assert_eq!(config.http.backoff(30), HttpSettings::MAX_BACKOFF);
+
+        // Defaults are not written back
+        let yaml = serde_yaml::to_string(&RepositoryConfig::default()).unwrap();
+        assert!(!yaml.contains("http"));
+    }
+
+    #[test]
+    fn test_cache_settings() {
+        assert_eq!(
+            RepositoryConfig::default().cache.ttl(),
+            Some(Duration::from_secs(24 * 60 * 60))
+        );
+
+        let config: RepositoryConfig = serde_yaml::from_str("cache:\n  ttlHours: 0\n").unwrap();
+        assert_eq!(config.cache.ttl(), None);
+        assert!(
+            serde_yaml::to_string(&config)
+                .unwrap()
+                .contains("ttlHours: 0")
+        );
+    }
 }
@@ -4,13 +4,18 @@
 //! - Credentials scoped to specific URL prefixes
 //! - NEVER sends credentials after cross-origin redirect
 //! - Support for environment variables (CI/CD friendly)
-//! - Optional Docker credential helper integration
+//! - Docker credential helpers and `~/.netrc` as fallbacks, so passwords
+//!   don't have to go through the command line
 
+use rand::RngExt;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 use url::Url;
 
+use crate::config::HttpSettings;
 use crate::error::{RepoError, Result};
 
 /// Credential types supported
@@ -117,6 +122,14 @@ impl ResolvedCredentials {
 pub struct DockerConfig {
     #[serde(default)]
     pub auths: HashMap<String, DockerAuth>,
+
+    /// Credential helper per registry host (`docker-credential-<helper>`)
+    #[serde(default, rename = "credHelpers")]
+    pub cred_helpers: HashMap<String, String>,
+
+    /// Credential helper for registries without a `credHelpers` entry
+    #[serde(default, rename = "credsStore")]
+    pub creds_store: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -158,6 +171,263 @@ impl DockerConfig {
         }
         None
     }
+
+    /// Credential helper configured for a registry host (`host[:port]`)
+    pub fn helper_for(&self, host: &str) -> Option<&str> {
+        self.cred_helpers
+            .get(host)
+            .or(self.creds_store.as_ref())
+            .map(String::as_str)
+    }
+}
+
+/// Registry host of a URL, with the port when one is given
+///
+/// `https://registry.example.com:5000/charts` gives
+/// `registry.example.com:5000`. This is the key Docker credential helpers
+/// and `credHelpers` use.
+pub fn registry_host(url: &str) -> Option<String> {
+    let parsed = Url::parse(url).ok()?;
+    let host = parsed.host_str()?;
+    Some(match parsed.port() {
+        Some(port) => format!("{}:{}", host, port),
+        None => host.to_string(),
+    })
+}
+
+/// Ask a Docker credential helper for the credentials of a registry host
+///
+/// Runs `docker-credential-<helper> get`. A helper that has nothing for the
+/// host, or that isn't installed, yields `None`.
+pub fn credential_helper_lookup(helper: &str, host: &str) -> Option<ResolvedCredentials> {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let program = format!("docker-credential-{}", helper);
+    let mut child = Command::new(&program)
+        .arg("get")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| tracing::debug!("Could not run {}: {}", program, e))
+        .ok()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(host.as_bytes());
+    }
+    let output = child.wait_with_output().ok()?;
+    if !output.status.success() {
+        tracing::debug!("{} has no credentials for {}", program, host);
+        return None;
+    }
+    parse_helper_output(&output.stdout)
+}
+
+/// Parse the JSON a credential helper prints for `get`
+///
+/// A `<token>` username marks an identity token, sent as a bearer token.
+fn parse_helper_output(output: &[u8]) -> Option<ResolvedCredentials> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct HelperCredentials {
+        username: String,
+        secret: String,
+    }
+
+    let creds: HelperCredentials = serde_json::from_slice(output).ok()?;
+    if creds.secret.is_empty() {
+        return None;
+    }
+    Some(if creds.username == "<token>" {
+        ResolvedCredentials::Bearer {
+            token: creds.secret,
+        }
+    } else {
+        ResolvedCredentials::Basic {
+            username: creds.username,
+            password: creds.secret,
+        }
+    })
+}
+
+/// Entries of a `.netrc` file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Netrc {
+    /// `(machine, login, password)` in file order
+    machines: Vec<(String, String, String)>,
+    /// `default` entry
+    default: Option<(String, String)>,
+}
+
+impl Netrc {
+    /// Parse `.netrc` content
+    ///
+    /// Supports `machine`, `default`, `login` and `password`; `macdef`
+    /// bodies are skipped. A machine may carry a port (`host:5000`).
+    pub fn parse(content: &str) -> Self {
+        let mut netrc = Self::default();
+        // (machine, or None for `default`, login, password)
+        let mut current: Option<(Option<String>, String, String)> = None;
+        let mut lines = content.lines();
+
+        fn finish(netrc: &mut Netrc, entry: Option<(Option<String>, String, String)>) {
+            match entry {
+                Some((Some(machine), login, password)) => {
+                    netrc.machines.push((machine, login, password))
+                }
+                Some((None, login, password)) if netrc.default.is_none() => {
+                    netrc.default = Some((login, password))
+                }
+                _ => {}
+            }
+        }
+
+        while let Some(line) = lines.next() {
+            let mut tokens = line.split_whitespace();
+            while let Some(token) = tokens.next() {
+                match token {
+                    "machine" => {
+                        finish(&mut netrc, current.take());
+                        let machine = tokens.next().unwrap_or_default().to_string();
+                        current = Some((Some(machine), String::new(), String::new()));
+                    }
+                    "default" => {
+                        finish(&mut netrc, current.take());
+                        current = Some((None, String::new(), String::new()));
+                    }
+                    "login" => {
+                        if let (Some(entry), Some(value)) = (current.as_mut(), tokens.next()) {
+                            entry.1 = value.to_string();
+                        }
+                    }
+                    "password" => {
+                        if let (Some(entry), Some(value)) = (current.as_mut(), tokens.next()) {
+                            entry.2 = value.to_string();
+                        }
+                    }
+                    "macdef" => {
+                        // The macro body runs up to the next empty line
+                        for body in lines.by_ref() {
+                            if body.trim().is_empty() {
+                                break;
+                            }
+                        }
+                        break;
+                    }
+                    _ if token.starts_with('#') => break,
+                    _ => {}
+                }
+            }
+        }
+        finish(&mut netrc, current);
+        netrc
+    }
+
+    /// Load `$NETRC`, or `~/.netrc`; a missing file is empty
+    pub fn load() -> Result<Self> {
+        let path = match std::env::var_os("NETRC") {
+            Some(path) => PathBuf::from(path),
+            None => match dirs::home_dir() {
+                Some(home) => home.join(".netrc"),
+                None => return Ok(Self::default()),
+            },
+        };
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Ok(Self::parse(&std::fs::read_to_string(path)?))
+    }
+
+    /// Credentials for a URL
+    ///
+    /// A `host:port` machine only matches that port and wins over a bare
+    /// `host` machine, which matches any port. `default` comes last.
+    pub fn credentials_for(&self, url: &str) -> Option<ResolvedCredentials> {
+        let parsed = Url::parse(url).ok()?;
+        let host = parsed.host_str()?;
+        let with_port = parsed.port().map(|port| format!("{}:{}", host, port));
+
+        let find = |machine: &str| {
+            self.machines
+                .iter()
+                .find(|(m, _, _)| m.eq_ignore_ascii_case(machine))
+                .map(|(_, login, password)| (login, password))
+        };
+
+        let (login, password) = with_port
+            .as_deref()
+            .and_then(find)
+            .or_else(|| find(host))
+            .or(self.default.as_ref().map(|(l, p)| (l, p)))?;
+
+        Some(ResolvedCredentials::Basic {
+            username: login.clone(),
+            password: password.clone(),
+        })
+    }
+}
+
+/// Credential sources consulted when a repository has no stored credentials
+#[derive(Debug, Clone, Default)]
+pub struct CredentialFallbacks {
+    /// Docker config, for its credential helpers
+    pub docker: DockerConfig,
+    /// `.netrc` entries
+    pub netrc: Netrc,
+}
+
+impl CredentialFallbacks {
+    /// Load the Docker config and `.netrc`, skipping unreadable files
+    pub fn load() -> Self {
+        Self {
+            docker: load_docker_config(None).unwrap_or_else(|e| {
+                tracing::debug!("Ignoring Docker config: {}", e);
+                DockerConfig::default()
+            }),
+            netrc: Netrc::load().unwrap_or_else(|e| {
+                tracing::debug!("Ignoring .netrc: {}", e);
+                Netrc::default()
+            }),
+        }
+    }
+}
+
+/// Credentials stored by `repo login` for a host or URL scope
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Login {
+    pub credentials: Credentials,
+}
+
+/// Normalize a login scope: `host[:port][/path]`, without scheme
+///
+/// `oci://registry.example.com:5000/team/` becomes
+/// `registry.example.com:5000/team`.
+pub fn login_scope(registry: &str) -> String {
+    let without_scheme = registry
+        .split_once("://")
+        .map_or(registry, |(_, rest)| rest)
+        .trim_end_matches('/');
+    // Host names are case-insensitive, paths are not
+    match without_scheme.split_once('/') {
+        Some((host, path)) => format!("{}/{}", host.to_lowercase(), path),
+        None => without_scheme.to_lowercase(),
+    }
+}
+
+/// Where resolved credentials came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CredentialSource {
+    /// Stored from command-line flags (`repo add --username/--token`)
+    Explicit,
+    /// Stored environment variable references
+    Env,
+    /// Stored by `repo login` for a scope covering the URL
+    Login(String),
+    /// A Docker credential helper
+    Helper(String),
+    /// `.netrc`
+    Netrc,
 }
 
 /// Load Docker config from default or specified path
@@ -187,6 +457,10 @@ pub struct CredentialStore {
     /// Credentials by repository name
     #[serde(default)]
     credentials: HashMap<String, Credentials>,
+
+    /// `repo login` credentials by scope (see [`login_scope`])
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    logins: BTreeMap<String, Login>,
 }
 
 impl CredentialStore {
@@ -222,11 +496,14 @@ impl CredentialStore {
         // Set restrictive permissions on Unix
         #[cfg(unix)]
         {
-            use std::os::unix::fs::OpenOptionsExt;
+            use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
             let content = serde_yaml::to_string(self)?;
             let mut options = std::fs::OpenOptions::new();
             options.write(true).create(true).truncate(true).mode(0o600);
-            std::io::Write::write_all(&mut options.open(path)?, content.as_bytes())?;
+            let mut file = options.open(path)?;
+            // `mode` only applies to new files: tighten an existing one first
+            file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+            std::io::Write::write_all(&mut file, content.as_bytes())?;
             Ok(())
         }
 
@@ -265,6 +542,90 @@ impl CredentialStore {
     pub fn has(&self, repo_name: &str) -> bool {
         self.credentials.contains_key(repo_name)
     }
+
+    /// Store `repo login` credentials for a scope, replacing earlier ones
+    pub fn login(&mut self, registry: &str, login: Login) {
+        self.logins.insert(login_scope(registry), login);
+    }
+
+    /// Remove `repo login` credentials for a scope
+    pub fn logout(&mut self, registry: &str) -> Option<Login> {
+        self.logins.remove(&login_scope(registry))
+    }
+
+    /// `repo login` credentials covering a URL, by longest matching scope
+    ///
+    /// Scopes match on host, port and path boundaries, like
+    /// [`ScopedCredentials`].
+    pub fn login_for(&self, url: &str) -> Option<(&str, &Login)> {
+        let target = login_scope(url);
+        self.logins
+            .iter()
+            .filter(|(scope, _)| {
+                target
+                    .strip_prefix(scope.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with(['/', '?', '#']))
+            })
+            .max_by_key(|(scope, _)| scope.len())
+            .map(|(scope, login)| (scope.as_str(), login))
+    }
+
+    /// Resolve the credentials to use for a repository at `url`
+    ///
+    /// Precedence: stored explicit credentials, then stored environment
+    /// variable references, then `repo login` credentials for the URL, then
+    /// the Docker credential helper for the registry host, then `.netrc`.
+    pub fn resolve_for(&self, repo_name: &str, url: &str) -> Option<ResolvedCredentials> {
+        self.resolve_with(
+            repo_name,
+            url,
+            &CredentialFallbacks::load(),
+            credential_helper_lookup,
+        )
+        .map(|(source, creds)| {
+            tracing::debug!("Using {:?} credentials for {}", source, repo_name);
+            creds
+        })
+    }
+
+    /// [`resolve_for`](Self::resolve_for) with given fallbacks and helper runner
+    pub fn resolve_with(
+        &self,
+        repo_name: &str,
+        url: &str,
+        fallbacks: &CredentialFallbacks,
+        run_helper: impl Fn(&str, &str) -> Option<ResolvedCredentials>,
+    ) -> Option<(CredentialSource, ResolvedCredentials)> {
+        if let Some(stored) = self.get(repo_name) {
+            let source = match stored {
+                Credentials::Env { .. } => CredentialSource::Env,
+                _ => CredentialSource::Explicit,
+            };
+            match stored.resolve() {
+                Ok(creds) => return Some((source, creds)),
+                Err(e) => tracing::debug!("Stored credentials for {}: {}", repo_name, e),
+            }
+        }
+
+        if let Some((scope, login)) = self.login_for(url) {
+            match login.credentials.resolve() {
+                Ok(creds) => return Some((CredentialSource::Login(scope.to_string()), creds)),
+                Err(e) => tracing::debug!("Login credentials for {}: {}", scope, e),
+            }
+        }
+
+        if let Some(host) = registry_host(url)
+            && let Some(helper) = fallbacks.docker.helper_for(&host)
+            && let Some(creds) = run_helper(helper, &host)
+        {
+            return Some((CredentialSource::Helper(helper.to_string()), creds));
+        }
+
+        fallbacks
+            .netrc
+            .credentials_for(url)
+            .map(|creds| (CredentialSource::Netrc, creds))
+    }
 }
 
 /// Scoped credentials - maps URL prefixes to credentials
@@ -283,10 +644,17 @@ impl ScopedCredentials {
     }
 
     /// Get credentials for a URL (by longest matching prefix)
+    ///
+    /// A prefix only matches on a boundary: `https://host/charts` covers
+    /// `https://host/charts/index.yaml`, not `https://host/charts-old/` or
+    /// `https://host:8443/charts/`.
     pub fn for_url(&self, url: &str) -> Option<&ResolvedCredentials> {
         self.scopes
             .iter()
-            .filter(|(prefix, _)| url.starts_with(prefix.as_str()))
+            .filter(|(prefix, _)| {
+                url.strip_prefix(prefix.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with(['/', '?', '#']))
+            })
             .max_by_key(|(prefix, _)| prefix.len())
             .map(|(_, creds)| creds)
     }
@@ -308,19 +676,29 @@ impl ScopedCredentials {
 }
 
 /// Secure HTTP client wrapper with redirect protection
+///
+/// Every request is a GET, so transient failures are retried with
+/// exponential backoff. Each attempt starts over from the original URL and
+/// goes through the same redirect checks.
 pub struct SecureHttpClient {
     client: reqwest::Client,
     credentials: ScopedCredentials,
+    settings: HttpSettings,
 }
 
 impl SecureHttpClient {
     /// Create a new secure HTTP client
     pub fn new(credentials: ScopedCredentials) -> Result<Self> {
+        Self::with_settings(credentials, HttpSettings::default())
+    }
+
+    /// Create a client with custom retry and timeout settings
+    pub fn with_settings(credentials: ScopedCredentials, settings: HttpSettings) -> Result<Self> {
         let client = reqwest::Client::builder()
             // CRITICAL: Disable automatic redirect following
             // We handle redirects manually to prevent credential leaks
             .redirect(reqwest::redirect::Policy::none())
-            .timeout(std::time::Duration::from_secs(30))
+            .timeout(settings.timeout())
             .build()
             .map_err(|e| RepoError::NetworkError {
                 message: e.to_string(),
@@ -329,6 +707,7 @@ impl SecureHttpClient {
         Ok(Self {
             client,
             credentials,
+            settings,
         })
     }
 
@@ -341,7 +720,58 @@ impl SecureHttpClient {
     ///
     /// SECURITY: Credentials are NEVER sent after cross-origin redirects
     pub async fn get(&self, url: &str) -> Result<reqwest::Response> {
-        self.get_with_redirects(url, 10).await
+        self.with_retry(url, || self.get_with_redirects(url, 10))
+            .await
+    }
+
+    /// Run a request until it succeeds, fails permanently, or runs out of attempts
+    async fn with_retry<T, F, Fut>(&self, url: &str, request: F) -> Result<T>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let max_attempts = self.settings.max_attempts.max(1);
+        let mut attempt = 1;
+
+        loop {
+            match request().await {
+                Ok(value) => return Ok(value),
+                Err(e) if e.is_transient() && attempt < max_attempts => {
+                    let delay = jitter(self.settings.backoff(attempt));
+                    tracing::debug!(
+                        "Attempt {}/{} for {} failed ({}), retrying in {:?}",
+                        attempt,
+                        max_attempts,
+                        url,
+                        e,
+                        delay
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(e) if attempt > 1 => {
+                    return Err(RepoError::RetriesExhausted {
+                        url: url.to_string(),
+                        attempts: attempt,
+                        source: Box::new(e),
+                    });
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Send a request, reporting timeouts with the configured duration
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+        request.send().await.map_err(|e| {
+            if e.is_timeout() {
+                RepoError::Timeout {
+                    seconds: self.settings.timeout_seconds,
+                }
+            } else {
+                e.into()
+            }
+        })
     }
 
     async fn get_with_redirects(&self, url: &str, max_redirects: u32) -> Result<reqwest::Response> {
@@ -369,7 +799,7 @@ impl SecureHttpClient {
                 );
             }
 
-            let response = request.send().await?;
+            let response = self.send(request).await?;
             let status = response.status();
 
             // Handle redirects
@@ -432,28 +862,51 @@ impl SecureHttpClient {
     }
 
     /// Fetch bytes from URL
+    ///
+    /// A connection dropped while reading the body is retried too.
     pub async fn get_bytes(&self, url: &str) -> Result<Vec<u8>> {
-        let response = self.get(url).await?;
-        let bytes = response
-            .bytes()
-            .await
-            .map_err(|e| RepoError::NetworkError {
-                message: e.to_string(),
-            })?;
-        Ok(bytes.to_vec())
+        self.with_retry(url, || async {
+            let response = self.get_with_redirects(url, 10).await?;
+            let bytes = response
+                .bytes()
+                .await
+                .map_err(|e| RepoError::NetworkError {
+                    message: e.to_string(),
+                })?;
+            Ok(bytes.to_vec())
+        })
+        .await
     }
 
     /// Fetch text from URL
     pub async fn get_text(&self, url: &str) -> Result<String> {
-        let response = self.get(url).await?;
-        let text = response.text().await.map_err(|e| RepoError::NetworkError {
-            message: e.to_string(),
-        })?;
-        Ok(text)
+        self.with_retry(url, || async {
+            let response = self.get_with_redirects(url, 10).await?;
+            response.text().await.map_err(|e| RepoError::NetworkError {
+                message: e.to_string(),
+            })
+        })
+        .await
+    }
+
+    /// Conditional fetch using the validators of a previous response
+    ///
+    /// Sends `If-None-Match` / `If-Modified-Since` so an unchanged resource
+    /// comes back as [`CachedResponse::NotModified`] without a body.
+    pub async fn get_cached(
+        &self,
+        url: &str,
+        validators: &CacheValidators,
+    ) -> Result<CachedResponse> {
+        self.with_retry(url, || self.get_cached_once(url, validators))
+            .await
     }
 
-    /// Fetch with ETag caching
-    pub async fn get_cached(&self, url: &str, etag: Option<&str>) -> Result<CachedResponse> {
+    async fn get_cached_once(
+        &self,
+        url: &str,
+        validators: &CacheValidators,
+    ) -> Result<CachedResponse> {
         let mut current_url = url.to_string();
         let mut redirects = 0;
         let original_url = url.to_string();
@@ -461,22 +914,30 @@ impl SecureHttpClient {
         loop {
             let mut request = self.client.get(&current_url);
 
-            // Add ETag for conditional request
-            if let Some(etag) = etag {
-                request = request.header("If-None-Match", etag);
-            }
-
-            // Add auth if same origin
-            if ScopedCredentials::same_origin(&original_url, &current_url)
-                && let Some(creds) = self.credentials.for_url(&current_url)
-                && let Some(auth) = creds.auth_header(&current_url)
-            {
-                request = request.header("Authorization", auth);
+            // Validators and auth are only sent to the original origin: like
+            // credentials, they describe our state on that server
+            if ScopedCredentials::same_origin(&original_url, &current_url) {
+                if let Some(etag) = &validators.etag {
+                    request = request.header("If-None-Match", etag);
+                }
+                if let Some(last_modified) = &validators.last_modified {
+                    request = request.header("If-Modified-Since", last_modified);
+                }
+                if let Some(creds) = self.credentials.for_url(&current_url)
+                    && let Some(auth) = creds.auth_header(&current_url)
+                {
+                    request = request.header("Authorization", auth);
+                }
             }
 
-            let response = request.send().await?;
+            let response = self.send(request).await?;
             let status = response.status();
 
+            // Not modified - use cache (checked first: 304 is a 3xx status)
+            if status == reqwest::StatusCode::NOT_MODIFIED {
+                return Ok(CachedResponse::NotModified);
+            }
+
             // Handle redirects
             if status.is_redirection() {
                 redirects += 1;
@@ -498,11 +959,6 @@ impl SecureHttpClient {
                 continue;
             }
 
-            // Not modified - use cache
-            if status == reqwest::StatusCode::NOT_MODIFIED {
-                return Ok(CachedResponse::NotModified);
-            }
-
             if !status.is_success() {
                 return Err(RepoError::HttpError {
                     status: status.as_u16(),
@@ -510,12 +966,17 @@ impl SecureHttpClient {
                 });
             }
 
-            // Get new ETag
-            let new_etag = response
-                .headers()
-                .get("ETag")
-                .and_then(|v| v.to_str().ok())
-                .map(String::from);
+            let header = |name: &str| {
+                response
+                    .headers()
+                    .get(name)
+                    .and_then(|v| v.to_str().ok())
+                    .map(String::from)
+            };
+            let validators = CacheValidators {
+                etag: header("ETag"),
+                last_modified: header("Last-Modified"),
+            };
 
             let bytes = response
                 .bytes()
@@ -526,19 +987,46 @@ impl SecureHttpClient {
 
             return Ok(CachedResponse::Fresh {
                 data: bytes.to_vec(),
-                etag: new_etag,
+                validators,
             });
         }
     }
 }
 
+/// Randomize a backoff delay within its upper half
+///
+/// Spreads out clients that failed at the same moment so they don't retry
+/// in lockstep against a recovering mirror.
+fn jitter(delay: Duration) -> Duration {
+    let half = delay / 2;
+    let spread = rand::rng().random_range(0..=half.as_millis() as u64);
+    half + Duration::from_millis(spread)
+}
+
+/// Validators returned by a server for a resource (`ETag`, `Last-Modified`)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheValidators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl CacheValidators {
+    /// Whether there is anything to make a request conditional on
+    pub fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+}
+
 /// Response from a cached request
 #[derive(Debug)]
 pub enum CachedResponse {
     /// Content hasn't changed, use cached version
     NotModified,
-    /// Fresh content with optional new ETag
-    Fresh { data: Vec<u8>, etag: Option<String> },
+    /// Fresh content with the validators to send next time
+    Fresh {
+        data: Vec<u8>,
+        validators: CacheValidators,
+    },
 }
 
 #[cfg(test)]
@@ -653,4 +1141,382 @@ mod tests {
         store.remove("bitnami");
         assert!(!store.has("bitnami"));
     }
+
+    #[test]
+    fn test_scoped_credentials_match_port_and_subpath() {
+        let mut scoped = ScopedCredentials::default();
+        scoped.add(
+            "https://registry.example.com:5000/charts/",
+            ResolvedCredentials::Bearer {
+                token: "secret".to_string(),
+            },
+        );
+
+        assert!(
+            scoped
+                .for_url("https://registry.example.com:5000/charts/index.yaml")
+                .is_some()
+        );
+        assert!(
+            scoped
+                .for_url("https://registry.example.com:5000/charts")
+                .is_some()
+        );
+        // Other port, or a sibling path sharing the prefix
+        assert!(
+            scoped
+                .for_url("https://registry.example.com:50001/charts/index.yaml")
+                .is_none()
+        );
+        assert!(
+            scoped
+                .for_url("https://registry.example.com/charts/index.yaml")
+                .is_none()
+        );
+        assert!(
+            scoped
+                .for_url("https://registry.example.com:5000/charts-old/index.yaml")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_registry_host() {
+        assert_eq!(
+            registry_host("oci://ghcr.io/myorg/charts").as_deref(),
+            Some("ghcr.io")
+        );
+        assert_eq!(
+            registry_host("https://registry.example.com:5000/charts").as_deref(),
+            Some("registry.example.com:5000")
+        );
+        // Default ports are dropped by the URL parser
+        assert_eq!(
+            registry_host("https://registry.example.com:443/charts").as_deref(),
+            Some("registry.example.com")
+        );
+    }
+
+    const NETRC: &str = "\
+# Company registries
+machine registry.example.com login alice password hunter2
+machine registry.example.com:5000
+    login bob
+    password s3cret
+
+macdef init
+machine evil.example.com login mallory password x
+
+default login anonymous password guest
+";
+
+    fn basic(creds: Option<ResolvedCredentials>) -> Option<(String, String)> {
+        match creds? {
+            ResolvedCredentials::Basic { username, password } => Some((username, password)),
+            other => panic!("Expected Basic credentials, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_netrc_host_matching() {
+        let netrc = Netrc::parse(NETRC);
+        let pair = |u: &str, p: &str| Some((u.to_string(), p.to_string()));
+
+        assert_eq!(
+            basic(netrc.credentials_for("https://registry.example.com/charts/index.yaml")),
+            pair("alice", "hunter2")
+        );
+        // A port-specific machine wins; other ports fall back to the bare host
+        assert_eq!(
+            basic(netrc.credentials_for("https://registry.example.com:5000/v2/")),
+            pair("bob", "s3cret")
+        );
+        assert_eq!(
+            basic(netrc.credentials_for("https://registry.example.com:8443/v2/")),
+            pair("alice", "hunter2")
+        );
+        // Lines inside a macdef are not entries
+        assert_eq!(
+            basic(netrc.credentials_for("https://evil.example.com/")),
+            pair("anonymous", "guest")
+        );
+        assert!(
+            Netrc::parse("machine a.example.com login u password p")
+                .credentials_for("https://b.example.com/")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_credential_helper_output() {
+        let basic_output = br#"{"ServerURL":"ghcr.io","Username":"bob","Secret":"pat"}"#;
+        assert_eq!(
+            basic(parse_helper_output(basic_output)),
+            Some(("bob".to_string(), "pat".to_string()))
+        );
+
+        let token = br#"{"ServerURL":"ghcr.io","Username":"<token>","Secret":"refresh"}"#;
+        assert!(matches!(
+            parse_helper_output(token),
+            Some(ResolvedCredentials::Bearer { token }) if token == "refresh"
+        ));
+
+        assert!(parse_helper_output(b"credentials not found in native keychain").is_none());
+    }
+
+    #[test]
+    fn test_resolve_precedence() {
+        let fallbacks = CredentialFallbacks {
+            docker: DockerConfig {
+                cred_helpers: [("registry.example.com:5000".to_string(), "pass".to_string())]
+                    .into_iter()
+                    .collect(),
+                ..Default::default()
+            },
+            netrc: Netrc::parse(NETRC),
+        };
+        let helper = |helper: &str, host: &str| {
+            assert_eq!(helper, "pass");
+            Some(ResolvedCredentials::Basic {
+                username: format!("helper@{}", host),
+                password: "from-helper".to_string(),
+            })
+        };
+        let url = "https://registry.example.com:5000/charts";
+
+        // Stored credentials come first
+        let mut store = CredentialStore::default();
+        store.set("private", Credentials::basic("flag-user", "flag-pass"));
+        let (source, _) = store
+            .resolve_with("private", url, &fallbacks, helper)
+            .unwrap();
+        assert_eq!(source, CredentialSource::Explicit);
+
+        // Env references that resolve come next; unset ones fall through
+        store.set(
+            "private",
+            Credentials::from_env("SHERPACK_TEST_UNSET_USER", "SHERPACK_TEST_UNSET_PASS"),
+        );
+        let (source, creds) = store
+            .resolve_with("private", url, &fallbacks, helper)
+            .unwrap();
+        assert_eq!(
+            source,
+            CredentialSource::Helper("pass".to_string()),
+            "unset env vars fall back to the credential helper"
+        );
+        assert_eq!(
+            basic(Some(creds)).unwrap().0,
+            "helper@registry.example.com:5000"
+        );
+
+        // The helper is keyed by host and port; without one, .netrc is used
+        let (source, creds) = store
+            .resolve_with(
+                "private",
+                "https://registry.example.com/charts",
+                &fallbacks,
+                helper,
+            )
+            .unwrap();
+        assert_eq!(source, CredentialSource::Netrc);
+        assert_eq!(basic(Some(creds)).unwrap().0, "alice");
+
+        // A helper without credentials for the host falls through to .netrc
+        let (source, _) = store
+            .resolve_with("private", url, &fallbacks, |_: &str, _: &str| None)
+            .unwrap();
+        assert_eq!(source, CredentialSource::Netrc);
+    }
+
+    #[test]
+    fn test_login_scopes() {
+        let mut store = CredentialStore::default();
+        let login = |user: &str| Login {
+            credentials: Credentials::basic(user, "pass"),
+        };
+        store.login("oci://Registry.Example.com:5000/", login("registry"));
+        store.login("registry.example.com:5000/team", login("team"));
+        store.login("https://charts.example.com/private", login("charts"));
+
+        fn user(store: &CredentialStore, url: &str) -> Option<String> {
+            store
+                .login_for(url)
+                .map(|(_, login)| match &login.credentials {
+                    Credentials::Basic { username, .. } => username.clone(),
+                    other => panic!("Expected Basic credentials, got {:?}", other),
+                })
+        }
+        assert_eq!(
+            user(&store, "oci://registry.example.com:5000/other/nginx").as_deref(),
+            Some("registry")
+        );
+        assert_eq!(
+            user(&store, "oci://registry.example.com:5000/team/nginx").as_deref(),
+            Some("team")
+        );
+        assert_eq!(
+            user(&store, "https://charts.example.com/private/index.yaml").as_deref(),
+            Some("charts")
+        );
+        // Other port, or a path outside the scope
+        assert!(user(&store, "oci://registry.example.com/team/nginx").is_none());
+        assert!(user(&store, "https://charts.example.com/public/index.yaml").is_none());
+        assert!(user(&store, "https://charts.example.com/private-old/index.yaml").is_none());
+
+        // Stored repository credentials still win
+        store.set("team-repo", Credentials::bearer("repo-token"));
+        let fallbacks = CredentialFallbacks::default();
+        let (source, _) = store
+            .resolve_with(
+                "team-repo",
+                "oci://registry.example.com:5000/team",
+                &fallbacks,
+                |_: &str, _: &str| None,
+            )
+            .unwrap();
+        assert_eq!(source, CredentialSource::Explicit);
+        let (source, _) = store
+            .resolve_with(
+                "other",
+                "oci://registry.example.com:5000/team",
+                &fallbacks,
+                |_: &str, _: &str| None,
+            )
+            .unwrap();
+        assert_eq!(
+            source,
+            CredentialSource::Login("registry.example.com:5000/team".to_string())
+        );
+
+        assert!(store.logout("registry.example.com:5000/team/").is_some());
+        assert_eq!(
+            user(&store, "oci://registry.example.com:5000/team/nginx").as_deref(),
+            Some("registry")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_save_tightens_existing_file_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("credentials.yaml");
+        std::fs::write(&path, "credentials: {}\n").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+        let mut store = CredentialStore::default();
+        store.login(
+            "ghcr.io",
+            Login {
+                credentials: Credentials::basic("user", "pass"),
+            },
+        );
+        store.save_to(&path).unwrap();
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert!(
+            CredentialStore::load_from(&path)
+                .unwrap()
+                .login_for("oci://ghcr.io/org")
+                .is_some()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retry_keeps_cross_origin_credentials_stripped() {
+        use wiremock::matchers::{header_exists, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let origin = MockServer::start().await;
+        let mirror = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/pack.tar.gz"))
+            .and(header_exists("Authorization"))
+            .respond_with(
+                ResponseTemplate::new(302)
+                    .insert_header("Location", format!("{}/pack.tar.gz", mirror.uri())),
+            )
+            .expect(2)
+            .mount(&origin)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .mount(&mirror)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("data"))
+            .mount(&mirror)
+            .await;
+
+        let mut scoped = ScopedCredentials::default();
+        scoped.add(
+            &origin.uri(),
+            ResolvedCredentials::Bearer {
+                token: "secret".into(),
+            },
+        );
+        let settings = HttpSettings {
+            initial_backoff_ms: 1,
+            ..Default::default()
+        };
+        let client = SecureHttpClient::with_settings(scoped, settings).unwrap();
+
+        let data = client
+            .get_bytes(&format!("{}/pack.tar.gz", origin.uri()))
+            .await
+            .unwrap();
+        assert_eq!(data, b"data");
+
+        let mirrored = mirror.received_requests().await.unwrap();
+        assert_eq!(mirrored.len(), 2);
+        assert!(
+            mirrored
+                .iter()
+                .all(|r| !r.headers.contains_key("authorization"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_conditional_headers_dropped_on_cross_origin_redirect() {
+        use wiremock::matchers::{header, header_exists, method};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let origin = MockServer::start().await;
+        let mirror = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(header("If-None-Match", "\"v1\""))
+            // Not `header()`: it splits values on commas, as in HTTP dates
+            .and(header_exists("If-Modified-Since"))
+            .respond_with(
+                ResponseTemplate::new(302)
+                    .insert_header("Location", format!("{}/index.yaml", mirror.uri())),
+            )
+            .expect(1)
+            .mount(&origin)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("entries: {}"))
+            .mount(&mirror)
+            .await;
+
+        let client = SecureHttpClient::public().unwrap();
+        let validators = CacheValidators {
+            etag: Some("\"v1\"".to_string()),
+            last_modified: Some("Wed, 21 Oct 2015 07:28:00 GMT".to_string()),
+        };
+        let response = client
+            .get_cached(&format!("{}/index.yaml", origin.uri()), &validators)
+            .await
+            .unwrap();
+        assert!(matches!(response, CachedResponse::Fresh { .. }));
+
+        let mirrored = mirror.received_requests().await.unwrap();
+        assert!(mirrored.iter().all(|r| {
+            !r.headers.contains_key("if-none-match") && !r.headers.contains_key("if-modified-since")
+        }));
+    }
 }
@@ -3,18 +3,21 @@
 //! Key features:
 //! - **Static disable**: Skip dependencies with `enabled: false`
 //! - **Condition evaluation**: Skip dependencies based on values.yaml conditions
-//! - **Diamond detection**: Error on conflicting versions
-//! - **No automatic resolution**: Humans must resolve conflicts
+//! - **Backtracking**: Pick the highest versions satisfying every constraint
+//! - **Diamond detection**: Error on conflicting versions, explaining who
+//!   requires what
 
 use semver::{Version, VersionReq};
+use std::cell::{Cell, RefCell};
 use std::collections::{HashMap, HashSet, VecDeque};
 
 use crate::error::{RepoError, Result};
+use crate::git::GitCache;
 use crate::index::PackEntry;
-use crate::lock::{LockFile, LockedDependency};
+use crate::lock::{LockFile, LockedDependency, LockedGitSource};
 
 // Re-export core types for convenience
-pub use sherpack_core::{Dependency, ResolvePolicy};
+pub use sherpack_core::{Dependency, GitSource, ResolvePolicy};
 
 /// Reason why a dependency was skipped during resolution
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -25,6 +28,8 @@ pub enum SkipReason {
     PolicyNever,
     /// Condition evaluated to false
     ConditionFalse { condition: String },
+    /// All tags set in values are false
+    TagsDisabled { tags: Vec<String> },
 }
 
 impl std::fmt::Display for SkipReason {
@@ -33,6 +38,7 @@ impl std::fmt::Display for SkipReason {
             Self::StaticDisabled => write!(f, "enabled: false"),
             Self::PolicyNever => write!(f, "resolve: never"),
             Self::ConditionFalse { condition } => write!(f, "condition '{}' is false", condition),
+            Self::TagsDisabled { tags } => write!(f, "tags {} are false", tags.join(", ")),
         }
     }
 }
@@ -113,15 +119,23 @@ pub fn filter_dependencies(deps: &[Dependency], values: &serde_json::Value) -> F
                 result.to_resolve.push(DependencySpec::from(dep));
             }
             ResolvePolicy::WhenEnabled => {
-                // Check condition if present
+                // Check condition, then tags
                 if dep.should_resolve(values) {
                     result.to_resolve.push(DependencySpec::from(dep));
-                } else if let Some(condition) = &dep.condition {
+                } else {
+                    let reason = match &dep.condition {
+                        Some(condition) if dep.condition_value(values).is_some() => {
+                            SkipReason::ConditionFalse {
+                                condition: condition.clone(),
+                            }
+                        }
+                        _ => SkipReason::TagsDisabled {
+                            tags: dep.tags.clone(),
+                        },
+                    };
                     result.skipped.push(SkippedDependency {
                         dependency: dep.clone(),
-                        reason: SkipReason::ConditionFalse {
-                            condition: condition.clone(),
-                        },
+                        reason,
                     });
                 }
             }
@@ -142,6 +156,8 @@ pub struct DependencySpec {
     pub condition: Option<String>,
     pub tags: Vec<String>,
     pub alias: Option<String>,
+    /// Git source, instead of `repository`
+    pub git: Option<GitSource>,
 }
 
 impl From<&Dependency> for DependencySpec {
@@ -153,6 +169,7 @@ impl From<&Dependency> for DependencySpec {
             condition: dep.condition.clone(),
             tags: dep.tags.clone(),
             alias: dep.alias.clone(),
+            git: dep.git.clone(),
         }
     }
 }
@@ -184,6 +201,8 @@ pub struct ResolvedDependency {
     pub transitive_deps: Vec<String>,
     /// Who required this dependency
     pub required_by: Vec<String>,
+    /// Commit a git dependency resolved to
+    pub git: Option<LockedGitSource>,
 }
 
 impl ResolvedDependency {
@@ -195,10 +214,164 @@ impl ResolvedDependency {
 /// Type alias for the pack fetcher function
 type PackFetcher<'a> = Box<dyn Fn(&str, &str, &str) -> Result<PackEntry> + 'a>;
 
+/// Type alias for the version lister function
+type VersionLister<'a> = Box<dyn Fn(&str, &str) -> Result<Vec<PackEntry>> + 'a>;
+
+/// Candidate versions tried before giving up on a conflict
+const MAX_ATTEMPTS: usize = 10_000;
+
 /// Dependency resolver
 pub struct DependencyResolver<'a> {
     /// Function to fetch pack entry from repository
     fetch_pack: PackFetcher<'a>,
+    /// Function to list all versions of a pack
+    list_versions: Option<VersionLister<'a>>,
+    /// Checkouts for git dependencies
+    git_cache: Option<GitCache>,
+    /// Candidates already looked up, so backtracking doesn't refetch them
+    candidates: RefCell<HashMap<String, Vec<Candidate>>>,
+    /// Candidate versions tried so far
+    attempts: Cell<usize>,
+    /// Packages that may only resolve to the given version
+    pinned: HashMap<String, Version>,
+}
+
+/// A version that may be chosen for a package
+#[derive(Debug, Clone)]
+struct Candidate {
+    entry: PackEntry,
+    version: Version,
+    git: Option<LockedGitSource>,
+}
+
+impl Candidate {
+    fn new(entry: PackEntry, git: Option<LockedGitSource>) -> Result<Self> {
+        let version = Version::parse(&entry.version).map_err(|e| RepoError::ResolutionFailed {
+            message: format!("Invalid version '{}': {}", entry.version, e),
+        })?;
+        Ok(Self {
+            entry,
+            version,
+            git,
+        })
+    }
+}
+
+/// A constraint on a package, with the chain of packages that led to it
+#[derive(Debug, Clone)]
+struct Requirement {
+    spec: DependencySpec,
+    req: VersionReq,
+    /// Packages from the root down to the requirer
+    path: Vec<(String, Version)>,
+}
+
+impl Requirement {
+    fn new(spec: DependencySpec, path: Vec<(String, Version)>) -> Result<Self> {
+        Ok(Self {
+            req: spec.version_req()?,
+            spec,
+            path,
+        })
+    }
+
+    fn requirer(&self) -> String {
+        self.path
+            .last()
+            .map_or_else(|| "root".to_string(), |(name, _)| name.clone())
+    }
+
+    /// `root → app@1.0.0 requires redis ^17.0.0`
+    fn describe(&self) -> String {
+        let chain: Vec<String> = std::iter::once("root".to_string())
+            .chain(
+                self.path
+                    .iter()
+                    .map(|(name, version)| format!("{}@{}", name, version)),
+            )
+            .collect();
+        format!(
+            "{} requires {} {}",
+            chain.join(" → "),
+            self.spec.effective_name(),
+            self.spec.version
+        )
+    }
+}
+
+/// A package chosen at a version, with the requirements it satisfies
+#[derive(Debug, Clone)]
+struct Chosen {
+    candidate: Candidate,
+    requirements: Vec<Requirement>,
+}
+
+/// Partial solution explored by the backtracking search
+#[derive(Debug, Clone, Default)]
+struct SearchState {
+    pending: VecDeque<Requirement>,
+    chosen: HashMap<String, Chosen>,
+}
+
+impl SearchState {
+    /// All requirements on a package: those it satisfies and those pending
+    fn requirements_on(&self, name: &str) -> Vec<&Requirement> {
+        self.chosen
+            .get(name)
+            .map(|c| c.requirements.iter().collect::<Vec<_>>())
+            .unwrap_or_default()
+            .into_iter()
+            .chain(
+                self.pending
+                    .iter()
+                    .filter(|r| r.spec.effective_name() == name),
+            )
+            .collect()
+    }
+
+    /// Requirements that can't all hold at once
+    fn conflict(
+        &self,
+        name: &str,
+        requirements: Vec<Requirement>,
+        available: Vec<Version>,
+    ) -> Conflict {
+        // Any package leading to these requirements, or constraining one
+        // that does, may avoid the conflict by picking another version
+        let mut involved: HashSet<String> = HashSet::from([name.to_string()]);
+        let mut queue: Vec<&Requirement> = requirements.iter().collect();
+        while let Some(requirement) = queue.pop() {
+            for (requirer, _) in &requirement.path {
+                if involved.insert(requirer.clone()) {
+                    queue.extend(self.requirements_on(requirer));
+                }
+            }
+        }
+
+        Conflict {
+            name: name.to_string(),
+            requirements,
+            available,
+            involved,
+        }
+    }
+}
+
+/// Why a partial solution can't be completed
+#[derive(Debug)]
+struct Conflict {
+    name: String,
+    requirements: Vec<Requirement>,
+    /// Versions that were considered
+    available: Vec<Version>,
+    /// Packages whose choice could avoid the conflict
+    involved: HashSet<String>,
+}
+
+/// Outcome of searching from a partial solution
+enum Search {
+    Solved(HashMap<String, Chosen>),
+    Conflict(Conflict),
 }
 
 impl<'a> DependencyResolver<'a> {
@@ -209,106 +382,354 @@ impl<'a> DependencyResolver<'a> {
     {
         Self {
             fetch_pack: Box::new(fetch_pack),
+            list_versions: None,
+            git_cache: None,
+            candidates: RefCell::new(HashMap::new()),
+            attempts: Cell::new(0),
+            pinned: HashMap::new(),
         }
     }
 
+    /// List all versions of a pack, so conflicts can be resolved by
+    /// backtracking to older versions
+    ///
+    /// Without it, or when it lists no versions, a pack's only candidate
+    /// is the best match for its known constraints.
+    pub fn with_versions<F>(mut self, list_versions: F) -> Self
+    where
+        F: Fn(&str, &str) -> Result<Vec<PackEntry>> + 'a,
+    {
+        self.list_versions = Some(Box::new(list_versions));
+        self
+    }
+
+    /// Resolve git dependencies by checking them out into `cache`
+    pub fn with_git_cache(mut self, cache: GitCache) -> Self {
+        self.git_cache = Some(cache);
+        self
+    }
+
+    /// Keep the versions of a lock file, except for the packages in `unpinned`
+    ///
+    /// Pinned packages may only resolve to their locked version, so a
+    /// requirement that needs another one is a conflict. Unpinned packages
+    /// go to the highest version satisfying their constraints, like
+    /// `cargo update -p`.
+    pub fn with_lock(mut self, lock: &LockFile, unpinned: &[&str]) -> Self {
+        for locked in &lock.dependencies {
+            let name = locked.effective_name();
+            if !unpinned.contains(&name) {
+                self.pinned.insert(name.to_string(), locked.version.clone());
+            }
+        }
+        self
+    }
+
     /// Resolve dependencies from specs
     ///
-    /// This will error on any diamond dependency (same package required at different versions).
-    /// Users must explicitly resolve conflicts using aliases or pinning versions.
+    /// Picks the highest versions satisfying every constraint, backtracking
+    /// to older versions on conflicts. When no such set exists, this errors
+    /// with the requirement chains that conflict. Aliases are separate
+    /// packages, so they can be used to install two versions on purpose.
     pub fn resolve(&self, deps: &[DependencySpec]) -> Result<DependencyGraph> {
+        let mut state = SearchState::default();
+        for dep in deps {
+            state
+                .pending
+                .push_back(Requirement::new(dep.clone(), Vec::new())?);
+        }
+
+        self.attempts.set(0);
+        let chosen = match self.search(state)? {
+            Search::Solved(chosen) => chosen,
+            // A single constraint nothing satisfies isn't a diamond
+            Search::Conflict(conflict) if conflict.requirements.len() == 1 => {
+                return Err(RepoError::UnsatisfiableConstraint {
+                    name: conflict.name,
+                    constraint: conflict.requirements[0].spec.version.clone(),
+                    available: format_versions(&conflict.available),
+                });
+            }
+            Search::Conflict(conflict) => {
+                return Err(RepoError::DiamondConflict {
+                    conflicts: format_diamond_conflict(&conflict),
+                });
+            }
+        };
+
         let mut graph = DependencyGraph::new();
-        let mut queue: VecDeque<(DependencySpec, String)> = deps
-            .iter()
-            .map(|d| (d.clone(), "root".to_string()))
-            .collect();
+        for chosen in chosen.into_values() {
+            let Chosen {
+                candidate,
+                requirements,
+            } = chosen;
+            let spec = &requirements[0].spec;
+
+            let mut required_by: Vec<String> = Vec::new();
+            for requirer in requirements.iter().map(Requirement::requirer) {
+                if !required_by.contains(&requirer) {
+                    required_by.push(requirer);
+                }
+            }
 
-        let mut seen: HashSet<String> = HashSet::new();
-
-        while let Some((dep, required_by)) = queue.pop_front() {
-            let effective_name = dep.effective_name().to_string();
-
-            // Check for conflicts
-            if let Some(existing) = graph.get(&effective_name) {
-                // Same package, potentially different version
-                let existing_version = &existing.version;
-                let dep_req = dep.version_req()?;
-
-                // Check if existing version satisfies new constraint
-                if !dep_req.matches(existing_version) {
-                    // CONFLICT! Don't auto-resolve, error out
-                    return Err(RepoError::DiamondConflict {
-                        conflicts: format_diamond_conflict(
-                            &dep.name,
-                            &dep.version,
-                            &required_by,
-                            &existing.version.to_string(),
-                            &existing.required_by,
-                        ),
-                    });
+            graph.add(ResolvedDependency {
+                name: spec.name.clone(),
+                version: candidate.version,
+                repository: candidate
+                    .git
+                    .as_ref()
+                    .map_or_else(|| spec.repository.clone(), |g| g.repo.clone()),
+                constraint: spec.version.clone(),
+                alias: spec.alias.clone(),
+                download_url: candidate
+                    .entry
+                    .download_url()
+                    .unwrap_or_default()
+                    .to_string(),
+                digest: candidate.entry.digest.clone(),
+                transitive_deps: candidate
+                    .entry
+                    .dependencies
+                    .iter()
+                    .map(|d| d.alias.clone().unwrap_or_else(|| d.name.clone()))
+                    .collect(),
+                required_by,
+                git: candidate.git,
+            });
+        }
+
+        // Final check for any diamond dependencies we might have missed
+        graph.check_diamonds()?;
+
+        Ok(graph)
+    }
+
+    /// Satisfy pending requirements, choosing versions depth-first
+    ///
+    /// Each choice is a decision point: when the rest of the search hits a
+    /// conflict that this package takes part in, its next candidate is
+    /// tried. Conflicts it can't affect are passed straight up.
+    fn search(&self, mut state: SearchState) -> Result<Search> {
+        while let Some(requirement) = state.pending.pop_front() {
+            let name = requirement.spec.effective_name().to_string();
+
+            // Already chosen: the version must also satisfy this requirement
+            if let Some(chosen) = state.chosen.get(&name) {
+                if requirement.req.matches(&chosen.candidate.version) {
+                    state
+                        .chosen
+                        .get_mut(&name)
+                        .expect("chosen above")
+                        .requirements
+                        .push(requirement);
+                    continue;
                 }
 
-                // Compatible - just add requirer
-                graph.add_requirer(&effective_name, required_by);
-                continue;
+                let version = chosen.candidate.version.clone();
+                let mut requirements = chosen.requirements.clone();
+                requirements.push(requirement);
+                return Ok(Search::Conflict(state.conflict(
+                    &name,
+                    requirements,
+                    vec![version],
+                )));
             }
 
-            if seen.contains(&effective_name) {
-                continue;
+            // Candidates must satisfy every requirement known so far
+            let mut requirements = vec![&requirement];
+            requirements.extend(state.requirements_on(&name));
+            let (candidates, available) = self.candidates(&requirements)?;
+            let requirements: Vec<Requirement> = requirements.into_iter().cloned().collect();
+
+            let mut conflict: Option<Conflict> = None;
+            for candidate in candidates {
+                if self.attempts.get() >= MAX_ATTEMPTS {
+                    break;
+                }
+                self.attempts.set(self.attempts.get() + 1);
+
+                let mut next = state.clone();
+                let mut path = requirement.path.clone();
+                path.push((name.clone(), candidate.version.clone()));
+                for dep in &candidate.entry.dependencies {
+                    let spec = DependencySpec {
+                        name: dep.name.clone(),
+                        version: dep.version.clone(),
+                        repository: dep
+                            .repository
+                            .clone()
+                            .unwrap_or_else(|| requirement.spec.repository.clone()),
+                        condition: dep.condition.clone(),
+                        tags: dep.tags.clone(),
+                        alias: dep.alias.clone(),
+                        git: dep.git.clone(),
+                    };
+                    next.pending
+                        .push_back(Requirement::new(spec, path.clone())?);
+                }
+                next.chosen.insert(
+                    name.clone(),
+                    Chosen {
+                        candidate,
+                        requirements: vec![requirement.clone()],
+                    },
+                );
+
+                match self.search(next)? {
+                    Search::Solved(chosen) => return Ok(Search::Solved(chosen)),
+                    Search::Conflict(found) => {
+                        let relevant = found.involved.contains(&name);
+                        match &mut conflict {
+                            Some(first) => first.involved.extend(found.involved),
+                            None => conflict = Some(found),
+                        }
+                        if !relevant {
+                            break;
+                        }
+                    }
+                }
             }
-            seen.insert(effective_name.clone());
 
-            // Fetch pack from repository
-            let entry = (self.fetch_pack)(&dep.repository, &dep.name, &dep.version)?;
+            // Every candidate failed: whoever constrains this package is
+            // now part of the conflict too
+            let conflict = match conflict {
+                Some(mut conflict) => {
+                    let widened = state.conflict(&name, requirements, available);
+                    conflict.involved.extend(widened.involved);
+                    conflict
+                }
+                None => state.conflict(&name, requirements, available),
+            };
+            return Ok(Search::Conflict(conflict));
+        }
+
+        Ok(Search::Solved(state.chosen))
+    }
 
-            let version =
-                Version::parse(&entry.version).map_err(|e| RepoError::ResolutionFailed {
-                    message: format!("Invalid version '{}': {}", entry.version, e),
-                })?;
+    /// Candidates satisfying all requirements, highest first, along with
+    /// every version that was considered
+    fn candidates(&self, requirements: &[&Requirement]) -> Result<(Vec<Candidate>, Vec<Version>)> {
+        let spec = &requirements[0].spec;
+        let pinned = self.pinned.get(spec.effective_name());
+
+        let mut all = match &spec.git {
+            Some(source) => self.cached(
+                format!(
+                    "git {} {} {:?}",
+                    source.repo, source.git_ref, source.subpath
+                ),
+                || {
+                    let (entry, git) = self.checkout_git(spec, source)?;
+                    Ok(vec![Candidate::new(entry, Some(git))?])
+                },
+            )?,
+            None => self.listed(&spec.repository, &spec.name)?,
+        };
 
-            // Collect transitive dependencies
-            let transitive: Vec<String> = entry
-                .dependencies
+        if all.is_empty() {
+            // Best match for the constraints combined; when none satisfies
+            // them all, the best match for the first one shows what exists
+            // A pinned version is asked for exactly
+            let mut combined: Vec<String> = requirements
                 .iter()
-                .map(|d| d.alias.clone().unwrap_or_else(|| d.name.clone()))
+                .map(|r| r.spec.version.clone())
                 .collect();
-
-            // Add to graph
-            let resolved = ResolvedDependency {
-                name: dep.name.clone(),
-                version,
-                repository: dep.repository.clone(),
-                constraint: dep.version.clone(),
-                alias: dep.alias.clone(),
-                download_url: entry.download_url().unwrap_or_default().to_string(),
-                digest: entry.digest.clone(),
-                transitive_deps: transitive.clone(),
-                required_by: vec![required_by.clone()],
+            if let Some(version) = pinned {
+                combined.push(format!("={}", version));
+            }
+            let fetch = |constraint: &str| {
+                self.cached(
+                    format!("fetch {} {} {}", spec.repository, spec.name, constraint),
+                    || {
+                        let entry = (self.fetch_pack)(&spec.repository, &spec.name, constraint)?;
+                        Ok(vec![Candidate::new(entry, None)?])
+                    },
+                )
             };
+            all = match fetch(&combined.join(", ")) {
+                Ok(found) => found,
+                Err(_) if combined.len() > 1 => fetch(&spec.version)?,
+                Err(e) => return Err(e),
+            };
+        }
 
-            graph.add(resolved);
+        let mut available: Vec<Version> = all.iter().map(|c| c.version.clone()).collect();
+        available.sort();
+        available.dedup();
 
-            // Queue transitive dependencies
-            for trans_dep in &entry.dependencies {
-                let spec = DependencySpec {
-                    name: trans_dep.name.clone(),
-                    version: trans_dep.version.clone(),
-                    repository: trans_dep
-                        .repository
-                        .clone()
-                        .unwrap_or_else(|| dep.repository.clone()),
-                    condition: trans_dep.condition.clone(),
-                    tags: trans_dep.tags.clone(),
-                    alias: trans_dep.alias.clone(),
-                };
-                queue.push_back((spec, effective_name.clone()));
-            }
+        let mut candidates: Vec<Candidate> = all
+            .into_iter()
+            .filter(|c| requirements.iter().all(|r| r.req.matches(&c.version)))
+            .filter(|c| pinned.is_none_or(|version| &c.version == version))
+            .collect();
+        candidates.sort_by(|a, b| b.version.cmp(&a.version));
+        candidates.dedup_by(|a, b| a.version == b.version);
+
+        Ok((candidates, available))
+    }
+
+    /// All versions of a pack from the version lister, if there is one
+    fn listed(&self, repository: &str, name: &str) -> Result<Vec<Candidate>> {
+        let Some(list_versions) = &self.list_versions else {
+            return Ok(Vec::new());
+        };
+
+        self.cached(format!("list {} {}", repository, name), || {
+            Ok(list_versions(repository, name)?
+                .into_iter()
+                .filter_map(|entry| Candidate::new(entry, None).ok())
+                .collect())
+        })
+    }
+
+    fn cached(
+        &self,
+        key: String,
+        load: impl FnOnce() -> Result<Vec<Candidate>>,
+    ) -> Result<Vec<Candidate>> {
+        if let Some(candidates) = self.candidates.borrow().get(&key) {
+            return Ok(candidates.clone());
         }
 
-        // Final check for any diamond dependencies we might have missed
-        graph.check_diamonds()?;
+        let candidates = load()?;
+        self.candidates.borrow_mut().insert(key, candidates.clone());
+        Ok(candidates)
+    }
 
-        Ok(graph)
+    /// Check out a git dependency and describe the pack found there
+    fn checkout_git(
+        &self,
+        dep: &DependencySpec,
+        source: &GitSource,
+    ) -> Result<(PackEntry, LockedGitSource)> {
+        let cache = self
+            .git_cache
+            .as_ref()
+            .ok_or_else(|| RepoError::ResolutionFailed {
+                message: format!("'{}' is a git dependency, but git is not enabled", dep.name),
+            })?;
+        let checkout = cache.checkout(source)?;
+        let pack = checkout.load_pack()?.pack;
+
+        if pack.metadata.name != dep.name {
+            return Err(RepoError::ResolutionFailed {
+                message: format!(
+                    "git dependency '{}' points at pack '{}'",
+                    dep.name, pack.metadata.name
+                ),
+            });
+        }
+        let entry = PackEntry {
+            digest: Some(checkout.digest()?),
+            ..PackEntry::from_pack(pack, source.repo.clone())
+        };
+        let locked = LockedGitSource {
+            repo: source.repo.clone(),
+            git_ref: source.git_ref.clone(),
+            commit: checkout.commit,
+            subpath: source.subpath.clone(),
+        };
+        Ok((entry, locked))
     }
 
     /// Resolve from existing lock file (for verification)
@@ -326,6 +747,7 @@ impl<'a> DependencyResolver<'a> {
                 digest: Some(locked.digest.clone()),
                 transitive_deps: locked.dependencies.clone(),
                 required_by: vec!["lock file".to_string()],
+                git: locked.git.clone(),
             };
             graph.add(resolved);
         }
@@ -381,11 +803,13 @@ impl DependencyGraph {
     }
 
     /// Check for diamond dependencies
+    ///
+    /// Aliased dependencies are separate packages, so they may use another
+    /// version of a pack than its unaliased occurrence or other aliases.
     pub fn check_diamonds(&self) -> Result<()> {
-        // Group by original name (not alias)
         let mut by_name: HashMap<&str, Vec<&ResolvedDependency>> = HashMap::new();
 
-        for dep in self.dependencies.values() {
+        for dep in self.dependencies.values().filter(|d| d.alias.is_none()) {
             by_name.entry(&dep.name).or_default().push(dep);
         }
 
@@ -430,6 +854,8 @@ impl DependencyGraph {
                 constraint: dep.constraint.clone(),
                 alias: dep.alias.clone(),
                 dependencies: dep.transitive_deps.clone(),
+                git: dep.git.clone(),
+                sources: Vec::new(),
             });
         }
 
@@ -480,84 +906,195 @@ impl DependencyGraph {
 
     /// Render as tree for display
     pub fn render_tree(&self) -> String {
-        let mut lines = Vec::new();
+        self.render_tree_with(&TreeOptions::default())
+    }
 
-        for dep in self.dependencies.values() {
-            if dep.required_by.iter().any(|r| r == "root") {
-                render_tree_node(dep, self, &mut lines, "", true);
+    /// Render the dependency tree with a depth limit and duplicate markers
+    ///
+    /// A dependency is only expanded the first time it is reached; later
+    /// occurrences (including cycles) are printed with a `(*)` suffix.
+    pub fn render_tree_with(&self, options: &TreeOptions) -> String {
+        let mut roots: Vec<&ResolvedDependency> = self
+            .dependencies
+            .values()
+            .filter(|dep| dep.required_by.iter().any(|r| r == "root"))
+            .collect();
+        roots.sort_by(|a, b| a.effective_name().cmp(b.effective_name()));
+
+        let paths = if options.duplicates {
+            self.path_counts(&roots)
+        } else {
+            HashMap::new()
+        };
+
+        let mut renderer = TreeRenderer {
+            graph: self,
+            options,
+            paths,
+            expanded: HashSet::new(),
+            lines: Vec::new(),
+        };
+        let count = roots.len();
+        for (i, dep) in roots.into_iter().enumerate() {
+            renderer.node(dep, "", i == count - 1, 1);
+        }
+
+        renderer.lines.join("\n")
+    }
+
+    /// Count how many distinct paths from the pack reach each dependency
+    fn path_counts(&self, roots: &[&ResolvedDependency]) -> HashMap<String, usize> {
+        fn visit(
+            graph: &DependencyGraph,
+            name: &str,
+            stack: &mut Vec<String>,
+            counts: &mut HashMap<String, usize>,
+        ) {
+            // Cycles are cut where they close, like the rendered tree
+            if stack.iter().any(|n| n == name) {
+                return;
             }
+            *counts.entry(name.to_string()).or_default() += 1;
+            let Some(dep) = graph.get(name) else {
+                return;
+            };
+            stack.push(name.to_string());
+            for trans in &dep.transitive_deps {
+                visit(graph, trans, stack, counts);
+            }
+            stack.pop();
         }
 
-        lines.join("\n")
+        let mut counts = HashMap::new();
+        for root in roots {
+            visit(self, root.effective_name(), &mut Vec::new(), &mut counts);
+        }
+        counts
     }
 }
 
-fn render_tree_node(
-    dep: &ResolvedDependency,
-    graph: &DependencyGraph,
-    lines: &mut Vec<String>,
-    prefix: &str,
-    is_last: bool,
-) {
-    let connector = if is_last { "└── " } else { "├── " };
-    let name_display = if dep.alias.is_some() {
-        format!("{} (alias: {})", dep.name, dep.effective_name())
-    } else {
-        dep.name.clone()
-    };
-
-    lines.push(format!(
-        "{}{}{}@{}",
-        prefix, connector, name_display, dep.version
-    ));
-
-    let new_prefix = format!("{}{}   ", prefix, if is_last { " " } else { "│" });
-
-    let trans_count = dep.transitive_deps.len();
-    for (i, trans_name) in dep.transitive_deps.iter().enumerate() {
-        if let Some(trans_dep) = graph.get(trans_name) {
-            render_tree_node(trans_dep, graph, lines, &new_prefix, i == trans_count - 1);
+/// Options for [`DependencyGraph::render_tree_with`]
+#[derive(Debug, Clone, Default)]
+pub struct TreeOptions {
+    /// Maximum number of levels below the pack to display (`None` = unlimited)
+    pub depth: Option<usize>,
+    /// Annotate dependencies reached through more than one path
+    pub duplicates: bool,
+}
+
+struct TreeRenderer<'a> {
+    graph: &'a DependencyGraph,
+    options: &'a TreeOptions,
+    paths: HashMap<String, usize>,
+    expanded: HashSet<String>,
+    lines: Vec<String>,
+}
+
+impl TreeRenderer<'_> {
+    fn node(&mut self, dep: &ResolvedDependency, prefix: &str, is_last: bool, level: usize) {
+        if self.options.depth.is_some_and(|max| level > max) {
+            return;
+        }
+
+        let connector = if is_last { "└── " } else { "├── " };
+        let mut line = if dep.alias.is_some() {
+            format!(
+                "{}{}{} (alias: {})@{}",
+                prefix,
+                connector,
+                dep.name,
+                dep.effective_name(),
+                dep.version
+            )
+        } else {
+            format!("{}{}{}@{}", prefix, connector, dep.name, dep.version)
+        };
+
+        if let Some(&count) = self.paths.get(dep.effective_name())
+            && count > 1
+        {
+            line.push_str(&format!(" [duplicate: {} paths]", count));
+        }
+
+        let first_visit = self.expanded.insert(dep.effective_name().to_string());
+        if !first_visit {
+            line.push_str(" (*)");
+        }
+        self.lines.push(line);
+        if !first_visit {
+            return;
+        }
+
+        let new_prefix = format!("{}{}   ", prefix, if is_last { " " } else { "│" });
+        let graph = self.graph;
+        let children: Vec<&ResolvedDependency> = dep
+            .transitive_deps
+            .iter()
+            .filter_map(|name| graph.get(name))
+            .collect();
+        let count = children.len();
+        for (i, child) in children.into_iter().enumerate() {
+            self.node(child, &new_prefix, i == count - 1, level + 1);
         }
     }
 }
 
 /// Format a diamond conflict error message
-fn format_diamond_conflict(
-    name: &str,
-    new_constraint: &str,
-    new_requirer: &str,
-    existing_version: &str,
-    existing_requirers: &[String],
-) -> String {
+fn format_versions(versions: &[Version]) -> String {
+    if versions.is_empty() {
+        return "none".to_string();
+    }
+    versions
+        .iter()
+        .map(Version::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn format_diamond_conflict(conflict: &Conflict) -> String {
+    let name = &conflict.name;
+    let requirements: Vec<String> = conflict
+        .requirements
+        .iter()
+        .map(|r| format!("  {}", r.describe()))
+        .collect();
+    let available = format_versions(&conflict.available);
+
+    let mut constraints: Vec<&str> = Vec::new();
+    for requirement in &conflict.requirements {
+        if !constraints.contains(&requirement.spec.version.as_str()) {
+            constraints.push(&requirement.spec.version);
+        }
+    }
+    let first = constraints.first().copied().unwrap_or("*");
+    let second = constraints.get(1).copied().unwrap_or(first);
+
     format!(
         r#"Diamond dependency conflict for '{name}':
 
-  Version {existing_version} required by: {existing}
-  Version {new_constraint} required by: {new_requirer}
+{requirements}
+
+  No version satisfies all of them (considered: {available})
 
 Solutions:
-  1. Pin a specific version in your Pack.yaml:
+  1. Pin a version that satisfies every requirement in your Pack.yaml:
      dependencies:
        - name: {name}
-         version: "{existing_version}"  # or another compatible version
+         version: "<version>"
 
   2. Use aliases to install both versions (creates TWO deployments!):
      dependencies:
        - name: {name}
-         version: "{existing_version}"
+         version: "{first}"
          alias: {name}-v1
        - name: {name}
-         version: "{new_constraint}"
+         version: "{second}"
          alias: {name}-v2
 
   3. Update the conflicting dependency to use a compatible version
 
 For more information: https://sherpack.io/docs/dependencies#conflicts"#,
-        name = name,
-        existing_version = existing_version,
-        new_constraint = new_constraint,
-        new_requirer = new_requirer,
-        existing = existing_requirers.join(", "),
+        requirements = requirements.join("\n"),
     )
 }
 
@@ -590,6 +1127,7 @@ mod tests {
                     condition: None,
                     tags: vec![],
                     alias: None,
+                    git: None,
                 })
                 .collect(),
             annotations: std::collections::HashMap::new(),
@@ -625,6 +1163,7 @@ mod tests {
                 condition: None,
                 tags: vec![],
                 alias: None,
+                git: None,
             },
             DependencySpec {
                 name: "redis".to_string(),
@@ -633,6 +1172,7 @@ mod tests {
                 condition: None,
                 tags: vec![],
                 alias: None,
+                git: None,
             },
         ];
 
@@ -642,6 +1182,60 @@ mod tests {
         assert!(graph.get("redis").is_some());
     }
 
+    #[test]
+    fn test_git_resolution() {
+        let upstream = tempfile::TempDir::new().unwrap();
+        let cache_dir = tempfile::TempDir::new().unwrap();
+        let repo = crate::git::tests::pack_repository(upstream.path());
+
+        let spec = |version: &str| DependencySpec {
+            name: "app".to_string(),
+            version: version.to_string(),
+            repository: String::new(),
+            condition: None,
+            tags: vec![],
+            alias: None,
+            git: Some(GitSource {
+                repo: repo.clone(),
+                git_ref: "v1.0.0".to_string(),
+                subpath: Some("packs/app".to_string()),
+            }),
+        };
+        let no_fetch = |_: &str, name: &str, _: &str| -> Result<PackEntry> {
+            panic!("'{}' should not be fetched from a repository", name)
+        };
+
+        // Git dependencies need a checkout cache
+        assert!(
+            DependencyResolver::new(no_fetch)
+                .resolve(&[spec("^1.0.0")])
+                .is_err()
+        );
+
+        let resolver =
+            DependencyResolver::new(no_fetch).with_git_cache(GitCache::at(cache_dir.path()));
+        let graph = resolver.resolve(&[spec("^1.0.0")]).unwrap();
+        let app = graph.get("app").unwrap();
+        assert_eq!(app.version.to_string(), "1.0.0");
+        assert!(app.digest.as_ref().unwrap().starts_with("sha256:"));
+
+        let git = app.git.as_ref().unwrap();
+        assert_eq!(git.commit.len(), 40);
+        assert_eq!(git.git_ref, "v1.0.0");
+        assert_eq!(app.repository, repo);
+
+        let lock = graph.to_lock_file("");
+        assert_eq!(
+            lock.get("app").unwrap().git.as_ref().unwrap().commit,
+            git.commit
+        );
+
+        assert!(matches!(
+            resolver.resolve(&[spec("^2.0.0")]),
+            Err(RepoError::UnsatisfiableConstraint { .. })
+        ));
+    }
+
     #[test]
     fn test_transitive_resolution() {
         let packs: HashMap<(&str, &str), PackEntry> = [
@@ -671,6 +1265,7 @@ mod tests {
             condition: None,
             tags: vec![],
             alias: None,
+            git: None,
         }];
 
         let graph = resolver.resolve(&deps).unwrap();
@@ -716,6 +1311,7 @@ mod tests {
                 condition: None,
                 tags: vec![],
                 alias: None,
+                git: None,
             },
             DependencySpec {
                 name: "app2".to_string(),
@@ -724,6 +1320,7 @@ mod tests {
                 condition: None,
                 tags: vec![],
                 alias: None,
+                git: None,
             },
         ];
 
@@ -734,6 +1331,178 @@ mod tests {
         assert!(matches!(err, RepoError::DiamondConflict { .. }));
     }
 
+    fn root_spec(name: &str, version: &str) -> DependencySpec {
+        DependencySpec {
+            name: name.to_string(),
+            version: version.to_string(),
+            repository: "repo".to_string(),
+            condition: None,
+            tags: vec![],
+            alias: None,
+            git: None,
+        }
+    }
+
+    /// Versions of every pack, for both the fetcher and the lister
+    fn versions_of<'a>(
+        packs: &'a [PackEntry],
+        name: &'a str,
+    ) -> impl Iterator<Item = &'a PackEntry> {
+        packs.iter().filter(move |p| p.name == name)
+    }
+
+    #[test]
+    fn test_backtracking_picks_compatible_versions() {
+        // app1@2.0.0 needs redis 17, which app2 can't use: app1 must drop to 1.0.0
+        let packs = vec![
+            mock_pack("app1", "2.0.0", vec![("redis", "^17.0.0")]),
+            mock_pack("app1", "1.0.0", vec![("redis", "^16.0.0")]),
+            mock_pack("app2", "1.0.0", vec![("redis", "^16.1.0")]),
+            mock_pack("redis", "17.0.0", vec![]),
+            mock_pack("redis", "16.2.0", vec![]),
+            mock_pack("redis", "16.0.0", vec![]),
+        ];
+
+        let resolver = DependencyResolver::new(|_, name, _| {
+            Err(RepoError::Other(format!(
+                "'{}' should come from the lister",
+                name
+            )))
+        })
+        .with_versions(|_, name| Ok(versions_of(&packs, name).cloned().collect()));
+
+        let graph = resolver
+            .resolve(&[root_spec("app1", ">=1.0.0"), root_spec("app2", "^1.0.0")])
+            .unwrap();
+
+        assert_eq!(graph.get("app1").unwrap().version.to_string(), "1.0.0");
+        assert_eq!(graph.get("redis").unwrap().version.to_string(), "16.2.0");
+        let mut required_by = graph.get("redis").unwrap().required_by.clone();
+        required_by.sort();
+        assert_eq!(required_by, ["app1", "app2"]);
+    }
+
+    #[test]
+    fn test_selective_update_keeps_other_entries() {
+        let old = vec![
+            mock_pack("app1", "1.0.0", vec![("redis", "^16.0.0")]),
+            mock_pack("app2", "1.0.0", vec![("redis", "^16.0.0")]),
+            mock_pack("redis", "16.0.0", vec![]),
+        ];
+        let mut current = old.clone();
+        current.extend([
+            mock_pack("app1", "1.1.0", vec![("redis", "^16.0.0")]),
+            mock_pack("app1", "2.0.0", vec![("redis", "^17.0.0")]),
+            mock_pack("app2", "1.1.0", vec![("redis", "^16.0.0")]),
+            mock_pack("redis", "16.1.0", vec![]),
+            mock_pack("redis", "17.0.0", vec![]),
+        ]);
+        let resolver = |packs: &[PackEntry]| {
+            let packs = packs.to_vec();
+            DependencyResolver::new(|_, name, _| {
+                Err(RepoError::Other(format!(
+                    "'{}' should come from the lister",
+                    name
+                )))
+            })
+            .with_versions(move |_, name| Ok(versions_of(&packs, name).cloned().collect()))
+        };
+        let specs = [root_spec("app1", ">=1.0.0"), root_spec("app2", "^1.0.0")];
+
+        let mut lock = resolver(&old).resolve(&specs).unwrap().to_lock_file("pack");
+        lock.dependencies
+            .iter_mut()
+            .find(|d| d.name == "app2")
+            .unwrap()
+            .digest = "sha256:republished".to_string();
+        let untouched = |lock: &LockFile| {
+            ["app2", "redis"].map(|name| {
+                let dep = lock.get(name).unwrap();
+                (dep.version.to_string(), dep.digest.clone())
+            })
+        };
+        let before = untouched(&lock);
+
+        // app1 goes to the highest version in range: 2.0.0 needs redis 17,
+        // but redis is pinned at 16
+        let graph = resolver(&current)
+            .with_lock(&lock, &["app1"])
+            .resolve(&specs)
+            .unwrap();
+        let mut updated = lock.clone();
+        updated.apply_update(graph.to_lock_file("pack"), "app1");
+        assert_eq!(updated.get("app1").unwrap().version.to_string(), "1.1.0");
+        assert_eq!(untouched(&updated), before);
+        assert_eq!(updated.dependencies.len(), 3);
+
+        // A constraint only a conflicting version satisfies is refused
+        let err = resolver(&current)
+            .with_lock(&lock, &["app1"])
+            .resolve(&[root_spec("app1", "^2.0.0"), root_spec("app2", "^1.0.0")])
+            .unwrap_err();
+        assert!(matches!(err, RepoError::DiamondConflict { .. }), "{}", err);
+    }
+
+    #[test]
+    fn test_best_match_for_combined_constraints() {
+        // Without a lister, redis is fetched once for both constraints
+        let packs = vec![
+            mock_pack("app1", "1.0.0", vec![("redis", "^17.0.0")]),
+            mock_pack("app2", "1.0.0", vec![("redis", "=17.0.0")]),
+            mock_pack("redis", "17.1.0", vec![]),
+            mock_pack("redis", "17.0.0", vec![]),
+        ];
+
+        let resolver = DependencyResolver::new(|repo, name, constraint| {
+            let req = VersionReq::parse(constraint)?;
+            versions_of(&packs, name)
+                .filter(|p| req.matches(&Version::parse(&p.version).unwrap()))
+                .max_by_key(|p| Version::parse(&p.version).unwrap())
+                .cloned()
+                .ok_or_else(|| RepoError::PackNotFound {
+                    name: name.to_string(),
+                    repo: repo.to_string(),
+                })
+        });
+
+        let graph = resolver
+            .resolve(&[root_spec("app1", "^1.0.0"), root_spec("app2", "^1.0.0")])
+            .unwrap();
+
+        assert_eq!(graph.get("redis").unwrap().version.to_string(), "17.0.0");
+    }
+
+    #[test]
+    fn test_conflict_explains_requirement_paths() {
+        let packs = vec![
+            mock_pack("web", "1.0.0", vec![("cache", "^1.0.0")]),
+            mock_pack("cache", "1.0.0", vec![("redis", "^17.0.0")]),
+            mock_pack("worker", "1.0.0", vec![("redis", "^16.0.0")]),
+            mock_pack("redis", "17.0.0", vec![]),
+            mock_pack("redis", "16.0.0", vec![]),
+        ];
+
+        let resolver = DependencyResolver::new(|_, name, _| {
+            Err(RepoError::Other(format!(
+                "'{}' should come from the lister",
+                name
+            )))
+        })
+        .with_versions(|_, name| Ok(versions_of(&packs, name).cloned().collect()));
+
+        let err = resolver
+            .resolve(&[root_spec("web", "^1.0.0"), root_spec("worker", "^1.0.0")])
+            .unwrap_err();
+
+        let RepoError::DiamondConflict { conflicts } = err else {
+            panic!("expected a diamond conflict, got {:?}", err);
+        };
+        assert!(conflicts.contains("Diamond dependency conflict for 'redis'"));
+        assert!(conflicts.contains("root → web@1.0.0 → cache@1.0.0 requires redis ^17.0.0"));
+        assert!(conflicts.contains("root → worker@1.0.0 requires redis ^16.0.0"));
+        assert!(conflicts.contains("considered: 16.0.0, 17.0.0"));
+    }
+
     #[test]
     fn test_compatible_versions() {
         // app1 -> redis@^17.0.0
@@ -771,6 +1540,7 @@ mod tests {
                 condition: None,
                 tags: vec![],
                 alias: None,
+                git: None,
             },
             DependencySpec {
                 name: "app2".to_string(),
@@ -779,6 +1549,7 @@ mod tests {
                 condition: None,
                 tags: vec![],
                 alias: None,
+                git: None,
             },
         ];
 
@@ -816,6 +1587,7 @@ mod tests {
                 condition: None,
                 tags: vec![],
                 alias: Some("cache-redis".to_string()),
+                git: None,
             },
             DependencySpec {
                 name: "redis".to_string(),
@@ -824,6 +1596,7 @@ mod tests {
                 condition: None,
                 tags: vec![],
                 alias: Some("session-redis".to_string()),
+                git: None,
             },
         ];
 
@@ -833,6 +1606,39 @@ mod tests {
         assert!(graph.get("session-redis").is_some());
     }
 
+    #[test]
+    fn test_aliases_resolve_to_different_versions() {
+        let packs = vec![
+            mock_pack("redis", "17.0.0", vec![]),
+            mock_pack("redis", "16.2.0", vec![]),
+        ];
+        let resolver = DependencyResolver::new(|_, name, _| {
+            Err(RepoError::Other(format!(
+                "'{}' should come from the lister",
+                name
+            )))
+        })
+        .with_versions(|_, name| Ok(versions_of(&packs, name).cloned().collect()));
+
+        let aliased = |alias: &str, version: &str| DependencySpec {
+            alias: Some(alias.to_string()),
+            ..root_spec("redis", version)
+        };
+        let graph = resolver
+            .resolve(&[
+                root_spec("redis", "^17.0.0"),
+                aliased("cache", "^17.0.0"),
+                aliased("legacy", "^16.0.0"),
+            ])
+            .unwrap();
+
+        assert_eq!(graph.len(), 3);
+        assert_eq!(graph.get("redis").unwrap().version.to_string(), "17.0.0");
+        assert_eq!(graph.get("cache").unwrap().version.to_string(), "17.0.0");
+        assert_eq!(graph.get("legacy").unwrap().version.to_string(), "16.2.0");
+        assert!(graph.check_diamonds().is_ok());
+    }
+
     #[test]
     fn test_install_order() {
         let packs: HashMap<(&str, &str), PackEntry> = [
@@ -866,6 +1672,7 @@ mod tests {
             condition: None,
             tags: vec![],
             alias: None,
+            git: None,
         }];
 
         let graph = resolver.resolve(&deps).unwrap();
@@ -895,6 +1702,7 @@ mod tests {
             digest: None,
             transitive_deps: vec!["redis".to_string()],
             required_by: vec!["root".to_string()],
+            git: None,
         });
 
         graph.add(ResolvedDependency {
@@ -907,6 +1715,7 @@ mod tests {
             digest: None,
             transitive_deps: vec![],
             required_by: vec!["app".to_string()],
+            git: None,
         });
 
         let tree = graph.render_tree();
@@ -914,6 +1723,66 @@ mod tests {
         assert!(tree.contains("redis@17.0.0"));
     }
 
+    fn tree_dep(name: &str, deps: &[&str], required_by: &[&str]) -> ResolvedDependency {
+        ResolvedDependency {
+            name: name.to_string(),
+            version: Version::new(1, 0, 0),
+            repository: "repo".to_string(),
+            constraint: "^1.0.0".to_string(),
+            alias: None,
+            download_url: String::new(),
+            digest: None,
+            transitive_deps: deps.iter().map(|d| d.to_string()).collect(),
+            required_by: required_by.iter().map(|r| r.to_string()).collect(),
+            git: None,
+        }
+    }
+
+    #[test]
+    fn test_render_tree_shared_dependency_marked_duplicate() {
+        let mut graph = DependencyGraph::new();
+        graph.add(tree_dep("postgresql", &["common"], &["root"]));
+        graph.add(tree_dep("redis", &["common"], &["root"]));
+        graph.add(tree_dep("common", &[], &["postgresql", "redis"]));
+
+        let tree = graph.render_tree();
+        assert_eq!(
+            tree,
+            "├── postgresql@1.0.0\n\
+             │   └── common@1.0.0\n\
+             └── redis@1.0.0\n    \
+             └── common@1.0.0 (*)"
+        );
+
+        let tree = graph.render_tree_with(&TreeOptions {
+            duplicates: true,
+            ..Default::default()
+        });
+        assert!(tree.contains("│   └── common@1.0.0 [duplicate: 2 paths]\n"));
+        assert!(tree.ends_with("common@1.0.0 [duplicate: 2 paths] (*)"));
+        assert!(!tree.contains("redis@1.0.0 [duplicate"));
+    }
+
+    #[test]
+    fn test_render_tree_cycle_and_depth() {
+        let mut graph = DependencyGraph::new();
+        graph.add(tree_dep("a", &["b"], &["root"]));
+        graph.add(tree_dep("b", &["a"], &["a"]));
+
+        // Terminates on the cycle instead of recursing forever
+        let tree = graph.render_tree();
+        assert_eq!(
+            tree,
+            "└── a@1.0.0\n    └── b@1.0.0\n        └── a@1.0.0 (*)"
+        );
+
+        let tree = graph.render_tree_with(&TreeOptions {
+            depth: Some(1),
+            ..Default::default()
+        });
+        assert_eq!(tree, "└── a@1.0.0");
+    }
+
     // =========================================================================
     // Filtering tests
     // =========================================================================
@@ -928,11 +1797,13 @@ mod tests {
             name: name.to_string(),
             version: "1.0.0".to_string(),
             repository: "https://example.com".to_string(),
+            git: None,
             enabled,
             condition: condition.map(String::from),
             resolve,
             tags: vec![],
             alias: None,
+            import_values: vec![],
         }
     }
 
@@ -1047,24 +1918,47 @@ mod tests {
     }
 
     #[test]
-    fn test_filter_condition_missing_is_falsy() {
+    fn test_filter_condition_missing_is_enabled() {
         let deps = vec![make_dep(
             "redis",
             true,
             ResolvePolicy::WhenEnabled,
             Some("redis.enabled"),
         )];
-        // redis.enabled not set at all → path doesn't exist → falsy → skip
+        // redis.enabled not set at all → path doesn't exist → enabled, as in Helm
         let values = serde_json::json!({});
 
         let result = filter_dependencies(&deps, &values);
 
-        // Missing condition path = falsy = skip the dependency
-        assert!(result.to_resolve.is_empty());
-        assert_eq!(result.skipped.len(), 1);
+        assert_eq!(result.to_resolve.len(), 1);
+        assert!(result.skipped.is_empty());
+    }
+
+    #[test]
+    fn test_filter_tags() {
+        let mut cache = make_dep("redis", true, ResolvePolicy::WhenEnabled, None);
+        cache.tags = vec!["cache".to_string()];
+        let mut db = make_dep(
+            "postgres",
+            true,
+            ResolvePolicy::WhenEnabled,
+            Some("postgres.enabled"),
+        );
+        db.tags = vec!["cache".to_string()];
+
+        // The condition wins over a disabled tag
+        let values = serde_json::json!({
+            "tags": { "cache": false },
+            "postgres": { "enabled": true }
+        });
+
+        let result = filter_dependencies(&[cache, db], &values);
+
+        assert_eq!(result.to_resolve.len(), 1);
+        assert_eq!(result.to_resolve[0].name, "postgres");
         assert!(matches!(
             &result.skipped[0].reason,
-            SkipReason::ConditionFalse { condition } if condition == "redis.enabled"
+            SkipReason::TagsDisabled { tags } if tags == &["cache"]
         ));
     }
 
@@ -1123,6 +2017,13 @@ mod tests {
             .to_string(),
             "condition 'redis.enabled' is false"
         );
+        assert_eq!(
+            SkipReason::TagsDisabled {
+                tags: vec!["cache".to_string(), "backend".to_string()]
+            }
+            .to_string(),
+            "tags cache, backend are false"
+        );
     }
 
     #[test]
@@ -1149,11 +2050,13 @@ mod tests {
             name: "redis".to_string(),
             version: "^17.0.0".to_string(),
             repository: "https://example.com".to_string(),
+            git: None,
             enabled: true,
             condition: Some("redis.enabled".to_string()),
             resolve: ResolvePolicy::WhenEnabled,
             tags: vec!["cache".to_string()],
             alias: Some("my-redis".to_string()),
+            import_values: vec![],
         };
 
         let spec = DependencySpec::from(&dep);
@@ -31,6 +31,14 @@ pub enum RepoError {
     #[error("Rate limited by server. Retry after {retry_after} seconds")]
     RateLimited { retry_after: u64 },
 
+    #[error("Request to {url} failed after {attempts} attempts: {source}")]
+    RetriesExhausted {
+        url: String,
+        attempts: u32,
+        #[source]
+        source: Box<RepoError>,
+    },
+
     // ============ Authentication Errors ============
     #[error("Authentication required for {url}")]
     AuthRequired { url: String },
@@ -68,6 +76,9 @@ pub enum RepoError {
     #[error("No versions available for pack: {name}")]
     NoVersionsAvailable { name: String },
 
+    #[error("Invalid pack archive {path}: {message}")]
+    InvalidArchive { path: String, message: String },
+
     // ============ Dependency Errors ============
     #[error("Dependency resolution failed: {message}")]
     ResolutionFailed { message: String },
@@ -114,6 +125,18 @@ pub enum RepoError {
     #[error("OCI push failed: {message}")]
     OciPushFailed { message: String },
 
+    #[error(
+        "Unsupported OCI config media type: {media_type} (expected a Sherpack pack or Helm chart)"
+    )]
+    UnsupportedMediaType { media_type: String },
+
+    #[error("Signature verification failed: {message}")]
+    SignatureVerification { message: String },
+
+    // ============ Git Errors ============
+    #[error("Git error: {message}")]
+    GitError { message: String },
+
     // ============ Cache Errors ============
     #[error("Cache error: {message}")]
     CacheError { message: String },
@@ -121,6 +144,9 @@ pub enum RepoError {
     #[error("Cache corrupted, rebuilding: {message}")]
     CacheCorrupted { message: String },
 
+    #[error("Offline mode: {what} is not in the local cache")]
+    Offline { what: String },
+
     // ============ IO Errors ============
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
@@ -139,6 +165,20 @@ pub enum RepoError {
 /// Result type for repository operations
 pub type Result<T> = std::result::Result<T, RepoError>;
 
+impl RepoError {
+    /// Whether retrying the same request may succeed
+    ///
+    /// Connection failures, timeouts and server errors (5xx) are transient;
+    /// client errors and authentication failures are not.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            RepoError::NetworkError { .. } | RepoError::Timeout { .. } => true,
+            RepoError::HttpError { status, .. } => *status >= 500,
+            _ => false,
+        }
+    }
+}
+
 impl From<reqwest::Error> for RepoError {
     fn from(e: reqwest::Error) -> Self {
         if e.is_timeout() {
@@ -0,0 +1,463 @@
+//! Git-hosted pack dependencies
+//!
+//! Packs that are not published to a repository can be taken straight from
+//! git. Checkouts are made with the `git` CLI into the cache, one shallow
+//! checkout per commit:
+//!
+//! ```text
+//! ~/.cache/sherpack/git/<repo-hash>/<commit>/
+//! ```
+//!
+//! A ref is resolved to its commit with `git ls-remote` first, so a commit
+//! that is already checked out is never fetched again.
+
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+
+use sherpack_core::{LoadedPack, Manifest};
+
+use crate::error::{RepoError, Result};
+
+pub use sherpack_core::GitSource;
+
+/// Checkouts of git dependencies
+#[derive(Debug, Clone)]
+pub struct GitCache {
+    root: PathBuf,
+    offline: bool,
+}
+
+impl GitCache {
+    /// Open the checkout cache at the default location
+    pub fn open() -> Result<Self> {
+        Ok(Self::at(Self::default_path()?))
+    }
+
+    /// Use a specific directory
+    pub fn at(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            offline: false,
+        }
+    }
+
+    /// Only use existing checkouts, never reach a remote
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// Get default checkout directory
+    pub fn default_path() -> Result<PathBuf> {
+        let cache_dir = dirs::cache_dir().ok_or_else(|| RepoError::CacheError {
+            message: "Could not determine cache directory".to_string(),
+        })?;
+        Ok(cache_dir.join("sherpack").join("git"))
+    }
+
+    /// Check out a source at its ref
+    pub fn checkout(&self, source: &GitSource) -> Result<GitCheckout> {
+        validate_source(source)?;
+        let commit = self.resolve_ref(source)?;
+        self.checkout_commit(source, &commit)
+    }
+
+    /// Check out a source at a known commit
+    pub fn checkout_commit(&self, source: &GitSource, commit: &str) -> Result<GitCheckout> {
+        validate_source(source)?;
+        let subpath = source.subpath.as_deref().unwrap_or("");
+
+        let root = self.commit_dir(&source.repo, commit);
+        if !root.is_dir() {
+            if self.offline {
+                return Err(RepoError::Offline {
+                    what: format!("commit {} of {}", short_commit(commit), source.repo),
+                });
+            }
+            fetch_commit(source, commit, &root)?;
+        }
+
+        let checkout = GitCheckout {
+            commit: commit.to_string(),
+            pack_dir: root.join(subpath),
+            root,
+        };
+        if !checkout.pack_dir.join("Pack.yaml").is_file() {
+            return Err(RepoError::GitError {
+                message: format!(
+                    "no Pack.yaml at '{}' in {} ({})",
+                    subpath,
+                    source.repo,
+                    short_commit(commit)
+                ),
+            });
+        }
+        Ok(checkout)
+    }
+
+    /// Resolve a ref to the commit it points at
+    fn resolve_ref(&self, source: &GitSource) -> Result<String> {
+        if is_commit_id(&source.git_ref) {
+            return Ok(source.git_ref.to_lowercase());
+        }
+        if self.offline {
+            return Err(RepoError::Offline {
+                what: format!("ref '{}' of {}", source.git_ref, source.repo),
+            });
+        }
+
+        // Newer git only lists the peeled line of a tag when asked for it
+        let peeled = format!("{}^{{}}", source.git_ref);
+        let refs = git(
+            None,
+            &["ls-remote", "--", &source.repo, &source.git_ref, &peeled],
+        )?;
+        parse_ls_remote(&refs, &source.git_ref).ok_or_else(|| RepoError::GitError {
+            message: format!("ref '{}' not found in {}", source.git_ref, source.repo),
+        })
+    }
+
+    fn commit_dir(&self, repo: &str, commit: &str) -> PathBuf {
+        use sha2::{Digest, Sha256};
+        let repo_key = hex::encode(Sha256::digest(repo.trim_end_matches('/').as_bytes()));
+
+        self.root.join(&repo_key[..16]).join(commit)
+    }
+}
+
+/// A pack checked out from git
+#[derive(Debug, Clone)]
+pub struct GitCheckout {
+    /// Commit the checkout was made at
+    pub commit: String,
+    /// Pack directory: the checkout joined with the subpath
+    pub pack_dir: PathBuf,
+    /// Checkout root
+    root: PathBuf,
+}
+
+impl GitCheckout {
+    /// Load the checked-out pack
+    pub fn load_pack(&self) -> Result<LoadedPack> {
+        LoadedPack::load(&self.pack_dir).map_err(|e| RepoError::GitError {
+            message: format!("invalid pack at {}: {}", self.pack_dir.display(), e),
+        })
+    }
+
+    /// Digest of the pack's files
+    ///
+    /// The MANIFEST digest `sherpack package` would record, so it only
+    /// changes when the packaged content does.
+    pub fn digest(&self) -> Result<String> {
+        let manifest = Manifest::generate(&self.load_pack()?).map_err(|e| RepoError::GitError {
+            message: format!("cannot hash {}: {}", self.pack_dir.display(), e),
+        })?;
+        Ok(format!("sha256:{}", manifest.digest))
+    }
+
+    /// Commit the checkout is at now
+    pub fn head(&self) -> Result<String> {
+        git(Some(&self.root), &["rev-parse", "HEAD"])
+    }
+
+    /// Copy the pack (without git metadata) to a directory, replacing it
+    pub fn copy_pack_to(&self, dest: &Path) -> Result<()> {
+        if dest.exists() {
+            std::fs::remove_dir_all(dest)?;
+        }
+        copy_tree(&self.pack_dir, dest)
+    }
+}
+
+/// Reject sources that could be taken for git options or escape the checkout
+///
+/// Sources come from Pack.yaml files, including those of dependencies, so a
+/// `repo` like `--upload-pack=<cmd>` must never reach the git command line.
+fn validate_source(source: &GitSource) -> Result<()> {
+    for (field, value) in [("repo", &source.repo), ("ref", &source.git_ref)] {
+        if value.is_empty() || value.starts_with('-') {
+            return Err(RepoError::GitError {
+                message: format!("invalid git {} '{}'", field, value),
+            });
+        }
+    }
+
+    let subpath = source.subpath.as_deref().unwrap_or("");
+    if Path::new(subpath)
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(RepoError::GitError {
+            message: format!("subpath '{}' must stay inside the repository", subpath),
+        });
+    }
+    Ok(())
+}
+
+/// Shallow-fetch one commit into `dest`
+///
+/// Servers usually allow fetching a commit directly; when one doesn't, the
+/// ref is fetched instead and must still point at the commit. The checkout
+/// is staged next to `dest` and renamed into place, so an interrupted fetch
+/// never looks cached.
+fn fetch_commit(source: &GitSource, commit: &str, dest: &Path) -> Result<()> {
+    let parent = dest.parent().unwrap_or(dest);
+    std::fs::create_dir_all(parent)?;
+    let staging = parent.join(format!("{}.{}.partial", commit, std::process::id()));
+    if staging.exists() {
+        std::fs::remove_dir_all(&staging)?;
+    }
+    std::fs::create_dir_all(&staging)?;
+
+    let result = (|| {
+        git(Some(&staging), &["init", "--quiet"])?;
+        let fetch = |what: &str| {
+            git(
+                Some(&staging),
+                &["fetch", "--quiet", "--depth", "1", "--", &source.repo, what],
+            )
+        };
+        if fetch(commit).is_err() {
+            fetch(&source.git_ref)?;
+        }
+        git(
+            Some(&staging),
+            &["checkout", "--quiet", "--detach", "FETCH_HEAD"],
+        )?;
+
+        let head = git(Some(&staging), &["rev-parse", "HEAD"])?;
+        if head != commit {
+            return Err(RepoError::GitError {
+                message: format!(
+                    "{} at '{}' is now {}, not {}; run 'sherpack dependency update'",
+                    source.repo,
+                    source.git_ref,
+                    short_commit(&head),
+                    short_commit(commit)
+                ),
+            });
+        }
+        Ok(())
+    })();
+
+    match result {
+        Ok(()) => Ok(std::fs::rename(&staging, dest)?),
+        Err(e) => {
+            let _ = std::fs::remove_dir_all(&staging);
+            Err(e)
+        }
+    }
+}
+
+/// Run git, returning its trimmed stdout
+fn git(dir: Option<&Path>, args: &[&str]) -> Result<String> {
+    let mut command = Command::new("git");
+    if let Some(dir) = dir {
+        command.current_dir(dir);
+    }
+    let output = command
+        .args(args)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()
+        .map_err(|e| RepoError::GitError {
+            message: format!("failed to run git: {}", e),
+        })?;
+
+    if !output.status.success() {
+        return Err(RepoError::GitError {
+            message: format!(
+                "git {} failed: {}",
+                args[0],
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Pick the commit for a ref out of `git ls-remote` output
+///
+/// Tags win over branches, and an annotated tag resolves to the commit it
+/// points at (its peeled `^{}` line), not the tag object.
+fn parse_ls_remote(output: &str, git_ref: &str) -> Option<String> {
+    let refs: Vec<(&str, &str)> = output
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .collect();
+
+    [
+        format!("refs/tags/{}^{{}}", git_ref),
+        format!("refs/tags/{}", git_ref),
+        format!("refs/heads/{}", git_ref),
+        git_ref.to_string(),
+    ]
+    .iter()
+    .find_map(|wanted| {
+        refs.iter()
+            .find(|(_, name)| name == wanted)
+            .map(|(commit, _)| commit.to_string())
+    })
+}
+
+/// Whether a ref is a full commit id (SHA-1 or SHA-256)
+fn is_commit_id(git_ref: &str) -> bool {
+    matches!(git_ref.len(), 40 | 64) && git_ref.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn short_commit(commit: &str) -> &str {
+    &commit[..commit.len().min(12)]
+}
+
+/// Copy a directory recursively, skipping `.git`
+fn copy_tree(src: &Path, dest: &Path) -> Result<()> {
+    std::fs::create_dir_all(dest)?;
+
+    for entry in std::fs::read_dir(src)? {
+        let entry = entry?;
+        if entry.file_name() == ".git" {
+            continue;
+        }
+        let src_path = entry.path();
+        let dest_path = dest.join(entry.file_name());
+
+        if src_path.is_dir() {
+            copy_tree(&src_path, &dest_path)?;
+        } else {
+            std::fs::copy(&src_path, &dest_path)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// A local repository with one pack under `packs/app`, tagged `v1.0.0`
+    pub(crate) fn pack_repository(dir: &Path) -> String {
+        let pack = dir.join("packs/app");
+        std::fs::create_dir_all(pack.join("templates")).unwrap();
+        std::fs::write(
+            pack.join("Pack.yaml"),
+            "apiVersion: sherpack/v1\nkind: application\nmetadata:\n  name: app\n  version: 1.0.0\n",
+        )
+        .unwrap();
+        std::fs::write(pack.join("values.yaml"), "replicas: 1\n").unwrap();
+        std::fs::write(pack.join("templates/cm.yaml"), "kind: ConfigMap\n").unwrap();
+
+        let run = |args: &[&str]| {
+            let identity = ["-c", "user.name=test", "-c", "user.email=test@example.com"];
+            git(Some(dir), &[&identity[..], args].concat()).unwrap();
+        };
+        run(&["init", "--quiet"]);
+        run(&["add", "."]);
+        run(&["commit", "--quiet", "-m", "app 1.0.0"]);
+        run(&["tag", "-a", "v1.0.0", "-m", "v1.0.0"]);
+
+        format!("file://{}", dir.display())
+    }
+
+    fn source(repo: &str, git_ref: &str) -> GitSource {
+        GitSource {
+            repo: repo.to_string(),
+            git_ref: git_ref.to_string(),
+            subpath: Some("packs/app".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_checkout_tag_and_reuse_commit() {
+        let upstream = tempfile::TempDir::new().unwrap();
+        let cache_dir = tempfile::TempDir::new().unwrap();
+        let repo = pack_repository(upstream.path());
+        let head = git(Some(upstream.path()), &["rev-parse", "HEAD"]).unwrap();
+
+        let cache = GitCache::at(cache_dir.path());
+        let checkout = cache.checkout(&source(&repo, "v1.0.0")).unwrap();
+        assert_eq!(checkout.commit, head);
+        assert_eq!(checkout.head().unwrap(), head);
+        assert_eq!(checkout.load_pack().unwrap().pack.metadata.name, "app");
+
+        // Cached by commit: the offline cache serves it without the remote
+        std::fs::remove_dir_all(upstream.path()).unwrap();
+        let cached = cache
+            .offline(true)
+            .checkout_commit(&source(&repo, "v1.0.0"), &head)
+            .unwrap();
+        assert_eq!(cached.digest().unwrap(), checkout.digest().unwrap());
+
+        let dest = cache_dir.path().join("vendored");
+        cached.copy_pack_to(&dest).unwrap();
+        assert!(dest.join("templates/cm.yaml").is_file());
+        assert!(!dest.join(".git").exists());
+    }
+
+    #[test]
+    fn test_checkout_errors() {
+        let upstream = tempfile::TempDir::new().unwrap();
+        let cache_dir = tempfile::TempDir::new().unwrap();
+        let repo = pack_repository(upstream.path());
+        let cache = GitCache::at(cache_dir.path());
+
+        let err = cache.checkout(&source(&repo, "v9.9.9")).unwrap_err();
+        assert!(err.to_string().contains("ref 'v9.9.9' not found"));
+
+        let mut escaping = source(&repo, "v1.0.0");
+        escaping.subpath = Some("../outside".to_string());
+        let err = cache.checkout(&escaping).unwrap_err();
+        assert!(err.to_string().contains("must stay inside"));
+        escaping.subpath = Some("/etc".to_string());
+        let err = cache.checkout(&escaping).unwrap_err();
+        assert!(err.to_string().contains("must stay inside"));
+
+        let err = cache
+            .offline(true)
+            .checkout(&source(&repo, "main"))
+            .unwrap_err();
+        assert!(matches!(err, RepoError::Offline { .. }));
+    }
+
+    #[test]
+    fn test_option_like_sources_are_rejected() {
+        let cache_dir = tempfile::TempDir::new().unwrap();
+        let marker = cache_dir.path().join("pwned");
+        let cache = GitCache::at(cache_dir.path());
+
+        let upload_pack = format!("--upload-pack=touch {}", marker.display());
+        let err = cache.checkout(&source(&upload_pack, "main")).unwrap_err();
+        assert!(err.to_string().contains("invalid git repo"), "{err}");
+
+        let err = cache
+            .checkout(&source("https://example.com/app.git", "--output=/tmp/x"))
+            .unwrap_err();
+        assert!(err.to_string().contains("invalid git ref"), "{err}");
+
+        // Known commits skip ls-remote, so the check must guard fetch too
+        let err = cache
+            .checkout_commit(&source(&upload_pack, "main"), &"a".repeat(40))
+            .unwrap_err();
+        assert!(err.to_string().contains("invalid git repo"), "{err}");
+        assert!(!marker.exists());
+    }
+
+    #[test]
+    fn test_parse_ls_remote() {
+        let output = "1111111111111111111111111111111111111111\trefs/heads/v1\n\
+                      2222222222222222222222222222222222222222\trefs/tags/v1\n\
+                      3333333333333333333333333333333333333333\trefs/tags/v1^{}\n\
+                      4444444444444444444444444444444444444444\trefs/heads/main";
+
+        assert_eq!(
+            parse_ls_remote(output, "v1").as_deref(),
+            Some("3333333333333333333333333333333333333333")
+        );
+        assert_eq!(
+            parse_ls_remote(output, "main").as_deref(),
+            Some("4444444444444444444444444444444444444444")
+        );
+        assert!(parse_ls_remote(output, "dev").is_none());
+        assert!(is_commit_id("3333333333333333333333333333333333333333"));
+        assert!(!is_commit_id("v1.0.0"));
+    }
+}
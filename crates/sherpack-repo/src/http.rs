@@ -4,9 +4,9 @@
 
 use std::path::Path;
 
-use crate::config::Repository;
+use crate::config::{HttpSettings, Repository};
 use crate::credentials::{
-    CachedResponse, ResolvedCredentials, ScopedCredentials, SecureHttpClient,
+    CacheValidators, CachedResponse, ResolvedCredentials, ScopedCredentials, SecureHttpClient,
 };
 use crate::error::{RepoError, Result};
 use crate::index::{PackEntry, RepositoryIndex};
@@ -19,22 +19,43 @@ pub struct HttpRepository {
     client: SecureHttpClient,
     /// Cached index
     cached_index: Option<RepositoryIndex>,
+    /// Validators of the cached index
+    validators: CacheValidators,
+}
+
+/// Outcome of a conditional index refresh
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IndexRefresh {
+    /// The server answered `304 Not Modified`: the caller's copy is current
+    NotModified,
+    /// A new index was downloaded, with the validators to store for next time
+    Updated(CacheValidators),
 }
 
 impl HttpRepository {
     /// Create a new HTTP repository client
     pub fn new(repo: Repository, credentials: Option<ResolvedCredentials>) -> Result<Self> {
+        Self::with_settings(repo, credentials, HttpSettings::default())
+    }
+
+    /// Create a client with custom retry and timeout settings
+    pub fn with_settings(
+        repo: Repository,
+        credentials: Option<ResolvedCredentials>,
+        settings: HttpSettings,
+    ) -> Result<Self> {
         let mut scoped = ScopedCredentials::default();
         if let Some(creds) = credentials {
             scoped.add(&repo.url, creds);
         }
 
-        let client = SecureHttpClient::new(scoped)?;
+        let client = SecureHttpClient::with_settings(scoped, settings)?;
 
         Ok(Self {
             repo,
             client,
             cached_index: None,
+            validators: CacheValidators::default(),
         })
     }
 
@@ -54,40 +75,50 @@ impl HttpRepository {
     }
 
     /// Fetch or refresh the repository index
+    ///
+    /// Once an index is loaded, later calls only re-download it if the
+    /// server reports a change.
     pub async fn fetch_index(&mut self) -> Result<&RepositoryIndex> {
-        let index_url = self.repo.index_url();
+        let validators = if self.cached_index.is_some() {
+            self.validators.clone()
+        } else {
+            CacheValidators::default()
+        };
+
+        if self.refresh_index(&validators).await? == IndexRefresh::NotModified
+            && self.cached_index.is_none()
+        {
+            return Err(RepoError::CacheError {
+                message: "Received 304 but no cached index".to_string(),
+            });
+        }
 
-        // Use ETag for conditional request if we have a cached index
+        self.cached_index
+            .as_ref()
+            .ok_or_else(|| RepoError::IndexNotFound {
+                url: self.repo.index_url(),
+            })
+    }
+
+    /// Conditionally refresh the index against validators stored by the caller
+    ///
+    /// On [`IndexRefresh::NotModified`] nothing is downloaded or parsed and
+    /// the caller's copy of the index (e.g. in the
+    /// [`IndexCache`](crate::IndexCache)) is still valid.
+    pub async fn refresh_index(&mut self, validators: &CacheValidators) -> Result<IndexRefresh> {
         let response = self
             .client
-            .get_cached(&index_url, self.repo.etag.as_deref())
+            .get_cached(&self.repo.index_url(), validators)
             .await?;
 
         match response {
-            CachedResponse::NotModified => {
-                // Index hasn't changed, use cached version
-                if self.cached_index.is_none() {
-                    return Err(RepoError::CacheError {
-                        message: "Received 304 but no cached index".to_string(),
-                    });
-                }
-            }
-            CachedResponse::Fresh { data, etag } => {
-                // Parse new index
-                let index = RepositoryIndex::from_bytes(&data)?;
-                self.cached_index = Some(index);
-
-                // Store new ETag (would need to save to config)
-                if etag.is_some() {
-                    // Note: caller should save updated repo config
-                    // self.repo.etag = etag;
-                }
+            CachedResponse::NotModified => Ok(IndexRefresh::NotModified),
+            CachedResponse::Fresh { data, validators } => {
+                self.cached_index = Some(RepositoryIndex::from_bytes(&data)?);
+                self.validators = validators.clone();
+                Ok(IndexRefresh::Updated(validators))
             }
         }
-
-        self.cached_index
-            .as_ref()
-            .ok_or(RepoError::IndexNotFound { url: index_url })
     }
 
     /// Get the cached index without fetching
@@ -258,4 +289,120 @@ mod tests {
         assert!(digest_matches(d1, d4));
         assert!(!digest_matches(d1, "sha256:xyz789"));
     }
+
+    mod remote {
+        use super::super::*;
+        use wiremock::matchers::{header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        fn repository(server: &MockServer) -> HttpRepository {
+            let repo = Repository::new("flaky", server.uri()).unwrap();
+            let settings = HttpSettings {
+                initial_backoff_ms: 1,
+                ..Default::default()
+            };
+            HttpRepository::with_settings(repo, None, settings).unwrap()
+        }
+
+        #[tokio::test]
+        async fn test_fetch_index_retries_server_errors() {
+            let server = MockServer::start().await;
+            Mock::given(method("GET"))
+                .and(path("/index.yaml"))
+                .respond_with(ResponseTemplate::new(503))
+                .up_to_n_times(2)
+                .expect(2)
+                .mount(&server)
+                .await;
+            Mock::given(method("GET"))
+                .and(path("/index.yaml"))
+                .respond_with(
+                    ResponseTemplate::new(200).set_body_string("apiVersion: v1\nentries: {}\n"),
+                )
+                .expect(1)
+                .mount(&server)
+                .await;
+
+            let mut repo = repository(&server);
+            let index = repo.fetch_index().await.unwrap();
+            assert!(index.entries.is_empty());
+        }
+
+        #[tokio::test]
+        async fn test_fetch_index_reports_attempts() {
+            let server = MockServer::start().await;
+            Mock::given(method("GET"))
+                .respond_with(ResponseTemplate::new(502))
+                .expect(3)
+                .mount(&server)
+                .await;
+
+            let err = repository(&server).fetch_index().await.unwrap_err();
+            match &err {
+                RepoError::RetriesExhausted {
+                    attempts, source, ..
+                } => {
+                    assert_eq!(*attempts, 3);
+                    assert!(matches!(**source, RepoError::HttpError { status: 502, .. }));
+                }
+                other => panic!("unexpected error: {:?}", other),
+            }
+            assert!(err.to_string().contains("after 3 attempts"));
+        }
+
+        #[tokio::test]
+        async fn test_client_errors_are_not_retried() {
+            let server = MockServer::start().await;
+            Mock::given(method("GET"))
+                .respond_with(ResponseTemplate::new(404))
+                .expect(1)
+                .mount(&server)
+                .await;
+
+            let err = repository(&server).fetch_index().await.unwrap_err();
+            assert!(matches!(err, RepoError::HttpError { status: 404, .. }));
+        }
+
+        #[tokio::test]
+        async fn test_refresh_index_not_modified() {
+            let server = MockServer::start().await;
+            Mock::given(method("GET"))
+                .and(header("If-None-Match", "\"v1\""))
+                .respond_with(ResponseTemplate::new(304))
+                .mount(&server)
+                .await;
+            Mock::given(method("GET"))
+                .respond_with(
+                    ResponseTemplate::new(200)
+                        .insert_header("ETag", "\"v1\"")
+                        .insert_header("Last-Modified", "Wed, 21 Oct 2015 07:28:00 GMT")
+                        .set_body_string("apiVersion: v1\nentries: {}\n"),
+                )
+                .expect(1)
+                .mount(&server)
+                .await;
+
+            let mut repo = repository(&server);
+            let validators = match repo
+                .refresh_index(&CacheValidators::default())
+                .await
+                .unwrap()
+            {
+                IndexRefresh::Updated(validators) => validators,
+                other => panic!("unexpected refresh: {:?}", other),
+            };
+            assert_eq!(validators.etag.as_deref(), Some("\"v1\""));
+            assert!(validators.last_modified.is_some());
+
+            let mut fresh = repository(&server);
+            assert_eq!(
+                fresh.refresh_index(&validators).await.unwrap(),
+                IndexRefresh::NotModified
+            );
+            assert!(fresh.index().is_none());
+
+            // A loaded index is revalidated instead of downloaded again
+            repo.fetch_index().await.unwrap();
+        }
+    }
 }
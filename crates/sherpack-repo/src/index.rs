@@ -5,9 +5,12 @@
 use chrono::{DateTime, Utc};
 use semver::Version;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use sherpack_core::{GitSource, Pack, PackKind};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 
 use crate::error::{RepoError, Result};
+use crate::lock::compute_sha256;
 
 /// Repository index (Helm-compatible)
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -48,6 +51,29 @@ impl RepositoryIndex {
         })
     }
 
+    /// Generate an index from a directory of packaged archives
+    ///
+    /// Equivalent to `helm repo index`: every `*.tgz` or `*.tar.gz` archive in
+    /// `dir` becomes an entry. With a `base_url`, entry URLs are
+    /// `<base_url>/<filename>`; without one they are the bare filename,
+    /// relative to the index.
+    pub fn generate_from_dir(dir: &Path, base_url: Option<&str>) -> Result<Self> {
+        let mut archives = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_file() && is_pack_archive(&path) {
+                archives.push(path);
+            }
+        }
+        archives.sort();
+
+        let mut index = Self::default();
+        for archive in &archives {
+            index.add_entry(PackEntry::from_archive(archive, base_url)?);
+        }
+        Ok(index)
+    }
+
     /// Parse index from bytes
     pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
         let yaml = std::str::from_utf8(bytes).map_err(|e| RepoError::IndexParseError {
@@ -212,6 +238,36 @@ impl RepositoryIndex {
         }
         self.generated = Utc::now();
     }
+
+    /// Merge in only the versions this index doesn't have yet
+    ///
+    /// Existing entries win, so regenerating an index keeps the digests and
+    /// timestamps of historical versions. Returns the number of entries added.
+    pub fn merge_new_versions(&mut self, other: RepositoryIndex) -> usize {
+        let mut known: HashSet<(String, String)> = self
+            .entries
+            .values()
+            .flatten()
+            .map(|e| (e.name.clone(), e.version.clone()))
+            .collect();
+
+        let mut added = 0;
+        for entry in other.entries.into_values().flatten() {
+            if known.insert((entry.name.clone(), entry.version.clone())) {
+                self.add_entry(entry);
+                added += 1;
+            }
+        }
+        self.generated = Utc::now();
+        added
+    }
+}
+
+/// Whether a path looks like a packaged pack archive
+fn is_pack_archive(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| n.ends_with(".tgz") || n.ends_with(".tar.gz"))
 }
 
 /// Pack entry in the index
@@ -286,6 +342,99 @@ pub struct PackEntry {
 }
 
 impl PackEntry {
+    /// Build an entry from a packaged archive
+    ///
+    /// Metadata comes from the archive's `Pack.yaml`, the creation time from
+    /// its MANIFEST (falling back to the file's modification time for
+    /// archives without one). The digest is the SHA256 of the archive in the
+    /// `sha256:<hex>` form lock files record, so locked dependencies served
+    /// from the index verify.
+    pub fn from_archive(path: &Path, base_url: Option<&str>) -> Result<Self> {
+        let invalid = |message: String| RepoError::InvalidArchive {
+            path: path.display().to_string(),
+            message,
+        };
+
+        let pack_yaml = sherpack_core::read_file_from_archive(path, "Pack.yaml")
+            .map_err(|e| invalid(e.to_string()))?;
+        let pack: Pack = serde_yaml::from_slice(&pack_yaml)
+            .map_err(|e| invalid(format!("invalid Pack.yaml: {}", e)))?;
+
+        let created = match sherpack_core::read_manifest_from_archive(path) {
+            Ok(manifest) => manifest.created,
+            Err(_) => std::fs::metadata(path)?.modified()?.into(),
+        };
+
+        let filename = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default()
+            .to_string();
+        let url = match base_url {
+            Some(base) => format!("{}/{}", base.trim_end_matches('/'), filename),
+            None => filename,
+        };
+
+        Ok(Self {
+            digest: Some(compute_sha256(&std::fs::read(path)?)),
+            created: Some(created),
+            ..Self::from_pack(pack, url)
+        })
+    }
+
+    /// Build an entry from a pack definition
+    ///
+    /// Digest and creation time are left for the caller, who knows what the
+    /// entry points at.
+    pub fn from_pack(pack: Pack, url: String) -> Self {
+        let metadata = pack.metadata;
+        Self {
+            name: metadata.name,
+            version: metadata.version.to_string(),
+            app_version: metadata.app_version,
+            description: metadata.description,
+            home: metadata.home,
+            icon: metadata.icon,
+            sources: metadata.sources,
+            keywords: metadata.keywords,
+            maintainers: metadata
+                .maintainers
+                .into_iter()
+                .map(|m| Maintainer {
+                    name: m.name,
+                    email: m.email,
+                    url: m.url,
+                })
+                .collect(),
+            urls: vec![url],
+            digest: None,
+            created: None,
+            deprecated: false,
+            dependencies: pack
+                .dependencies
+                .into_iter()
+                .map(|d| IndexDependency {
+                    name: d.name,
+                    version: d.version,
+                    repository: Some(d.repository).filter(|r| !r.is_empty()),
+                    condition: d.condition,
+                    tags: d.tags,
+                    alias: d.alias,
+                    git: d.git,
+                })
+                .collect(),
+            annotations: metadata.annotations,
+            api_version: Some(pack.api_version),
+            r#type: Some(
+                match pack.kind {
+                    PackKind::Application => "application",
+                    PackKind::Library => "library",
+                }
+                .to_string(),
+            ),
+        }
+    }
+
     /// Get the primary download URL
     pub fn download_url(&self) -> Option<&str> {
         self.urls.first().map(|s| s.as_str())
@@ -320,6 +469,8 @@ pub struct IndexDependency {
     pub tags: Vec<String>,
     #[serde(default)]
     pub alias: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git: Option<GitSource>,
 }
 
 #[cfg(test)]
@@ -485,4 +636,101 @@ entries:
         index1.merge(index2);
         assert!(index1.get("postgresql").is_some());
     }
+
+    /// Package a fixture pack into `dir`
+    fn package_fixture(name: &str, dir: &Path, filename: &str) -> std::path::PathBuf {
+        let fixture = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../../fixtures")
+            .join(name);
+        let pack = sherpack_core::LoadedPack::load(fixture).unwrap();
+        sherpack_core::create_archive(&pack, &dir.join(filename)).unwrap()
+    }
+
+    #[test]
+    fn test_generate_from_dir() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let archive = package_fixture("simple-pack", dir.path(), "simple-pack-0.1.0.tgz");
+        package_fixture("demo-pack", dir.path(), "demo-pack.tar.gz");
+        std::fs::write(dir.path().join("README.md"), "not a pack").unwrap();
+
+        let index =
+            RepositoryIndex::generate_from_dir(dir.path(), Some("https://example.com/charts/"))
+                .unwrap();
+        assert_eq!(index.entries.len(), 2);
+
+        let entry = index.get_version("simple-pack", "0.1.0").unwrap();
+        assert_eq!(
+            entry.download_url(),
+            Some("https://example.com/charts/simple-pack-0.1.0.tgz")
+        );
+        assert_eq!(entry.description.as_deref(), Some("A simple test pack"));
+        assert_eq!(entry.r#type.as_deref(), Some("application"));
+
+        let manifest = sherpack_core::read_manifest_from_archive(&archive).unwrap();
+        assert_eq!(entry.created, Some(manifest.created));
+
+        // The digest is what a lock file records for the same archive
+        let mut lock = crate::lock::LockFile::new("");
+        lock.add(crate::lock::LockedDependency {
+            name: "simple-pack".to_string(),
+            version: Version::new(0, 1, 0),
+            repository: "https://example.com/charts".to_string(),
+            digest: entry.digest.clone().unwrap(),
+            constraint: "^0.1".to_string(),
+            alias: None,
+            dependencies: vec![],
+            git: None,
+            sources: vec![],
+        });
+        let data = std::fs::read(&archive).unwrap();
+        assert_eq!(
+            lock.verify("simple-pack", &data).unwrap(),
+            crate::lock::VerifyResult::Match {
+                source: "https://example.com/charts".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_generate_from_dir_relative_urls() {
+        let dir = tempfile::TempDir::new().unwrap();
+        package_fixture("simple-pack", dir.path(), "simple-pack-0.1.0.tgz");
+
+        let index = RepositoryIndex::generate_from_dir(dir.path(), None).unwrap();
+        let entry = index.get_latest("simple-pack").unwrap();
+        assert_eq!(entry.download_url(), Some("simple-pack-0.1.0.tgz"));
+    }
+
+    #[test]
+    fn test_generate_from_dir_invalid_archive() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("broken.tgz"), b"not gzip").unwrap();
+
+        let err = RepositoryIndex::generate_from_dir(dir.path(), None).unwrap_err();
+        assert!(matches!(err, RepoError::InvalidArchive { .. }));
+        assert!(err.to_string().contains("broken.tgz"));
+    }
+
+    #[test]
+    fn test_merge_new_versions_keeps_existing() {
+        let mut index = sample_index();
+        let original_digest = index.get_version("nginx", "15.0.0").unwrap().digest.clone();
+
+        let mut generated = RepositoryIndex::default();
+        for version in ["15.0.0", "16.0.0"] {
+            generated.add_entry(PackEntry {
+                name: "nginx".to_string(),
+                version: version.to_string(),
+                digest: Some("sha256:new".to_string()),
+                ..Default::default()
+            });
+        }
+
+        assert_eq!(index.merge_new_versions(generated), 1);
+        assert_eq!(
+            index.get_version("nginx", "15.0.0").unwrap().digest,
+            original_digest
+        );
+        assert!(index.get_version("nginx", "16.0.0").is_some());
+    }
 }
@@ -17,14 +17,19 @@
 //! ## Example
 //!
 //! ```rust,no_run
-//! use sherpack_repo::{RepositoryConfig, Repository, RepositoryBackend, create_backend};
+//! use sherpack_repo::{BackendOptions, RepositoryConfig, Repository, RepositoryBackend, create_backend};
 //!
 //! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
 //! // Add a repository
 //! let repo = Repository::new("bitnami", "https://charts.bitnami.com/bitnami")?;
+//! let config = RepositoryConfig::load()?;
+//! let options = BackendOptions {
+//!     http: config.http,
+//!     offline: false,
+//! };
 //!
 //! // Create backend (works for HTTP, OCI, or file repos)
-//! let mut backend = create_backend(repo, None).await?;
+//! let mut backend = create_backend(repo, None, options).await?;
 //!
 //! // Search for packs
 //! let results = backend.search("nginx").await?;
@@ -47,24 +52,28 @@ pub mod config;
 pub mod credentials;
 pub mod dependency;
 pub mod error;
+pub mod git;
 pub mod http;
 pub mod index;
 pub mod lock;
 pub mod oci;
 
 // Re-exports for convenience
-pub use backend::{RepositoryBackend, create_backend, create_backend_by_name};
-pub use cache::{CacheStats, CachedPack, IndexCache};
-pub use config::{Repository, RepositoryConfig, RepositoryType};
+pub use backend::{BackendOptions, RepositoryBackend, create_backend, create_backend_by_name};
+pub use cache::{ArchiveCache, CacheStats, CachedPack, IndexCache};
+pub use config::{CacheSettings, HttpSettings, Repository, RepositoryConfig, RepositoryType};
 pub use credentials::{
-    CredentialStore, Credentials, ResolvedCredentials, ScopedCredentials, SecureHttpClient,
+    CacheValidators, CredentialFallbacks, CredentialSource, CredentialStore, Credentials,
+    DockerConfig, Login, Netrc, ResolvedCredentials, ScopedCredentials, SecureHttpClient,
+    login_scope, registry_host,
 };
 pub use dependency::{
     DependencyGraph, DependencyResolver, DependencySpec, FilterResult, ResolvedDependency,
-    SkipReason, SkippedDependency, filter_dependencies,
+    SkipReason, SkippedDependency, TreeOptions, filter_dependencies,
 };
 pub use error::{RepoError, Result};
-pub use http::HttpRepository;
+pub use git::{GitCache, GitCheckout, GitSource};
+pub use http::{HttpRepository, IndexRefresh};
 pub use index::{IndexDependency, Maintainer, PackEntry, RepositoryIndex};
-pub use lock::{LockFile, LockPolicy, LockedDependency, VerifyResult};
-pub use oci::{OciReference, OciRegistry};
+pub use lock::{LockFile, LockPolicy, LockedDependency, LockedGitSource, VerifyResult};
+pub use oci::{OciReference, OciRegistry, PackMediaType, SignatureKey, registry_login};
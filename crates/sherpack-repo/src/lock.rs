@@ -13,6 +13,7 @@ use std::collections::HashMap;
 use std::path::Path;
 
 use crate::error::{RepoError, Result};
+use crate::git::GitCheckout;
 
 /// Lock file format (Pack.lock.yaml)
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -86,6 +87,46 @@ pub struct LockedDependency {
     /// Transitive dependencies (by name)
     #[serde(default)]
     pub dependencies: Vec<String>,
+
+    /// Git source and the commit it resolved to, for git dependencies
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git: Option<LockedGitSource>,
+
+    /// Alternative repository URLs serving the same archive
+    ///
+    /// Tried in order when `repository` fails. Their archives must match
+    /// `digest` exactly, whatever the lock policy.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<String>,
+}
+
+/// A git dependency pinned to a commit
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedGitSource {
+    /// Repository URL
+    pub repo: String,
+
+    /// Ref from Pack.yaml
+    #[serde(rename = "ref")]
+    pub git_ref: String,
+
+    /// Commit the ref pointed at when the lock was written
+    pub commit: String,
+
+    /// Directory of the pack inside the repository
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subpath: Option<String>,
+}
+
+impl LockedGitSource {
+    /// The source to check out
+    pub fn source(&self) -> sherpack_core::GitSource {
+        sherpack_core::GitSource {
+            repo: self.repo.clone(),
+            git_ref: self.git_ref.clone(),
+            subpath: self.subpath.clone(),
+        }
+    }
 }
 
 impl LockedDependency {
@@ -93,6 +134,11 @@ impl LockedDependency {
     pub fn effective_name(&self) -> &str {
         self.alias.as_deref().unwrap_or(&self.name)
     }
+
+    /// `repository` followed by the alternative sources, in the order to try
+    pub fn all_sources(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.repository.as_str()).chain(self.sources.iter().map(String::as_str))
+    }
 }
 
 impl LockFile {
@@ -161,8 +207,124 @@ impl LockFile {
             path: format!("dependency '{}' not in lock file", name),
         })?;
 
-        let actual_digest = compute_sha256(data);
+        self.check_digest(name, &locked.repository, locked, compute_sha256(data))
+    }
+
+    /// Verify an archive downloaded from one of a dependency's sources
+    ///
+    /// The primary `repository` is checked according to the lock policy. An
+    /// alternative source must serve exactly the pinned digest: a mirror is
+    /// only trusted for content the lock already vouches for.
+    pub fn verify_source(&self, name: &str, source: &str, data: &[u8]) -> Result<VerifyResult> {
+        let locked = self.get(name).ok_or_else(|| RepoError::LockFileNotFound {
+            path: format!("dependency '{}' not in lock file", name),
+        })?;
+        if source == locked.repository {
+            return self.check_digest(name, source, locked, compute_sha256(data));
+        }
+        if !locked.sources.iter().any(|s| s == source) {
+            return Err(RepoError::ResolutionFailed {
+                message: format!(
+                    "'{}' is not a source of '{}' in the lock file",
+                    source, name
+                ),
+            });
+        }
+
+        let actual = compute_sha256(data);
+        if locked.digest.is_empty() || locked.digest != actual {
+            return Err(RepoError::IntegrityCheckFailed {
+                name: format!("{} (from {})", name, source),
+                expected: locked.digest.clone(),
+                actual,
+            });
+        }
+        Ok(VerifyResult::Mirror {
+            source: source.to_string(),
+        })
+    }
+
+    /// Keep the alternative sources of a previous lock file
+    ///
+    /// Sources are kept for dependencies still locked to the same version
+    /// and digest, so mirrors added to the lock survive `dependency update`.
+    pub fn carry_sources(&mut self, previous: &LockFile) {
+        for dep in &mut self.dependencies {
+            if let Some(old) = previous.get(dep.effective_name())
+                && old.name == dep.name
+                && old.version == dep.version
+                && old.digest == dep.digest
+                && dep.sources.is_empty()
+            {
+                dep.sources = old.sources.clone();
+            }
+        }
+    }
 
+    /// Apply a selective update of `name` from a freshly resolved lock file
+    ///
+    /// Only the entry of `name` and entries new to the lock are taken from
+    /// `updated`; every other entry is kept as it was, digest and sources
+    /// included. Entries `updated` no longer has are dropped. The order of
+    /// existing entries is kept, so the lock file diff stays small.
+    pub fn apply_update(&mut self, updated: LockFile, name: &str) {
+        let mut fresh = updated.dependencies;
+        let mut dependencies = Vec::with_capacity(fresh.len());
+        for kept in self.dependencies.drain(..) {
+            let Some(index) = fresh
+                .iter()
+                .position(|d| d.effective_name() == kept.effective_name())
+            else {
+                continue;
+            };
+            let resolved = fresh.remove(index);
+            if kept.effective_name() == name {
+                dependencies.push(resolved);
+            } else {
+                dependencies.push(kept);
+            }
+        }
+        dependencies.extend(fresh);
+        self.dependencies = dependencies;
+        self.pack_yaml_digest = updated.pack_yaml_digest;
+        self.generated = updated.generated;
+    }
+
+    /// Verify a git checkout against its locked commit and digest
+    ///
+    /// The commit must always match; the pack digest is checked like an
+    /// archive digest, according to the lock policy.
+    pub fn verify_checkout(&self, name: &str, checkout: &GitCheckout) -> Result<VerifyResult> {
+        let locked = self.get(name).ok_or_else(|| RepoError::LockFileNotFound {
+            path: format!("dependency '{}' not in lock file", name),
+        })?;
+        let git = locked
+            .git
+            .as_ref()
+            .ok_or_else(|| RepoError::ResolutionFailed {
+                message: format!("'{}' is not locked as a git dependency", name),
+            })?;
+
+        let head = checkout.head()?;
+        if head != git.commit {
+            return Err(RepoError::IntegrityCheckFailed {
+                name: name.to_string(),
+                expected: git.commit.clone(),
+                actual: head,
+            });
+        }
+
+        self.check_digest(name, &git.repo, locked, checkout.digest()?)
+    }
+
+    fn check_digest(
+        &self,
+        name: &str,
+        source: &str,
+        locked: &LockedDependency,
+        actual_digest: String,
+    ) -> Result<VerifyResult> {
+        let source = source.to_string();
         match self.policy {
             LockPolicy::Strict => {
                 if locked.digest != actual_digest {
@@ -172,7 +334,7 @@ impl LockFile {
                         actual: actual_digest,
                     })
                 } else {
-                    Ok(VerifyResult::Match)
+                    Ok(VerifyResult::Match { source })
                 }
             }
             LockPolicy::Version => {
@@ -180,32 +342,41 @@ impl LockFile {
                 // SHA might differ (republished), that's OK
                 if locked.digest != actual_digest {
                     Ok(VerifyResult::DigestChanged {
+                        source,
                         expected: locked.digest.clone(),
                         actual: actual_digest,
                     })
                 } else {
-                    Ok(VerifyResult::Match)
+                    Ok(VerifyResult::Match { source })
                 }
             }
             LockPolicy::SemverPatch | LockPolicy::SemverMinor => {
                 // More permissive - just check that we got something
-                Ok(VerifyResult::Match)
+                Ok(VerifyResult::Match { source })
             }
         }
     }
 }
 
 /// Result of integrity verification
+///
+/// Each outcome names the source the archive or checkout came from.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum VerifyResult {
-    /// Perfect match
-    Match,
+    /// Perfect match from the primary source
+    Match { source: String },
     /// Version matches but digest changed (republished)
-    DigestChanged { expected: String, actual: String },
+    DigestChanged {
+        source: String,
+        expected: String,
+        actual: String,
+    },
+    /// Served by an alternative source, with the pinned digest
+    Mirror { source: String },
 }
 
 /// Compute SHA256 digest of data
-fn compute_sha256(data: &[u8]) -> String {
+pub(crate) fn compute_sha256(data: &[u8]) -> String {
     let mut hasher = Sha256::new();
     hasher.update(data);
     let result = hasher.finalize();
@@ -270,6 +441,8 @@ mod tests {
             constraint: "^15.0.0".to_string(),
             alias: None,
             dependencies: vec![],
+            git: None,
+            sources: vec![],
         });
 
         assert_eq!(lock.dependencies.len(), 1);
@@ -288,11 +461,18 @@ mod tests {
             constraint: "1.0.0".to_string(),
             alias: None,
             dependencies: vec![],
+            git: None,
+            sources: vec![],
         });
 
         // Matching data should pass
         let result = lock.verify("test", b"test data").unwrap();
-        assert_eq!(result, VerifyResult::Match);
+        assert_eq!(
+            result,
+            VerifyResult::Match {
+                source: "https://example.com".to_string()
+            }
+        );
 
         // Different data should fail
         let result = lock.verify("test", b"different data");
@@ -311,6 +491,8 @@ mod tests {
             constraint: "1.0.0".to_string(),
             alias: None,
             dependencies: vec![],
+            git: None,
+            sources: vec![],
         });
 
         // Different data should just warn (DigestChanged), not error
@@ -318,6 +500,118 @@ mod tests {
         assert!(matches!(result, VerifyResult::DigestChanged { .. }));
     }
 
+    fn mirrored_lock(policy: LockPolicy) -> LockFile {
+        let mut lock = LockFile::new("test");
+        lock.policy = policy;
+        lock.add(LockedDependency {
+            name: "redis".to_string(),
+            version: Version::new(1, 0, 0),
+            repository: "https://charts.example.com".to_string(),
+            digest: compute_sha256(b"original data"),
+            constraint: "1.0.0".to_string(),
+            alias: None,
+            dependencies: vec![],
+            git: None,
+            sources: vec![
+                "https://mirror-a.example.com".to_string(),
+                "oci://mirror-b.example.com/charts".to_string(),
+            ],
+        });
+        lock
+    }
+
+    #[test]
+    fn test_verify_source() {
+        let lock = mirrored_lock(LockPolicy::Version);
+        let locked = lock.get("redis").unwrap();
+        assert_eq!(
+            locked.all_sources().collect::<Vec<_>>(),
+            [
+                "https://charts.example.com",
+                "https://mirror-a.example.com",
+                "oci://mirror-b.example.com/charts"
+            ]
+        );
+
+        // The primary follows the lock policy
+        let result = lock
+            .verify_source("redis", "https://charts.example.com", b"original data")
+            .unwrap();
+        assert_eq!(
+            result,
+            VerifyResult::Match {
+                source: "https://charts.example.com".to_string()
+            }
+        );
+        let result = lock
+            .verify_source("redis", "https://charts.example.com", b"republished data")
+            .unwrap();
+        assert!(matches!(
+            result,
+            VerifyResult::DigestChanged { source, .. } if source == "https://charts.example.com"
+        ));
+
+        // A mirror must serve the pinned digest, whatever the policy
+        let result = lock
+            .verify_source(
+                "redis",
+                "oci://mirror-b.example.com/charts",
+                b"original data",
+            )
+            .unwrap();
+        assert_eq!(
+            result,
+            VerifyResult::Mirror {
+                source: "oci://mirror-b.example.com/charts".to_string()
+            }
+        );
+        let err = lock
+            .verify_source("redis", "https://mirror-a.example.com", b"republished data")
+            .unwrap_err();
+        assert!(matches!(err, RepoError::IntegrityCheckFailed { .. }));
+        assert!(
+            err.to_string()
+                .contains("from https://mirror-a.example.com")
+        );
+
+        // Only listed sources are accepted
+        assert!(
+            lock.verify_source("redis", "https://evil.example.com", b"original data")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_sources_serialization_and_carry_over() {
+        let lock = mirrored_lock(LockPolicy::Strict);
+        let yaml = serde_yaml::to_string(&lock).unwrap();
+        assert!(yaml.contains("sources:"));
+        assert!(yaml.contains("- https://mirror-a.example.com"));
+
+        // Relocking the same archive keeps the mirrors
+        let mut relocked = mirrored_lock(LockPolicy::Strict);
+        relocked.dependencies[0].sources.clear();
+        relocked.carry_sources(&serde_yaml::from_str(&yaml).unwrap());
+        assert_eq!(relocked.get("redis").unwrap().sources.len(), 2);
+
+        // A new version drops them: mirrors vouch for one digest only
+        let mut upgraded = mirrored_lock(LockPolicy::Strict);
+        upgraded.dependencies[0].sources.clear();
+        upgraded.dependencies[0].version = Version::new(1, 0, 1);
+        upgraded.dependencies[0].digest = compute_sha256(b"new data");
+        upgraded.carry_sources(&lock);
+        assert!(upgraded.get("redis").unwrap().sources.is_empty());
+
+        // Lock files without sources still parse
+        let no_sources = yaml
+            .lines()
+            .filter(|l| !l.contains("sources:") && !l.contains("mirror-"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let parsed: LockFile = serde_yaml::from_str(&no_sources).unwrap();
+        assert!(parsed.get("redis").unwrap().sources.is_empty());
+    }
+
     #[test]
     fn test_serialization() {
         let mut lock = LockFile::new("test");
@@ -329,6 +623,8 @@ mod tests {
             constraint: "^15.0.0".to_string(),
             alias: Some("web".to_string()),
             dependencies: vec!["common".to_string()],
+            git: None,
+            sources: vec![],
         });
 
         let yaml = serde_yaml::to_string(&lock).unwrap();
@@ -341,6 +637,35 @@ mod tests {
         assert_eq!(parsed.get("web").unwrap().name, "nginx");
     }
 
+    #[test]
+    fn test_git_dependency_serialization() {
+        let mut lock = LockFile::new("test");
+        lock.add(LockedDependency {
+            name: "common".to_string(),
+            version: Version::new(1, 4, 0),
+            repository: String::new(),
+            digest: "sha256:abc123".to_string(),
+            constraint: "^1.0".to_string(),
+            alias: None,
+            dependencies: vec![],
+            git: Some(LockedGitSource {
+                repo: "https://git.example.com/platform/packs.git".to_string(),
+                git_ref: "v1.4.0".to_string(),
+                commit: "0123456789abcdef0123456789abcdef01234567".to_string(),
+                subpath: Some("packs/common".to_string()),
+            }),
+            sources: vec![],
+        });
+
+        let yaml = serde_yaml::to_string(&lock).unwrap();
+        assert!(yaml.contains("ref: v1.4.0"));
+        assert!(yaml.contains("commit: 0123456789abcdef0123456789abcdef01234567"));
+
+        let parsed: LockFile = serde_yaml::from_str(&yaml).unwrap();
+        let git = parsed.get("common").unwrap().git.as_ref().unwrap();
+        assert_eq!(git.source().subpath.as_deref(), Some("packs/common"));
+    }
+
     #[test]
     fn test_effective_name() {
         let dep_no_alias = LockedDependency {
@@ -351,6 +676,8 @@ mod tests {
             constraint: String::new(),
             alias: None,
             dependencies: vec![],
+            git: None,
+            sources: vec![],
         };
         assert_eq!(dep_no_alias.effective_name(), "nginx");
 
@@ -362,6 +689,8 @@ mod tests {
             constraint: String::new(),
             alias: Some("web".to_string()),
             dependencies: vec![],
+            git: None,
+            sources: vec![],
         };
         assert_eq!(dep_with_alias.effective_name(), "web");
     }
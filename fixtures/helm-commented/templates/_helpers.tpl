@@ -0,0 +1,6 @@
+{{/*
+Common labels
+*/}}
+{{- define "commented.labels" -}}
+app: {{ .Chart.Name }}
+{{- end }}